@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, RipdocError};
+
+/// The ripdoc config directory, honouring `RIPDOC_CONFIG_DIR` before the platform config
+/// directory.
+pub fn config_dir() -> Result<PathBuf> {
+	match env::var("RIPDOC_CONFIG_DIR") {
+		Ok(dir) => Ok(PathBuf::from(dir)),
+		Err(_) => Ok(dirs::config_dir()
+			.ok_or_else(|| {
+				RipdocError::Generate("Could not determine config directory".to_string())
+			})?
+			.join("ripdoc")),
+	}
+}
+
+/// The ripdoc state directory, holding the skelebuild state and the daemon socket: honours
+/// `RIPDOC_STATE_DIR` before the platform state directory, falling back to `~/.local/state`.
+pub fn state_dir() -> PathBuf {
+	if let Some(dir) = env::var_os("RIPDOC_STATE_DIR") {
+		return PathBuf::from(dir);
+	}
+	let mut path = dirs::state_dir().unwrap_or_else(|| {
+		let mut p = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+		p.push(".local");
+		p.push("state");
+		p
+	});
+	path.push("ripdoc");
+	path
+}
+
+/// Named shortcuts for target specifications, persisted in the ripdoc config directory.
+///
+/// An alias replaces the entrypoint of a target: with `widgets` mapped to
+/// `/work/crates/widgets`, the target `widgets::Button` resolves as
+/// `/work/crates/widgets::Button`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AliasStore {
+	/// Alias name to target specification.
+	#[serde(default)]
+	pub aliases: BTreeMap<String, String>,
+}
+
+impl AliasStore {
+	/// Path of the alias file in [`config_dir`].
+	pub fn config_file() -> Result<PathBuf> {
+		Ok(Self::file_in(&config_dir()?))
+	}
+
+	/// Path of the alias file kept in the config directory `dir`.
+	pub fn file_in(dir: &Path) -> PathBuf {
+		dir.join("aliases.json")
+	}
+
+	/// Load the stored aliases; a missing file yields an empty store.
+	pub fn load() -> Result<Self> {
+		Self::load_from(&Self::config_file()?)
+	}
+
+	/// Load aliases from the file at `path`; a missing file yields an empty store.
+	pub fn load_from(path: &Path) -> Result<Self> {
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		let content = fs::read_to_string(path)?;
+		serde_json::from_str(&content).map_err(|err| {
+			RipdocError::Generate(format!("Failed to parse alias file {}: {err}", path.display()))
+		})
+	}
+
+	/// Write the aliases back to the config file.
+	pub fn save(&self) -> Result<()> {
+		let path = Self::config_file()?;
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let content = serde_json::to_string_pretty(self)
+			.map_err(|err| RipdocError::Generate(format!("Failed to serialize aliases: {err}")))?;
+		fs::write(path, content)?;
+		Ok(())
+	}
+
+	/// Add or replace an alias, returning the target it previously pointed to.
+	///
+	/// Names must look like identifiers (letters, digits, `_`, `-`) so they cannot be confused with
+	/// paths, versions, or module separators.
+	pub fn add(&mut self, name: &str, target: &str) -> Result<Option<String>> {
+		let valid = !name.is_empty()
+			&& name
+				.chars()
+				.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-');
+		if !valid {
+			return Err(RipdocError::InvalidTarget(format!(
+				"Invalid alias name '{name}': use letters, digits, '_' or '-'"
+			)));
+		}
+		if target.is_empty() {
+			return Err(RipdocError::InvalidTarget(format!("Alias '{name}' needs a target")));
+		}
+		Ok(self.aliases.insert(name.to_string(), target.to_string()))
+	}
+
+	/// Remove an alias, returning its target if it existed.
+	pub fn remove(&mut self, name: &str) -> Option<String> {
+		self.aliases.remove(name)
+	}
+
+	/// Expand a target whose entrypoint is an alias, keeping any `::path` suffix.
+	///
+	/// Returns `None` when the entrypoint is not an alias.
+	pub fn expand(&self, target: &str) -> Option<String> {
+		let (entrypoint, rest) = match target.split_once("::") {
+			Some((entrypoint, rest)) => (entrypoint, Some(rest)),
+			None => (target, None),
+		};
+		let expanded = self.aliases.get(entrypoint)?;
+		Some(match rest {
+			Some(rest) => format!("{expanded}::{rest}"),
+			None => expanded.clone(),
+		})
+	}
+}
+
+/// Expand `target` through the stored aliases, leaving it unchanged when no alias applies.
+///
+/// An unreadable alias file is treated as empty so that a broken config never blocks targets
+/// that do not use aliases.
+pub fn expand_alias(target: &str) -> String {
+	AliasStore::load()
+		.ok()
+		.and_then(|store| store.expand(target))
+		.unwrap_or_else(|| target.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn expands_entrypoint_and_keeps_path() {
+		let mut store = AliasStore::default();
+		store.add("widgets", "/work/crates/widgets").unwrap();
+		assert_eq!(
+			store.expand("widgets::Button").as_deref(),
+			Some("/work/crates/widgets::Button")
+		);
+		assert_eq!(store.expand("widgets").as_deref(), Some("/work/crates/widgets"));
+		assert_eq!(store.expand("serde::Serialize"), None);
+		assert_eq!(store.expand("widgets@1.0"), None);
+	}
+
+	#[test]
+	fn rejects_names_that_look_like_targets() {
+		let mut store = AliasStore::default();
+		assert!(store.add("a/b", "/x").is_err());
+		assert!(store.add("a::b", "/x").is_err());
+		assert!(store.add("a@1", "/x").is_err());
+		assert!(store.add("", "/x").is_err());
+		assert!(store.add("ok-name", "").is_err());
+		assert_eq!(store.add("ok-name", "/x").unwrap(), None);
+		assert_eq!(store.add("ok-name", "/y").unwrap().as_deref(), Some("/x"));
+	}
+}
@@ -0,0 +1,110 @@
+//! Cross-process locks that serialize rustdoc builds of the same manifest.
+//!
+//! Several ripdoc processes asking for the same crate at once (agents fanning out over one
+//! workspace, say) would otherwise each run `cargo rustdoc`, contend on cargo's own locks, and
+//! produce the same JSON. Instead the first process to take the lock builds, and the others wait
+//! and then read the cache entry it wrote.
+//!
+//! Locks are advisory files under `<cache dir>/locks/`, one per manifest path. They are released
+//! when the holding process drops the guard or exits, so a crashed build never wedges later runs.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::cache::CacheConfig;
+
+/// How often a bounded wait re-checks the lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Held while this process builds rustdoc JSON for one manifest.
+#[derive(Debug)]
+pub(super) struct BuildLock {
+	_file: File,
+}
+
+/// Another process kept building past the wait budget.
+#[derive(Debug)]
+pub(super) struct LockTimedOut;
+
+impl BuildLock {
+	/// Wait until no other ripdoc process is building `manifest_path`, for at most `timeout`.
+	///
+	/// Returns `Ok(None)` when locking is unavailable (no cache directory, read-only filesystem,
+	/// or a platform without file locks); callers then build without coordination.
+	pub(super) fn acquire(
+		config: &CacheConfig,
+		manifest_path: &Path,
+		timeout: Option<Duration>,
+		quiet: bool,
+	) -> Result<Option<Self>, LockTimedOut> {
+		let Some(file) = open_lock_file(config, manifest_path) else {
+			return Ok(None);
+		};
+		match file.try_lock() {
+			Ok(()) => return Ok(Some(Self { _file: file })),
+			Err(TryLockError::Error(_)) => return Ok(None),
+			Err(TryLockError::WouldBlock) => {}
+		}
+		if !quiet {
+			eprintln!(
+				"Waiting for another ripdoc process building {}",
+				manifest_path.display()
+			);
+		}
+
+		let Some(timeout) = timeout else {
+			return Ok(file.lock().ok().map(|()| Self { _file: file }));
+		};
+		let deadline = Instant::now() + timeout;
+		loop {
+			match file.try_lock() {
+				Ok(()) => return Ok(Some(Self { _file: file })),
+				Err(TryLockError::Error(_)) => return Ok(None),
+				Err(TryLockError::WouldBlock) if Instant::now() >= deadline => {
+					return Err(LockTimedOut);
+				}
+				Err(TryLockError::WouldBlock) => thread::sleep(POLL_INTERVAL),
+			}
+		}
+	}
+}
+
+fn open_lock_file(config: &CacheConfig, manifest_path: &Path) -> Option<File> {
+	let dir = config.get_cache_dir().ok()?.join("locks");
+	fs::create_dir_all(&dir).ok()?;
+	let mut hasher = DefaultHasher::new();
+	manifest_path.hash(&mut hasher);
+	OpenOptions::new()
+		.create(true)
+		.truncate(false)
+		.write(true)
+		.open(dir.join(format!("{:x}.lock", hasher.finish())))
+		.ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn a_held_lock_makes_bounded_waits_time_out() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = CacheConfig::new().with_cache_dir(dir.path().to_path_buf());
+		let manifest = Path::new("/work/demo/Cargo.toml");
+
+		let held = BuildLock::acquire(&config, manifest, None, true).unwrap();
+		assert!(held.is_some());
+		let waited = BuildLock::acquire(&config, manifest, Some(Duration::from_millis(50)), true);
+		assert!(waited.is_err());
+
+		let other = BuildLock::acquire(&config, Path::new("/work/other/Cargo.toml"), None, true);
+		assert!(other.unwrap().is_some());
+
+		drop(held);
+		assert!(BuildLock::acquire(&config, manifest, Some(Duration::ZERO), true).unwrap().is_some());
+	}
+}
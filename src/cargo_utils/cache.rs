@@ -2,12 +2,20 @@
 //!
 //! Provides a disk-based cache for rustdoc JSON output to avoid
 //! expensive re-generation of documentation for the same crate.
+//!
+//! Rendered skeletons are cached in a second namespace, `rendered/<crate hash>/<render key>`,
+//! next to the build they were rendered from. They are only served while that build is cached
+//! and are dropped whenever it is rebuilt or removed.
 
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 use std::{env, fs};
 
+use once_cell::sync::{Lazy, OnceCell};
 use rustdoc_types::Crate;
 
 use super::error::{Result, RipdocError};
@@ -19,14 +27,51 @@ pub struct CacheConfig {
 	pub enabled: bool,
 	/// Directory where cached documentation is stored.
 	/// If None, uses the default cache directory.
+	///
+	/// Taken from `RIPDOC_CACHE_DIR` when the configuration is created, so later changes to the
+	/// environment do not move the cache of an existing configuration.
 	pub cache_dir: Option<PathBuf>,
+	/// Whether parsed crates are also kept in process memory.
+	///
+	/// Only useful for long-lived processes such as `ripdoc daemon`, where repeated requests for
+	/// the same crate can skip reading and decoding the on-disk cache.
+	pub memory: bool,
+	/// Longest a rustdoc build may run before it is abandoned in favour of the most recent
+	/// cached build of the same crate, even if that build is stale.
+	pub build_budget: Option<Duration>,
+	/// Set when cached builds are ignored and regenerated, holding the keys already rebuilt so a
+	/// crate read several times by one invocation is only rebuilt once. Shared between clones.
+	pub refresh: Option<Arc<Mutex<HashSet<String>>>>,
+	/// zstd level cache files are compressed with, or `None` to store them uncompressed.
+	///
+	/// Files written either way can be read back regardless of this setting.
+	pub compression_level: Option<i32>,
+	/// Whether builds use the feature set and rustdoc flags of the crate's
+	/// `[package.metadata.docs.rs]` table in place of the requested features.
+	pub docs_rs_config: bool,
 }
 
+/// zstd level used for cache files unless configured otherwise: fast to write, and large rustdoc
+/// JSON blobs typically shrink several-fold.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// How long a `.tmp` or `.partial` file may go unmodified before cleanup treats it as left over
+/// from an interrupted write rather than one still in progress in another process.
+const TEMP_FILE_GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Magic number at the start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
 impl Default for CacheConfig {
 	fn default() -> Self {
 		Self {
 			enabled: true,
-			cache_dir: None,
+			cache_dir: env::var_os("RIPDOC_CACHE_DIR").map(PathBuf::from),
+			memory: false,
+			build_budget: None,
+			refresh: None,
+			compression_level: Some(DEFAULT_COMPRESSION_LEVEL),
+			docs_rs_config: false,
 		}
 	}
 }
@@ -42,6 +87,11 @@ impl CacheConfig {
 		Self {
 			enabled: false,
 			cache_dir: None,
+			memory: false,
+			build_budget: None,
+			refresh: None,
+			compression_level: Some(DEFAULT_COMPRESSION_LEVEL),
+			docs_rs_config: false,
 		}
 	}
 
@@ -51,16 +101,49 @@ impl CacheConfig {
 		self
 	}
 
+	/// Keep parsed crates in process memory in addition to the disk cache.
+	pub fn with_memory(mut self, enabled: bool) -> Self {
+		self.memory = enabled;
+		self
+	}
+
+	/// Bound rustdoc builds to `budget`, falling back to the last cached build when exceeded.
+	pub fn with_build_budget(mut self, budget: Option<Duration>) -> Self {
+		self.build_budget = budget;
+		self
+	}
+
+	/// Compress cache files with zstd at `level`, or store them uncompressed with `None`.
+	pub fn with_compression(mut self, level: Option<i32>) -> Self {
+		self.compression_level = level;
+		self
+	}
+
+	/// Build crates the way docs.rs does, from their `[package.metadata.docs.rs]` table.
+	pub fn with_docs_rs_config(mut self, enabled: bool) -> Self {
+		self.docs_rs_config = enabled;
+		self
+	}
+
+	/// Ignore cached builds and regenerate them, overwriting the cache entries afterwards.
+	pub fn with_refresh(mut self, refresh: bool) -> Self {
+		self.refresh = refresh.then(Default::default);
+		self
+	}
+
+	/// Whether `key` must be rebuilt despite being cached.
+	fn needs_refresh(&self, key: &CacheKey) -> bool {
+		self.refresh
+			.as_ref()
+			.is_some_and(|refreshed| !refreshed.lock().is_ok_and(|keys| keys.contains(&key.hash())))
+	}
+
 	/// Get the cache directory, using the default if not specified.
-	fn get_cache_dir(&self) -> Result<PathBuf> {
+	pub fn get_cache_dir(&self) -> Result<PathBuf> {
 		if let Some(ref dir) = self.cache_dir {
 			return Ok(dir.clone());
 		}
 
-		if let Ok(dir) = env::var("RIPDOC_CACHE_DIR") {
-			return Ok(PathBuf::from(dir));
-		}
-
 		// Use platform-specific cache directory via the dirs crate
 		let cache_base = dirs::cache_dir().ok_or_else(|| {
 			RipdocError::Generate("Could not determine cache directory".to_string())
@@ -87,6 +170,11 @@ pub struct CacheKey {
 	pub private_items: bool,
 	/// Rust toolchain version (to handle rustdoc JSON format changes).
 	pub toolchain_version: Option<String>,
+	/// Fingerprint of the crate's source files, for local crates whose sources change between
+	/// builds. See [`source_fingerprint`].
+	pub source_fingerprint: Option<String>,
+	/// Extra flags passed to rustdoc, such as those of a docs.rs configuration.
+	pub rustdoc_flags: Vec<String>,
 }
 
 impl CacheKey {
@@ -111,11 +199,25 @@ impl CacheKey {
 			features,
 			private_items,
 			toolchain_version,
+			source_fingerprint: None,
+			rustdoc_flags: Vec::new(),
 		}
 	}
 
+	/// Tie the key to the current state of the crate's sources.
+	pub fn with_source_fingerprint(mut self, fingerprint: Option<String>) -> Self {
+		self.source_fingerprint = fingerprint;
+		self
+	}
+
+	/// Record the extra rustdoc flags the build is run with.
+	pub fn with_rustdoc_flags(mut self, flags: Vec<String>) -> Self {
+		self.rustdoc_flags = flags;
+		self
+	}
+
 	/// Compute a stable hash for this cache key.
-	fn hash(&self) -> String {
+	pub fn hash(&self) -> String {
 		let mut hasher = DefaultHasher::new();
 
 		// Hash the manifest path
@@ -135,31 +237,184 @@ impl CacheKey {
 		// Hash toolchain version
 		self.toolchain_version.hash(&mut hasher);
 
+		// Hash source fingerprint; omitted when absent so existing keys stay valid
+		if let Some(fingerprint) = &self.source_fingerprint {
+			fingerprint.hash(&mut hasher);
+		}
+
+		// Likewise for extra rustdoc flags
+		if !self.rustdoc_flags.is_empty() {
+			self.rustdoc_flags.hash(&mut hasher);
+		}
+
 		format!("{:x}", hasher.finish())
 	}
 
+	/// Hash of the fields that stay fixed across package versions and toolchain updates, used to
+	/// find the most recent build of the same crate and flags.
+	fn lineage_hash(&self) -> String {
+		let mut hasher = DefaultHasher::new();
+		self.manifest_path.hash(&mut hasher);
+		self.no_default_features.hash(&mut hasher);
+		self.all_features.hash(&mut hasher);
+		self.private_items.hash(&mut hasher);
+		self.features.hash(&mut hasher);
+		if !self.rustdoc_flags.is_empty() {
+			self.rustdoc_flags.hash(&mut hasher);
+		}
+		format!("{:x}", hasher.finish())
+	}
+
+	/// Directory holding the skeletons rendered from the build stored under this key.
+	fn rendered_dir(&self, cache_dir: &Path) -> PathBuf {
+		cache_dir.join("rendered").join(self.hash())
+	}
+
 	/// Get the cache file path for this key.
 	fn cache_path(&self, cache_dir: &Path) -> PathBuf {
 		let hash = self.hash();
 		cache_dir.join(format!("{}.bin", hash))
 	}
+
+	/// Path of the sidecar describing the build stored under this key.
+	fn meta_path(&self, cache_dir: &Path) -> PathBuf {
+		cache_dir.join(format!("{}.meta", self.hash()))
+	}
+
+	/// The build parameters recorded next to the cache file.
+	fn meta(&self) -> CacheEntryMeta {
+		CacheEntryMeta {
+			package_info: self.package_info.clone(),
+			manifest_path: self.manifest_path.clone(),
+			no_default_features: self.no_default_features,
+			all_features: self.all_features,
+			features: self.features.clone(),
+			private_items: self.private_items,
+			toolchain_version: self.toolchain_version.clone(),
+			source_fingerprint: self.source_fingerprint.clone(),
+			rustdoc_flags: self.rustdoc_flags.clone(),
+		}
+	}
+
+	/// Path of the file naming the most recently saved build in this key's lineage.
+	fn latest_path(&self, cache_dir: &Path) -> PathBuf {
+		cache_dir.join(format!("{}.latest", self.lineage_hash()))
+	}
+}
+
+/// Most crates [`MEMORY_CACHE`] holds before it evicts the least recently used one.
+const MEMORY_CACHE_CAPACITY: usize = 8;
+
+/// Parsed crates kept warm for long-lived processes, keyed by [`CacheKey`] hash.
+static MEMORY_CACHE: Lazy<Mutex<MemoryCache>> = Lazy::new(|| Mutex::new(MemoryCache::default()));
+
+/// Least-recently-used set of shared crates, so hits hand out the crate without copying it.
+#[derive(Default)]
+struct MemoryCache {
+	/// Each crate with the tick of its last use.
+	crates: HashMap<String, (Arc<Crate>, u64)>,
+	tick: u64,
+}
+
+impl MemoryCache {
+	fn get(&mut self, key: &str) -> Option<Arc<Crate>> {
+		self.tick += 1;
+		let (crate_data, used) = self.crates.get_mut(key)?;
+		*used = self.tick;
+		Some(Arc::clone(crate_data))
+	}
+
+	/// Insert `crate_data`, returning the keys evicted to stay within capacity.
+	fn insert(&mut self, key: String, crate_data: Arc<Crate>) -> Vec<String> {
+		self.tick += 1;
+		self.crates.insert(key, (crate_data, self.tick));
+		let mut evicted = Vec::new();
+		while self.crates.len() > MEMORY_CACHE_CAPACITY {
+			let Some(oldest) = self.crates.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone()) else {
+				break;
+			};
+			self.crates.remove(&oldest);
+			evicted.push(oldest);
+		}
+		evicted
+	}
+}
+
+fn memory_get(key: &CacheKey) -> Option<Arc<Crate>> {
+	MEMORY_CACHE.lock().ok()?.get(&key.hash())
+}
+
+fn memory_put(key: &CacheKey, crate_data: &Arc<Crate>) {
+	let evicted = match MEMORY_CACHE.lock() {
+		Ok(mut cache) => cache.insert(key.hash(), Arc::clone(crate_data)),
+		Err(_) => return,
+	};
+	// An index is only kept warm alongside its crate.
+	for key in evicted {
+		crate::core_api::search::SearchIndex::evict_warm(&key);
+	}
+}
+
+/// Number of crates currently held in the in-memory cache.
+pub fn memory_cache_len() -> usize {
+	MEMORY_CACHE.lock().map(|cache| cache.crates.len()).unwrap_or(0)
+}
+
+/// Drop every crate held in the in-memory cache.
+pub fn clear_memory_cache() {
+	if let Ok(mut cache) = MEMORY_CACHE.lock() {
+		cache.crates.clear();
+	}
 }
 
 /// Try to load cached documentation for the given parameters.
-pub fn load_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Crate>> {
-	if !config.enabled {
+pub fn load_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Arc<Crate>>> {
+	if !config.enabled || config.needs_refresh(key) {
 		return Ok(None);
 	}
 
+	if config.memory
+		&& let Some(crate_data) = memory_get(key)
+	{
+		return Ok(Some(crate_data));
+	}
+
 	let cache_dir = config.get_cache_dir()?;
-	let cache_path = key.cache_path(&cache_dir);
+	let Some(crate_data) = read_cache_file(&key.cache_path(&cache_dir))? else {
+		return Ok(None);
+	};
+	let crate_data = Arc::new(crate_data);
+
+	if config.memory {
+		memory_put(key, &crate_data);
+	}
+
+	Ok(Some(crate_data))
+}
 
+/// Load the most recent cached build of the same crate and flags as `key`, regardless of package
+/// version or toolchain.
+///
+/// Used as a fallback when a fresh build cannot be produced in time; the result may be stale.
+pub fn load_latest_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Arc<Crate>>> {
+	if !config.enabled {
+		return Ok(None);
+	}
+	let cache_dir = config.get_cache_dir()?;
+	let Ok(latest) = fs::read_to_string(key.latest_path(&cache_dir)) else {
+		return Ok(None);
+	};
+	Ok(read_cache_file(&cache_dir.join(format!("{}.bin", latest.trim())))?.map(Arc::new))
+}
+
+/// Decode a cache file, deleting it if it no longer deserializes.
+fn read_cache_file(cache_path: &Path) -> Result<Option<Crate>> {
 	if !cache_path.exists() {
 		return Ok(None);
 	}
 
 	// Try to load and deserialize the cached data
-	let data = fs::read(&cache_path).map_err(|e| {
+	let data = fs::read(cache_path).map_err(|e| {
 		RipdocError::Generate(format!(
 			"Failed to read cache file {}: {}",
 			cache_path.display(),
@@ -167,12 +422,25 @@ pub fn load_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Crate>
 		))
 	})?;
 
+	// Files written without compression (or before it existed) are plain bincode.
+	let data = if data.starts_with(&ZSTD_MAGIC) {
+		zstd::stream::decode_all(data.as_slice()).map_err(|e| {
+			let _ = fs::remove_file(cache_path);
+			RipdocError::Generate(format!(
+				"Cache decompression failed (removing corrupted cache): {}",
+				e
+			))
+		})?
+	} else {
+		data
+	};
+
 	let config = bincode::config::standard();
 	let (crate_data, _len): (Crate, usize) = bincode::serde::decode_from_slice(&data, config)
 		.map_err(|e| {
 			// If deserialization fails, the cache is likely stale or corrupted
 			// Delete it and return None
-			let _ = fs::remove_file(&cache_path);
+			let _ = fs::remove_file(cache_path);
 			RipdocError::Generate(format!(
 				"Cache deserialization failed (removing stale cache): {}",
 				e
@@ -183,11 +451,20 @@ pub fn load_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Crate>
 }
 
 /// Save documentation to the cache.
-pub fn save_cached(config: &CacheConfig, key: &CacheKey, crate_data: &Crate) -> Result<()> {
+pub fn save_cached(config: &CacheConfig, key: &CacheKey, crate_data: &Arc<Crate>) -> Result<()> {
+	if let Some(refreshed) = &config.refresh
+		&& let Ok(mut keys) = refreshed.lock()
+	{
+		keys.insert(key.hash());
+	}
 	if !config.enabled {
 		return Ok(());
 	}
 
+	if config.memory {
+		memory_put(key, crate_data);
+	}
+
 	let cache_dir = config.get_cache_dir()?;
 
 	// Create cache directory if it doesn't exist
@@ -202,9 +479,14 @@ pub fn save_cached(config: &CacheConfig, key: &CacheKey, crate_data: &Crate) ->
 	let cache_path = key.cache_path(&cache_dir);
 
 	// Serialize the crate data
-	let config = bincode::config::standard();
-	let data = bincode::serde::encode_to_vec(crate_data, config)
+	let bincode_config = bincode::config::standard();
+	let data = bincode::serde::encode_to_vec(&**crate_data, bincode_config)
 		.map_err(|e| RipdocError::Generate(format!("Failed to serialize cache data: {}", e)))?;
+	let data = match config.compression_level {
+		Some(level) => zstd::stream::encode_all(data.as_slice(), level)
+			.map_err(|e| RipdocError::Generate(format!("Failed to compress cache data: {}", e)))?,
+		None => data,
+	};
 
 	// Write to a temporary file first, then rename atomically
 	let temp_path = cache_path.with_extension("tmp");
@@ -224,11 +506,509 @@ pub fn save_cached(config: &CacheConfig, key: &CacheKey, crate_data: &Crate) ->
 		))
 	})?;
 
+	// Skeletons rendered from an earlier build under this key may no longer match it.
+	let _ = fs::remove_dir_all(key.rendered_dir(&cache_dir));
+
+	// A fingerprinted key is superseded by every source edit, so the build it replaces can never
+	// be looked up again; drop it rather than letting edits pile up stale blobs, or stale crates
+	// and indexes in a long-lived process.
+	if key.source_fingerprint.is_some()
+		&& let Ok(previous) = fs::read_to_string(key.latest_path(&cache_dir))
+		&& previous.trim() != key.hash()
+	{
+		let previous = previous.trim();
+		let _ = fs::remove_file(cache_dir.join(format!("{previous}.bin")));
+		let _ = fs::remove_file(cache_dir.join(format!("{previous}.meta")));
+		let _ = fs::remove_dir_all(cache_dir.join("rendered").join(previous));
+		if let Ok(mut cache) = MEMORY_CACHE.lock() {
+			cache.crates.remove(previous);
+		}
+		crate::core_api::search::SearchIndex::evict_warm(previous);
+	}
+
+	// Best-effort: only consulted as a fallback by `load_latest_cached`.
+	let _ = fs::write(key.latest_path(&cache_dir), key.hash());
+	// Best-effort: only read by `cache_entries` to describe the entry.
+	if let Ok(meta) = serde_json::to_string(&key.meta()) {
+		let _ = fs::write(key.meta_path(&cache_dir), meta);
+	}
+
 	Ok(())
 }
 
+/// Remove cached documentation for the given parameters from memory and disk.
+///
+/// Used when the crate's sources are known to have changed since the cache was written.
+pub fn remove_cached(config: &CacheConfig, key: &CacheKey) -> Result<()> {
+	if let Ok(mut cache) = MEMORY_CACHE.lock() {
+		cache.crates.remove(&key.hash());
+	}
+	if !config.enabled {
+		return Ok(());
+	}
+
+	let cache_dir = config.get_cache_dir()?;
+	let _ = fs::remove_file(key.meta_path(&cache_dir));
+	let _ = fs::remove_dir_all(key.rendered_dir(&cache_dir));
+	let cache_path = key.cache_path(&cache_dir);
+	match fs::remove_file(&cache_path) {
+		Ok(()) => Ok(()),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(err) => Err(RipdocError::Generate(format!(
+			"Failed to remove cache file {}: {}",
+			cache_path.display(),
+			err
+		))),
+	}
+}
+
+/// Load a skeleton rendered from the build cached under `key` with the options hashed into
+/// `render_key`.
+///
+/// Returns `None` unless the build itself is still cached, so a rendered skeleton never outlives
+/// the crate it came from.
+pub fn load_rendered(
+	config: &CacheConfig,
+	key: &CacheKey,
+	render_key: &str,
+) -> Result<Option<String>> {
+	if !config.enabled || config.needs_refresh(key) {
+		return Ok(None);
+	}
+	let cache_dir = config.get_cache_dir()?;
+	if !key.cache_path(&cache_dir).exists() {
+		return Ok(None);
+	}
+	let path = key.rendered_dir(&cache_dir).join(render_key);
+	let Ok(data) = fs::read(&path) else {
+		return Ok(None);
+	};
+	let data = if data.starts_with(&ZSTD_MAGIC) {
+		match zstd::stream::decode_all(data.as_slice()) {
+			Ok(data) => data,
+			Err(_) => {
+				let _ = fs::remove_file(&path);
+				return Ok(None);
+			}
+		}
+	} else {
+		data
+	};
+	match String::from_utf8(data) {
+		Ok(rendered) => Ok(Some(rendered)),
+		Err(_) => {
+			let _ = fs::remove_file(&path);
+			Ok(None)
+		}
+	}
+}
+
+/// Save a skeleton rendered from the build cached under `key`.
+///
+/// Skipped when the build is not cached, since the skeleton could never be served.
+pub fn save_rendered(
+	config: &CacheConfig,
+	key: &CacheKey,
+	render_key: &str,
+	rendered: &str,
+) -> Result<()> {
+	if !config.enabled {
+		return Ok(());
+	}
+	let cache_dir = config.get_cache_dir()?;
+	if !key.cache_path(&cache_dir).exists() {
+		return Ok(());
+	}
+	let dir = key.rendered_dir(&cache_dir);
+	fs::create_dir_all(&dir).map_err(|e| {
+		RipdocError::Generate(format!(
+			"Failed to create cache directory {}: {}",
+			dir.display(),
+			e
+		))
+	})?;
+
+	let data = match config.compression_level {
+		Some(level) => zstd::stream::encode_all(rendered.as_bytes(), level)
+			.map_err(|e| RipdocError::Generate(format!("Failed to compress cache data: {}", e)))?,
+		None => rendered.as_bytes().to_vec(),
+	};
+	let path = dir.join(render_key);
+	let temp_path = path.with_extension("tmp");
+	fs::write(&temp_path, &data).map_err(|e| {
+		RipdocError::Generate(format!(
+			"Failed to write cache file {}: {}",
+			temp_path.display(),
+			e
+		))
+	})?;
+	fs::rename(&temp_path, &path).map_err(|e| {
+		RipdocError::Generate(format!(
+			"Failed to finalize cache file {}: {}",
+			path.display(),
+			e
+		))
+	})
+}
+
+/// Whether `path` is a Rust source (including `build.rs`) or a `Cargo.toml` manifest.
+pub fn is_rustdoc_input(path: &Path) -> bool {
+	path.extension().is_some_and(|ext| ext == "rs")
+		|| path.file_name().is_some_and(|name| name == "Cargo.toml")
+}
+
+/// Fingerprint of the source files under `root`: the number of files and a hash of each file's
+/// path relative to `root`, size, and modification time.
+///
+/// Counts every file except hidden files and directories and those under `excluded` (the
+/// package's target directory and the ripdoc cache, which builds write to): sources can pull in
+/// any file with `include_str!`, `include_bytes!`, or `#[doc = include_str!(..)]`, so a README or
+/// data file is as much an input as a `.rs` file. `Cargo.lock` is skipped too, since cargo writes
+/// it during the build the fingerprint was taken for. Cheap enough to compute on every lookup, and
+/// changes whenever such a file is edited, added, removed, or renamed. Returns `None` if `root`
+/// cannot be read.
+pub fn source_fingerprint(root: &Path, excluded: &[PathBuf]) -> Option<String> {
+	fn visit(root: &Path, dir: &Path, excluded: &[PathBuf], files: &mut u64, hasher: &mut DefaultHasher) {
+		let Ok(read_dir) = fs::read_dir(dir) else {
+			return;
+		};
+		// Directory order is unspecified, and the hash depends on it.
+		let mut entries: Vec<_> = read_dir.filter_map(|entry| entry.ok()).collect();
+		entries.sort_by_key(|entry| entry.file_name());
+		for entry in entries {
+			let name = entry.file_name();
+			let name = name.to_string_lossy();
+			let Ok(file_type) = entry.file_type() else {
+				continue;
+			};
+			if name.starts_with('.') || name == "Cargo.lock" {
+				continue;
+			}
+			let path = entry.path();
+			if file_type.is_dir() {
+				if !excluded.contains(&path) {
+					visit(root, &path, excluded, files, hasher);
+				}
+				continue;
+			}
+			*files += 1;
+			path.strip_prefix(root).unwrap_or(&path).hash(hasher);
+			if let Ok(meta) = entry.metadata() {
+				meta.len().hash(hasher);
+				meta.modified().ok().hash(hasher);
+			}
+		}
+	}
+
+	let root = root.canonicalize().ok()?;
+	let excluded: Vec<PathBuf> = excluded
+		.iter()
+		.map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+		.collect();
+	let (mut files, mut hasher) = (0, DefaultHasher::new());
+	visit(&root, &root, &excluded, &mut files, &mut hasher);
+	Some(format!("{files}:{:x}", hasher.finish()))
+}
+
+/// Build parameters of a cached rustdoc build, stored in a `<hash>.meta` sidecar.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheEntryMeta {
+	/// Package name and version, as `name-version`.
+	pub package_info: String,
+	/// Manifest the crate was built from.
+	pub manifest_path: PathBuf,
+	/// Whether default features were disabled.
+	pub no_default_features: bool,
+	/// Whether all features were enabled.
+	pub all_features: bool,
+	/// Explicitly enabled features, sorted.
+	pub features: Vec<String>,
+	/// Whether private items were documented.
+	pub private_items: bool,
+	/// Toolchain that produced the build.
+	pub toolchain_version: Option<String>,
+	/// Source fingerprint the build was keyed on, for local crates.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source_fingerprint: Option<String>,
+	/// Extra rustdoc flags the build was run with.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub rustdoc_flags: Vec<String>,
+}
+
+/// What a cache entry holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheEntryKind {
+	/// Parsed rustdoc JSON of a local or registry build (`<hash>.bin`).
+	Rustdoc,
+	/// Compressed rustdoc JSON downloaded from docs.rs (`docs-rs/<name>-<version>.json.gz`).
+	DocsRs,
+	/// Skeleton rendered from a cached build (`rendered/<hash>/<render key>`).
+	Rendered,
+}
+
+/// One file in the on-disk cache.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheEntry {
+	/// What the entry holds.
+	pub kind: CacheEntryKind,
+	/// Location of the cache file.
+	pub path: PathBuf,
+	/// Size on disk in bytes.
+	pub bytes: u64,
+	/// Seconds since the entry was last written.
+	pub age_secs: u64,
+	/// Package name, when known.
+	pub package: Option<String>,
+	/// Package version, when known.
+	pub version: Option<String>,
+	/// Build parameters; entries written before sidecars existed have none.
+	pub build: Option<CacheEntryMeta>,
+}
+
+/// List every rustdoc build, docs.rs download, and rendered skeleton in the cache, largest first.
+pub fn cache_entries(config: &CacheConfig) -> Result<Vec<CacheEntry>> {
+	let cache_dir = config.get_cache_dir()?;
+	let mut entries = Vec::new();
+	let now = SystemTime::now();
+	let age = |path: &Path| {
+		fs::metadata(path)
+			.and_then(|meta| meta.modified())
+			.ok()
+			.and_then(|modified| now.duration_since(modified).ok())
+			.map_or(0, |age| age.as_secs())
+	};
+
+	for path in dir_files(&cache_dir) {
+		if path.extension().and_then(|ext| ext.to_str()) != Some("bin") {
+			continue;
+		}
+		let build: Option<CacheEntryMeta> = fs::read_to_string(path.with_extension("meta"))
+			.ok()
+			.and_then(|meta| serde_json::from_str(&meta).ok());
+		let (package, version) = match &build {
+			Some(build) => {
+				let (package, version) = split_package_info(&build.package_info);
+				(Some(package), version)
+			}
+			None => (None, None),
+		};
+		entries.push(CacheEntry {
+			kind: CacheEntryKind::Rustdoc,
+			bytes: fs::metadata(&path).map_or(0, |meta| meta.len()),
+			age_secs: age(&path),
+			package,
+			version,
+			build,
+			path,
+		});
+	}
+
+	for dir in dir_subdirs(&cache_dir.join("rendered")) {
+		let build: Option<CacheEntryMeta> = dir
+			.file_name()
+			.and_then(|hash| fs::read_to_string(cache_dir.join(hash).with_extension("meta")).ok())
+			.and_then(|meta| serde_json::from_str(&meta).ok());
+		let (package, version) = match &build {
+			Some(build) => {
+				let (package, version) = split_package_info(&build.package_info);
+				(Some(package), version)
+			}
+			None => (None, None),
+		};
+		for path in dir_files(&dir) {
+			entries.push(CacheEntry {
+				kind: CacheEntryKind::Rendered,
+				bytes: fs::metadata(&path).map_or(0, |meta| meta.len()),
+				age_secs: age(&path),
+				package: package.clone(),
+				version: version.clone(),
+				build: build.clone(),
+				path,
+			});
+		}
+	}
+
+	for path in dir_files(&cache_dir.join("docs-rs")) {
+		let Some(stem) = path
+			.file_name()
+			.and_then(|name| name.to_str())
+			.and_then(|name| name.strip_suffix(".json.gz"))
+		else {
+			continue;
+		};
+		let (package, version) = split_package_info(stem);
+		entries.push(CacheEntry {
+			kind: CacheEntryKind::DocsRs,
+			bytes: fs::metadata(&path).map_or(0, |meta| meta.len()),
+			age_secs: age(&path),
+			package: Some(package),
+			version,
+			build: None,
+			path,
+		});
+	}
+
+	entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+	Ok(entries)
+}
+
+/// Decode the crate stored in a rustdoc build or docs.rs download entry.
+///
+/// Rendered skeletons hold no crate data and yield `None`, as do builds whose file is gone.
+pub fn read_cache_entry(entry: &CacheEntry) -> Result<Option<Crate>> {
+	match entry.kind {
+		CacheEntryKind::Rustdoc => read_cache_file(&entry.path),
+		CacheEntryKind::DocsRs => super::path::read_rustdoc_json(&entry.path).map(Some),
+		CacheEntryKind::Rendered => Ok(None),
+	}
+}
+
+/// Delete every cache entry along with its bookkeeping files, returning what was removed.
+pub fn clear_cache(config: &CacheConfig) -> Result<Vec<CacheEntry>> {
+	clear_memory_cache();
+	let entries = cache_entries(config)?;
+	for entry in &entries {
+		remove_entry(entry)?;
+	}
+	remove_orphans(&config.get_cache_dir()?);
+	Ok(entries)
+}
+
+/// Delete entries older than `max_age`, then the oldest remaining entries until the cache fits in
+/// `max_size` bytes. Returns the removed entries.
+pub fn gc_cache(
+	config: &CacheConfig,
+	max_age: Option<Duration>,
+	max_size: Option<u64>,
+) -> Result<Vec<CacheEntry>> {
+	clear_memory_cache();
+	let mut entries = cache_entries(config)?;
+	// Newest first, so the oldest entries are popped off the end.
+	entries.sort_by_key(|entry| entry.age_secs);
+
+	let mut removed = Vec::new();
+	if let Some(max_age) = max_age {
+		let (keep, expired): (Vec<_>, Vec<_>) = entries
+			.into_iter()
+			.partition(|entry| entry.age_secs <= max_age.as_secs());
+		entries = keep;
+		removed.extend(expired);
+	}
+	if let Some(max_size) = max_size {
+		let mut total: u64 = entries.iter().map(|entry| entry.bytes).sum();
+		while total > max_size {
+			let Some(entry) = entries.pop() else {
+				break;
+			};
+			total -= entry.bytes;
+			removed.push(entry);
+		}
+	}
+
+	for entry in &removed {
+		remove_entry(entry)?;
+	}
+	remove_orphans(&config.get_cache_dir()?);
+	Ok(removed)
+}
+
+/// Files directly inside `dir`; empty when it does not exist.
+fn dir_files(dir: &Path) -> Vec<PathBuf> {
+	let Ok(read_dir) = fs::read_dir(dir) else {
+		return Vec::new();
+	};
+	read_dir
+		.filter_map(|entry| entry.ok())
+		.map(|entry| entry.path())
+		.filter(|path| path.is_file())
+		.collect()
+}
+
+/// Directories directly inside `dir`; empty when it does not exist.
+fn dir_subdirs(dir: &Path) -> Vec<PathBuf> {
+	let Ok(read_dir) = fs::read_dir(dir) else {
+		return Vec::new();
+	};
+	read_dir
+		.filter_map(|entry| entry.ok())
+		.map(|entry| entry.path())
+		.filter(|path| path.is_dir())
+		.collect()
+}
+
+fn remove_entry(entry: &CacheEntry) -> Result<()> {
+	if entry.kind == CacheEntryKind::Rustdoc {
+		let _ = fs::remove_file(entry.path.with_extension("meta"));
+		if let (Some(cache_dir), Some(hash)) = (entry.path.parent(), entry.path.file_stem()) {
+			let _ = fs::remove_dir_all(cache_dir.join("rendered").join(hash));
+		}
+	}
+	match fs::remove_file(&entry.path) {
+		Ok(()) => Ok(()),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(err) => Err(RipdocError::Generate(format!(
+			"Failed to remove cache file {}: {}",
+			entry.path.display(),
+			err
+		))),
+	}
+}
+
+/// Remove `.latest` pointers to deleted builds, sidecars and rendered skeletons without a build,
+/// and leftovers of interrupted writes (see [`TEMP_FILE_GRACE`]).
+fn remove_orphans(cache_dir: &Path) {
+	let mut rendered = Vec::new();
+	for dir in dir_subdirs(&cache_dir.join("rendered")) {
+		let built = dir
+			.file_name()
+			.is_some_and(|hash| cache_dir.join(hash).with_extension("bin").exists());
+		if built {
+			rendered.extend(dir_files(&dir));
+		} else {
+			let _ = fs::remove_dir_all(&dir);
+		}
+	}
+	let files = dir_files(cache_dir)
+		.into_iter()
+		.chain(dir_files(&cache_dir.join("docs-rs")))
+		.chain(rendered);
+	for path in files {
+		let orphaned = match path.extension().and_then(|ext| ext.to_str()) {
+			Some("latest") => !fs::read_to_string(&path)
+				.is_ok_and(|hash| cache_dir.join(format!("{}.bin", hash.trim())).exists()),
+			Some("meta") => !path.with_extension("bin").exists(),
+			Some("tmp" | "partial") => fs::metadata(&path)
+				.and_then(|meta| meta.modified())
+				.is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > TEMP_FILE_GRACE)),
+			_ => false,
+		};
+		if orphaned {
+			let _ = fs::remove_file(&path);
+		}
+	}
+}
+
+/// Split `name-version` at the first `-` followed by a digit.
+fn split_package_info(info: &str) -> (String, Option<String>) {
+	let split = info
+		.char_indices()
+		.find(|&(idx, ch)| ch == '-' && info[idx + 1..].starts_with(|c: char| c.is_ascii_digit()));
+	match split {
+		Some((idx, _)) => (info[..idx].to_string(), Some(info[idx + 1..].to_string())),
+		None => (info.to_string(), None),
+	}
+}
+
 /// Get the current Rust toolchain version for cache invalidation.
+///
+/// The version is probed once per process and reused afterwards.
 pub fn get_toolchain_version() -> Option<String> {
+	static TOOLCHAIN_VERSION: OnceCell<Option<String>> = OnceCell::new();
+	TOOLCHAIN_VERSION.get_or_init(probe_toolchain_version).clone()
+}
+
+fn probe_toolchain_version() -> Option<String> {
 	use std::process::Command;
 
 	let output = if super::is_rustup_available() {
@@ -303,4 +1083,227 @@ mod tests {
 
 		assert_ne!(key1.hash(), key2.hash());
 	}
+
+	#[test]
+	fn test_source_fingerprint_tracks_edits() {
+		let dir = tempfile::tempdir().unwrap();
+		fs::create_dir(dir.path().join("src")).unwrap();
+		fs::write(dir.path().join("src").join("lib.rs"), "pub fn a() {}").unwrap();
+		let excluded = [dir.path().join("tgt"), dir.path().join("cache")];
+		let fingerprint = || source_fingerprint(dir.path(), &excluded).unwrap();
+		let before = fingerprint();
+		assert!(before.starts_with("1:"));
+
+		// Build outputs go wherever the target directory is configured, not only `target/`.
+		for output in ["tgt", "cache"] {
+			fs::create_dir(dir.path().join(output)).unwrap();
+			fs::write(dir.path().join(output).join("out.json"), "{}").unwrap();
+		}
+		assert_eq!(fingerprint(), before);
+
+		fs::create_dir(dir.path().join(".git")).unwrap();
+		fs::write(dir.path().join(".git").join("index"), "written later").unwrap();
+		fs::write(dir.path().join(".ripdoc-state"), "written later").unwrap();
+		fs::write(dir.path().join("Cargo.lock"), "written by the build").unwrap();
+		assert_eq!(fingerprint(), before);
+
+		fs::write(dir.path().join("src").join("extra.rs"), "pub fn b() {}").unwrap();
+		let with_extra = fingerprint();
+		assert_ne!(with_extra, before);
+
+		// A rename keeps the file count and modification times.
+		fs::rename(dir.path().join("src").join("extra.rs"), dir.path().join("src").join("moved.rs")).unwrap();
+		let renamed = fingerprint();
+		assert_ne!(renamed, with_extra);
+
+		// `#![doc = include_str!("../README.md")]` makes the README an input too.
+		fs::write(dir.path().join("README.md"), "# Docs").unwrap();
+		let with_readme = fingerprint();
+		assert_ne!(with_readme, renamed);
+		fs::write(dir.path().join("README.md"), "# Edited docs").unwrap();
+		assert_ne!(fingerprint(), with_readme);
+		assert!(source_fingerprint(&dir.path().join("missing"), &excluded).is_none());
+	}
+
+	#[test]
+	fn test_refresh_rebuilds_each_key_once() {
+		let key = CacheKey::new(
+			PathBuf::from("/path/to/Cargo.toml"),
+			"test-crate-0.1.0".to_string(),
+			false,
+			false,
+			vec![],
+			false,
+			None,
+		);
+		let config = CacheConfig::new();
+		assert!(!config.needs_refresh(&key));
+
+		let config = config.with_refresh(true);
+		let shared = config.clone();
+		assert!(config.needs_refresh(&key));
+		config.refresh.as_ref().unwrap().lock().unwrap().insert(key.hash());
+		assert!(!shared.needs_refresh(&key));
+	}
+
+	#[test]
+	fn test_rendered_output_lives_and_dies_with_its_build() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = CacheConfig::new().with_cache_dir(dir.path().to_path_buf());
+		let key = CacheKey::new(
+			PathBuf::from("/path/to/Cargo.toml"),
+			"test-crate-0.1.0".to_string(),
+			false,
+			false,
+			vec![],
+			false,
+			None,
+		);
+
+		// Without a cached build there is nothing to tie the skeleton to.
+		save_rendered(&config, &key, "options", "pub fn a();").unwrap();
+		assert_eq!(load_rendered(&config, &key, "options").unwrap(), None);
+
+		fs::write(key.cache_path(dir.path()), b"build").unwrap();
+		save_rendered(&config, &key, "options", "pub fn a();").unwrap();
+		assert_eq!(
+			load_rendered(&config, &key, "options").unwrap(),
+			Some("pub fn a();".to_string())
+		);
+		assert_eq!(load_rendered(&config, &key, "other").unwrap(), None);
+
+		remove_cached(&config, &key).unwrap();
+		fs::write(key.cache_path(dir.path()), b"build").unwrap();
+		assert_eq!(load_rendered(&config, &key, "options").unwrap(), None);
+	}
+
+	fn empty_crate() -> Crate {
+		Crate {
+			root: rustdoc_types::Id(0),
+			crate_version: Some("0.1.0".into()),
+			includes_private: false,
+			index: HashMap::new(),
+			paths: HashMap::new(),
+			external_crates: HashMap::new(),
+			target: rustdoc_types::Target {
+				triple: "test-target".into(),
+				target_features: Vec::new(),
+			},
+			format_version: rustdoc_types::FORMAT_VERSION,
+		}
+	}
+
+	#[test]
+	fn test_memory_cache_evicts_the_least_recently_used_crate() {
+		let mut cache = MemoryCache::default();
+		let shared = Arc::new(empty_crate());
+		for idx in 0..MEMORY_CACHE_CAPACITY {
+			assert!(cache.insert(format!("k{idx}"), Arc::clone(&shared)).is_empty());
+		}
+		// Hits hand out the stored crate itself and count as a use.
+		assert!(Arc::ptr_eq(&cache.get("k0").unwrap(), &shared));
+
+		assert_eq!(cache.insert("new".to_string(), Arc::clone(&shared)), ["k1"]);
+		assert_eq!(cache.crates.len(), MEMORY_CACHE_CAPACITY);
+		assert!(cache.get("k0").is_some());
+		assert!(cache.get("k1").is_none());
+	}
+
+	#[test]
+	fn test_cache_files_round_trip_with_and_without_compression() {
+		let dir = tempfile::tempdir().unwrap();
+		let crate_data = Arc::new(empty_crate());
+		let key = CacheKey::new(
+			PathBuf::from("/path/to/Cargo.toml"),
+			"test-crate-0.1.0".to_string(),
+			false,
+			false,
+			vec![],
+			false,
+			None,
+		);
+
+		let plain = CacheConfig::new()
+			.with_cache_dir(dir.path().to_path_buf())
+			.with_compression(None);
+		save_cached(&plain, &key, &crate_data).unwrap();
+		let raw = fs::read(key.cache_path(dir.path())).unwrap();
+		assert!(!raw.starts_with(&ZSTD_MAGIC));
+
+		let compressed = plain.clone().with_compression(Some(DEFAULT_COMPRESSION_LEVEL));
+		assert_eq!(load_cached(&compressed, &key).unwrap(), Some(crate_data.clone()));
+		save_cached(&compressed, &key, &crate_data).unwrap();
+		let raw = fs::read(key.cache_path(dir.path())).unwrap();
+		assert!(raw.starts_with(&ZSTD_MAGIC));
+		assert_eq!(load_cached(&plain, &key).unwrap(), Some(crate_data));
+	}
+
+	#[test]
+	fn test_split_package_info() {
+		assert_eq!(
+			split_package_info("serde-json-1.0.0-rc.1"),
+			("serde-json".to_string(), Some("1.0.0-rc.1".to_string()))
+		);
+		assert_eq!(split_package_info("demo-workspace"), ("demo-workspace".to_string(), None));
+	}
+
+	#[test]
+	fn test_gc_removes_oldest_entries_over_size() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = CacheConfig::new().with_cache_dir(dir.path().to_path_buf());
+		fs::write(dir.path().join("aaaa.bin"), vec![0u8; 64]).unwrap();
+		fs::write(dir.path().join("aaaa.meta"), "{}").unwrap();
+		fs::write(dir.path().join("lineage.latest"), "aaaa").unwrap();
+		fs::create_dir(dir.path().join("docs-rs")).unwrap();
+		fs::write(dir.path().join("docs-rs").join("demo-0.1.0.json.gz"), vec![0u8; 32]).unwrap();
+
+		let entries = cache_entries(&config).unwrap();
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[1].kind, CacheEntryKind::DocsRs);
+		assert_eq!(entries[1].package.as_deref(), Some("demo"));
+		assert_eq!(entries[1].version.as_deref(), Some("0.1.0"));
+
+		let removed = gc_cache(&config, None, Some(64)).unwrap();
+		assert_eq!(removed.len(), 1);
+		assert_eq!(cache_entries(&config).unwrap().len(), 1);
+
+		clear_cache(&config).unwrap();
+		assert!(cache_entries(&config).unwrap().is_empty());
+		assert!(!dir.path().join("aaaa.meta").exists());
+		assert!(!dir.path().join("lineage.latest").exists());
+	}
+
+	#[test]
+	fn test_orphan_cleanup_spares_temp_files_still_being_written() {
+		let dir = tempfile::tempdir().unwrap();
+		fs::write(dir.path().join("fresh.tmp"), "in progress").unwrap();
+		let stale = fs::File::create(dir.path().join("stale.tmp")).unwrap();
+		stale.set_modified(SystemTime::now() - TEMP_FILE_GRACE * 2).unwrap();
+
+		remove_orphans(dir.path());
+		assert!(dir.path().join("fresh.tmp").exists());
+		assert!(!dir.path().join("stale.tmp").exists());
+	}
+
+	#[test]
+	fn test_lineage_hash_ignores_version_and_toolchain() {
+		let manifest = PathBuf::from("/path/to/Cargo.toml");
+		let key = |package_info: &str, toolchain: &str, private_items: bool| {
+			CacheKey::new(
+				manifest.clone(),
+				package_info.to_string(),
+				false,
+				false,
+				vec![],
+				private_items,
+				Some(toolchain.to_string()),
+			)
+		};
+
+		let old = key("test-crate-0.1.0", "rustc 1.70.0", false);
+		let new = key("test-crate-0.2.0", "rustc 1.71.0", false);
+		assert_ne!(old.hash(), new.hash());
+		assert_eq!(old.lineage_hash(), new.lineage_hash());
+		assert_ne!(old.lineage_hash(), key("test-crate-0.1.0", "rustc 1.70.0", true).lineage_hash());
+	}
 }
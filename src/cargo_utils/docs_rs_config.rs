@@ -0,0 +1,188 @@
+//! The docs.rs build configuration a crate declares in its manifest.
+//!
+//! docs.rs builds documentation with the feature set and extra rustdoc flags listed under
+//! `[package.metadata.docs.rs]`, always adding `--cfg docsrs`. Crates commonly gate
+//! `#[doc(cfg(...))]` annotations and feature-only items on that configuration, so a local build
+//! with default features can show a noticeably different API than the one users read online.
+//! [`DocsRsConfig`] reads the table, and builds run with it replicate the docs.rs build.
+
+use std::env;
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, RipdocError};
+
+/// cfg docs.rs passes to every rustdoc invocation.
+const DOCSRS_CFG: &str = "docsrs";
+
+/// Build settings from a manifest's `[package.metadata.docs.rs]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocsRsConfig {
+	/// Features docs.rs enables (`features`).
+	#[serde(default)]
+	pub features: Vec<String>,
+	/// Whether docs.rs enables every feature (`all-features`).
+	#[serde(default)]
+	pub all_features: bool,
+	/// Whether docs.rs disables default features (`no-default-features`).
+	#[serde(default)]
+	pub no_default_features: bool,
+	/// Extra flags docs.rs passes to rustdoc (`rustdoc-args`).
+	#[serde(default)]
+	pub rustdoc_args: Vec<String>,
+}
+
+impl DocsRsConfig {
+	/// Read the docs.rs table of the manifest at `manifest_path`.
+	///
+	/// A manifest without the table yields the default configuration, which is what docs.rs
+	/// builds such crates with.
+	pub fn from_manifest(manifest_path: &Path) -> Result<Self> {
+		let content = std::fs::read_to_string(manifest_path)?;
+		Self::from_manifest_str(&content)
+	}
+
+	fn from_manifest_str(content: &str) -> Result<Self> {
+		let manifest: toml::Table =
+			toml::from_str(content).map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+		let table = manifest
+			.get("package")
+			.and_then(|package| package.get("metadata"))
+			.and_then(|metadata| metadata.get("docs"))
+			.and_then(|docs| docs.get("rs"))
+			.and_then(toml::Value::as_table);
+		let Some(table) = table else {
+			return Ok(Self::default());
+		};
+		let strings = |key: &str| -> Vec<String> {
+			table
+				.get(key)
+				.and_then(toml::Value::as_array)
+				.map(|values| values.iter().filter_map(|value| value.as_str().map(str::to_string)).collect())
+				.unwrap_or_default()
+		};
+		let flag = |key: &str| table.get(key).and_then(toml::Value::as_bool).unwrap_or(false);
+		Ok(Self {
+			features: strings("features"),
+			all_features: flag("all-features"),
+			no_default_features: flag("no-default-features"),
+			rustdoc_args: strings("rustdoc-args"),
+		})
+	}
+
+	/// Every flag passed to rustdoc: the declared `rustdoc-args`, plus `--cfg docsrs` unless they
+	/// already set it.
+	pub fn rustdoc_flags(&self) -> Vec<String> {
+		let mut flags = self.rustdoc_args.clone();
+		if !self.cfgs().iter().any(|cfg| cfg == DOCSRS_CFG) {
+			flags.extend(["--cfg".to_string(), DOCSRS_CFG.to_string()]);
+		}
+		flags
+	}
+
+	/// The cfgs set by `--cfg` in `rustdoc-args`, in both `--cfg x` and `--cfg=x` forms.
+	pub fn cfgs(&self) -> Vec<String> {
+		let mut cfgs = Vec::new();
+		let mut args = self.rustdoc_args.iter();
+		while let Some(arg) = args.next() {
+			if arg == "--cfg" {
+				cfgs.extend(args.next().cloned());
+			} else if let Some(cfg) = arg.strip_prefix("--cfg=") {
+				cfgs.push(cfg.to_string());
+			}
+		}
+		cfgs
+	}
+}
+
+/// Serializes builds that pass extra rustdoc flags, since they do so through the environment.
+static RUSTDOCFLAGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Extra rustdoc flags set for the duration of one build.
+///
+/// cargo hands `CARGO_ENCODED_RUSTDOCFLAGS` to rustdoc, and the rustdoc JSON builder offers no
+/// other way to pass flags, so the variable is set for the build and restored afterwards. Flags
+/// already in the environment are kept ahead of the added ones.
+pub(super) struct RustdocFlagsGuard {
+	previous: Option<Option<OsString>>,
+	_lock: MutexGuard<'static, ()>,
+}
+
+impl RustdocFlagsGuard {
+	/// Add `flags` to the rustdoc flags of builds started while the guard is held.
+	pub(super) fn set(flags: &[String]) -> Self {
+		let lock = RUSTDOCFLAGS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		if flags.is_empty() {
+			return Self {
+				previous: None,
+				_lock: lock,
+			};
+		}
+		let previous = env::var_os("CARGO_ENCODED_RUSTDOCFLAGS");
+		let mut encoded: Vec<String> = match (&previous, env::var("RUSTDOCFLAGS")) {
+			(Some(previous), _) => previous
+				.to_string_lossy()
+				.split('\u{1f}')
+				.filter(|flag| !flag.is_empty())
+				.map(str::to_string)
+				.collect(),
+			(None, Ok(flags)) => flags.split_whitespace().map(str::to_string).collect(),
+			(None, Err(_)) => Vec::new(),
+		};
+		encoded.extend(flags.iter().cloned());
+		// SAFETY: the variable is only written while RUSTDOCFLAGS_LOCK is held, and ripdoc reads
+		// it nowhere else; cargo reads it in the child process.
+		unsafe { env::set_var("CARGO_ENCODED_RUSTDOCFLAGS", encoded.join("\u{1f}")) };
+		Self {
+			previous: Some(previous),
+			_lock: lock,
+		}
+	}
+}
+
+impl Drop for RustdocFlagsGuard {
+	fn drop(&mut self) {
+		// SAFETY: as in `set`; the lock is still held until the guard's fields drop.
+		match self.previous.take() {
+			None => {}
+			Some(Some(previous)) => unsafe { env::set_var("CARGO_ENCODED_RUSTDOCFLAGS", previous) },
+			Some(None) => unsafe { env::remove_var("CARGO_ENCODED_RUSTDOCFLAGS") },
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reads_the_docs_rs_table() {
+		let config = DocsRsConfig::from_manifest_str(
+			r#"
+[package]
+name = "demo"
+version = "0.1.0"
+
+[package.metadata.docs.rs]
+features = ["fs", "net"]
+no-default-features = true
+rustdoc-args = ["--cfg", "docsrs", "--cfg=tokio_unstable"]
+"#,
+		)
+		.unwrap();
+		assert_eq!(config.features, vec!["fs", "net"]);
+		assert!(config.no_default_features && !config.all_features);
+		assert_eq!(config.cfgs(), vec!["docsrs", "tokio_unstable"]);
+		assert_eq!(config.rustdoc_flags(), config.rustdoc_args);
+	}
+
+	#[test]
+	fn manifests_without_the_table_get_the_docs_rs_defaults() {
+		let config = DocsRsConfig::from_manifest_str("[package]\nname = \"demo\"\n").unwrap();
+		assert_eq!(config, DocsRsConfig::default());
+		assert_eq!(config.rustdoc_flags(), vec!["--cfg", "docsrs"]);
+	}
+}
@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, RipdocError};
+use super::path::CargoPath;
+use super::registry::get_cargo_home;
+use super::resolved_target::ResolvedTarget;
+
+/// Where the sources behind a resolved target live on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageLocation {
+	/// Package name from the manifest, if there is one.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub package: Option<String>,
+	/// Package version, unless inherited from a workspace.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+	/// Package root directory.
+	pub root: PathBuf,
+	/// `Cargo.toml` of the package; `None` for pre-built rustdoc JSON.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub manifest: Option<PathBuf>,
+	/// Crate root source file: the library target, or the first binary of a binary-only crate.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub entry: Option<PathBuf>,
+	/// Module path within the crate the target pointed at; empty for the crate root.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub module: String,
+	/// Archive in cargo's registry cache the sources were unpacked from, for registry crates.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub registry_archive: Option<PathBuf>,
+	/// Rustdoc JSON file read in place of sources, for pre-built JSON targets.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rustdoc_json: Option<PathBuf>,
+}
+
+impl ResolvedTarget {
+	/// Describe where this target's sources live, without building anything.
+	pub fn location(&self) -> Result<PackageLocation> {
+		let root = absolute(self.package_root())?;
+		if let CargoPath::RustdocJson(file) = &self.package_path {
+			return Ok(PackageLocation {
+				package: self.package_name.clone(),
+				version: None,
+				root,
+				manifest: None,
+				entry: None,
+				module: self.filter.clone(),
+				registry_archive: None,
+				rustdoc_json: Some(absolute(file)?),
+			});
+		}
+
+		let manifest = self.package_path.manifest_path()?;
+		let metadata = self.package_path.package_metadata()?;
+		let (package, version) = match metadata {
+			Some(metadata) => (Some(metadata.name), metadata.version),
+			None => (self.package_name.clone(), None),
+		};
+		Ok(PackageLocation {
+			package,
+			version,
+			entry: entry_file(&root, &manifest),
+			registry_archive: registry_archive(&root),
+			root,
+			manifest: Some(manifest),
+			module: self.filter.clone(),
+			rustdoc_json: None,
+		})
+	}
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+	std::path::absolute(path).map_err(|err| {
+		RipdocError::Generate(format!("Failed to resolve '{}': {err}", path.display()))
+	})
+}
+
+/// The library target's source file, falling back to the first binary target.
+fn entry_file(root: &Path, manifest_path: &Path) -> Option<PathBuf> {
+	let manifest = cargo_toml::Manifest::from_path(manifest_path).ok()?;
+	let declared = manifest
+		.lib
+		.as_ref()
+		.and_then(|lib| lib.path.clone())
+		.or_else(|| manifest.bin.first().and_then(|bin| bin.path.clone()))
+		.map(|path| root.join(path));
+	declared.or_else(|| {
+		["src/lib.rs", "src/main.rs"]
+			.iter()
+			.map(|candidate| root.join(candidate))
+			.find(|candidate| candidate.is_file())
+	})
+}
+
+/// Map `$CARGO_HOME/registry/src/<index>/<name>-<version>` to its `.crate` archive under
+/// `$CARGO_HOME/registry/cache`, if the package was unpacked from a registry.
+fn registry_archive(root: &Path) -> Option<PathBuf> {
+	let registry = get_cargo_home().ok()?.join("registry");
+	let relative = root.strip_prefix(registry.join("src")).ok()?;
+	let mut components = relative.components();
+	let index = components.next()?.as_os_str();
+	let package = components.next()?.as_os_str().to_str()?;
+	if components.next().is_some() {
+		return None;
+	}
+	let archive = registry
+		.join("cache")
+		.join(index)
+		.join(format!("{package}.crate"));
+	archive.is_file().then_some(archive)
+}
@@ -1,15 +1,38 @@
 //! Utilities for querying Cargo metadata and managing crate sources.
 
-pub use self::cache::{CacheConfig, CacheKey, get_toolchain_version, load_cached, save_cached};
+pub use self::alias::{AliasStore, config_dir, expand_alias, state_dir};
+pub use self::cache::{
+	CacheConfig, CacheEntry, CacheEntryKind, CacheEntryMeta, CacheKey, cache_entries, clear_cache,
+	clear_memory_cache, gc_cache, get_toolchain_version, load_cached, load_latest_cached,
+	is_rustdoc_input, load_rendered, memory_cache_len, read_cache_entry, remove_cached, save_cached,
+	save_rendered, source_fingerprint,
+};
+pub use self::docs_rs_config::DocsRsConfig;
 pub use self::error::{Result, RipdocError};
-pub use self::path::CargoPath;
-pub use self::registry::{fetch_readme, fetch_registry_crate, find_latest_cached_version};
-pub use self::resolved_target::{ResolvedTarget, resolve_target};
+pub use self::location::PackageLocation;
+pub use self::path::{CargoPath, PackageMetadata, read_rustdoc_json};
+pub use self::registry::{
+	fetch_docs_rs_json, fetch_readme, fetch_registry_crate, find_latest_cached_version,
+};
+pub use self::resolved_target::{
+	ResolveContext, ResolvedTarget, resolve_dependency_targets, resolve_docs_rs_target,
+	resolve_target, resolve_workspace_targets,
+};
 pub use self::rustdoc_error::map_rustdoc_build_error;
+pub use self::sysroot::{is_sysroot_crate, sysroot_crate_json};
+pub use self::verbosity::Verbosity;
+/// Named target shortcuts stored in the ripdoc config directory.
+pub mod alias;
+/// Cross-process locks serializing rustdoc builds of one manifest.
+mod build_lock;
 /// Caching layer for rustdoc JSON output.
 pub mod cache;
+/// Build settings from a crate's `[package.metadata.docs.rs]` table.
+pub mod docs_rs_config;
 /// Error helpers for interacting with Cargo and rustdoc.
 pub mod error;
+/// On-disk locations of resolved packages.
+pub mod location;
 /// CargoPath type and cargo crate path resolution.
 pub mod path;
 /// Downloading crates from crates.io into a local cache.
@@ -18,8 +41,12 @@ pub mod registry;
 pub mod resolved_target;
 /// Rustdoc error handling and diagnostics extraction.
 pub mod rustdoc_error;
+/// Standard library crates from the toolchain's rust-docs-json component.
+pub mod sysroot;
 /// Target parsing utilities.
 pub mod target;
+/// Verbosity levels for mirroring cargo and rustdoc output.
+pub mod verbosity;
 
 /// Check if rustup is available on the system
 pub fn is_rustup_available() -> bool {
@@ -1,20 +1,44 @@
-use std::fs;
-use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+use std::{fs, thread};
 
 use rustdoc_json::PackageTarget;
 use rustdoc_types::Crate;
 use tempfile::TempDir;
 
+use super::build_lock::{BuildLock, LockTimedOut};
+use super::docs_rs_config::{DocsRsConfig, RustdocFlagsGuard};
 use super::error::{Result, RipdocError};
+use super::verbosity::Verbosity;
+
+/// Package-level facts read from a crate's `Cargo.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageMetadata {
+	/// Package name.
+	pub name: String,
+	/// Package version, unless inherited from a workspace.
+	pub version: Option<String>,
+	/// One-line package description.
+	pub description: Option<String>,
+	/// Minimum supported Rust version (`rust-version`).
+	pub rust_version: Option<String>,
+	/// Declared feature names, sorted.
+	pub features: Vec<String>,
+}
 
-/// A path to a crate. This can be a directory on the filesystem or a temporary directory.
+/// A path to a crate. This can be a directory on the filesystem, a temporary directory, or a
+/// pre-built rustdoc JSON file.
 #[derive(Debug)]
 pub enum CargoPath {
 	/// Filesystem-backed crate directory containing a manifest.
 	Path(PathBuf),
 	/// Ephemeral crate stored inside a temporary directory when fetching dependencies.
 	TempDir(TempDir),
+	/// Rustdoc JSON built elsewhere (e.g. downloaded from docs.rs), either plain `.json` or
+	/// gzip-compressed `.json.gz`. Read as-is, so no nightly toolchain is needed; feature flags
+	/// are whatever the JSON was built with.
+	RustdocJson(PathBuf),
 }
 
 impl CargoPath {
@@ -23,30 +47,90 @@ impl CargoPath {
 		match self {
 			Self::Path(path) => path.as_path(),
 			Self::TempDir(temp_dir) => temp_dir.path(),
+			Self::RustdocJson(file) => file.parent().unwrap_or(file),
 		}
 	}
 
-	/// Load rustdoc JSON for the crate represented by this cargo path.
-	/// Read the crate data for this resolved target using rustdoc JSON generation.
-	pub fn read_crate(
+	/// Key identifying the rustdoc JSON built for this crate with the given options.
+	///
+	/// Matches the key [`Self::read_crate`] uses for its caches, including the docs.rs feature
+	/// set and rustdoc flags when `cache_config` enables them, so long-lived processes can keep
+	/// derived data (such as search indexes) alongside the parsed crate.
+	pub fn cache_key(
 		&self,
 		no_default_features: bool,
 		all_features: bool,
 		features: Vec<String>,
 		private_items: bool,
-		silent: bool,
 		cache_config: &super::cache::CacheConfig,
-	) -> Result<Crate> {
-		use std::io;
-
+	) -> Result<super::cache::CacheKey> {
+		if let Self::RustdocJson(file) = self {
+			return rustdoc_json_cache_key(file);
+		}
 		let manifest_path = self.manifest_path()?;
+		let manifest = read_manifest(&manifest_path)?;
+		let options = BuildOptions::new(
+			&manifest_path,
+			cache_config,
+			no_default_features,
+			all_features,
+			features,
+		)?;
+		Ok(self.options_cache_key(&manifest, manifest_path, &options, private_items, cache_config))
+	}
 
-		// Determine which target to document (lib or bin)
-		let manifest_content = fs::read_to_string(&manifest_path)?;
-		let manifest: cargo_toml::Manifest = cargo_toml::Manifest::from_str(&manifest_content)
-			.map_err(|e| RipdocError::ManifestParse(e.to_string()))?;
+	/// Cache key for a build of `manifest` with `options`.
+	fn options_cache_key(
+		&self,
+		manifest: &cargo_toml::Manifest,
+		manifest_path: PathBuf,
+		options: &BuildOptions,
+		private_items: bool,
+		cache_config: &super::cache::CacheConfig,
+	) -> super::cache::CacheKey {
+		let fingerprint = self.source_fingerprint(&manifest_path, cache_config);
+		Self::build_cache_key(
+			manifest,
+			manifest_path,
+			options.no_default_features,
+			options.all_features,
+			options.features.clone(),
+			private_items,
+		)
+		.with_source_fingerprint(fingerprint)
+		.with_rustdoc_flags(options.rustdoc_flags.clone())
+	}
 
-		// Build package info for cache key
+	/// Fingerprint of the crate's sources, for crates that can change between runs.
+	///
+	/// Registry and git checkouts under `CARGO_HOME` and fetched temporary copies are immutable
+	/// for a given version, so they are not fingerprinted. Cargo's target directory, wherever it
+	/// is configured, and the ripdoc cache are left out, since builds write to them.
+	fn source_fingerprint(&self, manifest_path: &Path, cache_config: &super::cache::CacheConfig) -> Option<String> {
+		let Self::Path(path) = self else {
+			return None;
+		};
+		let path = std::path::absolute(path).ok()?;
+		if let Ok(cargo_home) = super::registry::get_cargo_home()
+			&& path.starts_with(&cargo_home)
+		{
+			return None;
+		}
+		let mut excluded: Vec<PathBuf> = cache_config.get_cache_dir().into_iter().collect();
+		if let Ok(metadata) = metadata_command(manifest_path).no_deps().exec() {
+			excluded.push(metadata.target_directory.into_std_path_buf());
+		}
+		super::cache::source_fingerprint(&path, &excluded)
+	}
+
+	fn build_cache_key(
+		manifest: &cargo_toml::Manifest,
+		manifest_path: PathBuf,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> super::cache::CacheKey {
 		let package_info = if let Some(ref package) = manifest.package {
 			let name = &package.name;
 			let version = package
@@ -60,22 +144,81 @@ impl CargoPath {
 			// For virtual manifests or when package info is missing, use a default
 			"unknown-package".to_string()
 		};
-
-		// Try to load from cache
-		let toolchain_version = super::cache::get_toolchain_version();
-		let cache_key = super::cache::CacheKey::new(
-			manifest_path.clone(),
-			package_info.clone(),
+		super::cache::CacheKey::new(
+			manifest_path,
+			package_info,
 			no_default_features,
 			all_features,
-			features.clone(),
+			features,
 			private_items,
-			toolchain_version,
-		);
+			super::cache::get_toolchain_version(),
+		)
+	}
 
+	/// Load rustdoc JSON for the crate represented by this cargo path.
+	/// Read the crate data for this resolved target using rustdoc JSON generation.
+	pub fn read_crate(
+		&self,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+		verbosity: Verbosity,
+		cache_config: &super::cache::CacheConfig,
+	) -> Result<Arc<Crate>> {
+		if let Self::RustdocJson(file) = self {
+			let cache_key = rustdoc_json_cache_key(file)?;
+			if let Ok(Some(cached_crate)) = verbosity.time("cache lookup", || {
+				super::cache::load_cached(cache_config, &cache_key)
+			}) {
+				return Ok(cached_crate);
+			}
+			let crate_data = Arc::new(verbosity.time("parse rustdoc JSON", || read_rustdoc_json(file))?);
+			let _ = super::cache::save_cached(cache_config, &cache_key, &crate_data);
+			return Ok(crate_data);
+		}
+
+		let manifest_path = self.manifest_path()?;
+		let manifest = read_manifest(&manifest_path)?;
+		let options = BuildOptions::new(
+			&manifest_path,
+			cache_config,
+			no_default_features,
+			all_features,
+			features,
+		)?;
+
+		// Try to load from cache
+		let cache_key =
+			self.options_cache_key(&manifest, manifest_path.clone(), &options, private_items, cache_config);
+
+		if let Ok(Some(cached_crate)) =
+			verbosity.time("cache lookup", || super::cache::load_cached(cache_config, &cache_key))
+		{
+			return Ok(cached_crate);
+		}
+
+		// Only one process builds a given manifest at a time; a process that had to wait picks up
+		// the winner's cache entry instead of building the same crate again.
+		let wait_started = Instant::now();
+		let lock = match BuildLock::acquire(
+			cache_config,
+			&manifest_path,
+			cache_config.build_budget,
+			verbosity.is_quiet(),
+		) {
+			Ok(lock) => lock,
+			Err(LockTimedOut) => {
+				let budget = cache_config.build_budget.unwrap_or_default();
+				return fall_back_to_latest_cache(cache_config, &cache_key, budget);
+			}
+		};
 		if let Ok(Some(cached_crate)) = super::cache::load_cached(cache_config, &cache_key) {
 			return Ok(cached_crate);
 		}
+		let build_budget = cache_config
+			.build_budget
+			.map(|budget| budget.saturating_sub(wait_started.elapsed()));
 
 		let package_target = if manifest.lib.is_some() || self.as_path().join("src/lib.rs").exists()
 		{
@@ -105,41 +248,60 @@ impl CargoPath {
 			PackageTarget::Lib
 		};
 
-		let mut captured_stdout = Vec::new();
-		let mut captured_stderr = Vec::new();
-
 		let mut builder = rustdoc_json::Builder::default();
 
 		// Only set toolchain if rustup is available
 		if super::is_rustup_available() {
 			builder = builder.toolchain("nightly");
 		}
+		// Cargo reads `.cargo/config.toml` from its working directory rather than the manifest's,
+		// so pass on the target directory the package's own config chooses.
+		if let Ok(metadata) = metadata_command(&manifest_path).no_deps().exec() {
+			builder = builder.target_dir(metadata.target_directory.as_std_path());
+		}
 
-		let build_result = builder
-			.manifest_path(manifest_path)
-			.package_target(package_target)
-			.document_private_items(private_items)
-			.no_default_features(no_default_features)
-			.all_features(all_features)
-			.features(features)
-			.quiet(silent)
-			.silent(false)
-			.build_with_captured_output(&mut captured_stdout, &mut captured_stderr);
-
-		if !silent {
-			if !captured_stdout.is_empty() && io::stdout().write_all(&captured_stdout).is_err() {
-				// Best-effort output mirroring; ignore write failures.
-			}
-			if !captured_stderr.is_empty() && io::stderr().write_all(&captured_stderr).is_err() {
-				// Best-effort output mirroring; ignore write failures.
+		let build = move || {
+			let _rustdoc_flags = RustdocFlagsGuard::set(&options.rustdoc_flags);
+			let mut captured_stdout = Vec::new();
+			let mut captured_stderr = Vec::new();
+			let result = builder
+				.manifest_path(manifest_path)
+				.package_target(package_target)
+				.document_private_items(private_items)
+				.no_default_features(options.no_default_features)
+				.all_features(options.all_features)
+				.features(options.features)
+				.quiet(verbosity.is_quiet())
+				.silent(false)
+				.build_with_captured_output(&mut captured_stdout, &mut captured_stderr);
+			(result, captured_stdout, captured_stderr)
+		};
+		let ((build_result, captured_stdout, captured_stderr), lock) = match build_budget {
+			None => (verbosity.time("rustdoc build", build), lock),
+			Some(budget) => {
+				// An abandoned build keeps the lock until it finishes, so no other build starts
+				// writing to the same target directory in the meantime.
+				let run = move || (build(), lock);
+				match verbosity.time("rustdoc build", || run_with_budget(budget, run)) {
+					Some(built) => built,
+					None => return fall_back_to_latest_cache(cache_config, &cache_key, budget),
+				}
 			}
-		}
+		};
+		verbosity.mirror(&captured_stdout, &captured_stderr);
 
+		// Below full verbosity rustdoc's diagnostics were not mirrored, so embed them in the error.
 		let json_path = build_result.map_err(|err| {
-			super::rustdoc_error::map_rustdoc_build_error(&err, &captured_stderr, silent)
+			super::rustdoc_error::map_rustdoc_build_error(
+				&err,
+				&captured_stderr,
+				verbosity < Verbosity::Full,
+			)
 		})?;
 		let json_content = fs::read_to_string(&json_path)?;
-		let crate_data: Crate = serde_json::from_str(&json_content).map_err(|e| {
+		let parsed =
+			verbosity.time("parse rustdoc JSON", || serde_json::from_str::<Crate>(&json_content));
+		let crate_data = parsed.map_err(|e| {
             let update_msg = if super::is_rustup_available() {
                 "try running 'rustup update nightly'"
             } else {
@@ -149,9 +311,11 @@ impl CargoPath {
                 "Failed to parse rustdoc JSON, which may indicate an outdated nightly toolchain - {update_msg}:\nError: {e}"
             ))
         })?;
+		let crate_data = Arc::new(crate_data);
 
 		// Save to cache (ignore errors - cache is best-effort)
 		let _ = super::cache::save_cached(cache_config, &cache_key, &crate_data);
+		drop(lock);
 
 		Ok(crate_data)
 	}
@@ -159,6 +323,12 @@ impl CargoPath {
 	/// Compute the absolute `Cargo.toml` path for this source.
 	pub fn manifest_path(&self) -> Result<PathBuf> {
 		use std::path::absolute;
+		if let Self::RustdocJson(file) = self {
+			return Err(RipdocError::Generate(format!(
+				"'{}' is a pre-built rustdoc JSON file and has no Cargo manifest",
+				file.display()
+			)));
+		}
 		let manifest_path = self.as_path().join("Cargo.toml");
 		absolute(&manifest_path).map_err(|err| {
 			RipdocError::Generate(format!(
@@ -170,6 +340,9 @@ impl CargoPath {
 
 	/// Return whether this cargo path includes a `Cargo.toml`.
 	pub fn has_manifest(&self) -> Result<bool> {
+		if matches!(self, Self::RustdocJson(_)) {
+			return Ok(false);
+		}
 		Ok(self.as_path().join("Cargo.toml").exists())
 	}
 
@@ -193,8 +366,7 @@ impl CargoPath {
 	pub fn find_dependency(&self, dependency: &str, _offline: bool) -> Result<Option<Self>> {
 		let manifest_path = self.manifest_path()?;
 
-		let metadata = cargo_metadata::MetadataCommand::new()
-			.manifest_path(&manifest_path)
+		let metadata = metadata_command(&manifest_path)
 			.exec()
 			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
 
@@ -226,6 +398,21 @@ impl CargoPath {
 		Ok(None)
 	}
 
+	/// Package names of the normal dependencies declared in the manifest (renamed dependencies
+	/// report the package they point at). Dev- and build-dependencies are left out.
+	pub fn dependency_names(&self) -> Result<Vec<String>> {
+		if !self.has_manifest()? {
+			return Ok(Vec::new());
+		}
+		let manifest = cargo_toml::Manifest::from_path(self.manifest_path()?)
+			.map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+		Ok(manifest
+			.dependencies
+			.iter()
+			.map(|(key, dependency)| dependency.package().unwrap_or(key).to_string())
+			.collect())
+	}
+
 	/// Walk upwards from `start_dir` to locate the closest `Cargo.toml`.
 	pub fn nearest_manifest(start_dir: &Path) -> Option<Self> {
 		let mut current_dir = start_dir.to_path_buf();
@@ -256,8 +443,7 @@ impl CargoPath {
 			module_name.replace('-', "_")
 		};
 
-		let metadata = cargo_metadata::MetadataCommand::new()
-			.manifest_path(&workspace_manifest_path)
+		let metadata = metadata_command(&workspace_manifest_path)
 			.exec()
 			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
 
@@ -278,8 +464,7 @@ impl CargoPath {
 	pub(super) fn list_workspace_packages(&self) -> Result<Vec<(String, PathBuf)>> {
 		let workspace_manifest_path = self.manifest_path()?;
 
-		let metadata = cargo_metadata::MetadataCommand::new()
-			.manifest_path(&workspace_manifest_path)
+		let metadata = metadata_command(&workspace_manifest_path)
 			.exec()
 			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
 
@@ -298,8 +483,72 @@ impl CargoPath {
 		Ok(packages)
 	}
 
+	/// Locate the root of the workspace containing this package; a package outside any workspace
+	/// is its own root.
+	pub(super) fn workspace_root(&self) -> Result<Self> {
+		let metadata = metadata_command(&self.manifest_path()?)
+			.no_deps()
+			.exec()
+			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
+		Ok(Self::Path(metadata.workspace_root.into()))
+	}
+
+	/// List the packages a workspace-wide command covers: the workspace's `default-members` when
+	/// it declares them, otherwise every member.
+	pub(super) fn list_default_workspace_packages(&self) -> Result<Vec<(String, PathBuf)>> {
+		let packages = self.list_workspace_packages()?;
+		let manifest = cargo_toml::Manifest::from_path(self.manifest_path()?)
+			.map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+		let default_members = manifest
+			.workspace
+			.map(|workspace| workspace.default_members)
+			.unwrap_or_default();
+		if default_members.is_empty() {
+			return Ok(packages);
+		}
+
+		let defaults: Vec<PathBuf> = default_members
+			.iter()
+			.filter_map(|member| fs::canonicalize(self.as_path().join(member)).ok())
+			.collect();
+		Ok(packages
+			.into_iter()
+			.filter(|(_, path)| fs::canonicalize(path).is_ok_and(|path| defaults.contains(&path)))
+			.collect())
+	}
+
+	/// Read package metadata from the manifest; `None` for virtual (workspace-only) manifests.
+	///
+	/// Fields inherited from the workspace (`version.workspace = true`) are reported as unknown.
+	pub fn package_metadata(&self) -> Result<Option<PackageMetadata>> {
+		if matches!(self, Self::RustdocJson(_)) {
+			return Ok(None);
+		}
+		let manifest = cargo_toml::Manifest::from_path(self.manifest_path()?)
+			.map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+		let Some(package) = manifest.package else {
+			return Ok(None);
+		};
+		Ok(Some(PackageMetadata {
+			name: package.name.clone(),
+			version: package.version.get().ok().cloned(),
+			description: package
+				.description
+				.as_ref()
+				.and_then(|description| description.get().ok().cloned()),
+			rust_version: package
+				.rust_version
+				.as_ref()
+				.and_then(|version| version.get().ok().cloned()),
+			features: manifest.features.keys().cloned().collect(),
+		}))
+	}
+
 	/// Find and read the README file in the crate directory.
 	pub fn find_readme(&self) -> Result<Option<String>> {
+		if matches!(self, Self::RustdocJson(_)) {
+			return Ok(None);
+		}
 		let root = self.as_path();
 		let readme_names = [
 			"README.md",
@@ -324,11 +573,175 @@ impl CargoPath {
 	}
 }
 
+/// Feature selection and extra rustdoc flags a build runs with.
+struct BuildOptions {
+	no_default_features: bool,
+	all_features: bool,
+	features: Vec<String>,
+	rustdoc_flags: Vec<String>,
+}
+
+impl BuildOptions {
+	/// The requested options, replaced by the crate's docs.rs configuration when `cache_config`
+	/// asks for it.
+	fn new(
+		manifest_path: &Path,
+		cache_config: &super::cache::CacheConfig,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Self> {
+		if !cache_config.docs_rs_config {
+			return Ok(Self {
+				no_default_features,
+				all_features,
+				features,
+				rustdoc_flags: Vec::new(),
+			});
+		}
+		let docs_rs = DocsRsConfig::from_manifest(manifest_path)?;
+		Ok(Self {
+			no_default_features: docs_rs.no_default_features,
+			all_features: docs_rs.all_features,
+			rustdoc_flags: docs_rs.rustdoc_flags(),
+			features: docs_rs.features,
+		})
+	}
+}
+
+fn read_manifest(manifest_path: &Path) -> Result<cargo_toml::Manifest> {
+	let manifest_content = fs::read_to_string(manifest_path)?;
+	cargo_toml::Manifest::from_str(&manifest_content)
+		.map_err(|e| RipdocError::ManifestParse(e.to_string()))
+}
+
+/// `cargo metadata` for the package at `manifest_path`, run from the package's directory.
+fn metadata_command(manifest_path: &Path) -> cargo_metadata::MetadataCommand {
+	let mut command = cargo_metadata::MetadataCommand::new();
+	command.manifest_path(manifest_path);
+	if let Some(dir) = package_dir(manifest_path) {
+		command.current_dir(dir);
+	}
+	command
+}
+
+/// The directory cargo children for the package at `manifest_path` run in.
+///
+/// Cargo reads `.cargo/config.toml` from its working directory and that directory's ancestors,
+/// not from the manifest's, so a child left in ripdoc's own directory (the daemon's, when a
+/// request is served) would miss the package's config.
+fn package_dir(manifest_path: &Path) -> Option<&Path> {
+	manifest_path.parent().filter(|dir| !dir.as_os_str().is_empty())
+}
+
+/// Run `task` on a worker thread, giving up on it once `budget` has elapsed.
+///
+/// An abandoned build keeps running in the background until it finishes or the process exits.
+fn run_with_budget<T: Send + 'static>(
+	budget: Duration,
+	task: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		let _ = sender.send(task());
+	});
+	receiver.recv_timeout(budget).ok()
+}
+
+/// Serve the most recent cached build after a rustdoc build overran its budget, with a notice
+/// that the data may be out of date.
+fn fall_back_to_latest_cache(
+	cache_config: &super::cache::CacheConfig,
+	cache_key: &super::cache::CacheKey,
+	budget: Duration,
+) -> Result<Arc<Crate>> {
+	match super::cache::load_latest_cached(cache_config, cache_key) {
+		Ok(Some(crate_data)) => {
+			eprintln!(
+				"Notice: rustdoc build exceeded the {}s budget; using the most recent cached build, \
+				 which may be out of date",
+				budget.as_secs()
+			);
+			Ok(crate_data)
+		}
+		_ => Err(RipdocError::Generate(format!(
+			"rustdoc build exceeded the {}s budget and no earlier build of this crate is cached; \
+			 raise --max-build-seconds or run once without it to populate the cache",
+			budget.as_secs()
+		))),
+	}
+}
+
+/// Whether `path` names a rustdoc JSON file (`.json` or `.json.gz`).
+pub fn is_rustdoc_json_path(path: &Path) -> bool {
+	let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+	name.ends_with(".json") || name.ends_with(".json.gz")
+}
+
+/// Cache key for a pre-built rustdoc JSON file, tied to its modification time so a rebuilt file
+/// is not served stale from the cache.
+fn rustdoc_json_cache_key(file: &Path) -> Result<super::cache::CacheKey> {
+	let modified = fs::metadata(file)?
+		.modified()
+		.ok()
+		.and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+		.map(|age| age.as_secs())
+		.unwrap_or_default();
+	Ok(super::cache::CacheKey::new(
+		std::path::absolute(file)?,
+		format!("rustdoc-json-{modified}"),
+		false,
+		false,
+		Vec::new(),
+		false,
+		None,
+	))
+}
+
+/// Parse a pre-built rustdoc JSON file, decompressing `.json.gz` files.
+///
+/// A file built by a rustdoc with a different JSON format version is reported as such rather
+/// than as a bare parse error.
+pub fn read_rustdoc_json(file: &Path) -> Result<Crate> {
+	use std::io::Read;
+
+	let json = if file.extension().is_some_and(|ext| ext == "gz") {
+		let mut json = String::new();
+		flate2::read::GzDecoder::new(fs::File::open(file)?).read_to_string(&mut json)?;
+		json
+	} else {
+		fs::read_to_string(file)?
+	};
+
+	#[derive(serde::Deserialize)]
+	struct FormatVersion {
+		format_version: u32,
+	}
+
+	serde_json::from_str(&json).map_err(|err| {
+		match serde_json::from_str::<FormatVersion>(&json) {
+			Ok(found) if found.format_version != rustdoc_types::FORMAT_VERSION => {
+				RipdocError::Generate(format!(
+					"'{}' uses rustdoc JSON format version {}, but ripdoc reads version {}",
+					file.display(),
+					found.format_version,
+					rustdoc_types::FORMAT_VERSION
+				))
+			}
+			_ => RipdocError::Generate(format!(
+				"Failed to parse rustdoc JSON '{}': {err}",
+				file.display()
+			)),
+		}
+	})
+}
+
 #[cfg(test)]
 mod tests {
 	use tempfile::tempdir;
 
 	use super::*;
+	use crate::cargo_utils::CacheConfig;
 
 	#[test]
 	fn test_is_workspace() -> Result<()> {
@@ -357,4 +770,20 @@ version = "0.1.0"
 
 		Ok(())
 	}
+
+	#[test]
+	fn rustdoc_json_reports_format_version_mismatch() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let file = temp_dir.path().join("old.json");
+		fs::write(&file, r#"{"format_version": 1, "root": 0}"#)?;
+
+		let source = CargoPath::RustdocJson(file);
+		assert!(!source.is_package()?);
+		let err = source
+			.read_crate(false, false, Vec::new(), false, Verbosity::Quiet, &CacheConfig::disabled())
+			.unwrap_err();
+		assert!(err.to_string().contains("format version 1"), "unexpected error: {err}");
+
+		Ok(())
+	}
 }
@@ -10,6 +10,7 @@ use super::error::{Result, RipdocError};
 use super::path::CargoPath;
 
 const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+const DOCS_RS: &str = "https://docs.rs/crate";
 
 /// Download (or reuse a cached) crate from crates.io and expose it as a [`CargoPath`].
 pub fn fetch_registry_crate(
@@ -53,6 +54,68 @@ pub fn fetch_registry_crate(
 		})
 }
 
+/// Download (or reuse a cached) rustdoc JSON build of a crate from docs.rs.
+///
+/// The gzip-compressed artifact is stored as `docs-rs/<name>-<version>.json.gz` under
+/// `cache_dir`, so later runs (including offline ones) read it without touching the network.
+pub fn fetch_docs_rs_json(
+	name: &str,
+	version: Option<&Version>,
+	offline: bool,
+	cache_dir: &Path,
+) -> Result<CargoPath> {
+	let resolved_version = if let Some(version) = version {
+		version.to_string()
+	} else if offline {
+		return Err(RipdocError::Generate(format!(
+			"crate '{name}' requires an explicit version when running offline"
+		)));
+	} else {
+		fetch_latest_version(name)?
+	};
+
+	let file = cache_dir
+		.join("docs-rs")
+		.join(format!("{name}-{resolved_version}.json.gz"));
+	if file.exists() {
+		return Ok(CargoPath::RustdocJson(file));
+	}
+	if offline {
+		return Err(RipdocError::Generate(format!(
+			"docs.rs JSON for '{name}'@{resolved_version} is not cached locally for offline use. \
+             Run without --offline to download it."
+		)));
+	}
+
+	let url = format!("{DOCS_RS}/{name}/{resolved_version}/json.gz");
+	let mut response = ureq::get(&url).call().map_err(|err| match err {
+		ureq::Error::StatusCode(404) => RipdocError::Generate(format!(
+			"docs.rs has no rustdoc JSON for '{name}'@{resolved_version}; \
+             releases built before docs.rs started producing JSON do not have one"
+		)),
+		err => RipdocError::Generate(format!("Failed to reach docs.rs for '{name}': {err}")),
+	})?;
+
+	let mut body = Vec::new();
+	response
+		.body_mut()
+		.with_config()
+		.limit(u64::MAX)
+		.reader()
+		.read_to_end(&mut body)
+		.map_err(|err| {
+			RipdocError::Generate(format!("Failed to read docs.rs response for '{name}': {err}"))
+		})?;
+
+	// Write through a temporary file so an interrupted download never leaves a truncated artifact.
+	fs::create_dir_all(file.parent().unwrap_or(cache_dir))?;
+	let partial = file.with_extension("gz.partial");
+	fs::write(&partial, &body)?;
+	fs::rename(&partial, &file)?;
+
+	Ok(CargoPath::RustdocJson(file))
+}
+
 fn fetch_latest_version(name: &str) -> Result<String> {
 	let url = format!("{CRATES_IO_API}/{name}");
 	let mut response = request(&url, name)?;
@@ -228,7 +291,7 @@ edition = "2021"
 	Ok(())
 }
 
-fn get_cargo_home() -> Result<PathBuf> {
+pub(super) fn get_cargo_home() -> Result<PathBuf> {
 	if let Some(cargo_home) = env::var_os("CARGO_HOME") {
 		return Ok(PathBuf::from(cargo_home));
 	}
@@ -1,14 +1,21 @@
+use std::collections::HashSet;
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 use std::{env, fs};
 
 use rustdoc_types::Crate;
 use semver::Version;
 
+use super::alias::{AliasStore, expand_alias};
 use super::error::{Result, RipdocError};
-use super::path::CargoPath;
-use super::registry::fetch_registry_crate;
+use super::cache::CacheConfig;
+use super::path::{CargoPath, is_rustdoc_json_path};
+use super::registry::{fetch_docs_rs_json, fetch_registry_crate};
+use super::sysroot::{is_sysroot_crate, sysroot_crate_json};
 use super::target::{Entrypoint, Target};
 use super::to_import_name;
+use super::verbosity::Verbosity;
+use crate::timings;
 
 /// A resolved Rust package or module target.
 #[derive(Debug)]
@@ -25,6 +32,63 @@ pub struct ResolvedTarget {
 	pub package_name: Option<String>,
 }
 
+/// Settings target resolution would otherwise take from the process: whether the network may
+/// be used, the directory relative paths and named crates resolve against, and where aliases are
+/// stored.
+///
+/// Carrying them explicitly lets differently configured resolutions run side by side in one
+/// process, such as a server resolving targets for several projects at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolveContext {
+	/// Never fetch crates from the network.
+	pub offline: bool,
+	/// Base for relative target paths and for finding the enclosing workspace of named crates.
+	/// The process's current directory when unset.
+	pub working_dir: Option<PathBuf>,
+	/// Directory holding the alias file. [`AliasStore::config_file`] when unset.
+	pub config_dir: Option<PathBuf>,
+}
+
+impl ResolveContext {
+	/// Resolve against the process's current directory and alias file.
+	pub fn new(offline: bool) -> Self {
+		Self {
+			offline,
+			..Self::default()
+		}
+	}
+
+	/// Resolve relative paths and named crates from `dir` instead of the current directory.
+	pub fn with_working_dir(mut self, dir: Option<PathBuf>) -> Self {
+		self.working_dir = dir;
+		self
+	}
+
+	/// Read aliases from `dir` instead of the default config directory.
+	pub fn with_config_dir(mut self, dir: Option<PathBuf>) -> Self {
+		self.config_dir = dir;
+		self
+	}
+
+	fn working_dir(&self) -> Result<PathBuf> {
+		match &self.working_dir {
+			Some(dir) => Ok(dir.clone()),
+			None => Ok(env::current_dir()?),
+		}
+	}
+
+	/// `target` with its entrypoint alias expanded.
+	fn expand_alias(&self, target: &str) -> String {
+		match &self.config_dir {
+			Some(dir) => AliasStore::load_from(&AliasStore::file_in(dir))
+				.ok()
+				.and_then(|store| store.expand(target))
+				.unwrap_or_else(|| target.to_string()),
+			None => expand_alias(target),
+		}
+	}
+}
+
 enum TargetResolution {
 	FileModule {
 		file: PathBuf,
@@ -34,6 +98,10 @@ enum TargetResolution {
 		package: CargoPath,
 		extra_path: Vec<String>,
 	},
+	RustdocJson {
+		file: PathBuf,
+		extra_path: Vec<String>,
+	},
 	WorkspaceRoot {
 		workspace: CargoPath,
 		extra_path: Vec<String>,
@@ -46,11 +114,11 @@ enum TargetResolution {
 }
 
 impl TargetResolution {
-	fn plan(target: Target) -> Result<Self> {
+	fn plan(target: Target, ctx: &ResolveContext) -> Result<Self> {
 		match target.entrypoint {
 			Entrypoint::Path(path) => {
 				let path = if path.is_relative() {
-					std::path::absolute(&path).map_err(|err| {
+					std::path::absolute(ctx.working_dir()?.join(&path)).map_err(|err| {
 						RipdocError::InvalidTarget(format!(
 							"Failed to resolve target path '{}': {err}",
 							path.display()
@@ -67,6 +135,13 @@ impl TargetResolution {
 					});
 				}
 
+				if path.is_file() && is_rustdoc_json_path(&path) {
+					return Ok(Self::RustdocJson {
+						file: path,
+						extra_path: target.path,
+					});
+				}
+
 				let cargo_path = CargoPath::Path(path.clone());
 				if cargo_path.is_package()? {
 					Ok(Self::PackageDir {
@@ -93,7 +168,7 @@ impl TargetResolution {
 		}
 	}
 
-	fn resolve(self, offline: bool) -> Result<Vec<ResolvedTarget>> {
+	fn resolve(self, ctx: &ResolveContext) -> Result<Vec<ResolvedTarget>> {
 		match self {
 			Self::FileModule { file, extra_path } => {
 				Ok(vec![ResolvedTarget::from_rust_file(file, &extra_path)?])
@@ -102,6 +177,11 @@ impl TargetResolution {
 				package,
 				extra_path,
 			} => Ok(vec![ResolvedTarget::new(package, &extra_path, None)]),
+			Self::RustdocJson { file, extra_path } => Ok(vec![ResolvedTarget::new(
+				CargoPath::RustdocJson(file),
+				&extra_path,
+				None,
+			)]),
 			Self::WorkspaceRoot {
 				workspace,
 				mut extra_path,
@@ -136,7 +216,7 @@ impl TargetResolution {
 				&name,
 				version.as_ref(),
 				&extra_path,
-				offline,
+				ctx,
 			)?]),
 		}
 	}
@@ -171,28 +251,51 @@ impl ResolvedTarget {
 		all_features: bool,
 		features: Vec<String>,
 		private_items: bool,
-		silent: bool,
+		verbosity: Verbosity,
 		cache_config: &super::cache::CacheConfig,
-	) -> Result<Crate> {
+	) -> Result<Arc<Crate>> {
 		self.package_path.read_crate(
 			no_default_features,
 			all_features,
 			features,
 			private_items,
-			silent,
+			verbosity,
+			cache_config,
+		)
+	}
+
+	/// Cache key of the crate [`Self::read_crate`] produces with the same options.
+	pub fn cache_key(
+		&self,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+		cache_config: &super::cache::CacheConfig,
+	) -> Result<super::cache::CacheKey> {
+		self.package_path.cache_key(
+			no_default_features,
+			all_features,
+			features,
+			private_items,
 			cache_config,
 		)
 	}
 
+	/// Read the package metadata (description, MSRV, features) from the target's manifest.
+	pub fn package_metadata(&self) -> Result<Option<super::path::PackageMetadata>> {
+		self.package_path.package_metadata()
+	}
+
 	/// Return the crate root on disk backing this target.
 	pub fn package_root(&self) -> &Path {
 		self.package_path.as_path()
 	}
 
 	/// Resolve a `Target` into a fully-qualified location and filter path.
-	pub fn from_target(target: Target, offline: bool) -> Result<Vec<Self>> {
-		let resolution = TargetResolution::plan(target)?;
-		resolution.resolve(offline)
+	pub fn from_target(target: Target, ctx: &ResolveContext) -> Result<Vec<Self>> {
+		let resolution = TargetResolution::plan(target, ctx)?;
+		resolution.resolve(ctx)
 	}
 
 	/// Resolve a module path starting from a specific Rust source file.
@@ -252,7 +355,7 @@ impl ResolvedTarget {
 		path: &[String],
 		offline: bool,
 	) -> Result<Self> {
-		let cargo_path = fetch_registry_crate(name, version, offline)?;
+		let cargo_path = timings::time("fetch crate", || fetch_registry_crate(name, version, offline))?;
 		Ok(Self::new(cargo_path, path, Some(name.to_string())))
 	}
 
@@ -260,13 +363,22 @@ impl ResolvedTarget {
 		name: &str,
 		version: Option<&Version>,
 		path: &[String],
-		offline: bool,
+		ctx: &ResolveContext,
 	) -> Result<Self> {
+		let offline = ctx.offline;
 		if let Some(version) = version {
 			return Self::from_registry_crate(name, Some(version), path, offline);
 		}
 
-		let current_dir = env::current_dir()?;
+		if is_sysroot_crate(name) {
+			return Ok(Self::new(
+				CargoPath::RustdocJson(sysroot_crate_json(name, offline)?),
+				path,
+				Some(name.to_string()),
+			));
+		}
+
+		let current_dir = ctx.working_dir()?;
 		if let Some(root) = CargoPath::nearest_manifest(&current_dir) {
 			if let Some(workspace_member) = root.find_workspace_package(name)? {
 				return Ok(Self::new(
@@ -296,23 +408,123 @@ impl ResolvedTarget {
 /// Resovles a target specification and returns a ResolvedTarget, pointing to the package
 /// directory. If necessary, construct temporary dummy crate to download packages from cargo.io.
 /// Parse a textual target specification into a `ResolvedTarget`.
-pub fn resolve_target(target_str: &str, offline: bool) -> Result<Vec<ResolvedTarget>> {
-	let target = Target::parse(target_str)?;
+///
+/// An entrypoint matching a stored alias (see [`AliasStore`]) is expanded first.
+pub fn resolve_target(target_str: &str, ctx: &ResolveContext) -> Result<Vec<ResolvedTarget>> {
+	let target = Target::parse(&ctx.expand_alias(target_str))?;
 
 	match &target.entrypoint {
-		Entrypoint::Path(_) => ResolvedTarget::from_target(target, offline),
+		Entrypoint::Path(_) => ResolvedTarget::from_target(target, ctx),
 		Entrypoint::Name { name, version } => {
 			let resolved_list = ResolvedTarget::resolve_named_target(
 				name,
 				version.as_ref(),
 				&target.path,
-				offline,
+				ctx,
 			)?;
 			Ok(vec![resolved_list])
 		}
 	}
 }
 
+/// Resolve a named crate to its rustdoc JSON build on docs.rs instead of building it locally.
+///
+/// Removes the nightly-toolchain requirement for published crates. Downloads are kept under
+/// the cache directory of `cache_config`; path targets resolve as usual.
+pub fn resolve_docs_rs_target(
+	target_str: &str,
+	ctx: &ResolveContext,
+	cache_config: &CacheConfig,
+) -> Result<Vec<ResolvedTarget>> {
+	let target = Target::parse(&ctx.expand_alias(target_str))?;
+	let Entrypoint::Name { name, version } = &target.entrypoint else {
+		return ResolvedTarget::from_target(target, ctx);
+	};
+	if is_sysroot_crate(name) {
+		return ResolvedTarget::from_target(target, ctx);
+	}
+	let cache_dir = cache_config.get_cache_dir()?;
+	let source = timings::time("fetch crate", || {
+		fetch_docs_rs_json(name, version.as_ref(), ctx.offline, &cache_dir)
+	})?;
+	Ok(vec![ResolvedTarget::new(
+		source,
+		&target.path,
+		Some(name.clone()),
+	)])
+}
+
+/// Resolve the manifest dependencies of `targets`, following them `depth` levels deep (1 for
+/// direct dependencies only).
+///
+/// Each package is resolved once however many dependents share it, and never repeats one of
+/// `targets`. Dependencies are located with [`CargoPath::find_dependency`].
+pub fn resolve_dependency_targets(
+	targets: &[ResolvedTarget],
+	depth: usize,
+	offline: bool,
+) -> Result<Vec<ResolvedTarget>> {
+	let mut seen: HashSet<PathBuf> =
+		targets.iter().map(|rt| rt.package_root().to_path_buf()).collect();
+	let mut frontier: Vec<CargoPath> = targets
+		.iter()
+		.filter(|rt| !matches!(rt.package_path, CargoPath::RustdocJson(_)))
+		.map(|rt| CargoPath::Path(rt.package_root().to_path_buf()))
+		.collect();
+	let mut resolved = Vec::new();
+	for _ in 0..depth {
+		let mut next = Vec::new();
+		for package in &frontier {
+			for name in package.dependency_names()? {
+				let Some(dependency) = package.find_dependency(&name, offline)? else {
+					continue;
+				};
+				let root = dependency.as_path().to_path_buf();
+				if !seen.insert(root.clone()) {
+					continue;
+				}
+				resolved.push(ResolvedTarget::new(dependency, &[], Some(name)));
+				next.push(CargoPath::Path(root));
+			}
+		}
+		if next.is_empty() {
+			break;
+		}
+		frontier = next;
+	}
+	Ok(resolved)
+}
+
+/// Resolve every package of the workspace containing the target, honouring `default-members`.
+///
+/// The target's entrypoint only locates the workspace (any member, the root, or a named package
+/// works); a `::path` suffix is applied as a module filter to each package.
+pub fn resolve_workspace_targets(
+	target_str: &str,
+	ctx: &ResolveContext,
+) -> Result<Vec<ResolvedTarget>> {
+	let target = Target::parse(&ctx.expand_alias(target_str))?;
+	let anchor = ResolvedTarget::from_target(
+		Target {
+			entrypoint: target.entrypoint,
+			path: Vec::new(),
+		},
+		ctx,
+	)?
+	.into_iter()
+	.next()
+	.ok_or_else(|| RipdocError::InvalidTarget(format!("No package found for '{target_str}'")))?;
+
+	let packages = anchor
+		.package_path
+		.workspace_root()?
+		.list_default_workspace_packages()?;
+	Ok(packages
+		.into_iter()
+		.map(|(name, path)| ResolvedTarget::new(CargoPath::Path(path), &target.path, Some(name)))
+		.collect())
+}
+
 #[cfg(test)]
 mod tests {
 	use std::env;
@@ -442,7 +654,7 @@ mod tests {
 		];
 
 		for (i, (target, expected_result, expected_filter)) in test_cases.into_iter().enumerate() {
-			let result = ResolvedTarget::from_target(target, true);
+			let result = ResolvedTarget::from_target(target, &ResolveContext::new(true));
 
 			match (result, expected_result) {
 				(Ok(resolved_list), ExpectedResult::Path(expected)) => {
@@ -457,10 +669,8 @@ mod tests {
 								i
 							);
 						}
-						CargoPath::TempDir(_) => {
-							panic!(
-								"Test case {i} failed: expected CargoPath::Path, got CargoPath::TempDir"
-							);
+						CargoPath::TempDir(_) | CargoPath::RustdocJson(_) => {
+							panic!("Test case {i} failed: expected CargoPath::Path, got {resolved:?}");
 						}
 					}
 					assert_eq!(
@@ -515,7 +725,8 @@ mod tests {
 			path: vec![],
 		};
 
-		let resolved_list = ResolvedTarget::from_target(target, true).expect("workspace member");
+		let resolved_list =
+			ResolvedTarget::from_target(target, &ResolveContext::new(true)).expect("workspace member");
 		let resolved = &resolved_list[0];
 		match &resolved.package_path {
 			CargoPath::Path(path) => {
@@ -542,7 +753,8 @@ mod tests {
 			path: vec![],
 		};
 
-		let resolved_list = ResolvedTarget::from_target(target, true).expect("dependency");
+		let resolved_list =
+			ResolvedTarget::from_target(target, &ResolveContext::new(true)).expect("dependency");
 		let resolved = &resolved_list[0];
 		match &resolved.package_path {
 			CargoPath::Path(path) => {
@@ -569,10 +781,88 @@ mod tests {
 			path: vec![],
 		};
 
-		let err = ResolvedTarget::from_target(target, true).unwrap_err();
+		let err = ResolvedTarget::from_target(target, &ResolveContext::new(true)).unwrap_err();
 		assert!(
 			err.to_string().contains("requires an explicit version"),
 			"unexpected error: {err}"
 		);
 	}
+
+	#[test]
+	fn workspace_targets_cover_members_and_respect_default_members() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+
+		let member = root.join("workspace/pkg1");
+		let ctx = ResolveContext::new(true);
+		let resolved =
+			resolve_workspace_targets(&format!("{}::module", member.display()), &ctx).unwrap();
+		let names: Vec<_> = resolved
+			.iter()
+			.map(|rt| rt.package_name.as_deref().unwrap())
+			.collect();
+		assert_eq!(names, vec!["pkg1", "pkg2"]);
+		assert!(resolved.iter().all(|rt| rt.filter == "module"));
+
+		fs::write(
+			root.join("workspace/Cargo.toml"),
+			r#"
+            [workspace]
+            members = ["pkg1", "pkg2"]
+            default-members = ["pkg2"]
+            "#,
+		)
+		.unwrap();
+		let resolved = resolve_workspace_targets(&member.display().to_string(), &ctx).unwrap();
+		assert_eq!(resolved.len(), 1);
+		assert_eq!(resolved[0].package_name.as_deref(), Some("pkg2"));
+	}
+
+	#[test]
+	fn working_dir_replaces_the_current_directory() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let ctx = ResolveContext::new(true).with_working_dir(Some(root.join("workspace")));
+
+		let member = resolve_target("pkg1", &ctx).expect("workspace member");
+		let relative = resolve_target("./pkg2", &ctx).expect("relative path");
+		for (resolved, expected) in [(&member[0], "workspace/pkg1"), (&relative[0], "workspace/pkg2")] {
+			assert_eq!(
+				fs::canonicalize(resolved.package_root()).unwrap(),
+				fs::canonicalize(root.join(expected)).unwrap()
+			);
+		}
+	}
+
+	#[test]
+	fn standard_library_crates_resolve_to_the_toolchain_json() {
+		// A workspace member may share a standard library crate's name without shadowing it.
+		let temp_dir = TempDir::new().unwrap();
+		let root = temp_dir.path();
+		fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"core\"]\n").unwrap();
+		fs::create_dir_all(root.join("core/src")).unwrap();
+		fs::write(
+			root.join("core/Cargo.toml"),
+			"[package]\nname = \"core\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)
+		.unwrap();
+		fs::write(root.join("core/src/lib.rs"), "").unwrap();
+		let ctx = ResolveContext::new(true).with_working_dir(Some(root.to_path_buf()));
+
+		for name in ["std", "core", "alloc"] {
+			// Without the rust-docs-json component (or a toolchain) offline resolution fails
+			// rather than falling back to a workspace member or crates.io.
+			let Ok(resolved) = resolve_target(name, &ctx) else {
+				continue;
+			};
+			let [rt] = resolved.as_slice() else {
+				panic!("expected one target for {name}, got {}", resolved.len());
+			};
+			assert_eq!(rt.package_name.as_deref(), Some(name));
+			let CargoPath::RustdocJson(file) = &rt.package_path else {
+				panic!("{name} resolved to {:?}", rt.package_path);
+			};
+			assert!(file.ends_with(format!("share/doc/rust/json/{name}.json")), "{}", file.display());
+		}
+	}
 }
@@ -59,7 +59,7 @@ fn format_rustdoc_failure(captured_stderr: &[u8], silent: bool) -> RipdocError {
 	}
 
 	let summary = extract_primary_diagnostic(stderr_trimmed).unwrap_or_else(|| {
-		"rustdoc exited with an error; rerun with --verbose for full diagnostics.".to_string()
+		"rustdoc exited with an error; rerun with -vv for full diagnostics.".to_string()
 	});
 	let summary = summary.trim();
 
@@ -67,7 +67,7 @@ fn format_rustdoc_failure(captured_stderr: &[u8], silent: bool) -> RipdocError {
 		if stderr_trimmed.is_empty() {
 			return RipdocError::Generate(
                 "Failed to build rustdoc JSON: rustdoc exited with an error but emitted no diagnostics. \
-                 Re-run with --verbose or `cargo rustdoc` to inspect the failure.".to_string(),
+                 Re-run with -vv or `cargo rustdoc` to inspect the failure.".to_string(),
             );
 		}
 
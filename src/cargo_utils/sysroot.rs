@@ -0,0 +1,95 @@
+//! Standard library crates read from the toolchain's `rust-docs-json` component.
+//!
+//! `std`, `core` and friends are not Cargo packages, so they cannot be documented with
+//! `cargo doc`. The nightly `rust-docs-json` rustup component ships their rustdoc JSON under
+//! `<sysroot>/share/doc/rust/json`, which ripdoc reads like any pre-built JSON file.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::error::{Result, RipdocError};
+use super::is_rustup_available;
+
+/// Crates documented by the `rust-docs-json` component.
+const SYSROOT_CRATES: &[&str] = &["alloc", "core", "proc_macro", "std", "test"];
+
+/// Whether `name` refers to a standard library crate.
+pub fn is_sysroot_crate(name: &str) -> bool {
+	SYSROOT_CRATES.contains(&name)
+}
+
+/// Locate the rustdoc JSON for a standard library crate, installing the `rust-docs-json`
+/// component on the nightly toolchain when it is missing and the network may be used.
+pub fn sysroot_crate_json(name: &str, offline: bool) -> Result<PathBuf> {
+	let file = component_json(&sysroot()?, name);
+	if file.is_file() {
+		return Ok(file);
+	}
+
+	if offline || !is_rustup_available() {
+		return Err(RipdocError::Generate(format!(
+			"rustdoc JSON for '{name}' is not installed; run \
+             `rustup component add --toolchain nightly rust-docs-json`"
+		)));
+	}
+
+	let status = Command::new("rustup")
+		.args(["component", "add", "--toolchain", "nightly", "rust-docs-json"])
+		.stdout(Stdio::null())
+		.status()?;
+	if !status.success() || !file.is_file() {
+		return Err(RipdocError::Generate(format!(
+			"Failed to install the rust-docs-json component needed to render '{name}'"
+		)));
+	}
+	Ok(file)
+}
+
+/// Where the `rust-docs-json` component installs the JSON of `name` within `sysroot`.
+fn component_json(sysroot: &Path, name: &str) -> PathBuf {
+	sysroot.join("share/doc/rust/json").join(format!("{name}.json"))
+}
+
+/// Sysroot of the nightly toolchain (or of the default `rustc` without rustup).
+fn sysroot() -> Result<PathBuf> {
+	let output = if is_rustup_available() {
+		Command::new("rustup")
+			.args(["run", "nightly", "rustc", "--print", "sysroot"])
+			.output()?
+	} else {
+		Command::new("rustc").args(["--print", "sysroot"]).output()?
+	};
+	if !output.status.success() {
+		return Err(RipdocError::Generate(format!(
+			"Failed to locate the Rust sysroot: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+	Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recognizes_standard_library_crates() {
+		for name in ["std", "core", "alloc"] {
+			assert!(is_sysroot_crate(name), "{name}");
+		}
+		for name in ["serde", "stdx", "core-foundation", "Std"] {
+			assert!(!is_sysroot_crate(name), "{name}");
+		}
+	}
+
+	#[test]
+	fn component_json_lives_under_the_sysroot_docs() {
+		let sysroot = Path::new("/toolchains/nightly");
+		for name in ["std", "core", "alloc"] {
+			assert_eq!(
+				component_json(sysroot, name),
+				PathBuf::from(format!("/toolchains/nightly/share/doc/rust/json/{name}.json"))
+			);
+		}
+	}
+}
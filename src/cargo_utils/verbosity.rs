@@ -0,0 +1,107 @@
+//! How much of cargo's and rustdoc's output is mirrored while generating docs.
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::timings;
+
+/// Cargo status verbs whose lines count as progress output.
+const PROGRESS_STATUSES: &[&str] = &[
+	"Adding",
+	"Blocking",
+	"Checking",
+	"Compiling",
+	"Documenting",
+	"Downloaded",
+	"Downloading",
+	"Finished",
+	"Fresh",
+	"Generated",
+	"Locking",
+	"Updating",
+];
+
+/// Output level, selected on the command line by repeating `-v`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+	/// Mirror nothing; rustdoc diagnostics only surface inside errors.
+	#[default]
+	Quiet,
+	/// Cargo progress lines only (`Compiling`, `Documenting`, `Finished`, ...).
+	Progress,
+	/// Full cargo and rustdoc output.
+	Full,
+	/// Full output plus ripdoc's own per-phase timings.
+	Timing,
+}
+
+impl Verbosity {
+	/// Map a `-v` count to a level; counts above three saturate.
+	pub fn from_count(count: u8) -> Self {
+		match count {
+			0 => Self::Quiet,
+			1 => Self::Progress,
+			2 => Self::Full,
+			_ => Self::Timing,
+		}
+	}
+
+	/// Whether nothing is mirrored.
+	pub fn is_quiet(self) -> bool {
+		self == Self::Quiet
+	}
+
+	/// Run `f`, reporting how long it took on stderr at [`Self::Timing`] and adding it to the
+	/// `--timings` totals when those are recorded.
+	pub fn time<T>(self, phase: &str, f: impl FnOnce() -> T) -> T {
+		if self < Self::Timing {
+			return timings::time(phase, f);
+		}
+		let start = Instant::now();
+		let value = f();
+		let elapsed = start.elapsed();
+		timings::record(phase, elapsed);
+		eprintln!("[ripdoc] {phase}: {elapsed:.2?}");
+		value
+	}
+
+	/// Mirror captured cargo output: everything from [`Self::Full`], status lines only at
+	/// [`Self::Progress`]. Write failures are ignored, as mirroring is best-effort.
+	pub(crate) fn mirror(self, stdout: &[u8], stderr: &[u8]) {
+		match self {
+			Self::Quiet => {}
+			Self::Progress => {
+				let stderr = String::from_utf8_lossy(stderr);
+				let mut err = io::stderr().lock();
+				for line in stderr.lines().filter(|line| is_progress_line(line)) {
+					let _ = writeln!(err, "{line}");
+				}
+			}
+			Self::Full | Self::Timing => {
+				let _ = io::stdout().write_all(stdout);
+				let _ = io::stderr().write_all(stderr);
+			}
+		}
+	}
+}
+
+/// Whether `line` is a cargo status line such as `   Compiling serde v1.0.0`.
+fn is_progress_line(line: &str) -> bool {
+	line.split_whitespace()
+		.next()
+		.is_some_and(|status| PROGRESS_STATUSES.contains(&status))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn progress_lines_are_cargo_statuses_only() {
+		assert!(is_progress_line("   Compiling serde v1.0.219"));
+		assert!(is_progress_line("    Finished `dev` profile [unoptimized] target(s) in 1.2s"));
+		assert!(!is_progress_line("warning: unused import: `std::fmt`"));
+		assert!(!is_progress_line("  --> src/lib.rs:1:5"));
+		assert_eq!(Verbosity::from_count(7), Verbosity::Timing);
+	}
+}
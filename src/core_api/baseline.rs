@@ -0,0 +1,247 @@
+//! Checked-in lists of a crate's allowed public API.
+//!
+//! A baseline records every public item as `kind path signature-hash`, grouped under a
+//! `[crate]` header, sorted so regenerating it produces a minimal diff. Checking the current API
+//! against it flags items that are new or whose signature changed since the baseline was
+//! written, so accidental API growth shows up in review as a baseline change. Signatures are
+//! normalized and hashed the same way as [`super::fingerprint`], so formatting and doc changes
+//! do not count.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use rustdoc_types::Crate;
+use serde::{Deserialize, Serialize};
+
+use super::fingerprint::{from_blanket_impl, hash_lines, relative_path};
+use super::pattern::normalize_signature;
+use super::search::{SearchIndex, SearchItemKind};
+
+/// Default file a baseline is written to and checked against.
+pub const DEFAULT_BASELINE_FILE: &str = "ripdoc-api.txt";
+
+/// One public item in a baseline.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineItem {
+	/// Path below the crate root.
+	pub path: String,
+	/// Item kind in its serialized form, such as `function` or `enum_variant`.
+	pub kind: String,
+	/// Hash of the normalized signature.
+	pub signature: String,
+}
+
+/// The public items of one crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateBaseline {
+	/// Crate name.
+	pub name: String,
+	/// Items sorted by path and kind.
+	pub items: Vec<BaselineItem>,
+}
+
+/// Allowed public API of one or more crates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiBaseline {
+	/// Crates in the order they were resolved.
+	pub crates: Vec<CrateBaseline>,
+}
+
+/// How the current API differs from a baseline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineReport {
+	/// Items missing from the baseline, as `crate::path`.
+	pub added: Vec<String>,
+	/// Items whose signature changed since the baseline was written.
+	pub changed: Vec<String>,
+	/// Baseline items that no longer exist.
+	pub removed: Vec<String>,
+}
+
+impl BaselineReport {
+	/// Whether the API grew or changed beyond the baseline. Removed items only make the baseline
+	/// stale, so they do not fail a check.
+	pub fn is_violation(&self) -> bool {
+		!self.added.is_empty() || !self.changed.is_empty()
+	}
+}
+
+/// Record the public API of `crate_data`.
+pub fn crate_baseline(crate_data: &Crate) -> CrateBaseline {
+	let index = SearchIndex::build(crate_data, false, None);
+	let mut items: Vec<BaselineItem> = index
+		.entries()
+		.iter()
+		.filter(|entry| !matches!(entry.kind, SearchItemKind::Use | SearchItemKind::Crate))
+		.filter(|entry| !from_blanket_impl(crate_data, &entry.ancestors))
+		.map(|entry| {
+			let signature = entry
+				.signature
+				.as_deref()
+				.map(normalize_signature)
+				.unwrap_or_default();
+			BaselineItem {
+				path: relative_path(&entry.path),
+				kind: kind_name(entry.kind),
+				signature: hash_lines(&[signature]),
+			}
+		})
+		.collect();
+	items.sort();
+	items.dedup();
+	CrateBaseline {
+		name: crate_data
+			.index
+			.get(&crate_data.root)
+			.and_then(|root| root.name.clone())
+			.unwrap_or_else(|| "crate".to_string()),
+		items,
+	}
+}
+
+impl ApiBaseline {
+	/// Serialize to the checked-in text format.
+	pub fn render(&self) -> String {
+		let mut out = String::from("# Public API baseline; regenerate with `ripdoc api-baseline generate`.\n");
+		for krate in &self.crates {
+			let _ = writeln!(out, "\n[{}]", krate.name);
+			for item in &krate.items {
+				let _ = writeln!(out, "{} {} {}", item.kind, item.path, item.signature);
+			}
+		}
+		out
+	}
+
+	/// Parse the text format written by [`Self::render`].
+	pub fn parse(text: &str) -> Result<Self, String> {
+		let mut crates: Vec<CrateBaseline> = Vec::new();
+		for (number, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+				crates.push(CrateBaseline {
+					name: name.to_string(),
+					items: Vec::new(),
+				});
+				continue;
+			}
+			let parsed = line
+				.rsplit_once(' ')
+				.and_then(|(head, signature)| Some((head.split_once(' ')?, signature)));
+			let (Some(((kind, path), signature)), Some(krate)) = (parsed, crates.last_mut()) else {
+				return Err(format!("line {}: expected `kind path hash` under a `[crate]` header", number + 1));
+			};
+			krate.items.push(BaselineItem {
+				path: path.to_string(),
+				kind: kind.to_string(),
+				signature: signature.to_string(),
+			});
+		}
+		Ok(Self { crates })
+	}
+
+	/// Compare `current` against this baseline.
+	pub fn check(&self, current: &Self) -> BaselineReport {
+		let mut report = BaselineReport::default();
+		for krate in &current.crates {
+			let allowed: BTreeSet<&BaselineItem> = self
+				.crates
+				.iter()
+				.filter(|baseline| baseline.name == krate.name)
+				.flat_map(|baseline| &baseline.items)
+				.collect();
+			for item in &krate.items {
+				if allowed.contains(item) {
+					continue;
+				}
+				let known = allowed
+					.iter()
+					.any(|allowed| allowed.path == item.path && allowed.kind == item.kind);
+				let label = qualified(&krate.name, item);
+				if known {
+					report.changed.push(label);
+				} else {
+					report.added.push(label);
+				}
+			}
+			report.changed.dedup();
+		}
+		for krate in &self.crates {
+			let present: Vec<(&str, &str)> = current
+				.crates
+				.iter()
+				.filter(|current| current.name == krate.name)
+				.flat_map(|current| &current.items)
+				.map(|item| (item.path.as_str(), item.kind.as_str()))
+				.collect();
+			for item in &krate.items {
+				if !present.contains(&(item.path.as_str(), item.kind.as_str())) {
+					report.removed.push(qualified(&krate.name, item));
+				}
+			}
+		}
+		report
+	}
+}
+
+/// Serialized name of `kind`, which unlike its label has no spaces.
+fn kind_name(kind: SearchItemKind) -> String {
+	serde_json::to_value(kind)
+		.ok()
+		.and_then(|value| value.as_str().map(str::to_string))
+		.unwrap_or_default()
+}
+
+/// `kind crate::path` for reports.
+fn qualified(krate: &str, item: &BaselineItem) -> String {
+	if item.path.is_empty() {
+		format!("{} {krate}", item.kind)
+	} else {
+		format!("{} {krate}::{}", item.kind, item.path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn item(kind: &str, path: &str, signature: &str) -> BaselineItem {
+		BaselineItem {
+			path: path.to_string(),
+			kind: kind.to_string(),
+			signature: signature.to_string(),
+		}
+	}
+
+	fn baseline(items: Vec<BaselineItem>) -> ApiBaseline {
+		ApiBaseline {
+			crates: vec![CrateBaseline {
+				name: "demo".to_string(),
+				items,
+			}],
+		}
+	}
+
+	#[test]
+	fn text_format_round_trips() {
+		let original = baseline(vec![item("function", "io::read", "00aa"), item("struct", "Config", "11bb")]);
+		assert_eq!(ApiBaseline::parse(&original.render()).unwrap(), original);
+		assert!(ApiBaseline::parse("function orphan 00aa").is_err());
+	}
+
+	#[test]
+	fn check_flags_growth_and_signature_changes() {
+		let allowed = baseline(vec![item("function", "read", "00aa"), item("function", "write", "11bb")]);
+		let current = baseline(vec![item("function", "read", "ffff"), item("function", "flush", "2222")]);
+		let report = allowed.check(&current);
+		assert_eq!(report.added, vec!["function demo::flush".to_string()]);
+		assert_eq!(report.changed, vec!["function demo::read".to_string()]);
+		assert_eq!(report.removed, vec!["function demo::write".to_string()]);
+		assert!(report.is_violation());
+
+		let shrunk = baseline(vec![item("function", "read", "00aa")]);
+		assert!(!allowed.check(&shrunk).is_violation());
+	}
+}
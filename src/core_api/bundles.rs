@@ -0,0 +1,228 @@
+//! Per-module Markdown bundles pairing a module's skeleton with its long-form docs.
+//!
+//! Many crates keep their prose in Markdown files pulled in with
+//! `#![doc = include_str!("...")]`. A bundle carries the module's docs, the included files it
+//! references (read from disk next to the module source), and the skeleton of the items
+//! declared directly in the module, so each module can be read or indexed on its own.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustdoc_types::Crate;
+
+use super::error::Result;
+use super::search::{SearchIndex, SearchItemKind, build_render_selection};
+use super::split::plan_split;
+use crate::render::Renderer;
+
+/// A Markdown file a module pulls into its docs with `include_str!`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IncludedDoc {
+	/// Path as written in the `include_str!` call.
+	pub path: String,
+	/// File contents.
+	pub contents: String,
+}
+
+/// One module's docs and skeleton.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleBundle {
+	/// Crate the module belongs to.
+	pub crate_name: String,
+	/// Canonical module path.
+	pub module: String,
+	/// Module-level docs as rustdoc reports them.
+	pub docs: Option<String>,
+	/// Files referenced from `doc = include_str!(...)` attributes of the module.
+	pub included: Vec<IncludedDoc>,
+	/// Skeleton of the items declared directly in the module (child modules get their own
+	/// bundle).
+	pub skeleton: String,
+}
+
+impl ModuleBundle {
+	/// Path of the bundle file relative to an output directory, e.g. `demo/net/http.md`.
+	pub fn file_path(&self) -> PathBuf {
+		let mut path: PathBuf = self.module.split("::").collect();
+		if self.module.contains("::") {
+			path.set_extension("md");
+			path
+		} else {
+			path.join("index.md")
+		}
+	}
+
+	/// Render the bundle as one Markdown document.
+	///
+	/// Included files whose contents already appear in the module docs (rustdoc expands
+	/// `include_str!`) are listed by name only.
+	pub fn to_markdown(&self) -> String {
+		let mut output = format!("# `{}`\n\n", self.module);
+		let docs = self.docs.as_deref().unwrap_or("").trim();
+		if !docs.is_empty() {
+			output.push_str(docs);
+			output.push_str("\n\n");
+		}
+		for included in &self.included {
+			let contents = included.contents.trim();
+			if contents.is_empty() || docs.contains(contents) {
+				output.push_str(&format!("_Included from `{}`._\n\n", included.path));
+			} else {
+				output.push_str(&format!("## Included from `{}`\n\n{contents}\n\n", included.path));
+			}
+		}
+		let skeleton = self.skeleton.trim();
+		if !skeleton.is_empty() {
+			output.push_str("## API\n\n");
+			output.push_str(skeleton);
+			output.push('\n');
+		}
+		output
+	}
+}
+
+/// Bundle the module selected by `filter` (relative to the crate root) and every module below
+/// it.
+///
+/// `make` builds the renderer used for skeletons; it should be configured for Markdown.
+/// Relative source paths in the crate's spans resolve against `source_root`.
+pub(crate) fn module_bundles(
+	crate_data: &Crate,
+	index: &SearchIndex<'_>,
+	make: &dyn Fn() -> Renderer,
+	source_root: &Path,
+	filter: &str,
+) -> Result<Vec<ModuleBundle>> {
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	let context = BundleContext {
+		crate_data,
+		index,
+		make,
+		source_root,
+		crate_name: &crate_name,
+	};
+	let mut bundles = Vec::new();
+	let mut seen = HashSet::new();
+	bundle_module(&context, filter, &mut seen, &mut bundles)?;
+	Ok(bundles)
+}
+
+/// Inputs shared by every module of one crate.
+struct BundleContext<'a> {
+	crate_data: &'a Crate,
+	index: &'a SearchIndex<'a>,
+	make: &'a dyn Fn() -> Renderer,
+	source_root: &'a Path,
+	crate_name: &'a str,
+}
+
+fn bundle_module(
+	context: &BundleContext<'_>,
+	filter: &str,
+	seen: &mut HashSet<String>,
+	bundles: &mut Vec<ModuleBundle>,
+) -> Result<()> {
+	let BundleContext {
+		crate_data,
+		index,
+		make,
+		source_root,
+		crate_name,
+	} = context;
+	let module = if filter.is_empty() {
+		crate_name.to_string()
+	} else {
+		format!("{crate_name}::{filter}")
+	};
+	if !seen.insert(module.clone()) {
+		return Ok(());
+	}
+	let entry = index
+		.entries()
+		.iter()
+		.find(|entry| {
+			matches!(entry.kind, SearchItemKind::Module | SearchItemKind::Crate)
+				&& entry.path_string == module
+		});
+	let item = entry.and_then(|entry| crate_data.index.get(&entry.item_id));
+	let docs = item.and_then(|item| item.docs.clone());
+	let included = item
+		.and_then(|item| item.span.as_ref())
+		.map(|span| included_docs(&source_root.join(&span.filename)))
+		.unwrap_or_default();
+
+	let plan = plan_split(index, filter);
+	let skeleton = if plan.root_items.is_empty() {
+		String::new()
+	} else {
+		let selection = build_render_selection(index, &plan.root_items, true, HashSet::new());
+		make().with_filter(filter).with_selection(selection).render(crate_data)?
+	};
+
+	bundles.push(ModuleBundle {
+		crate_name: crate_name.to_string(),
+		module,
+		docs,
+		included,
+		skeleton,
+	});
+	for child in plan.modules {
+		bundle_module(context, &child.filter, seen, bundles)?;
+	}
+	Ok(())
+}
+
+/// Read the files referenced by `doc = include_str!("...")` inner attributes of `source`.
+///
+/// Paths resolve relative to the source file, as `include_str!` does. Unreadable sources and
+/// files are skipped.
+fn included_docs(source: &Path) -> Vec<IncludedDoc> {
+	let Ok(text) = fs::read_to_string(source) else {
+		return Vec::new();
+	};
+	let base = source.parent().unwrap_or(Path::new(""));
+	include_str_paths(&text)
+		.into_iter()
+		.filter_map(|path| {
+			let contents = fs::read_to_string(base.join(&path)).ok()?;
+			Some(IncludedDoc { path, contents })
+		})
+		.collect()
+}
+
+/// Paths named by `#![doc = include_str!("...")]` attributes, in order.
+fn include_str_paths(source: &str) -> Vec<String> {
+	let mut paths = Vec::new();
+	for line in source.lines() {
+		let compact: String = line.split_whitespace().collect();
+		let Some(rest) = compact.strip_prefix("#![doc=include_str!(\"") else {
+			continue;
+		};
+		if let Some((path, _)) = rest.split_once('"') {
+			paths.push(path.to_string());
+		}
+	}
+	paths
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finds_inner_include_str_doc_attributes() {
+		let source = "#![doc = include_str!(\"../README.md\")]\n\
+			#![doc = include_str!( \"guide.md\" )]\n\
+			#![warn(missing_docs)]\n\
+			//! Plain docs.\n";
+		assert_eq!(
+			include_str_paths(source),
+			vec!["../README.md".to_string(), "guide.md".to_string()]
+		);
+	}
+}
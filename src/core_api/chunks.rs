@@ -0,0 +1,176 @@
+//! Splitting rendered output into size-bounded chunks for retrieval pipelines.
+//!
+//! Chunks follow item boundaries: a module that fits the budget becomes one chunk, larger
+//! modules are broken into runs of their direct items (packed greedily) and their child modules
+//! are chunked recursively. An item is never split, so a single item larger than the budget
+//! becomes its own oversized chunk.
+
+use std::collections::HashSet;
+
+use rustdoc_types::Crate;
+
+use super::error::Result;
+use super::search::{SearchIndex, SearchItemKind, SearchResult, build_render_selection};
+use super::split::plan_split;
+use crate::render::{RenderFormat, Renderer};
+
+/// One chunk of rendered output with the metadata needed to cite it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Chunk {
+	/// Crate the chunk belongs to.
+	pub crate_name: String,
+	/// Canonical path of the module the chunk covers.
+	pub module: String,
+	/// Canonical paths of the items rendered in the chunk.
+	pub items: Vec<String>,
+	/// Length of `contents` in characters.
+	pub chars: usize,
+	/// Whether a single item alone exceeded the budget.
+	pub oversized: bool,
+	/// Rendered contents.
+	pub contents: String,
+}
+
+impl Chunk {
+	fn new(
+		crate_name: &str,
+		module: &str,
+		items: Vec<String>,
+		contents: String,
+		max_chars: usize,
+	) -> Self {
+		let chars = contents.chars().count();
+		Self {
+			crate_name: crate_name.to_string(),
+			module: module.to_string(),
+			items,
+			chars,
+			oversized: chars > max_chars,
+			contents,
+		}
+	}
+
+	/// Metadata header placed before the chunk, as a comment in the given format.
+	///
+	/// `number` is one-based.
+	pub fn header(&self, number: usize, total: usize, format: RenderFormat) -> String {
+		let mut lines = vec![
+			format!("ripdoc chunk {number}/{total}"),
+			format!("crate: {}", self.crate_name),
+			format!("module: {}", self.module),
+		];
+		if !self.items.is_empty() {
+			lines.push(format!("items: {}", self.items.join(", ")));
+		}
+		match format {
+			RenderFormat::Rust => lines.iter().map(|line| format!("// {line}\n")).collect(),
+			RenderFormat::Markdown | RenderFormat::Json | RenderFormat::Html => {
+				format!("<!-- {} -->\n", lines.join("\n"))
+			}
+		}
+	}
+}
+
+/// Renders either a whole module or a set of items of one crate.
+pub(crate) struct ChunkRenderer<'a> {
+	/// Builds a renderer configured like the rest of the session.
+	pub make: &'a dyn Fn() -> Renderer,
+	/// Crate being chunked.
+	pub crate_data: &'a Crate,
+	/// Search index over `crate_data`.
+	pub index: &'a SearchIndex<'a>,
+}
+
+impl ChunkRenderer<'_> {
+	/// Render everything below `filter`.
+	fn module(&self, filter: &str) -> Result<String> {
+		Ok((self.make)().with_filter(filter).render(self.crate_data)?)
+	}
+
+	/// Render only `items` (with their ancestors) below `filter`.
+	fn items(&self, filter: &str, items: &[SearchResult]) -> Result<String> {
+		let selection = build_render_selection(self.index, items, true, HashSet::new());
+		Ok((self.make)()
+			.with_filter(filter)
+			.with_selection(selection)
+			.render(self.crate_data)?)
+	}
+}
+
+/// Chunk the module selected by `filter` (relative to the crate root, empty for the root).
+pub(crate) fn chunk_module(
+	render: &ChunkRenderer<'_>,
+	crate_name: &str,
+	filter: &str,
+	max_chars: usize,
+	chunks: &mut Vec<Chunk>,
+) -> Result<()> {
+	let module_path = if filter.is_empty() {
+		crate_name.to_string()
+	} else {
+		format!("{crate_name}::{filter}")
+	};
+
+	let whole = render.module(filter)?;
+	if whole.trim().is_empty() {
+		return Ok(());
+	}
+	if whole.chars().count() <= max_chars {
+		let items = items_below(render.index, &module_path);
+		chunks.push(Chunk::new(crate_name, &module_path, items, whole, max_chars));
+		return Ok(());
+	}
+
+	let plan = plan_split(render.index, filter);
+	let mut group: Vec<SearchResult> = Vec::new();
+	let mut group_chars = 0;
+	for item in plan.root_items {
+		let chars = render.items(filter, std::slice::from_ref(&item))?.chars().count();
+		if !group.is_empty() && group_chars + chars > max_chars {
+			flush_group(render, crate_name, &module_path, filter, &mut group, max_chars, chunks)?;
+			group_chars = 0;
+		}
+		group_chars += chars;
+		group.push(item);
+	}
+	flush_group(render, crate_name, &module_path, filter, &mut group, max_chars, chunks)?;
+
+	for module in plan.modules {
+		chunk_module(render, crate_name, &module.filter, max_chars, chunks)?;
+	}
+	Ok(())
+}
+
+fn flush_group(
+	render: &ChunkRenderer<'_>,
+	crate_name: &str,
+	module_path: &str,
+	filter: &str,
+	group: &mut Vec<SearchResult>,
+	max_chars: usize,
+	chunks: &mut Vec<Chunk>,
+) -> Result<()> {
+	if group.is_empty() {
+		return Ok(());
+	}
+	let contents = render.items(filter, group)?;
+	let items = group.drain(..).map(|item| item.path_string).collect();
+	if !contents.trim().is_empty() {
+		chunks.push(Chunk::new(crate_name, module_path, items, contents, max_chars));
+	}
+	Ok(())
+}
+
+/// Canonical paths of every item declared below `module_path`, skipping `use` entries.
+fn items_below(index: &SearchIndex, module_path: &str) -> Vec<String> {
+	let prefix = format!("{module_path}::");
+	let mut items: Vec<String> = index
+		.entries()
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use)
+		.filter(|entry| entry.path_string.starts_with(&prefix))
+		.map(|entry| entry.path_string.clone())
+		.collect();
+	items.dedup();
+	items
+}
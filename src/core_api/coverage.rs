@@ -0,0 +1,141 @@
+//! Renderer coverage: indexed items that never made it into the rendered skeleton.
+//!
+//! The renderer silently skips item kinds it has no arm for and items outside the path filter.
+//! Rendering with a shared visited set and cross-checking it against the search index turns
+//! those gaps into a report, which doubles as a regression harness for the renderer.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use rustdoc_types::Crate;
+
+use super::search::{SearchIndex, SearchItemKind};
+use crate::render::Renderer;
+use crate::render::error::Result;
+use crate::render::items::is_renderable;
+use crate::render::state::RenderState;
+
+/// Why an indexed item is absent from the rendered output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingReason {
+	/// The renderer has no support for this kind of item.
+	UnsupportedKind,
+	/// The item lies outside the target's module filter.
+	Filtered,
+	/// The item is supported and in scope but was never rendered (e.g. only reachable through a
+	/// re-export the renderer did not follow).
+	NotReached,
+}
+
+impl MissingReason {
+	/// Short label used in text reports.
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::UnsupportedKind => "unsupported kind",
+			Self::Filtered => "filtered",
+			Self::NotReached => "not reached",
+		}
+	}
+}
+
+/// An indexed item the renderer dropped.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MissingItem {
+	/// Canonical item path.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Why the item is missing.
+	pub reason: MissingReason,
+}
+
+/// Coverage report for one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RenderCoverage {
+	/// Crate name.
+	pub crate_name: String,
+	/// Number of items checked.
+	pub checked: usize,
+	/// Items absent from the rendered output, sorted by path.
+	pub missing: Vec<MissingItem>,
+}
+
+/// Render `crate_data` with `renderer` (its format is irrelevant) and report indexed items that
+/// were not rendered.
+///
+/// Only items the renderer emits on their own are checked; members (fields, variants, methods,
+/// associated items) are rendered with their parent and `use` declarations are skipped.
+pub fn render_coverage(crate_data: &Crate, renderer: Renderer) -> Result<RenderCoverage> {
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+
+	let visited = Arc::new(Mutex::new(HashSet::new()));
+	let renderer = renderer.with_visited(visited.clone());
+	RenderState::new(&renderer, crate_data).render()?;
+	let visited = visited.lock().map(|set| set.clone()).unwrap_or_default();
+
+	let index = SearchIndex::build(crate_data, renderer.render_private_items, None);
+	let filter: Vec<&str> = renderer.filter.split("::").filter(|part| !part.is_empty()).collect();
+	let mut seen = HashSet::new();
+	let mut checked = 0;
+	let mut missing = Vec::new();
+	for entry in index.entries() {
+		if !is_standalone(entry.kind) || !seen.insert(entry.item_id) {
+			continue;
+		}
+		let Some(item) = crate_data.index.get(&entry.item_id) else {
+			continue;
+		};
+		checked += 1;
+		if visited.contains(&entry.item_id) {
+			continue;
+		}
+
+		let components: Vec<&str> = entry.path_string.split("::").skip(1).collect();
+		let in_scope = filter.is_empty()
+			|| components.starts_with(&filter)
+			|| filter.starts_with(&components);
+		let reason = if !is_renderable(item) {
+			MissingReason::UnsupportedKind
+		} else if !in_scope {
+			MissingReason::Filtered
+		} else {
+			MissingReason::NotReached
+		};
+		missing.push(MissingItem {
+			path: entry.path_string.clone(),
+			kind: entry.kind,
+			reason,
+		});
+	}
+	missing.sort_by(|a, b| a.path.cmp(&b.path));
+
+	Ok(RenderCoverage {
+		crate_name,
+		checked,
+		missing,
+	})
+}
+
+/// Whether items of `kind` are rendered by themselves rather than as part of a parent.
+fn is_standalone(kind: SearchItemKind) -> bool {
+	matches!(
+		kind,
+		SearchItemKind::Module
+			| SearchItemKind::Struct
+			| SearchItemKind::Union
+			| SearchItemKind::Enum
+			| SearchItemKind::Trait
+			| SearchItemKind::TraitAlias
+			| SearchItemKind::Function
+			| SearchItemKind::Constant
+			| SearchItemKind::Static
+			| SearchItemKind::TypeAlias
+			| SearchItemKind::Macro
+			| SearchItemKind::ProcMacro
+	)
+}
@@ -0,0 +1,244 @@
+//! Structured API comparison between two versions of a crate.
+//!
+//! Both crates are indexed like `ripdoc list` does and matched by item path (below the crate root)
+//! and kind. Items present on one side only are added or removed; items whose normalized
+//! signature differs are changed. Each change is classified by the semver bump it requires.
+
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Item, ItemEnum, StructKind};
+
+use super::pattern::normalize_signature;
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Semver bump required by a change.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SemverImpact {
+	/// No public API change.
+	Patch,
+	/// Backwards-compatible additions.
+	Minor,
+	/// Breaking changes.
+	Major,
+}
+
+impl SemverImpact {
+	/// Short label used in CLI output.
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::Patch => "patch",
+			Self::Minor => "minor",
+			Self::Major => "major",
+		}
+	}
+}
+
+/// What happened to an item between the two versions.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+	/// The item only exists in the new version.
+	Added,
+	/// The item only exists in the old version.
+	Removed,
+	/// The item exists in both versions with different signatures.
+	Changed,
+}
+
+impl ApiChangeKind {
+	/// Short label used in CLI output.
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::Added => "added",
+			Self::Removed => "removed",
+			Self::Changed => "changed",
+		}
+	}
+}
+
+/// A single difference between two versions of an API.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ApiChange {
+	/// What happened to the item.
+	pub change: ApiChangeKind,
+	/// Item path below the crate root.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Signature in the old version.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub old_signature: Option<String>,
+	/// Signature in the new version.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub new_signature: Option<String>,
+	/// Semver bump this change requires.
+	pub impact: SemverImpact,
+	/// Why an addition is breaking, when it is.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reason: Option<String>,
+}
+
+/// Structured API diff between two crate versions.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ApiDiff {
+	/// Label of the old version (usually the target it was built from).
+	pub old: String,
+	/// Label of the new version.
+	pub new: String,
+	/// Overall semver bump: the largest impact of any change.
+	pub impact: SemverImpact,
+	/// Changes sorted by path.
+	pub changes: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+	/// Compare two crates, labelling them for output.
+	pub fn new(old_label: &str, old: &Crate, new_label: &str, new: &Crate) -> Self {
+		let changes = diff_crates(old, new);
+		Self {
+			old: old_label.to_string(),
+			new: new_label.to_string(),
+			impact: changes
+				.iter()
+				.map(|change| change.impact)
+				.max()
+				.unwrap_or(SemverImpact::Patch),
+			changes,
+		}
+	}
+
+	/// Changes of one kind.
+	pub fn of_kind(&self, kind: ApiChangeKind) -> impl Iterator<Item = &ApiChange> {
+		self.changes
+			.iter()
+			.filter(move |change| change.change == kind)
+	}
+}
+
+/// Compute the public API changes from `old` to `new`, sorted by path.
+pub fn diff_crates(old: &Crate, new: &Crate) -> Vec<ApiChange> {
+	let old_index = SearchIndex::build(old, false, None);
+	let new_index = SearchIndex::build(new, false, None);
+	let old_items = keyed_entries(&old_index);
+	let new_items = keyed_entries(&new_index);
+
+	let mut changes = Vec::new();
+	for (key, old_entry) in &old_items {
+		match new_items.get(key) {
+			None => changes.push(ApiChange {
+				change: ApiChangeKind::Removed,
+				path: key.0.clone(),
+				kind: key.1,
+				old_signature: old_entry.signature.clone(),
+				new_signature: None,
+				impact: SemverImpact::Major,
+				reason: None,
+			}),
+			Some(new_entry) => {
+				let normalized = |entry: &SearchResult| entry.signature.as_deref().map(normalize_signature);
+				if normalized(old_entry) != normalized(new_entry) {
+					changes.push(ApiChange {
+						change: ApiChangeKind::Changed,
+						path: key.0.clone(),
+						kind: key.1,
+						old_signature: old_entry.signature.clone(),
+						new_signature: new_entry.signature.clone(),
+						impact: SemverImpact::Major,
+						reason: None,
+					});
+				}
+			}
+		}
+	}
+	for (key, new_entry) in &new_items {
+		if old_items.contains_key(key) {
+			continue;
+		}
+		let reason = breaking_addition(new, new_entry);
+		changes.push(ApiChange {
+			change: ApiChangeKind::Added,
+			path: key.0.clone(),
+			kind: key.1,
+			old_signature: None,
+			new_signature: new_entry.signature.clone(),
+			impact: if reason.is_some() {
+				SemverImpact::Major
+			} else {
+				SemverImpact::Minor
+			},
+			reason,
+		});
+	}
+
+	changes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.change.cmp(&b.change)));
+	changes
+}
+
+/// Index entries keyed by (path below the crate root, kind); the first entry for a key wins.
+fn keyed_entries<'a>(index: &'a SearchIndex) -> HashMap<(String, SearchItemKind), &'a SearchResult> {
+	let mut items = HashMap::new();
+	for entry in index.entries() {
+		if matches!(entry.kind, SearchItemKind::Use | SearchItemKind::Crate) {
+			continue;
+		}
+		let path = entry
+			.path
+			.iter()
+			.skip(1)
+			.map(|segment| segment.name.as_str())
+			.collect::<Vec<_>>()
+			.join("::");
+		items.entry((path, entry.kind)).or_insert(entry);
+	}
+	items
+}
+
+/// Explain why adding `entry` breaks downstream code, if it does.
+fn breaking_addition(crate_data: &Crate, entry: &SearchResult) -> Option<String> {
+	let item = crate_data.index.get(&entry.item_id)?;
+	let parent = entry
+		.ancestors
+		.iter()
+		.rev()
+		.find_map(|id| crate_data.index.get(id));
+	match (&item.inner, parent.map(|parent| (parent, &parent.inner))) {
+		(ItemEnum::Variant(_), Some((parent, ItemEnum::Enum(_)))) if !is_non_exhaustive(parent) => {
+			Some("variant added to an exhaustive enum".to_string())
+		}
+		(ItemEnum::StructField(_), Some((parent, ItemEnum::Struct(struct_))))
+			if !is_non_exhaustive(parent)
+				&& matches!(
+					struct_.kind,
+					StructKind::Plain {
+						has_stripped_fields: false,
+						..
+					}
+				) =>
+		{
+			Some("field added to a struct that can be built with a literal".to_string())
+		}
+		(ItemEnum::Function(function), Some((_, ItemEnum::Trait(_)))) if !function.has_body => {
+			Some("required method added to a trait".to_string())
+		}
+		(ItemEnum::AssocType { type_: None, .. }, Some((_, ItemEnum::Trait(_)))) => {
+			Some("required associated type added to a trait".to_string())
+		}
+		(ItemEnum::AssocConst { value: None, .. }, Some((_, ItemEnum::Trait(_)))) => {
+			Some("required associated constant added to a trait".to_string())
+		}
+		_ => None,
+	}
+}
+
+/// Whether an item carries `#[non_exhaustive]`.
+///
+/// The attribute representation changes between rustdoc format versions, so this checks the
+/// serialized form rather than a particular variant.
+fn is_non_exhaustive(item: &Item) -> bool {
+	serde_json::to_string(&item.attrs).is_ok_and(|attrs| attrs.contains("non_exhaustive"))
+}
@@ -0,0 +1,138 @@
+//! Doc-comment coverage of a crate's public API, per module.
+//!
+//! Every public item counts once (re-exports excluded), as `#![warn(missing_docs)]` would see
+//! it: members of trait impls inherit the trait's docs and positional fields need none, so both
+//! are left out. A doc comment that is present but blank counts as missing.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rustdoc_types::{Crate, ItemEnum};
+
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+use super::stats::enclosing_module;
+
+/// A public item without docs.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UndocumentedItem {
+	/// Canonical item path.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Whether the item has a doc attribute that is empty, rather than none at all.
+	pub blank: bool,
+}
+
+/// Coverage of the items directly inside one module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleDocCoverage {
+	/// Module path, starting with the crate name.
+	pub path: String,
+	/// Items checked, the module itself included.
+	pub items: usize,
+	/// Items with docs.
+	pub documented: usize,
+	/// Items without docs, sorted by path.
+	pub undocumented: Vec<UndocumentedItem>,
+}
+
+impl ModuleDocCoverage {
+	/// Documented share of the module's items, in percent.
+	pub fn percent(&self) -> f64 {
+		percent(self.documented, self.items)
+	}
+}
+
+/// Coverage report for one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DocCoverage {
+	/// Crate name.
+	pub crate_name: String,
+	/// Items checked.
+	pub items: usize,
+	/// Items with docs.
+	pub documented: usize,
+	/// Per-module coverage, sorted by path.
+	pub modules: Vec<ModuleDocCoverage>,
+}
+
+impl DocCoverage {
+	/// Documented share of the crate's items, in percent.
+	pub fn percent(&self) -> f64 {
+		percent(self.documented, self.items)
+	}
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+	if whole == 0 {
+		100.0
+	} else {
+		part as f64 * 100.0 / whole as f64
+	}
+}
+
+/// Check which public items of `crate_data` have doc comments.
+pub fn doc_coverage(crate_data: &Crate) -> DocCoverage {
+	let index = SearchIndex::build(crate_data, false, None);
+	let mut seen = HashSet::new();
+	let mut modules: BTreeMap<String, ModuleDocCoverage> = BTreeMap::new();
+	for entry in index.entries() {
+		if entry.kind == SearchItemKind::Use
+			|| !seen.insert(entry.item_id)
+			|| !needs_docs(crate_data, entry)
+		{
+			continue;
+		}
+		// Modules document themselves with inner `//!` comments, so they count towards their own
+		// coverage.
+		let module = match entry.kind {
+			SearchItemKind::Module | SearchItemKind::Crate => Some(entry.path_string.clone()),
+			_ => enclosing_module(entry),
+		};
+		let Some(module) = module else {
+			continue;
+		};
+		let coverage = modules.entry(module.clone()).or_insert_with(|| ModuleDocCoverage {
+			path: module,
+			items: 0,
+			documented: 0,
+			undocumented: Vec::new(),
+		});
+		coverage.items += 1;
+		match entry.docs.as_deref() {
+			Some(docs) if !docs.trim().is_empty() => coverage.documented += 1,
+			docs => coverage.undocumented.push(UndocumentedItem {
+				path: entry.path_string.clone(),
+				kind: entry.kind,
+				blank: docs.is_some(),
+			}),
+		}
+	}
+
+	let mut modules: Vec<ModuleDocCoverage> = modules.into_values().collect();
+	for module in &mut modules {
+		module.undocumented.sort_by(|a, b| a.path.cmp(&b.path));
+	}
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	DocCoverage {
+		crate_name,
+		items: modules.iter().map(|module| module.items).sum(),
+		documented: modules.iter().map(|module| module.documented).sum(),
+		modules,
+	}
+}
+
+/// Whether rustc's `missing_docs` lint would ask for docs on `entry`.
+fn needs_docs(crate_data: &Crate, entry: &SearchResult) -> bool {
+	let in_trait_impl = entry.ancestors.iter().any(|id| {
+		crate_data.index.get(id).is_some_and(
+			|item| matches!(&item.inner, ItemEnum::Impl(impl_) if impl_.trait_.is_some()),
+		)
+	});
+	let positional =
+		entry.kind == SearchItemKind::Field && entry.raw_name.parse::<usize>().is_ok();
+	!in_trait_impl && !positional
+}
@@ -0,0 +1,229 @@
+//! Dyn-compatibility of a crate's traits, and how its functions accept them.
+//!
+//! For every trait the report records rustdoc's `is_dyn_compatible` flag and the generic
+//! methods that commonly decide it, next to the functions taking the trait as `dyn Trait`
+//! (dynamic dispatch) or as `impl Trait`/a bounded generic (static dispatch).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use rustdoc_types::{
+	Crate, Function, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, Id, ItemEnum,
+	Type, WherePredicate,
+};
+
+use super::search::{SearchIndex, SearchItemKind};
+
+/// A trait method with type or const parameters of its own.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GenericMethod {
+	/// Method name.
+	pub name: String,
+	/// Whether the method is bounded by `where Self: Sized`, which keeps it off trait objects
+	/// and so out of the dyn-compatibility check.
+	pub requires_sized: bool,
+}
+
+/// Dyn-compatibility and usage of one trait.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DynCompatTrait {
+	/// Canonical trait path.
+	pub path: String,
+	/// Whether the trait can be used as `dyn Trait`.
+	pub dyn_compatible: bool,
+	/// Methods with their own type or const parameters.
+	pub generic_methods: Vec<GenericMethod>,
+	/// Functions and methods with a `dyn Trait` parameter, sorted by path.
+	pub dyn_users: Vec<String>,
+	/// Functions and methods with an `impl Trait` parameter or a generic parameter bounded by the
+	/// trait, sorted by path.
+	pub impl_users: Vec<String>,
+}
+
+/// Dyn-compatibility report for one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DynCompatReport {
+	/// Crate name.
+	pub crate_name: String,
+	/// Traits sorted by path.
+	pub traits: Vec<DynCompatTrait>,
+}
+
+/// Collect the dyn-compatibility of each trait in `crate_data` and the functions using it.
+pub fn dyn_compat(crate_data: &Crate, include_private: bool) -> DynCompatReport {
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	let index = SearchIndex::build(crate_data, include_private, None);
+
+	let mut dyn_users: HashMap<Id, BTreeSet<String>> = HashMap::new();
+	let mut impl_users: HashMap<Id, BTreeSet<String>> = HashMap::new();
+	let mut seen = HashSet::new();
+	for entry in index.entries() {
+		if !matches!(
+			entry.kind,
+			SearchItemKind::Function | SearchItemKind::Method | SearchItemKind::TraitMethod
+		) || !seen.insert(entry.item_id)
+		{
+			continue;
+		}
+		let Some(ItemEnum::Function(function)) =
+			crate_data.index.get(&entry.item_id).map(|item| &item.inner)
+		else {
+			continue;
+		};
+		let usage = ParamTraits::of(function);
+		for id in usage.dyn_traits {
+			dyn_users.entry(id).or_default().insert(entry.path_string.clone());
+		}
+		for id in usage.impl_traits {
+			impl_users.entry(id).or_default().insert(entry.path_string.clone());
+		}
+	}
+
+	let mut seen = HashSet::new();
+	let mut traits = Vec::new();
+	for entry in index.entries() {
+		if entry.kind != SearchItemKind::Trait || !seen.insert(entry.item_id) {
+			continue;
+		}
+		let Some(ItemEnum::Trait(trait_)) =
+			crate_data.index.get(&entry.item_id).map(|item| &item.inner)
+		else {
+			continue;
+		};
+		let generic_methods = trait_
+			.items
+			.iter()
+			.filter_map(|id| crate_data.index.get(id))
+			.filter_map(|item| match &item.inner {
+				ItemEnum::Function(function) if has_own_type_params(function) => {
+					Some(GenericMethod {
+						name: item.name.clone().unwrap_or_default(),
+						requires_sized: requires_sized_self(function),
+					})
+				}
+				_ => None,
+			})
+			.collect();
+		traits.push(DynCompatTrait {
+			path: entry.path_string.clone(),
+			dyn_compatible: trait_.is_dyn_compatible,
+			generic_methods,
+			dyn_users: take_users(&mut dyn_users, &entry.item_id),
+			impl_users: take_users(&mut impl_users, &entry.item_id),
+		});
+	}
+	traits.sort_by(|a, b| a.path.cmp(&b.path));
+
+	DynCompatReport { crate_name, traits }
+}
+
+fn take_users(users: &mut HashMap<Id, BTreeSet<String>>, id: &Id) -> Vec<String> {
+	users.remove(id).map(|paths| paths.into_iter().collect()).unwrap_or_default()
+}
+
+/// Whether a function declares type or const parameters (lifetimes do not count).
+fn has_own_type_params(function: &Function) -> bool {
+	function
+		.generics
+		.params
+		.iter()
+		.any(|param| !matches!(param.kind, GenericParamDefKind::Lifetime { .. }))
+}
+
+/// Whether a function carries a `where Self: Sized` bound.
+fn requires_sized_self(function: &Function) -> bool {
+	function.generics.where_predicates.iter().any(|predicate| match predicate {
+		WherePredicate::BoundPredicate {
+			type_: Type::Generic(name),
+			bounds,
+			..
+		} if name == "Self" => bounds.iter().any(|bound| {
+			matches!(bound, GenericBound::TraitBound { trait_, .. }
+				if trait_.path.rsplit("::").next() == Some("Sized"))
+		}),
+		_ => false,
+	})
+}
+
+/// Traits named by a function's parameters, split by how they are dispatched.
+#[derive(Default)]
+struct ParamTraits {
+	dyn_traits: HashSet<Id>,
+	impl_traits: HashSet<Id>,
+}
+
+impl ParamTraits {
+	fn of(function: &Function) -> Self {
+		// Trait bounds of the function's generic parameters, from the parameter list and the
+		// where clause alike.
+		let mut bounds: HashMap<&str, Vec<Id>> = HashMap::new();
+		for param in &function.generics.params {
+			if let GenericParamDefKind::Type { bounds: param_bounds, .. } = &param.kind {
+				bounds.entry(param.name.as_str()).or_default().extend(trait_ids(param_bounds));
+			}
+		}
+		for predicate in &function.generics.where_predicates {
+			if let WherePredicate::BoundPredicate {
+				type_: Type::Generic(name),
+				bounds: param_bounds,
+				..
+			} = predicate
+			{
+				bounds.entry(name.as_str()).or_default().extend(trait_ids(param_bounds));
+			}
+		}
+
+		let mut traits = Self::default();
+		for (_, ty) in &function.sig.inputs {
+			traits.collect(ty, &bounds);
+		}
+		traits
+	}
+
+	fn collect(&mut self, ty: &Type, bounds: &HashMap<&str, Vec<Id>>) {
+		match ty {
+			Type::DynTrait(dyn_trait) => {
+				self.dyn_traits.extend(dyn_trait.traits.iter().map(|poly| poly.trait_.id));
+			}
+			Type::ImplTrait(impl_bounds) => self.impl_traits.extend(trait_ids(impl_bounds)),
+			Type::Generic(name) => {
+				if let Some(ids) = bounds.get(name.as_str()) {
+					self.impl_traits.extend(ids.iter().copied());
+				}
+			}
+			Type::ResolvedPath(path) => {
+				if let Some(GenericArgs::AngleBracketed { args, .. }) = path.args.as_deref() {
+					for arg in args {
+						if let GenericArg::Type(ty) = arg {
+							self.collect(ty, bounds);
+						}
+					}
+				}
+			}
+			Type::Tuple(types) => {
+				for ty in types {
+					self.collect(ty, bounds);
+				}
+			}
+			Type::Slice(ty) => self.collect(ty, bounds),
+			Type::Array { type_, .. }
+			| Type::Pat { type_, .. }
+			| Type::RawPointer { type_, .. }
+			| Type::BorrowedRef { type_, .. } => self.collect(type_, bounds),
+			Type::FunctionPointer(_)
+			| Type::QualifiedPath { .. }
+			| Type::Primitive(_)
+			| Type::Infer => {}
+		}
+	}
+}
+
+fn trait_ids(bounds: &[GenericBound]) -> impl Iterator<Item = Id> + '_ {
+	bounds.iter().filter_map(|bound| match bound {
+		GenericBound::TraitBound { trait_, .. } => Some(trait_.id),
+		_ => None,
+	})
+}
@@ -15,6 +15,21 @@ pub enum RipdocError {
 	Io(std::io::Error),
 	/// Invalid target specifications provided by the user.
 	InvalidTarget(String),
+	/// Output exceeded a configured size budget; carries the size report.
+	BudgetExceeded(String),
+	/// Inputs of a `--locked` build differ from the lockfile; carries the differences.
+	LockMismatch(String),
+	/// A rebuilt document could not be delivered to an output sink.
+	Sink(String),
+}
+
+/// A resolved package that failed while others in the same invocation succeeded.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TargetFailure {
+	/// Package name, or the package root when the name is unknown.
+	pub package: String,
+	/// Rendered error message.
+	pub message: String,
 }
 
 impl fmt::Display for RipdocError {
@@ -24,7 +39,10 @@ impl fmt::Display for RipdocError {
 			Self::Render(err) => write!(f, "{err}"),
 			Self::Serialization(err) => write!(f, "{err}"),
 			Self::Io(err) => write!(f, "{err}"),
-			Self::InvalidTarget(message) => write!(f, "{message}"),
+			Self::InvalidTarget(message)
+			| Self::BudgetExceeded(message)
+			| Self::LockMismatch(message)
+			| Self::Sink(message) => write!(f, "{message}"),
 		}
 	}
 }
@@ -36,7 +54,10 @@ impl std::error::Error for RipdocError {
 			Self::Render(err) => Some(err),
 			Self::Serialization(err) => Some(err),
 			Self::Io(err) => Some(err),
-			Self::InvalidTarget(_) => None,
+			Self::InvalidTarget(_)
+			| Self::BudgetExceeded(_)
+			| Self::LockMismatch(_)
+			| Self::Sink(_) => None,
 		}
 	}
 }
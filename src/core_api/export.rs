@@ -0,0 +1,319 @@
+//! Item-level records for external pipelines (embeddings, search indexes).
+//!
+//! Sits between `raw` (the full rustdoc JSON) and `list` (path and location only): each record
+//! carries what a reader needs to understand one item without the rest of the crate.
+
+use std::path::Path;
+
+use rustdoc_types::{Crate, Item};
+
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+use crate::render::visibility::{EffectiveVisibility, ModuleMap};
+
+/// Source span of an exported item.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ItemSpan {
+	/// Source file, relative to the package root when it can be resolved.
+	pub file: String,
+	/// One-indexed first line.
+	pub start_line: usize,
+	/// One-indexed last line.
+	pub end_line: usize,
+}
+
+/// One item of a crate, self-contained for downstream indexing.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExportedItem {
+	/// Canonical path, e.g. `tokio::sync::Mutex`.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Rendered signature.
+	pub signature: Option<String>,
+	/// Full doc comment.
+	pub docs: Option<String>,
+	/// Visibility as written (`pub`, `pub(crate)`, `pub(super)`, `pub(in path)`, `private`), or
+	/// `inherited` for trait items, variants, and trait impl members.
+	pub visibility: String,
+	/// Source span, when rustdoc recorded one.
+	pub span: Option<ItemSpan>,
+	/// `cfg` predicates attached to the item (from `#[cfg]` or `#[doc(cfg)]`).
+	pub cfgs: Vec<String>,
+}
+
+/// Export every indexed item of `crate_data`; `use` declarations are skipped.
+///
+/// `source_root` is the package root used to shorten span paths.
+pub fn export_items(
+	crate_data: &Crate,
+	include_private: bool,
+	source_root: Option<&Path>,
+) -> Vec<ExportedItem> {
+	let index = SearchIndex::build(crate_data, include_private, source_root);
+	let modules = ModuleMap::build(crate_data);
+	index
+		.entries()
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use)
+		.filter_map(|entry| {
+			let item = crate_data.index.get(&entry.item_id)?;
+			Some(exported_item(entry, item, &modules))
+		})
+		.collect()
+}
+
+fn exported_item(entry: &SearchResult, item: &Item, modules: &ModuleMap) -> ExportedItem {
+	let visibility = match EffectiveVisibility::of(item, modules) {
+		EffectiveVisibility::Inherited => "inherited".to_string(),
+		EffectiveVisibility::Private => "private".to_string(),
+		EffectiveVisibility::CrateRoot => "pub(crate)".to_string(),
+		other => other.keyword().trim_end().to_string(),
+	};
+	let span = entry.source.as_ref().zip(item.span.as_ref()).map(|(source, span)| ItemSpan {
+		file: source.path.clone(),
+		start_line: span.begin.0,
+		end_line: span.end.0,
+	});
+	ExportedItem {
+		path: entry.path_string.clone(),
+		kind: entry.kind,
+		signature: entry.signature.clone(),
+		docs: item.docs.clone(),
+		visibility,
+		span,
+		cfgs: cfg_predicates(item),
+	}
+}
+
+/// Collect `cfg(...)` predicates from the item's attributes.
+pub(crate) fn cfg_predicates(item: &Item) -> Vec<String> {
+	let mut cfgs = Vec::new();
+	for text in attribute_texts(item) {
+		let predicates = match text.find("CfgTrace(") {
+			Some(start) => parse_cfg_trace(&text[start + "CfgTrace(".len()..]),
+			None => extract_cfg(&text).into_iter().collect(),
+		};
+		for predicate in predicates {
+			if !cfgs.contains(&predicate) {
+				cfgs.push(predicate);
+			}
+		}
+	}
+	cfgs
+}
+
+/// Whether the item is marked `#[doc(hidden)]`.
+pub(crate) fn is_doc_hidden(item: &Item) -> bool {
+	attribute_texts(item).iter().any(|text| hides_docs(text))
+}
+
+/// The feature gate named by the item's `#[unstable(feature = "...")]` attribute, as found on
+/// standard library items.
+pub(crate) fn unstable_feature(item: &Item) -> Option<String> {
+	attribute_texts(item)
+		.iter()
+		.find_map(|text| extract_unstable_feature(text))
+}
+
+/// The textual form of the item's attributes.
+///
+/// Attributes are read through their serialized form so both the plain-string and structured
+/// attribute encodings of rustdoc JSON are handled.
+fn attribute_texts(item: &Item) -> Vec<String> {
+	let Ok(serde_json::Value::Array(attrs)) = serde_json::to_value(&item.attrs) else {
+		return Vec::new();
+	};
+	attrs
+		.into_iter()
+		.filter_map(|attr| match attr {
+			serde_json::Value::String(text) => Some(text),
+			serde_json::Value::Object(map) => match map.get("other") {
+				Some(serde_json::Value::String(text)) => Some(text.clone()),
+				_ => None,
+			},
+			_ => None,
+		})
+		.collect()
+}
+
+/// Whether the attribute is `#[doc(hidden)]`, possibly alongside other `doc` arguments.
+fn hides_docs(attr: &str) -> bool {
+	let compact: String = attr.chars().filter(|ch| !ch.is_whitespace()).collect();
+	compact.starts_with("#[doc(")
+		&& compact
+			.trim_start_matches("#[doc(")
+			.trim_end_matches(")]")
+			.split(',')
+			.any(|arg| arg == "hidden")
+}
+
+/// Pull the feature name out of `#[unstable(feature = "...", ...)]`.
+fn extract_unstable_feature(attr: &str) -> Option<String> {
+	let rest = attr.trim_start().strip_prefix("#[unstable(")?;
+	let rest = &rest[rest.find("feature")? + "feature".len()..];
+	let rest = rest.trim_start().strip_prefix('=')?.trim_start().strip_prefix('"')?;
+	Some(rest[..rest.find('"')?].to_string())
+}
+
+/// Pull the predicate out of `#[cfg(...)]` or `#[doc(cfg(...))]`.
+fn extract_cfg(attr: &str) -> Option<String> {
+	let start = attr.find("cfg(")? + "cfg(".len();
+	let mut depth = 1usize;
+	for (offset, ch) in attr[start..].char_indices() {
+		match ch {
+			'(' => depth += 1,
+			')' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(attr[start..start + offset].trim().to_string());
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Turn the `[entry, ...]` list of a `#[attr = CfgTrace(...)]` attribute back into cfg
+/// predicates.
+///
+/// Newer toolchains report cfg gates as the compiler's debug form of the parsed predicate rather
+/// than the source attribute, one list entry per `#[cfg(...)]` on the item.
+fn parse_cfg_trace(text: &str) -> Vec<String> {
+	let mut predicates = Vec::new();
+	let Some(mut rest) = text.trim_start().strip_prefix('[') else {
+		return predicates;
+	};
+	while let Some((predicate, remaining)) = parse_cfg_entry(rest) {
+		predicates.push(predicate);
+		rest = remaining;
+	}
+	predicates
+}
+
+/// Parse one cfg entry, returning its predicate and the text after it.
+fn parse_cfg_entry(text: &str) -> Option<(String, &str)> {
+	let text = text.trim_start_matches(|ch: char| ch == ',' || ch.is_whitespace());
+	if let Some(rest) = text.strip_prefix("NameValue {") {
+		let rest = &rest[rest.find("name:")? + "name:".len()..];
+		let (name, rest) = parse_debug_str(rest)?;
+		let rest = rest.trim_start().strip_prefix(',')?.trim_start().strip_prefix("value:")?;
+		let rest = rest.trim_start();
+		let (predicate, rest) = match rest.strip_prefix("Some(") {
+			Some(value) => {
+				let (value, rest) = parse_debug_str(value)?;
+				(format!("{name} = {value:?}"), rest)
+			}
+			None => (name, rest),
+		};
+		return Some((predicate, &rest[rest.find('}')? + 1..]));
+	}
+	for (prefix, combinator) in [("All([", "all"), ("Any([", "any")] {
+		if let Some(mut rest) = text.strip_prefix(prefix) {
+			let mut parts = Vec::new();
+			while let Some((part, remaining)) = parse_cfg_entry(rest) {
+				parts.push(part);
+				rest = remaining;
+			}
+			let rest = rest.trim_start_matches(|ch: char| ch == ',' || ch.is_whitespace());
+			let rest = skip_to_close(rest.strip_prefix(']')?)?;
+			return Some((format!("{combinator}({})", parts.join(", ")), rest));
+		}
+	}
+	if let Some(rest) = text.strip_prefix("Not(") {
+		let (inner, rest) = parse_cfg_entry(rest)?;
+		return Some((format!("not({inner})"), skip_to_close(rest)?));
+	}
+	if let Some(rest) = text.strip_prefix("Bool(") {
+		let value = rest[..rest.find(',')?].trim().to_string();
+		return Some((value, skip_to_close(rest)?));
+	}
+	None
+}
+
+/// Read a debug-formatted string literal, returning its contents and the text after it.
+fn parse_debug_str(text: &str) -> Option<(String, &str)> {
+	let text = text.trim_start().strip_prefix('"')?;
+	let mut value = String::new();
+	let mut chars = text.char_indices();
+	while let Some((offset, ch)) = chars.next() {
+		match ch {
+			'\\' => value.push(chars.next()?.1),
+			'"' => return Some((value, &text[offset + 1..])),
+			_ => value.push(ch),
+		}
+	}
+	None
+}
+
+/// Skip past the `)` closing a group that is already open, such as the trailing span of an entry.
+fn skip_to_close(text: &str) -> Option<&str> {
+	let mut depth = 1usize;
+	for (offset, ch) in text.char_indices() {
+		match ch {
+			'(' => depth += 1,
+			')' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(&text[offset + 1..]);
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{extract_cfg, extract_unstable_feature, hides_docs, parse_cfg_trace};
+
+	#[test]
+	fn extracts_nested_cfg_predicates() {
+		assert_eq!(
+			extract_cfg("#[doc(cfg(all(feature = \"tls\", unix)))]").as_deref(),
+			Some("all(feature = \"tls\", unix)")
+		);
+		assert_eq!(extract_cfg("#[cfg(test)]").as_deref(), Some("test"));
+		assert_eq!(extract_cfg("#[inline]"), None);
+	}
+
+	#[test]
+	fn parses_cfg_trace_attributes() {
+		let trace = r#"[Any([Not(NameValue { name: "windows", value: None, span: src/lib.rs:6:15: 6:22 (#0) }, src/lib.rs:6:14: 6:23 (#0)), NameValue { name: "target_os", value: Some("linux"), span: src/lib.rs:6:25: 6:44 (#0) }], src/lib.rs:6:10: 6:45 (#0)),
+NameValue { name: "unix", value: None, span: src/lib.rs:7:7: 7:11 (#0) }])]"#;
+		assert_eq!(
+			parse_cfg_trace(trace),
+			vec![
+				"any(not(windows), target_os = \"linux\")".to_string(),
+				"unix".to_string()
+			]
+		);
+		assert_eq!(
+			parse_cfg_trace(r#"[Bool(true, src/lib.rs:9:7: 9:11 (#0))])]"#),
+			vec!["true".to_string()]
+		);
+	}
+
+	#[test]
+	fn recognizes_doc_hidden() {
+		assert!(hides_docs("#[doc(hidden)]"));
+		assert!(hides_docs("#[doc(inline, hidden)]"));
+		assert!(!hides_docs("#[doc(cfg(feature = \"hidden\"))]"));
+		assert!(!hides_docs("#[must_use]"));
+	}
+
+	#[test]
+	fn extracts_unstable_feature_gates() {
+		assert_eq!(
+			extract_unstable_feature("#[unstable(feature = \"new_uninit\", issue = \"63291\")]")
+				.as_deref(),
+			Some("new_uninit")
+		);
+		assert_eq!(
+			extract_unstable_feature("#[rustc_const_unstable(feature = \"const_x\", issue = \"1\")]"),
+			None
+		);
+		assert_eq!(extract_unstable_feature("#[stable(feature = \"rust1\", since = \"1.0.0\")]"), None);
+	}
+}
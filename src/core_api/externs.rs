@@ -0,0 +1,291 @@
+//! Cross-references from a crate's public API to the external crates it exposes.
+//!
+//! Every public item is scanned for paths (types, trait bounds, implemented traits, and
+//! re-exports) that rustdoc resolves into another crate. A dependency that shows up here is part
+//! of the crate's semver surface: bumping it can break downstream users.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rustdoc_types::{
+	AssocItemConstraintKind, Crate, GenericArg, GenericArgs, GenericBound, GenericParamDefKind,
+	Generics, Id, Item, ItemEnum, Path, Term, Type, WherePredicate,
+};
+
+use super::search::{SearchIndex, SearchItemKind};
+
+/// Crates that ship with the toolchain and are skipped unless explicitly requested.
+const TOOLCHAIN_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// A public item that exposes a path from an external crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExternLeak {
+	/// Canonical path of the public item.
+	pub item: String,
+	/// Kind of the public item.
+	pub kind: SearchItemKind,
+	/// Full path of the external item it references.
+	pub external: String,
+}
+
+/// An external crate referenced by the public API, with the items that expose it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExternCrateUsage {
+	/// Crate name as it appears in paths.
+	pub name: String,
+	/// Documentation root reported by rustdoc, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub html_root_url: Option<String>,
+	/// Public items referencing the crate, sorted by item path.
+	pub leaks: Vec<ExternLeak>,
+}
+
+/// Collect external crates referenced by the public API of `crate_data`.
+///
+/// Toolchain crates (`std`, `core`, `alloc`, ...) are skipped unless `include_std` is set.
+/// Crates are sorted by the number of leaking references, most first.
+pub fn extern_usage(crate_data: &Crate, include_std: bool) -> Vec<ExternCrateUsage> {
+	let index = SearchIndex::build(crate_data, false, None);
+	let mut by_crate: BTreeMap<u32, Vec<ExternLeak>> = BTreeMap::new();
+
+	for entry in index.entries() {
+		let Some(item) = crate_data.index.get(&entry.item_id) else {
+			continue;
+		};
+		let mut refs = Vec::new();
+		collect_item_refs(crate_data, item, &mut refs);
+		let mut seen = HashSet::new();
+		refs.retain(|id| seen.insert(*id));
+
+		for id in refs {
+			let Some(summary) = crate_data.paths.get(&id) else {
+				continue;
+			};
+			if summary.crate_id == 0 {
+				continue;
+			}
+			let leaks = by_crate.entry(summary.crate_id).or_default();
+			let leak = ExternLeak {
+				item: entry.path_string.clone(),
+				kind: entry.kind,
+				external: summary.path.join("::"),
+			};
+			if !leaks.contains(&leak) {
+				leaks.push(leak);
+			}
+		}
+	}
+
+	let mut usages: Vec<ExternCrateUsage> = by_crate
+		.into_iter()
+		.filter_map(|(crate_id, mut leaks)| {
+			let external = crate_data.external_crates.get(&crate_id)?;
+			if !include_std && TOOLCHAIN_CRATES.contains(&external.name.as_str()) {
+				return None;
+			}
+			leaks.sort_by(|a, b| a.item.cmp(&b.item).then_with(|| a.external.cmp(&b.external)));
+			Some(ExternCrateUsage {
+				name: external.name.clone(),
+				html_root_url: external.html_root_url.clone(),
+				leaks,
+			})
+		})
+		.collect();
+	usages.sort_by(|a, b| b.leaks.len().cmp(&a.leaks.len()).then_with(|| a.name.cmp(&b.name)));
+	usages
+}
+
+/// Record ids of every path an item mentions in its public signature.
+pub(crate) fn collect_item_refs(crate_data: &Crate, item: &Item, refs: &mut Vec<Id>) {
+	match &item.inner {
+		ItemEnum::Function(function) => {
+			for (_, ty) in &function.sig.inputs {
+				collect_type_refs(ty, refs);
+			}
+			if let Some(output) = &function.sig.output {
+				collect_type_refs(output, refs);
+			}
+			collect_generics_refs(&function.generics, refs);
+		}
+		ItemEnum::StructField(ty) => collect_type_refs(ty, refs),
+		ItemEnum::Struct(struct_) => {
+			collect_generics_refs(&struct_.generics, refs);
+			collect_impl_trait_refs(crate_data, &struct_.impls, refs);
+		}
+		ItemEnum::Union(union_) => {
+			collect_generics_refs(&union_.generics, refs);
+			collect_impl_trait_refs(crate_data, &union_.impls, refs);
+		}
+		ItemEnum::Enum(enum_) => {
+			collect_generics_refs(&enum_.generics, refs);
+			collect_impl_trait_refs(crate_data, &enum_.impls, refs);
+		}
+		ItemEnum::Trait(trait_) => {
+			collect_generics_refs(&trait_.generics, refs);
+			collect_bounds_refs(&trait_.bounds, refs);
+		}
+		ItemEnum::TraitAlias(alias) => {
+			collect_generics_refs(&alias.generics, refs);
+			collect_bounds_refs(&alias.params, refs);
+		}
+		ItemEnum::TypeAlias(alias) => {
+			collect_type_refs(&alias.type_, refs);
+			collect_generics_refs(&alias.generics, refs);
+		}
+		ItemEnum::Constant { type_, .. } | ItemEnum::AssocConst { type_, .. } => {
+			collect_type_refs(type_, refs);
+		}
+		ItemEnum::Static(static_) => collect_type_refs(&static_.type_, refs),
+		ItemEnum::AssocType {
+			generics,
+			bounds,
+			type_,
+		} => {
+			collect_generics_refs(generics, refs);
+			collect_bounds_refs(bounds, refs);
+			if let Some(ty) = type_ {
+				collect_type_refs(ty, refs);
+			}
+		}
+		ItemEnum::Use(import) => refs.extend(import.id),
+		_ => {}
+	}
+}
+
+/// Record traits implemented by a type, skipping auto traits and blanket impls.
+fn collect_impl_trait_refs(crate_data: &Crate, impls: &[Id], refs: &mut Vec<Id>) {
+	for impl_id in impls {
+		let Some(ItemEnum::Impl(impl_)) = crate_data.index.get(impl_id).map(|item| &item.inner)
+		else {
+			continue;
+		};
+		if impl_.is_synthetic || impl_.blanket_impl.is_some() {
+			continue;
+		}
+		if let Some(trait_) = &impl_.trait_ {
+			collect_path_refs(trait_, refs);
+		}
+	}
+}
+
+fn collect_generics_refs(generics: &Generics, refs: &mut Vec<Id>) {
+	for param in &generics.params {
+		match &param.kind {
+			GenericParamDefKind::Lifetime { .. } => {}
+			GenericParamDefKind::Type {
+				bounds, default, ..
+			} => {
+				collect_bounds_refs(bounds, refs);
+				if let Some(default) = default {
+					collect_type_refs(default, refs);
+				}
+			}
+			GenericParamDefKind::Const { type_, .. } => collect_type_refs(type_, refs),
+		}
+	}
+	for predicate in &generics.where_predicates {
+		match predicate {
+			WherePredicate::BoundPredicate { type_, bounds, .. } => {
+				collect_type_refs(type_, refs);
+				collect_bounds_refs(bounds, refs);
+			}
+			WherePredicate::LifetimePredicate { .. } => {}
+			WherePredicate::EqPredicate { lhs, rhs } => {
+				collect_type_refs(lhs, refs);
+				collect_term_refs(rhs, refs);
+			}
+		}
+	}
+}
+
+fn collect_bounds_refs(bounds: &[GenericBound], refs: &mut Vec<Id>) {
+	for bound in bounds {
+		if let GenericBound::TraitBound { trait_, .. } = bound {
+			collect_path_refs(trait_, refs);
+		}
+	}
+}
+
+fn collect_path_refs(path: &Path, refs: &mut Vec<Id>) {
+	refs.push(path.id);
+	if let Some(args) = &path.args {
+		collect_args_refs(args, refs);
+	}
+}
+
+fn collect_args_refs(args: &GenericArgs, refs: &mut Vec<Id>) {
+	match args {
+		GenericArgs::AngleBracketed { args, constraints } => {
+			for arg in args {
+				if let GenericArg::Type(ty) = arg {
+					collect_type_refs(ty, refs);
+				}
+			}
+			for constraint in constraints {
+				match &constraint.binding {
+					AssocItemConstraintKind::Equality(term) => collect_term_refs(term, refs),
+					AssocItemConstraintKind::Constraint(bounds) => collect_bounds_refs(bounds, refs),
+				}
+			}
+		}
+		GenericArgs::Parenthesized { inputs, output } => {
+			for input in inputs {
+				collect_type_refs(input, refs);
+			}
+			if let Some(output) = output {
+				collect_type_refs(output, refs);
+			}
+		}
+		GenericArgs::ReturnTypeNotation => {}
+	}
+}
+
+fn collect_term_refs(term: &Term, refs: &mut Vec<Id>) {
+	if let Term::Type(ty) = term {
+		collect_type_refs(ty, refs);
+	}
+}
+
+fn collect_type_refs(ty: &Type, refs: &mut Vec<Id>) {
+	match ty {
+		Type::ResolvedPath(path) => collect_path_refs(path, refs),
+		Type::DynTrait(dyn_trait) => {
+			for poly in &dyn_trait.traits {
+				collect_path_refs(&poly.trait_, refs);
+			}
+		}
+		Type::FunctionPointer(pointer) => {
+			for (_, input) in &pointer.sig.inputs {
+				collect_type_refs(input, refs);
+			}
+			if let Some(output) = &pointer.sig.output {
+				collect_type_refs(output, refs);
+			}
+		}
+		Type::Tuple(types) => {
+			for ty in types {
+				collect_type_refs(ty, refs);
+			}
+		}
+		Type::Slice(ty) => collect_type_refs(ty, refs),
+		Type::Array { type_, .. }
+		| Type::Pat { type_, .. }
+		| Type::RawPointer { type_, .. }
+		| Type::BorrowedRef { type_, .. } => collect_type_refs(type_, refs),
+		Type::ImplTrait(bounds) => collect_bounds_refs(bounds, refs),
+		Type::QualifiedPath {
+			args,
+			self_type,
+			trait_,
+			..
+		} => {
+			collect_type_refs(self_type, refs);
+			if let Some(trait_) = trait_ {
+				collect_path_refs(trait_, refs);
+			}
+			if let Some(args) = args {
+				collect_args_refs(args, refs);
+			}
+		}
+		Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+	}
+}
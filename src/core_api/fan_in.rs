@@ -0,0 +1,37 @@
+//! Fan-in: how many items of a crate mention each item in their signatures.
+//!
+//! Types that many functions, fields, and bounds refer to tend to be the ones a newcomer should
+//! read first. Counting references per referencing item (not per mention) keeps a function
+//! that takes the same type twice from counting double.
+
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_types::{Crate, Id};
+
+use super::externs::collect_item_refs;
+use super::search::{SearchIndex, SearchItemKind};
+
+/// Count, for every item id, the indexed items whose signature, field type, bounds, or trait
+/// impls mention it.
+///
+/// Only items in `index` act as referrers, so private items count only when the index includes
+/// them. `use` declarations and self-references are ignored.
+pub(crate) fn fan_in_counts(crate_data: &Crate, index: &SearchIndex<'_>) -> HashMap<Id, usize> {
+	let mut counts = HashMap::new();
+	let mut seen = HashSet::new();
+	for entry in index.entries() {
+		if entry.kind == SearchItemKind::Use || !seen.insert(entry.item_id) {
+			continue;
+		}
+		let Some(item) = crate_data.index.get(&entry.item_id) else {
+			continue;
+		};
+		let mut refs = Vec::new();
+		collect_item_refs(crate_data, item, &mut refs);
+		let referenced: HashSet<Id> = refs.into_iter().filter(|id| *id != entry.item_id).collect();
+		for id in referenced {
+			*counts.entry(id).or_insert(0) += 1;
+		}
+	}
+	counts
+}
@@ -0,0 +1,137 @@
+//! Stable content hashes of a crate's public API.
+//!
+//! Every public item contributes one line (kind, path below the crate root, and normalized
+//! signature). Lines are sorted before hashing, so the fingerprint ignores declaration order,
+//! formatting, and doc comments; it only changes when the API surface does. The hash is 64-bit
+//! FNV-1a, which is stable across platforms and toolchains.
+
+use std::collections::BTreeMap;
+
+use rustdoc_types::{Crate, Id, ItemEnum};
+
+use super::pattern::normalize_signature;
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Fingerprint of the items declared directly in one module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleFingerprint {
+	/// Module path below the crate root (empty for the root module).
+	pub module: String,
+	/// Number of items hashed.
+	pub items: usize,
+	/// Hex-encoded hash.
+	pub hash: String,
+}
+
+/// Fingerprint of a crate's public API.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ApiFingerprint {
+	/// Crate name.
+	pub name: String,
+	/// Hex-encoded hash over every public item.
+	pub hash: String,
+	/// Number of items hashed.
+	pub items: usize,
+	/// Per-module hashes, sorted by module path.
+	pub modules: Vec<ModuleFingerprint>,
+}
+
+/// Compute the public API fingerprint of `crate_data`.
+pub fn fingerprint(crate_data: &Crate) -> ApiFingerprint {
+	let index = SearchIndex::build(crate_data, false, None);
+	let mut modules: BTreeMap<String, Vec<String>> = BTreeMap::new();
+	for entry in index.entries() {
+		if matches!(entry.kind, SearchItemKind::Use | SearchItemKind::Crate)
+			|| from_blanket_impl(crate_data, &entry.ancestors)
+		{
+			continue;
+		}
+		modules
+			.entry(parent_module(entry))
+			.or_default()
+			.push(api_line(entry));
+	}
+
+	let mut all_lines = Vec::new();
+	let modules = modules
+		.into_iter()
+		.map(|(module, mut lines)| {
+			lines.sort();
+			lines.dedup();
+			all_lines.extend(lines.iter().cloned());
+			ModuleFingerprint {
+				module,
+				items: lines.len(),
+				hash: hash_lines(&lines),
+			}
+		})
+		.collect();
+	all_lines.sort();
+
+	ApiFingerprint {
+		name: crate_data
+			.index
+			.get(&crate_data.root)
+			.and_then(|root| root.name.clone())
+			.unwrap_or_else(|| "crate".to_string()),
+		hash: hash_lines(&all_lines),
+		items: all_lines.len(),
+		modules,
+	}
+}
+
+/// The line an entry contributes to the fingerprint.
+fn api_line(entry: &SearchResult) -> String {
+	let signature = entry
+		.signature
+		.as_deref()
+		.map(normalize_signature)
+		.unwrap_or_default();
+	format!(
+		"{}|{}|{signature}",
+		entry.kind.label(),
+		relative_path(&entry.path[..])
+	)
+}
+
+/// Path of the closest enclosing module, below the crate root.
+fn parent_module(entry: &SearchResult) -> String {
+	let parents = &entry.path[..entry.path.len().saturating_sub(1)];
+	let end = parents
+		.iter()
+		.rposition(|segment| matches!(segment.kind, SearchItemKind::Module | SearchItemKind::Crate))
+		.map_or(0, |idx| idx + 1);
+	relative_path(&parents[..end])
+}
+
+/// Whether an item was reached through a blanket impl such as `impl<T> From<T> for T`. Every
+/// type picks those up, so they would otherwise show as new API on each added type.
+pub(super) fn from_blanket_impl(crate_data: &Crate, ancestors: &[Id]) -> bool {
+	ancestors.iter().any(|id| {
+		matches!(
+			crate_data.index.get(id).map(|item| &item.inner),
+			Some(ItemEnum::Impl(impl_)) if impl_.blanket_impl.is_some()
+		)
+	})
+}
+
+pub(super) fn relative_path(segments: &[super::search::SearchPathSegment]) -> String {
+	segments
+		.iter()
+		.skip(1)
+		.map(|segment| segment.name.as_str())
+		.collect::<Vec<_>>()
+		.join("::")
+}
+
+/// 64-bit FNV-1a over the lines, each terminated by a newline.
+pub(super) fn hash_lines(lines: &[String]) -> String {
+	const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+	const PRIME: u64 = 0x0100_0000_01b3;
+	let mut hash = OFFSET;
+	for byte in lines.iter().flat_map(|line| line.bytes().chain([b'\n'])) {
+		hash ^= u64::from(byte);
+		hash = hash.wrapping_mul(PRIME);
+	}
+	format!("{hash:016x}")
+}
@@ -0,0 +1,82 @@
+//! Reverse impl lookups: the local implementors of a trait, or the traits a type implements.
+
+use rustdoc_types::Crate;
+
+use super::search::{ImplIndex, ImplRecord};
+
+/// What the query named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImplSubject {
+	/// A trait; the report lists implementing types.
+	Trait,
+	/// A type; the report lists implemented traits.
+	Type,
+}
+
+/// One trait implementation in an [`ImplsReport`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ImplMatch {
+	/// Canonical trait path.
+	pub trait_path: String,
+	/// Canonical path (or rendered form) of the implementing type.
+	pub type_path: String,
+	/// Auto-trait impl synthesized by rustdoc.
+	pub synthetic: bool,
+	/// Instance of a blanket impl.
+	pub blanket: bool,
+}
+
+/// Result of an impl lookup in one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ImplsReport {
+	/// Crate name.
+	pub crate_name: String,
+	/// The query as given.
+	pub query: String,
+	/// Whether the query matched a trait or a type.
+	pub subject: ImplSubject,
+	/// Matching impls, sorted by trait then type.
+	pub impls: Vec<ImplMatch>,
+}
+
+/// Look `query` up as a trait first, then as a type.
+///
+/// Synthetic auto-trait impls and blanket impl instances are only listed with `auto_impls`.
+/// Returns `None` when the query matches neither.
+pub fn find_impls(crate_data: &Crate, query: &str, auto_impls: bool) -> Option<ImplsReport> {
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	let index = ImplIndex::build(crate_data);
+	let keep = |record: &&ImplRecord| auto_impls || !(record.synthetic || record.blanket);
+
+	let implementors: Vec<&ImplRecord> =
+		index.implementors(query).into_iter().filter(keep).collect();
+	let (subject, records) = if !implementors.is_empty() {
+		(ImplSubject::Trait, implementors)
+	} else {
+		let traits: Vec<&ImplRecord> = index.traits_of(query).into_iter().filter(keep).collect();
+		if traits.is_empty() {
+			return None;
+		}
+		(ImplSubject::Type, traits)
+	};
+
+	Some(ImplsReport {
+		crate_name,
+		query: query.to_string(),
+		subject,
+		impls: records
+			.into_iter()
+			.map(|record| ImplMatch {
+				trait_path: record.trait_path.clone(),
+				type_path: record.type_path.clone(),
+				synthetic: record.synthetic,
+				blanket: record.blanket,
+			})
+			.collect(),
+	})
+}
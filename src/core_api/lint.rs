@@ -0,0 +1,254 @@
+//! Structural lints over a crate's export graph.
+//!
+//! The checks only need the rustdoc index (built with private items), so they run without
+//! compiling anything beyond the documentation JSON.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+use super::search::SourceLocation;
+
+/// Category of a structural export problem.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportLintKind {
+	/// A `pub use` whose target cannot be resolved.
+	DanglingReexport,
+	/// A `pub use` pointing at an item that is not public.
+	NonPublicReexport,
+	/// A `pub use module::*` whose module exposes no public items.
+	EmptyGlobReexport,
+	/// A module declared without any items.
+	EmptyModule,
+	/// A `pub` item that cannot be named from the crate root.
+	UnreachablePublic,
+}
+
+impl ExportLintKind {
+	/// Short label used in CLI output.
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::DanglingReexport => "dangling-reexport",
+			Self::NonPublicReexport => "non-public-reexport",
+			Self::EmptyGlobReexport => "empty-glob-reexport",
+			Self::EmptyModule => "empty-module",
+			Self::UnreachablePublic => "unreachable-pub",
+		}
+	}
+}
+
+/// A single finding from [`lint_exports`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExportLint {
+	/// Kind of problem found.
+	pub kind: ExportLintKind,
+	/// Path of the offending item (the `use` statement for re-export findings).
+	pub path: String,
+	/// Human-readable explanation.
+	pub message: String,
+	/// Where the offending item is declared, if known.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+}
+
+/// Run all export lints against a crate documented with private items.
+///
+/// `package_root` is used to shorten source paths in the reported locations.
+pub fn lint_exports(crate_data: &Crate, package_root: Option<&Path>) -> Vec<ExportLint> {
+	let paths = module_level_paths(crate_data);
+	let reachable = reachable_items(crate_data);
+	let path_of = |id: &Id| -> String {
+		paths
+			.get(id)
+			.cloned()
+			.unwrap_or_else(|| format!("<unknown {}>", id.0))
+	};
+	let location = |item: &Item| source_location(item, package_root);
+
+	let mut lints = Vec::new();
+	for (id, path) in &paths {
+		let Some(item) = crate_data.index.get(id) else {
+			continue;
+		};
+		match &item.inner {
+			ItemEnum::Use(import) if matches!(item.visibility, Visibility::Public) => {
+				let target = import.id.as_ref();
+				let resolved = target.and_then(|target| crate_data.index.get(target));
+				let external = target.is_some_and(|target| crate_data.paths.contains_key(target));
+				match resolved {
+					None if !external => lints.push(ExportLint {
+						kind: ExportLintKind::DanglingReexport,
+						path: path.clone(),
+						message: format!("`pub use {}` does not resolve to an item", import.source),
+						source: location(item),
+					}),
+					None => {}
+					Some(target_item) if import.is_glob => {
+						if let ItemEnum::Module(module) = &target_item.inner
+							&& !module.items.iter().any(|child| is_public(crate_data, child))
+						{
+							lints.push(ExportLint {
+								kind: ExportLintKind::EmptyGlobReexport,
+								path: path.clone(),
+								message: format!(
+									"`pub use {}::*` re-exports nothing: `{}` has no public items",
+									import.source,
+									path_of(&target_item.id)
+								),
+								source: location(item),
+							});
+						}
+					}
+					Some(target_item) if !matches!(target_item.visibility, Visibility::Public) => {
+						lints.push(ExportLint {
+							kind: ExportLintKind::NonPublicReexport,
+							path: path.clone(),
+							message: format!(
+								"`pub use {}` re-exports non-public item `{}`",
+								import.source,
+								path_of(&target_item.id)
+							),
+							source: location(item),
+						});
+					}
+					Some(_) => {}
+				}
+			}
+			ItemEnum::Module(module)
+				if *id != crate_data.root && module.items.is_empty() && !module.is_stripped =>
+			{
+				lints.push(ExportLint {
+					kind: ExportLintKind::EmptyModule,
+					path: path.clone(),
+					message: "module is declared but contains no items".to_string(),
+					source: location(item),
+				});
+			}
+			ItemEnum::Use(_) | ItemEnum::Impl(_) | ItemEnum::Macro(_) => {}
+			_ if matches!(item.visibility, Visibility::Public) && !reachable.contains(id) => {
+				lints.push(ExportLint {
+					kind: ExportLintKind::UnreachablePublic,
+					path: path.clone(),
+					message: "`pub` item is not reachable from the crate root".to_string(),
+					source: location(item),
+				});
+			}
+			_ => {}
+		}
+	}
+
+	lints.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+	lints
+}
+
+/// Whether `id` names a public item in this crate.
+fn is_public(crate_data: &Crate, id: &Id) -> bool {
+	crate_data
+		.index
+		.get(id)
+		.is_some_and(|item| matches!(item.visibility, Visibility::Public))
+}
+
+/// Declared path of every item listed in a module, regardless of visibility.
+fn module_level_paths(crate_data: &Crate) -> HashMap<Id, String> {
+	let mut paths = HashMap::new();
+	let Some(root) = crate_data.index.get(&crate_data.root) else {
+		return paths;
+	};
+	let root_name = root.name.clone().unwrap_or_else(|| "crate".to_string());
+	paths.insert(crate_data.root, root_name.clone());
+
+	let mut stack = vec![(crate_data.root, root_name)];
+	while let Some((module_id, module_path)) = stack.pop() {
+		let Some(ItemEnum::Module(module)) = crate_data.index.get(&module_id).map(|item| &item.inner)
+		else {
+			continue;
+		};
+		for child_id in &module.items {
+			let Some(child) = crate_data.index.get(child_id) else {
+				continue;
+			};
+			// Items listed in several modules (inlined re-exports) keep their first path.
+			if paths.contains_key(child_id) {
+				continue;
+			}
+			let name = match &child.inner {
+				ItemEnum::Use(import) => import.name.clone(),
+				_ => child.name.clone().unwrap_or_else(|| "_".to_string()),
+			};
+			let child_path = format!("{module_path}::{name}");
+			paths.insert(*child_id, child_path.clone());
+			if matches!(child.inner, ItemEnum::Module(_)) {
+				stack.push((*child_id, child_path));
+			}
+		}
+	}
+	paths
+}
+
+/// Items nameable from the crate root through public modules and `pub use` re-exports.
+fn reachable_items(crate_data: &Crate) -> HashSet<Id> {
+	let mut reachable = HashSet::from([crate_data.root]);
+	let mut expanded = HashSet::new();
+	let mut stack = vec![crate_data.root];
+
+	while let Some(module_id) = stack.pop() {
+		if !expanded.insert(module_id) {
+			continue;
+		}
+		let Some(ItemEnum::Module(module)) = crate_data.index.get(&module_id).map(|item| &item.inner)
+		else {
+			continue;
+		};
+		for child_id in &module.items {
+			let Some(child) = crate_data.index.get(child_id) else {
+				continue;
+			};
+			if !matches!(child.visibility, Visibility::Public) {
+				continue;
+			}
+			match &child.inner {
+				ItemEnum::Use(import) => {
+					let Some(target_id) = import.id else {
+						continue;
+					};
+					let is_module = matches!(
+						crate_data.index.get(&target_id).map(|item| &item.inner),
+						Some(ItemEnum::Module(_))
+					);
+					// A glob exposes the module's public items, not the module itself.
+					if !import.is_glob {
+						reachable.insert(target_id);
+					}
+					if is_module {
+						stack.push(target_id);
+					}
+				}
+				ItemEnum::Module(_) => {
+					reachable.insert(*child_id);
+					stack.push(*child_id);
+				}
+				_ => {
+					reachable.insert(*child_id);
+				}
+			}
+		}
+	}
+	reachable
+}
+
+fn source_location(item: &Item, package_root: Option<&Path>) -> Option<SourceLocation> {
+	let span = item.span.as_ref()?;
+	let path = package_root
+		.and_then(|root| span.filename.strip_prefix(root).ok())
+		.unwrap_or(&span.filename);
+	Some(SourceLocation {
+		path: path.to_string_lossy().into_owned(),
+		line: Some(span.begin.0),
+		column: None,
+	})
+}
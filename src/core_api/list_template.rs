@@ -0,0 +1,189 @@
+//! User-defined line layouts for textual `list` output.
+//!
+//! A template is literal text with `{placeholder}` fields, e.g. `{kind}\t{path}\t{file}:{line}`.
+//! `{{` and `}}` produce literal braces, and `\t`, `\n`, and `\\` are unescaped so templates can
+//! be passed through shells without `$'...'` quoting.
+
+use std::str::FromStr;
+
+use super::search::ListItem;
+
+/// Placeholders accepted in list templates.
+pub const LIST_TEMPLATE_FIELDS: &[&str] =
+	&["kind", "path", "name", "crate", "file", "line", "column", "location", "fan_in"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+	Literal(String),
+	Field(Field),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+	Kind,
+	Path,
+	Name,
+	Crate,
+	File,
+	Line,
+	Column,
+	Location,
+	FanIn,
+}
+
+/// A parsed list template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListTemplate {
+	segments: Vec<Segment>,
+}
+
+impl ListTemplate {
+	/// Render one list entry. Missing source information renders as `-`.
+	pub fn render(&self, item: &ListItem) -> String {
+		let mut line = String::new();
+		for segment in &self.segments {
+			match segment {
+				Segment::Literal(text) => line.push_str(text),
+				Segment::Field(field) => line.push_str(&field.value(item)),
+			}
+		}
+		line
+	}
+}
+
+impl Field {
+	fn parse(name: &str) -> Option<Self> {
+		Some(match name {
+			"kind" => Self::Kind,
+			"path" => Self::Path,
+			"name" => Self::Name,
+			"crate" => Self::Crate,
+			"file" => Self::File,
+			"line" => Self::Line,
+			"column" => Self::Column,
+			"location" => Self::Location,
+			"fan_in" => Self::FanIn,
+			_ => return None,
+		})
+	}
+
+	fn value(self, item: &ListItem) -> String {
+		let missing = || "-".to_string();
+		let source = item.source.as_ref();
+		match self {
+			Self::Kind => item.kind.label().to_string(),
+			Self::Path => item.path.clone(),
+			Self::Name => item.path.rsplit("::").next().unwrap_or_default().to_string(),
+			Self::Crate => item.path.split("::").next().unwrap_or_default().to_string(),
+			Self::File => source.map_or_else(missing, |source| source.path.clone()),
+			Self::Line => source
+				.and_then(|source| source.line)
+				.map_or_else(missing, |line| line.to_string()),
+			Self::Column => source
+				.and_then(|source| source.column)
+				.map_or_else(missing, |column| column.to_string()),
+			Self::Location => source.map_or_else(missing, |source| source.to_compact_string()),
+			Self::FanIn => item.fan_in.map_or_else(missing, |count| count.to_string()),
+		}
+	}
+}
+
+impl FromStr for ListTemplate {
+	type Err = String;
+
+	fn from_str(template: &str) -> Result<Self, Self::Err> {
+		let mut segments = Vec::new();
+		let mut literal = String::new();
+		let mut chars = template.chars().peekable();
+		while let Some(ch) = chars.next() {
+			match ch {
+				'\\' => match chars.peek() {
+					Some('t') => {
+						chars.next();
+						literal.push('\t');
+					}
+					Some('n') => {
+						chars.next();
+						literal.push('\n');
+					}
+					Some('\\') => {
+						chars.next();
+						literal.push('\\');
+					}
+					_ => literal.push('\\'),
+				},
+				'{' if chars.peek() == Some(&'{') => {
+					chars.next();
+					literal.push('{');
+				}
+				'}' if chars.peek() == Some(&'}') => {
+					chars.next();
+					literal.push('}');
+				}
+				'{' => {
+					let mut name = String::new();
+					loop {
+						match chars.next() {
+							Some('}') => break,
+							Some(ch) => name.push(ch),
+							None => return Err(format!("unclosed placeholder '{{{name}' in template")),
+						}
+					}
+					let field = Field::parse(name.trim()).ok_or_else(|| {
+						format!(
+							"unknown placeholder '{{{name}}}' (expected one of: {})",
+							LIST_TEMPLATE_FIELDS.join(", ")
+						)
+					})?;
+					if !literal.is_empty() {
+						segments.push(Segment::Literal(std::mem::take(&mut literal)));
+					}
+					segments.push(Segment::Field(field));
+				}
+				'}' => return Err("unmatched '}' in template (use '}}' for a literal brace)".to_string()),
+				other => literal.push(other),
+			}
+		}
+		if !literal.is_empty() {
+			segments.push(Segment::Literal(literal));
+		}
+		Ok(Self { segments })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core_api::search::{SearchItemKind, SourceLocation};
+
+	#[test]
+	fn renders_placeholders_escapes_and_missing_sources() {
+		let template: ListTemplate = r"{kind}\t{path}\t{file}:{line} {{{name}}}".parse().unwrap();
+		let located = ListItem {
+			kind: SearchItemKind::Struct,
+			path: "demo::io::Reader".to_string(),
+			source: Some(SourceLocation {
+				path: "demo/src/io.rs".to_string(),
+				line: Some(12),
+				column: None,
+			}),
+			fan_in: None,
+			deprecated: false,
+			deprecation_note: None,
+			cfg: Vec::new(),
+		};
+		assert_eq!(
+			template.render(&located),
+			"struct\tdemo::io::Reader\tdemo/src/io.rs:12 {Reader}"
+		);
+
+		let unlocated = ListItem {
+			source: None,
+			..located
+		};
+		assert_eq!(template.render(&unlocated), "struct\tdemo::io::Reader\t-:- {Reader}");
+
+		assert!("{kind".parse::<ListTemplate>().is_err());
+		assert!("{bogus}".parse::<ListTemplate>().unwrap_err().contains("expected one of"));
+	}
+}
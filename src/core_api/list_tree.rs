@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 
+use super::error::TargetFailure;
 use super::search::{ListItem, SearchItemKind};
 
 /// A hierarchical tree node representing a crate item and its children.
@@ -15,6 +16,9 @@ pub struct ListTreeNode {
 	/// Source location for the item if available (format: "path/to/file.rs:line" or "path/to/file.rs:line:col").
 	#[serde(skip_serializing_if = "Option::is_none", rename = "src")]
 	pub source: Option<String>,
+	/// Package version, set on package grouping nodes.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub version: Option<String>,
 	/// Child items nested under this item.
 	#[serde(skip_serializing_if = "Vec::is_empty", default)]
 	pub children: Vec<ListTreeNode>,
@@ -27,11 +31,52 @@ impl ListTreeNode {
 			name,
 			kind,
 			source,
+			version: None,
 			children: Vec::new(),
 		}
 	}
 }
 
+/// Listings of every resolved package, plus the packages that failed to build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListOutput {
+	/// Listings of the packages that built, in resolution order.
+	pub packages: Vec<PackageListing>,
+	/// Packages skipped because they failed.
+	pub errors: Vec<TargetFailure>,
+}
+
+/// Items listed from a single resolved package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageListing {
+	/// Package name (falls back to the crate name when unknown).
+	pub name: String,
+	/// Package version reported by rustdoc, if any.
+	pub version: Option<String>,
+	/// Items listed from the package.
+	pub items: Vec<ListItem>,
+}
+
+/// Build list trees for several packages, nesting each package's crate tree under a package node.
+///
+/// A single package keeps the plain crate-rooted shape produced by [`build_list_tree`], so
+/// single-target output is unchanged.
+pub fn build_package_tree(packages: &[PackageListing]) -> Vec<ListTreeNode> {
+	if let [package] = packages {
+		return build_list_tree(&package.items);
+	}
+
+	packages
+		.iter()
+		.map(|package| {
+			let mut node = ListTreeNode::new(package.name.clone(), SearchItemKind::Package, None);
+			node.version = package.version.clone();
+			node.children = build_list_tree(&package.items);
+			node
+		})
+		.collect()
+}
+
 /// Convert a flat list of items into a hierarchical tree structure.
 ///
 /// Methods and associated types from trait implementations are excluded because their paths
@@ -154,3 +199,57 @@ pub fn build_list_tree(items: &[ListItem]) -> Vec<ListTreeNode> {
 
 	result
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn item(kind: SearchItemKind, path: &str) -> ListItem {
+		ListItem {
+			kind,
+			path: path.to_string(),
+			source: None,
+			fan_in: None,
+			deprecated: false,
+			deprecation_note: None,
+			cfg: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn multiple_packages_nest_under_package_nodes() {
+		let packages = vec![
+			PackageListing {
+				name: "core-pkg".to_string(),
+				version: Some("0.2.0".to_string()),
+				items: vec![item(SearchItemKind::Struct, "core_pkg::util::Thing")],
+			},
+			PackageListing {
+				name: "cli-pkg".to_string(),
+				version: None,
+				items: vec![item(SearchItemKind::Function, "cli_pkg::util::run")],
+			},
+		];
+
+		let tree = build_package_tree(&packages);
+		assert_eq!(tree.len(), 2);
+		assert_eq!(tree[0].name, "core-pkg");
+		assert_eq!(tree[0].kind, SearchItemKind::Package);
+		assert_eq!(tree[0].version.as_deref(), Some("0.2.0"));
+		assert_eq!(tree[0].children[0].name, "core_pkg");
+		assert_eq!(tree[1].children[0].children[0].name, "util");
+	}
+
+	#[test]
+	fn single_package_keeps_crate_roots() {
+		let packages = vec![PackageListing {
+			name: "solo".to_string(),
+			version: Some("1.0.0".to_string()),
+			items: vec![item(SearchItemKind::Struct, "solo::Thing")],
+		}];
+
+		let tree = build_package_tree(&packages);
+		assert_eq!(tree.len(), 1);
+		assert_eq!(tree[0].kind, SearchItemKind::Crate);
+	}
+}
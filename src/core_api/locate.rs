@@ -0,0 +1,107 @@
+//! Resolving item paths to `file:line:column` locations for editors.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use rustdoc_types::Crate;
+
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Where an item is declared on disk.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ItemLocation {
+	/// Canonical item path.
+	pub item: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Source file, absolute when the package root is known.
+	pub file: PathBuf,
+	/// One-indexed line.
+	pub line: usize,
+	/// One-indexed column.
+	pub column: usize,
+}
+
+impl ItemLocation {
+	/// The location in grep/quickfix form: `file:line:column: kind path`.
+	pub fn quickfix_line(&self) -> String {
+		format!(
+			"{}:{}:{}: {} {}",
+			self.file.display(),
+			self.line,
+			self.column,
+			self.kind.label(),
+			self.item
+		)
+	}
+}
+
+/// Locate the items of `crate_data` named by `query`.
+///
+/// `query` is resolved as in [`matching_entries`]. Relative span paths resolve against
+/// `source_root`.
+pub(crate) fn locate_items(
+	crate_data: &Crate,
+	index: &SearchIndex<'_>,
+	source_root: &Path,
+	query: &str,
+) -> Vec<ItemLocation> {
+	let matches = matching_entries(crate_data, index, query);
+
+	let mut seen = HashSet::new();
+	let mut locations = Vec::new();
+	for entry in matches {
+		if entry.kind == SearchItemKind::Use || !seen.insert(entry.item_id) {
+			continue;
+		}
+		let Some(span) = crate_data
+			.index
+			.get(&entry.item_id)
+			.and_then(|item| item.span.as_ref())
+		else {
+			continue;
+		};
+		locations.push(ItemLocation {
+			item: entry.path_string.clone(),
+			kind: entry.kind,
+			file: source_root.join(&span.filename),
+			line: span.begin.0,
+			column: span.begin.1,
+		});
+	}
+	locations
+}
+
+/// Index entries of `crate_data` named by `query`.
+///
+/// `query` is a path relative to the crate root, a full path including the crate name, or a
+/// trailing path suffix (`Deserialize`, `de::Deserialize`). Exact matches win over suffix
+/// matches.
+pub(crate) fn matching_entries<'i>(
+	crate_data: &Crate,
+	index: &'i SearchIndex<'_>,
+	query: &str,
+) -> Vec<&'i SearchResult> {
+	let query = query.trim().trim_start_matches("::");
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_default();
+	let full = format!("{crate_name}::{query}");
+	let suffix = format!("::{query}");
+
+	let exact: Vec<_> = index
+		.entries()
+		.iter()
+		.filter(|entry| entry.path_string == query || entry.path_string == full)
+		.collect();
+	if !exact.is_empty() {
+		return exact;
+	}
+	index
+		.entries()
+		.iter()
+		.filter(|entry| entry.path_string.ends_with(&suffix))
+		.collect()
+}
@@ -0,0 +1,228 @@
+//! Pager-friendly manual pages for single items.
+//!
+//! A page gathers what is needed to use one item without reading its module: the signature and
+//! its `where` clauses, the docs, the item's own members (fields, variants, inherent methods,
+//! trait items), the trait impls it takes part in, and related items to look at next.
+//! [`ManPage::render`] lays this out like `man`: uppercase section headings over indented bodies,
+//! with the docs wrapped to the page width.
+
+use std::collections::{BTreeSet, HashSet};
+
+use rustdoc_types::{Crate, Generics, Item, ItemEnum};
+use serde::{Deserialize, Serialize};
+
+use super::externs::collect_item_refs;
+use super::locate::matching_entries;
+use super::search::{ImplIndex, SearchIndex, SearchItemKind, SearchResult, SourceLocation};
+use crate::render::DocOptions;
+use crate::render::syntax::generics::render_where_predicate;
+
+/// Indent of section bodies.
+const INDENT: &str = "    ";
+
+/// Everything shown on one item's manual page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManPage {
+	/// Canonical path, starting with the crate name.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Signature without its `where` clause.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+	/// Predicates of the `where` clause, one per entry.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub where_clauses: Vec<String>,
+	/// Documentation comment, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub docs: Option<String>,
+	/// Deprecation note, or an empty string for a bare `#[deprecated]`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<String>,
+	/// Signatures of the item's fields, variants, inherent methods, or trait items.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub members: Vec<String>,
+	/// Trait impls the item takes part in, as `impl Trait for Type`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub impls: Vec<String>,
+	/// Related items: the enclosing module or type and the local items the signature mentions.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub see_also: Vec<String>,
+	/// Where the item is declared.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+}
+
+impl ManPage {
+	/// Lay the page out as plain text `width` columns wide.
+	///
+	/// `docs` shapes the description; unless it sets its own wrap width, prose is wrapped to fit
+	/// the page.
+	pub fn render(&self, width: usize, docs: &DocOptions) -> String {
+		let mut out = String::new();
+		section(&mut out, "NAME", [format!("{} - {}", self.path, self.kind.label())]);
+		section(&mut out, "SYNOPSIS", self.signature.clone());
+		section(&mut out, "WHERE", self.where_clauses.iter().map(|clause| format!("{clause},")));
+		if let Some(note) = &self.deprecated {
+			let note = if note.is_empty() { "Deprecated." } else { note.as_str() };
+			section(&mut out, "DEPRECATED", [note.to_string()]);
+		}
+		let options = DocOptions {
+			wrap: docs.wrap.or(Some(width.saturating_sub(INDENT.len()).max(20))),
+			..*docs
+		};
+		let description = self.docs.as_deref().and_then(|text| options.apply(text));
+		section(&mut out, "DESCRIPTION", description.iter().flat_map(|text| text.lines()));
+		let heading = match self.kind {
+			SearchItemKind::Enum => "VARIANTS",
+			SearchItemKind::Trait => "REQUIRED AND PROVIDED ITEMS",
+			SearchItemKind::Module | SearchItemKind::Crate => "ITEMS",
+			_ => "MEMBERS",
+		};
+		section(&mut out, heading, &self.members);
+		section(&mut out, "IMPLEMENTATIONS", &self.impls);
+		section(&mut out, "SEE ALSO", (!self.see_also.is_empty()).then(|| self.see_also.join(", ")));
+		section(&mut out, "SOURCE", self.source.as_ref().map(SourceLocation::to_compact_string));
+		out
+	}
+}
+
+/// Append a section unless `lines` is empty.
+fn section<I>(out: &mut String, heading: &str, lines: I)
+where
+	I: IntoIterator,
+	I::Item: AsRef<str>,
+{
+	let mut lines = lines.into_iter().peekable();
+	if lines.peek().is_none() {
+		return;
+	}
+	if !out.is_empty() {
+		out.push('\n');
+	}
+	out.push_str(heading);
+	out.push('\n');
+	for line in lines {
+		let line = line.as_ref();
+		if !line.is_empty() {
+			out.push_str(INDENT);
+			out.push_str(line);
+		}
+		out.push('\n');
+	}
+}
+
+/// Build the pages of the items of `crate_data` named by `query` (see [`matching_entries`]).
+pub(crate) fn man_pages(crate_data: &Crate, index: &SearchIndex<'_>, query: &str) -> Vec<ManPage> {
+	let impls = ImplIndex::build(crate_data);
+	let mut seen = HashSet::new();
+	matching_entries(crate_data, index, query)
+		.into_iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use && seen.insert(entry.item_id))
+		.map(|entry| man_page(crate_data, index, &impls, entry))
+		.collect()
+}
+
+fn man_page(
+	crate_data: &Crate,
+	index: &SearchIndex<'_>,
+	impls: &ImplIndex,
+	entry: &SearchResult,
+) -> ManPage {
+	let item = crate_data.index.get(&entry.item_id);
+	let generics = item.and_then(generics_of);
+	let where_clauses: Vec<String> = generics
+		.map(|generics| generics.where_predicates.iter().filter_map(render_where_predicate).collect())
+		.unwrap_or_default();
+	let signature = entry.signature.as_deref().map(|signature| {
+		// Signatures end with their `where` clause, which gets its own section.
+		let clause = format!(" where {}", where_clauses.join(", "));
+		signature
+			.strip_suffix(clause.as_str())
+			.filter(|_| !where_clauses.is_empty())
+			.unwrap_or(signature)
+			.to_string()
+	});
+
+	let prefix = format!("{}::", entry.path_string);
+	let members = index
+		.entries()
+		.iter()
+		.filter(|child| {
+			child
+				.path_string
+				.strip_prefix(&prefix)
+				.is_some_and(|rest| !rest.contains("::"))
+		})
+		.filter(|child| child.kind != SearchItemKind::Use)
+		.map(|child| match &child.signature {
+			Some(signature) => signature.clone(),
+			None => format!("{} {}", child.kind.label(), child.raw_name),
+		})
+		.collect();
+
+	let records = match entry.kind {
+		SearchItemKind::Trait => impls.implementors(&entry.path_string),
+		_ => impls.traits_of(&entry.path_string),
+	};
+	let impls = records
+		.into_iter()
+		.filter(|record| !record.synthetic && !record.blanket)
+		.map(|record| format!("impl {} for {}", record.trait_path, record.type_path))
+		.collect();
+
+	ManPage {
+		path: entry.path_string.clone(),
+		kind: entry.kind,
+		signature,
+		where_clauses,
+		docs: entry.docs.clone(),
+		deprecated: entry
+			.deprecated
+			.then(|| entry.deprecation_note.clone().unwrap_or_default()),
+		members,
+		impls,
+		see_also: item.map(|item| see_also(crate_data, entry, item)).unwrap_or_default(),
+		source: entry.source.clone(),
+	}
+}
+
+/// The enclosing module or type, then the local items `item`'s signature mentions, sorted.
+fn see_also(crate_data: &Crate, entry: &SearchResult, item: &Item) -> Vec<String> {
+	let mut related = Vec::new();
+	if entry.path.len() > 1 {
+		related.push(
+			entry.path[..entry.path.len() - 1]
+				.iter()
+				.map(|segment| segment.name.as_str())
+				.collect::<Vec<_>>()
+				.join("::"),
+		);
+	}
+
+	let mut refs = Vec::new();
+	collect_item_refs(crate_data, item, &mut refs);
+	let mentioned: BTreeSet<String> = refs
+		.into_iter()
+		.filter(|id| *id != entry.item_id)
+		.filter_map(|id| crate_data.paths.get(&id))
+		.filter(|summary| summary.crate_id == 0)
+		.map(|summary| summary.path.join("::"))
+		.filter(|path| !related.contains(path))
+		.collect();
+	related.extend(mentioned);
+	related
+}
+
+fn generics_of(item: &Item) -> Option<&Generics> {
+	match &item.inner {
+		ItemEnum::Function(function) => Some(&function.generics),
+		ItemEnum::Struct(struct_) => Some(&struct_.generics),
+		ItemEnum::Enum(enum_) => Some(&enum_.generics),
+		ItemEnum::Union(union_) => Some(&union_.generics),
+		ItemEnum::Trait(trait_) => Some(&trait_.generics),
+		ItemEnum::TraitAlias(alias) => Some(&alias.generics),
+		ItemEnum::TypeAlias(alias) => Some(&alias.generics),
+		_ => None,
+	}
+}
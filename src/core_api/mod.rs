@@ -4,29 +4,125 @@
 //! crate documentation generation, and rendering. It is designed to be UI-agnostic and
 //! can be used by any frontend (CLI, GUI, language server, etc.).
 
+/// Checked-in baselines of a crate's public items.
+pub mod baseline;
+/// Per-module Markdown bundles of docs, included files, and skeletons.
+pub mod bundles;
+/// Size-bounded chunks of rendered output for retrieval pipelines.
+pub mod chunks;
+/// Indexed items missing from the rendered skeleton.
+pub mod coverage;
+/// Structured API diffs between two versions of a crate.
+pub mod diff;
+/// Doc-comment coverage of the public API, per module.
+pub mod doc_coverage;
+/// Dyn-compatibility of traits and their `dyn`/`impl` parameter usage.
+pub mod dyn_compat;
 /// Error helpers for the core API.
 pub mod error;
+/// Item-level records for embedding and search pipelines.
+pub mod export;
+/// Stable content hashes of a crate's public API.
+pub mod fingerprint;
+/// Implementors of a trait and traits implemented by a type.
+pub mod impls;
+/// External crates exposed through the public API.
+pub mod externs;
+/// How many items reference each item, for ranking listings.
+mod fan_in;
+/// User-defined line layouts for textual list output.
+pub mod list_template;
 /// Hierarchical tree structure for organizing list output.
 pub mod list_tree;
+/// Structural lints over re-exports, modules, and reachability.
+pub mod lint;
+/// Item paths resolved to source locations for editors.
+pub mod locate;
+/// Plain-text manual pages for single items.
+pub mod man;
 /// Pattern utilities for search query handling.
 pub mod pattern;
+/// Headers recording the ripdoc version, toolchain, and command behind rendered output.
+pub mod provenance;
+/// Render selections saved to disk and re-applied later.
+pub mod saved_selection;
+/// Throwaway cargo projects for trying out a crate.
+pub mod sandbox;
 /// Search and indexing utilities.
 pub mod search;
+/// Splitting rendered output into one file per top-level module.
+pub mod split;
+/// API-surface metrics, optionally broken down by cargo feature.
+pub mod stats;
+/// One-screen crate summaries.
+pub mod summary;
+/// `ctags` and `etags` files for editors, built from list entries.
+pub mod tags;
+/// Supertrait and implementor graphs of a crate's traits.
+pub mod trait_graph;
+/// Walking a crate's item tree with user callbacks.
+pub mod visit;
 use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 
 use rustdoc_types::Crate;
 
-pub use self::error::Result;
-pub use self::list_tree::{ListTreeNode, build_list_tree};
+pub use self::baseline::{
+	ApiBaseline, BaselineItem, BaselineReport, CrateBaseline, DEFAULT_BASELINE_FILE, crate_baseline,
+};
+pub use self::bundles::{IncludedDoc, ModuleBundle};
+pub use self::chunks::Chunk;
+pub use self::coverage::{MissingItem, MissingReason, RenderCoverage, render_coverage};
+pub use self::diff::{ApiChange, ApiChangeKind, ApiDiff, SemverImpact, diff_crates};
+pub use self::doc_coverage::{DocCoverage, ModuleDocCoverage, UndocumentedItem, doc_coverage};
+pub use self::dyn_compat::{DynCompatReport, DynCompatTrait, GenericMethod, dyn_compat};
+pub use self::error::{Result, TargetFailure};
+pub use self::export::{ExportedItem, ItemSpan, export_items};
+pub use self::externs::{ExternCrateUsage, ExternLeak, extern_usage};
+pub use self::fingerprint::{ApiFingerprint, ModuleFingerprint, fingerprint};
+pub use self::impls::{ImplMatch, ImplSubject, ImplsReport, find_impls};
+pub use self::lint::{ExportLint, ExportLintKind, lint_exports};
+pub use self::locate::ItemLocation;
+pub use self::man::ManPage;
+pub use self::provenance::Provenance;
+pub use self::list_template::{LIST_TEMPLATE_FIELDS, ListTemplate};
+pub use self::list_tree::{
+	ListOutput, ListTreeNode, PackageListing, build_list_tree, build_package_tree,
+};
+pub use self::sandbox::Sandbox;
+pub use self::saved_selection::{SavedSelection, SelectedItem};
 pub use self::search::{
-	ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchResponse, SourceLocation,
+	DeprecationFilter, ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchResponse,
+	SourceLocation,
+};
+pub use self::split::{SPLIT_INDEX_FILE, SplitFile, render_split_index};
+pub use self::stats::{
+	CrateStats, FeatureBreakdown, FeatureItemCount, KindCount, ModuleSize, crate_stats,
+};
+pub use self::summary::{CrateSummary, KeyItem, ModuleCount, summarize};
+pub use self::tags::{render_ctags, render_etags};
+pub use self::trait_graph::{TraitGraph, TraitNode, render_dot, trait_graph};
+pub use self::visit::{ItemVisitor, VisitContext, VisitFlow, VisitOptions, walk_crate};
+use self::error::RipdocError;
+use self::search::{SearchIndex, SearchResult, build_render_selection};
+use super::cargo_utils::{
+	CacheEntry, CacheEntryKind, CacheKey, PackageLocation, ResolveContext, ResolvedTarget,
+	Verbosity, cache_entries, load_rendered, read_cache_entry, resolve_dependency_targets,
+	resolve_docs_rs_target, resolve_target, resolve_workspace_targets, save_rendered,
 };
-use self::search::{SearchIndex, build_render_selection};
-use super::cargo_utils::resolve_target;
+use super::render::utils::{path_excluded, source_label};
 /// Target parsing helpers exposed through cargo_utils.
 pub use super::cargo_utils::target;
-pub use super::render::{RenderFormat, Renderer};
+pub use super::render::{
+	DeriveStyle, DocMode, DocOptions, FenceLanguage, JsonDocument, JsonItem, MarkdownOptions,
+	RenderFormat, Renderer, VisibilityLevel,
+};
 
 /// Ripdoc generates a skeletonized version of a Rust crate in a single page.
 /// It produces syntactically valid Rust code with all implementations omitted.
@@ -49,11 +145,87 @@ pub struct Ripdoc {
 	/// Whether to inject source filename labels.
 	render_source_labels: bool,
 
-	/// Whether to suppress output during processing.
-	silent: bool,
+	/// How much cargo/rustdoc output to mirror during processing.
+	verbosity: Verbosity,
+
+	/// Whether to collapse enum variant fields in rendered output.
+	collapse_variants: bool,
+
+	/// How impls of commonly derived traits are shown.
+	derive_style: DeriveStyle,
+
+	/// Whether to elide redundant lifetimes in rendered signatures.
+	simplify_lifetimes: bool,
+
+	/// Whether doc-comment code examples are repeated as sections after their items.
+	doc_examples: bool,
+
+	/// How much of each doc comment is rendered.
+	doc_options: DocOptions,
+
+	/// Code-fence options for Markdown output.
+	markdown_options: MarkdownOptions,
+
+	/// Minimum visibility of rendered items when private items are included.
+	min_visibility: Option<VisibilityLevel>,
+
+	/// Whether only `pub` items reachable from the crate root are rendered.
+	strict_public: bool,
+
+	/// Whether modules are rendered as one-line inventories instead of their items.
+	module_summary: bool,
+
+	/// Path patterns left out of rendered, listed, and searched items.
+	exclude: Vec<String>,
+
+	/// Longest source span, in lines, rendered in full in implementation mode.
+	max_impl_lines: Option<usize>,
+
+	/// Whether cfg-gated items show their `#[cfg(...)]` attributes.
+	cfg_annotations: bool,
+
+	/// Whether items marked `#[doc(hidden)]` are rendered and listed.
+	doc_hidden: bool,
 
 	/// Cache configuration for rustdoc JSON output.
 	cache_config: super::cargo_utils::CacheConfig,
+
+	/// Directory relative targets and named crates resolve against, instead of the current one.
+	working_dir: Option<PathBuf>,
+
+	/// Directory aliases are read from, instead of the default config directory.
+	config_dir: Option<PathBuf>,
+
+	/// Directory the skelebuild state is kept in, instead of the default state directory.
+	state_dir: Option<PathBuf>,
+
+	/// Whether targets expand to every package of their enclosing workspace.
+	workspace: bool,
+
+	/// Whether named crates are read from docs.rs rustdoc JSON instead of built locally.
+	docs_rs: bool,
+
+	/// How many levels of manifest dependencies are resolved alongside each target.
+	dependency_depth: usize,
+
+	/// Whether listings are ranked by how many items reference each entry.
+	rank_by_fan_in: bool,
+
+	/// Which listing entries to keep based on their deprecation status.
+	list_deprecation: DeprecationFilter,
+}
+
+/// Whether items of `kind` hold other items rather than a body of their own.
+fn is_container(kind: SearchItemKind) -> bool {
+	matches!(
+		kind,
+		SearchItemKind::Module
+			| SearchItemKind::Crate
+			| SearchItemKind::Struct
+			| SearchItemKind::Enum
+			| SearchItemKind::Union
+			| SearchItemKind::Trait
+	)
 }
 
 /// Check if the rendered output is essentially empty (just an empty module declaration).
@@ -69,6 +241,181 @@ fn is_empty_output(rendered: &str) -> bool {
 		&& normalized.matches('{').count() == 1
 }
 
+/// Cargo features a package's rustdoc JSON is built with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSelection {
+	/// Disable the package's default features (`--no-default-features`).
+	pub no_default_features: bool,
+	/// Enable every feature of the package (`--all-features`).
+	pub all_features: bool,
+	/// Features to enable explicitly (`--features`).
+	pub features: Vec<String>,
+}
+
+/// How [`Ripdoc::render_matches`] renders the items its selector picks.
+struct MatchRendering {
+	include_private: bool,
+	expand_containers: bool,
+	implementation: bool,
+	raw_source: bool,
+}
+
+/// Rendered output of every resolved package, plus the packages that failed to build.
+#[derive(Debug, Clone)]
+pub struct RenderOutput {
+	/// Rendered packages, joined in resolution order.
+	pub rendered: String,
+	/// Packages left out because they failed.
+	pub errors: Vec<TargetFailure>,
+}
+
+/// Per-package output before packages are joined.
+enum TargetOutput {
+	Document(JsonDocument),
+	Text(String),
+}
+
+/// One package of a render, either served from the rendered cache or ready to render.
+enum PreparedTarget {
+	/// Skeleton from the rendered cache, without the package header.
+	Cached(String),
+	/// Built crate still to be rendered.
+	Built(Box<BuiltTarget>),
+}
+
+/// A package whose crate was built because its skeleton was not cached.
+struct BuiltTarget {
+	crate_data: Arc<Crate>,
+	renderer: Renderer,
+	/// Raw source files to write ahead of the skeleton (`--raw-source`).
+	raw_files_content: String,
+	/// Where to cache the rendered skeleton, if caching applies.
+	render_cache: Option<(CacheKey, String)>,
+}
+
+/// Writes through to `out` while keeping a copy of everything written, so streamed skeletons can
+/// be stored in the rendered cache.
+struct CopyingWriter<'a, W: Write + ?Sized> {
+	out: &'a mut W,
+	copy: Vec<u8>,
+}
+
+impl<W: Write + ?Sized> Write for CopyingWriter<'_, W> {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		let written = self.out.write(buf)?;
+		self.copy.extend_from_slice(&buf[..written]);
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		self.out.flush()
+	}
+}
+
+/// Run `per_target` over every resolved target, collecting failures instead of aborting on the
+/// first one.
+///
+/// Targets are processed concurrently (see [`map_targets`]) but outputs and failures keep the
+/// resolution order. The first error is returned when every target fails (including the
+/// single-target case), so a successful result always contains at least one package's output.
+fn collect_target_outputs<T: Send>(
+	targets: &[ResolvedTarget],
+	per_target: impl Fn(&ResolvedTarget) -> Result<T> + Sync,
+) -> Result<(Vec<T>, Vec<TargetFailure>)> {
+	let mut outputs = Vec::new();
+	let mut failures = Vec::new();
+	let mut first_error = None;
+	for (rt, result) in targets.iter().zip(map_targets(targets, &per_target)) {
+		match result {
+			Ok(output) => outputs.push(output),
+			Err(err) => {
+				failures.push(target_failure(rt, &err));
+				first_error.get_or_insert(err);
+			}
+		}
+	}
+	match first_error {
+		Some(err) if outputs.is_empty() => Err(err),
+		_ => Ok((outputs, failures)),
+	}
+}
+
+/// Record a package that failed while others may still succeed.
+fn target_failure(rt: &ResolvedTarget, err: &RipdocError) -> TargetFailure {
+	TargetFailure {
+		package: rt
+			.package_name
+			.clone()
+			.unwrap_or_else(|| rt.package_root().display().to_string()),
+		message: err.to_string(),
+	}
+}
+
+/// Apply `per_target` to every target on a pool of at most `available_parallelism` scoped
+/// threads, returning results in target order.
+///
+/// Rustdoc JSON generation dominates multi-package runs; cargo serializes access to a shared
+/// target directory itself, so concurrent builds are safe.
+fn map_targets<T: Send>(
+	targets: &[ResolvedTarget],
+	per_target: &(dyn Fn(&ResolvedTarget) -> Result<T> + Sync),
+) -> Vec<Result<T>> {
+	let workers = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+	map_on_workers(targets, workers, per_target)
+}
+
+/// [`map_targets`] with at most `workers` threads; one worker runs on the calling thread.
+fn map_on_workers<I: Sync, T: Send>(
+	targets: &[I],
+	workers: usize,
+	per_target: &(dyn Fn(&I) -> Result<T> + Sync),
+) -> Vec<Result<T>> {
+	let workers = workers.min(targets.len());
+	if workers <= 1 {
+		return targets.iter().map(per_target).collect();
+	}
+
+	let next = AtomicUsize::new(0);
+	let slots: Vec<Mutex<Option<Result<T>>>> = targets.iter().map(|_| Mutex::new(None)).collect();
+	let timings = crate::timings::current();
+	std::thread::scope(|scope| {
+		for _ in 0..workers {
+			scope.spawn(|| {
+				let work = || loop {
+					let index = next.fetch_add(1, Ordering::Relaxed);
+					let Some(rt) = targets.get(index) else {
+						break;
+					};
+					let result = per_target(rt);
+					*slots[index].lock().unwrap_or_else(PoisonError::into_inner) = Some(result);
+				};
+				// Workers record into the caller's recorder so parallel phases still add up.
+				match &timings {
+					Some(timings) => timings.scope(work),
+					None => work(),
+				}
+			});
+		}
+	});
+	slots
+		.into_iter()
+		.map(|slot| {
+			slot.into_inner()
+				.unwrap_or_else(PoisonError::into_inner)
+				.expect("every target is processed")
+		})
+		.collect()
+}
+
+/// Serialize rendered JSON documents: a single object for one crate, an array for several.
+fn join_json_documents(documents: Vec<JsonDocument>) -> Result<String> {
+	let json = match <[JsonDocument; 1]>::try_from(documents) {
+		Ok([document]) => serde_json::to_string_pretty(&document)?,
+		Err(documents) => serde_json::to_string_pretty(&documents)?,
+	};
+	Ok(json)
+}
+
 impl Default for Ripdoc {
 	fn default() -> Self {
 		Self::new()
@@ -109,10 +456,31 @@ impl Ripdoc {
 		Self {
 			offline: false,
 			auto_impls: false,
-			silent: false,
+			verbosity: Verbosity::Full,
 			render_format: RenderFormat::Markdown,
 			render_source_labels: true,
+			collapse_variants: false,
+			derive_style: DeriveStyle::default(),
+			simplify_lifetimes: false,
+			doc_examples: false,
+			doc_options: DocOptions::default(),
+			markdown_options: MarkdownOptions::default(),
+			min_visibility: None,
+			strict_public: false,
+			module_summary: false,
+			exclude: Vec::new(),
+			max_impl_lines: None,
+			cfg_annotations: false,
+			doc_hidden: false,
 			cache_config: super::cargo_utils::CacheConfig::default(),
+			working_dir: None,
+			config_dir: None,
+			state_dir: None,
+			workspace: false,
+			docs_rs: false,
+			dependency_depth: 0,
+			rank_by_fan_in: false,
+			list_deprecation: DeprecationFilter::Any,
 		}
 	}
 
@@ -141,9 +509,72 @@ impl Ripdoc {
 		self
 	}
 
+	/// Enables or disables collapsing of enum variant fields into placeholders.
+	pub fn with_collapsed_variants(mut self, collapse: bool) -> Self {
+		self.collapse_variants = collapse;
+		self
+	}
+
+	/// Shows derived traits as `#[derive(...)]` (default), as a `/* derives: ... */` summary, or
+	/// as their impl blocks.
+	pub fn with_derive_style(mut self, style: DeriveStyle) -> Self {
+		self.derive_style = style;
+		self
+	}
+
+	/// Enables or disables lifetime elision and `for<'a>` collapsing in rendered signatures.
+	pub fn with_simplified_lifetimes(mut self, simplify: bool) -> Self {
+		self.simplify_lifetimes = simplify;
+		self
+	}
+
+	/// Sets the code-fence language and maximum block length used for Markdown output.
+	pub fn with_markdown_options(mut self, options: MarkdownOptions) -> Self {
+		self.markdown_options = options;
+		self
+	}
+
+	/// Repeats the Rust code blocks of item docs as labelled example sections after each item.
+	pub fn with_doc_examples(mut self, enabled: bool) -> Self {
+		self.doc_examples = enabled;
+		self
+	}
+
+	/// Renders docs in full, cut to their first paragraph or first line, or not at all.
+	pub fn with_docs(mut self, mode: DocMode) -> Self {
+		self.doc_options.mode = mode;
+		self
+	}
+
+	/// Wraps doc prose at `width` columns, leaving code blocks, headings, and tables untouched.
+	pub fn with_doc_wrap(mut self, width: Option<usize>) -> Self {
+		self.doc_options.wrap = width;
+		self
+	}
+
+	/// Drops fenced code blocks from docs, keeping the surrounding prose.
+	pub fn with_doc_code_stripped(mut self, strip: bool) -> Self {
+		self.doc_options.strip_code_blocks = strip;
+		self
+	}
+
 	/// Enables or disables silent mode, which suppresses output during processing.
+	///
+	/// Shorthand for [`Self::with_verbosity`] with [`Verbosity::Quiet`] or [`Verbosity::Full`].
 	pub fn with_silent(mut self, silent: bool) -> Self {
-		self.silent = silent;
+		self.verbosity = if silent { Verbosity::Quiet } else { Verbosity::Full };
+		self
+	}
+
+	/// Sets how much cargo/rustdoc output is mirrored while generating docs.
+	pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+		self.verbosity = verbosity;
+		self
+	}
+
+	/// Hides items less visible than `level` (e.g. `pub(crate)`) when rendering private items.
+	pub fn with_min_visibility(mut self, level: Option<VisibilityLevel>) -> Self {
+		self.min_visibility = level;
 		self
 	}
 
@@ -159,6 +590,207 @@ impl Ripdoc {
 		self
 	}
 
+	/// Resolves relative target paths, and finds the workspace of named crates, from `dir`
+	/// instead of the process's current directory.
+	///
+	/// Together with [`Self::with_cache_dir`], [`Self::with_config_dir`], and
+	/// [`Self::with_state_dir`], this keeps an instance independent of process-wide state, so
+	/// differently configured instances can serve requests side by side.
+	pub fn with_working_dir(mut self, dir: PathBuf) -> Self {
+		self.working_dir = Some(dir);
+		self
+	}
+
+	/// Reads target aliases from `dir` instead of `RIPDOC_CONFIG_DIR` or the platform config
+	/// directory.
+	pub fn with_config_dir(mut self, dir: PathBuf) -> Self {
+		self.config_dir = Some(dir);
+		self
+	}
+
+	/// Keeps the skelebuild state in `dir` instead of `RIPDOC_STATE_DIR` or the platform state
+	/// directory.
+	pub fn with_state_dir(mut self, dir: PathBuf) -> Self {
+		self.state_dir = Some(dir);
+		self
+	}
+
+	/// Directory the skelebuild state is kept in (see [`Self::with_state_dir`]).
+	pub fn state_dir(&self) -> PathBuf {
+		self.state_dir.clone().unwrap_or_else(crate::cargo_utils::state_dir)
+	}
+
+	/// `path` relative to the directory set with [`Self::with_working_dir`]; unchanged when none
+	/// is set, so relative paths stay relative to the process's current directory.
+	pub fn in_working_dir(&self, path: impl AsRef<Path>) -> PathBuf {
+		match &self.working_dir {
+			Some(dir) => dir.join(path),
+			None => path.as_ref().to_path_buf(),
+		}
+	}
+
+	/// Returns the settings targets are resolved with.
+	pub fn resolve_context(&self) -> ResolveContext {
+		ResolveContext::new(self.offline)
+			.with_working_dir(self.working_dir.clone())
+			.with_config_dir(self.config_dir.clone())
+	}
+
+	/// Sets the zstd level cache files are written with; `None` stores them uncompressed.
+	pub fn with_cache_compression(mut self, level: Option<i32>) -> Self {
+		self.cache_config = self.cache_config.with_compression(level);
+		self
+	}
+
+	/// Ignores cached rustdoc JSON and regenerates it, overwriting the cache entries afterwards.
+	///
+	/// Each crate is rebuilt at most once per `Ripdoc` (and its clones), however often it is read.
+	pub fn with_force_refresh(mut self, refresh: bool) -> Self {
+		self.cache_config = self.cache_config.with_refresh(refresh);
+		self
+	}
+
+	/// Keeps parsed crates in process memory between calls (used by `ripdoc daemon`).
+	pub fn with_memory_cache(mut self, enabled: bool) -> Self {
+		self.cache_config = self.cache_config.with_memory(enabled);
+		self
+	}
+
+	/// Abandons rustdoc builds that run longer than `budget`, serving the most recent cached build
+	/// of the crate instead (with a notice that it may be stale), or failing if none is cached.
+	pub fn with_max_build_time(mut self, budget: Option<std::time::Duration>) -> Self {
+		self.cache_config = self.cache_config.with_build_budget(budget);
+		self
+	}
+
+	/// Expands every target to all packages of its enclosing workspace (respecting
+	/// `default-members`), producing one combined result with a section per package.
+	pub fn with_workspace(mut self, workspace: bool) -> Self {
+		self.workspace = workspace;
+		self
+	}
+
+	/// Reads named crates from their docs.rs rustdoc JSON build instead of running nightly
+	/// `cargo doc` locally. Path targets are unaffected.
+	pub fn with_docs_rs(mut self, docs_rs: bool) -> Self {
+		self.docs_rs = docs_rs;
+		self
+	}
+
+	/// Builds crates with the features and rustdoc flags of their `[package.metadata.docs.rs]`
+	/// table (plus `--cfg docsrs`) in place of the requested features, matching the docs.rs build.
+	pub fn with_docs_rs_config(mut self, enabled: bool) -> Self {
+		self.cache_config = self.cache_config.with_docs_rs_config(enabled);
+		self
+	}
+
+	/// Also resolves the dependencies declared in each target's manifest, `depth` levels deep
+	/// (1 for direct dependencies, 0 to disable). Every package is rendered once under its own
+	/// package header.
+	pub fn with_dependencies(mut self, depth: usize) -> Self {
+		self.dependency_depth = depth;
+		self
+	}
+
+	/// Orders [`Self::list_packages`] entries by fan-in (the number of items whose signatures,
+	/// fields, or bounds mention them), most referenced first, and records the count on each
+	/// entry. Ties keep index order.
+	pub fn with_fan_in_ranking(mut self, rank_by_fan_in: bool) -> Self {
+		self.rank_by_fan_in = rank_by_fan_in;
+		self
+	}
+
+	/// Keeps only deprecated [`Self::list_packages`] entries, or drops them. Searches passed to
+	/// `list_packages` filter with [`SearchOptions::deprecation`] instead.
+	pub fn with_list_deprecation(mut self, filter: DeprecationFilter) -> Self {
+		self.list_deprecation = filter;
+		self
+	}
+
+	/// Renders only `pub` items reachable from the crate root through public modules and
+	/// re-exports, reflecting the crate's external API exactly even with private items included.
+	pub fn with_strict_public(mut self, strict: bool) -> Self {
+		self.strict_public = strict;
+		self
+	}
+
+	/// Returns whether only items reachable from the crate root are rendered.
+	pub fn strict_public(&self) -> bool {
+		self.strict_public
+	}
+
+	/// Renders each module as a one-line inventory (`pub mod net; // 12 structs, 3 traits, 40 fns`)
+	/// instead of its items: an orientation layer between [`Self::list`] and the full skeleton.
+	pub fn with_module_summary(mut self, summary: bool) -> Self {
+		self.module_summary = summary;
+		self
+	}
+
+	/// Returns whether modules are rendered as inventories.
+	pub fn module_summary(&self) -> bool {
+		self.module_summary
+	}
+
+	/// Leaves items matching any of `patterns` out of rendering, listings, and search results,
+	/// e.g. `tokio::runtime` to prune a large module; a `*` matches within one path segment.
+	pub fn with_exclude(mut self, patterns: Vec<String>) -> Self {
+		self.exclude = patterns;
+		self
+	}
+
+	/// Returns the configured exclude patterns.
+	pub fn exclude(&self) -> &[String] {
+		&self.exclude
+	}
+
+	/// In implementation mode, renders the full source only of items spanning at most `lines`
+	/// lines; longer items fall back to their skeleton, with function bodies replaced by a
+	/// `/* N lines elided */` marker.
+	pub fn with_max_impl_lines(mut self, lines: Option<usize>) -> Self {
+		self.max_impl_lines = lines;
+		self
+	}
+
+	/// Returns the longest span rendered in full in implementation mode, if limited.
+	pub fn max_impl_lines(&self) -> Option<usize> {
+		self.max_impl_lines
+	}
+
+	/// Annotates items gated on cargo features or other cfgs with their `#[cfg(...)]`
+	/// attributes, so readers can tell what must be enabled to use them.
+	pub fn with_cfg_annotations(mut self, enabled: bool) -> Self {
+		self.cfg_annotations = enabled;
+		self
+	}
+
+	/// Returns whether cfg-gated items show their `#[cfg(...)]` attributes.
+	pub fn cfg_annotations(&self) -> bool {
+		self.cfg_annotations
+	}
+
+	/// Renders and lists items marked `#[doc(hidden)]` when the rustdoc JSON contains them. By
+	/// default they are left out, as in rustdoc's HTML output. Searches passed to
+	/// [`Self::list_packages`] use [`SearchOptions::include_doc_hidden`] instead.
+	pub fn with_doc_hidden(mut self, enabled: bool) -> Self {
+		self.doc_hidden = enabled;
+		self
+	}
+
+	/// Returns whether items marked `#[doc(hidden)]` are rendered and listed.
+	pub fn doc_hidden(&self) -> bool {
+		self.doc_hidden
+	}
+
+	/// Returns how doc comments are shortened, wrapped, and stripped.
+	pub fn doc_options(&self) -> DocOptions {
+		self.doc_options
+	}
+
+	/// Returns the minimum visibility filter, if any.
+	pub fn min_visibility(&self) -> Option<VisibilityLevel> {
+		self.min_visibility
+	}
+
 	/// Returns the currently configured render format.
 	pub fn render_format(&self) -> RenderFormat {
 		self.render_format
@@ -169,6 +801,26 @@ impl Ripdoc {
 		self.render_source_labels
 	}
 
+	/// Returns whether enum variant fields are collapsed in rendered output.
+	pub fn collapse_variants(&self) -> bool {
+		self.collapse_variants
+	}
+
+	/// Returns how derived traits are shown in rendered output.
+	pub fn derive_style(&self) -> DeriveStyle {
+		self.derive_style
+	}
+
+	/// Returns whether redundant lifetimes are elided in rendered signatures.
+	pub fn simplify_lifetimes(&self) -> bool {
+		self.simplify_lifetimes
+	}
+
+	/// Returns the code-fence options used for Markdown output.
+	pub fn markdown_options(&self) -> MarkdownOptions {
+		self.markdown_options
+	}
+
 	/// Returns whether ripdoc is running in offline mode.
 	pub fn offline(&self) -> bool {
 		self.offline
@@ -176,7 +828,12 @@ impl Ripdoc {
 
 	/// Returns whether ripdoc is running in silent mode.
 	pub fn silent(&self) -> bool {
-		self.silent
+		self.verbosity.is_quiet()
+	}
+
+	/// Returns the configured output verbosity.
+	pub fn verbosity(&self) -> Verbosity {
+		self.verbosity
 	}
 
 	/// Returns the active cache configuration.
@@ -184,75 +841,441 @@ impl Ripdoc {
 		&self.cache_config
 	}
 
-	/// Returns the parsed representation of the crate's API.
-	///
-	/// # Arguments
-	/// * `target` - The target specification (see new() documentation for format)
-	/// * `no_default_features` - Whether to build without default features
-	/// * `all_features` - Whether to build with all features
-	/// * `features` - List of specific features to enable
-	/// * `private_items` - Whether to include private items in the output
-	pub fn inspect(
-		&self,
-		target: &str,
-		no_default_features: bool,
-		all_features: bool,
-		features: Vec<String>,
-		private_items: bool,
-	) -> Result<Vec<Crate>> {
-		let resolved_targets = resolve_target(target, self.offline)?;
-		let mut crates = Vec::with_capacity(resolved_targets.len());
-		for rt in resolved_targets {
-			crates.push(rt.read_crate(
-				no_default_features,
-				all_features,
-				features.clone(),
-				private_items,
-				self.silent,
-				&self.cache_config,
-			)?);
-		}
-		Ok(crates)
+	/// Returns whether targets expand to their whole workspace.
+	pub fn workspace(&self) -> bool {
+		self.workspace
 	}
-
-	/// Execute a search against the crate and return the matched items along with a rendered skeleton.
-	///
-	/// The search respects the same target resolution logic as [`Self::render`], but only the
-	/// matched items and their ancestors are emitted in the final skeleton.
-	pub fn search(
+	/// Build the renderer used by [`Self::render`] for one package, together with the raw source
+	/// files to prepend when `raw_source` is set.
+	fn target_renderer(
 		&self,
-		target: &str,
-		no_default_features: bool,
-		all_features: bool,
-		features: Vec<String>,
-		options: &SearchOptions,
+		rt: &ResolvedTarget,
+		crate_data: &Crate,
+		features: &FeatureSelection,
+		private_items: bool,
 		implementation: bool,
 		raw_source: bool,
-	) -> Result<SearchResponse> {
-		let resolved_targets = resolve_target(target, self.offline)?;
-		let mut all_results = Vec::new();
-		let mut all_rendered = Vec::new();
-
-		for rt in resolved_targets {
-			let crate_data = rt.read_crate(
-				no_default_features,
-				all_features,
-				features.clone(),
-				options.include_private,
-				self.silent,
-				&self.cache_config,
-			)?;
+	) -> (Renderer, String) {
+		let mut full_source_ids = HashSet::new();
+		let mut whole_crate = Vec::new();
+		let mut raw_files_content = String::new();
 
-			let index = SearchIndex::build(
-				&crate_data,
-				options.include_private,
-				Some(rt.package_root()),
+		if implementation || raw_source {
+			let index = self.index_for(
+				rt,
+				crate_data,
+				features.no_default_features,
+				features.all_features,
+				&features.features,
+				private_items,
 			);
-			let results = index.search(options);
-
-			if results.is_empty() {
-				continue;
-			}
+			// Without a filter every item is selected, which an empty query never matches.
+			let whole = rt.filter.trim().is_empty();
+			let results = if whole {
+				index.entries().to_vec()
+			} else {
+				let mut options = SearchOptions::new(&rt.filter);
+				options.include_private = private_items;
+				options.domains = SearchDomain::PATHS;
+				index.search(&options)
+			};
+
+			if implementation {
+				// Containers stay skeletons in the whole crate: their members are selected on their
+				// own, and a container rendered from source would drop its impl blocks.
+				for res in &results {
+					if !whole || !is_container(res.kind) {
+						full_source_ids.insert(res.item_id);
+					}
+				}
+				if whole {
+					whole_crate = results.clone();
+				}
+			}
+
+			if raw_source {
+				let mut seen_files = HashSet::new();
+				for res in &results {
+					if let Some(item) = crate_data.index.get(&res.item_id)
+						&& let Some(span) = &item.span
+						&& seen_files.insert(span.filename.clone())
+					{
+						let abs_path = if span.filename.is_absolute() {
+							span.filename.clone()
+						} else {
+							rt.package_root().join(&span.filename)
+						};
+						if let Ok(content) = fs::read_to_string(&abs_path) {
+							raw_files_content
+								.push_str(&source_label(&span.filename, Some(rt.package_root())));
+							raw_files_content.push_str(&format!("{content}\n\n"));
+						}
+					}
+				}
+			}
+		}
+
+		let mut renderer = self
+			.renderer()
+			.with_filter(&rt.filter)
+			.with_private_items(private_items)
+			.with_source_root(rt.package_root().to_path_buf());
+
+		if !full_source_ids.is_empty() {
+			let index = self.index_for(
+				rt,
+				crate_data,
+				features.no_default_features,
+				features.all_features,
+				&features.features,
+				private_items,
+			);
+			// No explicit search results here (we're using the filter), except for the whole crate,
+			// whose every item must stay in the selection.
+			let selection = build_render_selection(&index, &whole_crate, true, full_source_ids);
+			renderer = renderer.with_selection(selection);
+		}
+
+		(renderer, raw_files_content)
+	}
+
+	/// Section header introducing one package in combined output; empty for JSON, whose documents
+	/// carry the package name.
+	fn package_header(&self, name: &str) -> String {
+		match self.render_format {
+			RenderFormat::Markdown => format!("# Package: {name}\n\n"),
+			RenderFormat::Rust => format!("// Package: {name}\n\n"),
+			RenderFormat::Html => format!("<!-- Package: {name} -->\n"),
+			RenderFormat::Json => String::new(),
+		}
+	}
+
+	/// Divider placed between packages in combined textual output; empty for JSON, whose
+	/// documents are joined into an array.
+	fn package_separator(&self) -> &'static str {
+		match self.render_format {
+			RenderFormat::Markdown => "\n\n---\n\n",
+			RenderFormat::Rust => {
+				"\n\n// ----------------------------------------------------------------------------\n\n"
+			}
+			// Each package is a page of its own; split output keeps them in separate files.
+			RenderFormat::Html => "\n",
+			RenderFormat::Json => "",
+		}
+	}
+
+	/// Resolve a target into packages, expanding to the whole workspace when configured.
+	fn resolve(&self, target: &str) -> Result<Vec<ResolvedTarget>> {
+		let ctx = self.resolve_context();
+		let mut resolved = self.verbosity.time("resolve target", || {
+			if self.docs_rs {
+				resolve_docs_rs_target(target, &ctx, &self.cache_config)
+			} else if self.workspace {
+				resolve_workspace_targets(target, &ctx)
+			} else {
+				resolve_target(target, &ctx)
+			}
+		})?;
+		if self.dependency_depth > 0 {
+			let dependencies = self.verbosity.time("resolve dependencies", || {
+				resolve_dependency_targets(&resolved, self.dependency_depth, self.offline)
+			})?;
+			if !dependencies.is_empty() {
+				// Give the roots a package header too, so every package is labelled.
+				for rt in &mut resolved {
+					if rt.package_name.is_none() {
+						rt.package_name = rt.package_metadata()?.map(|metadata| metadata.name);
+					}
+				}
+				resolved.extend(dependencies);
+			}
+		}
+		Ok(resolved)
+	}
+
+	/// Build a renderer carrying the output options configured on this instance.
+	fn renderer(&self) -> Renderer {
+		Renderer::default()
+			.with_auto_impls(self.auto_impls)
+			.with_source_labels(self.render_source_labels)
+			.with_format(self.render_format)
+			.with_collapsed_variants(self.collapse_variants)
+			.with_derive_style(self.derive_style)
+			.with_simplified_lifetimes(self.simplify_lifetimes)
+			.with_doc_examples(self.doc_examples)
+			.with_doc_options(self.doc_options)
+			.with_markdown_options(self.markdown_options)
+			.with_min_visibility(self.min_visibility)
+			.with_strict_public(self.strict_public)
+			.with_module_summary(self.module_summary)
+			.with_exclude(self.exclude.clone())
+			.with_max_impl_lines(self.max_impl_lines)
+			.with_cfg_annotations(self.cfg_annotations)
+			.with_doc_hidden_items(self.doc_hidden)
+	}
+
+	/// Search index of `crate_data`, the crate built for `rt`, reusing the one kept warm under
+	/// [`Self::warm_index_key`] when there is one.
+	fn index_for<'c>(
+		&self,
+		rt: &ResolvedTarget,
+		crate_data: &'c Crate,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+	) -> SearchIndex<'c> {
+		let warm = self.warm_index_key(rt, no_default_features, all_features, features, private_items);
+		SearchIndex::build_warm(crate_data, private_items, Some(rt.package_root()), warm.as_deref())
+	}
+
+	/// Key under which the search index for `rt` is kept warm, when the memory cache is enabled.
+	///
+	/// The hash of the same [`crate::cargo_utils::CacheKey`] as the in-memory crate, which covers
+	/// the manifest and whether private items are documented, so an index is only reused for the
+	/// crate it was built from, and is dropped along with a build its sources superseded. When
+	/// refreshing, the crate is rebuilt under the same key, so its warm index is dropped and not
+	/// reused.
+	fn warm_index_key(
+		&self,
+		rt: &ResolvedTarget,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+	) -> Option<String> {
+		if !self.cache_config.enabled || !self.cache_config.memory {
+			return None;
+		}
+		let key = rt
+			.cache_key(
+				no_default_features,
+				all_features,
+				features.to_vec(),
+				private_items,
+				&self.cache_config,
+			)
+			.ok()?;
+		let key = key.hash();
+		if self.cache_config.refresh.is_some() {
+			SearchIndex::evict_warm(&key);
+			return None;
+		}
+		Some(key)
+	}
+
+	/// Where the skeleton rendered for `rt` is cached: the key of the crate build it is rendered
+	/// from, and a hash of every option that shapes the output, including whether it was
+	/// streamed.
+	///
+	/// The hash includes the ripdoc version, so an upgraded renderer never serves skeletons
+	/// rendered by an older one. `None` when the cache is disabled or the key cannot be computed.
+	fn rendered_cache_key(
+		&self,
+		rt: &ResolvedTarget,
+		features: &FeatureSelection,
+		private_items: bool,
+		implementation: bool,
+		streamed: bool,
+	) -> Option<(CacheKey, String)> {
+		if !self.cache_config.enabled {
+			return None;
+		}
+		let key = rt
+			.cache_key(
+				features.no_default_features,
+				features.all_features,
+				features.features.clone(),
+				private_items,
+				&self.cache_config,
+			)
+			.ok()?;
+		let options = format!(
+			"{}|{:?}|{}|{}|{}|{:?}|{}|{}|{:?}|{:?}|{:?}|{}|{}|{:?}|{:?}|{}|{}|{}|{}|{}|{}|{}|{}",
+			env!("CARGO_PKG_VERSION"),
+			self.render_format,
+			self.auto_impls,
+			self.render_source_labels,
+			self.collapse_variants,
+			self.derive_style,
+			self.simplify_lifetimes,
+			self.doc_examples,
+			self.doc_options,
+			self.markdown_options,
+			self.min_visibility,
+			self.strict_public,
+			self.module_summary,
+			self.exclude,
+			self.max_impl_lines,
+			self.cfg_annotations,
+			self.doc_hidden,
+			private_items,
+			implementation,
+			streamed,
+			self.cache_config.docs_rs_config,
+			rt.filter,
+			rt.package_root().display()
+		);
+		let mut hasher = DefaultHasher::new();
+		options.hash(&mut hasher);
+		Some((key, format!("{:x}", hasher.finish())))
+	}
+
+	/// Returns the parsed representation of the crate's API.
+	///
+	/// # Arguments
+	/// * `target` - The target specification (see new() documentation for format)
+	/// * `no_default_features` - Whether to build without default features
+	/// * `all_features` - Whether to build with all features
+	/// * `features` - List of specific features to enable
+	/// * `private_items` - Whether to include private items in the output
+	pub fn inspect(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<Crate>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut crates = Vec::with_capacity(resolved_targets.len());
+		for rt in resolved_targets {
+			crates.push(Arc::unwrap_or_clone(rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?));
+		}
+		Ok(crates)
+	}
+
+	/// Walk the item tree of each resolved crate in module order, calling `visitor` per item.
+	///
+	/// Packages are walked in resolution order; a visitor returning [`VisitFlow::Stop`] ends the
+	/// whole walk. Each crate is only borrowed for the duration of its walk, so the visitor must
+	/// accept items of any lifetime; use [`walk_crate`] on a crate from [`Self::inspect`] to keep
+	/// item references around afterwards.
+	pub fn visit<V>(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		options: &VisitOptions,
+		visitor: &mut V,
+	) -> Result<()>
+	where
+		V: for<'a> ItemVisitor<'a>,
+	{
+		for rt in self.resolve(target)? {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				options.include_private,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			if !walk_crate(&crate_data, options, visitor) {
+				break;
+			}
+		}
+		Ok(())
+	}
+
+	/// Execute a search against the crate and return the matched items along with a rendered skeleton.
+	///
+	/// The search respects the same target resolution logic as [`Self::render`], but only the
+	/// matched items and their ancestors are emitted in the final skeleton.
+	pub fn search(
+		&self,
+		target: &str,
+		features: FeatureSelection,
+		options: &SearchOptions,
+		implementation: bool,
+		raw_source: bool,
+	) -> Result<SearchResponse> {
+		let rendering = MatchRendering {
+			include_private: options.include_private,
+			expand_containers: options.expand_containers,
+			implementation,
+			raw_source,
+		};
+		self.render_matches(target, features, rendering, |index| index.search(options))
+	}
+
+	/// Re-render the items pinned by a saved selection, without running a search.
+	///
+	/// Selected paths are looked up in a fresh index of each resolved package; entries that no
+	/// longer exist are simply absent from [`SearchResponse::results`] (see
+	/// [`SavedSelection::missing`]).
+	pub fn render_saved_selection(
+		&self,
+		target: &str,
+		features: FeatureSelection,
+		private_items: bool,
+		selection: &SavedSelection,
+		implementation: bool,
+		raw_source: bool,
+	) -> Result<SearchResponse> {
+		let rendering = MatchRendering {
+			include_private: private_items,
+			expand_containers: selection.expand_containers,
+			implementation,
+			raw_source,
+		};
+		self.render_matches(target, features, rendering, |index| selection.resolve(index))
+	}
+
+	/// Render the items chosen by `select` from each resolved package's index.
+	fn render_matches(
+		&self,
+		target: &str,
+		features: FeatureSelection,
+		rendering: MatchRendering,
+		select: impl Fn(&SearchIndex) -> Vec<SearchResult> + Sync,
+	) -> Result<SearchResponse> {
+		let FeatureSelection {
+			no_default_features,
+			all_features,
+			features,
+		} = features;
+		let MatchRendering {
+			include_private,
+			expand_containers,
+			implementation,
+			raw_source,
+		} = rendering;
+		let resolved_targets = self.resolve(target)?;
+		let sectioned = resolved_targets.len() > 1;
+
+		let (outputs, errors) = collect_target_outputs(&resolved_targets, |rt| {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				include_private,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+
+			let index = self.index_for(
+				rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				include_private,
+			);
+			let mut results = select(&index);
+			results.retain(|result| !path_excluded(&result.path_string, &self.exclude));
+
+			if results.is_empty() {
+				return Ok(None);
+			}
 
 			let mut full_source_ids = HashSet::new();
 			if implementation {
@@ -275,11 +1298,9 @@ impl Ripdoc {
 							rt.package_root().join(&span.filename)
 						};
 						if let Ok(content) = fs::read_to_string(&abs_path) {
-							raw_files_content.push_str(&format!(
-								"// ripdoc:source: {}\n\n{}\n\n",
-								span.filename.display(),
-								content
-							));
+							raw_files_content
+								.push_str(&source_label(&span.filename, Some(rt.package_root())));
+							raw_files_content.push_str(&format!("{content}\n\n"));
 						}
 					}
 				}
@@ -288,34 +1309,64 @@ impl Ripdoc {
 			let selection = build_render_selection(
 				&index,
 				&results,
-				options.expand_containers,
+				expand_containers,
 				full_source_ids,
 			);
-			let renderer = Renderer::default()
+			let renderer = self
+				.renderer()
 				.with_filter(&rt.filter)
-				.with_auto_impls(self.auto_impls)
-				.with_private_items(options.include_private)
-				.with_source_labels(self.render_source_labels)
-				.with_format(self.render_format)
+				.with_private_items(include_private)
 				.with_source_root(rt.package_root().to_path_buf())
 				.with_selection(selection);
+
+			if self.render_format == RenderFormat::Json {
+				let mut document = renderer.render_document(&crate_data);
+				document.package = rt.package_name.clone();
+				return Ok(Some((results, TargetOutput::Document(document))));
+			}
+
 			let mut rendered = renderer.render(&crate_data)?;
 
 			if !raw_files_content.is_empty() {
 				rendered = format!("{}\n---\n\n{}", raw_files_content, rendered);
 			}
 
+			// Several packages (e.g. a whole workspace) get one section each.
+			if sectioned && let Some(ref name) = rt.package_name {
+				rendered = format!("{}{rendered}", self.package_header(name));
+			}
+
+			Ok(Some((results, TargetOutput::Text(rendered))))
+		})?;
+
+		let mut all_results = Vec::new();
+		let mut all_rendered = Vec::new();
+		let mut documents = Vec::new();
+		for (results, output) in outputs.into_iter().flatten() {
 			all_results.extend(results);
-			all_rendered.push(rendered);
+			match output {
+				TargetOutput::Document(document) => documents.push(document),
+				TargetOutput::Text(rendered) => all_rendered.push(rendered),
+			}
 		}
 
+		let rendered = if self.render_format == RenderFormat::Json {
+			join_json_documents(documents)?
+		} else {
+			all_rendered.join("\n")
+		};
+
 		Ok(SearchResponse {
 			results: all_results,
-			rendered: all_rendered.join("\n"),
+			rendered,
+			errors,
 		})
 	}
 
 	/// Produce a lightweight listing of crate items, optionally filtered by a search query.
+	///
+	/// Packages that fail to build are skipped when others succeed; use [`Self::list_packages`]
+	/// to see which ones failed.
 	pub fn list(
 		&self,
 		target: &str,
@@ -325,166 +1376,1004 @@ impl Ripdoc {
 		include_private: bool,
 		search: Option<&SearchOptions>,
 	) -> Result<Vec<ListItem>> {
+		let packages = self.list_packages(
+			target,
+			no_default_features,
+			all_features,
+			features,
+			include_private,
+			search,
+		)?;
+		Ok(packages
+			.packages
+			.into_iter()
+			.flat_map(|package| package.items)
+			.collect())
+	}
+
+	/// Like [`Self::list`], but keeps the items of each resolved package separate and reports the
+	/// packages that failed.
+	pub fn list_packages(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		include_private: bool,
+		search: Option<&SearchOptions>,
+	) -> Result<ListOutput> {
 		let include_private = include_private
 			|| search
 				.map(|options| options.include_private)
 				.unwrap_or(false);
 
-		let resolved_targets = resolve_target(target, self.offline)?;
-		let mut all_results = Vec::new();
-
-		for rt in resolved_targets {
+		let resolved_targets = self.resolve(target)?;
+		let (packages, errors) = collect_target_outputs(&resolved_targets, |rt| {
 			let crate_data = rt.read_crate(
 				no_default_features,
 				all_features,
 				features.clone(),
 				include_private,
-				self.silent,
+				self.verbosity,
 				&self.cache_config,
 			)?;
 
-			let index = SearchIndex::build(&crate_data, include_private, Some(rt.package_root()));
+			let index = self.index_for(
+				rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				include_private,
+			);
 
-			let results: Vec<ListItem> = if let Some(options) = search {
-				index
-					.search(options)
-					.into_iter()
-					.map(|result| ListItem {
-						kind: result.kind,
-						path: result.path_string,
-						source: result.source,
-					})
-					.collect()
+			let fan_in = self
+				.rank_by_fan_in
+				.then(|| fan_in::fan_in_counts(&crate_data, &index));
+			let to_item = |result: SearchResult| ListItem {
+				kind: result.kind,
+				fan_in: fan_in
+					.as_ref()
+					.map(|counts| counts.get(&result.item_id).copied().unwrap_or(0)),
+				path: result.path_string,
+				source: result.source,
+				deprecated: result.deprecated,
+				deprecation_note: result.deprecation_note,
+				cfg: result.cfg,
+			};
+			let mut items: Vec<ListItem> = if let Some(options) = search {
+				index.search(options).into_iter().map(to_item).collect()
 			} else {
 				index
 					.entries()
 					.iter()
+					.filter(|entry| self.list_deprecation.allows(entry.deprecated))
+					.filter(|entry| self.doc_hidden || !entry.doc_hidden)
 					.cloned()
-					.map(|entry| ListItem {
-						kind: entry.kind,
-						path: entry.path_string,
-						source: entry.source,
-					})
+					.map(to_item)
 					.collect()
 			};
-			all_results.extend(results);
-		}
+			items.retain(|item| {
+				item.kind != SearchItemKind::Use && !path_excluded(&item.path, &self.exclude)
+			});
+			if fan_in.is_some() {
+				items.sort_by_key(|item| std::cmp::Reverse(item.fan_in));
+			}
 
-		all_results.retain(|item| item.kind != SearchItemKind::Use);
+			let name = rt
+				.package_name
+				.clone()
+				.or_else(|| {
+					crate_data
+						.index
+						.get(&crate_data.root)
+						.and_then(|root| root.name.clone())
+				})
+				.unwrap_or_else(|| "crate".to_string());
+			Ok(PackageListing {
+				name,
+				version: crate_data.crate_version.clone(),
+				items,
+			})
+		})?;
 
-		Ok(all_results)
+		Ok(ListOutput { packages, errors })
+	}
+
+	/// Search every crate in the rustdoc cache, without resolving a target.
+	///
+	/// Each `name@version` is searched once, in its most recently written build (local builds and
+	/// docs.rs downloads alike), so "which cached crate defines `X`?" needs no target. Packages
+	/// without matches are left out; entries that no longer decode are reported as failures.
+	pub fn search_cache(&self, options: &SearchOptions) -> Result<ListOutput> {
+		let mut entries: Vec<CacheEntry> = cache_entries(&self.cache_config)?
+			.into_iter()
+			.filter(|entry| entry.kind != CacheEntryKind::Rendered)
+			.collect();
+		entries.sort_by_key(|entry| entry.age_secs);
+		let mut seen = HashSet::new();
+		entries.retain(|entry| {
+			let package = entry.package.clone().unwrap_or_else(|| entry.path.display().to_string());
+			seen.insert((package, entry.version.clone()))
+		});
+
+		let mut packages = Vec::new();
+		let mut errors = Vec::new();
+		for entry in &entries {
+			let crate_data = match read_cache_entry(entry) {
+				Ok(Some(crate_data)) => crate_data,
+				Ok(None) => continue,
+				Err(err) => {
+					errors.push(TargetFailure {
+						package: entry.package.clone().unwrap_or_else(|| entry.path.display().to_string()),
+						message: err.to_string(),
+					});
+					continue;
+				}
+			};
+			let source_root = entry
+				.build
+				.as_ref()
+				.and_then(|build| build.manifest_path.parent().map(PathBuf::from));
+			let index = SearchIndex::build(&crate_data, options.include_private, source_root.as_deref());
+			let items: Vec<ListItem> = index
+				.search(options)
+				.into_iter()
+				.filter(|result| result.kind != SearchItemKind::Use)
+				.filter(|result| !path_excluded(&result.path_string, &self.exclude))
+				.map(|result| ListItem {
+					kind: result.kind,
+					path: result.path_string,
+					source: result.source,
+					fan_in: None,
+					deprecated: result.deprecated,
+					deprecation_note: result.deprecation_note,
+					cfg: result.cfg,
+				})
+				.collect();
+			if items.is_empty() {
+				continue;
+			}
+			let name = entry.package.clone().unwrap_or_else(|| {
+				crate_data
+					.index
+					.get(&crate_data.root)
+					.and_then(|root| root.name.clone())
+					.unwrap_or_else(|| "crate".to_string())
+			});
+			packages.push(PackageListing {
+				name,
+				version: entry.version.clone().or_else(|| crate_data.crate_version.clone()),
+				items,
+			});
+		}
+		packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+		Ok(ListOutput { packages, errors })
 	}
 
 	/// Render the crate target into a Rust skeleton without filtering.
+	///
+	/// When several packages are resolved, packages that fail to build are left out and reported
+	/// in [`RenderOutput::errors`]; the call only fails if every package does.
 	pub fn render(
 		&self,
 		target: &str,
-		no_default_features: bool,
-		all_features: bool,
-		features: Vec<String>,
+		features: FeatureSelection,
 		private_items: bool,
 		implementation: bool,
 		raw_source: bool,
-	) -> Result<String> {
-		let resolved_targets = resolve_target(target, self.offline)?;
-		let mut rendered_outputs = Vec::new();
+	) -> Result<RenderOutput> {
+		let resolved_targets = self.resolve(target)?;
 
-		for rt in resolved_targets {
-			let crate_data = rt.read_crate(
-				no_default_features,
-				all_features,
-				features.clone(),
+		let (outputs, errors) = collect_target_outputs(&resolved_targets, |rt| {
+			let prepared = self.prepare_target(
+				rt,
+				&features,
 				private_items,
-				self.silent,
-				&self.cache_config,
+				implementation,
+				raw_source,
+				false,
 			)?;
-
-			let mut full_source_ids = HashSet::new();
-			let mut raw_files_content = String::new();
-
-			if implementation || raw_source {
-				let index = SearchIndex::build(&crate_data, private_items, Some(rt.package_root()));
-				let mut options = SearchOptions::new(&rt.filter);
-				options.include_private = private_items;
-				options.domains = SearchDomain::PATHS;
-				let results = index.search(&options);
-
-				if implementation {
-					for res in &results {
-						full_source_ids.insert(res.item_id);
-					}
+			let built = match prepared {
+				PreparedTarget::Cached(rendered) => {
+					return Ok(TargetOutput::Text(self.with_package_header(rt, rendered)));
 				}
+				PreparedTarget::Built(built) => built,
+			};
 
-				if raw_source {
-					let mut seen_files = HashSet::new();
-					for res in &results {
-						if let Some(item) = crate_data.index.get(&res.item_id)
-							&& let Some(span) = &item.span
-							&& seen_files.insert(span.filename.clone())
-						{
-							let abs_path = if span.filename.is_absolute() {
-								span.filename.clone()
-							} else {
-								rt.package_root().join(&span.filename)
-							};
-							if let Ok(content) = fs::read_to_string(&abs_path) {
-								raw_files_content.push_str(&format!(
-									"// ripdoc:source: {}\n\n{}\n\n",
-									span.filename.display(),
-									content
-								));
-							}
-						}
-					}
-				}
+			if self.render_format == RenderFormat::Json {
+				let mut document = built.renderer.render_document(&built.crate_data);
+				document.package = rt.package_name.clone();
+				return Ok(TargetOutput::Document(document));
 			}
 
-			let mut renderer = Renderer::default()
-				.with_filter(&rt.filter)
-				.with_auto_impls(self.auto_impls)
-				.with_private_items(private_items)
-				.with_source_labels(self.render_source_labels)
-				.with_format(self.render_format)
-				.with_source_root(rt.package_root().to_path_buf());
+			let mut rendered = built.renderer.render(&built.crate_data)?;
+			if let Some((key, render_key)) = &built.render_cache {
+				// Best-effort: a failed write only costs a re-render next time.
+				let _ = save_rendered(&self.cache_config, key, render_key, &rendered);
+			}
 
-			if !full_source_ids.is_empty() {
-				let index = SearchIndex::build(&crate_data, private_items, Some(rt.package_root()));
-				let selection = build_render_selection(
-					&index,
-					&[], // No explicit search results here, we're using filter
-					true,
-					full_source_ids,
-				);
-				renderer = renderer.with_selection(selection);
+			if !built.raw_files_content.is_empty() {
+				rendered = format!("{}\n---\n\n{}", built.raw_files_content, rendered);
 			}
 
-			let mut rendered = renderer.render(&crate_data)?;
+			Ok(TargetOutput::Text(self.with_package_header(rt, rendered)))
+		})?;
 
-			if !raw_files_content.is_empty() {
-				rendered = format!("{}\n---\n\n{}", raw_files_content, rendered);
+		let mut rendered_outputs = Vec::new();
+		let mut documents = Vec::new();
+		for output in outputs {
+			match output {
+				TargetOutput::Document(document) => documents.push(document),
+				TargetOutput::Text(rendered) if !rendered.trim().is_empty() => {
+					rendered_outputs.push(rendered)
+				}
+				TargetOutput::Text(_) => {}
 			}
+		}
 
-			if let Some(ref name) = rt.package_name {
-				let header = match self.render_format {
-					RenderFormat::Markdown => format!("# Package: {name}\n\n"),
-					RenderFormat::Rust => format!("// Package: {name}\n\n"),
-				};
-				rendered = format!("{header}{rendered}");
-			}
+		if self.render_format == RenderFormat::Json {
+			let rendered = join_json_documents(documents)?;
+			return Ok(RenderOutput { rendered, errors });
+		}
+
+		Ok(RenderOutput {
+			rendered: rendered_outputs.join(self.package_separator()),
+			errors,
+		})
+	}
 
-			if !rendered.trim().is_empty() {
-				rendered_outputs.push(rendered);
+	/// Stream the rendered packages into `out` instead of returning one string.
+	///
+	/// Takes the same options as [`Self::render`], and packages are built the same way, in
+	/// parallel and through the rendered cache. Each built package is then written one top-level
+	/// item at a time (see [`Renderer::render_to`]), so the skeleton is never assembled as one
+	/// string before output starts. Packages that fail to build are skipped and returned, and the
+	/// call only fails if every package does. JSON output is still assembled in memory.
+	pub fn render_to_writer<W: Write + ?Sized>(
+		&self,
+		target: &str,
+		features: FeatureSelection,
+		private_items: bool,
+		implementation: bool,
+		raw_source: bool,
+		out: &mut W,
+	) -> Result<Vec<TargetFailure>> {
+		if self.render_format == RenderFormat::Json {
+			let output = self.render(target, features, private_items, implementation, raw_source)?;
+			writeln!(out, "{}", output.rendered)?;
+			return Ok(output.errors);
+		}
+
+		let resolved_targets = self.resolve(target)?;
+		let (prepared, failures) = collect_target_outputs(&resolved_targets, |rt| {
+			let prepared = self.prepare_target(
+				rt,
+				&features,
+				private_items,
+				implementation,
+				raw_source,
+				true,
+			)?;
+			Ok((rt.package_name.clone(), prepared))
+		})?;
+
+		for (index, (package_name, prepared)) in prepared.into_iter().enumerate() {
+			if index > 0 {
+				write!(out, "{}", self.package_separator())?;
+			}
+			if let Some(ref name) = package_name {
+				write!(out, "{}", self.package_header(name))?;
 			}
+			let built = match prepared {
+				PreparedTarget::Cached(rendered) => {
+					write!(out, "{rendered}")?;
+					continue;
+				}
+				PreparedTarget::Built(built) => built,
+			};
+			if !built.raw_files_content.is_empty() {
+				write!(out, "{}\n---\n\n", built.raw_files_content)?;
+			}
+			let Some((key, render_key)) = &built.render_cache else {
+				built.renderer.render_to(&built.crate_data, out)?;
+				continue;
+			};
+			let mut copying = CopyingWriter {
+				out: &mut *out,
+				copy: Vec::new(),
+			};
+			built.renderer.render_to(&built.crate_data, &mut copying)?;
+			let rendered = String::from_utf8_lossy(&copying.copy);
+			// Best-effort: a failed write only costs a re-render next time.
+			let _ = save_rendered(&self.cache_config, key, render_key, &rendered);
 		}
+		out.flush()?;
+		Ok(failures)
+	}
 
-		let separator = match self.render_format {
-			RenderFormat::Markdown => "\n\n---\n\n",
-			RenderFormat::Rust => {
-				"\n\n// ----------------------------------------------------------------------------\n\n"
+	/// Look up the rendered cache for one package of [`Self::render`] or
+	/// [`Self::render_to_writer`], building its crate and renderer on a miss.
+	///
+	/// `streamed` selects the cache entries written by [`Renderer::render_to`], whose spacing
+	/// differs slightly from [`Renderer::render`].
+	fn prepare_target(
+		&self,
+		rt: &ResolvedTarget,
+		features: &FeatureSelection,
+		private_items: bool,
+		implementation: bool,
+		raw_source: bool,
+		streamed: bool,
+	) -> Result<PreparedTarget> {
+		// Raw sources are read fresh on every run, and JSON documents are not plain text.
+		let render_cache = if raw_source || self.render_format == RenderFormat::Json {
+			None
+		} else {
+			self.rendered_cache_key(rt, features, private_items, implementation, streamed)
+		};
+		if let Some((key, render_key)) = &render_cache
+			&& let Ok(Some(rendered)) = self.verbosity.time("rendered cache lookup", || {
+				load_rendered(&self.cache_config, key, render_key)
+			}) {
+			return Ok(PreparedTarget::Cached(rendered));
+		}
+
+		let crate_data = rt.read_crate(
+			features.no_default_features,
+			features.all_features,
+			features.features.clone(),
+			private_items,
+			self.verbosity,
+			&self.cache_config,
+		)?;
+		let (renderer, raw_files_content) = self.target_renderer(
+			rt,
+			&crate_data,
+			features,
+			private_items,
+			implementation,
+			raw_source,
+		);
+		Ok(PreparedTarget::Built(Box::new(BuiltTarget {
+			crate_data,
+			renderer,
+			raw_files_content,
+			render_cache,
+		})))
+	}
+
+	/// Prefix `rendered` with the package header when several packages are rendered.
+	fn with_package_header(&self, rt: &ResolvedTarget, rendered: String) -> String {
+		match rt.package_name {
+			Some(ref name) => format!("{}{rendered}", self.package_header(name)),
+			None => rendered,
+		}
+	}
+
+	/// Render each resolved crate split into one file per top-level module.
+	///
+	/// Items declared directly in the crate root (or the targeted module) go to `lib.<ext>` and
+	/// each child module, with its whole subtree, to `<module>.<ext>`. When several packages are
+	/// resolved, each gets its own subdirectory. Pair the files with [`render_split_index`] for a
+	/// table of contents.
+	pub fn render_split(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<SplitFile>> {
+		let resolved_targets = self.resolve(target)?;
+		let nested = resolved_targets.len() > 1;
+		let extension = match self.render_format {
+			RenderFormat::Markdown => "md",
+			RenderFormat::Rust => "rs",
+			RenderFormat::Json => "json",
+			RenderFormat::Html => "html",
+		};
+
+		let mut files = Vec::new();
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let index = self.index_for(
+				&rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				private_items,
+			);
+			let plan = split::plan_split(&index, &rt.filter);
+			let dir = match &rt.package_name {
+				Some(name) if nested => PathBuf::from(name),
+				_ => PathBuf::new(),
+			};
+			let renderer = || {
+				self.renderer()
+					.with_private_items(private_items)
+					.with_source_root(rt.package_root().to_path_buf())
+			};
+
+			if !plan.root_items.is_empty() {
+				let crate_name = crate_data
+					.index
+					.get(&crate_data.root)
+					.and_then(|root| root.name.clone())
+					.unwrap_or_else(|| "crate".to_string());
+				let selection =
+					build_render_selection(&index, &plan.root_items, true, HashSet::new());
+				files.push(SplitFile {
+					path: dir.join(format!("lib.{extension}")),
+					module: if rt.filter.is_empty() {
+						crate_name
+					} else {
+						format!("{crate_name}::{}", rt.filter)
+					},
+					items: plan.root_items.len(),
+					contents: renderer()
+						.with_filter(&rt.filter)
+						.with_selection(selection)
+						.render(&crate_data)?,
+				});
+			}
+
+			for module in plan.modules {
+				let contents = renderer().with_filter(&module.filter).render(&crate_data)?;
+				if contents.trim().is_empty() {
+					continue;
+				}
+				files.push(SplitFile {
+					path: dir.join(format!("{}.{extension}", module.name)),
+					module: module.path,
+					items: module.items,
+					contents,
+				});
 			}
+		}
+		Ok(files)
+	}
+
+	/// Run structural export lints (dangling re-exports, empty modules, unreachable `pub` items).
+	///
+	/// The crate is always documented with private items so private re-export targets and
+	/// modules are visible to the checks.
+	pub fn lint_exports(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Vec<ExportLint>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut lints = Vec::new();
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				true,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			lints.extend(lint_exports(&crate_data, Some(rt.package_root())));
+		}
+		Ok(lints)
+	}
+
+	/// List the external crates referenced by the target's public API, with the items exposing them.
+	///
+	/// Toolchain crates (`std`, `core`, `alloc`) are only reported when `include_std` is set.
+	pub fn externs(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		include_std: bool,
+	) -> Result<Vec<ExternCrateUsage>> {
+		let crates = self.inspect(target, no_default_features, all_features, features, false)?;
+		Ok(crates
+			.iter()
+			.flat_map(|crate_data| extern_usage(crate_data, include_std))
+			.collect())
+	}
+
+	/// Summarize each resolved crate as a compact card.
+	///
+	/// Manifest details (description, MSRV, features) are filled in when the target resolves to a
+	/// package with a readable `Cargo.toml`. At most `top` key items are kept per crate.
+	pub fn summary(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		top: usize,
+	) -> Result<Vec<CrateSummary>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut summaries = Vec::with_capacity(resolved_targets.len());
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				false,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let metadata = rt.package_metadata()?;
+			summaries.push(summarize(&crate_data, metadata.as_ref(), top));
+		}
+		Ok(summaries)
+	}
+
+	/// Compute API-surface stats for each resolved crate.
+	///
+	/// Crates are built with private items so the public/private split can be counted. The token
+	/// estimate is taken from the public skeleton in the configured render format (Markdown when
+	/// that is JSON). With `by_feature`, crates are built with all features enabled so gated
+	/// items are present, and each item is attributed to the features its `cfg` attributes name.
+	pub fn stats(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		by_feature: bool,
+	) -> Result<Vec<CrateStats>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut stats = Vec::with_capacity(resolved_targets.len());
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features || by_feature,
+				features.clone(),
+				true,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let metadata = rt.package_metadata()?;
+			let format = match self.render_format {
+				RenderFormat::Json => RenderFormat::Markdown,
+				format => format,
+			};
+			let rendered = self
+				.renderer()
+				.with_format(format)
+				.with_source_root(rt.package_root().to_path_buf())
+				.render(&crate_data)?;
+			stats.push(crate_stats(&crate_data, metadata.as_ref(), by_feature, &rendered));
+		}
+		Ok(stats)
+	}
+
+	/// Collect the trait hierarchy of each resolved crate.
+	pub fn trait_graph(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<TraitGraph>> {
+		let crates =
+			self.inspect(target, no_default_features, all_features, features, private_items)?;
+		Ok(crates
+			.iter()
+			.map(|crate_data| trait_graph(crate_data, private_items))
+			.collect())
+	}
+
+	/// Report the dyn-compatibility of each resolved crate's traits, and which functions take
+	/// them as `dyn Trait` or as `impl Trait`/generic parameters.
+	pub fn dyn_compat(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<DynCompatReport>> {
+		let crates =
+			self.inspect(target, no_default_features, all_features, features, private_items)?;
+		Ok(crates
+			.iter()
+			.map(|crate_data| dyn_compat(crate_data, private_items))
+			.collect())
+	}
+
+	/// Find the local implementors of the trait named by `query`, or failing that the traits
+	/// implemented by the type it names, in each resolved crate.
+	///
+	/// Crates where `query` matches neither are left out. Auto-trait and blanket impls are only
+	/// listed when auto impls are enabled.
+	pub fn impls(
+		&self,
+		target: &str,
+		query: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<ImplsReport>> {
+		let crates =
+			self.inspect(target, no_default_features, all_features, features, private_items)?;
+		Ok(crates
+			.iter()
+			.filter_map(|crate_data| find_impls(crate_data, query, self.auto_impls))
+			.collect())
+	}
+
+	/// Report items of each resolved crate that the renderer dropped from the skeleton.
+	pub fn render_coverage(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<RenderCoverage>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut reports = Vec::with_capacity(resolved_targets.len());
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let renderer = self
+				.renderer()
+				.with_filter(&rt.filter)
+				.with_private_items(private_items)
+				.with_source_root(rt.package_root().to_path_buf());
+			reports.push(render_coverage(&crate_data, renderer)?);
+		}
+		Ok(reports)
+	}
+
+	/// Check which public items of each resolved crate lack doc comments.
+	pub fn doc_coverage(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Vec<DocCoverage>> {
+		let resolved_targets = self.resolve(target)?;
+		let mut reports = Vec::with_capacity(resolved_targets.len());
+		for rt in resolved_targets {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				false,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			reports.push(doc_coverage(&crate_data));
+		}
+		Ok(reports)
+	}
+
+	/// Compare the public API of two targets.
+	///
+	/// Both targets are built with the same feature flags and must each resolve to a single crate,
+	/// e.g. `serde@1.0.190` against `serde@1.0.200`, or a local path against a published version.
+	pub fn diff(
+		&self,
+		old_target: &str,
+		new_target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<ApiDiff> {
+		let old = self.inspect_single(old_target, no_default_features, all_features, features.clone())?;
+		let new = self.inspect_single(new_target, no_default_features, all_features, features)?;
+		Ok(ApiDiff::new(old_target, &old, new_target, &new))
+	}
+
+	/// Report where the sources of each resolved package live on disk: package root, manifest,
+	/// crate root file, and the registry archive for registry crates. Nothing is built.
+	pub fn locations(&self, target: &str) -> Result<Vec<PackageLocation>> {
+		let mut locations = Vec::new();
+		for rt in self.resolve(target)? {
+			locations.push(rt.location()?);
+		}
+		Ok(locations)
+	}
+
+	/// Create a cargo project in the system temp directory that depends on the package named by
+	/// `target` with the given features, and write the package's rendered skeleton next to it.
+	///
+	/// The project is kept on disk; the caller decides when to remove it.
+	pub fn sandbox(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Sandbox> {
+		let resolved_targets = self.resolve(target)?;
+		let [rt] = resolved_targets.as_slice() else {
+			return Err(RipdocError::InvalidTarget(format!(
+				"{target} resolves to {} packages; name a single crate",
+				resolved_targets.len()
+			)));
 		};
+		let location = rt.location()?;
+		let selection = FeatureSelection { no_default_features, all_features, features };
+		let skeleton = self.render(target, selection.clone(), false, false, false)?.rendered;
+		let skeleton_name = match self.render_format {
+			RenderFormat::Markdown => "SKELETON.md",
+			RenderFormat::Rust => "skeleton.rs",
+			RenderFormat::Json => "skeleton.json",
+			RenderFormat::Html => "skeleton.html",
+		};
+		Sandbox::create(&std::env::temp_dir(), &location, &selection, &skeleton, skeleton_name)
+	}
+
+	/// Compute a public API fingerprint for each resolved crate.
+	///
+	/// The hash ignores item order, formatting, and docs, so it only changes when the public API
+	/// does; see [`fingerprint`] for what contributes.
+	pub fn fingerprint(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Vec<ApiFingerprint>> {
+		let crates = self.inspect(target, no_default_features, all_features, features, false)?;
+		Ok(crates.iter().map(fingerprint).collect())
+	}
+
+	/// Record the public items of each resolved crate for `api-baseline` files.
+	pub fn api_baseline(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<ApiBaseline> {
+		let crates = self.inspect(target, no_default_features, all_features, features, false)?;
+		Ok(ApiBaseline {
+			crates: crates.iter().map(crate_baseline).collect(),
+		})
+	}
+
+	/// Export one self-contained record per item (path, kind, signature, docs, span,
+	/// visibility, cfgs) for every resolved crate.
+	pub fn export_items(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<ExportedItem>> {
+		let mut items = Vec::new();
+		for rt in self.resolve(target)? {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			items.extend(export_items(&crate_data, private_items, Some(rt.package_root())));
+		}
+		Ok(items)
+	}
+
+	/// Split the rendered output of every resolved crate into chunks of at most `max_chars`
+	/// characters, breaking only at module and item boundaries.
+	///
+	/// A single item larger than `max_chars` still becomes one (oversized) chunk.
+	pub fn export_chunks(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+		max_chars: usize,
+	) -> Result<Vec<Chunk>> {
+		let mut chunks = Vec::new();
+		for rt in self.resolve(target)? {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let index = self.index_for(
+				&rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				private_items,
+			);
+			let crate_name = crate_data
+				.index
+				.get(&crate_data.root)
+				.and_then(|root| root.name.clone())
+				.unwrap_or_else(|| "crate".to_string());
+			let make = || {
+				self.renderer()
+					.with_private_items(private_items)
+					.with_source_root(rt.package_root().to_path_buf())
+			};
+			let render = chunks::ChunkRenderer {
+				make: &make,
+				crate_data: &crate_data,
+				index: &index,
+			};
+			chunks::chunk_module(&render, &crate_name, &rt.filter, max_chars, &mut chunks)?;
+		}
+		Ok(chunks)
+	}
+
+	/// Bundle every module of the resolved crates with its docs, the Markdown files it pulls in
+	/// through `doc = include_str!(...)`, and the skeleton of its direct items.
+	///
+	/// Skeletons use the configured render format; Markdown reads best in bundles.
+	pub fn export_bundles(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<ModuleBundle>> {
+		let mut bundles = Vec::new();
+		for rt in self.resolve(target)? {
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let index = self.index_for(
+				&rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				private_items,
+			);
+			let make = || {
+				self.renderer()
+					.with_private_items(private_items)
+					.with_source_root(rt.package_root().to_path_buf())
+			};
+			bundles.extend(bundles::module_bundles(
+				&crate_data,
+				&index,
+				&make,
+				rt.package_root(),
+				&rt.filter,
+			)?);
+		}
+		Ok(bundles)
+	}
 
-		Ok(rendered_outputs.join(separator))
+	/// Resolve an item to the file, line, and column declaring it.
+	///
+	/// `item` is matched against canonical paths (see [`locate::ItemLocation`]); without it, the
+	/// module path of the target itself (e.g. `de::Deserialize` in `serde::de::Deserialize`) is
+	/// located. Every match is returned, across all resolved crates.
+	pub fn locate(
+		&self,
+		target: &str,
+		item: Option<&str>,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<ItemLocation>> {
+		let mut locations = Vec::new();
+		for rt in self.resolve(target)? {
+			let query = item.unwrap_or(&rt.filter);
+			if query.trim().is_empty() {
+				return Err(RipdocError::InvalidTarget(format!(
+					"No item to locate in '{target}'; name one, e.g. `{target}::Item`"
+				)));
+			}
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let index = self.index_for(
+				&rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				private_items,
+			);
+			locations.extend(locate::locate_items(&crate_data, &index, rt.package_root(), query));
+		}
+		Ok(locations)
+	}
+
+	/// Build manual pages for the items named by `item` (or the target's module path), in every
+	/// resolved crate.
+	pub fn man(
+		&self,
+		target: &str,
+		item: Option<&str>,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+		private_items: bool,
+	) -> Result<Vec<ManPage>> {
+		let mut pages = Vec::new();
+		for rt in self.resolve(target)? {
+			let query = item.unwrap_or(&rt.filter);
+			if query.trim().is_empty() {
+				return Err(RipdocError::InvalidTarget(format!(
+					"No item to document in '{target}'; name one, e.g. `{target}::Item`"
+				)));
+			}
+			let crate_data = rt.read_crate(
+				no_default_features,
+				all_features,
+				features.clone(),
+				private_items,
+				self.verbosity,
+				&self.cache_config,
+			)?;
+			let index = self.index_for(
+				&rt,
+				&crate_data,
+				no_default_features,
+				all_features,
+				&features,
+				private_items,
+			);
+			pages.extend(man::man_pages(&crate_data, &index, query));
+		}
+		Ok(pages)
+	}
+
+	/// Inspect a target that must resolve to exactly one crate.
+	fn inspect_single(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Result<Crate> {
+		let mut crates = self.inspect(target, no_default_features, all_features, features, false)?;
+		if crates.len() != 1 {
+			return Err(error::RipdocError::InvalidTarget(format!(
+				"Target '{target}' resolves to {} crates; diff needs exactly one",
+				crates.len()
+			)));
+		}
+		Ok(crates.remove(0))
 	}
 
 	/// Returns a pretty-printed version of the crate's JSON representation.
@@ -518,3 +2407,50 @@ impl Ripdoc {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	#[test]
+	fn parallel_results_keep_target_order_and_errors() {
+		let targets: Vec<u64> = (0..16).collect();
+		// Earlier targets take longer, so workers finish them last.
+		let per_target = |target: &u64| -> Result<u64> {
+			std::thread::sleep(Duration::from_millis(16 - target));
+			if target.is_multiple_of(5) {
+				Err(RipdocError::InvalidTarget(format!("target {target}")))
+			} else {
+				Ok(target * 10)
+			}
+		};
+
+		let results = map_on_workers(&targets, 4, &per_target);
+		assert_eq!(results.len(), targets.len());
+		for (target, result) in targets.iter().zip(results) {
+			match result {
+				Ok(value) => assert_eq!(value, target * 10),
+				Err(err) => {
+					assert_eq!(target % 5, 0, "unexpected failure: {err}");
+					assert!(err.to_string().contains(&format!("target {target}")), "{err}");
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn parallel_runs_record_into_the_callers_timings() {
+		let timings = crate::timings::Timings::new();
+		let targets: Vec<u64> = (0..8).collect();
+		let per_target = |_: &u64| -> Result<()> {
+			crate::timings::record("work", Duration::from_millis(1));
+			Ok(())
+		};
+		timings.scope(|| map_on_workers(&targets, 4, &per_target));
+		let recorded = timings.take();
+		assert_eq!(recorded.len(), 1);
+		assert_eq!(recorded[0].calls, targets.len());
+	}
+}
@@ -1,7 +1,10 @@
 //! Pattern utilities for search query handling.
 //!
 //! Provides regex escaping and symbol stripping functions that preserve pipe characters
-//! for OR search functionality.
+//! for OR search functionality, signature normalization for the signature domain, and parsing
+//! of per-domain query specifications such as `name:Builder doc:timeout`.
+
+use super::search::SearchDomain;
 
 /// Escape regex metacharacters except pipes, enabling literal matching with OR support.
 ///
@@ -34,6 +37,135 @@ pub fn strip_symbols_preserving_pipes(text: &str) -> String {
 		.collect()
 }
 
+/// Fully qualified standard library paths rewritten to their prelude names.
+const SIGNATURE_ALIASES: &[(&str, &str)] = &[
+	("std::string::String", "String"),
+	("alloc::string::String", "String"),
+	("std::vec::Vec", "Vec"),
+	("alloc::vec::Vec", "Vec"),
+	("std::boxed::Box", "Box"),
+	("alloc::boxed::Box", "Box"),
+	("std::option::Option", "Option"),
+	("core::option::Option", "Option"),
+	("std::result::Result", "Result"),
+	("core::result::Result", "Result"),
+	("std::rc::Rc", "Rc"),
+	("alloc::rc::Rc", "Rc"),
+	("std::sync::Arc", "Arc"),
+	("alloc::sync::Arc", "Arc"),
+	("std::borrow::Cow", "Cow"),
+	("alloc::borrow::Cow", "Cow"),
+];
+
+/// Normalize a signature (or signature query) into a canonical spelling.
+///
+/// Whitespace is dropped around punctuation and collapsed to a single space between words, and
+/// fully qualified prelude types lose their path, so `fn new(cfg:&std::string::String)` and
+/// `fn new(cfg: &String)` both become `fn new(cfg:&String)`.
+pub fn normalize_signature(text: &str) -> String {
+	let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+	let mut spaced = String::with_capacity(text.len());
+	let mut pending_space = false;
+	for ch in text.chars() {
+		if ch.is_whitespace() {
+			pending_space = true;
+			continue;
+		}
+		if pending_space && is_word(ch) && spaced.chars().next_back().is_some_and(is_word) {
+			spaced.push(' ');
+		}
+		pending_space = false;
+		spaced.push(ch);
+	}
+
+	let mut normalized = spaced;
+	for (full, short) in SIGNATURE_ALIASES {
+		normalized = replace_path(&normalized, full, short);
+	}
+	normalized
+}
+
+/// Replace `full` with `short` wherever it starts a path (not preceded by `::` or a word).
+fn replace_path(text: &str, full: &str, short: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(pos) = rest.find(full) {
+		out.push_str(&rest[..pos]);
+		let after = &rest[pos + full.len()..];
+		let bounded_left =
+			!(out.ends_with("::") || out.ends_with(|c: char| c.is_alphanumeric() || c == '_'));
+		let bounded_right = !after.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+		out.push_str(if bounded_left && bounded_right { short } else { full });
+		rest = after;
+	}
+	out.push_str(rest);
+	out
+}
+
+/// One term of a per-domain query specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTerm {
+	/// Domain restriction from a `domain:` prefix, or `None` to use the search's default domains.
+	pub domains: Option<SearchDomain>,
+	/// Text to match, with any surrounding quotes removed.
+	pub text: String,
+}
+
+/// Map a query prefix such as `name` or `doc` onto its search domain.
+fn domain_for_prefix(prefix: &str) -> Option<SearchDomain> {
+	match prefix {
+		"name" | "names" => Some(SearchDomain::NAMES),
+		"doc" | "docs" => Some(SearchDomain::DOCS),
+		"path" | "paths" => Some(SearchDomain::PATHS),
+		"sig" | "signature" | "signatures" => Some(SearchDomain::SIGNATURES),
+		_ => None,
+	}
+}
+
+/// Parse a query of whitespace-separated `domain:text` terms.
+///
+/// Recognized prefixes are `name:`, `doc:`, `path:`, and `sig:` (plus plural and long forms).
+/// Values containing spaces can be double-quoted (`sig:"fn new(cfg: &Config)"`). Terms without a
+/// prefix search the default domains. Every term must match for an item to be reported.
+///
+/// Returns `None` when no term carries a domain prefix, so plain queries (including ones with
+/// spaces or `::` paths) keep their whole-string meaning.
+pub fn parse_query_spec(query: &str) -> Option<Vec<QueryTerm>> {
+	let mut terms = Vec::new();
+	let mut has_prefix = false;
+	let mut chars = query.trim().chars().peekable();
+
+	while chars.peek().is_some() {
+		while chars.next_if(|c| c.is_whitespace()).is_some() {}
+		let starts_quoted = chars.peek() == Some(&'"');
+		let mut token = String::new();
+		let mut quoted = false;
+		while let Some(ch) = chars.next_if(|c| quoted || !c.is_whitespace()) {
+			if ch == '"' {
+				quoted = !quoted;
+			} else {
+				token.push(ch);
+			}
+		}
+		if token.is_empty() {
+			continue;
+		}
+
+		let prefixed = token
+			.split_once(':')
+			.filter(|(_, rest)| !starts_quoted && !rest.starts_with(':') && !rest.is_empty())
+			.and_then(|(prefix, rest)| Some((domain_for_prefix(prefix)?, rest.to_string())));
+		let (domains, text) = match prefixed {
+			Some((domain, rest)) => (Some(domain), rest),
+			None => (None, token),
+		};
+		has_prefix |= domains.is_some();
+		terms.push(QueryTerm { domains, text });
+	}
+
+	has_prefix.then_some(terms)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -96,4 +228,72 @@ mod tests {
 			"fn init|fn clone"
 		);
 	}
+
+	#[test]
+	fn test_normalize_signature_spacing() {
+		assert_eq!(normalize_signature("fn new(cfg:&Config)"), "fn new(cfg:&Config)");
+		assert_eq!(
+			normalize_signature("fn new( cfg : & Config ) -> Self"),
+			"fn new(cfg:&Config)->Self"
+		);
+		assert_eq!(normalize_signature("&'a  mut T"), "&'a mut T");
+	}
+
+	#[test]
+	fn test_normalize_signature_aliases() {
+		assert_eq!(
+			normalize_signature("fn name(&self) -> std::string::String"),
+			"fn name(&self)->String"
+		);
+		assert_eq!(
+			normalize_signature("fn get(&self) -> core::option::Option<&T>"),
+			"fn get(&self)->Option<&T>"
+		);
+		assert_eq!(
+			normalize_signature("fn f(x: std::vec::Vec<u8>)"),
+			"fn f(x:Vec<u8>)"
+		);
+		assert_eq!(
+			normalize_signature("fn f(x: my::std::string::String)"),
+			"fn f(x:my::std::string::String)"
+		);
+	}
+
+	#[test]
+	fn test_parse_query_spec_domains() {
+		let terms = parse_query_spec("name:Builder doc:timeout path:net").unwrap();
+		assert_eq!(
+			terms,
+			vec![
+				QueryTerm {
+					domains: Some(SearchDomain::NAMES),
+					text: "Builder".to_string(),
+				},
+				QueryTerm {
+					domains: Some(SearchDomain::DOCS),
+					text: "timeout".to_string(),
+				},
+				QueryTerm {
+					domains: Some(SearchDomain::PATHS),
+					text: "net".to_string(),
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_parse_query_spec_quotes_and_plain_terms() {
+		let terms = parse_query_spec(r#"sig:"fn new(cfg: &Config)" Builder"#).unwrap();
+		assert_eq!(terms[0].domains, Some(SearchDomain::SIGNATURES));
+		assert_eq!(terms[0].text, "fn new(cfg: &Config)");
+		assert_eq!(terms[1].domains, None);
+		assert_eq!(terms[1].text, "Builder");
+	}
+
+	#[test]
+	fn test_parse_query_spec_ignores_plain_queries() {
+		assert_eq!(parse_query_spec("widget docs"), None);
+		assert_eq!(parse_query_spec("std::io::Read"), None);
+		assert_eq!(parse_query_spec("other:thing"), None);
+	}
 }
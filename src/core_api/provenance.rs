@@ -0,0 +1,167 @@
+//! Headers recording how a rendered skeleton was produced.
+//!
+//! Checked-in or shared skeletons go stale silently. A provenance header names the ripdoc and
+//! toolchain versions, the target, the command line, and the time of the run, so a reader can
+//! tell what the output reflects and regenerate it the same way.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cargo_utils::get_toolchain_version;
+use crate::render::RenderFormat;
+
+/// Facts about one ripdoc run, written ahead of its output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+	/// Version of ripdoc that rendered the output.
+	pub ripdoc_version: String,
+	/// `rustc --version` of the toolchain that built the rustdoc JSON, if it could be probed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub toolchain: Option<String>,
+	/// Target spec as given on the command line.
+	pub target: String,
+	/// Command-line arguments, program name first.
+	pub command: Vec<String>,
+	/// UTC time of the run, in RFC 3339 form.
+	pub generated_at: String,
+}
+
+impl Provenance {
+	/// Record the current run of `target` invoked with `args`.
+	pub fn capture(target: &str, args: impl IntoIterator<Item = String>) -> Self {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|elapsed| elapsed.as_secs())
+			.unwrap_or_default();
+		Self {
+			ripdoc_version: env!("CARGO_PKG_VERSION").to_string(),
+			toolchain: get_toolchain_version(),
+			target: target.to_string(),
+			command: args.into_iter().collect(),
+			generated_at: format_timestamp(now),
+		}
+	}
+
+	/// The header as a comment in `format`, ending with a blank line.
+	///
+	/// JSON has no comment syntax, so it gets no header.
+	pub fn header(&self, format: RenderFormat) -> Option<String> {
+		let mut lines = vec![format!("Generated by ripdoc {}", self.ripdoc_version)];
+		if let Some(toolchain) = &self.toolchain {
+			lines.push(format!("Toolchain: {toolchain}"));
+		}
+		lines.push(format!("Target: {}", self.target));
+		if !self.command.is_empty() {
+			let command: Vec<String> = self.command.iter().map(|arg| shell_quote(arg)).collect();
+			lines.push(format!("Command: {}", command.join(" ")));
+		}
+		lines.push(format!("Generated at: {}", self.generated_at));
+
+		match format {
+			RenderFormat::Rust => {
+				let mut out: String = lines.iter().map(|line| format!("// {line}\n")).collect();
+				out.push('\n');
+				Some(out)
+			}
+			RenderFormat::Markdown | RenderFormat::Html => {
+				let mut out = String::from("<!--\n");
+				for line in &lines {
+					// Flags keep their `--`; only a `-->` would end the comment early.
+					out.push_str(&line.replace("-->", "-- >"));
+					out.push('\n');
+				}
+				out.push_str("-->\n\n");
+				Some(out)
+			}
+			RenderFormat::Json => None,
+		}
+	}
+}
+
+/// Quote `arg` for a POSIX shell unless it is made only of characters that need no quoting.
+fn shell_quote(arg: &str) -> String {
+	let plain = !arg.is_empty()
+		&& arg
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || "-_./:=,@+%".contains(c));
+	if plain {
+		arg.to_string()
+	} else {
+		format!("'{}'", arg.replace('\'', r"'\''"))
+	}
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+fn format_timestamp(secs: u64) -> String {
+	let days = (secs / 86_400) as i64;
+	let rem = secs % 86_400;
+	let (year, month, day) = civil_from_days(days);
+	format!(
+		"{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+		rem / 3600,
+		rem % 3600 / 60,
+		rem % 60
+	)
+}
+
+/// Proleptic Gregorian date of `days` since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z.rem_euclid(146_097);
+	let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+	let year = yoe + era * 400 + i64::from(month <= 2);
+	(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sample() -> Provenance {
+		Provenance {
+			ripdoc_version: "1.2.3".to_string(),
+			toolchain: Some("rustc 1.90.0-nightly".to_string()),
+			target: "serde".to_string(),
+			command: vec!["ripdoc".into(), "print".into(), "serde".into(), "--search".into(), "a b".into()],
+			generated_at: "2024-02-29T12:00:00Z".to_string(),
+		}
+	}
+
+	#[test]
+	fn timestamps_are_utc_rfc3339() {
+		assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+		assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+		assert_eq!(format_timestamp(1_709_208_000), "2024-02-29T12:00:00Z");
+		assert_eq!(format_timestamp(1_735_689_599), "2024-12-31T23:59:59Z");
+	}
+
+	#[test]
+	fn headers_use_the_format_comment_syntax() {
+		let rust = sample().header(RenderFormat::Rust).unwrap();
+		assert!(rust.starts_with("// Generated by ripdoc 1.2.3\n"));
+		assert!(rust.contains("// Command: ripdoc print serde --search 'a b'\n"));
+		assert!(rust.ends_with("Z\n\n"));
+
+		let markdown = sample().header(RenderFormat::Markdown).unwrap();
+		assert!(markdown.starts_with("<!--\n"));
+		assert!(markdown.contains("Command: ripdoc print serde --search 'a b'\n"));
+		assert!(markdown.ends_with("-->\n\n"));
+
+		assert!(sample().header(RenderFormat::Json).is_none());
+	}
+
+	#[test]
+	fn html_comments_cannot_be_closed_by_arguments() {
+		let mut provenance = sample();
+		provenance.command.push("a-->b".into());
+		let html = provenance.header(RenderFormat::Html).unwrap();
+		assert!(html.contains("--search 'a b' 'a-- >b'\n"), "{html}");
+		assert_eq!(html.matches("-->").count(), 1, "{html}");
+	}
+}
@@ -0,0 +1,129 @@
+//! Throwaway cargo projects for trying out a crate (`ripdoc try`).
+//!
+//! A sandbox is a binary package in the system temp directory that depends on the crate with the
+//! requested features, next to the crate's rendered skeleton, so experimenting with an unfamiliar
+//! dependency starts from a project that already builds.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{FeatureSelection, Result};
+use super::error::RipdocError;
+use crate::cargo_utils::PackageLocation;
+
+/// A sandbox project created on disk.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Sandbox {
+	/// Project directory; it is kept after ripdoc exits.
+	pub dir: PathBuf,
+	/// The project's `Cargo.toml`.
+	pub manifest: PathBuf,
+	/// The project's `src/main.rs`.
+	pub main: PathBuf,
+	/// The rendered skeleton of the crate.
+	pub skeleton: PathBuf,
+	/// Package the project depends on.
+	pub package: String,
+	/// Version the dependency is pinned to, for registry crates.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+}
+
+impl Sandbox {
+	/// Create a sandbox for the package at `location` in a new directory under `parent`, writing
+	/// `skeleton` to `skeleton_name` inside it.
+	///
+	/// Registry crates are pinned to their exact version; other packages are depended on by
+	/// path.
+	pub fn create(
+		parent: &Path,
+		location: &PackageLocation,
+		features: &FeatureSelection,
+		skeleton: &str,
+		skeleton_name: &str,
+	) -> Result<Self> {
+		let package = location.package.clone().ok_or_else(|| {
+			RipdocError::InvalidTarget(format!(
+				"{} has no package name to depend on",
+				location.root.display()
+			))
+		})?;
+		if location.manifest.is_none() {
+			return Err(RipdocError::InvalidTarget(format!(
+				"{package} was read from rustdoc JSON and has no sources to depend on"
+			)));
+		}
+		let version = location
+			.registry_archive
+			.as_ref()
+			.and(location.version.clone());
+
+		let dir = tempfile::Builder::new()
+			.prefix(&format!("ripdoc-try-{package}-"))
+			.tempdir_in(parent)?
+			.keep();
+		let sandbox = Self {
+			manifest: dir.join("Cargo.toml"),
+			main: dir.join("src").join("main.rs"),
+			skeleton: dir.join(skeleton_name),
+			dir,
+			package,
+			version,
+		};
+		fs::create_dir_all(sandbox.dir.join("src"))?;
+		fs::write(&sandbox.manifest, sandbox.manifest_toml(location, features))?;
+		fs::write(&sandbox.main, sandbox.main_rs(skeleton_name))?;
+		fs::write(&sandbox.skeleton, skeleton)?;
+		Ok(sandbox)
+	}
+
+	fn manifest_toml(&self, location: &PackageLocation, features: &FeatureSelection) -> String {
+		let mut dependency = match &self.version {
+			Some(version) => format!("version = \"={version}\""),
+			None => format!("path = {}", toml_string(&location.root.display().to_string())),
+		};
+		if features.no_default_features {
+			dependency.push_str(", default-features = false");
+		}
+		// Dependencies cannot ask for every feature, so `--all-features` lists them one by one.
+		let enabled = if features.all_features {
+			declared_features(location)
+		} else {
+			features.features.clone()
+		};
+		if !enabled.is_empty() {
+			let list: Vec<String> = enabled.iter().map(|feature| toml_string(feature)).collect();
+			dependency.push_str(&format!(", features = [{}]", list.join(", ")));
+		}
+		format!(
+			"[package]\nname = \"try-{}\"\nversion = \"0.1.0\"\nedition = \"2024\"\npublish = false\n\n\
+			 [dependencies]\n{} = {{ {dependency} }}\n",
+			self.package.replace('_', "-"),
+			self.package
+		)
+	}
+
+	fn main_rs(&self, skeleton_name: &str) -> String {
+		format!(
+			"//! Sandbox for trying out `{package}`; its API skeleton is in `{skeleton_name}`.\n\n\
+			 use {krate} as _;\n\nfn main() {{}}\n",
+			package = self.package,
+			krate = self.package.replace('-', "_"),
+		)
+	}
+}
+
+/// Features declared in the package's manifest, sorted by name.
+fn declared_features(location: &PackageLocation) -> Vec<String> {
+	location
+		.manifest
+		.as_ref()
+		.and_then(|manifest| cargo_toml::Manifest::from_path(manifest).ok())
+		.map(|manifest| manifest.features.into_keys().collect())
+		.unwrap_or_default()
+}
+
+/// Quote a string as a TOML basic string.
+fn toml_string(value: &str) -> String {
+	format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,100 @@
+//! Render selections saved to disk so a curated item set can be re-rendered without a search.
+//!
+//! Rustdoc item IDs are not stable across builds, so a saved selection records canonical paths
+//! and kinds; they are resolved against a fresh search index when the selection is applied.
+
+use std::fs;
+use std::path::Path;
+
+use super::error::Result;
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Current on-disk format version.
+const SELECTION_VERSION: u32 = 1;
+
+/// One item pinned by a saved selection.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SelectedItem {
+	/// Canonical `::` path, including the crate name.
+	pub path: String,
+	/// Item kind, used to tell apart items sharing a path (e.g. a struct and its impls).
+	pub kind: SearchItemKind,
+}
+
+/// A serializable set of items to render, as written by `--save-selection`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SavedSelection {
+	/// Format version.
+	pub version: u32,
+	/// Whether matched containers were expanded to include their children.
+	#[serde(default)]
+	pub expand_containers: bool,
+	/// Items to render, in the order they were matched.
+	pub items: Vec<SelectedItem>,
+}
+
+impl SavedSelection {
+	/// Capture the items matched by a search.
+	pub fn from_results(results: &[SearchResult], expand_containers: bool) -> Self {
+		let mut items: Vec<SelectedItem> = Vec::with_capacity(results.len());
+		for result in results {
+			let item = SelectedItem {
+				path: result.path_string.clone(),
+				kind: result.kind,
+			};
+			if !items.contains(&item) {
+				items.push(item);
+			}
+		}
+		Self {
+			version: SELECTION_VERSION,
+			expand_containers,
+			items,
+		}
+	}
+
+	/// Read a selection previously written with [`Self::save`].
+	pub fn load(path: &Path) -> Result<Self> {
+		let selection: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+		if selection.version > SELECTION_VERSION {
+			return Err(super::error::RipdocError::InvalidTarget(format!(
+				"selection file {} has version {}, newer than the supported version {SELECTION_VERSION}",
+				path.display(),
+				selection.version
+			)));
+		}
+		Ok(selection)
+	}
+
+	/// Write the selection as pretty-printed JSON.
+	pub fn save(&self, path: &Path) -> Result<()> {
+		fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+		Ok(())
+	}
+
+	/// Index entries matching the selected items.
+	pub fn resolve(&self, index: &SearchIndex) -> Vec<SearchResult> {
+		index
+			.entries()
+			.iter()
+			.filter(|entry| self.contains(&entry.path_string, entry.kind))
+			.cloned()
+			.collect()
+	}
+
+	/// Selected items that do not appear in `results`, e.g. because they were renamed or removed.
+	pub fn missing<'a>(&'a self, results: &[SearchResult]) -> Vec<&'a SelectedItem> {
+		self.items
+			.iter()
+			.filter(|item| {
+				!results
+					.iter()
+					.any(|result| result.path_string == item.path && result.kind == item.kind)
+			})
+			.collect()
+	}
+
+	fn contains(&self, path: &str, kind: SearchItemKind) -> bool {
+		self.items.iter().any(|item| item.path == path && item.kind == kind)
+	}
+}
@@ -0,0 +1,121 @@
+//! Subsequence scoring for fuzzy search, in the spirit of fzf's v1 algorithm.
+//!
+//! A candidate matches when every query character appears in it in order. The match is found
+//! greedily left to right, then tightened by scanning backwards from its end so the shortest
+//! window ending there is scored. Consecutive runs and characters at word boundaries
+//! (after `_`, `:`, or at a lowercase-to-uppercase step) score higher; gaps cost a little.
+
+/// Points for every matched character.
+const SCORE_MATCH: i32 = 16;
+/// Extra points for a match directly following the previous one.
+const BONUS_CONSECUTIVE: i32 = 8;
+/// Extra points for a match at the start of a word.
+const BONUS_BOUNDARY: i32 = 10;
+/// Extra points when the first query character matches the first candidate character.
+const BONUS_FIRST: i32 = 8;
+/// Penalty per skipped candidate character inside the matched window.
+const PENALTY_GAP: i32 = 1;
+
+/// Score `candidate` against `query`, or `None` if the query is not a subsequence.
+///
+/// Higher scores are better; an exact match of the whole candidate scores highest.
+pub fn fuzzy_score(candidate: &str, query: &str, case_sensitive: bool) -> Option<u32> {
+	let fold = |c: char| {
+		if case_sensitive {
+			c
+		} else {
+			c.to_ascii_lowercase()
+		}
+	};
+	let original: Vec<char> = candidate.chars().collect();
+	let text: Vec<char> = original.iter().copied().map(fold).collect();
+	let pattern: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).map(fold).collect();
+	if pattern.is_empty() || pattern.len() > text.len() {
+		return None;
+	}
+
+	// Forward pass: find where the earliest complete match ends.
+	let mut pi = 0;
+	let mut end = 0;
+	for (ti, &ch) in text.iter().enumerate() {
+		if ch == pattern[pi] {
+			pi += 1;
+			if pi == pattern.len() {
+				end = ti;
+				break;
+			}
+		}
+	}
+	if pi < pattern.len() {
+		return None;
+	}
+
+	// Backward pass: the latest start that still matches, giving the tightest window.
+	let mut positions = Vec::with_capacity(pattern.len());
+	let mut pi = pattern.len();
+	for ti in (0..=end).rev() {
+		if text[ti] == pattern[pi - 1] {
+			positions.push(ti);
+			pi -= 1;
+			if pi == 0 {
+				break;
+			}
+		}
+	}
+	positions.reverse();
+
+	let mut score = 0;
+	for (idx, &pos) in positions.iter().enumerate() {
+		score += SCORE_MATCH;
+		if is_boundary(&original, pos) {
+			score += BONUS_BOUNDARY;
+		}
+		match idx.checked_sub(1).map(|prev| positions[prev]) {
+			Some(prev) if prev + 1 == pos => score += BONUS_CONSECUTIVE,
+			Some(prev) => score -= PENALTY_GAP * (pos - prev - 1) as i32,
+			None if pos == 0 => score += BONUS_FIRST,
+			None => {}
+		}
+	}
+	if positions.len() == text.len() {
+		score += BONUS_FIRST;
+	}
+	Some(score.max(1) as u32)
+}
+
+/// Whether the character at `pos` starts a word.
+fn is_boundary(chars: &[char], pos: usize) -> bool {
+	let Some(prev) = pos.checked_sub(1).map(|prev| chars[prev]) else {
+		return true;
+	};
+	let current = chars[pos];
+	matches!(prev, '_' | ':' | '-' | '.' | ' ' | '<' | '(')
+		|| (prev.is_lowercase() && current.is_uppercase())
+		|| (!prev.is_alphanumeric() && current.is_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn matches_abbreviated_subsequences() {
+		assert!(fuzzy_score("spawn_blocking", "spwn_blkng", false).is_some());
+		assert!(fuzzy_score("spawn_blocking", "blockspawn", false).is_none());
+		assert!(fuzzy_score("HashMap", "hm", false).is_some());
+		assert!(fuzzy_score("HashMap", "hm", true).is_none());
+	}
+
+	#[test]
+	fn ranks_tighter_and_boundary_matches_higher() {
+		let exact = fuzzy_score("spawn", "spawn", false).unwrap();
+		let prefix = fuzzy_score("spawn_blocking", "spawn", false).unwrap();
+		let scattered = fuzzy_score("sleep_pause_await_now", "spawn", false).unwrap();
+		assert!(exact > prefix);
+		assert!(prefix > scattered);
+
+		let boundary = fuzzy_score("spawn_blocking", "sb", false).unwrap();
+		let inner = fuzzy_score("sandbox", "sb", false).unwrap();
+		assert!(boundary > inner);
+	}
+}
@@ -0,0 +1,118 @@
+use rustdoc_types::{Crate, Id, ItemEnum, Type};
+
+use crate::render::render_type;
+
+/// One trait implementation: which trait, for which type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplRecord {
+	/// Id of the `impl` block.
+	pub impl_id: Id,
+	/// Canonical path of the implemented trait.
+	pub trait_path: String,
+	/// Canonical path of the implementing type, or the rendered type for non-path types.
+	pub type_path: String,
+	/// Whether the implementing type is declared in the crate itself.
+	pub local_type: bool,
+	/// Auto-trait impl synthesized by rustdoc (`Send`, `Sync`, ...).
+	pub synthetic: bool,
+	/// Instance of a blanket impl (`impl<T> From<T> for T`, ...).
+	pub blanket: bool,
+}
+
+/// Reverse index over a crate's trait impls, answering "who implements this trait" and "which
+/// traits does this type implement".
+///
+/// Built alongside [`super::SearchIndex`], which only records impl members. Inherent impls are
+/// not indexed.
+#[derive(Debug, Clone, Default)]
+pub struct ImplIndex {
+	records: Vec<ImplRecord>,
+}
+
+impl ImplIndex {
+	/// Index every trait impl in `crate_data`.
+	pub fn build(crate_data: &Crate) -> Self {
+		let mut records = Vec::new();
+		for (id, item) in &crate_data.index {
+			let ItemEnum::Impl(impl_) = &item.inner else {
+				continue;
+			};
+			let Some(trait_) = &impl_.trait_ else {
+				continue;
+			};
+			let (type_path, local_type) = match &impl_.for_ {
+				Type::ResolvedPath(path) => (
+					canonical_path(crate_data, &path.id).unwrap_or_else(|| path.path.clone()),
+					is_local(crate_data, &path.id),
+				),
+				other => (render_type(other), false),
+			};
+			records.push(ImplRecord {
+				impl_id: *id,
+				trait_path: canonical_path(crate_data, &trait_.id)
+					.unwrap_or_else(|| trait_.path.clone()),
+				type_path,
+				local_type,
+				synthetic: impl_.is_synthetic,
+				blanket: impl_.blanket_impl.is_some(),
+			});
+		}
+		records.sort_by(|a, b| (&a.trait_path, &a.type_path).cmp(&(&b.trait_path, &b.type_path)));
+		Self { records }
+	}
+
+	/// All indexed impls, sorted by trait then type.
+	pub fn records(&self) -> &[ImplRecord] {
+		&self.records
+	}
+
+	/// Impls of the trait named by `query` (a full path or a trailing path suffix such as
+	/// `Display` or `fmt::Display`) for types declared in the crate.
+	pub fn implementors(&self, query: &str) -> Vec<&ImplRecord> {
+		self.records
+			.iter()
+			.filter(|record| record.local_type && path_matches(&record.trait_path, query))
+			.collect()
+	}
+
+	/// Trait impls of the type named by `query`.
+	pub fn traits_of(&self, query: &str) -> Vec<&ImplRecord> {
+		self.records
+			.iter()
+			.filter(|record| path_matches(&record.type_path, query))
+			.collect()
+	}
+}
+
+/// Whether `path` is `query` or ends with `::query`.
+fn path_matches(path: &str, query: &str) -> bool {
+	let query = query.trim_start_matches("::");
+	path == query
+		|| path
+			.strip_suffix(query)
+			.is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+fn canonical_path(crate_data: &Crate, id: &Id) -> Option<String> {
+	crate_data.paths.get(id).map(|summary| summary.path.join("::"))
+}
+
+fn is_local(crate_data: &Crate, id: &Id) -> bool {
+	crate_data
+		.paths
+		.get(id)
+		.map_or(crate_data.index.contains_key(id), |summary| summary.crate_id == 0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn paths_match_whole_trailing_segments() {
+		assert!(path_matches("core::fmt::Display", "Display"));
+		assert!(path_matches("core::fmt::Display", "fmt::Display"));
+		assert!(path_matches("core::fmt::Display", "core::fmt::Display"));
+		assert!(!path_matches("core::fmt::Display", "play"));
+	}
+}
@@ -1,26 +1,51 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+use once_cell::sync::Lazy;
 use regex::Regex;
 use rustdoc_types::{Crate, Id, Item, ItemEnum, Module, Struct, StructKind, Visibility};
 
-use super::super::pattern::{escape_regex_preserving_pipes, strip_symbols_preserving_pipes};
+use super::super::export::{cfg_predicates, is_doc_hidden};
+use super::super::pattern::{
+	QueryTerm, escape_regex_preserving_pipes, normalize_signature, parse_query_spec,
+	strip_symbols_preserving_pipes,
+};
+use super::fuzzy::fuzzy_score;
+use super::signature_query::SignatureQuery;
 use super::types::{
 	SearchDomain, SearchItemKind, SearchOptions, SearchPathSegment, SearchResult, SourceLocation,
 };
 use crate::render::{render_name, render_path, render_type, signatures as signature};
+use crate::timings;
 
 /// Index of crate items prepared for search queries.
 #[derive(Debug, Clone)]
 pub struct SearchIndex<'a> {
 	crate_data: &'a Crate,
-	entries: Vec<SearchResult>,
-	id_to_entry: HashMap<Id, usize>,
+	entries: Arc<Vec<SearchResult>>,
+	id_to_entry: Arc<HashMap<Id, usize>>,
+	/// [`normalize_signature`] of each entry's signature, in entry order.
+	normalized_signatures: Arc<Vec<Option<String>>>,
 }
 
+/// Index entries kept warm for long-lived processes, keyed by [`SearchIndex::build_warm`] keys.
+type WarmEntries = (Arc<Vec<SearchResult>>, Arc<HashMap<Id, usize>>, Arc<Vec<Option<String>>>);
+
+static WARM_INDEXES: Lazy<Mutex<HashMap<String, WarmEntries>>> =
+	Lazy::new(|| Mutex::new(HashMap::new()));
+
 impl<'a> SearchIndex<'a> {
 	/// Construct a new index by traversing the provided crate.
 	pub fn build(crate_data: &'a Crate, include_private: bool, source_root: Option<&Path>) -> Self {
+		timings::time("index build", || Self::traverse_crate(crate_data, include_private, source_root))
+	}
+
+	fn traverse_crate(
+		crate_data: &'a Crate,
+		include_private: bool,
+		source_root: Option<&Path>,
+	) -> Self {
 		let mut builder = IndexBuilder::new(crate_data, include_private, source_root);
 		builder.traverse();
 		let mut entries = builder.finish_entries();
@@ -67,10 +92,74 @@ impl<'a> SearchIndex<'a> {
 		for (idx, entry) in entries.iter().enumerate() {
 			id_to_entry.entry(entry.item_id).or_insert(idx);
 		}
+		let normalized_signatures = entries
+			.iter()
+			.map(|entry| entry.signature.as_deref().map(normalize_signature))
+			.collect();
 		Self {
 			crate_data,
-			entries,
-			id_to_entry,
+			entries: Arc::new(entries),
+			id_to_entry: Arc::new(id_to_entry),
+			normalized_signatures: Arc::new(normalized_signatures),
+		}
+	}
+
+	/// Like [`Self::build`], but reuses the entries built earlier in this process for `key`.
+	///
+	/// `key` must identify the crate contents and the index options (see
+	/// [`crate::cargo_utils::CacheKey`]); `None` always builds a fresh index. Used with the
+	/// in-memory crate cache so `ripdoc daemon` answers repeated searches without re-indexing.
+	pub fn build_warm(
+		crate_data: &'a Crate,
+		include_private: bool,
+		source_root: Option<&Path>,
+		key: Option<&str>,
+	) -> Self {
+		let Some(key) = key else {
+			return Self::build(crate_data, include_private, source_root);
+		};
+		if let Some((entries, id_to_entry, normalized_signatures)) = WARM_INDEXES
+			.lock()
+			.ok()
+			.and_then(|warm| warm.get(key).cloned())
+		{
+			return Self {
+				crate_data,
+				entries,
+				id_to_entry,
+				normalized_signatures,
+			};
+		}
+		let index = Self::build(crate_data, include_private, source_root);
+		if let Ok(mut warm) = WARM_INDEXES.lock() {
+			warm.insert(
+				key.to_string(),
+				(
+					Arc::clone(&index.entries),
+					Arc::clone(&index.id_to_entry),
+					Arc::clone(&index.normalized_signatures),
+				),
+			);
+		}
+		index
+	}
+
+	/// Number of indexes currently kept warm by [`Self::build_warm`].
+	pub fn warm_len() -> usize {
+		WARM_INDEXES.lock().map(|warm| warm.len()).unwrap_or(0)
+	}
+
+	/// Drop the index kept warm under `key`, e.g. because its crate is being rebuilt.
+	pub fn evict_warm(key: &str) {
+		if let Ok(mut warm) = WARM_INDEXES.lock() {
+			warm.remove(key);
+		}
+	}
+
+	/// Drop every index kept warm by [`Self::build_warm`].
+	pub fn clear_warm() {
+		if let Ok(mut warm) = WARM_INDEXES.lock() {
+			warm.clear();
 		}
 	}
 
@@ -86,7 +175,7 @@ impl<'a> SearchIndex<'a> {
 
 	/// Prepare the index for a new search by clearing cached match metadata.
 	pub fn reset_matches(&mut self) {
-		for entry in &mut self.entries {
+		for entry in Arc::make_mut(&mut self.entries) {
 			entry.clear_match_info();
 		}
 	}
@@ -102,6 +191,12 @@ impl<'a> SearchIndex<'a> {
 	/// For example, `"init|clone|fetch"` matches items containing any of those terms.
 	/// Single-term queries use simple substring matching for better performance.
 	///
+	/// Queries made of `domain:text` terms (for example `name:Builder doc:timeout`) restrict each
+	/// term to its own domain and require every term to match; see [`parse_query_spec`].
+	///
+	/// Queries shaped like a function type (`fn(&str) -> Result<_, io::Error>`) match functions
+	/// and methods by their structured signature instead; see [`SignatureQuery`].
+	///
 	/// # Arguments
 	/// * `options` - Search configuration including query string, domains, and matching behavior
 	///
@@ -111,24 +206,149 @@ impl<'a> SearchIndex<'a> {
 		let mut opts = options.clone();
 		opts.ensure_domains();
 		let trimmed = opts.query.trim();
-		if trimmed.is_empty() {
+		if trimmed.is_empty() && opts.kinds.is_none() {
 			return Vec::new();
 		}
 
-		let has_or_pattern = trimmed.contains('|');
+		let mut results = if trimmed.is_empty() {
+			// A kind constraint alone selects every item of those kinds.
+			self.entries.to_vec()
+		} else if let Some(shape) = SignatureQuery::parse(trimmed) {
+			self.search_signature_shape(&shape)
+		} else if let Some(terms) = parse_query_spec(trimmed) {
+			self.search_terms(&terms, &opts)
+		} else {
+			self.search_term(trimmed, &opts)
+		};
+		if opts.fuzzy {
+			// Shorter names win ties; the sort is stable, so index order breaks the rest.
+			results.sort_by(|a, b| {
+				b.score
+					.cmp(&a.score)
+					.then_with(|| a.raw_name.len().cmp(&b.raw_name.len()))
+			});
+		}
+		results.retain(|result| {
+			opts.deprecation.allows(result.deprecated)
+				&& (opts.include_doc_hidden || !result.doc_hidden)
+				&& opts.kinds.as_ref().is_none_or(|kinds| kinds.contains(&result.kind))
+		});
+		if let Some(limit) = opts.max_results {
+			results.truncate(limit);
+		}
+		results
+	}
 
-		if has_or_pattern {
-			self.search_with_pattern(trimmed, &opts)
+	/// Search a single term, dispatching to fuzzy, OR, or substring matching.
+	fn search_term(&self, term: &str, opts: &SearchOptions) -> Vec<SearchResult> {
+		if opts.fuzzy {
+			self.search_fuzzy(term, opts)
+		} else if term.contains('|') {
+			self.search_with_pattern(term, opts)
 		} else {
-			self.search_simple(trimmed, &opts)
+			self.search_simple(term, opts)
+		}
+	}
+
+	/// Search a per-domain query specification, keeping entries matched by every term.
+	///
+	/// The reported `matched` domains are the union of the domains each term matched in.
+	fn search_terms(&self, terms: &[QueryTerm], opts: &SearchOptions) -> Vec<SearchResult> {
+		let mut combined: Option<Vec<SearchResult>> = None;
+		for term in terms {
+			let mut term_opts = opts.clone();
+			if let Some(domains) = term.domains {
+				term_opts.domains = domains;
+			}
+			let hits = self.search_term(&term.text, &term_opts);
+			combined = Some(match combined {
+				None => hits,
+				Some(previous) => previous
+					.into_iter()
+					.filter_map(|mut result| {
+						let hit = hits.iter().find(|hit| {
+							hit.item_id == result.item_id && hit.path_string == result.path_string
+						})?;
+						result.matched |= hit.matched;
+						result.score += hit.score;
+						Some(result)
+					})
+					.collect(),
+			});
 		}
+		combined.unwrap_or_default()
+	}
+
+	/// Match functions and methods whose structured signature has the queried shape.
+	fn search_signature_shape(&self, shape: &SignatureQuery) -> Vec<SearchResult> {
+		let mut results = Vec::new();
+		for entry in self.entries.iter() {
+			let Some(ItemEnum::Function(function)) =
+				self.crate_data.index.get(&entry.item_id).map(|item| &item.inner)
+			else {
+				continue;
+			};
+			if shape.matches(self.crate_data, &function.sig, &function.generics) {
+				let mut clone = entry.clone();
+				clone.matched = SearchDomain::SIGNATURES;
+				results.push(clone);
+			}
+		}
+		results
+	}
+
+	/// Perform fuzzy search, scoring names (and paths, when selected) as subsequence matches.
+	///
+	/// Docs and signatures keep their exact matching; entries found only that way score zero,
+	/// so they rank after every fuzzy name or path hit.
+	fn search_fuzzy(&self, query: &str, opts: &SearchOptions) -> Vec<SearchResult> {
+		let mut exact_opts = opts.clone();
+		exact_opts.fuzzy = false;
+		exact_opts.domains &= SearchDomain::DOCS | SearchDomain::SIGNATURES;
+		let exact: HashMap<(Id, String), SearchDomain> = if exact_opts.domains.is_empty() {
+			HashMap::new()
+		} else {
+			self.search_term(query, &exact_opts)
+				.into_iter()
+				.map(|hit| ((hit.item_id, hit.path_string), hit.matched))
+				.collect()
+		};
+
+		let mut results = Vec::new();
+		for entry in self.entries.iter() {
+			let key = (entry.item_id, entry.path_string.clone());
+			let mut matched = exact.get(&key).copied().unwrap_or_else(SearchDomain::empty);
+			let mut score = 0;
+			if opts.domains.contains(SearchDomain::NAMES)
+				&& let Some(name_score) = fuzzy_score(&entry.raw_name, query, opts.case_sensitive)
+			{
+				matched |= SearchDomain::NAMES;
+				score = name_score;
+			}
+			if opts.domains.contains(SearchDomain::PATHS)
+				&& let Some(path_score) =
+					fuzzy_score(&entry.path_string, query, opts.case_sensitive)
+			{
+				matched |= SearchDomain::PATHS;
+				score = score.max(path_score);
+			}
+
+			if !matched.is_empty() {
+				let mut clone = entry.clone();
+				clone.matched = matched;
+				clone.score = score;
+				results.push(clone);
+			}
+		}
+		results
 	}
 
 	/// Perform substring-based search for single-term queries.
 	///
 	/// This method uses simple string containment checks, which provide better performance
 	/// than regex for single-term searches. For documentation and signature searches,
-	/// special characters are stripped to avoid false negatives from syntax noise.
+	/// special characters are stripped to avoid false negatives from syntax noise. Signatures
+	/// also match when the query equals a fragment after [`normalize_signature`].
 	fn search_simple(&self, query: &str, opts: &SearchOptions) -> Vec<SearchResult> {
 		let normalized_query = if opts.case_sensitive {
 			query.to_string()
@@ -137,9 +357,14 @@ impl<'a> SearchIndex<'a> {
 		};
 
 		let stripped_query = strip_symbols_preserving_pipes(&normalized_query);
+		let signature_query = if opts.case_sensitive {
+			normalize_signature(query)
+		} else {
+			normalize_signature(query).to_lowercase()
+		};
 
 		let mut results = Vec::new();
-		for entry in &self.entries {
+		for (entry, normalized_sig) in self.entries.iter().zip(self.normalized_signatures.iter()) {
 			let mut matched = SearchDomain::empty();
 			if opts.domains.contains(SearchDomain::NAMES)
 				&& contains(&entry.raw_name, &normalized_query, opts.case_sensitive)
@@ -162,6 +387,9 @@ impl<'a> SearchIndex<'a> {
 				&& entry.signature.as_ref().is_some_and(|sig| {
 					let stripped_sig = strip_symbols_preserving_pipes(sig);
 					contains(&stripped_sig, &stripped_query, opts.case_sensitive)
+						|| normalized_sig
+							.as_ref()
+							.is_some_and(|normalized| contains(normalized, &signature_query, opts.case_sensitive))
 				}) {
 				matched |= SearchDomain::SIGNATURES;
 			}
@@ -185,6 +413,7 @@ impl<'a> SearchIndex<'a> {
 	///
 	/// For documentation and signature searches, a symbol-stripped version of the pattern
 	/// is created to match against stripped content, avoiding false negatives from syntax.
+	/// Signatures are additionally matched alternative-by-alternative after normalization.
 	///
 	/// Falls back to substring search if regex compilation fails.
 	fn search_with_pattern(&self, pattern: &str, opts: &SearchOptions) -> Vec<SearchResult> {
@@ -215,8 +444,20 @@ impl<'a> SearchIndex<'a> {
 			None
 		};
 
+		let signature_regex = if opts.domains.contains(SearchDomain::SIGNATURES) {
+			let alternatives: Vec<String> = pattern.split('|').map(normalize_signature).collect();
+			let escaped_signature = escape_regex_preserving_pipes(&alternatives.join("|"));
+			if opts.case_sensitive {
+				Regex::new(&escaped_signature).ok()
+			} else {
+				Regex::new(&format!("(?i){}", escaped_signature)).ok()
+			}
+		} else {
+			None
+		};
+
 		let mut results = Vec::new();
-		for entry in &self.entries {
+		for (entry, normalized_sig) in self.entries.iter().zip(self.normalized_signatures.iter()) {
 			let mut matched = SearchDomain::empty();
 
 			if opts.domains.contains(SearchDomain::NAMES) {
@@ -248,9 +489,14 @@ impl<'a> SearchIndex<'a> {
 				&& let Some(sig) = &entry.signature
 			{
 				let stripped_sig = strip_symbols_preserving_pipes(sig);
-				if let Some(ref stripped_re) = stripped_regex
-					&& stripped_re.is_match(&stripped_sig)
-				{
+				let stripped_match = stripped_regex
+					.as_ref()
+					.is_some_and(|re| re.is_match(&stripped_sig));
+				let normalized_match = signature_regex
+					.as_ref()
+					.zip(normalized_sig.as_ref())
+					.is_some_and(|(re, normalized)| re.is_match(normalized));
+				if stripped_match || normalized_match {
 					matched |= SearchDomain::SIGNATURES;
 				}
 			}
@@ -768,8 +1014,16 @@ impl<'a> IndexBuilder<'a> {
 			docs: item.docs.clone(),
 			signature,
 			source,
+			deprecated: item.deprecation.is_some(),
+			deprecation_note: item
+				.deprecation
+				.as_ref()
+				.and_then(|deprecation| deprecation.note.clone()),
+			cfg: cfg_predicates(item),
+			doc_hidden: is_doc_hidden(item),
 			ancestors,
 			matched: SearchDomain::empty(),
+			score: 0,
 		};
 
 		self.entries.push(result);
@@ -817,7 +1071,7 @@ impl<'a> IndexBuilder<'a> {
 		Some(SourceLocation {
 			path: display_path.to_string_lossy().into_owned(),
 			line: Some(span.begin.0),
-			column: None,
+			column: Some(span.begin.1),
 		})
 	}
 
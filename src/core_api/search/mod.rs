@@ -1,14 +1,20 @@
 //! Internal search index implementation.
 #![allow(clippy::missing_docs_in_private_items)]
 
+mod fuzzy;
+mod impl_index;
 mod index;
 mod selection;
+mod signature_query;
 mod types;
 
+pub use fuzzy::fuzzy_score;
+pub use impl_index::{ImplIndex, ImplRecord};
 pub use index::SearchIndex;
 pub use selection::{build_render_selection, describe_domains};
+pub use signature_query::SignatureQuery;
 pub use types::{
-	ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchPathSegment, SearchResponse,
+	DeprecationFilter, ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchPathSegment, SearchResponse,
 	SearchResult, SourceLocation,
 };
 
@@ -5,6 +5,7 @@ use rustdoc_types::Id;
 use super::index::SearchIndex;
 use super::types::{SearchDomain, SearchItemKind, SearchResult};
 use crate::render::RenderSelection;
+use crate::timings;
 
 /// Build a renderer selection set covering matches, their ancestors, and optionally their children.
 pub fn build_render_selection(
@@ -12,6 +13,15 @@ pub fn build_render_selection(
 	results: &[SearchResult],
 	expand_containers: bool,
 	full_source: HashSet<Id>,
+) -> RenderSelection {
+	timings::time("selection", || collect_render_selection(index, results, expand_containers, full_source))
+}
+
+fn collect_render_selection(
+	index: &SearchIndex,
+	results: &[SearchResult],
+	expand_containers: bool,
+	full_source: HashSet<Id>,
 ) -> RenderSelection {
 	let mut matches = HashSet::new();
 	let mut context = HashSet::new();
@@ -0,0 +1,611 @@
+//! Type-signature queries that match function shapes instead of rendered text.
+//!
+//! A query such as `fn(&str) -> Result<_, io::Error>` is parsed into a small type pattern and
+//! compared against the structured [`FunctionSignature`] of every function and method:
+//!
+//! - `_` matches any type.
+//! - A single capital letter (optionally followed by digits, e.g. `T`, `U2`) is a generic
+//!   placeholder: it matches any type, but every occurrence must match the same type.
+//! - `..` in a parameter list matches any number of parameters.
+//! - Paths match by their last segment; leading segments must appear, in order, in the written
+//!   or canonical path (so `io::Error` matches `std::io::error::Error`).
+//! - `impl Trait` matches `impl Trait` arguments and generic parameters bounded by `Trait`.
+//! - Omitting `-> ...` matches any return type; `-> ()` requires the unit type.
+//! - Methods match with or without their receiver (`&self`, `&mut self`, `self`).
+//!
+//! Lifetimes, parameter names, and array lengths are ignored. Type aliases are not expanded.
+
+use std::collections::HashMap;
+
+use rustdoc_types::{
+	Crate, FunctionSignature, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, Generics,
+	Path, Type, WherePredicate,
+};
+
+/// A parsed function-shape query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureQuery {
+	inputs: Vec<ParamQuery>,
+	output: Option<TypeQuery>,
+}
+
+/// One entry in a parameter list pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParamQuery {
+	/// `..`: any number of parameters.
+	Rest,
+	/// A single parameter of the given type.
+	Type(TypeQuery),
+}
+
+/// A type pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeQuery {
+	/// `_`
+	Any,
+	/// `T`, bound to the first type it matches.
+	Placeholder(String),
+	/// A path or primitive, with optional generic arguments.
+	Named {
+		segments: Vec<String>,
+		args: Option<Vec<TypeQuery>>,
+	},
+	Ref {
+		mutable: bool,
+		inner: Box<TypeQuery>,
+	},
+	Ptr {
+		mutable: bool,
+		inner: Box<TypeQuery>,
+	},
+	Slice(Box<TypeQuery>),
+	Array(Box<TypeQuery>),
+	Tuple(Vec<TypeQuery>),
+	ImplTrait(Vec<TypeQuery>),
+	DynTrait(Vec<TypeQuery>),
+	FnPtr {
+		inputs: Vec<ParamQuery>,
+		output: Option<Box<TypeQuery>>,
+	},
+}
+
+/// Placeholder bindings collected while matching one signature.
+type Bindings<'t> = HashMap<String, &'t Type>;
+
+impl SignatureQuery {
+	/// Parse a query of the form `fn(<params>) [-> <type>]`.
+	///
+	/// Returns `None` when the text is not a function shape, so callers can fall back to text
+	/// search.
+	pub fn parse(query: &str) -> Option<Self> {
+		let tokens = tokenize(query)?;
+		let mut parser = Parser { tokens, pos: 0 };
+		if !parser.eat_ident("fn") {
+			return None;
+		}
+		let (inputs, output) = parser.fn_tail()?;
+		if parser.pos != parser.tokens.len() {
+			return None;
+		}
+		Some(Self {
+			inputs,
+			output: output.map(|output| *output),
+		})
+	}
+
+	/// Whether `sig` (declared with `generics`) has the queried shape.
+	pub fn matches(&self, crate_data: &Crate, sig: &FunctionSignature, generics: &Generics) -> bool {
+		let matcher = Matcher {
+			crate_data,
+			generics,
+		};
+		let inputs: Vec<&Type> = sig.inputs.iter().map(|(_, ty)| ty).collect();
+		let has_receiver = sig.inputs.first().is_some_and(|(name, _)| name == "self");
+
+		// Try the full parameter list first, then without the receiver.
+		(0..=usize::from(has_receiver)).any(|skip| {
+			let mut bindings = Bindings::new();
+			matcher.params(&self.inputs, &inputs[skip..], &mut bindings)
+				&& matcher.output(self.output.as_ref(), sig.output.as_ref(), &mut bindings)
+		})
+	}
+}
+
+struct Matcher<'a> {
+	crate_data: &'a Crate,
+	generics: &'a Generics,
+}
+
+impl<'a> Matcher<'a> {
+	fn params(
+		&self,
+		query: &[ParamQuery],
+		inputs: &[&'a Type],
+		bindings: &mut Bindings<'a>,
+	) -> bool {
+		match query.split_first() {
+			None => inputs.is_empty(),
+			Some((ParamQuery::Rest, rest)) => (0..=inputs.len()).any(|skip| {
+				let mut attempt = bindings.clone();
+				if self.params(rest, &inputs[skip..], &mut attempt) {
+					*bindings = attempt;
+					true
+				} else {
+					false
+				}
+			}),
+			Some((ParamQuery::Type(first), rest)) => {
+				let Some((input, remaining)) = inputs.split_first() else {
+					return false;
+				};
+				self.ty(first, input, bindings) && self.params(rest, remaining, bindings)
+			}
+		}
+	}
+
+	/// Match a return type; a missing one is the unit type.
+	fn output(
+		&self,
+		query: Option<&TypeQuery>,
+		output: Option<&'a Type>,
+		bindings: &mut Bindings<'a>,
+	) -> bool {
+		match (query, output) {
+			(None, _) => true,
+			(Some(TypeQuery::Any), None) => true,
+			(Some(TypeQuery::Tuple(items)), None) => items.is_empty(),
+			(Some(_), None) => false,
+			(Some(query), Some(output)) => self.ty(query, output, bindings),
+		}
+	}
+
+	fn ty(&self, query: &TypeQuery, ty: &'a Type, bindings: &mut Bindings<'a>) -> bool {
+		match (query, ty) {
+			(TypeQuery::Any, _) => true,
+			(TypeQuery::Placeholder(name), _) => match bindings.get(name) {
+				Some(bound) => *bound == ty,
+				None => {
+					bindings.insert(name.clone(), ty);
+					true
+				}
+			},
+			(TypeQuery::Named { segments, args }, Type::ResolvedPath(path)) => {
+				self.path(segments, args.as_deref(), path, bindings)
+			}
+			(
+				TypeQuery::Named {
+					segments,
+					args: None,
+				},
+				Type::Primitive(name) | Type::Generic(name),
+			) => segments.len() == 1 && segments[0] == *name,
+			(TypeQuery::Ref { mutable, inner }, Type::BorrowedRef { is_mutable, type_, .. })
+			| (TypeQuery::Ptr { mutable, inner }, Type::RawPointer { is_mutable, type_ }) => {
+				mutable == is_mutable && self.ty(inner, type_, bindings)
+			}
+			(TypeQuery::Slice(inner), Type::Slice(type_))
+			| (TypeQuery::Array(inner), Type::Array { type_, .. }) => self.ty(inner, type_, bindings),
+			(TypeQuery::Tuple(items), Type::Tuple(types)) => {
+				items.len() == types.len()
+					&& items
+						.iter()
+						.zip(types)
+						.all(|(item, ty)| self.ty(item, ty, bindings))
+			}
+			(TypeQuery::ImplTrait(traits), Type::ImplTrait(bounds)) => {
+				self.bounds(traits, bounds.iter(), bindings)
+			}
+			(TypeQuery::ImplTrait(traits), Type::Generic(name)) => {
+				self.bounds(traits, self.generic_bounds(name), bindings)
+			}
+			(TypeQuery::DynTrait(traits), Type::DynTrait(dyn_trait)) => traits.iter().all(|query| {
+				dyn_trait
+					.traits
+					.iter()
+					.any(|poly| self.trait_path(query, &poly.trait_, bindings))
+			}),
+			(TypeQuery::FnPtr { inputs, output }, Type::FunctionPointer(pointer)) => {
+				let types: Vec<&Type> = pointer.sig.inputs.iter().map(|(_, ty)| ty).collect();
+				self.params(inputs, &types, bindings)
+					&& self.output(output.as_deref(), pointer.sig.output.as_ref(), bindings)
+			}
+			_ => false,
+		}
+	}
+
+	/// Whether every queried trait is satisfied by one of `bounds`.
+	fn bounds(
+		&self,
+		traits: &[TypeQuery],
+		bounds: impl Iterator<Item = &'a GenericBound> + Clone,
+		bindings: &mut Bindings<'a>,
+	) -> bool {
+		traits.iter().all(|query| {
+			bounds.clone().any(|bound| match bound {
+				GenericBound::TraitBound { trait_, .. } => {
+					self.trait_path(query, trait_, bindings)
+				}
+				_ => false,
+			})
+		})
+	}
+
+	fn trait_path(&self, query: &TypeQuery, path: &'a Path, bindings: &mut Bindings<'a>) -> bool {
+		match query {
+			TypeQuery::Any => true,
+			TypeQuery::Named { segments, args } => {
+				self.path(segments, args.as_deref(), path, bindings)
+			}
+			_ => false,
+		}
+	}
+
+	/// Bounds declared for the generic parameter `name`, inline or in a `where` clause.
+	fn generic_bounds(&self, name: &str) -> impl Iterator<Item = &'a GenericBound> + Clone {
+		let generics = self.generics;
+		let inline = generics
+			.params
+			.iter()
+			.filter(move |param| param.name == name)
+			.flat_map(|param| match &param.kind {
+				GenericParamDefKind::Type { bounds, .. } => bounds.as_slice(),
+				_ => &[],
+			});
+		let clauses = generics
+			.where_predicates
+			.iter()
+			.flat_map(move |predicate| match predicate {
+				WherePredicate::BoundPredicate {
+					type_: Type::Generic(generic),
+					bounds,
+					..
+				} if generic == name => bounds.as_slice(),
+				_ => &[],
+			});
+		inline.chain(clauses)
+	}
+
+	fn path(
+		&self,
+		segments: &[String],
+		args: Option<&[TypeQuery]>,
+		path: &'a Path,
+		bindings: &mut Bindings<'a>,
+	) -> bool {
+		let written: Vec<&str> = path
+			.path
+			.split("::")
+			.filter(|segment| !segment.starts_with('$'))
+			.collect();
+		let canonical = self
+			.crate_data
+			.paths
+			.get(&path.id)
+			.map(|summary| summary.path.iter().map(String::as_str).collect::<Vec<_>>());
+		let Some((last, leading)) = segments.split_last() else {
+			return false;
+		};
+		let path_matches = |candidate: &[&str]| {
+			candidate.split_last().is_some_and(|(name, prefix)| {
+				*name == last.as_str() && is_subsequence(leading, prefix)
+			})
+		};
+		if !path_matches(&written) && !canonical.as_deref().is_some_and(path_matches) {
+			return false;
+		}
+
+		let Some(args) = args else {
+			return true;
+		};
+		let types: Vec<&Type> = match path.args.as_deref() {
+			Some(GenericArgs::AngleBracketed { args: generic_args, .. }) => generic_args
+				.iter()
+				.filter_map(|arg| match arg {
+					GenericArg::Type(ty) => Some(ty),
+					_ => None,
+				})
+				.collect(),
+			_ => Vec::new(),
+		};
+		args.len() == types.len()
+			&& args
+				.iter()
+				.zip(types)
+				.all(|(query, ty)| self.ty(query, ty, bindings))
+	}
+}
+
+/// Whether `needle` appears in `haystack` in order, not necessarily contiguously.
+fn is_subsequence(needle: &[String], haystack: &[&str]) -> bool {
+	let mut remaining = haystack.iter();
+	needle
+		.iter()
+		.all(|segment| remaining.any(|candidate| *candidate == segment.as_str()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+	Ident(String),
+	Lifetime,
+	Punct(&'static str),
+}
+
+fn tokenize(query: &str) -> Option<Vec<Token>> {
+	const PUNCTS: &[&str] = &[
+		"::", "->", "..", "&", "*", "(", ")", "[", "]", "<", ">", ",", ";", ":", "+", "!", "?", "=",
+	];
+	let mut tokens = Vec::new();
+	let mut rest = query.trim();
+	while let Some(ch) = rest.chars().next() {
+		if ch.is_whitespace() {
+			rest = rest.trim_start();
+		} else if ch == '\'' {
+			let end = rest[1..]
+				.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+				.map_or(rest.len(), |end| end + 1);
+			tokens.push(Token::Lifetime);
+			rest = &rest[end..];
+		} else if ch.is_alphanumeric() || ch == '_' {
+			let end = rest
+				.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+				.unwrap_or(rest.len());
+			tokens.push(Token::Ident(rest[..end].to_string()));
+			rest = &rest[end..];
+		} else {
+			let punct = PUNCTS.iter().find(|punct| rest.starts_with(**punct))?;
+			tokens.push(Token::Punct(punct));
+			rest = &rest[punct.len()..];
+		}
+	}
+	Some(tokens)
+}
+
+struct Parser {
+	tokens: Vec<Token>,
+	pos: usize,
+}
+
+impl Parser {
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.pos)
+	}
+
+	fn peek_at(&self, offset: usize) -> Option<&Token> {
+		self.tokens.get(self.pos + offset)
+	}
+
+	fn eat(&mut self, punct: &str) -> bool {
+		if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+			self.pos += 1;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn eat_ident(&mut self, ident: &str) -> bool {
+		if matches!(self.peek(), Some(Token::Ident(name)) if name == ident) {
+			self.pos += 1;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn expect(&mut self, punct: &str) -> Option<()> {
+		self.eat(punct).then_some(())
+	}
+
+	fn ident(&mut self) -> Option<String> {
+		match self.peek() {
+			Some(Token::Ident(name)) => {
+				let name = name.clone();
+				self.pos += 1;
+				Some(name)
+			}
+			_ => None,
+		}
+	}
+
+	/// `(<params>) [-> <type>]`, after the `fn` keyword.
+	fn fn_tail(&mut self) -> Option<(Vec<ParamQuery>, Option<Box<TypeQuery>>)> {
+		self.expect("(")?;
+		let mut inputs = Vec::new();
+		while !self.eat(")") {
+			if self.eat("..") {
+				inputs.push(ParamQuery::Rest);
+			} else {
+				// Parameter names (`name: Type`) are accepted and ignored.
+				if matches!(self.peek(), Some(Token::Ident(_)))
+					&& matches!(self.peek_at(1), Some(Token::Punct(":")))
+				{
+					self.pos += 2;
+				}
+				inputs.push(ParamQuery::Type(self.ty()?));
+			}
+			if !self.eat(",") {
+				self.expect(")")?;
+				break;
+			}
+		}
+		let output = if self.eat("->") {
+			Some(Box::new(self.ty()?))
+		} else {
+			None
+		};
+		Some((inputs, output))
+	}
+
+	fn ty(&mut self) -> Option<TypeQuery> {
+		if self.eat("&") {
+			if self.peek() == Some(&Token::Lifetime) {
+				self.pos += 1;
+			}
+			let mutable = self.eat_ident("mut");
+			return Some(TypeQuery::Ref {
+				mutable,
+				inner: Box::new(self.ty()?),
+			});
+		}
+		if self.eat("*") {
+			let mutable = self.eat_ident("mut");
+			if !mutable && !self.eat_ident("const") {
+				return None;
+			}
+			return Some(TypeQuery::Ptr {
+				mutable,
+				inner: Box::new(self.ty()?),
+			});
+		}
+		if self.eat("!") {
+			return Some(named("never"));
+		}
+		if self.eat("[") {
+			let inner = Box::new(self.ty()?);
+			if self.eat(";") {
+				// Array lengths are ignored.
+				while !self.eat("]") {
+					self.peek()?;
+					self.pos += 1;
+				}
+				return Some(TypeQuery::Array(inner));
+			}
+			self.expect("]")?;
+			return Some(TypeQuery::Slice(inner));
+		}
+		if self.eat("(") {
+			let mut items = Vec::new();
+			let mut trailing_comma = false;
+			while !self.eat(")") {
+				items.push(self.ty()?);
+				trailing_comma = self.eat(",");
+				if !trailing_comma {
+					self.expect(")")?;
+					break;
+				}
+			}
+			// `(T)` is just a parenthesized type; `(T,)` is a one-element tuple.
+			if items.len() == 1 && !trailing_comma {
+				return items.pop();
+			}
+			return Some(TypeQuery::Tuple(items));
+		}
+		if self.eat_ident("impl") {
+			return Some(TypeQuery::ImplTrait(self.bounds()?));
+		}
+		if self.eat_ident("dyn") {
+			return Some(TypeQuery::DynTrait(self.bounds()?));
+		}
+		if self.eat_ident("fn") {
+			let (inputs, output) = self.fn_tail()?;
+			return Some(TypeQuery::FnPtr { inputs, output });
+		}
+		self.path()
+	}
+
+	fn bounds(&mut self) -> Option<Vec<TypeQuery>> {
+		let mut traits = Vec::new();
+		loop {
+			if self.peek() == Some(&Token::Lifetime) {
+				self.pos += 1;
+			} else if self.eat("?") {
+				// `?Sized` relaxes a bound rather than adding one.
+				self.path()?;
+			} else {
+				traits.push(self.path()?);
+			}
+			if !self.eat("+") {
+				return Some(traits);
+			}
+		}
+	}
+
+	fn path(&mut self) -> Option<TypeQuery> {
+		let first = self.ident()?;
+		if first == "_" {
+			return Some(TypeQuery::Any);
+		}
+		let mut segments = vec![if first == "self" { "Self".to_string() } else { first }];
+		while self.eat("::") {
+			segments.push(self.ident()?);
+		}
+
+		let args = if self.eat("<") {
+			let mut args = Vec::new();
+			while !self.eat(">") {
+				if self.peek() == Some(&Token::Lifetime) {
+					self.pos += 1;
+				} else {
+					args.push(self.ty()?);
+				}
+				if !self.eat(",") {
+					self.expect(">")?;
+					break;
+				}
+			}
+			Some(args)
+		} else {
+			None
+		};
+
+		if args.is_none() && segments.len() == 1 && is_placeholder(&segments[0]) {
+			return segments.pop().map(TypeQuery::Placeholder);
+		}
+		Some(TypeQuery::Named { segments, args })
+	}
+}
+
+fn named(name: &str) -> TypeQuery {
+	TypeQuery::Named {
+		segments: vec![name.to_string()],
+		args: None,
+	}
+}
+
+/// Single capital letters, optionally followed by digits, are generic placeholders.
+fn is_placeholder(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars.next().is_some_and(|first| first.is_ascii_uppercase())
+		&& chars.all(|ch| ch.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_function_shapes() {
+		let query = SignatureQuery::parse("fn(&str, ..) -> Result<_, io::Error>").unwrap();
+		assert_eq!(query.inputs.len(), 2);
+		assert_eq!(query.inputs[1], ParamQuery::Rest);
+		assert_eq!(
+			query.output,
+			Some(TypeQuery::Named {
+				segments: vec!["Result".into()],
+				args: Some(vec![
+					TypeQuery::Any,
+					TypeQuery::Named {
+						segments: vec!["io".into(), "Error".into()],
+						args: None,
+					},
+				]),
+			})
+		);
+
+		let query = SignatureQuery::parse("fn(s: &'a mut [u8; 4], T) -> (T, ())").unwrap();
+		assert!(matches!(
+			&query.inputs[0],
+			ParamQuery::Type(TypeQuery::Ref { mutable: true, inner })
+				if matches!(**inner, TypeQuery::Array(_))
+		));
+		assert_eq!(query.inputs[1], ParamQuery::Type(TypeQuery::Placeholder("T".into())));
+	}
+
+	#[test]
+	fn rejects_text_queries() {
+		assert!(SignatureQuery::parse("fn new").is_none());
+		assert!(SignatureQuery::parse("Result<T>").is_none());
+		assert!(SignatureQuery::parse("fn(&str) extra").is_none());
+		assert!(SignatureQuery::parse("fn(#)").is_none());
+	}
+}
@@ -322,6 +322,52 @@ fn signature_domain_matches_free_function() {
 	assert!(results.iter().any(|r| r.raw_name == "helper"));
 }
 
+#[test]
+fn signature_domain_ignores_whitespace_differences() {
+	let crate_data = fixture_crate();
+	let index = build_index(&crate_data);
+	for query in ["fn helper(count:i32)", "fn helper( count : i32 )"] {
+		let mut options = SearchOptions::new(query);
+		options.domains = SearchDomain::SIGNATURES;
+		let results = index.search(&options);
+		assert!(
+			results.iter().any(|r| r.raw_name == "helper"),
+			"{query} should match helper"
+		);
+	}
+}
+
+#[test]
+fn domain_prefixed_terms_must_all_match() {
+	let crate_data = fixture_crate();
+	let index = build_index(&crate_data);
+	let options = SearchOptions::new("name:helper doc:Widget");
+	let results = index.search(&options);
+	let names: Vec<_> = results.iter().map(|r| r.raw_name.as_str()).collect();
+	assert_eq!(names, vec!["helper"]);
+	assert!(results[0].matched.contains(SearchDomain::NAMES | SearchDomain::DOCS));
+
+	let options = SearchOptions::new("name:Widget doc:helper");
+	assert!(index.search(&options).is_empty());
+}
+
+#[test]
+fn fuzzy_search_ranks_abbreviated_names() {
+	let crate_data = fixture_crate();
+	let index = build_index(&crate_data);
+	let mut options = SearchOptions::new("pnt");
+	options.domains = SearchDomain::NAMES;
+	options.fuzzy = true;
+	let results = index.search(&options);
+	let names: Vec<_> = results.iter().map(|r| r.raw_name.as_str()).collect();
+	assert_eq!(names.first(), Some(&"paint"));
+	assert!(names.contains(&"Paintable"));
+	assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+	options.max_results = Some(1);
+	assert_eq!(index.search(&options).len(), 1);
+}
+
 #[test]
 fn case_sensitive_toggle_affects_results() {
 	let crate_data = fixture_crate();
@@ -509,3 +555,19 @@ fn or_search_with_special_chars_escaped() {
 	// "helper." should be treated literally, so won't match "helper"
 	assert!(!results.iter().any(|r| r.raw_name == "helper"));
 }
+
+#[test]
+fn signature_shape_queries_match_structured_signatures() {
+	let crate_data = fixture_crate();
+	let index = build_index(&crate_data);
+	let names = |query: &str| -> Vec<String> {
+		let results = index.search(&SearchOptions::new(query));
+		results.into_iter().map(|r| r.raw_name).collect()
+	};
+
+	assert_eq!(names("fn(i32) -> Widget"), vec!["helper"]);
+	assert_eq!(names("fn(i32, ..)"), vec!["helper"]);
+	assert_eq!(names("fn() -> u32"), vec!["render"]);
+	assert_eq!(names("fn(&self) -> ()"), vec!["paint"]);
+	assert!(names("fn(&mut self)").is_empty());
+}
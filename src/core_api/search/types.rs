@@ -1,6 +1,10 @@
+use std::collections::HashSet;
+
 use bitflags::bitflags;
 use rustdoc_types::Id;
 
+use crate::core_api::error::TargetFailure;
+
 bitflags! {
 	/// Domains that a search query can operate over.
 	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,8 +37,18 @@ pub struct SearchOptions {
 	pub case_sensitive: bool,
 	/// Whether to include private or crate-private items.
 	pub include_private: bool,
+	/// Whether to include items marked `#[doc(hidden)]`.
+	pub include_doc_hidden: bool,
 	/// Whether matched container items should expand to include their children.
 	pub expand_containers: bool,
+	/// Match names and paths as fuzzy subsequences and order results by score.
+	pub fuzzy: bool,
+	/// Keep at most this many results (after ranking, when fuzzy).
+	pub max_results: Option<usize>,
+	/// Keep or drop deprecated items.
+	pub deprecation: DeprecationFilter,
+	/// Keep only matches of these kinds. With an empty query, every item of these kinds matches.
+	pub kinds: Option<HashSet<SearchItemKind>>,
 }
 
 impl SearchOptions {
@@ -45,7 +59,12 @@ impl SearchOptions {
 			domains: SearchDomain::default(),
 			case_sensitive: false,
 			include_private: false,
+			include_doc_hidden: false,
 			expand_containers: true,
+			fuzzy: false,
+			max_results: None,
+			deprecation: DeprecationFilter::Any,
+			kinds: None,
 		}
 	}
 
@@ -57,10 +76,35 @@ impl SearchOptions {
 	}
 }
 
+/// Which items to keep based on their `#[deprecated]` status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeprecationFilter {
+	/// Keep items regardless of deprecation.
+	#[default]
+	Any,
+	/// Keep only deprecated items.
+	Only,
+	/// Drop deprecated items.
+	Exclude,
+}
+
+impl DeprecationFilter {
+	/// Whether an item with the given deprecation status passes the filter.
+	pub fn allows(self, deprecated: bool) -> bool {
+		match self {
+			Self::Any => true,
+			Self::Only => deprecated,
+			Self::Exclude => !deprecated,
+		}
+	}
+}
+
 /// Classified kind associated with a search result.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchItemKind {
+	/// Synthetic package grouping node used when listing several packages at once.
+	Package,
 	/// Synthetic crate root module.
 	Crate,
 	/// Regular module.
@@ -111,6 +155,7 @@ impl SearchItemKind {
 	/// Human-friendly label describing the item kind.
 	pub fn label(self) -> &'static str {
 		match self {
+			Self::Package => "package",
 			Self::Crate => "crate",
 			Self::Module => "module",
 			Self::Struct => "struct",
@@ -157,6 +202,8 @@ pub struct SearchResponse {
 	pub results: Vec<SearchResult>,
 	/// Rendered skeleton filtered to only include matched items.
 	pub rendered: String,
+	/// Packages skipped because they failed while others succeeded.
+	pub errors: Vec<TargetFailure>,
 }
 
 /// Source location associated with an item.
@@ -190,6 +237,18 @@ pub struct ListItem {
 	pub path: String,
 	/// Source location for the item if available.
 	pub source: Option<SourceLocation>,
+	/// Number of items referencing this one, when the listing is ranked by fan-in.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fan_in: Option<usize>,
+	/// Whether the item is marked `#[deprecated]`.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub deprecated: bool,
+	/// Deprecation note, if one was given.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecation_note: Option<String>,
+	/// `cfg` predicates gating the item, such as `feature = "fs"`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub cfg: Vec<String>,
 }
 
 /// Result of performing a query against a crate index.
@@ -213,15 +272,26 @@ pub struct SearchResult {
 	pub signature: Option<String>,
 	/// Source location for the item if available.
 	pub source: Option<SourceLocation>,
+	/// Whether the item is marked `#[deprecated]`.
+	pub deprecated: bool,
+	/// Deprecation note, if one was given.
+	pub deprecation_note: Option<String>,
+	/// `cfg` predicates gating the item, such as `feature = "fs"`.
+	pub cfg: Vec<String>,
+	/// Whether the item is marked `#[doc(hidden)]`.
+	pub doc_hidden: bool,
 	/// Ancestor chain of items that must be rendered for context.
 	pub ancestors: Vec<Id>,
 	/// Domains that produced a match (empty when stored in the index).
 	pub matched: SearchDomain,
+	/// Fuzzy match score, higher is better (zero for non-fuzzy searches).
+	pub score: u32,
 }
 
 impl SearchResult {
 	/// Reset match metadata so the record can be reused for a new query.
 	pub(crate) fn clear_match_info(&mut self) {
 		self.matched = SearchDomain::empty();
+		self.score = 0;
 	}
 }
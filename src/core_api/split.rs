@@ -0,0 +1,120 @@
+//! Splitting rendered output into one file per top-level module.
+//!
+//! Items declared directly in the crate root (or the targeted module) go to a `lib` file; every
+//! child module and its whole subtree goes to a file named after the module. An index file links
+//! them all.
+
+use std::path::PathBuf;
+
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Name of the index file written next to the split files.
+pub const SPLIT_INDEX_FILE: &str = "index.md";
+
+/// One file of a split render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitFile {
+	/// Path relative to the output directory.
+	pub path: PathBuf,
+	/// Canonical path of the module the file covers.
+	pub module: String,
+	/// Number of items rendered into the file.
+	pub items: usize,
+	/// Rendered contents.
+	pub contents: String,
+}
+
+/// A top-level module to render into its own file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SplitModule {
+	/// Module name, used as the file stem.
+	pub name: String,
+	/// Renderer filter selecting the module (relative to the crate root).
+	pub filter: String,
+	/// Canonical module path.
+	pub path: String,
+	/// Items declared anywhere below the module.
+	pub items: usize,
+}
+
+/// Layout of a split render below a filter path.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SplitPlan {
+	/// Non-module items declared directly under the filter path.
+	pub root_items: Vec<SearchResult>,
+	/// Child modules of the filter path.
+	pub modules: Vec<SplitModule>,
+}
+
+/// Work out which items stay in the root file and which modules get their own file.
+///
+/// `filter` is relative to the crate root, like the renderer filter. Re-export aliases are
+/// skipped: they render where their target is declared.
+pub(crate) fn plan_split(index: &SearchIndex, filter: &str) -> SplitPlan {
+	let prefix: Vec<&str> = filter
+		.split("::")
+		.filter(|segment| !segment.is_empty())
+		.collect();
+	let relative = |entry: &SearchResult| -> Option<Vec<String>> {
+		let below_root: Vec<String> = entry
+			.path
+			.iter()
+			.skip(1)
+			.map(|segment| segment.name.clone())
+			.collect();
+		let under_filter = below_root.len() > prefix.len()
+			&& below_root
+				.iter()
+				.zip(&prefix)
+				.all(|(actual, expected)| actual == expected);
+		under_filter.then_some(below_root)
+	};
+	let is_alias = |entry: &SearchResult| {
+		index
+			.get(&entry.item_id)
+			.is_some_and(|first| first.path_string != entry.path_string)
+	};
+
+	let mut plan = SplitPlan::default();
+	for entry in index.entries() {
+		if entry.kind == SearchItemKind::Use || is_alias(entry) {
+			continue;
+		}
+		let Some(path) = relative(entry) else {
+			continue;
+		};
+		if path.len() != prefix.len() + 1 {
+			continue;
+		}
+		if entry.kind != SearchItemKind::Module {
+			plan.root_items.push(entry.clone());
+			continue;
+		}
+		let module_prefix = format!("{}::", entry.path_string);
+		plan.modules.push(SplitModule {
+			name: entry.raw_name.clone(),
+			filter: path.join("::"),
+			path: entry.path_string.clone(),
+			items: index
+				.entries()
+				.iter()
+				.filter(|inner| inner.kind != SearchItemKind::Use && !is_alias(inner))
+				.filter(|inner| inner.path_string.starts_with(&module_prefix))
+				.count(),
+		});
+	}
+	plan
+}
+
+/// Render the Markdown index linking every split file.
+pub fn render_split_index(files: &[SplitFile]) -> String {
+	let mut index = String::from("# API index\n\n");
+	for file in files {
+		let link = file.path.to_string_lossy().replace('\\', "/");
+		index.push_str(&format!(
+			"- [`{}`]({link}) ({} items)\n",
+			file.module, file.items
+		));
+	}
+	index
+}
@@ -0,0 +1,308 @@
+//! API-surface metrics for a crate.
+//!
+//! Counts cover public items (re-exports excluded, each item once); private items are only
+//! counted as a total, for the public/private split. The token estimate is taken from the
+//! rendered skeleton, at one token per four characters. The optional feature
+//! breakdown attributes items to the cargo features named in their `cfg` attributes, including
+//! those inherited from gated parent modules and types, so it is only meaningful for a crate
+//! built with all features enabled.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rustdoc_types::{Crate, GenericParamDefKind, Generics, Id, ItemEnum};
+
+use super::export::cfg_predicates;
+use super::fingerprint::from_blanket_impl;
+use super::search::{SearchIndex, SearchItemKind, SearchResult};
+use crate::cargo_utils::PackageMetadata;
+
+/// Matches `feature = "name"` inside a cfg predicate.
+static FEATURE_PREDICATE: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r#"feature[ \t]*=[ \t]*"([^"]+)""#).expect("valid feature regex"));
+
+/// Number of modules listed in [`CrateStats::largest_modules`].
+const LARGEST_MODULES: usize = 10;
+
+/// Number of items of one kind.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KindCount {
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Number of items of that kind.
+	pub items: usize,
+}
+
+/// Items contributed by one cargo feature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FeatureItemCount {
+	/// Feature name.
+	pub feature: String,
+	/// Items that need the feature (possibly together with others).
+	pub items: usize,
+	/// Items gated on this feature alone.
+	pub exclusive: usize,
+}
+
+/// Split of a crate's items between always-available and feature-gated API.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FeatureBreakdown {
+	/// Items that do not depend on any feature.
+	pub ungated: usize,
+	/// Per-feature counts, largest first. Declared features without gated items are included.
+	pub features: Vec<FeatureItemCount>,
+}
+
+/// Number of public items directly inside one module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleSize {
+	/// Module path, starting with the crate name.
+	pub path: String,
+	/// Items whose nearest enclosing module this is, including fields and associated items.
+	pub items: usize,
+}
+
+/// Metrics for one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CrateStats {
+	/// Package (or crate) name.
+	pub name: String,
+	/// Total number of public items.
+	pub items: usize,
+	/// Item counts per kind, largest first.
+	pub kinds: Vec<KindCount>,
+	/// Number of items only visible with private items included.
+	pub private_items: usize,
+	/// Public items with type or const generic parameters (including `impl Trait` arguments).
+	pub generic_items: usize,
+	/// Public items with a non-empty doc comment.
+	pub documented_items: usize,
+	/// Modules holding the most public items, largest first.
+	pub largest_modules: Vec<ModuleSize>,
+	/// Approximate tokens in the rendered public skeleton.
+	pub estimated_tokens: usize,
+	/// Feature breakdown, when requested.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub by_feature: Option<FeatureBreakdown>,
+}
+
+impl CrateStats {
+	/// Share of all items that are public, from 0 to 1.
+	pub fn public_ratio(&self) -> f64 {
+		ratio(self.items, self.items + self.private_items)
+	}
+
+	/// Share of public items with docs, from 0 to 1.
+	pub fn doc_coverage(&self) -> f64 {
+		ratio(self.documented_items, self.items)
+	}
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+	if whole == 0 {
+		1.0
+	} else {
+		part as f64 / whole as f64
+	}
+}
+
+/// Compute stats for the public API of `crate_data`, adding a feature breakdown when `by_feature`
+/// is set.
+///
+/// `crate_data` should be documented with private items for the private count to be non-zero.
+/// `rendered` is the crate's rendered skeleton, used for the token estimate.
+pub fn crate_stats(
+	crate_data: &Crate,
+	metadata: Option<&PackageMetadata>,
+	by_feature: bool,
+	rendered: &str,
+) -> CrateStats {
+	let index = SearchIndex::build(crate_data, false, None);
+	let mut seen = HashSet::new();
+	let entries: Vec<_> = index
+		.entries()
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use)
+		.filter(|entry| !from_blanket_impl(crate_data, &entry.ancestors))
+		.filter(|entry| seen.insert(entry.item_id))
+		.collect();
+
+	let mut by_kind: HashMap<SearchItemKind, usize> = HashMap::new();
+	for entry in &entries {
+		*by_kind.entry(entry.kind).or_default() += 1;
+	}
+	let mut kinds: Vec<KindCount> = by_kind
+		.into_iter()
+		.map(|(kind, items)| KindCount { kind, items })
+		.collect();
+	kinds.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.kind.label().cmp(b.kind.label())));
+
+	let private_index = SearchIndex::build(crate_data, true, None);
+	let mut all_items = HashSet::new();
+	let total = private_index
+		.entries()
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use)
+		.filter(|entry| !from_blanket_impl(crate_data, &entry.ancestors))
+		.filter(|entry| all_items.insert(entry.item_id))
+		.count();
+
+	let generic_items = entries
+		.iter()
+		.filter(|entry| {
+			crate_data
+				.index
+				.get(&entry.item_id)
+				.and_then(|item| item_generics(&item.inner))
+				.is_some_and(has_type_or_const_params)
+		})
+		.count();
+	let documented_items = entries
+		.iter()
+		.filter(|entry| entry.docs.as_deref().is_some_and(|docs| !docs.trim().is_empty()))
+		.count();
+
+	let mut module_sizes: HashMap<String, usize> = HashMap::new();
+	for entry in &entries {
+		if let Some(path) = enclosing_module(entry) {
+			*module_sizes.entry(path).or_default() += 1;
+		}
+	}
+	let mut largest_modules: Vec<ModuleSize> = module_sizes
+		.into_iter()
+		.map(|(path, items)| ModuleSize { path, items })
+		.collect();
+	largest_modules.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.path.cmp(&b.path)));
+	largest_modules.truncate(LARGEST_MODULES);
+
+	let by_feature = by_feature.then(|| {
+		let declared = metadata.map(|metadata| metadata.features.as_slice()).unwrap_or_default();
+		let mut own_features: HashMap<Id, Vec<String>> = HashMap::new();
+		let mut features_of = |id: &Id| -> Vec<String> {
+			own_features
+				.entry(*id)
+				.or_insert_with(|| {
+					crate_data
+						.index
+						.get(id)
+						.map(|item| item_features(&cfg_predicates(item)))
+						.unwrap_or_default()
+				})
+				.clone()
+		};
+
+		let mut ungated = 0;
+		let mut counts: BTreeMap<String, (usize, usize)> = declared
+			.iter()
+			.map(|feature| (feature.clone(), (0, 0)))
+			.collect();
+		for entry in &entries {
+			let mut gates: Vec<String> = features_of(&entry.item_id);
+			for ancestor in &entry.ancestors {
+				gates.extend(features_of(ancestor));
+			}
+			gates.sort();
+			gates.dedup();
+			if gates.is_empty() {
+				ungated += 1;
+				continue;
+			}
+			let exclusive = gates.len() == 1;
+			for feature in gates {
+				let count = counts.entry(feature).or_default();
+				count.0 += 1;
+				if exclusive {
+					count.1 += 1;
+				}
+			}
+		}
+
+		let mut features: Vec<FeatureItemCount> = counts
+			.into_iter()
+			.map(|(feature, (items, exclusive))| FeatureItemCount {
+				feature,
+				items,
+				exclusive,
+			})
+			.collect();
+		features.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.feature.cmp(&b.feature)));
+		FeatureBreakdown { ungated, features }
+	});
+
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	CrateStats {
+		name: metadata.map_or(crate_name, |metadata| metadata.name.clone()),
+		items: entries.len(),
+		kinds,
+		private_items: total.saturating_sub(entries.len()),
+		generic_items,
+		documented_items,
+		largest_modules,
+		estimated_tokens: rendered.chars().count().div_ceil(4),
+		by_feature,
+	}
+}
+
+/// Path of the nearest module above `entry`, not counting the entry itself.
+pub(super) fn enclosing_module(entry: &SearchResult) -> Option<String> {
+	let above = &entry.path[..entry.path.len().saturating_sub(1)];
+	let end = above.iter().rposition(|segment| {
+		matches!(segment.kind, SearchItemKind::Module | SearchItemKind::Crate)
+	})?;
+	let path: Vec<&str> = above[..=end].iter().map(|segment| segment.name.as_str()).collect();
+	Some(path.join("::"))
+}
+
+/// The generics declared by an item, for the kinds that have them.
+fn item_generics(inner: &ItemEnum) -> Option<&Generics> {
+	match inner {
+		ItemEnum::Function(function) => Some(&function.generics),
+		ItemEnum::Struct(item) => Some(&item.generics),
+		ItemEnum::Enum(item) => Some(&item.generics),
+		ItemEnum::Union(item) => Some(&item.generics),
+		ItemEnum::Trait(item) => Some(&item.generics),
+		ItemEnum::TraitAlias(item) => Some(&item.generics),
+		ItemEnum::TypeAlias(item) => Some(&item.generics),
+		_ => None,
+	}
+}
+
+/// Whether `generics` has parameters beyond lifetimes.
+fn has_type_or_const_params(generics: &Generics) -> bool {
+	generics
+		.params
+		.iter()
+		.any(|param| !matches!(param.kind, GenericParamDefKind::Lifetime { .. }))
+}
+
+/// Feature names referenced by a list of cfg predicates.
+fn item_features(predicates: &[String]) -> Vec<String> {
+	let mut features: Vec<String> = predicates
+		.iter()
+		.flat_map(|predicate| FEATURE_PREDICATE.captures_iter(predicate))
+		.map(|captures| captures[1].to_string())
+		.collect();
+	features.sort();
+	features.dedup();
+	features
+}
+
+#[cfg(test)]
+mod tests {
+	use super::item_features;
+
+	#[test]
+	fn extracts_features_from_predicates() {
+		let predicates = vec![
+			r#"all(feature = "tls", unix)"#.to_string(),
+			r#"any(feature="json", feature = "tls")"#.to_string(),
+			"test".to_string(),
+		];
+		assert_eq!(item_features(&predicates), vec!["json", "tls"]);
+	}
+}
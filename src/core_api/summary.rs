@@ -0,0 +1,136 @@
+//! One-screen crate summary: what the crate is, how it is laid out, and which items matter.
+
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_types::{Crate, Id, ItemEnum};
+
+use super::externs::collect_item_refs;
+use super::search::{SearchIndex, SearchItemKind};
+use crate::cargo_utils::PackageMetadata;
+
+/// Item count for a top-level module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleCount {
+	/// Canonical module path.
+	pub path: String,
+	/// Number of public items declared anywhere below the module.
+	pub items: usize,
+}
+
+/// A central type or trait, ranked by how often the rest of the API refers to it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KeyItem {
+	/// Canonical item path.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Public items mentioning this one in their signatures, plus implementations for traits.
+	pub fan_in: usize,
+}
+
+/// Compact overview of a crate, sitting between the README and the full skeleton.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CrateSummary {
+	/// Package (or crate) name.
+	pub name: String,
+	/// Package version, if known.
+	pub version: Option<String>,
+	/// Package description from the manifest.
+	pub description: Option<String>,
+	/// Minimum supported Rust version from the manifest.
+	pub msrv: Option<String>,
+	/// Declared cargo features.
+	pub features: Vec<String>,
+	/// Public top-level modules with their item counts.
+	pub modules: Vec<ModuleCount>,
+	/// Most referenced public types and traits.
+	pub key_items: Vec<KeyItem>,
+}
+
+/// Summarize the public API of `crate_data`, keeping at most `top` key items.
+pub fn summarize(crate_data: &Crate, metadata: Option<&PackageMetadata>, top: usize) -> CrateSummary {
+	let index = SearchIndex::build(crate_data, false, None);
+	let entries: Vec<_> = index
+		.entries()
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use)
+		.collect();
+
+	let mut modules: Vec<ModuleCount> = entries
+		.iter()
+		.filter(|entry| entry.kind == SearchItemKind::Module && entry.path.len() == 2)
+		.map(|module| {
+			let prefix = format!("{}::", module.path_string);
+			ModuleCount {
+				path: module.path_string.clone(),
+				items: entries
+					.iter()
+					.filter(|entry| entry.path_string.starts_with(&prefix))
+					.count(),
+			}
+		})
+		.collect();
+	modules.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.path.cmp(&b.path)));
+	modules.dedup_by(|a, b| a.path == b.path);
+
+	let mut fan_in: HashMap<Id, usize> = HashMap::new();
+	for entry in &entries {
+		let Some(item) = crate_data.index.get(&entry.item_id) else {
+			continue;
+		};
+		let mut refs = Vec::new();
+		collect_item_refs(crate_data, item, &mut refs);
+		let refs: HashSet<Id> = refs.into_iter().collect();
+		for id in refs {
+			if id != entry.item_id {
+				*fan_in.entry(id).or_default() += 1;
+			}
+		}
+	}
+
+	let mut seen = HashSet::new();
+	let mut key_items: Vec<KeyItem> = entries
+		.iter()
+		.filter(|entry| {
+			matches!(
+				entry.kind,
+				SearchItemKind::Struct
+					| SearchItemKind::Enum
+					| SearchItemKind::Union
+					| SearchItemKind::Trait
+			)
+		})
+		.filter(|entry| seen.insert(entry.item_id))
+		.map(|entry| {
+			let implementations = match crate_data.index.get(&entry.item_id).map(|item| &item.inner) {
+				Some(ItemEnum::Trait(trait_)) => trait_.implementations.len(),
+				_ => 0,
+			};
+			KeyItem {
+				path: entry.path_string.clone(),
+				kind: entry.kind,
+				fan_in: fan_in.get(&entry.item_id).copied().unwrap_or(0) + implementations,
+			}
+		})
+		.filter(|item| item.fan_in > 0)
+		.collect();
+	key_items.sort_by(|a, b| b.fan_in.cmp(&a.fan_in).then_with(|| a.path.cmp(&b.path)));
+	key_items.truncate(top);
+
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	CrateSummary {
+		name: metadata.map_or(crate_name, |metadata| metadata.name.clone()),
+		version: metadata
+			.and_then(|metadata| metadata.version.clone())
+			.or_else(|| crate_data.crate_version.clone()),
+		description: metadata.and_then(|metadata| metadata.description.clone()),
+		msrv: metadata.and_then(|metadata| metadata.rust_version.clone()),
+		features: metadata.map(|metadata| metadata.features.clone()).unwrap_or_default(),
+		modules,
+		key_items,
+	}
+}
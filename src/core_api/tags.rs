@@ -0,0 +1,163 @@
+//! Editor tags files built from `list` output.
+//!
+//! Vim reads `ctags` files: one sorted line per tag giving the name, file, and a line-number
+//! address, with the kind as a single letter following universal-ctags' Rust kinds. Emacs reads
+//! `etags` files: a section per source file whose entries carry the text of the defining line and
+//! its line number and byte offset, so the source files are read to fill them in. Items without a
+//! source line (re-exports, primitives, synthetic nodes) are left out of both.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use super::search::{ListItem, SearchItemKind};
+
+/// One tag: the item's own name and where it is declared.
+struct Tag<'a> {
+	name: &'a str,
+	file: &'a str,
+	line: usize,
+	kind: char,
+}
+
+/// Render `items` as a sorted `ctags` file.
+pub fn render_ctags(items: &[ListItem]) -> String {
+	let mut tags: Vec<Tag<'_>> = items.iter().filter_map(tag).collect();
+	tags.sort_by(|a, b| (a.name, a.file, a.line).cmp(&(b.name, b.file, b.line)));
+
+	let mut out = String::from(concat!(
+		"!_TAG_FILE_FORMAT\t2\t/extended format/\n",
+		"!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/\n",
+		"!_TAG_PROGRAM_NAME\tripdoc\t//\n",
+	));
+	for tag in tags {
+		out.push_str(&format!("{}\t{}\t{};\"\t{}\n", tag.name, tag.file, tag.line, tag.kind));
+	}
+	out
+}
+
+/// Render `items` as an `etags` file, reading each source file for the defining lines.
+pub fn render_etags(items: &[ListItem]) -> String {
+	let mut files: BTreeMap<&str, Vec<Tag<'_>>> = BTreeMap::new();
+	for tag in items.iter().filter_map(tag) {
+		files.entry(tag.file).or_default().push(tag);
+	}
+
+	let mut out = String::new();
+	for (file, mut tags) in files {
+		tags.sort_by_key(|tag| tag.line);
+		let source = fs::read_to_string(file).unwrap_or_default();
+		let line_starts: Vec<usize> = std::iter::once(0)
+			.chain(source.match_indices('\n').map(|(index, _)| index + 1))
+			.collect();
+
+		let mut section = String::new();
+		for tag in &tags {
+			let start = line_starts.get(tag.line - 1).copied();
+			let text = start
+				.map(|start| source[start..].lines().next().unwrap_or_default())
+				.unwrap_or_default();
+			// The tag text runs up to the end of the name, as emacs matches it as a prefix.
+			let text = match text.find(tag.name) {
+				Some(index) => &text[..index + tag.name.len()],
+				None if text.is_empty() => tag.name,
+				None => text.trim_end(),
+			};
+			let offset = start.map(|start| start.to_string()).unwrap_or_default();
+			section.push_str(&format!("{text}\u{7f}{}\u{1}{},{offset}\n", tag.name, tag.line));
+		}
+		out.push_str(&format!("\u{c}\n{file},{}\n{section}", section.len()));
+	}
+	out
+}
+
+fn tag(item: &ListItem) -> Option<Tag<'_>> {
+	let kind = ctags_kind(item.kind)?;
+	let source = item.source.as_ref()?;
+	let line = source.line.filter(|line| *line > 0)?;
+	let last = item.path.rsplit("::").next().unwrap_or(&item.path);
+	let name = last.split('<').next().unwrap_or(last).trim();
+	if name.is_empty() {
+		return None;
+	}
+	Some(Tag {
+		name,
+		file: &source.path,
+		line,
+		kind,
+	})
+}
+
+/// Single-letter kind, following universal-ctags' Rust parser where it has an equivalent.
+fn ctags_kind(kind: SearchItemKind) -> Option<char> {
+	Some(match kind {
+		SearchItemKind::Crate | SearchItemKind::Module => 'n',
+		SearchItemKind::Struct | SearchItemKind::Union => 's',
+		SearchItemKind::Enum => 'g',
+		SearchItemKind::EnumVariant => 'e',
+		SearchItemKind::Field => 'm',
+		SearchItemKind::Trait | SearchItemKind::TraitAlias => 'i',
+		SearchItemKind::Function => 'f',
+		SearchItemKind::Method | SearchItemKind::TraitMethod => 'P',
+		SearchItemKind::AssocConst | SearchItemKind::Constant | SearchItemKind::Static => 'v',
+		SearchItemKind::AssocType | SearchItemKind::TypeAlias => 't',
+		SearchItemKind::Macro | SearchItemKind::ProcMacro => 'M',
+		SearchItemKind::Package
+		| SearchItemKind::Use
+		| SearchItemKind::Primitive
+		| SearchItemKind::ImplTarget => return None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core_api::search::SourceLocation;
+
+	fn item(kind: SearchItemKind, path: &str, file: &str, line: Option<usize>) -> ListItem {
+		ListItem {
+			kind,
+			path: path.to_string(),
+			source: Some(SourceLocation {
+				path: file.to_string(),
+				line,
+				column: None,
+			}),
+			fan_in: None,
+			deprecated: false,
+			deprecation_note: None,
+			cfg: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn ctags_are_sorted_by_name_with_kind_letters() {
+		let items = vec![
+			item(SearchItemKind::Struct, "demo::Reader", "src/io.rs", Some(3)),
+			item(SearchItemKind::Method, "demo::Reader::fill", "src/io.rs", Some(7)),
+			item(SearchItemKind::Use, "demo::Alias", "src/lib.rs", Some(1)),
+			item(SearchItemKind::Function, "demo::open", "src/lib.rs", None),
+		];
+		let tags = render_ctags(&items);
+		let entries: Vec<&str> = tags.lines().filter(|line| !line.starts_with("!_TAG")).collect();
+		assert_eq!(entries, vec!["Reader\tsrc/io.rs\t3;\"\ts", "fill\tsrc/io.rs\t7;\"\tP"]);
+		assert!(tags.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+	}
+
+	#[test]
+	fn etags_sections_carry_line_text_and_offsets() {
+		let dir = tempfile::tempdir().unwrap();
+		let file = dir.path().join("lib.rs");
+		fs::write(&file, "//! Demo.\npub struct Reader;\n\npub fn open() {}\n").unwrap();
+		let file = file.to_string_lossy().into_owned();
+
+		let items = vec![
+			item(SearchItemKind::Function, "demo::open", &file, Some(4)),
+			item(SearchItemKind::Struct, "demo::Reader", &file, Some(2)),
+		];
+		let section = "pub struct Reader\u{7f}Reader\u{1}2,10\npub fn open\u{7f}open\u{1}4,30\n";
+		assert_eq!(
+			render_etags(&items),
+			format!("\u{c}\n{file},{}\n{section}", section.len())
+		);
+	}
+}
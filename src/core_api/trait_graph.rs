@@ -0,0 +1,204 @@
+//! Trait hierarchies: supertrait edges and implementing types for every trait in a crate.
+//!
+//! Flat skeletons list traits one after another, which hides how an ecosystem of traits (e.g.
+//! `Service`/`Layer`) fits together. The graph collects each trait's supertraits and
+//! implementors so the hierarchy can be listed or exported as Graphviz DOT.
+
+use std::collections::{BTreeSet, HashSet};
+
+use rustdoc_types::{Crate, GenericBound, Id, ItemEnum, Type};
+
+use super::search::{SearchIndex, SearchItemKind};
+use crate::render::render_type;
+
+/// A trait declared in the crate, or an external trait one of them extends.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TraitNode {
+	/// Canonical trait path.
+	pub path: String,
+	/// Whether the trait is declared in the crate itself.
+	pub local: bool,
+	/// Canonical paths of the supertraits named in the trait's bounds.
+	pub supertraits: Vec<String>,
+	/// Types with an explicit implementation of the trait, blanket impls shown by their
+	/// generic parameter. Empty for external traits.
+	pub implementors: Vec<String>,
+}
+
+/// Trait hierarchy of one crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TraitGraph {
+	/// Crate name.
+	pub crate_name: String,
+	/// Traits sorted by path, local ones first.
+	pub traits: Vec<TraitNode>,
+}
+
+/// Collect the traits of `crate_data` with their supertraits and implementors.
+///
+/// External supertraits (e.g. `core::clone::Clone`) are included as non-local nodes so every
+/// edge has both ends in the graph. Auto-trait and synthetic impls are skipped.
+pub fn trait_graph(crate_data: &Crate, include_private: bool) -> TraitGraph {
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.clone())
+		.unwrap_or_else(|| "crate".to_string());
+	let index = SearchIndex::build(crate_data, include_private, None);
+
+	let mut seen = HashSet::new();
+	let mut traits = Vec::new();
+	let mut external = BTreeSet::new();
+	for entry in index.entries() {
+		if entry.kind != SearchItemKind::Trait || !seen.insert(entry.item_id) {
+			continue;
+		}
+		let Some(item) = crate_data.index.get(&entry.item_id) else {
+			continue;
+		};
+		let ItemEnum::Trait(trait_) = &item.inner else {
+			continue;
+		};
+
+		let mut supertraits = Vec::new();
+		for bound in &trait_.bounds {
+			if let GenericBound::TraitBound { trait_: path, .. } = bound {
+				let supertrait =
+					canonical_path(crate_data, &path.id).unwrap_or_else(|| path.path.clone());
+				if !crate_data.index.contains_key(&path.id) {
+					external.insert(supertrait.clone());
+				}
+				supertraits.push(supertrait);
+			}
+		}
+		supertraits.dedup();
+
+		let mut implementors = BTreeSet::new();
+		for impl_id in &trait_.implementations {
+			let Some(ItemEnum::Impl(impl_)) = crate_data.index.get(impl_id).map(|item| &item.inner)
+			else {
+				continue;
+			};
+			if impl_.is_synthetic {
+				continue;
+			}
+			implementors.insert(implementor_name(crate_data, &impl_.for_));
+		}
+
+		traits.push(TraitNode {
+			path: entry.path_string.clone(),
+			local: true,
+			supertraits,
+			implementors: implementors.into_iter().collect(),
+		});
+	}
+
+	traits.sort_by(|a, b| a.path.cmp(&b.path));
+	let local: HashSet<String> = traits.iter().map(|node| node.path.clone()).collect();
+	traits.extend(external.into_iter().filter(|path| !local.contains(path)).map(|path| {
+		TraitNode {
+			path,
+			local: false,
+			supertraits: Vec::new(),
+			implementors: Vec::new(),
+		}
+	}));
+
+	TraitGraph { crate_name, traits }
+}
+
+/// Render trait graphs as one Graphviz `digraph`, with a cluster per crate.
+///
+/// Edges point from a trait to its supertraits (hollow arrow) and from implementing types to
+/// the trait (dashed). External traits are drawn dashed.
+pub fn render_dot(graphs: &[TraitGraph]) -> String {
+	let mut dot = String::from("digraph traits {\n\trankdir=BT;\n\tnode [shape=box];\n");
+	for (idx, graph) in graphs.iter().enumerate() {
+		dot.push_str(&format!(
+			"\tsubgraph cluster_{idx} {{\n\t\tlabel={};\n",
+			dot_string(&graph.crate_name)
+		));
+		let mut types = BTreeSet::new();
+		for node in &graph.traits {
+			let style = if node.local { "" } else { ", style=dashed" };
+			dot.push_str(&format!("\t\t{} [shape=ellipse{style}];\n", dot_string(&node.path)));
+			types.extend(node.implementors.iter().map(String::as_str));
+		}
+		for ty in types {
+			dot.push_str(&format!("\t\t{};\n", dot_string(ty)));
+		}
+		dot.push_str("\t}\n");
+	}
+	for graph in graphs {
+		for node in &graph.traits {
+			for supertrait in &node.supertraits {
+				dot.push_str(&format!(
+					"\t{} -> {} [arrowhead=empty];\n",
+					dot_string(&node.path),
+					dot_string(supertrait)
+				));
+			}
+			for implementor in &node.implementors {
+				dot.push_str(&format!(
+					"\t{} -> {} [style=dashed];\n",
+					dot_string(implementor),
+					dot_string(&node.path)
+				));
+			}
+		}
+	}
+	dot.push_str("}\n");
+	dot
+}
+
+/// Quote a DOT identifier.
+fn dot_string(value: &str) -> String {
+	format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn canonical_path(crate_data: &Crate, id: &Id) -> Option<String> {
+	crate_data.paths.get(id).map(|summary| summary.path.join("::"))
+}
+
+/// Display name of an implementing type: the canonical path for named types, the rendered
+/// type otherwise (blanket impls render as their generic parameter).
+fn implementor_name(crate_data: &Crate, ty: &Type) -> String {
+	match ty {
+		Type::ResolvedPath(path) => {
+			canonical_path(crate_data, &path.id).unwrap_or_else(|| path.path.clone())
+		}
+		Type::Generic(name) => format!("{name} (blanket)"),
+		other => render_type(other),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dot_output_draws_supertrait_and_impl_edges() {
+		let graph = TraitGraph {
+			crate_name: "demo".to_string(),
+			traits: vec![
+				TraitNode {
+					path: "demo::Layer".to_string(),
+					local: true,
+					supertraits: vec!["demo::Service".to_string()],
+					implementors: vec!["demo::Stack".to_string()],
+				},
+				TraitNode {
+					path: "demo::Service".to_string(),
+					local: true,
+					supertraits: Vec::new(),
+					implementors: vec!["T (blanket)".to_string()],
+				},
+			],
+		};
+		let dot = render_dot(&[graph]);
+		assert!(dot.starts_with("digraph traits {"));
+		assert!(dot.contains("\"demo::Layer\" -> \"demo::Service\" [arrowhead=empty];"));
+		assert!(dot.contains("\"demo::Stack\" -> \"demo::Layer\" [style=dashed];"));
+		assert!(dot.contains("\"T (blanket)\" -> \"demo::Service\" [style=dashed];"));
+	}
+}
@@ -0,0 +1,207 @@
+//! Walking a crate's item tree in module order with user callbacks.
+//!
+//! The walk borrows the crate for its whole duration, so visitors can keep `&Item` references
+//! around for later analysis without cloning. It descends from the crate root into modules,
+//! type fields and variants, trait items, and impl blocks, in declaration order. Re-exports are
+//! reported as `use` items and not followed, so every item is visited at most once.
+
+use std::collections::HashSet;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind, VariantKind, Visibility};
+
+/// What the walk should do after an item is visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VisitFlow {
+	/// Descend into the item's children.
+	#[default]
+	Continue,
+	/// Move on to the item's next sibling without visiting its children.
+	SkipChildren,
+	/// End the walk.
+	Stop,
+}
+
+/// Options controlling which items a walk reaches.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VisitOptions {
+	/// Visit private and restricted items as well as public ones.
+	pub include_private: bool,
+	/// Descend into synthetic auto-trait impls and blanket impl instances.
+	pub auto_impls: bool,
+}
+
+/// Where a visited item sits in the crate, valid for the duration of one callback.
+#[derive(Debug, Clone, Copy)]
+pub struct VisitContext<'a> {
+	/// The crate being walked.
+	pub crate_data: &'a Crate,
+	/// Names of the enclosing items, starting with the crate name.
+	pub path: &'a [String],
+	/// The enclosing item, `None` for the crate root.
+	pub parent: Option<&'a Item>,
+}
+
+impl VisitContext<'_> {
+	/// Nesting depth below the crate root; the root is at depth 0.
+	pub fn depth(&self) -> usize {
+		self.path.len().saturating_sub(1)
+	}
+
+	/// The item's path, joining the enclosing names with `name`.
+	pub fn path_of(&self, name: &str) -> String {
+		let mut path = self.path.join("::");
+		if !path.is_empty() {
+			path.push_str("::");
+		}
+		path.push_str(name);
+		path
+	}
+}
+
+/// Callbacks invoked by [`walk_crate`].
+///
+/// Both methods have empty defaults, so visitors only implement what they need.
+pub trait ItemVisitor<'a> {
+	/// Called when the walk reaches `item`, before its children.
+	fn visit_item(&mut self, item: &'a Item, context: &VisitContext<'_>) -> VisitFlow {
+		let _ = (item, context);
+		VisitFlow::Continue
+	}
+
+	/// Called after `item`'s children were walked (or skipped).
+	fn leave_item(&mut self, item: &'a Item, context: &VisitContext<'_>) {
+		let _ = (item, context);
+	}
+}
+
+/// Any `FnMut(&Item, &VisitContext) -> VisitFlow` closure is a visitor.
+impl<'a, F> ItemVisitor<'a> for F
+where
+	F: FnMut(&'a Item, &VisitContext<'_>) -> VisitFlow,
+{
+	fn visit_item(&mut self, item: &'a Item, context: &VisitContext<'_>) -> VisitFlow {
+		self(item, context)
+	}
+}
+
+/// Walk `crate_data` from its root module, calling `visitor` for every reachable item.
+///
+/// Returns `false` if the visitor stopped the walk early.
+pub fn walk_crate<'a>(
+	crate_data: &'a Crate,
+	options: &VisitOptions,
+	visitor: &mut dyn ItemVisitor<'a>,
+) -> bool {
+	let Some(root) = crate_data.index.get(&crate_data.root) else {
+		return true;
+	};
+	let mut walker = Walker {
+		crate_data,
+		options: *options,
+		visitor,
+		path: Vec::new(),
+		seen: HashSet::new(),
+	};
+	walker.walk(root, None)
+}
+
+struct Walker<'a, 'v> {
+	crate_data: &'a Crate,
+	options: VisitOptions,
+	visitor: &'v mut dyn ItemVisitor<'a>,
+	path: Vec<String>,
+	/// Guards against cycles through glob re-exports and shared impls.
+	seen: HashSet<Id>,
+}
+
+impl<'a> Walker<'a, '_> {
+	/// Visit `item` and its children; `false` once the walk is stopped.
+	fn walk(&mut self, item: &'a Item, parent: Option<&'a Item>) -> bool {
+		if !self.seen.insert(item.id) || !self.should_visit(item) {
+			return true;
+		}
+		let flow = {
+			let context = VisitContext {
+				crate_data: self.crate_data,
+				path: &self.path,
+				parent,
+			};
+			self.visitor.visit_item(item, &context)
+		};
+		if flow == VisitFlow::Stop {
+			return false;
+		}
+		if flow == VisitFlow::Continue {
+			let name = item.name.clone().unwrap_or_else(|| "{impl}".to_string());
+			self.path.push(name);
+			let children = self.children(item);
+			let mut completed = true;
+			for id in children {
+				let Some(child) = self.crate_data.index.get(&id) else {
+					continue;
+				};
+				if !self.walk(child, Some(item)) {
+					completed = false;
+					break;
+				}
+			}
+			self.path.pop();
+			if !completed {
+				return false;
+			}
+		}
+		let context = VisitContext {
+			crate_data: self.crate_data,
+			path: &self.path,
+			parent,
+		};
+		self.visitor.leave_item(item, &context);
+		true
+	}
+
+	fn should_visit(&self, item: &Item) -> bool {
+		if let ItemEnum::Impl(impl_) = &item.inner
+			&& !self.options.auto_impls
+			&& (impl_.is_synthetic || impl_.blanket_impl.is_some())
+		{
+			return false;
+		}
+		self.options.include_private
+			|| matches!(item.visibility, Visibility::Public | Visibility::Default)
+	}
+
+	/// Child ids of `item` in declaration order.
+	fn children(&self, item: &Item) -> Vec<Id> {
+		let fields = |kind: &StructKind| match kind {
+			StructKind::Unit => Vec::new(),
+			StructKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+			StructKind::Plain { fields, .. } => fields.clone(),
+		};
+		match &item.inner {
+			ItemEnum::Module(module) => module.items.clone(),
+			ItemEnum::Struct(struct_) => {
+				let mut children = fields(&struct_.kind);
+				children.extend(&struct_.impls);
+				children
+			}
+			ItemEnum::Union(union_) => {
+				let mut children = union_.fields.clone();
+				children.extend(&union_.impls);
+				children
+			}
+			ItemEnum::Enum(enum_) => {
+				let mut children = enum_.variants.clone();
+				children.extend(&enum_.impls);
+				children
+			}
+			ItemEnum::Variant(variant) => match &variant.kind {
+				VariantKind::Plain => Vec::new(),
+				VariantKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+				VariantKind::Struct { fields, .. } => fields.clone(),
+			},
+			ItemEnum::Trait(trait_) => trait_.items.clone(),
+			ItemEnum::Impl(impl_) => impl_.items.clone(),
+			_ => Vec::new(),
+		}
+	}
+}
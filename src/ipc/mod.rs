@@ -0,0 +1,307 @@
+//! Client and server for the local daemon that keeps crates warm between CLI invocations.
+//!
+//! The daemon listens on a Unix socket under the ripdoc state directory. Each connection carries
+//! one JSON request line (the CLI arguments, working directory, and build-relevant environment of
+//! the caller) and receives one JSON response line with the captured stdout and stderr or the
+//! error message. Callers whose environment differs from the daemon's are told to run locally,
+//! since the daemon's cache directory, rustdoc flags, and toolchain would otherwise apply.
+//!
+//! While serving, parsed crates and their search indexes stay in memory, keyed by package,
+//! features, and visibility, so repeated `list` and `print --search` calls skip both the rustdoc
+//! build and re-indexing.
+//!
+//! `print`, `list`, `raw`, and most of `skelebuild` are served. Served skelebuild calls resolve
+//! relative paths against the caller's directory and keep the session state in memory, still
+//! writing it to the state file after every change and reloading it when another process has
+//! modified that file since. Subcommands that read stdin, shell out to git, or watch files run in
+//! the calling process, as do rebuilds delivering to sinks other than files, since those act in
+//! the caller's terminal, clipboard, or environment.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Set this environment variable to bypass a running daemon and execute locally.
+pub const NO_DAEMON_ENV: &str = "RIPDOC_NO_DAEMON";
+
+/// Environment variables that change what a forwarded command builds or where it caches.
+const FORWARDED_ENV: &[&str] = &[
+	"RIPDOC_CACHE_DIR",
+	"RIPDOC_CONFIG_DIR",
+	"RIPDOC_RUSTFMT_WARN",
+	"RUSTDOCFLAGS",
+	"CARGO_ENCODED_RUSTDOCFLAGS",
+	"CARGO_HOME",
+	"CARGO_TARGET_DIR",
+	"RUSTUP_TOOLCHAIN",
+	"RUSTC",
+	"RUSTDOC",
+];
+
+/// How long the daemon waits for a connected client to send its request line.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A CLI invocation forwarded to the daemon.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonRequest {
+	/// Working directory of the calling process; relative targets are resolved against it.
+	pub cwd: PathBuf,
+	/// Full argument vector, including the program name.
+	pub args: Vec<String>,
+	/// Whether the caller's stdout supports colored output.
+	#[serde(default)]
+	pub color: bool,
+	/// Ask the daemon to shut down instead of running a command.
+	#[serde(default)]
+	pub stop: bool,
+	/// The caller's values of the variables in `FORWARDED_ENV`, as returned by
+	/// [`forwarded_env`].
+	#[serde(default)]
+	pub env: BTreeMap<String, String>,
+}
+
+/// Result of a forwarded invocation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DaemonResponse {
+	/// Captured stdout of the command.
+	pub stdout: String,
+	/// Captured stderr of the command, such as warnings about skipped packages.
+	#[serde(default)]
+	pub stderr: String,
+	/// Error message when the command failed.
+	pub error: Option<String>,
+	/// The caller's environment differs from the daemon's, so the command was not run and the
+	/// caller should run it itself.
+	#[serde(default)]
+	pub run_locally: bool,
+}
+
+/// This process's values of the environment variables that affect forwarded commands.
+pub fn forwarded_env() -> BTreeMap<String, String> {
+	FORWARDED_ENV
+		.iter()
+		.filter_map(|name| Some((name.to_string(), std::env::var(name).ok()?)))
+		.collect()
+}
+
+/// Location of the daemon socket (`daemon.sock` in [`crate::cargo_utils::state_dir`]).
+pub fn socket_path() -> PathBuf {
+	crate::cargo_utils::state_dir().join("daemon.sock")
+}
+
+/// Send a request to a running daemon.
+///
+/// Returns `None` when no daemon is listening, so the caller can fall back to running locally.
+#[cfg(unix)]
+pub fn forward(request: &DaemonRequest) -> Option<io::Result<DaemonResponse>> {
+	use std::io::{BufRead, BufReader, Write};
+	use std::os::unix::net::UnixStream;
+
+	let mut stream = UnixStream::connect(socket_path()).ok()?;
+	let mut exchange = || -> io::Result<DaemonResponse> {
+		let mut payload = serde_json::to_string(request)?;
+		payload.push('\n');
+		stream.write_all(payload.as_bytes())?;
+		stream.flush()?;
+
+		let mut line = String::new();
+		BufReader::new(&stream).read_line(&mut line)?;
+		if line.trim().is_empty() {
+			return Ok(DaemonResponse::default());
+		}
+		Ok(serde_json::from_str(&line)?)
+	};
+	Some(exchange())
+}
+
+/// Send a request to a running daemon (unsupported on this platform).
+#[cfg(not(unix))]
+pub fn forward(_request: &DaemonRequest) -> Option<io::Result<DaemonResponse>> {
+	None
+}
+
+/// Serve requests until a stop request arrives.
+///
+/// Requests are handled one at a time, in the order they arrive. A client that connects but does
+/// not send its request within `REQUEST_READ_TIMEOUT` is dropped, so it cannot stall the others.
+#[cfg(unix)]
+pub fn serve(mut handler: impl FnMut(DaemonRequest) -> DaemonResponse) -> io::Result<()> {
+	use std::io::{BufRead, BufReader};
+	use std::os::unix::net::{UnixListener, UnixStream};
+
+	let path = socket_path();
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	if path.exists() {
+		if UnixStream::connect(&path).is_ok() {
+			return Err(io::Error::new(
+				io::ErrorKind::AddrInUse,
+				format!("a ripdoc daemon is already listening on {}", path.display()),
+			));
+		}
+		// Left behind by a daemon that did not shut down cleanly.
+		std::fs::remove_file(&path)?;
+	}
+
+	let listener = UnixListener::bind(&path)?;
+	eprintln!("ripdoc daemon listening on {}", path.display());
+	let daemon_env = forwarded_env();
+
+	for stream in listener.incoming() {
+		let mut stream = match stream {
+			Ok(stream) => stream,
+			Err(err) => {
+				eprintln!("ripdoc daemon: connection failed: {err}");
+				continue;
+			}
+		};
+
+		let mut line = String::new();
+		if let Err(err) = stream
+			.set_read_timeout(Some(REQUEST_READ_TIMEOUT))
+			.and_then(|()| BufReader::new(&stream).read_line(&mut line))
+		{
+			eprintln!("ripdoc daemon: failed to read request: {err}");
+			continue;
+		}
+		let request: DaemonRequest = match serde_json::from_str(&line) {
+			Ok(request) => request,
+			Err(err) => {
+				let response = DaemonResponse {
+					error: Some(format!("Malformed daemon request: {err}")),
+					..DaemonResponse::default()
+				};
+				let _ = write_response(&mut stream, &response);
+				continue;
+			}
+		};
+
+		if request.stop {
+			let _ = write_response(&mut stream, &DaemonResponse::default());
+			break;
+		}
+
+		let response = if request.env == daemon_env {
+			let (response, stderr) = capture_stderr(|| handler(request));
+			match response {
+				Ok(response) => DaemonResponse { stderr, ..response },
+				Err(err) => DaemonResponse {
+					error: Some(format!("ripdoc daemon could not capture stderr: {err}")),
+					..DaemonResponse::default()
+				},
+			}
+		} else {
+			DaemonResponse {
+				run_locally: true,
+				..DaemonResponse::default()
+			}
+		};
+		if let Err(err) = write_response(&mut stream, &response) {
+			eprintln!("ripdoc daemon: failed to send response: {err}");
+		}
+	}
+
+	drop(listener);
+	std::fs::remove_file(&path)?;
+	Ok(())
+}
+
+/// Serve requests until a stop request arrives (unsupported on this platform).
+#[cfg(not(unix))]
+pub fn serve(_handler: impl FnMut(DaemonRequest) -> DaemonResponse) -> io::Result<()> {
+	Err(io::Error::new(
+		io::ErrorKind::Unsupported,
+		"`ripdoc daemon` requires Unix domain sockets",
+	))
+}
+
+/// Run `f` with this process's stderr (file descriptor 2) redirected into a buffer, so output
+/// written by the command and any child process it starts reaches the client.
+///
+/// The outer `Err` means the redirection could not be set up and `f` did not run.
+#[cfg(unix)]
+fn capture_stderr<T>(f: impl FnOnce() -> T) -> (io::Result<T>, String) {
+	use std::io::{Read, Seek, Write};
+	use std::os::fd::AsRawFd;
+
+	let setup = || -> io::Result<(std::fs::File, libc::c_int)> {
+		let file = tempfile::tempfile()?;
+		// SAFETY: `dup` and `dup2` only duplicate descriptors this process owns.
+		let saved = unsafe { libc::dup(libc::STDERR_FILENO) };
+		if saved < 0 {
+			return Err(io::Error::last_os_error());
+		}
+		if unsafe { libc::dup2(file.as_raw_fd(), libc::STDERR_FILENO) } < 0 {
+			let err = io::Error::last_os_error();
+			unsafe { libc::close(saved) };
+			return Err(err);
+		}
+		Ok((file, saved))
+	};
+	let (mut file, saved) = match setup() {
+		Ok(setup) => setup,
+		Err(err) => return (Err(err), String::new()),
+	};
+
+	let value = f();
+
+	let _ = io::stderr().flush();
+	// SAFETY: restores the descriptor saved above and releases the duplicate.
+	unsafe {
+		libc::dup2(saved, libc::STDERR_FILENO);
+		libc::close(saved);
+	}
+	let mut captured = String::new();
+	if file.rewind().is_ok() {
+		let mut bytes = Vec::new();
+		let _ = file.read_to_end(&mut bytes);
+		captured = String::from_utf8_lossy(&bytes).into_owned();
+	}
+	(Ok(value), captured)
+}
+
+#[cfg(unix)]
+fn write_response(stream: &mut impl io::Write, response: &DaemonResponse) -> io::Result<()> {
+	let mut payload = serde_json::to_string(response)?;
+	payload.push('\n');
+	stream.write_all(payload.as_bytes())?;
+	stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn request_defaults_optional_fields() {
+		let request: DaemonRequest =
+			serde_json::from_str(r#"{"cwd":"/tmp","args":["ripdoc","list","serde"]}"#).unwrap();
+		assert!(!request.color);
+		assert!(!request.stop);
+		assert_eq!(request.args.len(), 3);
+		assert!(request.env.is_empty());
+
+		let response: DaemonResponse = serde_json::from_str(r#"{"stdout":"out","error":null}"#).unwrap();
+		assert!(response.stderr.is_empty());
+		assert!(!response.run_locally);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn captures_stderr_of_the_command_and_its_children() {
+		use std::io::Write;
+
+		// The test harness captures `eprintln!`, so write to the stderr handle directly.
+		let (status, stderr) = capture_stderr(|| {
+			writeln!(io::stderr(), "Warning: skipped package `broken`").unwrap();
+			std::process::Command::new("sh").args(["-c", "echo from-child >&2"]).status()
+		});
+		assert!(status.unwrap().unwrap().success());
+		assert!(stderr.contains("Warning: skipped package `broken`\n"), "{stderr:?}");
+		assert!(stderr.contains("from-child\n"), "{stderr:?}");
+	}
+}
@@ -16,10 +16,24 @@ pub mod skelebuild;
 /// Core API for ripdoc operations.
 pub mod core_api;
 
+/// Local IPC protocol for `ripdoc daemon` and its clients.
+pub mod ipc;
+
+/// Language Server Protocol frontend (`ripdoc lsp`).
+pub mod lsp;
+
+/// Per-phase durations collected for `--timings`.
+pub mod timings;
+
+/// Interactive terminal browser (`ripdoc tui`).
+pub mod tui;
+
 // Re-export main public API from core_api
 // Re-export target parsing from cargo_utils
 pub use crate::cargo_utils::target;
 pub use crate::core_api::{
-	ListTreeNode, RenderFormat, Result, Ripdoc, SearchDomain, SearchItemKind, SearchOptions,
-	SearchResponse, SourceLocation, build_list_tree,
+	ExportLint, ExportLintKind, ExternCrateUsage, ExternLeak, ListOutput, ListTreeNode,
+	FeatureSelection, PackageListing, RenderFormat, RenderOutput, Result, Ripdoc, SavedSelection, SearchDomain,
+	SearchItemKind, SearchOptions, SearchResponse, SourceLocation, TargetFailure, build_list_tree,
+	build_package_tree,
 };
@@ -0,0 +1,515 @@
+//! Language Server Protocol frontend.
+//!
+//! [`LspServer`] answers JSON-RPC requests on any reader/writer pair (stdio for `ripdoc lsp`), so
+//! editors can query a crate without spawning the CLI per request. Supported methods:
+//!
+//! - `workspace/symbol`: fuzzy item search over the served target, with source locations.
+//! - `textDocument/hover`: signature and docs for the item named under the cursor.
+//! - `ripdoc/skeleton`: the rendered skeleton of a target, optionally filtered by a search query.
+//!
+//! The served target defaults to the workspace root sent in `initialize`. Parsed crates stay in
+//! memory between requests.
+
+/// JSON-RPC message framing.
+pub mod transport;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::core_api::search::{SearchIndex, SearchResult};
+use crate::{FeatureSelection, Ripdoc, SearchDomain, SearchItemKind, SearchOptions};
+
+/// Custom request returning a rendered skeleton.
+pub const SKELETON_METHOD: &str = "ripdoc/skeleton";
+
+/// Largest number of symbols returned for one `workspace/symbol` request.
+const MAX_SYMBOLS: usize = 200;
+
+/// JSON-RPC error codes used in responses.
+mod codes {
+	pub const INVALID_PARAMS: i64 = -32602;
+	pub const METHOD_NOT_FOUND: i64 = -32601;
+	pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+	pub const INVALID_REQUEST: i64 = -32600;
+	pub const REQUEST_FAILED: i64 = -32803;
+}
+
+/// Error returned from a request handler.
+struct RequestError {
+	code: i64,
+	message: String,
+}
+
+impl RequestError {
+	fn new(code: i64, message: impl Into<String>) -> Self {
+		Self {
+			code,
+			message: message.into(),
+		}
+	}
+}
+
+impl From<crate::core_api::error::RipdocError> for RequestError {
+	fn from(err: crate::core_api::error::RipdocError) -> Self {
+		Self::new(codes::REQUEST_FAILED, err.to_string())
+	}
+}
+
+/// A language server answering ripdoc queries.
+pub struct LspServer {
+	ripdoc: Ripdoc,
+	target: Option<String>,
+	no_default_features: bool,
+	all_features: bool,
+	features: Vec<String>,
+	initialized: bool,
+	shutdown_requested: bool,
+	/// Text of documents opened by the editor, keyed by URI; other files are read from disk.
+	documents: HashMap<String, String>,
+}
+
+impl LspServer {
+	/// Create a server using `ripdoc` for every query.
+	///
+	/// Parsed crates are kept in memory between requests, and build output is silenced so it
+	/// cannot corrupt the protocol stream.
+	pub fn new(ripdoc: Ripdoc) -> Self {
+		Self {
+			ripdoc: ripdoc.with_memory_cache(true).with_silent(true),
+			target: None,
+			no_default_features: false,
+			all_features: false,
+			features: Vec::new(),
+			initialized: false,
+			shutdown_requested: false,
+			documents: HashMap::new(),
+		}
+	}
+
+	/// Serve this target instead of the workspace root announced by the client.
+	pub fn with_target(mut self, target: impl Into<String>) -> Self {
+		self.target = Some(target.into());
+		self
+	}
+
+	/// Feature selection used when building the served crates.
+	pub fn with_features(
+		mut self,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Self {
+		self.no_default_features = no_default_features;
+		self.all_features = all_features;
+		self.features = features;
+		self
+	}
+
+	/// Serve messages from `input` until the client sends `exit` or closes the stream.
+	pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+		while let Some(message) = transport::read_message(&mut input)? {
+			if message.get("method").and_then(Value::as_str) == Some("exit") {
+				break;
+			}
+			if let Some(response) = self.handle(&message) {
+				transport::write_message(&mut output, &response)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Handle one decoded message, returning the response for requests.
+	///
+	/// Notifications (messages without an `id`) never produce a response.
+	pub fn handle(&mut self, message: &Value) -> Option<Value> {
+		let id = message.get("id").cloned();
+		let method = message.get("method").and_then(Value::as_str);
+		let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+		let Some(id) = id else {
+			if let Some(method) = method {
+				self.notification(method, &params);
+			}
+			return None;
+		};
+		let result = match method {
+			Some(method) => self.request(method, &params),
+			None => Err(RequestError::new(codes::INVALID_REQUEST, "request without a method")),
+		};
+		Some(match result {
+			Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+			Err(err) => json!({
+				"jsonrpc": "2.0",
+				"id": id,
+				"error": {"code": err.code, "message": err.message},
+			}),
+		})
+	}
+
+	fn notification(&mut self, method: &str, params: &Value) {
+		let uri = params
+			.pointer("/textDocument/uri")
+			.and_then(Value::as_str)
+			.map(str::to_string);
+		match (method, uri) {
+			("textDocument/didOpen", Some(uri)) => {
+				if let Some(text) = params.pointer("/textDocument/text").and_then(Value::as_str) {
+					self.documents.insert(uri, text.to_string());
+				}
+			}
+			("textDocument/didChange", Some(uri)) => {
+				// Full sync: the last change carries the whole document.
+				if let Some(text) = params
+					.get("contentChanges")
+					.and_then(Value::as_array)
+					.and_then(|changes| changes.last())
+					.and_then(|change| change.get("text"))
+					.and_then(Value::as_str)
+				{
+					self.documents.insert(uri, text.to_string());
+				}
+			}
+			("textDocument/didClose", Some(uri)) => {
+				self.documents.remove(&uri);
+			}
+			_ => {}
+		}
+	}
+
+	fn request(&mut self, method: &str, params: &Value) -> Result<Value, RequestError> {
+		if method == "initialize" {
+			return Ok(self.initialize(params));
+		}
+		if !self.initialized {
+			return Err(RequestError::new(
+				codes::SERVER_NOT_INITIALIZED,
+				"initialize must be the first request",
+			));
+		}
+		if self.shutdown_requested {
+			return Err(RequestError::new(codes::INVALID_REQUEST, "server is shutting down"));
+		}
+		match method {
+			"shutdown" => {
+				self.shutdown_requested = true;
+				Ok(Value::Null)
+			}
+			"workspace/symbol" => self.workspace_symbol(params),
+			"textDocument/hover" => self.hover(params),
+			SKELETON_METHOD => self.skeleton(params),
+			_ => Err(RequestError::new(
+				codes::METHOD_NOT_FOUND,
+				format!("unsupported method '{method}'"),
+			)),
+		}
+	}
+
+	fn initialize(&mut self, params: &Value) -> Value {
+		self.initialized = true;
+		if self.target.is_none() {
+			self.target = params
+				.pointer("/initializationOptions/target")
+				.and_then(Value::as_str)
+				.map(str::to_string)
+				.or_else(|| {
+					params
+						.get("rootUri")
+						.and_then(Value::as_str)
+						.and_then(uri_to_path)
+						.or_else(|| params.get("rootPath").and_then(Value::as_str).map(PathBuf::from))
+						.map(|path| path.display().to_string())
+				});
+		}
+		json!({
+			"capabilities": {
+				"textDocumentSync": 1,
+				"hoverProvider": true,
+				"workspaceSymbolProvider": true,
+				"experimental": {"skeletonProvider": SKELETON_METHOD},
+			},
+			"serverInfo": {"name": "ripdoc", "version": env!("CARGO_PKG_VERSION")},
+		})
+	}
+
+	fn target<'a>(&'a self, params: &'a Value) -> &'a str {
+		params
+			.get("target")
+			.and_then(Value::as_str)
+			.or(self.target.as_deref())
+			.unwrap_or("./")
+	}
+
+	fn workspace_symbol(&self, params: &Value) -> Result<Value, RequestError> {
+		let query = params.get("query").and_then(Value::as_str).unwrap_or("");
+		let mut options = SearchOptions::new(query);
+		options.domains = SearchDomain::NAMES | SearchDomain::PATHS;
+		options.fuzzy = true;
+		options.expand_containers = false;
+		options.max_results = Some(MAX_SYMBOLS);
+		let search = (!query.trim().is_empty()).then_some(&options);
+
+		let items = self.ripdoc.list(
+			self.target(params),
+			self.no_default_features,
+			self.all_features,
+			self.features.clone(),
+			false,
+			search,
+		)?;
+		let symbols: Vec<Value> = items
+			.iter()
+			.filter_map(|item| {
+				let source = item.source.as_ref()?;
+				let (container, name) = item.path.rsplit_once("::").unwrap_or(("", &item.path));
+				let position = json!({
+					"line": source.line.unwrap_or(1).saturating_sub(1),
+					"character": source.column.unwrap_or(1).saturating_sub(1),
+				});
+				Some(json!({
+					"name": name,
+					"kind": symbol_kind(item.kind),
+					"containerName": container,
+					"location": {
+						"uri": path_to_uri(Path::new(&source.path)),
+						"range": {"start": position, "end": position},
+					},
+				}))
+			})
+			.take(MAX_SYMBOLS)
+			.collect();
+		Ok(Value::Array(symbols))
+	}
+
+	fn hover(&self, params: &Value) -> Result<Value, RequestError> {
+		let uri = params
+			.pointer("/textDocument/uri")
+			.and_then(Value::as_str)
+			.ok_or_else(|| RequestError::new(codes::INVALID_PARAMS, "missing textDocument.uri"))?;
+		let line = params.pointer("/position/line").and_then(Value::as_u64);
+		let character = params.pointer("/position/character").and_then(Value::as_u64);
+		let (Some(line), Some(character)) = (line, character) else {
+			return Err(RequestError::new(codes::INVALID_PARAMS, "missing position"));
+		};
+
+		let text = match self.documents.get(uri) {
+			Some(text) => text.clone(),
+			None => match uri_to_path(uri).map(std::fs::read_to_string) {
+				Some(Ok(text)) => text,
+				_ => return Ok(Value::Null),
+			},
+		};
+		let Some(path) = text
+			.lines()
+			.nth(line as usize)
+			.and_then(|line| path_at(line, character as usize))
+		else {
+			return Ok(Value::Null);
+		};
+
+		let crates = self.ripdoc.inspect(
+			self.target(params),
+			self.no_default_features,
+			self.all_features,
+			self.features.clone(),
+			false,
+		)?;
+		for crate_data in &crates {
+			let index = SearchIndex::build(crate_data, false, None);
+			if let Some(entry) = best_match(index.entries(), &path) {
+				return Ok(json!({
+					"contents": {"kind": "markdown", "value": hover_markdown(entry)},
+				}));
+			}
+		}
+		Ok(Value::Null)
+	}
+
+	fn skeleton(&self, params: &Value) -> Result<Value, RequestError> {
+		let target = self.target(params);
+		let private = params.get("private").and_then(Value::as_bool).unwrap_or(false);
+		let implementation = params
+			.get("implementation")
+			.and_then(Value::as_bool)
+			.unwrap_or(false);
+		let raw_source = params.get("rawSource").and_then(Value::as_bool).unwrap_or(false);
+
+		let features = FeatureSelection {
+			no_default_features: self.no_default_features,
+			all_features: self.all_features,
+			features: self.features.clone(),
+		};
+		let skeleton = match params.get("search").and_then(Value::as_str) {
+			Some(query) => {
+				let mut options = SearchOptions::new(query);
+				options.include_private = private;
+				self.ripdoc
+					.search(target, features, &options, implementation, raw_source)?
+					.rendered
+			}
+			None => {
+				self.ripdoc
+					.render(target, features, private, implementation, raw_source)?
+					.rendered
+			}
+		};
+		Ok(json!({"target": target, "skeleton": skeleton}))
+	}
+}
+
+/// The (possibly qualified) path under `character` in `line`, up to the segment under the cursor.
+///
+/// Columns are counted in characters, which matches the UTF-16 offsets LSP uses for the ASCII
+/// identifiers that matter here.
+fn path_at(line: &str, character: usize) -> Option<String> {
+	let chars: Vec<char> = line.chars().collect();
+	let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+	let is_path = |ch: char| is_ident(ch) || ch == ':';
+	let cursor = character.min(chars.len().checked_sub(1)?);
+	if !is_ident(chars[cursor]) {
+		return None;
+	}
+
+	let mut start = cursor;
+	while start > 0 && is_path(chars[start - 1]) {
+		start -= 1;
+	}
+	let mut end = cursor;
+	while end < chars.len() && is_ident(chars[end]) {
+		end += 1;
+	}
+	let path: String = chars[start..end].iter().collect();
+	let path = path.trim_start_matches(':');
+	(!path.is_empty()).then(|| path.to_string())
+}
+
+/// The entry best matching a written path: same name, preferring the longest matching suffix.
+fn best_match<'a>(entries: &'a [SearchResult], path: &str) -> Option<&'a SearchResult> {
+	let segments: Vec<&str> = path.split("::").filter(|segment| !segment.is_empty()).collect();
+	let name = *segments.last()?;
+	entries
+		.iter()
+		.filter(|entry| entry.kind != SearchItemKind::Use && entry.raw_name == name)
+		.max_by_key(|entry| {
+			let matched = segments
+				.iter()
+				.rev()
+				.zip(entry.path.iter().rev())
+				.take_while(|(written, segment)| **written == segment.name)
+				.count();
+			// Prefer the longest suffix match, then the shortest (most canonical) path.
+			(matched, std::cmp::Reverse(entry.path.len()))
+		})
+}
+
+fn hover_markdown(entry: &SearchResult) -> String {
+	let mut value = format!("```rust\n{}\n```", entry.signature.as_deref().unwrap_or(&entry.path_string));
+	value.push_str(&format!("\n\n`{}` ({})", entry.path_string, entry.kind.label()));
+	if let Some(docs) = entry.docs.as_deref().filter(|docs| !docs.trim().is_empty()) {
+		value.push_str("\n\n---\n\n");
+		value.push_str(docs.trim());
+	}
+	value
+}
+
+/// LSP `SymbolKind` for an item kind.
+fn symbol_kind(kind: SearchItemKind) -> u8 {
+	match kind {
+		SearchItemKind::Package | SearchItemKind::Crate => 4,
+		SearchItemKind::Module => 2,
+		SearchItemKind::Struct | SearchItemKind::Union | SearchItemKind::Primitive => 23,
+		SearchItemKind::Enum => 10,
+		SearchItemKind::EnumVariant => 22,
+		SearchItemKind::Field => 8,
+		SearchItemKind::Trait | SearchItemKind::TraitAlias => 11,
+		SearchItemKind::Method | SearchItemKind::TraitMethod => 6,
+		SearchItemKind::Function | SearchItemKind::Macro | SearchItemKind::ProcMacro => 12,
+		SearchItemKind::Constant | SearchItemKind::AssocConst => 14,
+		SearchItemKind::Static => 13,
+		SearchItemKind::AssocType | SearchItemKind::TypeAlias => 26,
+		SearchItemKind::Use | SearchItemKind::ImplTarget => 19,
+	}
+}
+
+/// Convert a `file://` URI to a path, decoding percent escapes.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+	let encoded = uri.strip_prefix("file://")?;
+	let bytes = encoded.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut idx = 0;
+	while idx < bytes.len() {
+		if bytes[idx] == b'%'
+			&& let Some(byte) = encoded
+				.get(idx + 1..idx + 3)
+				.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+		{
+			decoded.push(byte);
+			idx += 3;
+		} else {
+			decoded.push(bytes[idx]);
+			idx += 1;
+		}
+	}
+	let path = String::from_utf8(decoded).ok()?;
+	// Windows URIs look like `file:///C:/...`.
+	let path = match path.strip_prefix('/') {
+		Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+		_ => path,
+	};
+	Some(PathBuf::from(path))
+}
+
+/// Convert a path to a `file://` URI, escaping characters that are not URI-safe.
+fn path_to_uri(path: &Path) -> String {
+	let path = path.to_string_lossy().replace('\\', "/");
+	let mut uri = String::from("file://");
+	if !path.starts_with('/') {
+		uri.push('/');
+	}
+	for byte in path.bytes() {
+		if byte.is_ascii_alphanumeric() || b"/-_.~:".contains(&byte) {
+			uri.push(byte as char);
+		} else {
+			uri.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	uri
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn requests_before_initialize_are_rejected_and_shutdown_is_acknowledged() {
+		let mut server = LspServer::new(Ripdoc::new());
+		let early = server
+			.handle(&json!({"jsonrpc": "2.0", "id": 1, "method": "workspace/symbol", "params": {"query": "x"}}))
+			.unwrap();
+		assert_eq!(early["error"]["code"], codes::SERVER_NOT_INITIALIZED);
+
+		let init = server
+			.handle(&json!({"jsonrpc": "2.0", "id": 2, "method": "initialize", "params": {"rootUri": "file:///work/my%20crate"}}))
+			.unwrap();
+		assert_eq!(init["result"]["capabilities"]["hoverProvider"], true);
+		assert_eq!(server.target.as_deref(), Some("/work/my crate"));
+
+		assert!(server.handle(&json!({"jsonrpc": "2.0", "method": "initialized"})).is_none());
+		let unknown = server.handle(&json!({"jsonrpc": "2.0", "id": 3, "method": "foo/bar"})).unwrap();
+		assert_eq!(unknown["error"]["code"], codes::METHOD_NOT_FOUND);
+		let shutdown = server.handle(&json!({"jsonrpc": "2.0", "id": 4, "method": "shutdown"})).unwrap();
+		assert_eq!(shutdown["result"], Value::Null);
+	}
+
+	#[test]
+	fn extracts_paths_under_the_cursor() {
+		let line = "    let err: io::Error = Widget::new(1);";
+		assert_eq!(path_at(line, 13).as_deref(), Some("io"));
+		assert_eq!(path_at(line, 18).as_deref(), Some("io::Error"));
+		assert_eq!(path_at(line, 34).as_deref(), Some("Widget::new"));
+		assert_eq!(path_at(line, 3), None);
+		assert_eq!(path_to_uri(Path::new("/a b/c.rs")), "file:///a%20b/c.rs");
+	}
+}
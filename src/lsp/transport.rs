@@ -0,0 +1,63 @@
+//! JSON-RPC message framing over the LSP base protocol (`Content-Length` headers).
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Read one message, returning `None` at end of input.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+	let mut content_length = None;
+	loop {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 {
+			return Ok(None);
+		}
+		let line = line.trim_end_matches(['\r', '\n']);
+		if line.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = line.split_once(':')
+			&& name.trim().eq_ignore_ascii_case("content-length")
+		{
+			content_length = Some(value.trim().parse::<usize>().map_err(|err| {
+				io::Error::new(io::ErrorKind::InvalidData, format!("invalid Content-Length: {err}"))
+			})?);
+		}
+	}
+
+	let Some(length) = content_length else {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"message without Content-Length header",
+		));
+	};
+	let mut body = vec![0; length];
+	reader.read_exact(&mut body)?;
+	Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Write one message with its `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+	let body = serde_json::to_string(message)?;
+	write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+	writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trips_framed_messages() {
+		let mut buffer = Vec::new();
+		let first = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+		let second = serde_json::json!({"jsonrpc": "2.0", "method": "exit", "params": "ü"});
+		write_message(&mut buffer, &first).unwrap();
+		write_message(&mut buffer, &second).unwrap();
+
+		let mut reader = io::BufReader::new(buffer.as_slice());
+		assert_eq!(read_message(&mut reader).unwrap(), Some(first));
+		assert_eq!(read_message(&mut reader).unwrap(), Some(second));
+		assert_eq!(read_message(&mut reader).unwrap(), None);
+	}
+}
@@ -1,16 +1,28 @@
 //! CLI entrypoint.
 
 use std::error::Error;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
 use std::process::{self, Command as ProcessCommand, Stdio};
+use std::time::Duration;
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use owo_colors::OwoColorize;
 use regex::Regex;
-use ripdoc::cargo_utils::{fetch_readme, find_latest_cached_version, resolve_target};
-use ripdoc::core_api::pattern::escape_regex_preserving_pipes;
-use ripdoc::core_api::search::{SearchIndex, SearchItemKind};
-use ripdoc::{RenderFormat, Ripdoc, SearchDomain, SearchOptions, SourceLocation};
+use ripdoc::cargo_utils::{
+	AliasStore, CacheConfig, CacheEntry, CacheEntryKind, PackageLocation, ResolveContext, Verbosity, cache_entries, clear_cache, config_dir, expand_alias, fetch_readme,
+	find_latest_cached_version, gc_cache, resolve_target,
+};
+use ripdoc::core_api::{
+	ApiChangeKind, DeprecationFilter, DeriveStyle, DocMode, FenceLanguage, ImplSubject, ListItem, ListOutput, ListTemplate, MarkdownOptions, PackageListing, Provenance, SPLIT_INDEX_FILE, TargetFailure, VisibilityLevel, render_ctags, render_dot, render_etags, render_split_index,
+};
+use ripdoc::core_api::pattern::{escape_regex_preserving_pipes, parse_query_spec};
+use ripdoc::core_api::search::{SearchIndex, SearchItemKind, SignatureQuery};
+use ripdoc::ipc;
+use ripdoc::lsp::LspServer;
+use ripdoc::skelebuild::reports::{SourceRange, find_test_definition, parse_diagnostics, parse_test_report, resolve_report_path};
+use ripdoc::timings::{self, PhaseTiming, Timings};
+use ripdoc::tui::Browser;
+use ripdoc::{FeatureSelection, RenderFormat, Ripdoc, SavedSelection, SearchDomain, SearchOptions, SourceLocation};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 /// Available search domains accepted by `--search-spec`.
@@ -36,6 +48,74 @@ impl From<SearchSpec> for SearchDomain {
 	}
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+/// Item kinds accepted by `--kind`.
+enum KindFilter {
+	/// Modules.
+	#[value(alias = "mod")]
+	Module,
+	/// Structs.
+	Struct,
+	/// Unions.
+	Union,
+	/// Enums.
+	Enum,
+	/// Enum variants.
+	Variant,
+	/// Struct, union, and variant fields.
+	Field,
+	/// Traits.
+	Trait,
+	/// Trait aliases.
+	TraitAlias,
+	/// Free functions.
+	#[value(alias = "fn")]
+	Function,
+	/// Methods in impl blocks.
+	Method,
+	/// Method declarations in traits.
+	TraitMethod,
+	/// Associated constants.
+	AssocConst,
+	/// Associated types.
+	AssocType,
+	/// Constants.
+	Const,
+	/// Statics.
+	Static,
+	/// Type aliases.
+	Type,
+	/// `macro_rules!` macros.
+	Macro,
+	/// Procedural macros.
+	ProcMacro,
+}
+
+impl From<KindFilter> for SearchItemKind {
+	fn from(kind: KindFilter) -> Self {
+		match kind {
+			KindFilter::Module => Self::Module,
+			KindFilter::Struct => Self::Struct,
+			KindFilter::Union => Self::Union,
+			KindFilter::Enum => Self::Enum,
+			KindFilter::Variant => Self::EnumVariant,
+			KindFilter::Field => Self::Field,
+			KindFilter::Trait => Self::Trait,
+			KindFilter::TraitAlias => Self::TraitAlias,
+			KindFilter::Function => Self::Function,
+			KindFilter::Method => Self::Method,
+			KindFilter::TraitMethod => Self::TraitMethod,
+			KindFilter::AssocConst => Self::AssocConst,
+			KindFilter::AssocType => Self::AssocType,
+			KindFilter::Const => Self::Constant,
+			KindFilter::Static => Self::Static,
+			KindFilter::Type => Self::TypeAlias,
+			KindFilter::Macro => Self::Macro,
+			KindFilter::ProcMacro => Self::ProcMacro,
+		}
+	}
+}
+
 #[derive(Args, Clone)]
 struct CommonArgs {
 	/// Include auto-implemented traits
@@ -46,6 +126,22 @@ struct CommonArgs {
 	#[arg(short = 'p', long, default_value_t = false)]
 	private: bool,
 
+	/// With `--private`, hide items less visible than this (`pub`, `pub(crate)`, `pub(super)`, `private`)
+	#[arg(long, value_name = "VISIBILITY", requires = "private")]
+	min_visibility: Option<VisibilityLevel>,
+
+	/// Render only `pub` items reachable from the crate root via public modules and re-exports (the real external API)
+	#[arg(long, default_value_t = false)]
+	strict_public: bool,
+
+	/// Show the `#[cfg(...)]` gates of feature- or platform-specific items
+	#[arg(long, default_value_t = false)]
+	show_cfg: bool,
+
+	/// Include items marked `#[doc(hidden)]` when the rustdoc JSON contains them (hidden by default, as in rustdoc's HTML)
+	#[arg(long, default_value_t = false)]
+	doc_hidden: bool,
+
 	/// Disable default features
 	#[arg(short = 'n', long, default_value_t = false)]
 	no_default_features: bool,
@@ -62,11 +158,31 @@ struct CommonArgs {
 	#[arg(short = 'o', long, default_value_t = false)]
 	offline: bool,
 
-	/// Enable verbose mode, showing cargo output while generating docs
-	#[arg(short = 'v', long, default_value_t = false)]
-	verbose: bool,
+	/// Cover every package of the target's workspace (respecting `default-members`), one section per package
+	#[arg(long, default_value_t = false)]
+	workspace: bool,
+
+	/// Abort rustdoc generation after this many seconds and fall back to the most recent cached build
+	#[arg(long, value_name = "N")]
+	max_build_seconds: Option<u64>,
 
-	/// Select the output format (`rust` or `markdown`)
+	/// Ignore cached rustdoc JSON for this invocation, regenerate it, and overwrite the cache entry
+	#[arg(long, default_value_t = false)]
+	refresh: bool,
+
+	/// Read named crates from docs.rs rustdoc JSON instead of building them with nightly locally
+	#[arg(long, default_value_t = false, conflicts_with = "workspace")]
+	docs_rs: bool,
+
+	/// Build with the features and rustdoc flags of the crate's `[package.metadata.docs.rs]` table, as docs.rs does
+	#[arg(long, default_value_t = false, conflicts_with_all = ["no_default_features", "all_features", "features"])]
+	docsrs_config: bool,
+
+	/// Show build output while generating docs: `-v` cargo progress, `-vv` full rustdoc output, `-vvv` adds per-phase timings
+	#[arg(short = 'v', long, action = clap::ArgAction::Count)]
+	verbose: u8,
+
+	/// Select the output format (`rust`, `markdown`, `json`, `jsonl`, `dot`, `html`, `ctags`, or `etags`)
 	#[arg(short = 'f', long, value_enum, default_value = "markdown")]
 	format: OutputFormat,
 
@@ -74,9 +190,76 @@ struct CommonArgs {
 	#[arg(long, default_value_t = false)]
 	no_source_labels: bool,
 
+	/// Collapse enum variant fields to `Variant { /* .. */ }` placeholders (discriminants are kept)
+	#[arg(long, default_value_t = false)]
+	collapse_variants: bool,
+
+	/// Show derived traits (`Clone`, `Debug`, `Serialize`, ...) as a `#[derive]` attribute, a `/* derives: .. */` summary comment, or as impl blocks
+	#[arg(long, value_enum, value_name = "STYLE", default_value = "attribute")]
+	derives: DerivesArg,
+
+	/// Elide lifetimes that Rust can infer and collapse `for<'a>` noise in signatures
+	#[arg(long, default_value_t = false)]
+	simplify_lifetimes: bool,
+
+	/// Repeat the code examples from item docs as labelled sections after each signature
+	#[arg(long, default_value_t = false)]
+	examples: bool,
+
+	/// How much of each doc comment to render: `full`, `first-paragraph`, `first-line`, or `none`
+	#[arg(long, value_name = "MODE", default_value_t = DocMode::Full)]
+	docs: DocMode,
+
+	/// Keep only the first paragraph of each doc comment (shorthand for `--docs first-paragraph`)
+	#[arg(long, default_value_t = false, conflicts_with = "docs")]
+	doc_summary: bool,
+
+	/// Wrap doc comment prose at N columns; code blocks, headings, and tables are left as written
+	#[arg(long, value_name = "N")]
+	doc_width: Option<usize>,
+
+	/// Drop fenced code blocks from doc comments, keeping the prose around them
+	#[arg(long, default_value_t = false)]
+	strip_doc_code: bool,
+
+	/// Render signatures with their precise lifetimes (overrides `--simplify-lifetimes`)
+	#[arg(long, default_value_t = false, overrides_with = "simplify_lifetimes")]
+	exact_signatures: bool,
+
+	/// Language tag for Markdown code fences (`rust`, `rs`, or `none`)
+	#[arg(long, value_enum, default_value = "rust")]
+	fence_lang: FenceLang,
+
+	/// Split Markdown code blocks longer than this many lines, marking continuations
+	#[arg(long, value_name = "LINES", value_parser = parse_positive)]
+	max_code_lines: Option<usize>,
+
+	/// In Markdown output, keep source labels as HTML comments instead of `### Source:` headings
+	#[arg(long, default_value_t = false)]
+	no_source_headings: bool,
+
 	/// Disable ANSI colors in CLI output
 	#[arg(long, default_value_t = false)]
 	no_color: bool,
+
+	/// Force colored output (set by the daemon on behalf of a terminal client).
+	#[arg(skip)]
+	force_color: bool,
+
+	/// Directory relative targets resolve against instead of the current directory (set by the
+	/// daemon to the client's directory).
+	#[arg(skip)]
+	working_dir: Option<std::path::PathBuf>,
+}
+
+impl CommonArgs {
+	fn feature_selection(&self) -> FeatureSelection {
+		FeatureSelection {
+			no_default_features: self.no_default_features,
+			all_features: self.all_features,
+			features: self.features.clone(),
+		}
+	}
 }
 
 #[derive(Args, Clone)]
@@ -98,6 +281,46 @@ struct SearchFilterArgs {
 	/// Suppress automatic expansion of matched containers when searching.
 	#[arg(short = 'd', long, default_value_t = false)]
 	direct_match_only: bool,
+
+	/// How to highlight query hits (defaults to `ansi` on color terminals, otherwise `none`).
+	#[arg(long, value_enum, value_name = "STYLE")]
+	highlight_style: Option<HighlightStyle>,
+
+	/// Match names and paths fuzzily (e.g. `spwn_blkng` finds `spawn_blocking`), best matches first.
+	#[arg(long, default_value_t = false)]
+	fuzzy: bool,
+
+	/// Keep at most this many search hits.
+	#[arg(long, value_name = "N")]
+	max_results: Option<usize>,
+
+	/// Keep only items marked `#[deprecated]`.
+	#[arg(long, default_value_t = false, conflicts_with = "no_deprecated")]
+	only_deprecated: bool,
+
+	/// Drop items marked `#[deprecated]`.
+	#[arg(long, default_value_t = false)]
+	no_deprecated: bool,
+
+	/// Keep only items of these kinds (e.g. `struct,trait,fn`); without a search query, selects every item of those kinds.
+	#[arg(long, value_enum, value_delimiter = ',', value_name = "KIND[,KIND...]")]
+	kind: Vec<KindFilter>,
+
+	/// Leave out items under this path, e.g. `tokio::runtime` (repeatable; `*` matches within a segment).
+	#[arg(long, value_name = "PATH")]
+	exclude: Vec<String>,
+}
+
+impl SearchFilterArgs {
+	fn deprecation(&self) -> DeprecationFilter {
+		if self.only_deprecated {
+			DeprecationFilter::Only
+		} else if self.no_deprecated {
+			DeprecationFilter::Exclude
+		} else {
+			DeprecationFilter::Any
+		}
+	}
 }
 
 impl Default for SearchFilterArgs {
@@ -106,20 +329,52 @@ impl Default for SearchFilterArgs {
 			search_spec: vec![SearchSpec::Name, SearchSpec::Doc, SearchSpec::Signature],
 			search_case_sensitive: false,
 			direct_match_only: false,
+			highlight_style: None,
+			fuzzy: false,
+			max_results: None,
+			only_deprecated: false,
+			no_deprecated: false,
+			kind: Vec::new(),
+			exclude: Vec::new(),
 		}
 	}
 }
 
+#[derive(Args, Clone)]
+/// Arguments for the `search-all` subcommand.
+struct SearchAllArgs {
+	/// Search query (accepts the same terms as `list --search`)
+	query: String,
+
+	#[command(flatten)]
+	filters: SearchFilterArgs,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
 #[derive(Args, Clone)]
 struct ListArgs {
 	/// Target to generate - a directory, file path, or a module name
 	#[arg(default_value = "./")]
 	target: String,
 
-	/// Optional search query used to filter the listing
+	/// Optional search query used to filter the listing (accepts `name:`, `doc:`, `path:`, `sig:` terms, or a `fn(..) -> T` shape)
 	#[arg(short = 's', long)]
 	search: Option<String>,
 
+	/// Cluster search hits under their defining file, with per-file counts.
+	#[arg(long, value_enum, value_name = "KEY", requires = "search")]
+	group_by: Option<ListGroupBy>,
+
+	/// Lay out each text line with placeholders: {kind}, {path}, {name}, {crate}, {file}, {line}, {column}, {location}, {fan_in}
+	#[arg(long, value_name = "TEMPLATE", conflicts_with = "group_by")]
+	template: Option<ListTemplate>,
+
+	/// Order entries by a ranking instead of index order; `fan-in` puts the items most referenced by other signatures, fields, and bounds first.
+	#[arg(long, value_enum, value_name = "RANKING", conflicts_with = "group_by")]
+	rank: Option<ListRank>,
+
 	#[command(flatten)]
 	filters: SearchFilterArgs,
 
@@ -137,7 +392,7 @@ struct PrintArgs {
 	#[arg(value_name = "ITEM", conflicts_with = "search")]
 	item: Option<String>,
 
-	/// Search query used to filter the printed skeleton
+	/// Search query used to filter the printed skeleton (accepts `name:`, `doc:`, `path:`, `sig:` terms, or a `fn(..) -> T` shape)
 	#[arg(short = 's', long)]
 	search: Option<String>,
 
@@ -145,10 +400,43 @@ struct PrintArgs {
 	#[arg(long, default_value_t = false)]
 	implementation: bool,
 
+	/// With `--implementation`, only include the source of items spanning at most N lines; longer function bodies become `/* N lines elided */`.
+	#[arg(long, value_name = "N", requires = "implementation")]
+	max_impl_lines: Option<usize>,
+
 	/// Include the literal, unelided source code for the containing file.
 	#[arg(long, alias = "source", default_value_t = false)]
 	raw_source: bool,
 
+	/// Write one file per top-level module (plus `lib` for root items and an `index.md`) into this directory.
+	#[arg(long, value_name = "DIR", conflicts_with_all = ["search", "item", "implementation", "raw_source"])]
+	split_dir: Option<std::path::PathBuf>,
+
+	/// Write the items matched by the search to a selection file for later `--selection` runs.
+	#[arg(long, value_name = "FILE")]
+	save_selection: Option<std::path::PathBuf>,
+
+	/// Render exactly the items recorded in a selection file instead of searching.
+	#[arg(long, value_name = "FILE", conflicts_with_all = ["search", "item", "split_dir", "save_selection"])]
+	selection: Option<std::path::PathBuf>,
+
+	/// Also render the dependencies declared in the manifest, each under its own package header (DEPTH levels deep, default 1 = direct only).
+	#[arg(long, value_name = "DEPTH", num_args = 0..=1, default_missing_value = "1")]
+	with_deps: Option<usize>,
+
+	/// Start the output with a comment naming the ripdoc and toolchain versions, target, command line, and time of the run.
+	#[arg(long)]
+	provenance: bool,
+
+	/// Command line recorded by `--provenance` when it is not this process's own (set by the
+	/// daemon and by `batch` to the invocation they run).
+	#[arg(skip)]
+	invocation: Option<Vec<String>>,
+
+	/// Show only the module tree, each module with a one-line inventory (`pub mod net; // 12 structs, 3 traits, 40 fns`)
+	#[arg(long, conflicts_with_all = ["implementation", "raw_source"])]
+	summary: bool,
+
 	#[command(flatten)]
 	filters: SearchFilterArgs,
 
@@ -167,868 +455,3439 @@ struct ReadmeArgs {
 }
 
 #[derive(Args, Clone)]
-/// Arguments for the `skelebuild` subcommand.
-struct SkelebuildArgs {
-	#[command(subcommand)]
-	command: Option<SkelebuildSubcommand>,
-
-	/// Output file for the skeleton.
-	#[arg(short = 'O', long)]
-	output: Option<std::path::PathBuf>,
-
-	/// Reset the current skelebuild state.
-	#[arg(long)]
-	reset: bool,
+/// Arguments for the `diff-rev` subcommand.
+struct DiffRevArgs {
+	/// Crate directory (or file) inside a git repository
+	#[arg(default_value = "./")]
+	path: std::path::PathBuf,
 
-	/// Plain output (skip module nesting).
-	#[arg(long, conflicts_with = "no_plain")]
-	plain: bool,
+	/// Base revision to render
+	#[arg(long, value_name = "REV")]
+	from: String,
 
-	/// Disable plain output (use module nesting).
-	#[arg(long = "no-plain", conflicts_with = "plain")]
-	no_plain: bool,
+	/// Revision to compare against the base
+	#[arg(long, value_name = "REV", default_value = "HEAD")]
+	to: String,
 
-	/// Print full skelebuild state after the command.
-	#[arg(long = "show-state", default_value_t = false)]
-	show_state: bool,
+	/// Lines of context around each change
+	#[arg(short = 'U', long, value_name = "LINES", default_value_t = 3)]
+	unified: usize,
 
 	#[command(flatten)]
-	/// Common arguments for configuring Ripdoc.
 	common: CommonArgs,
 }
 
-#[derive(Subcommand, Clone)]
-enum SkelebuildSubcommand {
-	/// Add a target to the skeleton.
-	Add {
-		/// Target to add.
-		target: String,
+#[derive(Args, Clone)]
+/// Arguments for the `changed` subcommand.
+struct ChangedArgs {
+	/// Git revspec/range to diff, e.g. `main...HEAD` (default: uncommitted changes against `HEAD`).
+	#[arg(long, value_name = "REVSPEC", conflicts_with = "staged")]
+	git: Option<String>,
 
-		/// Item paths to add (uses path-search mode when present).
-		#[arg(value_name = "ITEM")]
-		items: Vec<String>,
+	/// Use staged changes (`git diff --cached`).
+	#[arg(long, default_value_t = false)]
+	staged: bool,
 
-		/// Include the elided source implementation for this item (default: true).
-		#[arg(long, default_value_t = true)]
-		implementation: bool,
+	/// Only include Rust source files (`.rs`).
+	#[arg(long, default_value_t = false)]
+	only_rust: bool,
 
-		/// Exclude implementation spans (show signatures only).
-		#[arg(long = "no-implementation", conflicts_with = "implementation")]
-		no_implementation: bool,
+	/// Only include changed files matching this glob (repo-relative, repeatable).
+	#[arg(long, value_name = "GLOB")]
+	include: Vec<String>,
 
-		/// Include the literal, unelided source code for the containing file.
-		#[arg(short = 's', long, alias = "source", default_value_t = false)]
-		raw_source: bool,
+	/// Skip changed files matching this glob (repo-relative, repeatable).
+	#[arg(long, value_name = "GLOB")]
+	exclude: Vec<String>,
 
-		/// Include private items when resolving targets (default: true).
-		#[arg(short = 'p', long, default_value_t = true)]
-		private: bool,
+	/// Also report the matching lines of the original path of renamed or copied files.
+	#[arg(long, default_value_t = false)]
+	with_origins: bool,
 
-		/// Exclude private items when resolving targets.
-		#[arg(long = "no-private", conflicts_with = "private")]
-		no_private: bool,
+	#[command(flatten)]
+	limits: ChangedContextArgs,
 
-		/// Disable validation (allows adding even if it won't resolve until later).
-		#[arg(long = "no-validate", default_value_t = false)]
-		no_validate: bool,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Strict mode: disable all heuristics (no auto-rewriting crate prefixes).
-		#[arg(long, default_value_t = false)]
-		strict: bool,
+#[derive(Args, Clone)]
+/// Arguments for the `diff` subcommand.
+struct DiffArgs {
+	/// Old version of the API, e.g. `serde@1.0.190` or a crate directory
+	old: String,
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
+	/// New version of the API, e.g. `serde@1.0.200` or a crate directory
+	#[arg(default_value = "./")]
+	new: String,
 
-		/// Plain output (skip module nesting).
-		#[arg(long)]
-		plain: bool,
-	},
-	/// Add an arbitrary raw source snippet by file and line range.
-	AddRaw {
-		/// Raw source spec: `/path/to/file.rs[:start[:end]]` (1-based lines).
-		spec: String,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
-	},
-	/// Add an entire file from disk as raw source.
-	AddFile {
-		/// Path to the file to include.
-		file: std::path::PathBuf,
+#[derive(Args, Clone)]
+/// Arguments for the `lint-exports` subcommand.
+struct LintExportsArgs {
+	/// Target to analyze - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
-	},
-	/// Add changed-context from a git diff (rustdoc items + raw hunks).
-	AddChanged {
-		/// Git revspec/range to diff (passed to `git diff --name-only`).
-		/// Example: `main...HEAD`.
-		#[arg(long, value_name = "REVSPEC", conflicts_with = "staged")]
-		git: Option<String>,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Use staged changes (`git diff --name-only --cached`).
-		#[arg(long, default_value_t = false)]
-		staged: bool,
+#[derive(Args, Clone)]
+/// Arguments for the `externs` subcommand.
+struct ExternsArgs {
+	/// Target to analyze - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Only include Rust source files (`.rs`).
-		#[arg(long, default_value_t = false)]
-		only_rust: bool,
+	/// Also report toolchain crates (`std`, `core`, `alloc`).
+	#[arg(long, default_value_t = false)]
+	std: bool,
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
-	},
-	/// Update an existing target entry.
-	Update {
-		/// Target spec to update (matches like `inject --after-target`).
-		spec: String,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Enable implementation extraction for this entry.
-		#[arg(long, conflicts_with = "no_implementation")]
-		implementation: bool,
-		/// Disable implementation extraction for this entry.
-		#[arg(long = "no-implementation", conflicts_with = "implementation")]
-		no_implementation: bool,
+#[derive(Args, Clone)]
+/// Arguments for the `summary` subcommand.
+struct SummaryArgs {
+	/// Target to summarize - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Enable raw-source inclusion for this entry.
-		#[arg(long, conflicts_with = "no_raw_source")]
-		raw_source: bool,
-		/// Disable raw-source inclusion for this entry.
-		#[arg(long = "no-raw-source", conflicts_with = "raw_source")]
-		no_raw_source: bool,
+	/// Number of key types and traits to show.
+	#[arg(long, default_value_t = 8)]
+	top: usize,
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
-	},
-	/// Inject manual commentary.
-	///
-	/// Examples:
-	///   # Positional content
-	///   ripdoc skelebuild inject "## Notes\nMy commentary" --at 0
-	///
-	///   # From stdin (heredoc)
-	///   ripdoc skelebuild inject --at 0 <<'EOF'
-	///   ## Notes
-	///   My commentary
-	///   EOF
-	///
-	///   # From stdin (pipe)
-	///   cat notes.md | ripdoc skelebuild inject --at 0
-	///
-	///   # From file
-	///   ripdoc skelebuild inject --from-file notes.md --at 0
-	///
-	///   # After a target
-	///   ripdoc skelebuild inject "## Context" --after-target crate::module::Type
-	Inject {
-		/// Text to inject.
-		content: Option<String>,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Read injection content from stdin.
-		#[arg(long, default_value_t = false, conflicts_with = "from_file")]
-		from_stdin: bool,
+#[derive(Args, Clone)]
+/// Arguments for the `stats` subcommand.
+struct StatsArgs {
+	/// Target to measure - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Read injection content from a file.
-		#[arg(long, value_name = "PATH", conflicts_with = "from_stdin")]
-		from_file: Option<std::path::PathBuf>,
+	/// Break item counts down by the cargo features gating them (builds with all features).
+	#[arg(long)]
+	by_feature: bool,
 
-		/// Treat `\\n` / `\\t` as literal characters.
-		#[arg(long, default_value_t = false)]
-		literal: bool,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Inject after this entry (target path or injection content prefix).
-		#[arg(long, conflicts_with_all = ["at", "after_target", "before_target"])]
-		after: Option<String>,
+#[derive(Args, Clone)]
+/// Arguments for the `traitgraph` subcommand.
+struct TraitGraphArgs {
+	/// Target to graph - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Inject after a matching target (recommended).
-		#[arg(long, conflicts_with_all = ["at", "after", "before_target"])]
-		after_target: Option<String>,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Inject before a matching target.
-		#[arg(long, conflicts_with_all = ["at", "after", "after_target"])]
-		before_target: Option<String>,
+#[derive(Args, Clone)]
+/// Arguments for the `dyn-compat` subcommand.
+struct DynCompatArgs {
+	/// Target to check - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
 
-		/// Inject at this numeric index (0-based, use `status` to see indices).
-		#[arg(long, conflicts_with_all = ["after", "after_target", "before_target"])]
-		at: Option<usize>,
+	#[command(flatten)]
+	common: CommonArgs,
+}
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
+#[derive(Args, Clone)]
+/// Arguments for the `impls` subcommand.
+struct ImplsArgs {
+	/// Target to search - a directory, file path, or a module name
+	target: String,
+
+	/// Trait or type to look up, by full path or trailing segments (e.g. `Display`, `fmt::Display`)
+	query: String,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `open` subcommand.
+struct OpenArgs {
+	/// Target holding the item - a directory, file path, or a crate name; `serde::de::Deserialize` names the item directly
+	target: String,
+
+	/// Item path to locate, by full path or trailing segments (defaults to the target's module path)
+	#[arg(value_name = "ITEM")]
+	item: Option<String>,
+
+	/// Open the location in `$VISUAL`/`$EDITOR` instead of printing it.
+	#[arg(long)]
+	edit: bool,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `man` subcommand.
+struct ManArgs {
+	/// Target holding the item - a directory, file path, or a crate name; `serde::de::Deserialize` names the item directly
+	target: String,
+
+	/// Item path to document, by full path or trailing segments (defaults to the target's module path)
+	#[arg(value_name = "ITEM")]
+	item: Option<String>,
+
+	/// Page width in columns; docs are wrapped to fit.
+	#[arg(long, value_name = "N", default_value_t = 80)]
+	width: usize,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `coverage` subcommand.
+struct CoverageArgs {
+	/// Target to check - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
+
+	/// Exit with an error when a crate's doc coverage is below this percentage (for CI).
+	#[arg(long, value_name = "PCT")]
+	fail_under: Option<f64>,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `render-coverage` subcommand.
+struct RenderCoverageArgs {
+	/// Target to check - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
+
+	/// Exit with an error when any item is missing (useful as a regression check).
+	#[arg(long)]
+	strict: bool,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `where` subcommand.
+struct WhereArgs {
+	/// Target to locate - a directory, file path, or a crate name
+	#[arg(default_value = "./")]
+	target: String,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `try` subcommand.
+struct TryArgs {
+	/// Crate to try out - a crate name (optionally `name@version`), directory, or file path
+	target: String,
+
+	/// Only print the project's paths; do not open `$VISUAL`/`$EDITOR`.
+	#[arg(long)]
+	no_edit: bool,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `fingerprint` subcommand.
+struct FingerprintArgs {
+	/// Target to fingerprint - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
+
+	/// Fail unless the fingerprint matches this hash (useful in CI).
+	#[arg(long, value_name = "HASH")]
+	expect: Option<String>,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `api-baseline` subcommand.
+struct ApiBaselineArgs {
+	#[command(subcommand)]
+	command: ApiBaselineSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+enum ApiBaselineSubcommand {
+	/// Write the current public items (paths and signature hashes) to the baseline file.
+	Generate {
+		/// Target to record - a directory, file path, or a module name
+		#[arg(default_value = "./")]
+		target: String,
+
+		/// Baseline file to write.
+		#[arg(long, value_name = "PATH", default_value = ripdoc::core_api::DEFAULT_BASELINE_FILE)]
+		file: std::path::PathBuf,
+
+		#[command(flatten)]
+		common: CommonArgs,
 	},
-	/// Remove a target from the skeleton.
-	Remove {
-		/// Target to remove.
+	/// Fail if the public API has items that are missing from the baseline file or whose signatures changed.
+	Check {
+		/// Target to check - a directory, file path, or a module name
+		#[arg(default_value = "./")]
 		target: String,
 
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
+		/// Baseline file to check against.
+		#[arg(long, value_name = "PATH", default_value = ripdoc::core_api::DEFAULT_BASELINE_FILE)]
+		file: std::path::PathBuf,
+
+		#[command(flatten)]
+		common: CommonArgs,
 	},
-	/// Clear all targets and reset state.
-	Reset {
-		/// Output file for the skeleton.
-		#[arg(short = 'O', long)]
-		output: Option<std::path::PathBuf>,
+}
 
-		/// Plain output (skip module nesting).
-		#[arg(long)]
-		plain: bool,
+#[derive(Args, Clone)]
+/// Arguments for the `export` subcommand.
+struct ExportArgs {
+	#[command(subcommand)]
+	command: ExportSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+enum ExportSubcommand {
+	/// One record per item: path, kind, signature, docs, span, visibility, and cfgs.
+	Items {
+		/// Target to export - a directory, file path, or a module name
+		#[arg(default_value = "./")]
+		target: String,
+
+		#[command(flatten)]
+		common: CommonArgs,
 	},
-	/// Show current targets and output path.
-	Status {
-		/// Show entry keys in a machine-parsable format.
-		#[arg(long, default_value_t = false)]
-		keys: bool,
+	/// Rendered skeleton split at item and module boundaries, each chunk with a metadata header.
+	Chunks {
+		/// Target to export - a directory, file path, or a module name
+		#[arg(default_value = "./")]
+		target: String,
+
+		/// Upper bound on characters per chunk (single items larger than this stay whole).
+		#[arg(long, value_name = "N", default_value_t = 4000)]
+		max_chars: usize,
+
+		#[command(flatten)]
+		common: CommonArgs,
 	},
-	/// Preview the rebuilt output to stdout.
-	Preview,
-	/// Rebuild the output file without adding anything.
-	Rebuild,
+	/// One Markdown bundle per module: module docs, files pulled in with `doc = include_str!(...)`, and the module's skeleton.
+	Modules {
+		/// Target to export - a directory, file path, or a module name
+		#[arg(default_value = "./")]
+		target: String,
+
+		/// Write one `.md` file per module into this directory instead of printing.
+		#[arg(long, value_name = "DIR")]
+		out_dir: Option<std::path::PathBuf>,
+
+		#[command(flatten)]
+		common: CommonArgs,
+	},
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `lsp` subcommand.
+struct LspArgs {
+	/// Target to serve (defaults to the workspace root sent by the editor)
+	#[arg(long)]
+	target: Option<String>,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `tui` subcommand.
+struct TuiArgs {
+	/// Target to browse - a directory, file path, or a module name
+	#[arg(default_value = "./")]
+	target: String,
+
+	#[command(flatten)]
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `alias` subcommand.
+struct AliasArgs {
+	#[command(subcommand)]
+	command: AliasSubcommand,
 }
 
 #[derive(Subcommand, Clone)]
-enum Command {
-	/// Print a crate skeleton (default).
-	Print(PrintArgs),
-	/// Produce a structured item listing.
-	List(ListArgs),
-	/// Emit raw rustdoc JSON.
-	Raw(PrintArgs),
-	/// Fetch and print the README of the target crate.
-	Readme(ReadmeArgs),
-	/// Build a skeleton incrementally.
-	Skelebuild(SkelebuildArgs),
+enum AliasSubcommand {
+	/// Add or replace an alias; relative paths are stored as absolute paths.
+	Add {
+		/// Alias name (letters, digits, `_`, `-`).
+		name: String,
+		/// Target the alias expands to, e.g. a workspace member directory.
+		target: String,
+	},
+	/// Remove an alias.
+	Remove {
+		/// Alias name.
+		name: String,
+	},
+	/// List stored aliases.
+	List,
 }
 
-#[derive(Parser)]
-#[command(author, version, about, long_about = "Query Rust docs and crate API from the command line.")]
-/// Parsed command-line options for the ripdoc CLI.
-struct Cli {
+#[derive(Args, Clone)]
+/// Arguments for the `cache` subcommand.
+struct CacheArgs {
 	#[command(subcommand)]
-	command: Command,
+	command: CacheSubcommand,
 }
 
-/// Ensure the nightly toolchain and rust-docs JSON component are present.
-fn check_nightly_toolchain() -> Result<(), String> {
-	// First, check if rustup is available
-	let rustup_available = ProcessCommand::new("rustup")
-		.arg("--version")
-		.stderr(Stdio::null())
-		.stdout(Stdio::null())
-		.status()
-		.map(|status| status.success())
-		.unwrap_or(false);
+#[derive(Subcommand, Clone)]
+enum CacheSubcommand {
+	/// List cached rustdoc builds and docs.rs downloads, largest first, with their package, version, and features.
+	Stats {
+		/// Print the entries as JSON.
+		#[arg(long)]
+		json: bool,
+	},
+	/// Delete every cached build and download.
+	Clear,
+	/// Delete entries older than `--max-age`, then the oldest entries until the cache fits in `--max-size`.
+	Gc {
+		/// Maximum entry age, e.g. `30d`, `12h`, `90m` (units: s, m, h, d, w).
+		#[arg(long, value_parser = parse_age)]
+		max_age: Option<Duration>,
+		/// Maximum total cache size, e.g. `2G`, `500M` (units: K, M, G, T; powers of 1024).
+		#[arg(long, value_parser = parse_size)]
+		max_size: Option<u64>,
+	},
+	/// Print the cache directory.
+	Path,
+}
 
-	if rustup_available {
-		// Check if nightly toolchain is installed via rustup
-		let output = ProcessCommand::new("rustup")
-			.args(["run", "nightly", "rustc", "--version"])
-			.stderr(Stdio::null())
-			.output()
-			.map_err(|e| format!("Failed to run rustup: {e}"))?;
+/// Parse an age such as `30d` or `12h`.
+fn parse_age(value: &str) -> Result<Duration, String> {
+	let value = value.trim();
+	let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+	let count: u64 = value[..split].parse().map_err(|_| format!("invalid age '{value}'"))?;
+	let unit = match &value[split..] {
+		"" | "s" => 1,
+		"m" => 60,
+		"h" => 60 * 60,
+		"d" => 24 * 60 * 60,
+		"w" => 7 * 24 * 60 * 60,
+		other => return Err(format!("unknown age unit '{other}' (expected s, m, h, d, or w)")),
+	};
+	Ok(Duration::from_secs(count * unit))
+}
 
-		if !output.status.success() {
-			return Err("ripdoc requires the nightly toolchain to be installed.\nRun: rustup toolchain install nightly".to_string());
-		}
-	} else {
-		// rustup is not available - check for nightly rustc directly
-		let output = ProcessCommand::new("rustc")
-			.arg("--version")
-			.output()
-			.map_err(|e| format!("Failed to run rustc: {e}\nEnsure nightly Rust is installed and available in PATH."))?;
+/// Parse a size such as `2G` or `500M`.
+fn parse_size(value: &str) -> Result<u64, String> {
+	let value = value.trim();
+	let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+	let count: u64 = value[..split].parse().map_err(|_| format!("invalid size '{value}'"))?;
+	let unit = value[split..].trim_end_matches("iB").trim_end_matches('B');
+	let shift = match unit.to_ascii_uppercase().as_str() {
+		"" => 0,
+		"K" => 10,
+		"M" => 20,
+		"G" => 30,
+		"T" => 40,
+		other => return Err(format!("unknown size unit '{other}' (expected K, M, G, or T)")),
+	};
+	Ok(count << shift)
+}
 
-		if !output.status.success() {
-			return Err("ripdoc requires a nightly Rust toolchain.\nEnsure nightly Rust is installed and available in PATH.".to_string());
-		}
+/// Format a byte count with a binary unit, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+	const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+	let mut size = bytes as f64;
+	let mut unit = 0;
+	while size >= 1024.0 && unit + 1 < UNITS.len() {
+		size /= 1024.0;
+		unit += 1;
+	}
+	if unit == 0 { format!("{bytes} B") } else { format!("{size:.1} {}", UNITS[unit]) }
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `daemon` subcommand.
+struct DaemonArgs {
+	/// Stop the running daemon.
+	#[arg(long, default_value_t = false, conflicts_with = "status")]
+	stop: bool,
+
+	/// Report whether a daemon is running.
+	#[arg(long, default_value_t = false)]
+	status: bool,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `completions` subcommand.
+struct CompletionsArgs {
+	/// Shell to generate the completion script for.
+	shell: clap_complete::Shell,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the hidden `__complete-items` helper called by completion scripts.
+struct CompleteItemsArgs {
+	/// Crate or path target whose items are completed.
+	target: String,
+
+	/// Item path typed so far, relative to the target (e.g. `de::Des`).
+	#[arg(default_value = "")]
+	prefix: String,
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `batch` subcommand.
+struct BatchArgs {
+	/// TOML file of `[[query]]` tables, each with `name`, `command`, optional `target`, `args`, and `output`.
+	file: std::path::PathBuf,
+
+	/// Stop at the first failing query instead of running the rest.
+	#[arg(long)]
+	fail_fast: bool,
+}
+
+/// A batch file: queries run in order within one process.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchFile {
+	/// Queries to run.
+	#[serde(rename = "query", default)]
+	queries: Vec<BatchQuery>,
+}
+
+/// One command of a batch file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchQuery {
+	/// Label used in progress and error messages.
+	name: String,
+	/// Subcommand to run, e.g. `print` or `list`.
+	command: String,
+	/// Target passed before the other arguments.
+	target: Option<String>,
+	/// Further command-line arguments, e.g. `["--search", "Deserialize"]`.
+	#[serde(default)]
+	args: Vec<String>,
+	/// File receiving the output, relative to the batch file; stdout when absent.
+	output: Option<std::path::PathBuf>,
+}
+
+impl BatchQuery {
+	/// The query as a command line: `ripdoc <command> [target] <args>...`.
+	fn argv(&self) -> Vec<String> {
+		let mut argv = vec!["ripdoc".to_string(), self.command.clone()];
+		argv.extend(self.target.clone());
+		argv.extend(self.args.iter().cloned());
+		argv
+	}
+}
+
+#[derive(Args, Clone)]
+/// Arguments for the `skelebuild` subcommand.
+struct SkelebuildArgs {
+	#[command(subcommand)]
+	command: Option<SkelebuildSubcommand>,
+
+	/// Output file for the skeleton.
+	#[arg(short = 'O', long)]
+	output: Option<std::path::PathBuf>,
+
+	/// Reset the current skelebuild state.
+	#[arg(long)]
+	reset: bool,
+
+	/// Plain output (skip module nesting).
+	#[arg(long, conflicts_with = "no_plain")]
+	plain: bool,
+
+	/// Disable plain output (use module nesting).
+	#[arg(long = "no-plain", conflicts_with = "plain")]
+	no_plain: bool,
+
+	/// Render entry notes as HTML comments above their entries.
+	#[arg(long, conflicts_with = "no_render_notes")]
+	render_notes: bool,
+
+	/// Stop rendering entry notes.
+	#[arg(long = "no-render-notes", conflicts_with = "render_notes")]
+	no_render_notes: bool,
+
+	/// Refuse to rebuild if crate versions, features, or toolchain differ from `ripdoc.lock`.
+	#[arg(long, global = true, default_value_t = false)]
+	locked: bool,
+
+	/// Print full skelebuild state after the command.
+	#[arg(long = "show-state", default_value_t = false)]
+	show_state: bool,
+
+	/// Directory holding the skelebuild state [default: `RIPDOC_STATE_DIR`, or `ripdoc` under
+	/// the platform state directory].
+	#[arg(long, value_name = "DIR", global = true)]
+	state_dir: Option<std::path::PathBuf>,
+
+	#[command(flatten)]
+	/// Common arguments for configuring Ripdoc.
+	common: CommonArgs,
+}
+
+#[derive(Args, Clone, Debug, Default)]
+/// Budgets controlling how much context `add-changed` resolves per hunk and overall.
+///
+/// Flags left out fall back to the `[changed]` table of `config.toml` in the ripdoc config
+/// directory, then to the defaults shown.
+struct ChangedContextArgs {
+	/// Lines of surrounding context considered around each hunk [default: 30].
+	#[arg(long, value_name = "LINES")]
+	context_lines: Option<usize>,
+
+	/// Maximum length of each raw hunk snippet [default: 220].
+	#[arg(long, value_name = "LINES", value_parser = parse_positive)]
+	max_snippet_lines: Option<usize>,
+
+	/// Maximum number of overlapping items added per hunk [default: 6].
+	#[arg(long, value_name = "N", value_parser = parse_positive)]
+	max_items_per_hunk: Option<usize>,
+
+	/// Maximum number of nearby items added when no item overlaps a hunk; 0 adds none [default: 3].
+	#[arg(long, value_name = "N")]
+	nearest_item_limit: Option<usize>,
+
+	/// Maximum distance (in lines) for a nearby item to be considered [default: 80].
+	#[arg(long, value_name = "LINES")]
+	nearest_item_max_distance: Option<usize>,
+
+	/// Maximum number of targets added across all hunks [default: 200].
+	#[arg(long, value_name = "N")]
+	max_targets: Option<usize>,
+}
+
+/// Parse a count of at least 1.
+fn parse_positive(value: &str) -> Result<usize, String> {
+	match value.trim().parse() {
+		Ok(0) => Err("must be at least 1".to_string()),
+		Ok(count) => Ok(count),
+		Err(_) => Err(format!("invalid count '{value}'")),
+	}
+}
+
+impl ChangedContextArgs {
+	/// The limits for this run: the flags given, then the config file, then the defaults.
+	fn resolve(&self) -> Result<ChangedContextLimits, Box<dyn Error>> {
+		let configured = match config_dir() {
+			Ok(dir) => ChangedContextLimits::load(&dir.join(CONFIG_FILE))?,
+			Err(_) => ChangedContextLimits::default(),
+		};
+		Ok(self.over(configured))
+	}
+
+	/// `base` with the limits given as flags replaced.
+	fn over(&self, base: ChangedContextLimits) -> ChangedContextLimits {
+		ChangedContextLimits {
+			context_lines: self.context_lines.unwrap_or(base.context_lines),
+			max_snippet_lines: self.max_snippet_lines.unwrap_or(base.max_snippet_lines),
+			max_items_per_hunk: self.max_items_per_hunk.unwrap_or(base.max_items_per_hunk),
+			nearest_item_limit: self.nearest_item_limit.unwrap_or(base.nearest_item_limit),
+			nearest_item_max_distance: self.nearest_item_max_distance.unwrap_or(base.nearest_item_max_distance),
+			max_targets: self.max_targets.unwrap_or(base.max_targets),
+		}
+	}
+}
+
+/// Settings file in the ripdoc config directory.
+const CONFIG_FILE: &str = "config.toml";
+
+/// Resolved budgets for changed-context resolution, also read from the `[changed]` table of
+/// the config file under the flag names, e.g. `max-targets = 400`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct ChangedContextLimits {
+	context_lines: usize,
+	max_snippet_lines: usize,
+	max_items_per_hunk: usize,
+	nearest_item_limit: usize,
+	nearest_item_max_distance: usize,
+	max_targets: usize,
+}
+
+impl Default for ChangedContextLimits {
+	fn default() -> Self {
+		Self {
+			context_lines: 30,
+			max_snippet_lines: 220,
+			max_items_per_hunk: 6,
+			nearest_item_limit: 3,
+			nearest_item_max_distance: 80,
+			max_targets: 200,
+		}
+	}
+}
+
+impl ChangedContextLimits {
+	/// The `[changed]` table of the config file at `path`, with defaults for whatever it leaves
+	/// out; all defaults when the file does not exist.
+	fn load(path: &std::path::Path) -> Result<Self, Box<dyn Error>> {
+		#[derive(Default, serde::Deserialize)]
+		struct ConfigFile {
+			#[serde(default)]
+			changed: ChangedContextLimits,
+		}
+
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+		let config: ConfigFile = toml::from_str(&content).map_err(|e| format!("Invalid config file {}: {e}", path.display()))?;
+		// Like the flags, these caps start at 1: a hunk always keeps one item and one line.
+		for (name, value) in [("max-snippet-lines", config.changed.max_snippet_lines), ("max-items-per-hunk", config.changed.max_items_per_hunk)] {
+			if value == 0 {
+				return Err(format!("Invalid config file {}: changed.{name} must be at least 1", path.display()).into());
+			}
+		}
+		Ok(config.changed)
+	}
+}
+
+#[derive(Subcommand, Clone)]
+enum SkelebuildSubcommand {
+	/// Add a target to the skeleton.
+	Add {
+		/// Target to add.
+		target: String,
+
+		/// Item paths to add (uses path-search mode when present).
+		#[arg(value_name = "ITEM")]
+		items: Vec<String>,
+
+		/// Include the elided source implementation for this item (default: true).
+		#[arg(long, default_value_t = true)]
+		implementation: bool,
+
+		/// Exclude implementation spans (show signatures only).
+		#[arg(long = "no-implementation", conflicts_with = "implementation")]
+		no_implementation: bool,
+
+		/// Include the literal, unelided source code for the containing file.
+		#[arg(short = 's', long, alias = "source", default_value_t = false)]
+		raw_source: bool,
+
+		/// Include private items when resolving targets (default: true).
+		#[arg(short = 'p', long, default_value_t = true)]
+		private: bool,
+
+		/// Exclude private items when resolving targets.
+		#[arg(long = "no-private", conflicts_with = "private")]
+		no_private: bool,
+
+		/// Disable validation (allows adding even if it won't resolve until later).
+		#[arg(long = "no-validate", default_value_t = false)]
+		no_validate: bool,
+
+		/// Strict mode: disable all heuristics (no auto-rewriting crate prefixes).
+		#[arg(long, default_value_t = false)]
+		strict: bool,
+
+		/// Features to enable when documenting the target's crate (stored with the entry).
+		#[arg(short = 'F', long, value_delimiter = ',')]
+		features: Vec<String>,
+
+		/// Enable all features of the target's crate.
+		#[arg(long, default_value_t = false)]
+		all_features: bool,
+
+		/// Disable the default features of the target's crate.
+		#[arg(long, default_value_t = false)]
+		no_default_features: bool,
+
+		/// Also add local types the target references (fields, signatures, bounds), following
+		/// references DEPTH hops (default: 1).
+		#[arg(long, value_name = "DEPTH", num_args = 0..=1, default_missing_value = "1")]
+		with_deps: Option<usize>,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+
+		/// Plain output (skip module nesting).
+		#[arg(long)]
+		plain: bool,
+	},
+	/// Add an arbitrary raw source snippet by file and line range.
+	AddRaw {
+		/// Raw source spec: `/path/to/file.rs[:start[:end]]` (1-based lines).
+		spec: String,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Add an entire file from disk as raw source.
+	AddFile {
+		/// Path to the file to include.
+		file: std::path::PathBuf,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Add changed-context from a git diff (rustdoc items + raw hunks).
+	AddChanged {
+		/// Git revspec/range to diff (passed to `git diff --name-only`).
+		/// Example: `main...HEAD`.
+		#[arg(long, value_name = "REVSPEC", conflicts_with = "staged")]
+		git: Option<String>,
+
+		/// Use staged changes (`git diff --name-only --cached`).
+		#[arg(long, default_value_t = false)]
+		staged: bool,
+
+		/// Only include Rust source files (`.rs`).
+		#[arg(long, default_value_t = false)]
+		only_rust: bool,
+
+		/// Only include changed files matching this glob (repo-relative, repeatable).
+		/// Patterns without a `/` match at any depth, e.g. `*.rs` or `generated`.
+		#[arg(long, value_name = "GLOB")]
+		include: Vec<String>,
+
+		/// Skip changed files matching this glob (repo-relative, repeatable), e.g. `vendor/**`.
+		#[arg(long, value_name = "GLOB")]
+		exclude: Vec<String>,
+
+		/// Insert an empty "## Reviewer notes" section after each changed hunk snippet.
+		#[arg(long, default_value_t = false)]
+		with_notes: bool,
+
+		/// For renamed or copied files, also add the matching lines of the original path
+		/// as raw source when it still exists on disk.
+		#[arg(long, default_value_t = false)]
+		with_origins: bool,
+
+		#[command(flatten)]
+		/// Budgets for how much context each hunk may pull in.
+		limits: ChangedContextArgs,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Add context for failing tests from a saved `cargo test` report (items + raw hunks).
+	///
+	/// Each failing test contributes its definition, its panic locations (and the documented line
+	/// for doctests), plus the rustdoc items overlapping them. A summary of the failures is injected
+	/// before the added entries.
+	///
+	/// Examples:
+	///   cargo test --message-format json > test.log
+	///   ripdoc skelebuild add-test-failures --from cargo-json test.log
+	AddTestFailures {
+		/// Format of the report.
+		#[arg(long, value_enum, default_value = "cargo-json")]
+		from: TestReportFormat,
+
+		/// Report file (`-` reads stdin).
+		file: std::path::PathBuf,
+
+		/// Insert an empty "## Reviewer notes" section after each snippet.
+		#[arg(long, default_value_t = false)]
+		with_notes: bool,
+
+		#[command(flatten)]
+		/// Budgets for how much context each location may pull in.
+		limits: ChangedContextArgs,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Add context for compiler diagnostics from a saved `cargo check` report (items + raw hunks).
+	///
+	/// Each error or warning contributes its primary spans and the spans of its notes, plus the
+	/// rustdoc items overlapping them. A list of the diagnostics is injected before the added entries.
+	///
+	/// Examples:
+	///   cargo check --message-format json > check.json
+	///   ripdoc skelebuild add-diagnostics --from cargo-check-json check.json --errors-only
+	AddDiagnostics {
+		/// Format of the report.
+		#[arg(long, value_enum, default_value = "cargo-check-json")]
+		from: DiagnosticsFormat,
+
+		/// Report file (`-` reads stdin).
+		file: std::path::PathBuf,
+
+		/// Skip warnings and other non-error diagnostics.
+		#[arg(long, default_value_t = false)]
+		errors_only: bool,
+
+		/// Insert an empty "## Reviewer notes" section after each snippet.
+		#[arg(long, default_value_t = false)]
+		with_notes: bool,
+
+		#[command(flatten)]
+		/// Budgets for how much context each span may pull in.
+		limits: ChangedContextArgs,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Update an existing entry in place.
+	///
+	/// Examples:
+	///   ripdoc skelebuild update bat::config::Config --no-implementation
+	///   ripdoc skelebuild update src/main.rs:10:40 --start 12 --end 48
+	///   ripdoc skelebuild update bat::config::Config --to-raw --end 80
+	///   ripdoc skelebuild update src/main.rs:10:40 --to-target bat::config::Config
+	Update {
+		/// Entry to update: a target spec (matches like `inject --after-target`) or a raw source
+		/// (`file`, `file:start:end`, or its canonical key).
+		spec: String,
+
+		/// Enable implementation extraction for this entry.
+		#[arg(long, conflicts_with = "no_implementation")]
+		implementation: bool,
+		/// Disable implementation extraction for this entry.
+		#[arg(long = "no-implementation", conflicts_with = "implementation")]
+		no_implementation: bool,
+
+		/// Enable raw-source inclusion for this entry.
+		#[arg(long, conflicts_with = "no_raw_source")]
+		raw_source: bool,
+		/// Disable raw-source inclusion for this entry.
+		#[arg(long = "no-raw-source", conflicts_with = "raw_source")]
+		no_raw_source: bool,
+
+		/// Point a raw-source entry at a different file.
+		#[arg(long)]
+		file: Option<String>,
+		/// New 1-based start line for a raw-source entry.
+		#[arg(long)]
+		start: Option<usize>,
+		/// New 1-based inclusive end line for a raw-source entry.
+		#[arg(long)]
+		end: Option<usize>,
+
+		/// Convert a target entry into a raw-source entry covering the item's span.
+		#[arg(long, conflicts_with = "to_target")]
+		to_raw: bool,
+		/// Convert a raw-source entry into a target entry for PATH.
+		#[arg(long, value_name = "PATH")]
+		to_target: Option<String>,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Inject manual commentary.
+	///
+	/// Examples:
+	///   # Positional content
+	///   ripdoc skelebuild inject "## Notes\nMy commentary" --at 0
+	///
+	///   # From stdin (heredoc)
+	///   ripdoc skelebuild inject --at 0 <<'EOF'
+	///   ## Notes
+	///   My commentary
+	///   EOF
+	///
+	///   # From stdin (pipe)
+	///   cat notes.md | ripdoc skelebuild inject --at 0
+	///
+	///   # From file
+	///   ripdoc skelebuild inject --from-file notes.md --at 0
+	///
+	///   # After a target
+	///   ripdoc skelebuild inject "## Context" --after-target crate::module::Type
+	Inject {
+		/// Text to inject.
+		content: Option<String>,
+
+		/// Read injection content from stdin.
+		#[arg(long, default_value_t = false, conflicts_with = "from_file")]
+		from_stdin: bool,
+
+		/// Read injection content from a file.
+		#[arg(long, value_name = "PATH", conflicts_with = "from_stdin")]
+		from_file: Option<std::path::PathBuf>,
+
+		/// Treat `\\n` / `\\t` as literal characters.
+		#[arg(long, default_value_t = false)]
+		literal: bool,
+
+		/// Inject after this entry (target path or injection content prefix).
+		#[arg(long, conflicts_with_all = ["at", "after_target", "before_target"])]
+		after: Option<String>,
+
+		/// Inject after a matching target (recommended).
+		#[arg(long, conflicts_with_all = ["at", "after", "before_target"])]
+		after_target: Option<String>,
+
+		/// Inject before a matching target.
+		#[arg(long, conflicts_with_all = ["at", "after", "after_target"])]
+		before_target: Option<String>,
+
+		/// Inject at this numeric index (0-based, use `status` to see indices).
+		#[arg(long, conflicts_with_all = ["after", "after_target", "before_target"])]
+		at: Option<usize>,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Attach a note explaining why an entry is in the skeleton (shown by `status`).
+	///
+	/// Examples:
+	///   ripdoc skelebuild annotate bat::config::Config "entry point for theme loading"
+	///   ripdoc skelebuild annotate src/main.rs:10:40 --clear
+	Annotate {
+		/// Entry to annotate: a target spec or a raw source (matches like `update`).
+		spec: String,
+
+		/// The note.
+		#[arg(required_unless_present = "clear", conflicts_with = "clear")]
+		note: Option<String>,
+
+		/// Remove the entry's note.
+		#[arg(long)]
+		clear: bool,
+	},
+	/// Remove a target from the skeleton.
+	Remove {
+		/// Target to remove.
+		target: String,
+
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+	},
+	/// Clear all targets and reset state.
+	Reset {
+		/// Output file for the skeleton.
+		#[arg(short = 'O', long)]
+		output: Option<std::path::PathBuf>,
+
+		/// Plain output (skip module nesting).
+		#[arg(long)]
+		plain: bool,
+	},
+	/// Show current targets and output path.
+	Status {
+		/// Show entry keys in a machine-parsable format.
+		#[arg(long, default_value_t = false)]
+		keys: bool,
+	},
+	/// Preview the rebuilt output to stdout; `--format json` splits it into per-entry sections with byte ranges and token estimates.
+	Preview {
+		/// Output format (only `json` changes the preview).
+		#[arg(short = 'f', long, value_enum)]
+		format: Option<OutputFormat>,
+	},
+	/// Rebuild the output file without adding anything.
+	Rebuild,
+	/// Set the size budget enforced on every rebuild (persisted in the state).
+	///
+	/// Tokens are approximated as one per four characters. When the output is over budget,
+	/// `--on-exceed` picks the reduction to try before failing with a per-entry size report.
+	///
+	/// Examples:
+	///   ripdoc skelebuild budget --max-tokens 8000 --on-exceed truncate-docs
+	///   ripdoc skelebuild budget --clear
+	Budget {
+		/// Maximum approximate tokens.
+		#[arg(long, value_name = "N", conflicts_with_all = ["max_chars", "clear"], required_unless_present_any = ["max_chars", "clear"])]
+		max_tokens: Option<usize>,
+
+		/// Maximum characters.
+		#[arg(long, value_name = "N", conflicts_with = "clear")]
+		max_chars: Option<usize>,
+
+		/// Reduction applied when the output is over budget.
+		#[arg(long, value_enum, default_value = "error", conflicts_with = "clear")]
+		on_exceed: BudgetExceedArg,
+
+		/// Remove the budget.
+		#[arg(long, default_value_t = false)]
+		clear: bool,
+	},
+	/// Set the extra destinations explicit rebuilds are delivered to (persisted in the state).
+	///
+	/// The output file is always written; on `skelebuild rebuild` and `skelebuild watch`, each
+	/// `--sink` receives the same document afterwards: `stdout` (or `-`), `clipboard`, `file:PATH`,
+	/// or an `http(s)://` URL that the document is POSTed to. The rebuild after each edit (`add`,
+	/// `inject`, ...) only writes the output file. A failing sink is reported as a warning.
+	/// `--header` adds request headers to URL sinks; `$VAR` in a header value is read from the
+	/// environment on every delivery, so tokens stay out of the state file.
+	///
+	/// Examples:
+	///   ripdoc skelebuild sink --sink clipboard
+	///   ripdoc skelebuild sink --sink https://docs.example.com/api/pages --header 'Authorization: Bearer $DOCS_TOKEN'
+	///   ripdoc skelebuild sink --clear
+	Sink {
+		/// Destination for rebuilt output (repeatable).
+		#[arg(long = "sink", value_name = "SINK", conflicts_with = "clear", required_unless_present = "clear")]
+		sinks: Vec<ripdoc::skelebuild::SinkSpec>,
+
+		/// `Name: value` header sent with every URL sink (repeatable).
+		#[arg(long = "header", value_name = "HEADER", conflicts_with = "clear")]
+		headers: Vec<String>,
+
+		/// Remove all sinks.
+		#[arg(long, default_value_t = false)]
+		clear: bool,
+	},
+	/// Rebuild a committed document from a manifest (for CI and pre-push hooks).
+	///
+	/// The manifest is a TOML file with `output`, optional `plain`, and `[[entries]]` tables using
+	/// the same fields as the skelebuild state file. Paths are relative to the manifest.
+	///
+	/// Examples:
+	///   # Regenerate the committed document
+	///   ripdoc skelebuild ci
+	///
+	///   # Fail if the committed document is out of date
+	///   ripdoc skelebuild ci --manifest ripdoc.toml --check
+	Ci {
+		/// Path to the skelebuild manifest.
+		#[arg(long, value_name = "PATH", default_value = ripdoc::skelebuild::DEFAULT_MANIFEST)]
+		manifest: std::path::PathBuf,
+
+		/// Do not write anything; exit non-zero if the output file differs from a fresh rebuild.
+		#[arg(long, default_value_t = false)]
+		check: bool,
+	},
+	/// Rebuild the output whenever sources behind the stored entries change.
+	///
+	/// Watches the package roots of target entries and the files of raw-source entries and
+	/// rebuilds on every change. Cached rustdoc JSON is keyed by a fingerprint of the crate's
+	/// sources, so edited crates miss the cache and are rebuilt. Runs until interrupted.
+	Watch {
+		/// Milliseconds to wait for further changes before rebuilding.
+		#[arg(long, value_name = "MS", default_value_t = 300)]
+		debounce: u64,
+	},
+}
+
+#[derive(Subcommand, Clone)]
+enum Command {
+	/// Print a crate skeleton (default).
+	Print(PrintArgs),
+	/// Produce a structured item listing.
+	List(ListArgs),
+	/// Search every crate in the rustdoc cache, tagging matches with `crate@version`, e.g. `ripdoc search-all Deserializer`.
+	SearchAll(SearchAllArgs),
+	/// Emit raw rustdoc JSON.
+	Raw(PrintArgs),
+	/// Fetch and print the README of the target crate.
+	Readme(ReadmeArgs),
+	/// Build a skeleton incrementally.
+	Skelebuild(SkelebuildArgs),
+	/// Diff the rendered skeleton of a crate between two git revisions.
+	DiffRev(DiffRevArgs),
+	/// Map a git diff to the rustdoc items it touches plus raw hunk snippets, as `skelebuild add-changed` would, without changing the skelebuild state.
+	Changed(ChangedArgs),
+	/// Compare the public API of two targets and classify each change as a semver bump.
+	Diff(DiffArgs),
+	/// Flag dangling re-exports, empty modules, and `pub` items unreachable from the crate root.
+	LintExports(LintExportsArgs),
+	/// List external crates exposed by the public API and the items that leak them.
+	Externs(ExternsArgs),
+	/// Print a one-screen card: description, version, modules, key items, features, and MSRV.
+	Summary(SummaryArgs),
+	/// Report API-surface metrics: items per kind, public/private split, generic items, doc coverage, largest modules, and a rendered token estimate; `--by-feature` shows how much API each cargo feature adds.
+	Stats(StatsArgs),
+	/// Show traits with their supertraits and implementing types; `--format dot` emits Graphviz.
+	#[command(name = "traitgraph")]
+	TraitGraph(TraitGraphArgs),
+	/// List traits with their dyn-compatibility, generic methods, and the functions taking them as `dyn Trait` vs `impl Trait`.
+	DynCompat(DynCompatArgs),
+	/// List the local types implementing a trait, or the traits a type implements (`--auto-impls` adds auto-trait and blanket impls).
+	Impls(ImplsArgs),
+	/// Print where an item is declared as `file:line:column` (quickfix format), or open it in `$EDITOR` with `--edit`.
+	Open(OpenArgs),
+	/// Print a `man`-style page for one item: signature, where clauses, docs, members, impls, and related items.
+	Man(ManArgs),
+	/// Print where a crate's sources live: package root, manifest, crate root file, and registry archive.
+	Where(WhereArgs),
+	/// Create a throwaway cargo project depending on a crate (with the chosen features), write its skeleton next to `src/main.rs`, open both in `$EDITOR`, and print the path.
+	Try(TryArgs),
+	/// Report public items without doc comments, grouped by module; `--fail-under` fails CI below a coverage percentage.
+	Coverage(CoverageArgs),
+	/// Debug the renderer: list indexed items missing from the skeleton and why they were dropped.
+	RenderCoverage(RenderCoverageArgs),
+	/// Print an order-independent hash of the public API; `--format json` adds per-module hashes.
+	Fingerprint(FingerprintArgs),
+	/// Keep a checked-in list of public items and fail `check` when the API grows without updating it.
+	ApiBaseline(ApiBaselineArgs),
+	/// Export crate data for external pipelines, e.g. `ripdoc export items serde --format jsonl`.
+	Export(ExportArgs),
+	/// Manage target aliases, e.g. `ripdoc alias add widgets ./crates/widgets` then `ripdoc print widgets::Button`.
+	Alias(AliasArgs),
+	/// Inspect and prune the on-disk rustdoc cache, e.g. `ripdoc cache gc --max-age 30d --max-size 2G`.
+	Cache(CacheArgs),
+	/// Print a shell completion script; zsh and fish scripts also complete `crate::module::Item` paths for `print` and `skelebuild add`.
+	Completions(CompletionsArgs),
+	/// List item paths of a target starting with a prefix, one per line (used by completion scripts).
+	#[command(name = "__complete-items", hide = true)]
+	CompleteItems(CompleteItemsArgs),
+	/// Run a TOML file of named queries in one process, sharing parsed crates between them.
+	Batch(BatchArgs),
+	/// Keep parsed crates warm and serve `print`, `list`, and `raw` over a local socket.
+	Daemon(DaemonArgs),
+	/// Serve workspace symbols, hover, and `ripdoc/skeleton` requests over the Language Server Protocol on stdio.
+	Lsp(LspArgs),
+	/// Browse a target's module tree with fuzzy search and skeleton previews; `a` adds the selected item to the skelebuild session.
+	Tui(TuiArgs),
+}
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = "Query Rust docs and crate API from the command line.")]
+/// Parsed command-line options for the ripdoc CLI.
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+
+	/// Report per-phase durations (resolve, fetch, rustdoc, cache, index, selection, render, rustfmt) on stderr.
+	#[arg(long, global = true)]
+	timings: bool,
+
+	/// Write per-phase durations as JSON to this file.
+	#[arg(long, global = true, value_name = "FILE")]
+	timings_json: Option<std::path::PathBuf>,
+}
+
+/// Ensure the nightly toolchain and rust-docs JSON component are present.
+fn check_nightly_toolchain() -> Result<(), String> {
+	// First, check if rustup is available
+	let rustup_available = ProcessCommand::new("rustup")
+		.arg("--version")
+		.stderr(Stdio::null())
+		.stdout(Stdio::null())
+		.status()
+		.map(|status| status.success())
+		.unwrap_or(false);
+
+	if rustup_available {
+		// Check if nightly toolchain is installed via rustup
+		let output = ProcessCommand::new("rustup")
+			.args(["run", "nightly", "rustc", "--version"])
+			.stderr(Stdio::null())
+			.output()
+			.map_err(|e| format!("Failed to run rustup: {e}"))?;
+
+		if !output.status.success() {
+			return Err("ripdoc requires the nightly toolchain to be installed.\nRun: rustup toolchain install nightly".to_string());
+		}
+	} else {
+		// rustup is not available - check for nightly rustc directly
+		let output = ProcessCommand::new("rustc")
+			.arg("--version")
+			.output()
+			.map_err(|e| format!("Failed to run rustc: {e}\nEnsure nightly Rust is installed and available in PATH."))?;
+
+		if !output.status.success() {
+			return Err("ripdoc requires a nightly Rust toolchain.\nEnsure nightly Rust is installed and available in PATH.".to_string());
+		}
+
+		let version_str = String::from_utf8_lossy(&output.stdout);
+		if !version_str.contains("nightly") {
+			return Err(format!(
+				"ripdoc requires a nightly Rust toolchain, but found: {}\nEnsure nightly Rust is installed and available in PATH.",
+				version_str.trim()
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// Build a Ripdoc instance configured with common CLI knobs.
+fn build_ripdoc(common: &CommonArgs) -> Ripdoc {
+	let ripdoc = Ripdoc::new()
+		.with_offline(common.offline)
+		.with_auto_impls(common.auto_impls)
+		.with_render_format(common.format.into())
+		.with_verbosity(Verbosity::from_count(common.verbose))
+		.with_source_labels(!common.no_source_labels)
+		.with_collapsed_variants(common.collapse_variants)
+		.with_derive_style(common.derives.into())
+		.with_simplified_lifetimes(common.simplify_lifetimes && !common.exact_signatures)
+		.with_doc_examples(common.examples)
+		.with_docs(if common.doc_summary { DocMode::FirstParagraph } else { common.docs })
+		.with_doc_wrap(common.doc_width)
+		.with_doc_code_stripped(common.strip_doc_code)
+		.with_markdown_options(MarkdownOptions {
+			fence_language: common.fence_lang.into(),
+			max_block_lines: common.max_code_lines,
+			source_headings: !common.no_source_headings,
+		})
+		.with_min_visibility(common.min_visibility)
+		.with_strict_public(common.strict_public)
+		.with_cfg_annotations(common.show_cfg)
+		.with_doc_hidden(common.doc_hidden)
+		.with_workspace(common.workspace)
+		.with_docs_rs(common.docs_rs)
+		.with_docs_rs_config(common.docsrs_config)
+		.with_max_build_time(common.max_build_seconds.map(Duration::from_secs))
+		.with_force_refresh(common.refresh);
+	match &common.working_dir {
+		Some(dir) => ripdoc.with_working_dir(dir.clone()),
+		None => ripdoc,
+	}
+}
+
+/// [`build_ripdoc`] for `skelebuild`, which also takes the directory its state is kept in.
+fn build_skelebuild_ripdoc(args: &SkelebuildArgs) -> Ripdoc {
+	let ripdoc = build_ripdoc(&args.common);
+	match &args.state_dir {
+		Some(dir) => {
+			let dir = ripdoc.in_working_dir(dir);
+			ripdoc.with_state_dir(dir)
+		}
+		None => ripdoc,
+	}
+}
+
+/// Resolve the active search domains specified by the CLI flags.
+fn search_domains_from_filters(filters: &SearchFilterArgs) -> SearchDomain {
+	if filters.search_spec.is_empty() {
+		SearchDomain::default()
+	} else {
+		filters.search_spec.iter().fold(SearchDomain::empty(), |mut acc, spec| {
+			acc |= SearchDomain::from(*spec);
+			acc
+		})
+	}
+}
+
+/// Build a `SearchOptions` value using the provided CLI configuration and query.
+fn build_search_options(common: &CommonArgs, filters: &SearchFilterArgs, query: &str) -> SearchOptions {
+	let mut options = SearchOptions::new(query);
+	options.include_private = common.private;
+	options.include_doc_hidden = common.doc_hidden;
+	options.case_sensitive = filters.search_case_sensitive;
+	options.expand_containers = !filters.direct_match_only;
+	options.domains = search_domains_from_filters(filters);
+	options.fuzzy = filters.fuzzy;
+	options.max_results = filters.max_results;
+	options.deprecation = filters.deprecation();
+	if !filters.kind.is_empty() {
+		options.kinds = Some(filters.kind.iter().map(|&kind| kind.into()).collect());
+	}
+	options
+}
+
+/// Print a skeleton to stdout.
+fn split_path_target_spec(value: &str) -> Option<(String, String)> {
+	let split_at = value.find("::")?;
+	let (left, right_with_sep) = value.split_at(split_at);
+	let right = right_with_sep.strip_prefix("::")?;
+	let left = left.trim();
+	let right = right.trim();
+	if left.is_empty() || right.is_empty() {
+		return None;
+	}
+
+	let looks_like_path = left.contains('/') || left.contains('\\') || left.starts_with('.') || left.starts_with('/');
+	if looks_like_path || std::path::Path::new(left).exists() {
+		Some((left.to_string(), right.to_string()))
+	} else {
+		None
+	}
+}
+
+#[derive(Debug, Clone)]
+struct DiffHunk {
+	file: std::path::PathBuf,
+	start_line: usize,
+	end_line: usize,
+	/// Original path and old-side line range when the file was renamed or copied in this diff.
+	origin: Option<HunkOrigin>,
+}
+
+/// Where a hunk of a renamed or copied file came from, using the old side of the `@@ -a,b` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HunkOrigin {
+	file: std::path::PathBuf,
+	start_line: usize,
+	end_line: usize,
+}
+
+fn git_toplevel() -> Result<std::path::PathBuf, Box<dyn Error>> {
+	git_toplevel_in(std::path::Path::new("."))
+}
+
+fn git_toplevel_in(dir: &std::path::Path) -> Result<std::path::PathBuf, Box<dyn Error>> {
+	let toplevel = ProcessCommand::new("git").arg("-C").arg(dir).args(["rev-parse", "--show-toplevel"]).output()?;
+	if !toplevel.status.success() {
+		return Err("Failed to run `git rev-parse --show-toplevel`; are you in a git repo?".into());
+	}
+	let root = String::from_utf8_lossy(&toplevel.stdout);
+	let root = root.trim();
+	if root.is_empty() {
+		return Err("`git rev-parse --show-toplevel` returned empty output".into());
+	}
+	Ok(std::path::PathBuf::from(root))
+}
+
+fn git_diff_text(dir: &std::path::Path, rev_spec: Option<&str>, staged: bool) -> Result<String, Box<dyn Error>> {
+	let mut cmd = ProcessCommand::new("git");
+	cmd.arg("-C").arg(dir);
+	// Detect renames and copies explicitly so hunks map to the new path regardless of `diff.renames`.
+	cmd.args(["diff", "--unified=0", "--no-color", "--find-renames", "--find-copies"]);
+	if staged {
+		cmd.arg("--cached");
+	}
+	if let Some(spec) = rev_spec {
+		cmd.arg(spec);
+	}
+	let output = cmd.output()?;
+	if !output.status.success() {
+		return Err("Failed to run `git diff --unified=0`".into());
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Find a commit that touches Rust files by walking back from HEAD.
+/// Returns the commit hash if found within the limit.
+fn find_rust_touching_commit(limit: usize) -> Result<String, Box<dyn Error>> {
+	// Get the last N commits
+	let output = ProcessCommand::new("git").args(["log", &format!("-{}", limit), "--format=%H"]).output()?;
+
+	if !output.status.success() {
+		return Err("Failed to run `git log`".into());
+	}
+
+	let commits = String::from_utf8_lossy(&output.stdout);
+	for commit in commits.lines() {
+		let commit = commit.trim();
+		if commit.is_empty() {
+			continue;
+		}
+
+		// Check if this commit touches any .rs files
+		let files_output = ProcessCommand::new("git")
+			.args(["diff-tree", "--no-commit-id", "--name-only", "-r", commit])
+			.output()?;
+
+		if files_output.status.success() {
+			let files = String::from_utf8_lossy(&files_output.stdout);
+			if files.lines().any(|f| f.trim().ends_with(".rs")) {
+				return Ok(commit.to_string());
+			}
+		}
+	}
+
+	Err("No Rust-touching commit found".into())
+}
+
+fn parse_git_diff_hunks(diff: &str, git_root: &std::path::Path, only_rust: bool) -> Vec<DiffHunk> {
+	let mut current_file: Option<std::path::PathBuf> = None;
+	let mut current_origin: Option<std::path::PathBuf> = None;
+	let mut hunks: Vec<DiffHunk> = Vec::new();
+
+	/// Strip git's C-style quoting from a path (used for names with control characters, quotes or
+	/// non-ASCII bytes), decoding `\t`-style and `\ooo` octal escapes back into the raw bytes.
+	fn unquote_path(path: &str) -> String {
+		let path = path.trim();
+		let Some(inner) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+			return path.to_string();
+		};
+		let mut bytes = Vec::with_capacity(inner.len());
+		let mut rest = inner.as_bytes();
+		while let Some((&byte, tail)) = rest.split_first() {
+			rest = tail;
+			if byte != b'\\' {
+				bytes.push(byte);
+				continue;
+			}
+			let Some((&escape, tail)) = rest.split_first() else {
+				bytes.push(b'\\');
+				break;
+			};
+			rest = tail;
+			match escape {
+				b'a' => bytes.push(0x07),
+				b'b' => bytes.push(0x08),
+				b't' => bytes.push(b'\t'),
+				b'n' => bytes.push(b'\n'),
+				b'v' => bytes.push(0x0b),
+				b'f' => bytes.push(0x0c),
+				b'r' => bytes.push(b'\r'),
+				b'0'..=b'7' => {
+					let digits = rest.iter().take(2).take_while(|d| (b'0'..=b'7').contains(d)).count();
+					let value = rest[..digits].iter().fold(u32::from(escape - b'0'), |acc, d| acc * 8 + u32::from(d - b'0'));
+					bytes.push(value as u8);
+					rest = &rest[digits..];
+				}
+				other => bytes.push(other),
+			}
+		}
+		String::from_utf8_lossy(&bytes).into_owned()
+	}
+
+	let is_rust = |path: &std::path::Path| path.extension().and_then(|e| e.to_str()) == Some("rs");
+
+	fn parse_usize_prefix(s: &str) -> Option<(usize, &str)> {
+		let mut end = 0usize;
+		for (idx, ch) in s.char_indices() {
+			if ch.is_ascii_digit() {
+				end = idx + ch.len_utf8();
+			} else {
+				break;
+			}
+		}
+		if end == 0 {
+			return None;
+		}
+		let num = s[..end].parse::<usize>().ok()?;
+		Some((num, &s[end..]))
+	}
+
+	for line in diff.lines() {
+		if line.starts_with("diff --git ") {
+			current_file = None;
+			current_origin = None;
+			continue;
+		}
+
+		// Renames and copies map hunks to the new path and remember where the file came from.
+		if let Some(rest) = line.strip_prefix("rename from ").or_else(|| line.strip_prefix("copy from ")) {
+			current_origin = Some(git_root.join(unquote_path(rest)));
+			continue;
+		}
+		if let Some(rest) = line.strip_prefix("rename to ").or_else(|| line.strip_prefix("copy to ")) {
+			let abs = git_root.join(unquote_path(rest));
+			current_file = (!only_rust || is_rust(&abs)).then_some(abs);
+			continue;
+		}
+
+		if let Some(rest) = line.strip_prefix("+++ ") {
+			let path = unquote_path(rest);
+			if path == "/dev/null" {
+				current_file = None;
+				continue;
+			}
+			let rel = path.strip_prefix("b/").unwrap_or(&path);
+			let abs = git_root.join(rel);
+			if only_rust && !is_rust(&abs) {
+				current_file = None;
+				continue;
+			}
+			current_file = Some(abs);
+			continue;
+		}
+
+		if !line.starts_with("@@") {
+			continue;
+		}
+		let Some(ref file) = current_file else {
+			continue;
+		};
+
+		// `@@ -a,b +c,d @@`: the new side locates the hunk, the old side locates it in a rename/copy origin.
+		let Some(ranges) = line.strip_prefix("@@").and_then(|rest| rest.split("@@").next()) else {
+			continue;
+		};
+		let parse_range = |prefix: char| {
+			let range = ranges.split_whitespace().find_map(|part| part.strip_prefix(prefix))?;
+			let (start, rest) = parse_usize_prefix(range)?;
+			let len = match rest.strip_prefix(',') {
+				Some(rest) => parse_usize_prefix(rest).map_or(1, |(len, _)| len),
+				None => 1,
+			};
+			// A zero-length range means the lines sit right after `start`; keep one line as an anchor.
+			let start = start.max(1);
+			Some((start, start.saturating_add(len.max(1) - 1)))
+		};
+		let Some((start, end)) = parse_range('+') else {
+			continue;
+		};
+		let origin = current_origin.as_ref().and_then(|origin| {
+			let (start_line, end_line) = parse_range('-')?;
+			Some(HunkOrigin {
+				file: origin.clone(),
+				start_line,
+				end_line,
+			})
+		});
+		hunks.push(DiffHunk {
+			file: file.clone(),
+			start_line: start,
+			end_line: end,
+			origin,
+		});
+	}
+
+	// Preserve first-seen order but drop duplicates.
+	let mut seen = std::collections::BTreeSet::new();
+	hunks.retain(|h| seen.insert((h.file.clone(), h.start_line, h.end_line)));
+	hunks
+}
+
+/// Include/exclude glob filters applied to changed files, matched against repo-relative paths.
+struct ChangedPathFilter {
+	include: Vec<Regex>,
+	exclude: Vec<Regex>,
+}
+
+impl ChangedPathFilter {
+	fn new(include: &[String], exclude: &[String]) -> Result<Self, Box<dyn Error>> {
+		let compile = |patterns: &[String]| -> Result<Vec<Regex>, Box<dyn Error>> {
+			patterns
+				.iter()
+				.map(|pattern| Regex::new(&glob_to_regex(pattern)).map_err(|err| format!("Invalid glob `{pattern}`: {err}").into()))
+				.collect()
+		};
+		Ok(Self {
+			include: compile(include)?,
+			exclude: compile(exclude)?,
+		})
+	}
+
+	fn is_active(&self) -> bool {
+		!self.include.is_empty() || !self.exclude.is_empty()
+	}
+
+	fn allows(&self, file: &std::path::Path, git_root: &std::path::Path) -> bool {
+		let rel = file.strip_prefix(git_root).unwrap_or(file);
+		let rel = rel.to_string_lossy().replace('\\', "/");
+		let included = self.include.is_empty() || self.include.iter().any(|re| re.is_match(&rel));
+		included && !self.exclude.iter().any(|re| re.is_match(&rel))
+	}
+}
+
+/// Translate a gitignore-style glob into an anchored regex.
+///
+/// `*` and `?` stay within a path segment, `**` crosses segments, a trailing `/` matches everything
+/// below a directory, and patterns without a `/` match at any depth.
+fn glob_to_regex(glob: &str) -> String {
+	let trimmed = glob.trim().trim_start_matches("./");
+	let anchored = trimmed.trim_end_matches('/').contains('/');
+	let mut pattern = trimmed.trim_start_matches('/').to_string();
+	if pattern.ends_with('/') {
+		pattern.push_str("**");
+	}
+
+	let mut regex = String::from("^");
+	if !anchored {
+		regex.push_str("(?:.*/)?");
+	}
+	let mut chars = pattern.chars().peekable();
+	while let Some(ch) = chars.next() {
+		match ch {
+			'*' if chars.peek() == Some(&'*') => {
+				chars.next();
+				if chars.peek() == Some(&'/') {
+					chars.next();
+					regex.push_str("(?:.*/)?");
+				} else {
+					regex.push_str(".*");
+				}
+			}
+			'*' => regex.push_str("[^/]*"),
+			'?' => regex.push_str("[^/]"),
+			other => regex.push_str(&regex::escape(&other.to_string())),
+		}
+	}
+	// A bare directory name also matches everything beneath it.
+	regex.push_str("(?:/.*)?$");
+	regex
+}
+
+fn find_package_root(file: &std::path::Path, git_root: &std::path::Path) -> Option<std::path::PathBuf> {
+	let mut cur = file.parent()?.to_path_buf();
+	loop {
+		if cur.join("Cargo.toml").exists() {
+			return Some(cur);
+		}
+		if cur == git_root {
+			return None;
+		}
+		if !cur.pop() {
+			return None;
+		}
+	}
+}
+
+/// Raw-source specs pointing at the original path of renamed or copied files.
+///
+/// Origins that no longer exist on disk (plain renames) are skipped, since raw sources are read
+/// from the working tree.
+fn origin_raw_specs(hunks: &[DiffHunk], limits: &ChangedContextLimits) -> Vec<String> {
+	let mut seen = std::collections::HashSet::new();
+	let mut specs = Vec::new();
+	for hunk in hunks {
+		let Some(origin) = hunk.origin.as_ref() else {
+			continue;
+		};
+		if !origin.file.is_file() {
+			if seen.insert(origin.file.display().to_string()) {
+				eprintln!("Note: {} was renamed from {} (original no longer on disk)", hunk.file.display(), origin.file.display());
+			}
+			continue;
+		}
+		let end = capped_snippet_end(origin.start_line, origin.end_line, limits);
+		let spec = format!("{}:{}:{}", origin.file.display(), origin.start_line, end);
+		if seen.insert(spec.clone()) {
+			specs.push(spec);
+		}
+	}
+	specs
+}
+
+/// Raw-source specs covering each hunk plus its context, capped at `max_snippet_lines`.
+///
+/// Unlike the snippets from [`resolve_changed_context`], these also cover files without rustdoc
+/// coverage, such as integration tests.
+fn hunk_raw_specs(hunks: &[DiffHunk], limits: &ChangedContextLimits) -> Vec<String> {
+	let mut seen = std::collections::HashSet::new();
+	let mut specs = Vec::new();
+	for hunk in hunks {
+		let file = hunk.file.canonicalize().unwrap_or_else(|_| hunk.file.clone());
+		let start = hunk.start_line.saturating_sub(limits.context_lines).max(1);
+		let end = capped_snippet_end(start, hunk.end_line.saturating_add(limits.context_lines), limits);
+		let spec = format!("{}:{}:{}", file.display(), start, end);
+		if seen.insert(spec.clone()) {
+			specs.push(spec);
+		}
+	}
+	specs
+}
+
+/// Read a report file, or stdin when the path is `-`.
+fn read_report_file(path: &std::path::Path) -> Result<String, Box<dyn Error>> {
+	if path == std::path::Path::new("-") {
+		let mut text = String::new();
+		std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)?;
+		return Ok(text);
+	}
+	std::fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {err}", path.display()).into())
+}
+
+fn resolve_changed_context(
+	hunks: &[DiffHunk],
+	git_root: &std::path::Path,
+	rs: &Ripdoc,
+	common: &CommonArgs,
+	limits: &ChangedContextLimits,
+) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+	let mut targets: Vec<String> = Vec::new();
+	let mut raw_specs: Vec<String> = Vec::new();
+	let mut seen_targets = std::collections::BTreeSet::new();
+	let mut seen_raw = std::collections::BTreeSet::new();
+
+	let mut hunks_by_pkg: std::collections::HashMap<std::path::PathBuf, Vec<&DiffHunk>> = std::collections::HashMap::new();
+	for hunk in hunks {
+		let Some(pkg_root) = find_package_root(&hunk.file, git_root) else {
+			continue;
+		};
+		hunks_by_pkg.entry(pkg_root).or_default().push(hunk);
+	}
+
+	for (pkg_root, pkg_hunks) in hunks_by_pkg {
+		let pkg_root_str = pkg_root.display().to_string();
+		let resolved = resolve_target(&pkg_root_str, &rs.resolve_context());
+		let Ok(resolved) = resolved else {
+			continue;
+		};
+
+		for rt in resolved {
+			let crate_data = match rt.read_crate(
+				common.no_default_features,
+				common.all_features,
+				common.features.clone(),
+				true,
+				rs.verbosity(),
+				rs.cache_config(),
+			) {
+				Ok(data) => data,
+				Err(_) => continue,
+			};
+
+			let index = SearchIndex::build(&crate_data, true, Some(&pkg_root));
+
+			let resolve_span_path = |span: &rustdoc_types::Span| -> std::path::PathBuf {
+				let mut path = span.filename.clone();
+				if path.is_relative() {
+					let joined = pkg_root.join(&path);
+					if joined.exists() {
+						path = joined;
+					} else {
+						let mut components = span.filename.components();
+						while components.next().is_some() {
+							let candidate = pkg_root.join(components.as_path());
+							if candidate.exists() {
+								path = candidate;
+								break;
+							}
+						}
+					}
+				}
+				path.canonicalize().unwrap_or(path)
+			};
+
+			let mut entries_by_file: std::collections::HashMap<std::path::PathBuf, Vec<&ripdoc::core_api::search::SearchResult>> =
+				std::collections::HashMap::new();
+			for entry in index.entries() {
+				let Some(item) = crate_data.index.get(&entry.item_id) else {
+					continue;
+				};
+				let Some(span) = &item.span else {
+					continue;
+				};
+				let span_path = resolve_span_path(span);
+				entries_by_file.entry(span_path).or_default().push(entry);
+			}
+
+			for hunk in &pkg_hunks {
+				let file = hunk.file.canonicalize().unwrap_or_else(|_| hunk.file.clone());
+				let Some(entries) = entries_by_file.get(&file) else {
+					continue;
+				};
+
+				let range_start = hunk.start_line.saturating_sub(limits.context_lines).max(1);
+				let range_end = hunk.end_line.saturating_add(limits.context_lines).max(range_start);
+
+				let mut candidates: Vec<(usize, usize, String)> = Vec::new();
+				for entry in entries {
+					let Some(item) = crate_data.index.get(&entry.item_id) else {
+						continue;
+					};
+					let Some(span) = &item.span else {
+						continue;
+					};
+					let begin = span.begin.0;
+					let end = span.end.0;
+					if begin == 0 || end == 0 {
+						continue;
+					}
+
+					let overlaps = begin <= range_end && end >= range_start;
+					let distance = if overlaps {
+						0
+					} else if end < range_start {
+						range_start - end
+					} else {
+						begin.saturating_sub(range_end)
+					};
+
+					let kind_priority = match entry.kind {
+						SearchItemKind::Method
+						| SearchItemKind::Function
+						| SearchItemKind::Struct
+						| SearchItemKind::Enum
+						| SearchItemKind::Trait
+						| SearchItemKind::TypeAlias => 0usize,
+						SearchItemKind::Module => 2usize,
+						_ => 3usize,
+					};
+
+					let spec = format!("{}::{}", pkg_root.display(), entry.path_string);
+					candidates.push((distance, kind_priority, spec));
+				}
+
+				candidates.sort_by_key(|(dist, pri, spec)| (*dist, *pri, spec.len()));
+
+				select_hunk_items(&candidates, limits, &mut targets, &mut seen_targets);
+
+				let snippet_start = range_start;
+				let snippet_end = capped_snippet_end(snippet_start, range_end, limits);
+				let spec = format!("{}:{}:{}", file.display(), snippet_start, snippet_end);
+				if seen_raw.insert(spec.clone()) {
+					raw_specs.push(spec);
+				}
+			}
+		}
+	}
+
+	Ok((targets, raw_specs))
+}
+
+/// Add the items of one hunk from its `(distance, kind priority, spec)` candidates, sorted best
+/// first: the overlapping ones (distance 0), or the nearest ones when none overlaps.
+///
+/// `max_items_per_hunk` is at least 1, so every hunk with an overlapping candidate adds an item
+/// unless `max_targets` is reached; a `nearest_item_limit` of 0 adds no nearby items.
+fn select_hunk_items(
+	candidates: &[(usize, usize, String)],
+	limits: &ChangedContextLimits,
+	targets: &mut Vec<String>,
+	seen_targets: &mut std::collections::BTreeSet<String>,
+) {
+	let mut added_for_hunk = 0usize;
+	for (dist, _pri, spec) in candidates {
+		if *dist != 0 {
+			continue;
+		}
+		if targets.len() >= limits.max_targets {
+			break;
+		}
+		if added_for_hunk >= limits.max_items_per_hunk {
+			break;
+		}
+		if seen_targets.insert(spec.clone()) {
+			targets.push(spec.clone());
+			added_for_hunk += 1;
+		}
+	}
+
+	if added_for_hunk == 0 {
+		let mut nearest_added = 0usize;
+		for (dist, _pri, spec) in candidates {
+			if *dist == 0 || *dist > limits.nearest_item_max_distance {
+				continue;
+			}
+			if targets.len() >= limits.max_targets {
+				break;
+			}
+			if nearest_added >= limits.nearest_item_limit {
+				break;
+			}
+			if seen_targets.insert(spec.clone()) {
+				targets.push(spec.clone());
+				nearest_added += 1;
+			}
+		}
+	}
+}
+
+/// Last line of a snippet starting at `start` and wanting to reach `end`, capped at
+/// `max_snippet_lines`, which is at least 1.
+fn capped_snippet_end(start: usize, end: usize, limits: &ChangedContextLimits) -> usize {
+	end.min(start.saturating_add(limits.max_snippet_lines.saturating_sub(1))).max(start)
+}
+
+#[cfg(test)]
+mod diff_tests {
+	use super::{
+		ChangedContextArgs, ChangedContextLimits, ChangedPathFilter, DiffHunk, HunkOrigin, glob_to_regex, hunk_raw_specs, parse_git_diff_hunks, select_hunk_items,
+		unified_diff,
+	};
+
+	fn limits() -> ChangedContextLimits {
+		ChangedContextLimits {
+			context_lines: 0,
+			max_snippet_lines: 220,
+			max_items_per_hunk: 6,
+			nearest_item_limit: 3,
+			nearest_item_max_distance: 80,
+			max_targets: 200,
+		}
+	}
+
+	#[test]
+	fn parse_git_diff_hunks_extracts_new_ranges() {
+		let diff = "diff --git a/src/lib.rs b/src/lib.rs\nindex 111..222 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +10,3 @@\n+added\n";
+		let root = std::path::PathBuf::from("/repo");
+		let hunks = parse_git_diff_hunks(diff, &root, true);
+		assert_eq!(hunks.len(), 1);
+		let DiffHunk {
+			file,
+			start_line,
+			end_line,
+			origin,
+		} = &hunks[0];
+		assert!(file.ends_with("src/lib.rs"));
+		assert_eq!((*start_line, *end_line), (10, 12));
+		assert!(origin.is_none());
+	}
+
+	#[test]
+	fn parse_git_diff_hunks_maps_renames_to_new_path() {
+		let diff = "diff --git a/src/old.rs b/src/new.rs\nsimilarity index 90%\nrename from src/old.rs\nrename to src/new.rs\nindex 111..222 100644\n--- a/src/old.rs\n+++ b/src/new.rs\n@@ -4,2 +7,3 @@\n+added\ndiff --git a/src/a.rs b/src/b.rs\nsimilarity index 100%\ncopy from src/a.rs\ncopy to src/b.rs\n";
+		let root = std::path::PathBuf::from("/repo");
+		let hunks = parse_git_diff_hunks(diff, &root, true);
+		assert_eq!(hunks.len(), 1);
+		assert_eq!(hunks[0].file, root.join("src/new.rs"));
+		assert_eq!(
+			hunks[0].origin,
+			Some(HunkOrigin {
+				file: root.join("src/old.rs"),
+				start_line: 4,
+				end_line: 5,
+			})
+		);
+		assert_eq!((hunks[0].start_line, hunks[0].end_line), (7, 9));
+	}
+
+	#[test]
+	fn parse_git_diff_hunks_unquotes_paths() {
+		let diff = "diff --git \"a/src/caf\\303\\251.rs\" \"b/src/caf\\303\\251.rs\"\n--- \"a/src/caf\\303\\251.rs\"\n+++ \"b/src/caf\\303\\251.rs\"\n@@ -1 +1 @@\n+x\ndiff --git \"a/src/old\\tname.rs\" \"b/src/\\\"new\\\".rs\"\nrename from \"src/old\\tname.rs\"\nrename to \"src/\\\"new\\\".rs\"\n--- \"a/src/old\\tname.rs\"\n+++ \"b/src/\\\"new\\\".rs\"\n@@ -2 +2 @@\n+y\n";
+		let root = std::path::PathBuf::from("/repo");
+		let hunks = parse_git_diff_hunks(diff, &root, true);
+		assert_eq!(hunks.len(), 2);
+		assert_eq!(hunks[0].file, root.join("src/café.rs"));
+		assert_eq!(hunks[1].file, root.join("src/\"new\".rs"));
+		assert_eq!(hunks[1].origin.as_ref().map(|origin| &origin.file), Some(&root.join("src/old\tname.rs")));
+	}
+
+	#[test]
+	fn glob_filters_match_repo_relative_paths() {
+		let root = std::path::PathBuf::from("/repo");
+		let filter = ChangedPathFilter::new(&["crates/**".to_string()], &["vendor/".to_string(), "*.snap".to_string(), "generated".to_string()]).unwrap();
+		assert!(filter.allows(&root.join("crates/core/src/lib.rs"), &root));
+		assert!(!filter.allows(&root.join("src/main.rs"), &root));
+		assert!(!filter.allows(&root.join("crates/core/tests/snapshots/out.snap"), &root));
+		assert!(!filter.allows(&root.join("crates/core/generated/bindings.rs"), &root));
+
+		let filter = ChangedPathFilter::new(&[], &["vendor/".to_string()]).unwrap();
+		assert!(!filter.allows(&root.join("vendor/dep/src/lib.rs"), &root));
+		assert!(filter.allows(&root.join("src/vendor.rs"), &root));
+	}
+
+	#[test]
+	fn glob_to_regex_keeps_single_star_within_segment() {
+		let re = regex::Regex::new(&glob_to_regex("src/*.rs")).unwrap();
+		assert!(re.is_match("src/lib.rs"));
+		assert!(!re.is_match("src/nested/lib.rs"));
+		assert!(!re.is_match("crates/src/lib.rs"));
+	}
+
+	#[test]
+	fn item_caps_are_checked_before_adding() {
+		let candidates = vec![(0, 0, "pkg::a".to_string()), (0, 0, "pkg::b".to_string()), (5, 0, "pkg::near".to_string())];
+		let limits = ChangedContextLimits { max_items_per_hunk: 1, ..limits() };
+		let (mut targets, mut seen) = (Vec::new(), std::collections::BTreeSet::new());
+		select_hunk_items(&candidates, &limits, &mut targets, &mut seen);
+		assert_eq!(targets, ["pkg::a"]);
+
+		let candidates = vec![(3, 0, "pkg::near".to_string())];
+		let no_nearest = ChangedContextLimits { nearest_item_limit: 0, ..limits };
+		let (mut targets, mut seen) = (Vec::new(), std::collections::BTreeSet::new());
+		select_hunk_items(&candidates, &no_nearest, &mut targets, &mut seen);
+		assert!(targets.is_empty(), "{targets:?}");
+	}
+
+	#[test]
+	fn zero_caps_are_rejected() {
+		use clap::Parser;
+
+		for flag in ["--max-items-per-hunk=0", "--max-snippet-lines=0"] {
+			assert!(super::Cli::try_parse_from(["ripdoc", "changed", flag]).is_err(), "{flag}");
+		}
+		assert!(super::Cli::try_parse_from(["ripdoc", "changed", "--max-items-per-hunk=1"]).is_ok());
+
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("config.toml");
+		std::fs::write(&path, "[changed]\nmax-items-per-hunk = 0\n").unwrap();
+		let err = ChangedContextLimits::load(&path).unwrap_err().to_string();
+		assert!(err.contains("max-items-per-hunk"), "{err}");
+	}
+
+	#[test]
+	fn nearest_items_only_fill_hunks_without_overlaps() {
+		let candidates = vec![(3, 0, "pkg::near".to_string()), (90, 0, "pkg::far".to_string())];
+		let (mut targets, mut seen) = (Vec::new(), std::collections::BTreeSet::new());
+		select_hunk_items(&candidates, &limits(), &mut targets, &mut seen);
+		assert_eq!(targets, ["pkg::near"]);
+	}
+
+	#[test]
+	fn one_snippet_line_keeps_the_first_line() {
+		let hunk = DiffHunk {
+			file: std::path::PathBuf::from("/repo/missing/lib.rs"),
+			start_line: 10,
+			end_line: 12,
+			origin: None,
+		};
+		let limits = ChangedContextLimits { max_snippet_lines: 1, ..limits() };
+		assert_eq!(hunk_raw_specs(&[hunk], &limits), ["/repo/missing/lib.rs:10:10"]);
+	}
+
+	#[test]
+	fn unified_diff_labels_revisions() {
+		let diff = unified_diff("pub fn a() {}\npub fn b() {}\n", "pub fn a() {}\npub fn c() {}\n", "lib@main", "lib@HEAD", 1).unwrap();
+		assert!(diff.starts_with("--- lib@main\n+++ lib@HEAD\n@@"));
+		assert!(diff.contains("-pub fn b() {}\n+pub fn c() {}\n"));
+	}
+
+	#[test]
+	fn changed_limits_take_flags_over_the_config_file_over_defaults() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("config.toml");
+		assert_eq!(ChangedContextLimits::load(&path).unwrap(), ChangedContextLimits::default());
+
+		std::fs::write(&path, "[changed]\nmax-targets = 400\ncontext-lines = 10\n").unwrap();
+		let configured = ChangedContextLimits::load(&path).unwrap();
+		assert_eq!(configured, ChangedContextLimits { max_targets: 400, context_lines: 10, ..ChangedContextLimits::default() });
+
+		let flags = ChangedContextArgs { context_lines: Some(5), ..ChangedContextArgs::default() };
+		let limits = flags.over(configured);
+		assert_eq!((limits.context_lines, limits.max_targets, limits.max_snippet_lines), (5, 400, 220));
+
+		std::fs::write(&path, "[changed]\nmax-target = 400\n").unwrap();
+		let err = ChangedContextLimits::load(&path).unwrap_err().to_string();
+		assert!(err.contains("max-target"), "{err}");
+	}
+}
+
+#[cfg(test)]
+mod changed_tests {
+	use super::{Cli, Command, build_ripdoc, run_changed};
+	use clap::Parser;
+
+	fn git(dir: &std::path::Path, args: &[&str]) {
+		let status = std::process::Command::new("git")
+			.arg("-C")
+			.arg(dir)
+			.args(["-c", "user.name=ripdoc", "-c", "user.email=ripdoc@example.com"])
+			.args(args)
+			.status()
+			.unwrap();
+		assert!(status.success(), "git {args:?} failed");
+	}
+
+	/// A committed crate with `pub fn untouched() {}` in its `src/lib.rs`.
+	fn committed_crate() -> tempfile::TempDir {
+		let dir = tempfile::TempDir::new().unwrap();
+		std::fs::create_dir(dir.path().join("src")).unwrap();
+		std::fs::write(
+			dir.path().join("Cargo.toml"),
+			"[package]\nname = \"changed_fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)
+		.unwrap();
+		std::fs::write(dir.path().join("src/lib.rs"), "pub fn untouched() {}\n").unwrap();
+		git(dir.path(), &["init", "-q"]);
+		git(dir.path(), &["add", "-A"]);
+		git(dir.path(), &["commit", "-q", "-m", "initial"]);
+		dir
+	}
+
+	fn changed(dir: &std::path::Path, extra: &[&str]) -> String {
+		let cli = Cli::try_parse_from(["ripdoc", "changed", "--offline"].iter().chain(extra)).unwrap();
+		let Command::Changed(args) = cli.command else {
+			panic!("expected the changed command");
+		};
+		let rs = build_ripdoc(&args.common).with_silent(true);
+		let mut out = Vec::new();
+		run_changed(dir, &args.common, &args, &rs, &mut out).unwrap();
+		String::from_utf8(out).unwrap()
+	}
+
+	#[test]
+	fn clean_tree_reports_no_hunks() {
+		let dir = committed_crate();
+		assert_eq!(changed(dir.path(), &[]), "No changed hunks found (revspec: HEAD).\n");
+	}
+
+	#[test]
+	fn uncommitted_edits_map_to_items_and_snippets() {
+		let dir = committed_crate();
+		std::fs::write(dir.path().join("src/lib.rs"), "pub fn untouched() {}\n\npub fn added() -> u32 {\n    1\n}\n").unwrap();
+
+		let text = changed(dir.path(), &[]);
+		assert!(text.starts_with("1 hunk(s) changed (revspec: HEAD)\n"), "{text}");
+		let (items, snippets) = text.split_once("\nRaw snippets:\n").unwrap();
+		assert!(items.lines().any(|line| line.ends_with("::changed_fixture::added")), "{text}");
+		assert!(snippets.lines().any(|line| line.contains("src/lib.rs:")), "{text}");
+
+		let json: serde_json::Value = serde_json::from_str(&changed(dir.path(), &["--format", "json"])).unwrap();
+		assert_eq!(json["revspec"], "HEAD");
+		assert_eq!(json["hunks"].as_array().unwrap().len(), 1);
+		assert_eq!(json["hunks"][0]["start_line"], 2);
+		assert!(json["targets"].as_array().unwrap().iter().any(|target| target.as_str().unwrap().ends_with("::added")));
+	}
+}
+
+/// Print a skeleton to stdout.
+fn run_print(common: &CommonArgs, args: &PrintArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let header = if args.provenance {
+		let command = args.invocation.clone().unwrap_or_else(|| std::env::args().collect());
+		let header = Provenance::capture(&args.target, command).header(rs.render_format());
+		if header.is_none() {
+			eprintln!("Warning: --provenance is ignored for JSON output, which has no comment syntax");
+		}
+		header.unwrap_or_default()
+	} else {
+		String::new()
+	};
+	let mut target = args.target.clone();
+	let mut item_query = args.item.clone();
+
+	if args.search.is_none()
+		&& item_query.is_none()
+		&& let Some((split_target, split_query)) = split_path_target_spec(&args.target)
+	{
+		target = split_target;
+		item_query = Some(split_query);
+	}
+
+	if let Some(path) = &args.selection {
+		let selection = SavedSelection::load(path)?;
+		let response =
+			rs.render_saved_selection(&target, common.feature_selection(), common.private, &selection, args.implementation, args.raw_source)?;
+		report_target_failures(&response.errors);
+		for item in selection.missing(&response.results) {
+			eprintln!("Warning: selected {} `{}` was not found", item.kind.label(), item.path);
+		}
+		write!(out, "{header}")?;
+		writeln!(out, "{}", response.rendered)?;
+		return Ok(());
+	}
+
+	let explicit_search = args.search.as_deref();
+	let implicit_search = item_query.as_deref();
+	let query = explicit_search.or(implicit_search);
+	// `--kind` on its own selects every item of the kinds.
+	let kinds_only = query.is_none() && !args.filters.kind.is_empty();
+
+	// If search query is provided, use search mode.
+	if let Some(query) = query.or(kinds_only.then_some("")) {
+		let trimmed = query.trim();
+		if trimmed.is_empty() && !kinds_only {
+			writeln!(out, "Search query is empty; nothing to do.")?;
+			return Ok(());
+		}
+
+		let mut options = build_search_options(common, &args.filters, trimmed);
+		if args.search.is_none() {
+			// Positional item mode: always treat as a path query.
+			options.domains = SearchDomain::PATHS;
+		}
+
+		let response = rs.search(&target, common.feature_selection(), &options, args.implementation, args.raw_source)?;
+		report_target_failures(&response.errors);
+
+		if let Some(path) = &args.save_selection {
+			let selection = SavedSelection::from_results(&response.results, options.expand_containers);
+			selection.save(path)?;
+			eprintln!("Saved {} selected items to {}", selection.items.len(), path.display());
+		}
+
+		if response.results.is_empty() && response.rendered.is_empty() {
+			if kinds_only {
+				writeln!(out, "No items of the selected kinds found.")?;
+				return Ok(());
+			}
+			writeln!(out, "No matches found for \"{}\".", trimmed)?;
+			if trimmed.contains("::") {
+				let last_segment = trimmed.rsplit("::").next().unwrap_or(trimmed);
+				writeln!(
+					out,
+					"Tip: discover the exact rustdoc path with: ripdoc list {} --search \"{}\" --search-spec path --private",
+					target, last_segment
+				)?;
+			}
+			return Ok(());
+		}
+
+		let style = highlight_style(common, &args.filters);
+		let output = highlight_matches(&response.rendered, trimmed, args.filters.search_case_sensitive, style);
+
+		write!(out, "{header}{}", output)?;
+		return Ok(());
+	}
+
+	if args.save_selection.is_some() {
+		return Err("--save-selection needs a search query or item path to record".into());
+	}
+
+	if let Some(dir) = &args.split_dir {
+		let files = rs.render_split(&target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+		for file in &files {
+			let path = dir.join(&file.path);
+			if let Some(parent) = path.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+			std::fs::write(&path, format!("{header}{}", file.contents))?;
+		}
+		std::fs::create_dir_all(dir)?;
+		std::fs::write(dir.join(SPLIT_INDEX_FILE), render_split_index(&files))?;
+		writeln!(out, "Wrote {} files and {} to {}", files.len(), SPLIT_INDEX_FILE, dir.display())?;
+		return Ok(());
+	}
+
+	// Normal print mode: stream so large crates start printing before they are fully rendered.
+	write!(out, "{header}")?;
+	let errors = rs.render_to_writer(&target, common.feature_selection(), common.private, args.implementation, args.raw_source, out)?;
+	report_target_failures(&errors);
+
+	Ok(())
+}
+
+/// Search the cached crates and print matches tagged with `crate@version`.
+fn run_search_all(common: &CommonArgs, args: &SearchAllArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let trimmed = args.query.trim();
+	if trimmed.is_empty() {
+		writeln!(out, "Search query is empty; nothing to do.")?;
+		return Ok(());
+	}
+	let mut options = build_search_options(common, &args.filters, trimmed);
+	if trimmed.contains("::") && !options.domains.contains(SearchDomain::PATHS) {
+		options.domains |= SearchDomain::PATHS;
+	}
+	let ListOutput { packages, errors } = rs.search_cache(&options)?;
+	for failure in &errors {
+		eprintln!("Warning: skipped cached `{}`: {}", failure.package, failure.message);
+	}
+	let tag = |package: &PackageListing| match &package.version {
+		Some(version) => format!("{}@{version}", package.name),
+		None => package.name.clone(),
+	};
+
+	if common.format.is_json() {
+		let json: Vec<_> = packages
+			.iter()
+			.flat_map(|package| {
+				package.items.iter().map(|item| {
+					let mut value = serde_json::to_value(item).unwrap_or_default();
+					if let Some(object) = value.as_object_mut() {
+						object.insert("crate".to_string(), serde_json::Value::String(tag(package)));
+					}
+					value
+				})
+			})
+			.collect();
+		writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?;
+		return Ok(());
+	}
+
+	if packages.is_empty() {
+		writeln!(out, "No cached crate matches \"{trimmed}\".")?;
+		writeln!(out, "Tip: only crates ripdoc has built or downloaded before are searched; see `ripdoc cache stats`.")?;
+		return Ok(());
+	}
+
+	let rows: Vec<(String, &ListItem)> = packages.iter().flat_map(|package| package.items.iter().map(move |item| (tag(package), item))).collect();
+	let tag_width = rows.iter().map(|(tag, _)| tag.len()).max().unwrap_or(0);
+	let label_width = rows.iter().map(|(_, item)| item.kind.label().len()).max().unwrap_or(0);
+	let path_width = rows.iter().map(|(_, item)| item.path.len()).max().unwrap_or(0);
+	let style = highlight_style(common, &args.filters);
+	let mut buffer = String::new();
+	for (tag, item) in &rows {
+		let location = format_source_location(item.source.as_ref());
+		let line = format!("{tag:<tag_width$} {label:<label_width$} {path:<path_width$} {location}\n", label = item.kind.label(), path = item.path);
+		buffer.push_str(&highlight_matches(&line, trimmed, args.filters.search_case_sensitive, style));
+	}
+	write!(out, "{buffer}")?;
+	Ok(())
+}
+
+/// Warn about packages left out of multi-package output because they failed to build.
+fn report_target_failures(errors: &[TargetFailure]) {
+	for failure in errors {
+		eprintln!("Warning: skipped package `{}`: {}", failure.package, failure.message);
+	}
+}
+
+/// Output raw rustdoc JSON.
+fn run_raw(common: &CommonArgs, target: &str, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let output = rs.raw_json(target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+
+	writeln!(out, "{output}")?;
+
+	Ok(())
+}
+
+/// Execute the list flow and print a structured item summary.
+fn run_list(common: &CommonArgs, args: &ListArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let mut search_options: Option<SearchOptions> = None;
+	let mut trimmed_query: Option<String> = None;
+
+	if let Some(query) = args.search.as_deref() {
+		let trimmed = query.trim();
+		if trimmed.is_empty() {
+			writeln!(out, "Search query is empty; nothing to do.")?;
+			return Ok(());
+		}
+		trimmed_query = Some(trimmed.to_string());
+		let mut options = build_search_options(common, &args.filters, trimmed);
+		// Heuristic: queries that look like `crate::module::Item` are usually path searches.
+		if trimmed.contains("::") && !options.domains.contains(SearchDomain::PATHS) {
+			options.domains |= SearchDomain::PATHS;
+		}
+		search_options = Some(options);
+	} else if !args.filters.kind.is_empty() {
+		search_options = Some(build_search_options(common, &args.filters, ""));
+	}
+
+	let ListOutput { packages, errors } = rs.list_packages(
+		&args.target,
+		common.no_default_features,
+		common.all_features,
+		common.features.clone(),
+		common.private,
+		search_options.as_ref(),
+	)?;
+	report_target_failures(&errors);
+	let listings: Vec<_> = packages.iter().flat_map(|package| package.items.iter().cloned()).collect();
+
+	// Tags files are read by editors, so they are written even when empty.
+	match common.format {
+		OutputFormat::Ctags => {
+			write!(out, "{}", render_ctags(&listings))?;
+			return Ok(());
+		}
+		OutputFormat::Etags => {
+			write!(out, "{}", render_etags(&listings))?;
+			return Ok(());
+		}
+		_ => {}
+	}
+
+	if listings.is_empty() {
+		if let Some(query) = trimmed_query {
+			writeln!(out, "No matches found for \"{query}\".")?;
+			if !common.private {
+				writeln!(out, "Tip: pass `--private` to include private items.")?;
+			}
+			if query.contains("::") && !args.filters.search_spec.iter().any(|spec| matches!(spec, SearchSpec::Path)) {
+				writeln!(out, "Tip: pass `--search-spec path` to search canonical item paths.")?;
+			}
+		} else {
+			writeln!(out, "No items found.")?;
+			if !common.private {
+				writeln!(out, "Tip: pass `--private` to include private items.")?;
+			}
+		}
+		return Ok(());
+	}
+
+	if args.group_by == Some(ListGroupBy::File) {
+		let groups = group_listings_by_file(&listings);
+		if common.format.is_json() {
+			let json: Vec<_> = groups
+				.iter()
+				.map(|(file, entries)| serde_json::json!({ "file": file, "count": entries.len(), "items": entries }))
+				.collect();
+			writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?;
+			return Ok(());
+		}
+
+		let label_width = listings.iter().map(|entry| entry.kind.label().len()).max().unwrap_or(0);
+		let path_width = listings.iter().map(|entry| entry.path.len()).max().unwrap_or(0);
+		let style = highlight_style(common, &args.filters);
+		let mut buffer = String::new();
+		for (file, entries) in &groups {
+			buffer.push_str(&format!("{file} ({})\n", entries.len()));
+			for entry in entries {
+				let line = entry.source.as_ref().and_then(|source| source.line).map(|line| format!(":{line}")).unwrap_or_default();
+				let row = format!("  {label:<label_width$} {path:<path_width$} {line}\n", label = entry.kind.label(), path = entry.path);
+				match trimmed_query {
+					Some(ref query) => buffer.push_str(&highlight_matches(&row, query, args.filters.search_case_sensitive, style)),
+					None => buffer.push_str(&row),
+				}
+			}
+		}
+		write!(out, "{buffer}")?;
+		return Ok(());
+	}
+
+	// Use JSON format if requested
+	if common.format.is_json() && args.rank.is_some() {
+		// A ranking is an order, which the nested tree cannot express: emit the flat list.
+		writeln!(out, "{}", serde_json::to_string_pretty(&listings)?)?;
+		return Ok(());
+	}
+	if common.format.is_json() {
+		// Several resolved packages (e.g. a workspace) nest under package nodes.
+		let tree = ripdoc::build_package_tree(&packages);
+		let json = serde_json::to_string_pretty(&tree)?;
+		writeln!(out, "{json}")?;
+		return Ok(());
+	}
+
+	if let Some(template) = &args.template {
+		for entry in &listings {
+			writeln!(out, "{}", template.render(entry))?;
+		}
+		return Ok(());
+	}
+
+	let label_width = listings.iter().map(|entry| entry.kind.label().len()).max().unwrap_or(0);
+	let path_width = listings.iter().map(|entry| entry.path.len()).max().unwrap_or(0);
+	let fan_in_width = listings.iter().filter_map(|entry| entry.fan_in).max().map(|max| max.to_string().len());
+
+	let style = highlight_style(common, &args.filters);
+	let mut buffer = String::new();
+	for entry in listings {
+		let label = entry.kind.label();
+		let location = format_source_location(entry.source.as_ref());
+		let rank = match (fan_in_width, entry.fan_in) {
+			(Some(width), Some(count)) => format!("{count:>width$} "),
+			_ => String::new(),
+		};
+		let deprecated = if entry.deprecated { " [deprecated]" } else { "" };
+		let cfg = entry.cfg.iter().map(|predicate| format!(" [cfg({predicate})]")).collect::<String>();
+		let line = format!("{rank}{label:<label_width$} {path:<path_width$} {location}{deprecated}{cfg}\n", path = entry.path);
+		let highlighted_line = match trimmed_query {
+			Some(ref query) => highlight_matches(&line, query, args.filters.search_case_sensitive, style),
+			None => line,
+		};
 
-		let version_str = String::from_utf8_lossy(&output.stdout);
-		if !version_str.contains("nightly") {
-			return Err(format!(
-				"ripdoc requires a nightly Rust toolchain, but found: {}\nEnsure nightly Rust is installed and available in PATH.",
-				version_str.trim()
-			));
-		}
+		buffer.push_str(&highlighted_line);
 	}
 
+	write!(out, "{}", buffer)?;
+
 	Ok(())
 }
 
-/// Build a Ripdoc instance configured with common CLI knobs.
-fn build_ripdoc(common: &CommonArgs) -> Ripdoc {
-	Ripdoc::new()
-		.with_offline(common.offline)
-		.with_auto_impls(common.auto_impls)
-		.with_render_format(common.format.into())
-		.with_silent(!common.verbose)
-		.with_source_labels(!common.no_source_labels)
+/// A detached `git worktree` that is removed again on drop.
+struct RevisionWorktree {
+	git_root: std::path::PathBuf,
+	path: std::path::PathBuf,
 }
 
-/// Resolve the active search domains specified by the CLI flags.
-fn search_domains_from_filters(filters: &SearchFilterArgs) -> SearchDomain {
-	if filters.search_spec.is_empty() {
-		SearchDomain::default()
-	} else {
-		filters.search_spec.iter().fold(SearchDomain::empty(), |mut acc, spec| {
-			acc |= SearchDomain::from(*spec);
-			acc
+impl RevisionWorktree {
+	fn checkout(git_root: &std::path::Path, rev: &str, path: std::path::PathBuf) -> Result<Self, Box<dyn Error>> {
+		let output = ProcessCommand::new("git")
+			.arg("-C")
+			.arg(git_root)
+			.args(["worktree", "add", "--detach", "--quiet"])
+			.arg(&path)
+			.arg(rev)
+			.output()?;
+		if !output.status.success() {
+			return Err(format!("Failed to check out '{rev}' into a worktree: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+		}
+		Ok(Self {
+			git_root: git_root.to_path_buf(),
+			path,
 		})
 	}
 }
 
-/// Build a `SearchOptions` value using the provided CLI configuration and query.
-fn build_search_options(common: &CommonArgs, filters: &SearchFilterArgs, query: &str) -> SearchOptions {
-	let mut options = SearchOptions::new(query);
-	options.include_private = common.private;
-	options.case_sensitive = filters.search_case_sensitive;
-	options.expand_containers = !filters.direct_match_only;
-	options.domains = search_domains_from_filters(filters);
-	options
+impl Drop for RevisionWorktree {
+	fn drop(&mut self) {
+		let _ = ProcessCommand::new("git")
+			.arg("-C")
+			.arg(&self.git_root)
+			.args(["worktree", "remove", "--force"])
+			.arg(&self.path)
+			.stderr(Stdio::null())
+			.status();
+	}
 }
 
-/// Print a skeleton to stdout.
-fn split_path_target_spec(value: &str) -> Option<(String, String)> {
-	let split_at = value.find("::")?;
-	let (left, right_with_sep) = value.split_at(split_at);
-	let right = right_with_sep.strip_prefix("::")?;
-	let left = left.trim();
-	let right = right.trim();
-	if left.is_empty() || right.is_empty() {
-		return None;
-	}
+/// Render the crate at `rel` (relative to the repo root) as of `rev`.
+fn render_revision(
+	common: &CommonArgs,
+	rs: &Ripdoc,
+	git_root: &std::path::Path,
+	rel: &std::path::Path,
+	rev: &str,
+	worktree_path: std::path::PathBuf,
+) -> Result<String, Box<dyn Error>> {
+	let worktree = RevisionWorktree::checkout(git_root, rev, worktree_path)?;
+	let target = worktree.path.join(rel);
+	let rendered = rs
+		.render(&target.to_string_lossy(), common.feature_selection(), common.private, false, false)
+		.map_err(|e| format!("Failed to render '{rev}': {e}"))?;
+	report_target_failures(&rendered.errors);
+	let rendered = rendered.rendered;
+	// Worktree paths differ per run; strip them so only API changes show up in the diff.
+	Ok(rendered.replace(&format!("{}/", worktree.path.display()), ""))
+}
 
-	let looks_like_path = left.contains('/') || left.contains('\\') || left.starts_with('.') || left.starts_with('/');
-	if looks_like_path || std::path::Path::new(left).exists() {
-		Some((left.to_string(), right.to_string()))
-	} else {
-		None
+/// Produce a unified diff of two texts using `git diff --no-index`.
+fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str, context: usize) -> Result<String, Box<dyn Error>> {
+	let dir = tempfile::TempDir::new()?;
+	let old_path = dir.path().join("old");
+	let new_path = dir.path().join("new");
+	std::fs::write(&old_path, old)?;
+	std::fs::write(&new_path, new)?;
+
+	let output = ProcessCommand::new("git")
+		.args(["diff", "--no-index", "--no-color", "--no-ext-diff"])
+		.arg(format!("--unified={context}"))
+		.arg(&old_path)
+		.arg(&new_path)
+		.output()?;
+	// `git diff --no-index` exits with 1 when the inputs differ.
+	if !matches!(output.status.code(), Some(0 | 1)) {
+		return Err(format!("`git diff --no-index` failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
 	}
-}
 
-#[derive(Debug, Clone)]
-struct DiffHunk {
-	file: std::path::PathBuf,
-	start_line: usize,
-	end_line: usize,
+	let diff = String::from_utf8_lossy(&output.stdout);
+	let mut rendered = String::new();
+	for line in diff.lines() {
+		if line.starts_with("diff --git ") || line.starts_with("index ") {
+			continue;
+		}
+		if line.starts_with("--- ") {
+			rendered.push_str(&format!("--- {old_label}\n"));
+		} else if line.starts_with("+++ ") {
+			rendered.push_str(&format!("+++ {new_label}\n"));
+		} else {
+			rendered.push_str(line);
+			rendered.push('\n');
+		}
+	}
+	Ok(rendered)
 }
 
-fn git_toplevel() -> Result<std::path::PathBuf, Box<dyn Error>> {
-	let toplevel = ProcessCommand::new("git").args(["rev-parse", "--show-toplevel"]).output()?;
-	if !toplevel.status.success() {
-		return Err("Failed to run `git rev-parse --show-toplevel`; are you in a git repo?".into());
+/// Print the items and raw snippets `skelebuild add-changed` would add for a diff of the git
+/// repository containing `dir`.
+fn run_changed(dir: &std::path::Path, common: &CommonArgs, args: &ChangedArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let git_root = git_toplevel_in(dir)?;
+	let revspec = args.git.as_deref().unwrap_or(if args.staged { "--cached" } else { "HEAD" });
+	let diff = git_diff_text(dir, args.git.as_deref(), args.staged)?;
+	let path_filter = ChangedPathFilter::new(&args.include, &args.exclude)?;
+	let mut hunks = parse_git_diff_hunks(&diff, &git_root, args.only_rust);
+	hunks.retain(|hunk| path_filter.allows(&hunk.file, &git_root));
+
+	let limits = args.limits.resolve()?;
+	let (targets, mut raw_specs) = if hunks.is_empty() { (Vec::new(), Vec::new()) } else { resolve_changed_context(&hunks, &git_root, rs, common, &limits)? };
+	if args.with_origins {
+		raw_specs.extend(origin_raw_specs(&hunks, &limits));
 	}
-	let root = String::from_utf8_lossy(&toplevel.stdout);
-	let root = root.trim();
-	if root.is_empty() {
-		return Err("`git rev-parse --show-toplevel` returned empty output".into());
+
+	if common.format.is_json() {
+		let hunks: Vec<_> = hunks
+			.iter()
+			.map(|hunk| {
+				serde_json::json!({
+					"file": hunk.file,
+					"start_line": hunk.start_line,
+					"end_line": hunk.end_line,
+					"origin": hunk.origin.as_ref().map(|origin| &origin.file),
+				})
+			})
+			.collect();
+		let value = serde_json::json!({ "revspec": revspec, "hunks": hunks, "targets": targets, "raw_specs": raw_specs });
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
 	}
-	Ok(std::path::PathBuf::from(root))
-}
 
-fn git_diff_text(rev_spec: Option<&str>, staged: bool) -> Result<String, Box<dyn Error>> {
-	let mut cmd = ProcessCommand::new("git");
-	cmd.args(["diff", "--unified=0", "--no-color"]);
-	if staged {
-		cmd.arg("--cached");
+	if hunks.is_empty() {
+		writeln!(out, "No changed hunks found (revspec: {revspec}).")?;
+		return Ok(());
 	}
-	if let Some(spec) = rev_spec {
-		cmd.arg(spec);
+	writeln!(out, "{} hunk(s) changed (revspec: {revspec})", hunks.len())?;
+	writeln!(out, "\nItems:")?;
+	for target in &targets {
+		writeln!(out, "  {target}")?;
 	}
-	let output = cmd.output()?;
-	if !output.status.success() {
-		return Err("Failed to run `git diff --unified=0`".into());
+	writeln!(out, "\nRaw snippets:")?;
+	for spec in &raw_specs {
+		writeln!(out, "  {spec}")?;
 	}
-	Ok(String::from_utf8_lossy(&output.stdout).to_string())
+	Ok(())
 }
 
-/// Find a commit that touches Rust files by walking back from HEAD.
-/// Returns the commit hash if found within the limit.
-fn find_rust_touching_commit(limit: usize) -> Result<String, Box<dyn Error>> {
-	// Get the last N commits
-	let output = ProcessCommand::new("git").args(["log", &format!("-{}", limit), "--format=%H"]).output()?;
-
-	if !output.status.success() {
-		return Err("Failed to run `git log`".into());
+/// Render a crate at two git revisions and print a unified diff of the skeletons.
+fn run_diff_rev(common: &CommonArgs, args: &DiffRevArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let path = std::path::absolute(&args.path)?;
+	let dir = if path.is_file() { path.parent().unwrap_or(&path).to_path_buf() } else { path.clone() };
+	let git_root = git_toplevel_in(&dir)?;
+	let rel = path.strip_prefix(&git_root).map_err(|_| format!("{} is not inside the git repository at {}", path.display(), git_root.display()))?;
+
+	let scratch = tempfile::TempDir::new()?;
+	// Throwaway worktrees would only pollute the rustdoc cache.
+	let rs = rs.clone().with_cache(false);
+	let before = render_revision(common, &rs, &git_root, rel, &args.from, scratch.path().join("from"))?;
+	let after = render_revision(common, &rs, &git_root, rel, &args.to, scratch.path().join("to"))?;
+
+	if before == after {
+		writeln!(out, "No skeleton changes between {} and {}.", args.from, args.to)?;
+		return Ok(());
 	}
 
-	let commits = String::from_utf8_lossy(&output.stdout);
-	for commit in commits.lines() {
-		let commit = commit.trim();
-		if commit.is_empty() {
-			continue;
-		}
+	let label = rel.display().to_string();
+	let label = if label.is_empty() { ".".to_string() } else { label };
+	let diff = unified_diff(&before, &after, &format!("{label}@{}", args.from), &format!("{label}@{}", args.to), args.unified)?;
+	write!(out, "{diff}")?;
+	Ok(())
+}
 
-		// Check if this commit touches any .rs files
-		let files_output = ProcessCommand::new("git")
-			.args(["diff-tree", "--no-commit-id", "--name-only", "-r", commit])
-			.output()?;
+/// Run export lints and print one finding per line; fails when any finding is reported.
+fn run_lint_exports(common: &CommonArgs, args: &LintExportsArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let lints = rs.lint_exports(&args.target, common.no_default_features, common.all_features, common.features.clone())?;
 
-		if files_output.status.success() {
-			let files = String::from_utf8_lossy(&files_output.stdout);
-			if files.lines().any(|f| f.trim().ends_with(".rs")) {
-				return Ok(commit.to_string());
-			}
+	if common.format.is_json() {
+		writeln!(out, "{}", serde_json::to_string_pretty(&lints)?)?;
+	} else if lints.is_empty() {
+		writeln!(out, "No export issues found.")?;
+	} else {
+		let label_width = lints.iter().map(|lint| lint.kind.label().len()).max().unwrap_or(0);
+		let path_width = lints.iter().map(|lint| lint.path.len()).max().unwrap_or(0);
+		for lint in &lints {
+			writeln!(
+				out,
+				"{label:<label_width$} {path:<path_width$} {location}  {message}",
+				label = lint.kind.label(),
+				path = lint.path,
+				location = format_source_location(lint.source.as_ref()),
+				message = lint.message
+			)?;
 		}
 	}
 
-	Err("No Rust-touching commit found".into())
+	if lints.is_empty() {
+		Ok(())
+	} else {
+		Err(format!("Found {} export issue(s).", lints.len()).into())
+	}
 }
 
-fn parse_git_diff_hunks(diff: &str, git_root: &std::path::Path, only_rust: bool) -> Vec<DiffHunk> {
-	let mut current_file: Option<std::path::PathBuf> = None;
-	let mut hunks: Vec<DiffHunk> = Vec::new();
+/// List external crates referenced by the public API, grouped by crate.
+fn run_externs(common: &CommonArgs, args: &ExternsArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let usages = rs.externs(&args.target, common.no_default_features, common.all_features, common.features.clone(), args.std)?;
 
-	fn parse_usize_prefix(s: &str) -> Option<(usize, &str)> {
-		let mut end = 0usize;
-		for (idx, ch) in s.char_indices() {
-			if ch.is_ascii_digit() {
-				end = idx + ch.len_utf8();
-			} else {
-				break;
-			}
-		}
-		if end == 0 {
-			return None;
-		}
-		let num = s[..end].parse::<usize>().ok()?;
-		Some((num, &s[end..]))
+	if common.format.is_json() {
+		writeln!(out, "{}", serde_json::to_string_pretty(&usages)?)?;
+		return Ok(());
 	}
-
-	for line in diff.lines() {
-		if let Some(rest) = line.strip_prefix("+++ ") {
-			let path = rest.trim();
-			if path == "/dev/null" {
-				current_file = None;
-				continue;
-			}
-			let rel = path.strip_prefix("b/").unwrap_or(path);
-			let abs = git_root.join(rel);
-			if only_rust && abs.extension().and_then(|e| e.to_str()) != Some("rs") {
-				current_file = None;
-				continue;
-			}
-			current_file = Some(abs);
-			continue;
-		}
-
-		if !line.starts_with("@@") {
-			continue;
-		}
-		let Some(ref file) = current_file else {
-			continue;
-		};
-
-		let plus_idx = line.find(" +").map(|i| i + 2).or_else(|| line.find('+').map(|i| i + 1));
-		let Some(plus_idx) = plus_idx else {
-			continue;
-		};
-		let after_plus = &line[plus_idx..];
-		let Some((start, rest)) = parse_usize_prefix(after_plus) else {
-			continue;
-		};
-		let (len, _rest) = if let Some(rest) = rest.strip_prefix(',') {
-			parse_usize_prefix(rest).unwrap_or((1, rest))
-		} else {
-			(1, rest)
-		};
-
-		let len = len.max(1);
-		let end = start.saturating_add(len - 1).max(start);
-		hunks.push(DiffHunk {
-			file: file.clone(),
-			start_line: start.max(1),
-			end_line: end.max(1),
-		});
+	if usages.is_empty() {
+		writeln!(out, "The public API does not expose any external crates.")?;
+		return Ok(());
 	}
 
-	// Preserve first-seen order but drop duplicates.
-	let mut seen = std::collections::BTreeSet::new();
-	hunks.retain(|h| seen.insert((h.file.clone(), h.start_line, h.end_line)));
-	hunks
-}
-
-fn find_package_root(file: &std::path::Path, git_root: &std::path::Path) -> Option<std::path::PathBuf> {
-	let mut cur = file.parent()?.to_path_buf();
-	loop {
-		if cur.join("Cargo.toml").exists() {
-			return Some(cur);
-		}
-		if cur == git_root {
-			return None;
-		}
-		if !cur.pop() {
-			return None;
+	let leaks = usages.iter().flat_map(|usage| usage.leaks.iter());
+	let label_width = leaks.clone().map(|leak| leak.kind.label().len()).max().unwrap_or(0);
+	let path_width = leaks.map(|leak| leak.item.len()).max().unwrap_or(0);
+	for usage in &usages {
+		writeln!(out, "{} ({})", usage.name, usage.leaks.len())?;
+		for leak in &usage.leaks {
+			writeln!(out, "  {label:<label_width$} {item:<path_width$} {external}", label = leak.kind.label(), item = leak.item, external = leak.external)?;
 		}
 	}
+	Ok(())
 }
 
-fn resolve_changed_context(hunks: &[DiffHunk], rs: &Ripdoc, common: &CommonArgs) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
-	const CONTEXT_LINES: usize = 30;
-	const MAX_SNIPPET_LINES: usize = 220;
-	const MAX_ITEMS_PER_HUNK: usize = 6;
-	const NEAREST_ITEM_LIMIT: usize = 3;
-	const NEAREST_ITEM_MAX_DISTANCE: usize = 80;
-	const MAX_TARGETS: usize = 200;
+/// Print the structured API diff between two targets.
+fn run_diff(common: &CommonArgs, args: &DiffArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let diff = rs.diff(&args.old, &args.new, common.no_default_features, common.all_features, common.features.clone())?;
 
-	let git_root = git_toplevel()?;
-
-	let mut targets: Vec<String> = Vec::new();
-	let mut raw_specs: Vec<String> = Vec::new();
-	let mut seen_targets = std::collections::BTreeSet::new();
-	let mut seen_raw = std::collections::BTreeSet::new();
+	if common.format.is_json() {
+		writeln!(out, "{}", serde_json::to_string_pretty(&diff)?)?;
+		return Ok(());
+	}
 
-	let mut hunks_by_pkg: std::collections::HashMap<std::path::PathBuf, Vec<&DiffHunk>> = std::collections::HashMap::new();
-	for hunk in hunks {
-		let Some(pkg_root) = find_package_root(&hunk.file, &git_root) else {
-			continue;
-		};
-		hunks_by_pkg.entry(pkg_root).or_default().push(hunk);
+	if diff.changes.is_empty() {
+		writeln!(out, "No public API changes between {} and {}.", diff.old, diff.new)?;
+		return Ok(());
 	}
 
-	for (pkg_root, pkg_hunks) in hunks_by_pkg {
-		let pkg_root_str = pkg_root.display().to_string();
-		let resolved = resolve_target(&pkg_root_str, rs.offline());
-		let Ok(resolved) = resolved else {
+	let label_width = diff.changes.iter().map(|change| change.kind.label().len()).max().unwrap_or(0);
+	for kind in [ApiChangeKind::Removed, ApiChangeKind::Changed, ApiChangeKind::Added] {
+		let changes: Vec<_> = diff.of_kind(kind).collect();
+		if changes.is_empty() {
 			continue;
+		}
+		let marker = match kind {
+			ApiChangeKind::Added => '+',
+			ApiChangeKind::Removed => '-',
+			ApiChangeKind::Changed => '~',
 		};
-
-		for rt in resolved {
-			let crate_data = match rt.read_crate(
-				common.no_default_features,
-				common.all_features,
-				common.features.clone(),
-				true,
-				rs.silent(),
-				rs.cache_config(),
-			) {
-				Ok(data) => data,
-				Err(_) => continue,
+		writeln!(out, "{} ({}):", kind.label(), changes.len())?;
+		for change in changes {
+			let note = match &change.reason {
+				Some(reason) => format!(" [{}: {reason}]", change.impact.label()),
+				None => format!(" [{}]", change.impact.label()),
 			};
+			writeln!(out, "  {marker} {label:<label_width$} {path}{note}", label = change.kind.label(), path = change.path)?;
+			if kind == ApiChangeKind::Changed {
+				if let Some(old) = &change.old_signature {
+					writeln!(out, "      - {old}")?;
+				}
+				if let Some(new) = &change.new_signature {
+					writeln!(out, "      + {new}")?;
+				}
+			}
+		}
+		writeln!(out)?;
+	}
+	writeln!(out, "{} -> {}: {} version bump required ({} changes)", diff.old, diff.new, diff.impact.label(), diff.changes.len())?;
+	Ok(())
+}
 
-			let index = SearchIndex::build(&crate_data, true, Some(&pkg_root));
+/// Print a compact summary card per resolved crate.
+fn run_summary(common: &CommonArgs, args: &SummaryArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let summaries = rs.summary(&args.target, common.no_default_features, common.all_features, common.features.clone(), args.top)?;
 
-			let resolve_span_path = |span: &rustdoc_types::Span| -> std::path::PathBuf {
-				let mut path = span.filename.clone();
-				if path.is_relative() {
-					let joined = pkg_root.join(&path);
-					if joined.exists() {
-						path = joined;
-					} else {
-						let mut components = span.filename.components();
-						while components.next().is_some() {
-							let candidate = pkg_root.join(components.as_path());
-							if candidate.exists() {
-								path = candidate;
-								break;
-							}
-						}
-					}
-				}
-				path.canonicalize().unwrap_or(path)
-			};
+	if common.format.is_json() {
+		let value = if summaries.len() == 1 { serde_json::to_value(&summaries[0])? } else { serde_json::to_value(&summaries)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
+
+	for (idx, summary) in summaries.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out)?;
+		}
+		match &summary.version {
+			Some(version) => writeln!(out, "{} {version}", summary.name)?,
+			None => writeln!(out, "{}", summary.name)?,
+		}
+		if let Some(description) = &summary.description {
+			writeln!(out, "  {}", description.trim())?;
+		}
+		if let Some(msrv) = &summary.msrv {
+			writeln!(out, "  MSRV: {msrv}")?;
+		}
+		if !summary.features.is_empty() {
+			writeln!(out, "  Features: {}", summary.features.join(", "))?;
+		}
+		if !summary.modules.is_empty() {
+			writeln!(out, "\nModules:")?;
+			let width = summary.modules.iter().map(|module| module.path.len()).max().unwrap_or(0);
+			for module in &summary.modules {
+				writeln!(out, "  {path:<width$} {items} items", path = module.path, items = module.items)?;
+			}
+		}
+		if !summary.key_items.is_empty() {
+			writeln!(out, "\nKey items:")?;
+			let label_width = summary.key_items.iter().map(|item| item.kind.label().len()).max().unwrap_or(0);
+			let path_width = summary.key_items.iter().map(|item| item.path.len()).max().unwrap_or(0);
+			for item in &summary.key_items {
+				writeln!(out, "  {label:<label_width$} {path:<path_width$} {fan_in} refs", label = item.kind.label(), path = item.path, fan_in = item.fan_in)?;
+			}
+		}
+	}
+	Ok(())
+}
 
-			let mut entries_by_file: std::collections::HashMap<std::path::PathBuf, Vec<&ripdoc::core_api::search::SearchResult>> =
-				std::collections::HashMap::new();
-			for entry in index.entries() {
-				let Some(item) = crate_data.index.get(&entry.item_id) else {
-					continue;
-				};
-				let Some(span) = &item.span else {
-					continue;
-				};
-				let span_path = resolve_span_path(span);
-				entries_by_file.entry(span_path).or_default().push(entry);
+/// Print item counts per kind, and per feature with `--by-feature`, for each resolved crate.
+fn run_stats(common: &CommonArgs, args: &StatsArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let stats = rs.stats(&args.target, common.no_default_features, common.all_features, common.features.clone(), args.by_feature)?;
+
+	if common.format.is_json() {
+		let value = if stats.len() == 1 { serde_json::to_value(&stats[0])? } else { serde_json::to_value(&stats)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
+
+	for (idx, crate_stats) in stats.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out)?;
+		}
+		writeln!(out, "{} ({} items)", crate_stats.name, crate_stats.items)?;
+		let label_width = crate_stats.kinds.iter().map(|kind| kind.kind.label().len()).max().unwrap_or(0);
+		for kind in &crate_stats.kinds {
+			writeln!(out, "  {label:<label_width$} {items}", label = kind.kind.label(), items = kind.items)?;
+		}
+		writeln!(out)?;
+		writeln!(out, "  Public:     {:.0}% ({} public, {} private)", crate_stats.public_ratio() * 100.0, crate_stats.items, crate_stats.private_items)?;
+		writeln!(out, "  Generic:    {} items", crate_stats.generic_items)?;
+		writeln!(out, "  Documented: {:.0}% ({} of {} items)", crate_stats.doc_coverage() * 100.0, crate_stats.documented_items, crate_stats.items)?;
+		writeln!(out, "  Tokens:     ~{} rendered", crate_stats.estimated_tokens)?;
+		if !crate_stats.largest_modules.is_empty() {
+			writeln!(out, "\nLargest modules:")?;
+			let width = crate_stats.largest_modules.iter().map(|module| module.path.len()).max().unwrap_or(0);
+			for module in &crate_stats.largest_modules {
+				writeln!(out, "  {path:<width$} {items}", path = module.path, items = module.items)?;
+			}
+		}
+		if let Some(breakdown) = &crate_stats.by_feature {
+			writeln!(out, "\nBy feature:")?;
+			let width = breakdown.features.iter().map(|feature| feature.feature.len()).max().unwrap_or(0).max("(ungated)".len());
+			writeln!(out, "  {label:<width$} {items}", label = "(ungated)", items = breakdown.ungated)?;
+			for feature in &breakdown.features {
+				writeln!(
+					out,
+					"  {name:<width$} {items} ({exclusive} only this feature)",
+					name = feature.feature,
+					items = feature.items,
+					exclusive = feature.exclusive
+				)?;
 			}
+		}
+	}
+	Ok(())
+}
 
-			for hunk in &pkg_hunks {
-				let file = hunk.file.canonicalize().unwrap_or_else(|_| hunk.file.clone());
-				let Some(entries) = entries_by_file.get(&file) else {
-					continue;
-				};
+/// Print the trait hierarchy of each resolved crate.
+fn run_trait_graph(common: &CommonArgs, args: &TraitGraphArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let graphs = rs.trait_graph(&args.target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
 
-				let range_start = hunk.start_line.saturating_sub(CONTEXT_LINES).max(1);
-				let range_end = hunk.end_line.saturating_add(CONTEXT_LINES).max(range_start);
+	if common.format == OutputFormat::Dot {
+		write!(out, "{}", render_dot(&graphs))?;
+		return Ok(());
+	}
+	if common.format.is_json() {
+		let value = if graphs.len() == 1 { serde_json::to_value(&graphs[0])? } else { serde_json::to_value(&graphs)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
 
-				let mut candidates: Vec<(usize, usize, String)> = Vec::new();
-				for entry in entries {
-					let Some(item) = crate_data.index.get(&entry.item_id) else {
-						continue;
-					};
-					let Some(span) = &item.span else {
-						continue;
-					};
-					let begin = span.begin.0;
-					let end = span.end.0;
-					if begin == 0 || end == 0 {
-						continue;
-					}
+	for (idx, graph) in graphs.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out)?;
+		}
+		let local = graph.traits.iter().filter(|node| node.local).count();
+		writeln!(out, "{} ({local} traits)", graph.crate_name)?;
+		for node in graph.traits.iter().filter(|node| node.local) {
+			if node.supertraits.is_empty() {
+				writeln!(out, "  trait {}", node.path)?;
+			} else {
+				writeln!(out, "  trait {}: {}", node.path, node.supertraits.join(" + "))?;
+			}
+			for implementor in &node.implementors {
+				writeln!(out, "    impl for {implementor}")?;
+			}
+		}
+	}
+	Ok(())
+}
 
-					let overlaps = begin <= range_end && end >= range_start;
-					let distance = if overlaps {
-						0
-					} else if end < range_start {
-						range_start - end
-					} else {
-						begin.saturating_sub(range_end)
-					};
+/// Print each trait's dyn-compatibility and how functions accept it, crate by crate.
+fn run_dyn_compat(common: &CommonArgs, args: &DynCompatArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let reports = rs.dyn_compat(&args.target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
 
-					let kind_priority = match entry.kind {
-						SearchItemKind::Method
-						| SearchItemKind::Function
-						| SearchItemKind::Struct
-						| SearchItemKind::Enum
-						| SearchItemKind::Trait
-						| SearchItemKind::TypeAlias => 0usize,
-						SearchItemKind::Module => 2usize,
-						_ => 3usize,
-					};
+	if common.format.is_json() {
+		let value = if reports.len() == 1 { serde_json::to_value(&reports[0])? } else { serde_json::to_value(&reports)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
 
-					let spec = format!("{}::{}", pkg_root.display(), entry.path_string);
-					candidates.push((distance, kind_priority, spec));
-				}
+	for (idx, report) in reports.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out)?;
+		}
+		let compatible = report.traits.iter().filter(|trait_| trait_.dyn_compatible).count();
+		writeln!(out, "{} ({} traits, {compatible} dyn-compatible)", report.crate_name, report.traits.len())?;
+		for trait_ in &report.traits {
+			let status = if trait_.dyn_compatible { "dyn-compatible" } else { "not dyn-compatible" };
+			writeln!(out, "  trait {}: {status}", trait_.path)?;
+			if !trait_.generic_methods.is_empty() {
+				let methods: Vec<String> = trait_
+					.generic_methods
+					.iter()
+					.map(|method| if method.requires_sized { format!("{} (where Self: Sized)", method.name) } else { method.name.clone() })
+					.collect();
+				writeln!(out, "    generic methods: {}", methods.join(", "))?;
+			}
+			for path in &trait_.dyn_users {
+				writeln!(out, "    takes dyn: {path}")?;
+			}
+			for path in &trait_.impl_users {
+				writeln!(out, "    takes impl: {path}")?;
+			}
+		}
+	}
+	Ok(())
+}
 
-				candidates.sort_by_key(|(dist, pri, spec)| (*dist, *pri, spec.len()));
+/// Print the implementors of a trait, or the traits of a type, in each resolved crate.
+fn run_impls(common: &CommonArgs, args: &ImplsArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let reports = rs.impls(&args.target, &args.query, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+	if reports.is_empty() {
+		return Err(format!("No trait or type matching '{}' has trait impls in {}", args.query, args.target).into());
+	}
 
-				let mut added_for_hunk = 0usize;
-				for (dist, _pri, spec) in &candidates {
-					if *dist != 0 {
-						continue;
-					}
-					if targets.len() >= MAX_TARGETS {
-						break;
-					}
-					if seen_targets.insert(spec.clone()) {
-						targets.push(spec.clone());
-						added_for_hunk += 1;
-						if added_for_hunk >= MAX_ITEMS_PER_HUNK {
-							break;
-						}
-					}
-				}
+	if common.format.is_json() {
+		let value = if reports.len() == 1 { serde_json::to_value(&reports[0])? } else { serde_json::to_value(&reports)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
 
-				if added_for_hunk == 0 {
-					let mut nearest_added = 0usize;
-					for (dist, _pri, spec) in &candidates {
-						if *dist == 0 || *dist > NEAREST_ITEM_MAX_DISTANCE {
-							continue;
-						}
-						if targets.len() >= MAX_TARGETS {
-							break;
-						}
-						if seen_targets.insert(spec.clone()) {
-							targets.push(spec.clone());
-							nearest_added += 1;
-							if nearest_added >= NEAREST_ITEM_LIMIT {
-								break;
-							}
-						}
-					}
-				}
+	for (idx, report) in reports.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out)?;
+		}
+		writeln!(out, "{} ({} impls)", report.crate_name, report.impls.len())?;
+		for found in &report.impls {
+			let note = if found.synthetic {
+				" (auto)"
+			} else if found.blanket {
+				" (blanket)"
+			} else {
+				""
+			};
+			match report.subject {
+				ImplSubject::Trait => writeln!(out, "  {} for {}{note}", found.trait_path, found.type_path)?,
+				ImplSubject::Type => writeln!(out, "  {}: {}{note}", found.type_path, found.trait_path)?,
+			}
+		}
+	}
+	Ok(())
+}
 
-				let snippet_start = range_start;
-				let mut snippet_end = range_end;
-				let max_end = snippet_start.saturating_add(MAX_SNIPPET_LINES.saturating_sub(1));
-				if snippet_end > max_end {
-					snippet_end = max_end;
-				}
-				let spec = format!("{}:{}:{}", file.display(), snippet_start, snippet_end);
-				if seen_raw.insert(spec.clone()) {
-					raw_specs.push(spec);
-				}
+/// Print the declaration site of an item, or open it in the user's editor.
+fn run_open(common: &CommonArgs, args: &OpenArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let (target, item) = match (&args.item, split_path_target_spec(&args.target)) {
+		(None, Some((target, item))) => (target, Some(item)),
+		_ => (args.target.clone(), args.item.clone()),
+	};
+	let locations = rs.locate(&target, item.as_deref(), common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+	let query = item.as_deref().unwrap_or(&target);
+	if locations.is_empty() {
+		return Err(format!("No item matching '{query}' found").into());
+	}
+
+	if !args.edit {
+		if common.format.is_json() {
+			writeln!(out, "{}", serde_json::to_string_pretty(&locations)?)?;
+		} else {
+			for location in &locations {
+				writeln!(out, "{}", location.quickfix_line())?;
 			}
 		}
+		return Ok(());
 	}
 
-	Ok((targets, raw_specs))
+	if let [_, _, ..] = locations.as_slice() {
+		let candidates: Vec<_> = locations.iter().map(|location| format!("  {}", location.quickfix_line())).collect();
+		return Err(format!("'{query}' is ambiguous; pick one of:\n{}", candidates.join("\n")).into());
+	}
+	let location = &locations[0];
+	let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).map_err(|_| "Set $VISUAL or $EDITOR to open items")?;
+	let (mut command, program) = editor_command(&editor)?;
+	let name = std::path::Path::new(&program).file_stem().and_then(|stem| stem.to_str()).unwrap_or(&program);
+	let position = format!("{}:{}:{}", location.file.display(), location.line, location.column);
+	match name {
+		"code" | "code-insiders" | "codium" | "cursor" => command.arg("--goto").arg(position),
+		"hx" | "helix" | "zed" | "subl" => command.arg(position),
+		// `+LINE FILE` is understood by vi, vim, nvim, emacs, nano, and kak.
+		_ => command.arg(format!("+{}", location.line)).arg(&location.file),
+	};
+	let status = command.status()?;
+	if !status.success() {
+		return Err(format!("{program} exited with {status}").into());
+	}
+	Ok(())
 }
 
-#[cfg(test)]
-mod diff_tests {
-	use super::{DiffHunk, parse_git_diff_hunks};
+/// Split an editor setting such as `code --wait` into a command and its program name.
+fn editor_command(editor: &str) -> Result<(ProcessCommand, String), Box<dyn Error>> {
+	let mut words = editor.split_whitespace();
+	let program = words.next().ok_or("$VISUAL/$EDITOR is empty")?;
+	let mut command = ProcessCommand::new(program);
+	command.args(words);
+	Ok((command, program.to_string()))
+}
 
-	#[test]
-	fn parse_git_diff_hunks_extracts_new_ranges() {
-		let diff = "diff --git a/src/lib.rs b/src/lib.rs\nindex 111..222 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +10,3 @@\n+added\n";
-		let root = std::path::PathBuf::from("/repo");
-		let hunks = parse_git_diff_hunks(diff, &root, true);
-		assert_eq!(hunks.len(), 1);
-		let DiffHunk { file, start_line, end_line } = &hunks[0];
-		assert!(file.ends_with("src/lib.rs"));
-		assert_eq!((*start_line, *end_line), (10, 12));
+/// Create a sandbox project for a crate, print where it lives, and open it in the user's editor.
+fn run_try(common: &CommonArgs, args: &TryArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let sandbox = rs.sandbox(&args.target, common.no_default_features, common.all_features, common.features.clone())?;
+	if common.format.is_json() {
+		writeln!(out, "{}", serde_json::to_string_pretty(&sandbox)?)?;
+	} else {
+		match &sandbox.version {
+			Some(version) => writeln!(out, "package:  {} {version}", sandbox.package)?,
+			None => writeln!(out, "package:  {}", sandbox.package)?,
+		}
+		writeln!(out, "project:  {}", sandbox.dir.display())?;
+		writeln!(out, "skeleton: {}", sandbox.skeleton.display())?;
+	}
+	out.flush()?;
+
+	// Without an editor the printed path is the whole result.
+	if args.no_edit {
+		return Ok(());
+	}
+	let Ok(editor) = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")) else {
+		return Ok(());
+	};
+	let (mut command, program) = editor_command(&editor)?;
+	let status = command.current_dir(&sandbox.dir).arg(&sandbox.main).arg(&sandbox.skeleton).status()?;
+	if !status.success() {
+		return Err(format!("{program} exited with {status}").into());
 	}
+	Ok(())
 }
 
-/// Print a skeleton to stdout.
-fn run_print(common: &CommonArgs, args: &PrintArgs, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
-	let mut target = args.target.clone();
-	let mut item_query = args.item.clone();
+/// Print the on-disk locations of each resolved package.
+fn run_where(common: &CommonArgs, args: &WhereArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let locations = rs.locations(&args.target)?;
+	if common.format.is_json() {
+		let value = if locations.len() == 1 { serde_json::to_value(&locations[0])? } else { serde_json::to_value(&locations)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+		return Ok(());
+	}
+	for (index, location) in locations.iter().enumerate() {
+		if index > 0 {
+			writeln!(out)?;
+		}
+		write_location(location, out)?;
+	}
+	Ok(())
+}
 
-	if args.search.is_none()
-		&& item_query.is_none()
-		&& let Some((split_target, split_query)) = split_path_target_spec(&args.target)
-	{
-		target = split_target;
-		item_query = Some(split_query);
+fn write_location(location: &PackageLocation, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	match (&location.package, &location.version) {
+		(Some(package), Some(version)) => writeln!(out, "package:  {package} {version}")?,
+		(Some(package), None) => writeln!(out, "package:  {package}")?,
+		_ => {}
+	}
+	writeln!(out, "root:     {}", location.root.display())?;
+	if let Some(manifest) = &location.manifest {
+		writeln!(out, "manifest: {}", manifest.display())?;
+	}
+	if let Some(entry) = &location.entry {
+		writeln!(out, "entry:    {}", entry.display())?;
+	}
+	if !location.module.is_empty() {
+		writeln!(out, "module:   {}", location.module)?;
+	}
+	if let Some(archive) = &location.registry_archive {
+		writeln!(out, "archive:  {}", archive.display())?;
 	}
+	if let Some(json) = &location.rustdoc_json {
+		writeln!(out, "json:     {}", json.display())?;
+	}
+	Ok(())
+}
 
-	let explicit_search = args.search.as_deref();
-	let implicit_search = item_query.as_deref();
-	let query = explicit_search.or(implicit_search);
+/// Print the public items without docs in each resolved crate, module by module.
+fn run_coverage(common: &CommonArgs, args: &CoverageArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let reports = rs.doc_coverage(&args.target, common.no_default_features, common.all_features, common.features.clone())?;
 
-	// If search query is provided, use search mode.
-	if let Some(query) = query {
-		let trimmed = query.trim();
-		if trimmed.is_empty() {
-			println!("Search query is empty; nothing to do.");
-			return Ok(());
+	if common.format.is_json() {
+		let value = if reports.len() == 1 { serde_json::to_value(&reports[0])? } else { serde_json::to_value(&reports)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+	} else {
+		for (idx, report) in reports.iter().enumerate() {
+			if idx > 0 {
+				writeln!(out)?;
+			}
+			writeln!(out, "{}: {:.1}% documented ({}/{} items)", report.crate_name, report.percent(), report.documented, report.items)?;
+			let width = report.modules.iter().map(|module| module.path.len()).max().unwrap_or(0);
+			for module in &report.modules {
+				writeln!(out, "  {path:<width$} {:>5.1}% ({}/{})", module.percent(), module.documented, module.items, path = module.path)?;
+				for item in &module.undocumented {
+					let blank = if item.blank { " (blank docs)" } else { "" };
+					writeln!(out, "    {} {}{blank}", item.kind.label(), item.path)?;
+				}
+			}
 		}
+	}
 
-		let mut options = build_search_options(common, &args.filters, trimmed);
-		if args.search.is_none() {
-			// Positional item mode: always treat as a path query.
-			options.domains = SearchDomain::PATHS;
-		}
+	if let Some(threshold) = args.fail_under
+		&& let Some(report) = reports.iter().find(|report| report.percent() < threshold)
+	{
+		return Err(format!("{} doc coverage is {:.1}%, below --fail-under {threshold}%", report.crate_name, report.percent()).into());
+	}
+	Ok(())
+}
 
-		let response = rs.search(
-			&target,
-			common.no_default_features,
-			common.all_features,
-			common.features.clone(),
-			&options,
-			args.implementation,
-			args.raw_source,
-		)?;
+/// Print the items each resolved crate's skeleton is missing.
+fn run_render_coverage(common: &CommonArgs, args: &RenderCoverageArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let reports = rs.render_coverage(&args.target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
 
-		if response.results.is_empty() && response.rendered.is_empty() {
-			println!("No matches found for \"{}\".", trimmed);
-			if trimmed.contains("::") {
-				let last_segment = trimmed.rsplit("::").next().unwrap_or(trimmed);
-				println!(
-					"Tip: discover the exact rustdoc path with: ripdoc list {} --search \"{}\" --search-spec path --private",
-					target, last_segment
-				);
+	if common.format.is_json() {
+		let value = if reports.len() == 1 { serde_json::to_value(&reports[0])? } else { serde_json::to_value(&reports)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+	} else {
+		for (idx, report) in reports.iter().enumerate() {
+			if idx > 0 {
+				writeln!(out)?;
+			}
+			let rendered = report.checked - report.missing.len();
+			writeln!(out, "{}: {rendered}/{} items rendered", report.crate_name, report.checked)?;
+			for item in &report.missing {
+				writeln!(out, "  {} {} ({})", item.kind.label(), item.path, item.reason.label())?;
 			}
-			return Ok(());
 		}
+	}
 
-		let output = if should_color_output(common) {
-			highlight_matches(&response.rendered, trimmed, args.filters.search_case_sensitive)
-		} else {
-			response.rendered
-		};
+	let missing: usize = reports.iter().map(|report| report.missing.len()).sum();
+	if args.strict && missing > 0 {
+		return Err(format!("{missing} item(s) missing from the rendered output").into());
+	}
+	Ok(())
+}
+
+/// Print the manual page of each item matching the target.
+fn run_man(common: &CommonArgs, args: &ManArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let (target, item) = match (&args.item, split_path_target_spec(&args.target)) {
+		(None, Some((target, item))) => (target, Some(item)),
+		_ => (args.target.clone(), args.item.clone()),
+	};
+	let pages = rs.man(&target, item.as_deref(), common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+	if pages.is_empty() {
+		return Err(format!("No item matching '{}' found", item.as_deref().unwrap_or(&target)).into());
+	}
 
-		print!("{}", output);
+	if common.format.is_json() {
+		let value = if pages.len() == 1 { serde_json::to_value(&pages[0])? } else { serde_json::to_value(&pages)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
 		return Ok(());
 	}
+	let rule = "-".repeat(args.width);
+	for (idx, page) in pages.iter().enumerate() {
+		if idx > 0 {
+			writeln!(out, "\n{rule}\n")?;
+		}
+		write!(out, "{}", page.render(args.width, &rs.doc_options()))?;
+	}
+	Ok(())
+}
 
-	// Normal print mode
-	let output = rs.render(
-		&target,
-		common.no_default_features,
-		common.all_features,
-		common.features.clone(),
-		common.private,
-		args.implementation,
-		args.raw_source,
-	)?;
+/// Print the public API fingerprint of each resolved crate.
+fn run_fingerprint(common: &CommonArgs, args: &FingerprintArgs, rs: &Ripdoc, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let fingerprints = rs.fingerprint(&args.target, common.no_default_features, common.all_features, common.features.clone())?;
 
-	println!("{output}");
+	if common.format.is_json() {
+		let value = if fingerprints.len() == 1 { serde_json::to_value(&fingerprints[0])? } else { serde_json::to_value(&fingerprints)? };
+		writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?;
+	} else {
+		for fingerprint in &fingerprints {
+			writeln!(out, "{}  {}", fingerprint.hash, fingerprint.name)?;
+		}
+	}
 
+	if let Some(expected) = &args.expect {
+		let mismatched: Vec<_> = fingerprints.iter().filter(|fingerprint| !fingerprint.hash.eq_ignore_ascii_case(expected.trim())).collect();
+		if !mismatched.is_empty() {
+			let names: Vec<_> = mismatched.iter().map(|fingerprint| format!("{} ({})", fingerprint.name, fingerprint.hash)).collect();
+			return Err(format!("Public API fingerprint does not match {expected}: {}", names.join(", ")).into());
+		}
+	}
 	Ok(())
 }
 
-/// Output raw rustdoc JSON.
-fn run_raw(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
-	let output = rs.raw_json(target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
-
-	println!("{output}");
+/// Run an `api-baseline` subcommand.
+fn run_api_baseline(args: &ApiBaselineArgs, warm: bool, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	match &args.command {
+		ApiBaselineSubcommand::Generate { target, file, common } => {
+			let rs = build_ripdoc(common).with_memory_cache(warm);
+			let baseline = rs.api_baseline(target, common.no_default_features, common.all_features, common.features.clone())?;
+			std::fs::write(file, baseline.render())?;
+			let items: usize = baseline.crates.iter().map(|krate| krate.items.len()).sum();
+			writeln!(out, "Wrote {items} public items to {}", file.display())?;
+		}
+		ApiBaselineSubcommand::Check { target, file, common } => {
+			let text = std::fs::read_to_string(file).map_err(|err| format!("Failed to read baseline '{}': {err}; create it with `ripdoc api-baseline generate`", file.display()))?;
+			let baseline = ripdoc::core_api::ApiBaseline::parse(&text).map_err(|err| format!("{}: {err}", file.display()))?;
+			let rs = build_ripdoc(common).with_memory_cache(warm);
+			let current = rs.api_baseline(target, common.no_default_features, common.all_features, common.features.clone())?;
+			let report = baseline.check(&current);
+
+			if common.format.is_json() {
+				writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+			} else {
+				for (label, items) in [("added", &report.added), ("changed", &report.changed), ("removed", &report.removed)] {
+					for item in items {
+						writeln!(out, "{label}: {item}")?;
+					}
+				}
+			}
+			if report.is_violation() {
+				return Err(format!(
+					"{} new and {} changed public item(s) are not in {}; run `ripdoc api-baseline generate` if this is intended",
+					report.added.len(),
+					report.changed.len(),
+					file.display()
+				)
+				.into());
+			}
+			if !report.removed.is_empty() {
+				eprintln!("{} baseline item(s) no longer exist; regenerate {} to drop them", report.removed.len(), file.display());
+			}
+		}
+	}
+	Ok(())
+}
 
+/// Run an `export` subcommand.
+fn run_export(args: &ExportArgs, warm: bool, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	match &args.command {
+		ExportSubcommand::Items { target, common } => {
+			let rs = build_ripdoc(common).with_memory_cache(warm);
+			let items = rs.export_items(target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+			match common.format {
+				OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&items)?)?,
+				OutputFormat::Jsonl => {
+					for item in &items {
+						writeln!(out, "{}", serde_json::to_string(item)?)?;
+					}
+				}
+				OutputFormat::Rust | OutputFormat::Markdown | OutputFormat::Dot | OutputFormat::Html | OutputFormat::Ctags | OutputFormat::Etags => {
+					return Err("`export items` writes JSON; pass `--format jsonl` or `--format json`".into());
+				}
+			}
+		}
+		ExportSubcommand::Chunks { target, max_chars, common } => {
+			if *max_chars == 0 {
+				return Err("--max-chars must be greater than zero".into());
+			}
+			if common.format == OutputFormat::Html {
+				return Err("`export chunks` writes Rust, Markdown, or JSON".into());
+			}
+			// JSON records embed chunk contents as Markdown rather than rustdoc JSON.
+			let rs = build_ripdoc(common).with_memory_cache(warm);
+			let rs = if common.format.is_json() { rs.with_render_format(RenderFormat::Markdown) } else { rs };
+			let chunks = rs.export_chunks(target, common.no_default_features, common.all_features, common.features.clone(), common.private, *max_chars)?;
+			match common.format {
+				OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&chunks)?)?,
+				OutputFormat::Jsonl => {
+					for chunk in &chunks {
+						writeln!(out, "{}", serde_json::to_string(chunk)?)?;
+					}
+				}
+				OutputFormat::Rust | OutputFormat::Markdown | OutputFormat::Dot | OutputFormat::Html | OutputFormat::Ctags | OutputFormat::Etags => {
+					let format = common.format.into();
+					for (idx, chunk) in chunks.iter().enumerate() {
+						if idx > 0 {
+							writeln!(out)?;
+						}
+						write!(out, "{}", chunk.header(idx + 1, chunks.len(), format))?;
+						writeln!(out, "{}", chunk.contents.trim_end())?;
+					}
+				}
+			}
+		}
+		ExportSubcommand::Modules { target, out_dir, common } => {
+			// Bundles are Markdown documents whatever the output format.
+			let rs = build_ripdoc(common).with_memory_cache(warm).with_render_format(RenderFormat::Markdown);
+			let bundles = rs.export_bundles(target, common.no_default_features, common.all_features, common.features.clone(), common.private)?;
+			if let Some(dir) = out_dir {
+				for bundle in &bundles {
+					let path = dir.join(bundle.file_path());
+					if let Some(parent) = path.parent() {
+						std::fs::create_dir_all(parent)?;
+					}
+					std::fs::write(&path, bundle.to_markdown())?;
+				}
+				writeln!(out, "Wrote {} module bundles to {}", bundles.len(), dir.display())?;
+				return Ok(());
+			}
+			match common.format {
+				OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&bundles)?)?,
+				OutputFormat::Jsonl => {
+					for bundle in &bundles {
+						writeln!(out, "{}", serde_json::to_string(bundle)?)?;
+					}
+				}
+				OutputFormat::Rust | OutputFormat::Markdown | OutputFormat::Dot | OutputFormat::Html | OutputFormat::Ctags | OutputFormat::Etags => {
+					for (idx, bundle) in bundles.iter().enumerate() {
+						if idx > 0 {
+							writeln!(out)?;
+						}
+						write!(out, "{}", bundle.to_markdown())?;
+					}
+				}
+			}
+		}
+	}
 	Ok(())
 }
 
-/// Execute the list flow and print a structured item summary.
-fn run_list(common: &CommonArgs, args: &ListArgs, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
-	let mut search_options: Option<SearchOptions> = None;
-	let mut trimmed_query: Option<String> = None;
+/// Add, remove, or list target aliases.
+fn run_alias(args: &AliasArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	use ripdoc::cargo_utils::target::{Entrypoint, Target};
 
-	if let Some(query) = args.search.as_deref() {
-		let trimmed = query.trim();
-		if trimmed.is_empty() {
-			println!("Search query is empty; nothing to do.");
-			return Ok(());
+	let mut store = AliasStore::load()?;
+	match &args.command {
+		AliasSubcommand::Add { name, target } => {
+			// Pin relative paths so the alias works from any directory.
+			let target = match (Target::parse(target)?.entrypoint, target.split_once("::")) {
+				(Entrypoint::Path(path), rest) if path.is_relative() => {
+					let path = std::path::absolute(&path)?;
+					match rest {
+						Some((_, rest)) => format!("{}::{rest}", path.display()),
+						None => path.display().to_string(),
+					}
+				}
+				_ => target.clone(),
+			};
+			match store.add(name, &target)? {
+				Some(previous) => writeln!(out, "Updated alias {name}: {previous} -> {target}")?,
+				None => writeln!(out, "Added alias {name} -> {target}")?,
+			}
+			store.save()?;
 		}
-		trimmed_query = Some(trimmed.to_string());
-		let mut options = build_search_options(common, &args.filters, trimmed);
-		// Heuristic: queries that look like `crate::module::Item` are usually path searches.
-		if trimmed.contains("::") && !options.domains.contains(SearchDomain::PATHS) {
-			options.domains |= SearchDomain::PATHS;
+		AliasSubcommand::Remove { name } => match store.remove(name) {
+			Some(target) => {
+				store.save()?;
+				writeln!(out, "Removed alias {name} ({target})")?;
+			}
+			None => return Err(format!("No alias named '{name}'").into()),
+		},
+		AliasSubcommand::List => {
+			if store.aliases.is_empty() {
+				writeln!(out, "No aliases defined. Add one with `ripdoc alias add <name> <target>`.")?;
+			}
+			let width = store.aliases.keys().map(String::len).max().unwrap_or(0);
+			for (name, target) in &store.aliases {
+				writeln!(out, "{name:<width$} {target}")?;
+			}
 		}
-		search_options = Some(options);
 	}
+	Ok(())
+}
 
-	let listings = rs.list(
-		&args.target,
-		common.no_default_features,
-		common.all_features,
-		common.features.clone(),
-		common.private,
-		search_options.as_ref(),
-	)?;
-
-	if listings.is_empty() {
-		if let Some(query) = trimmed_query {
-			println!("No matches found for \"{query}\".");
-			if !common.private {
-				println!("Tip: pass `--private` to include private items.");
+/// Report on or prune the on-disk cache.
+fn run_cache(args: &CacheArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let config = CacheConfig::new();
+	let describe = |entry: &CacheEntry| {
+		let package = match (&entry.package, &entry.version) {
+			(Some(package), Some(version)) => format!("{package} {version}"),
+			(Some(package), None) => package.clone(),
+			(None, _) => entry.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+		};
+		let mut flags = Vec::new();
+		match entry.kind {
+			CacheEntryKind::Rustdoc => {}
+			CacheEntryKind::DocsRs => flags.push("docs.rs".to_string()),
+			CacheEntryKind::Rendered => flags.push("rendered".to_string()),
+		}
+		if let Some(build) = &entry.build {
+			if build.no_default_features {
+				flags.push("no-default-features".to_string());
 			}
-			if query.contains("::") && !args.filters.search_spec.iter().any(|spec| matches!(spec, SearchSpec::Path)) {
-				println!("Tip: pass `--search-spec path` to search canonical item paths.");
+			if build.all_features {
+				flags.push("all-features".to_string());
 			}
-		} else {
-			println!("No items found.");
-			if !common.private {
-				println!("Tip: pass `--private` to include private items.");
+			if !build.features.is_empty() {
+				flags.push(format!("features={}", build.features.join(",")));
+			}
+			if build.private_items {
+				flags.push("private".to_string());
+			}
+			if !build.rustdoc_flags.is_empty() {
+				flags.push(format!("rustdoc-flags='{}'", build.rustdoc_flags.join(" ")));
 			}
 		}
-		return Ok(());
-	}
-
-	// Use JSON format if requested
-	if common.format == OutputFormat::Json {
-		use ripdoc::build_list_tree;
-		let tree = build_list_tree(&listings);
-		let json = serde_json::to_string_pretty(&tree)?;
-		println!("{json}");
-		return Ok(());
-	}
-
-	let label_width = listings.iter().map(|entry| entry.kind.label().len()).max().unwrap_or(0);
-	let path_width = listings.iter().map(|entry| entry.path.len()).max().unwrap_or(0);
+		let age_days = entry.age_secs / (24 * 60 * 60);
+		format!("{:>10}  {:>4}d  {package}  {}", format_size(entry.bytes), age_days, flags.join(" ")).trim_end().to_string()
+	};
+	let summarize = |verb: &str, entries: &[CacheEntry], out: &mut dyn Write| -> std::io::Result<()> {
+		let bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+		writeln!(out, "{verb} {} entries ({})", entries.len(), format_size(bytes))
+	};
 
-	let mut buffer = String::new();
-	for entry in listings {
-		let label = entry.kind.label();
-		let location = format_source_location(entry.source.as_ref());
-		let line = format!("{label:<label_width$} {path:<path_width$} {location}\n", path = entry.path);
-		let highlighted_line = if let Some(ref query) = trimmed_query {
-			if should_color_output(common) {
-				highlight_matches(&line, query, args.filters.search_case_sensitive)
-			} else {
-				line
+	match &args.command {
+		CacheSubcommand::Stats { json } => {
+			let entries = cache_entries(&config)?;
+			if *json {
+				writeln!(out, "{}", serde_json::to_string_pretty(&entries)?)?;
+				return Ok(());
 			}
-		} else {
-			line
-		};
-
-		buffer.push_str(&highlighted_line);
+			for entry in &entries {
+				writeln!(out, "{}", describe(entry))?;
+			}
+			let bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+			writeln!(out, "{} entries, {} in {}", entries.len(), format_size(bytes), config.get_cache_dir()?.display())?;
+		}
+		CacheSubcommand::Clear => summarize("Removed", &clear_cache(&config)?, out)?,
+		CacheSubcommand::Gc { max_age, max_size } => {
+			if max_age.is_none() && max_size.is_none() {
+				return Err("cache gc needs --max-age and/or --max-size".into());
+			}
+			let removed = gc_cache(&config, *max_age, *max_size)?;
+			for entry in &removed {
+				writeln!(out, "removed {}", describe(entry))?;
+			}
+			summarize("Removed", &removed, out)?;
+		}
+		CacheSubcommand::Path => writeln!(out, "{}", config.get_cache_dir()?.display())?,
 	}
-
-	print!("{}", buffer);
-
 	Ok(())
 }
 
@@ -1046,6 +3905,65 @@ fn format_source_location(source: Option<&SourceLocation>) -> String {
 	}
 }
 
+/// Group list entries by source file, busiest files first; entries without a source come last.
+fn group_listings_by_file(listings: &[ListItem]) -> Vec<(String, Vec<&ListItem>)> {
+	let mut groups: Vec<(String, Vec<&ListItem>)> = Vec::new();
+	for entry in listings {
+		let file = entry.source.as_ref().map_or_else(|| "-".to_string(), |source| source.path.clone());
+		match groups.iter_mut().find(|(existing, _)| *existing == file) {
+			Some((_, entries)) => entries.push(entry),
+			None => groups.push((file, vec![entry])),
+		}
+	}
+	groups.sort_by(|(a_file, a), (b_file, b)| {
+		(a_file == "-").cmp(&(b_file == "-")).then_with(|| b.len().cmp(&a.len())).then_with(|| a_file.cmp(b_file))
+	});
+	for (_, entries) in &mut groups {
+		entries.sort_by_key(|entry| entry.source.as_ref().and_then(|source| source.line));
+	}
+	groups
+}
+
+#[cfg(test)]
+mod list_tests {
+	use super::{ListItem, SearchItemKind, SourceLocation, group_listings_by_file};
+
+	fn item(path: &str, file: Option<&str>, line: usize) -> ListItem {
+		ListItem {
+			kind: SearchItemKind::Function,
+			path: path.to_string(),
+			source: file.map(|file| SourceLocation {
+				path: file.to_string(),
+				line: Some(line),
+				column: None,
+			}),
+			fan_in: None,
+			deprecated: false,
+			deprecation_note: None,
+			cfg: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn group_by_file_orders_busiest_files_first() {
+		let listings = vec![
+			item("krate::a", Some("src/a.rs"), 3),
+			item("krate::b2", Some("src/b.rs"), 9),
+			item("krate::orphan", None, 0),
+			item("krate::b1", Some("src/b.rs"), 2),
+		];
+		let groups = group_listings_by_file(&listings);
+		let summary: Vec<_> = groups
+			.iter()
+			.map(|(file, entries)| (file.as_str(), entries.iter().map(|entry| entry.path.as_str()).collect::<Vec<_>>()))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![("src/b.rs", vec!["krate::b1", "krate::b2"]), ("src/a.rs", vec!["krate::a"]), ("-", vec!["krate::orphan"])]
+		);
+	}
+}
+
 /// Fetch and print the README for the target crate.
 fn run_readme(common: &CommonArgs, args: &ReadmeArgs) -> Result<(), Box<dyn Error>> {
 	use std::env;
@@ -1054,14 +3972,15 @@ fn run_readme(common: &CommonArgs, args: &ReadmeArgs) -> Result<(), Box<dyn Erro
 	use ripdoc::cargo_utils::target::{Entrypoint, Target};
 
 	// Parse the target first to understand what type it is
-	let target_parsed = Target::parse(&args.target)?;
+	let target = expand_alias(&args.target);
+	let target_parsed = Target::parse(&target)?;
 
 	// Determine the starting path for local README search
 	let search_path: Option<PathBuf> = match &target_parsed.entrypoint {
 		Entrypoint::Path(path) => Some(if path.is_absolute() { path.clone() } else { env::current_dir()?.join(path) }),
 		Entrypoint::Name { name: _, .. } => {
 			// Try to resolve target to see if it's a local workspace member or dependency
-			resolve_target(&args.target, common.offline)
+			resolve_target(&target, &ResolveContext::new(common.offline))
 				.ok()
 				.and_then(|resolved_list| resolved_list.first().map(|resolved| resolved.package_root().to_path_buf()))
 		}
@@ -1126,132 +4045,844 @@ fn run_readme(common: &CommonArgs, args: &ReadmeArgs) -> Result<(), Box<dyn Erro
 	}
 }
 
-fn should_color_output(common: &CommonArgs) -> bool {
-	if common.no_color {
-		return false;
+fn should_color_output(common: &CommonArgs) -> bool {
+	if common.no_color {
+		return false;
+	}
+	if common.force_color {
+		return true;
+	}
+	if std::env::var_os("NO_COLOR").is_some() {
+		return false;
+	}
+	if std::env::var("TERM").ok().as_deref() == Some("dumb") {
+		return false;
+	}
+	std::io::stdout().is_terminal()
+}
+
+/// Pick the highlight style: an explicit `--highlight-style`, else ANSI on color terminals.
+fn highlight_style(common: &CommonArgs, filters: &SearchFilterArgs) -> HighlightStyle {
+	if common.format.is_json() {
+		return HighlightStyle::None;
+	}
+	match filters.highlight_style {
+		Some(style) => style,
+		None if should_color_output(common) => HighlightStyle::Ansi,
+		None => HighlightStyle::None,
+	}
+}
+
+/// Highlight all occurrences of the search query in the given text.
+///
+/// Queries containing pipe characters are treated as OR patterns and use regex highlighting.
+/// Single-term queries use substring-based highlighting for better performance.
+/// Per-domain queries (`name:Builder doc:timeout`) highlight the text of every term.
+///
+/// Highlighting is markup-safe: fence markers and source labels are left untouched, and
+/// matches never span a code-span backtick. With [`HighlightStyle::Markdown`], text inside
+/// fenced blocks and code spans is not highlighted at all, since `**` would show literally.
+fn highlight_matches(text: &str, query: &str, case_sensitive: bool, style: HighlightStyle) -> String {
+	// Shape queries match structured signatures, so there is no literal text to mark.
+	if query.is_empty() || style == HighlightStyle::None || SignatureQuery::parse(query).is_some() {
+		return text.to_string();
+	}
+
+	let query = match parse_query_spec(query) {
+		Some(terms) => terms.iter().map(|term| term.text.as_str()).collect::<Vec<_>>().join("|"),
+		None => query.to_string(),
+	};
+	let highlight = |segment: &str| {
+		if query.contains('|') {
+			highlight_matches_regex(segment, &query, case_sensitive, style)
+		} else {
+			highlight_matches_simple(segment, &query, case_sensitive, style)
+		}
+	};
+
+	let mut result = String::with_capacity(text.len() * 2);
+	let mut in_fence = false;
+	for line in text.split_inclusive('\n') {
+		let trimmed = line.trim_start();
+		if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+			in_fence = !in_fence;
+			result.push_str(line);
+		} else if trimmed.starts_with("// ripdoc:source:") || trimmed.starts_with("<!-- ripdoc:source:") || trimmed.starts_with("### Source: ") {
+			result.push_str(line);
+		} else if in_fence {
+			if style == HighlightStyle::Markdown {
+				result.push_str(line);
+			} else {
+				result.push_str(&highlight(line));
+			}
+		} else {
+			for (idx, segment) in line.split('`').enumerate() {
+				if idx > 0 {
+					result.push('`');
+				}
+				let in_code_span = idx % 2 == 1;
+				if in_code_span && style == HighlightStyle::Markdown {
+					result.push_str(segment);
+				} else {
+					result.push_str(&highlight(segment));
+				}
+			}
+		}
+	}
+	result
+}
+
+/// Highlight matches using substring search for single-term queries.
+///
+/// This performs simple string containment matching and highlights all occurrences.
+/// More efficient than regex for single-term searches.
+fn highlight_matches_simple(text: &str, query: &str, case_sensitive: bool, style: HighlightStyle) -> String {
+	let mut result = String::with_capacity(text.len() * 2);
+	let search_text = if case_sensitive { text.to_string() } else { text.to_lowercase() };
+	let search_query = if case_sensitive { query.to_string() } else { query.to_lowercase() };
+
+	let mut last_end = 0;
+	let mut search_start = 0;
+
+	while let Some(pos) = search_text[search_start..].find(&search_query) {
+		let absolute_pos = search_start + pos;
+		result.push_str(&text[last_end..absolute_pos]);
+		let match_end = absolute_pos + query.len();
+		let matched_text = &text[absolute_pos..match_end];
+		result.push_str(&style.mark(matched_text));
+		last_end = match_end;
+		search_start = match_end;
+	}
+
+	result.push_str(&text[last_end..]);
+	result
+}
+
+/// Highlight matches using regex for OR queries containing pipe characters.
+///
+/// The pipe character is treated as a regex OR operator while other regex
+/// metacharacters are escaped. Falls back to substring highlighting if regex
+/// compilation fails.
+fn highlight_matches_regex(text: &str, pattern: &str, case_sensitive: bool, style: HighlightStyle) -> String {
+	let escaped_pattern = escape_regex_preserving_pipes(pattern);
+
+	let regex = match if case_sensitive {
+		Regex::new(&escaped_pattern)
+	} else {
+		Regex::new(&format!("(?i){}", escaped_pattern))
+	} {
+		Ok(re) => re,
+		Err(_) => {
+			return highlight_matches_simple(text, pattern, case_sensitive, style);
+		}
+	};
+
+	let mut result = String::with_capacity(text.len() * 2);
+	let mut last_end = 0;
+
+	for mat in regex.find_iter(text) {
+		result.push_str(&text[last_end..mat.start()]);
+		let matched_text = &text[mat.start()..mat.end()];
+		result.push_str(&style.mark(matched_text));
+		last_end = mat.end();
+	}
+
+	result.push_str(&text[last_end..]);
+	result
+}
+
+#[cfg(test)]
+mod batch_tests {
+	use super::{BatchFile, Cli, Command, record_invocation};
+	use clap::Parser;
+
+	#[test]
+	fn batch_queries_parse_as_command_lines() {
+		let batch: BatchFile = toml::from_str(
+			r#"
+			[[query]]
+			name = "serde-de"
+			command = "print"
+			target = "serde"
+			args = ["--search", "Deserialize"]
+			output = "out/serde-de.md"
+
+			[[query]]
+			name = "tokio-list"
+			command = "list"
+			target = "tokio"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(batch.queries.len(), 2);
+		assert_eq!(batch.queries[1].output, None);
+
+		let cli = Cli::try_parse_from(batch.queries[0].argv()).unwrap();
+		let Command::Print(args) = cli.command else {
+			panic!("expected a print command");
+		};
+		assert_eq!(args.search.as_deref(), Some("Deserialize"));
+	}
+
+	#[test]
+	fn provenance_names_the_query_rather_than_the_batch() {
+		let batch: BatchFile = toml::from_str(
+			r#"
+			[[query]]
+			name = "serde"
+			command = "print"
+			target = "serde"
+			args = ["--provenance"]
+			"#,
+		)
+		.unwrap();
+		let argv = batch.queries[0].argv();
+		let mut cli = Cli::try_parse_from(&argv).unwrap();
+		record_invocation(&mut cli, argv);
+		let Command::Print(args) = cli.command else {
+			panic!("expected a print command");
+		};
+		assert_eq!(args.invocation.unwrap(), ["ripdoc", "print", "serde", "--provenance"]);
+	}
+}
+
+#[cfg(test)]
+mod completion_tests {
+	use super::{Cli, Command, CompletionsArgs, run_completions};
+	use clap::Parser;
+	use clap_complete::Shell;
+
+	fn script(shell: Shell) -> String {
+		let mut out = Vec::new();
+		run_completions(&CompletionsArgs { shell }, &mut out).unwrap();
+		String::from_utf8(out).unwrap()
+	}
+
+	#[test]
+	fn zsh_and_fish_scripts_complete_item_paths() {
+		let zsh = script(Shell::Zsh);
+		assert!(zsh.contains("#compdef ripdoc"));
+		assert!(zsh.contains("ripdoc __complete-items --"));
+		assert!(zsh.trim_end().ends_with("compdef _ripdoc_with_items ripdoc"));
+
+		let fish = script(Shell::Fish);
+		assert!(fish.contains("complete -c ripdoc"));
+		assert!(fish.contains("function __ripdoc_complete_items"));
+		assert!(fish.contains("(__ripdoc_complete_items)"));
+	}
+
+	#[test]
+	fn other_shells_get_the_plain_generated_script() {
+		for shell in [Shell::Bash, Shell::PowerShell, Shell::Elvish] {
+			let script = script(shell);
+			assert!(script.contains("ripdoc"), "{shell}");
+			assert!(!script.contains("_ripdoc_with_items"), "{shell}");
+			assert!(!script.contains("__ripdoc_complete_items"), "{shell}");
+		}
+	}
+
+	#[test]
+	fn complete_items_helper_parses_target_and_prefix() {
+		let cli = Cli::try_parse_from(["ripdoc", "__complete-items", "--", "serde", "de::Des"]).unwrap();
+		let Command::CompleteItems(args) = cli.command else {
+			panic!("expected the __complete-items helper");
+		};
+		assert_eq!((args.target.as_str(), args.prefix.as_str()), ("serde", "de::Des"));
+	}
+}
+
+#[cfg(test)]
+mod print_tests {
+	use super::Cli;
+	use clap::Parser;
+
+	#[test]
+	fn zero_max_code_lines_is_rejected() {
+		assert!(Cli::try_parse_from(["ripdoc", "print", "serde", "--max-code-lines=0"]).is_err());
+		assert!(Cli::try_parse_from(["ripdoc", "print", "serde", "--max-code-lines=1"]).is_ok());
+	}
+}
+
+#[cfg(test)]
+mod highlight_tests {
+	use super::{HighlightStyle, highlight_matches};
+
+	#[test]
+	fn markdown_style_skips_code() {
+		let text = "Parse a `Config` value.\n```rust\npub fn config() -> Config {}\n```\n";
+		let highlighted = highlight_matches(text, "config", false, HighlightStyle::Markdown);
+		assert_eq!(highlighted, "Parse a `Config` value.\n```rust\npub fn config() -> Config {}\n```\n");
+
+		let highlighted = highlight_matches("Loads the config file.\n", "config", false, HighlightStyle::Markdown);
+		assert_eq!(highlighted, "Loads the **config** file.\n");
+	}
+
+	#[test]
+	fn ansi_style_leaves_fences_and_labels_intact() {
+		let text = "### Source: src/rust.rs\n\n```rust\nfn rust() {}\n```\n";
+		let highlighted = highlight_matches(text, "rust", false, HighlightStyle::Ansi);
+		let lines: Vec<&str> = highlighted.lines().collect();
+		assert_eq!(lines[0], "### Source: src/rust.rs");
+		assert_eq!(lines[2], "```rust");
+		assert_ne!(lines[3], "fn rust() {}");
+		assert_eq!(lines[4], "```");
+	}
+
+	#[test]
+	fn matches_never_span_code_span_backticks() {
+		let highlighted = highlight_matches("a`b", "a`b", false, HighlightStyle::Markdown);
+		assert_eq!(highlighted, "a`b");
+	}
+}
+
+fn main() {
+	let cli = Cli::parse();
+	let (print_timings, timings_json) = (cli.timings, cli.timings_json.clone());
+	let timings = (print_timings || timings_json.is_some()).then(Timings::new);
+
+	// Hand the invocation to a running daemon when possible; fall back to running locally.
+	// Timings are only measured in-process.
+	if timings.is_none()
+		&& let Some(response) = forward_to_daemon(&cli)
+	{
+		match response {
+			Ok(response) => {
+				print!("{}", response.stdout);
+				eprint!("{}", response.stderr);
+				if let Some(error) = response.error {
+					eprintln!("{error}");
+					process::exit(1);
+				}
+				return;
+			}
+			Err(e) => eprintln!("ripdoc daemon unavailable ({e}); running locally"),
+		}
+	}
+
+	if let Err(e) = check_nightly_toolchain() {
+		eprintln!("{e}");
+		process::exit(1);
+	}
+
+	let result = match &timings {
+		Some(timings) => {
+			let result = timings.scope(|| run(cli));
+			if let Err(e) = write_timings(&timings.take(), print_timings, timings_json.as_deref()) {
+				eprintln!("Failed to write timings: {e}");
+			}
+			result
+		}
+		None => run(cli),
+	};
+
+	if let Err(e) = result {
+		eprintln!("{e}");
+		process::exit(1);
+	}
+}
+
+/// Report recorded phase durations on stderr and/or as JSON to `json_path`.
+fn write_timings(
+	recorded: &[PhaseTiming],
+	print: bool,
+	json_path: Option<&std::path::Path>,
+) -> Result<(), Box<dyn Error>> {
+	if print {
+		eprint!("{}", timings::render_report(recorded));
 	}
-	if std::env::var_os("NO_COLOR").is_some() {
-		return false;
+	if let Some(path) = json_path {
+		std::fs::write(path, serde_json::to_string_pretty(recorded)?)?;
 	}
-	if std::env::var("TERM").ok().as_deref() == Some("dumb") {
-		return false;
+	Ok(())
+}
+
+/// Common arguments of commands the daemon can serve.
+fn daemon_common_args(command: &Command) -> Option<&CommonArgs> {
+	match command {
+		Command::Print(args) | Command::Raw(args) => Some(&args.common),
+		Command::List(args) => Some(&args.common),
+		Command::Skelebuild(args) if skelebuild_is_served(args) => Some(&args.common),
+		_ => None,
 	}
-	std::io::stdout().is_terminal()
 }
 
-/// Highlight all occurrences of the search query in the given text.
+/// Whether the daemon can run a `skelebuild` invocation.
 ///
-/// Queries containing pipe characters are treated as OR patterns and use regex highlighting.
-/// Single-term queries use substring-based highlighting for better performance.
+/// Subcommands that read stdin, shell out to git or cargo from the current directory, or keep
+/// running (`watch`) stay with the client.
+fn skelebuild_is_served(args: &SkelebuildArgs) -> bool {
+	match &args.command {
+		Some(
+			SkelebuildSubcommand::AddChanged { .. }
+			| SkelebuildSubcommand::AddTestFailures { .. }
+			| SkelebuildSubcommand::AddDiagnostics { .. }
+			| SkelebuildSubcommand::Ci { .. }
+			| SkelebuildSubcommand::Watch { .. },
+		) => false,
+		Some(SkelebuildSubcommand::Inject { content, from_stdin, from_file, .. }) => {
+			!from_stdin && (content.is_some() || from_file.is_some())
+		}
+		_ => true,
+	}
+}
+
+/// Forward a `print`, `list`, `raw`, or `skelebuild` invocation to a running daemon.
 ///
-/// Matches are highlighted in bright green and bold using ANSI escape codes.
-fn highlight_matches(text: &str, query: &str, case_sensitive: bool) -> String {
-	if query.is_empty() {
-		return text.to_string();
+/// Returns `None` when the command is not served by the daemon, forwarding is disabled, no
+/// daemon is listening, or the daemon runs under different build-relevant environment variables.
+fn forward_to_daemon(cli: &Cli) -> Option<std::io::Result<ipc::DaemonResponse>> {
+	let common = daemon_common_args(&cli.command)?;
+	if std::env::var_os(ipc::NO_DAEMON_ENV).is_some() {
+		return None;
 	}
+	let request = ipc::DaemonRequest {
+		cwd: std::env::current_dir().ok()?,
+		args: std::env::args().collect(),
+		color: should_color_output(common),
+		stop: false,
+		env: ipc::forwarded_env(),
+	};
+	// A daemon started under different cache, rustdoc, or toolchain settings would not produce
+	// what this process would, so those invocations run here.
+	ipc::forward(&request).filter(|response| !response.as_ref().is_ok_and(|response| response.run_locally))
+}
 
-	if query.contains('|') {
-		highlight_matches_regex(text, query, case_sensitive)
-	} else {
-		highlight_matches_simple(text, query, case_sensitive)
+/// Run one forwarded invocation inside the daemon, capturing its stdout.
+fn serve_daemon_request(request: ipc::DaemonRequest) -> ipc::DaemonResponse {
+	let mut stdout = Vec::new();
+	let mut run_locally = false;
+	let result = (|| -> Result<(), Box<dyn Error>> {
+		let mut cli = Cli::try_parse_from(&request.args)?;
+		// Requests share the daemon process, so the client's directory is passed down rather
+		// than made the process's current directory.
+		let in_client_dir = |path: &mut Option<std::path::PathBuf>| {
+			if let Some(path) = path.as_mut().filter(|path| path.is_relative()) {
+				*path = request.cwd.join(&*path);
+			}
+		};
+		match &mut cli.command {
+			Command::Print(args) | Command::Raw(args) => {
+				args.common.force_color = request.color;
+				args.common.working_dir = Some(request.cwd.clone());
+				in_client_dir(&mut args.split_dir);
+				in_client_dir(&mut args.save_selection);
+				in_client_dir(&mut args.selection);
+			}
+			Command::List(args) => {
+				args.common.force_color = request.color;
+				args.common.working_dir = Some(request.cwd.clone());
+			}
+			Command::Skelebuild(args) => {
+				args.common.force_color = request.color;
+				args.common.working_dir = Some(request.cwd.clone());
+				// Sinks other than files write to the client's terminal, clipboard, or
+				// environment-configured endpoints, so rebuilds that deliver to them run there.
+				if matches!(args.command, Some(SkelebuildSubcommand::Rebuild))
+					&& ripdoc::skelebuild::SkeleState::load_warm(&build_skelebuild_ripdoc(args))
+						.sinks
+						.iter()
+						.any(|sink| !matches!(sink, ripdoc::skelebuild::SinkSpec::File { .. }))
+				{
+					run_locally = true;
+					return Ok(());
+				}
+			}
+			_ => {}
+		}
+		if daemon_common_args(&cli.command).is_none() {
+			return Err("the ripdoc daemon only serves `print`, `list`, `raw`, and `skelebuild`".into());
+		}
+		record_invocation(&mut cli, request.args.clone());
+		run_command(cli, &mut stdout, true)
+	})();
+
+	ipc::DaemonResponse {
+		stdout: String::from_utf8_lossy(&stdout).into_owned(),
+		error: result.err().map(|e| e.to_string()),
+		run_locally,
+		..ipc::DaemonResponse::default()
 	}
 }
 
-/// Highlight matches using substring search for single-term queries.
-///
-/// This performs simple string containment matching and highlights all occurrences.
-/// More efficient than regex for single-term searches.
-fn highlight_matches_simple(text: &str, query: &str, case_sensitive: bool) -> String {
-	let mut result = String::with_capacity(text.len() * 2);
-	let search_text = if case_sensitive { text.to_string() } else { text.to_lowercase() };
-	let search_query = if case_sensitive { query.to_string() } else { query.to_lowercase() };
+#[cfg(test)]
+mod daemon_tests {
+	use super::{ipc, serve_daemon_request};
 
-	let mut last_end = 0;
-	let mut search_start = 0;
+	#[test]
+	fn served_builds_read_the_cargo_config_of_the_client_directory() {
+		let dir = tempfile::TempDir::new().unwrap();
+		std::fs::create_dir_all(dir.path().join("src")).unwrap();
+		std::fs::create_dir_all(dir.path().join(".cargo")).unwrap();
+		std::fs::write(
+			dir.path().join("Cargo.toml"),
+			"[package]\nname = \"served_fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)
+		.unwrap();
+		std::fs::write(dir.path().join("src/lib.rs"), "pub fn served() {}\n").unwrap();
+		std::fs::write(dir.path().join(".cargo/config.toml"), "[build]\ntarget-dir = \"configured-target\"\n").unwrap();
+
+		let response = serve_daemon_request(ipc::DaemonRequest {
+			cwd: dir.path().to_path_buf(),
+			args: ["ripdoc", "print", ".", "--offline"].map(String::from).to_vec(),
+			color: false,
+			stop: false,
+			env: ipc::forwarded_env(),
+		});
+		assert_eq!(response.error, None);
+		assert!(response.stdout.contains("pub fn served()"), "{}", response.stdout);
+		assert!(dir.path().join("configured-target/doc/served_fixture.json").exists());
+	}
 
-	while let Some(pos) = search_text[search_start..].find(&search_query) {
-		let absolute_pos = search_start + pos;
-		result.push_str(&text[last_end..absolute_pos]);
-		let match_end = absolute_pos + query.len();
-		let matched_text = &text[absolute_pos..match_end];
-		result.push_str(&matched_text.to_string().bright_green().bold().to_string());
-		last_end = match_end;
-		search_start = match_end;
+	#[test]
+	fn served_skelebuild_resolves_paths_in_the_client_directory_and_tracks_the_state_file() {
+		use clap::Parser;
+		use ripdoc::skelebuild::{SkeleEntry, SkeleState};
+
+		let dir = tempfile::TempDir::new().unwrap();
+		std::fs::create_dir_all(dir.path().join("src")).unwrap();
+		std::fs::write(dir.path().join("src/lib.rs"), "pub fn served() {}\n").unwrap();
+		let state_dir = dir.path().join("state");
+		let ripdoc = ripdoc::Ripdoc::new().with_state_dir(state_dir.clone());
+		// `--state-dir` is relative to the client's directory, like every other path it passes.
+		let serve = |args: &[&str]| {
+			serve_daemon_request(ipc::DaemonRequest {
+				cwd: dir.path().to_path_buf(),
+				args: ["ripdoc", "skelebuild", "--state-dir", "state"].iter().chain(args).map(|arg| arg.to_string()).collect(),
+				color: false,
+				stop: false,
+				env: ipc::forwarded_env(),
+			})
+		};
+		let status_locally = || {
+			let mut out = Vec::new();
+			let cli = super::Cli::parse_from(["ripdoc", "skelebuild", "status", "--state-dir", &state_dir.display().to_string()]);
+			super::run_command(cli, &mut out, false).unwrap();
+			String::from_utf8(out).unwrap()
+		};
+
+		let response = serve(&["add-raw", "src/lib.rs:1:1", "--output", "skel.md"]);
+		assert_eq!(response.error, None);
+		assert!(dir.path().join("skel.md").exists());
+		let mut state = SkeleState::load(&ripdoc);
+		assert!(matches!(&state.entries[..], [SkeleEntry::RawSource(raw)] if raw.file == dir.path().join("src/lib.rs")));
+
+		let served = serve(&["status"]);
+		assert_eq!(served.error, None);
+		assert!(served.stdout.contains("Entries: 1"), "{}", served.stdout);
+		assert_eq!(served.stdout, status_locally());
+
+		// A run outside the daemon changes the state file; the daemon must not keep its copy, even
+		// when the change lands within the file system's timestamp granularity.
+		state.entries.clear();
+		state.save(&ripdoc).unwrap();
+		let served = serve(&["status"]);
+		assert!(served.stdout.contains("Entries: 0"), "{}", served.stdout);
+		assert_eq!(served.stdout, status_locally());
+
+		assert_eq!(serve(&["sink", "--sink", "stdout"]).error, None);
+		assert!(serve(&["rebuild"]).run_locally);
+		assert!(!serve(&["status"]).run_locally);
 	}
+}
 
-	result.push_str(&text[last_end..]);
-	result
+/// Item completion for zsh, appended to the generated script: `crate::…` words after `print` or
+/// `skelebuild add` are completed from `ripdoc __complete-items`, everything else falls back to
+/// the generated `_ripdoc`.
+const ZSH_ITEM_COMPLETION: &str = r#"
+_ripdoc_with_items() {
+	local word=${words[CURRENT]}
+	if [[ $word == *::* ]] && [[ ${words[2]} == print || ( ${words[2]} == skelebuild && ${words[3]} == add ) ]]; then
+		local -a items
+		items=(${(f)"$(ripdoc __complete-items -- ${word%%::*} ${word#*::} 2>/dev/null)"})
+		if (( ${#items} )); then
+			compadd -Q -S '' -a items
+			return
+		fi
+	fi
+	_ripdoc "$@"
+}
+compdef _ripdoc_with_items ripdoc
+"#;
+
+/// Item completion for fish, appended to the generated script.
+const FISH_ITEM_COMPLETION: &str = r#"
+function __ripdoc_complete_items
+	set -l word (commandline -ct)
+	string match -q -- '*::*' $word; or return
+	set -l parts (string split -m1 -- '::' $word)
+	ripdoc __complete-items -- $parts[1] $parts[2] 2>/dev/null
+end
+complete -c ripdoc -n '__fish_seen_subcommand_from print add' -f -a '(__ripdoc_complete_items)'
+"#;
+
+/// Print the completion script for a shell.
+fn run_completions(args: &CompletionsArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	use clap::CommandFactory;
+	use clap_complete::Shell;
+
+	clap_complete::generate(args.shell, &mut Cli::command(), "ripdoc", out);
+	match args.shell {
+		Shell::Zsh => write!(out, "{ZSH_ITEM_COMPLETION}")?,
+		Shell::Fish => write!(out, "{FISH_ITEM_COMPLETION}")?,
+		_ => {}
+	}
+	Ok(())
 }
 
-/// Highlight matches using regex for OR queries containing pipe characters.
+/// Print `target::path` for every item of `target` whose crate-relative path starts with the
+/// prefix. Runs offline and silently, and relies on the rustdoc JSON cache to stay fast.
+fn run_complete_items(args: &CompleteItemsArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let rs = Ripdoc::new().with_offline(true).with_silent(true);
+	let items = rs.list(&args.target, false, false, Vec::new(), false, None)?;
+	let mut seen = std::collections::HashSet::new();
+	for item in items {
+		let Some((_, relative)) = item.path.split_once("::") else {
+			continue;
+		};
+		if relative.starts_with(&args.prefix) && seen.insert(relative.to_string()) {
+			writeln!(out, "{}::{relative}", args.target)?;
+		}
+	}
+	Ok(())
+}
+
+/// Run every query of a batch file, keeping parsed crates in memory between queries.
 ///
-/// The pipe character is treated as a regex OR operator while other regex
-/// metacharacters are escaped. Falls back to substring highlighting if regex
-/// compilation fails.
-fn highlight_matches_regex(text: &str, pattern: &str, case_sensitive: bool) -> String {
-	let escaped_pattern = escape_regex_preserving_pipes(pattern);
+/// Each query is parsed like a command line (`ripdoc <command> <target> <args>...`). Output
+/// goes to the query's `output` file, or to `out` under a `==> name <==` header.
+fn run_batch(args: &BatchArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+	let content = std::fs::read_to_string(&args.file).map_err(|e| format!("Failed to read {}: {e}", args.file.display()))?;
+	let batch: BatchFile = toml::from_str(&content).map_err(|e| format!("Invalid batch file {}: {e}", args.file.display()))?;
+	let base = args.file.parent().unwrap_or(std::path::Path::new(""));
+
+	let mut failed = Vec::new();
+	for query in &batch.queries {
+		let result = (|| -> Result<(), Box<dyn Error>> {
+			let mut cli = Cli::try_parse_from(query.argv())?;
+			if matches!(cli.command, Command::Batch(_) | Command::Daemon(_) | Command::Lsp(_) | Command::Tui(_)) {
+				return Err(format!("`{}` cannot run inside a batch", query.command).into());
+			}
+			record_invocation(&mut cli, query.argv());
+			match &query.output {
+				Some(path) => {
+					let path = base.join(path);
+					if let Some(parent) = path.parent() {
+						std::fs::create_dir_all(parent)?;
+					}
+					let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+					run_command(cli, &mut file, true)?;
+					file.flush()?;
+					eprintln!("[{}] wrote {}", query.name, path.display());
+				}
+				None => {
+					writeln!(out, "==> {} <==", query.name)?;
+					run_command(cli, out, true)?;
+				}
+			}
+			Ok(())
+		})();
+		if let Err(e) = result {
+			eprintln!("[{}] failed: {e}", query.name);
+			failed.push(query.name.as_str());
+			if args.fail_fast {
+				break;
+			}
+		}
+	}
 
-	let regex = match if case_sensitive {
-		Regex::new(&escaped_pattern)
+	if failed.is_empty() {
+		Ok(())
 	} else {
-		Regex::new(&format!("(?i){}", escaped_pattern))
-	} {
-		Ok(re) => re,
-		Err(_) => {
-			return highlight_matches_simple(text, pattern, case_sensitive);
-		}
-	};
+		Err(format!("{} of {} queries failed: {}", failed.len(), batch.queries.len(), failed.join(", ")).into())
+	}
+}
 
-	let mut result = String::with_capacity(text.len() * 2);
-	let mut last_end = 0;
+/// Record `argv` as the command line a `--provenance` header names, for commands run on behalf
+/// of another invocation.
+fn record_invocation(cli: &mut Cli, argv: Vec<String>) {
+	if let Command::Print(args) | Command::Raw(args) = &mut cli.command {
+		args.invocation = Some(argv);
+	}
+}
 
-	for mat in regex.find_iter(text) {
-		result.push_str(&text[last_end..mat.start()]);
-		let matched_text = &text[mat.start()..mat.end()];
-		result.push_str(&matched_text.to_string().bright_green().bold().to_string());
-		last_end = mat.end();
+/// Execute the `daemon` subcommand.
+fn run_daemon(args: &DaemonArgs) -> Result<(), Box<dyn Error>> {
+	if args.stop || args.status {
+		let request = ipc::DaemonRequest {
+			cwd: std::env::current_dir()?,
+			args: Vec::new(),
+			color: false,
+			stop: args.stop,
+			env: ipc::forwarded_env(),
+		};
+		let socket = ipc::socket_path();
+		// A status probe only needs the connection to succeed.
+		let reachable = if args.stop {
+			ipc::forward(&request).map(|response| response.is_ok()).unwrap_or(false)
+		} else {
+			daemon_is_listening(&socket)
+		};
+		match (args.stop, reachable) {
+			(true, true) => println!("Stopped ripdoc daemon ({})", socket.display()),
+			(false, true) => println!("ripdoc daemon is running ({})", socket.display()),
+			(_, false) => println!("No ripdoc daemon is running ({})", socket.display()),
+		}
+		return Ok(());
 	}
 
-	result.push_str(&text[last_end..]);
-	result
+	ipc::serve(serve_daemon_request)?;
+	Ok(())
 }
 
-fn main() {
-	let cli = Cli::parse();
-	if let Err(e) = check_nightly_toolchain() {
-		eprintln!("{e}");
-		process::exit(1);
+/// Run the language server on stdin/stdout until the client exits.
+fn run_lsp(args: &LspArgs) -> Result<(), Box<dyn Error>> {
+	let common = &args.common;
+	let mut server = LspServer::new(build_ripdoc(common)).with_features(common.no_default_features, common.all_features, common.features.clone());
+	if let Some(target) = &args.target {
+		server = server.with_target(target.as_str());
 	}
+	server.run(std::io::stdin().lock(), std::io::stdout().lock())?;
+	Ok(())
+}
 
-	let result = run(cli);
+fn run_tui(args: &TuiArgs) -> Result<(), Box<dyn Error>> {
+	let common = &args.common;
+	Browser::new(build_ripdoc(common), args.target.as_str())
+		.with_features(common.no_default_features, common.all_features, common.features.clone())
+		.with_private(common.private)
+		.run()?;
+	Ok(())
+}
 
-	if let Err(e) = result {
-		eprintln!("{e}");
-		process::exit(1);
-	}
+#[cfg(unix)]
+fn daemon_is_listening(socket: &std::path::Path) -> bool {
+	std::os::unix::net::UnixStream::connect(socket).is_ok()
+}
+
+#[cfg(not(unix))]
+fn daemon_is_listening(_socket: &std::path::Path) -> bool {
+	false
 }
 
 fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+	run_command(cli, &mut std::io::stdout().lock(), false)
+}
+
+/// Dispatch a parsed command, writing `print`/`list`/`raw` output to `out`.
+///
+/// `warm` keeps parsed crates in memory between calls; the daemon sets it.
+fn run_command(cli: Cli, out: &mut dyn Write, warm: bool) -> Result<(), Box<dyn Error>> {
 	match cli.command {
 		Command::Print(args) => {
-			let rs = build_ripdoc(&args.common);
-			run_print(&args.common, &args, &rs)
+			let rs = build_ripdoc(&args.common)
+				.with_memory_cache(warm)
+				.with_dependencies(args.with_deps.unwrap_or(0))
+				.with_module_summary(args.summary)
+				.with_exclude(args.filters.exclude.clone())
+				.with_max_impl_lines(args.max_impl_lines);
+			run_print(&args.common, &args, &rs, out)
 		}
 		Command::Raw(args) => {
-			if args.item.is_some() || args.search.is_some() || args.implementation || args.raw_source {
-				return Err("`ripdoc raw` only accepts a target (no item/search/source flags).".into());
+			if args.item.is_some() || args.search.is_some() || args.implementation || args.raw_source || args.with_deps.is_some() {
+				return Err("`ripdoc raw` only accepts a target (no item/search/source/dependency flags).".into());
 			}
-			let rs = build_ripdoc(&args.common);
-			run_raw(&args.common, &args.target, &rs)
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_raw(&args.common, &args.target, &rs, out)
 		}
 		Command::List(args) => {
+			let rs = build_ripdoc(&args.common)
+				.with_memory_cache(warm)
+				.with_fan_in_ranking(args.rank == Some(ListRank::FanIn))
+				.with_list_deprecation(args.filters.deprecation())
+				.with_exclude(args.filters.exclude.clone());
+			run_list(&args.common, &args, &rs, out)
+		}
+		Command::SearchAll(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm).with_exclude(args.filters.exclude.clone());
+			run_search_all(&args.common, &args, &rs, out)
+		}
+		Command::DiffRev(args) => {
+			let rs = build_ripdoc(&args.common);
+			run_diff_rev(&args.common, &args, &rs, out)
+		}
+		Command::Changed(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_changed(std::path::Path::new("."), &args.common, &args, &rs, out)
+		}
+		Command::Diff(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_diff(&args.common, &args, &rs, out)
+		}
+		Command::LintExports(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_lint_exports(&args.common, &args, &rs, out)
+		}
+		Command::Externs(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_externs(&args.common, &args, &rs, out)
+		}
+		Command::Summary(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_summary(&args.common, &args, &rs, out)
+		}
+		Command::Stats(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_stats(&args.common, &args, &rs, out)
+		}
+		Command::TraitGraph(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_trait_graph(&args.common, &args, &rs, out)
+		}
+		Command::DynCompat(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_dyn_compat(&args.common, &args, &rs, out)
+		}
+		Command::Impls(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_impls(&args.common, &args, &rs, out)
+		}
+		Command::Open(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_open(&args.common, &args, &rs, out)
+		}
+		Command::Coverage(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_coverage(&args.common, &args, &rs, out)
+		}
+		Command::RenderCoverage(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_render_coverage(&args.common, &args, &rs, out)
+		}
+		Command::Man(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_man(&args.common, &args, &rs, out)
+		}
+		Command::Where(args) => {
+			let rs = build_ripdoc(&args.common);
+			run_where(&args.common, &args, &rs, out)
+		}
+		Command::Try(args) => {
 			let rs = build_ripdoc(&args.common);
-			run_list(&args.common, &args, &rs)
+			run_try(&args.common, &args, &rs, out)
 		}
+		Command::Fingerprint(args) => {
+			let rs = build_ripdoc(&args.common).with_memory_cache(warm);
+			run_fingerprint(&args.common, &args, &rs, out)
+		}
+		Command::ApiBaseline(args) => run_api_baseline(&args, warm, out),
+		Command::Export(args) => run_export(&args, warm, out),
+		Command::Alias(args) => run_alias(&args, out),
+		Command::Cache(args) => run_cache(&args, out),
+		Command::Completions(args) => run_completions(&args, out),
+		Command::CompleteItems(args) => run_complete_items(&args, out),
+		Command::Batch(args) => run_batch(&args, out),
+		Command::Daemon(args) => run_daemon(&args),
+		Command::Lsp(args) => run_lsp(&args),
+		Command::Tui(args) => run_tui(&args),
 		Command::Readme(args) => run_readme(&args.common, &args),
 		Command::Skelebuild(args) => {
 			use ripdoc::skelebuild::SkeleAction;
-			let rs = build_ripdoc(&args.common);
+			let rs = build_skelebuild_ripdoc(&args).with_memory_cache(warm);
 
 			let mut output = args.output;
 			let mut plain: Option<bool> = if args.plain {
@@ -1276,6 +4907,10 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 						no_private,
 						no_validate,
 						strict,
+						features,
+						all_features,
+						no_default_features,
+						with_deps,
 						output: o,
 						plain: p,
 					} => {
@@ -1304,6 +4939,10 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 								validate,
 								private: effective_private,
 								strict,
+								features,
+								all_features,
+								no_default_features,
+								with_deps,
 							})
 						} else {
 							Some(SkeleAction::AddMany {
@@ -1313,6 +4952,10 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 								validate,
 								private: effective_private,
 								strict,
+								features,
+								all_features,
+								no_default_features,
+								with_deps,
 							})
 						}
 					}
@@ -1335,23 +4978,33 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 						git,
 						staged,
 						only_rust,
+						include,
+						exclude,
+						with_notes,
+						with_origins,
+						limits,
 						output: o,
 					} => {
 						if o.is_some() {
 							output = o;
 						}
+						let limits = limits.resolve()?;
 						let git_root = git_toplevel()?;
 						let revspec = git.as_deref().unwrap_or(if staged { "--cached" } else { "HEAD" });
 
 						eprintln!("Analyzing changes (revspec: {})...", revspec);
 
-						let diff = git_diff_text(git.as_deref(), staged)?;
+						let diff = git_diff_text(std::path::Path::new("."), git.as_deref(), staged)?;
 						let all_hunks = parse_git_diff_hunks(&diff, &git_root, false);
-						let filtered_hunks = if only_rust {
+						let mut filtered_hunks = if only_rust {
 							parse_git_diff_hunks(&diff, &git_root, true)
 						} else {
 							all_hunks.clone()
 						};
+						let path_filter = ChangedPathFilter::new(&include, &exclude)?;
+						let before_glob_filter = filtered_hunks.len();
+						filtered_hunks.retain(|hunk| path_filter.allows(&hunk.file, &git_root));
+						let hunks_filtered_by_globs = before_glob_filter - filtered_hunks.len();
 
 						// Count unique changed files
 						let mut all_files = std::collections::BTreeSet::new();
@@ -1370,10 +5023,13 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 							eprintln!("  Resolved revspec: {}", revspec);
 							eprintln!("  Total changed files discovered: {}", all_files.len());
 							eprintln!("  Total hunks discovered (before filtering): {}", all_hunks.len());
+							if path_filter.is_active() {
+								eprintln!("  Hunks filtered out by --include/--exclude: {}", hunks_filtered_by_globs);
+							}
 
 							if only_rust {
 								let files_filtered = all_files.len() - filtered_files.len();
-								let hunks_filtered = all_hunks.len() - filtered_hunks.len();
+								let hunks_filtered = all_hunks.len() - before_glob_filter;
 								eprintln!("  Files filtered out by --only-rust: {}", files_filtered);
 								eprintln!("  Hunks filtered out by --only-rust: {}", hunks_filtered);
 
@@ -1400,6 +5056,10 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 								}
 							}
 
+							if hunks_filtered_by_globs > 0 {
+								eprintln!("  - {} hunks were skipped by --include/--exclude; check the glob patterns", hunks_filtered_by_globs);
+							}
+
 							// Optional: compute a concrete suggestion by walking back
 							if only_rust {
 								eprintln!("\nSearching for recent Rust-touching commits...");
@@ -1413,7 +5073,10 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 
 							return Ok(());
 						}
-						let (targets, raw_specs) = resolve_changed_context(&filtered_hunks, &rs, &args.common)?;
+						let (targets, mut raw_specs) = resolve_changed_context(&filtered_hunks, &git_root, &rs, &args.common, &limits)?;
+						if with_origins {
+							raw_specs.extend(origin_raw_specs(&filtered_hunks, &limits));
+						}
 						if targets.is_empty() && raw_specs.is_empty() {
 							eprintln!("No changed context could be resolved.");
 							eprintln!("\nDiagnostics:");
@@ -1423,7 +5086,121 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 							eprintln!("      This may happen if changes are in files without rustdoc coverage.");
 							return Ok(());
 						}
-						Some(SkeleAction::AddChangedResolved { targets, raw_specs })
+						Some(SkeleAction::AddChangedResolved {
+							targets,
+							raw_specs,
+							with_notes,
+							preamble: None,
+						})
+					}
+					SkelebuildSubcommand::AddTestFailures {
+						from: TestReportFormat::CargoJson,
+						file,
+						with_notes,
+						limits,
+						output: o,
+					} => {
+						if o.is_some() {
+							output = o;
+						}
+						let limits = limits.resolve()?;
+						let report = parse_test_report(&read_report_file(&file)?);
+						if report.failures.is_empty() {
+							eprintln!("No failing tests found in {}.", file.display());
+							return Ok(());
+						}
+
+						let mut roots = report.package_roots.clone();
+						roots.push(std::env::current_dir()?);
+						let mut hunks = Vec::new();
+						let mut preamble = String::from("## Failing tests\n");
+						for failure in &report.failures {
+							preamble.push_str(&format!("\n- `{}`", failure.name));
+							if let Some(message) = &failure.message {
+								preamble.push_str(&format!(": {message}"));
+							}
+							for location in &failure.locations {
+								preamble.push_str(&format!(" ({}:{})", location.file.display(), location.start_line));
+							}
+
+							// Doctest names are `file - path (line N)`; their location is already known.
+							let definition = if failure.name.contains(" - ") { None } else { find_test_definition(&failure.name, &roots) };
+							let located = failure.locations.iter().filter_map(|location| {
+								resolve_report_path(&location.file, &roots).map(|file| SourceRange { file, ..location.clone() })
+							});
+							hunks.extend(definition.into_iter().chain(located).map(|range| DiffHunk {
+								file: range.file,
+								start_line: range.start_line,
+								end_line: range.end_line,
+								origin: None,
+							}));
+						}
+						preamble.push('\n');
+
+						if hunks.is_empty() {
+							eprintln!("Found {} failing tests, but none of their sources could be located.", report.failures.len());
+							return Ok(());
+						}
+						let (targets, _) = resolve_changed_context(&hunks, &git_toplevel()?, &rs, &args.common, &limits)?;
+						Some(SkeleAction::AddChangedResolved {
+							targets,
+							raw_specs: hunk_raw_specs(&hunks, &limits),
+							with_notes,
+							preamble: Some(preamble),
+						})
+					}
+					SkelebuildSubcommand::AddDiagnostics {
+						from: DiagnosticsFormat::CargoCheckJson,
+						file,
+						errors_only,
+						with_notes,
+						limits,
+						output: o,
+					} => {
+						if o.is_some() {
+							output = o;
+						}
+						let limits = limits.resolve()?;
+						let mut diagnostics = parse_diagnostics(&read_report_file(&file)?);
+						if errors_only {
+							diagnostics.retain(|diagnostic| diagnostic.level.starts_with("error"));
+						}
+						if diagnostics.is_empty() {
+							eprintln!("No diagnostics with source spans found in {}.", file.display());
+							return Ok(());
+						}
+
+						let cwd = std::env::current_dir()?;
+						let mut hunks = Vec::new();
+						let mut preamble = String::from("## Diagnostics\n");
+						for diagnostic in &diagnostics {
+							let code = diagnostic.code.as_ref().map(|code| format!("[{code}]")).unwrap_or_default();
+							let primary = &diagnostic.spans[0];
+							preamble.push_str(&format!("\n- {}{code} `{}:{}`: {}", diagnostic.level, primary.file.display(), primary.start_line, diagnostic.message));
+
+							let roots: Vec<std::path::PathBuf> = diagnostic.package_root.iter().cloned().chain([cwd.clone()]).collect();
+							hunks.extend(diagnostic.spans.iter().filter_map(|span| {
+								resolve_report_path(&span.file, &roots).map(|file| DiffHunk {
+									file,
+									start_line: span.start_line,
+									end_line: span.end_line,
+									origin: None,
+								})
+							}));
+						}
+						preamble.push('\n');
+
+						if hunks.is_empty() {
+							eprintln!("Found {} diagnostics, but none of their source files could be located.", diagnostics.len());
+							return Ok(());
+						}
+						let (targets, _) = resolve_changed_context(&hunks, &git_toplevel()?, &rs, &args.common, &limits)?;
+						Some(SkeleAction::AddChangedResolved {
+							targets,
+							raw_specs: hunk_raw_specs(&hunks, &limits),
+							with_notes,
+							preamble: Some(preamble),
+						})
 					}
 					SkelebuildSubcommand::Update {
 						spec,
@@ -1431,6 +5208,11 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 						no_implementation,
 						raw_source,
 						no_raw_source,
+						file,
+						start,
+						end,
+						to_raw,
+						to_target,
 						output: o,
 					} => {
 						if o.is_some() {
@@ -1454,6 +5236,11 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 							spec,
 							implementation: impl_value,
 							raw_source: raw_value,
+							file,
+							start_line: start,
+							end_line: end,
+							to_raw,
+							to_target,
 						})
 					}
 					SkelebuildSubcommand::Inject {
@@ -1480,7 +5267,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 							buf
 						} else if let Some(path) = from_file {
 							// Read from file
-							std::fs::read_to_string(path)?
+							std::fs::read_to_string(rs.in_working_dir(path))?
 						} else if let Some(c) = content {
 							// Positional content provided
 							c
@@ -1523,6 +5310,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 						})
 					}
 
+					SkelebuildSubcommand::Annotate { spec, note, clear: _ } => Some(SkeleAction::Annotate { spec, note }),
 					SkelebuildSubcommand::Remove { target, output: o } => {
 						if o.is_some() {
 							output = o;
@@ -1539,14 +5327,78 @@ fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
 						Some(SkeleAction::Reset)
 					}
 					SkelebuildSubcommand::Status { keys } => Some(SkeleAction::Status { keys }),
-					SkelebuildSubcommand::Preview => Some(SkeleAction::Preview),
+					SkelebuildSubcommand::Preview { format } => Some(SkeleAction::Preview {
+						json: format.unwrap_or(args.common.format).is_json(),
+					}),
 					SkelebuildSubcommand::Rebuild => Some(SkeleAction::Rebuild),
+					SkelebuildSubcommand::Budget { max_tokens, max_chars, on_exceed, clear } => {
+						use ripdoc::skelebuild::{BudgetUnit, SkeleBudget};
+
+						let budget = match (max_tokens, max_chars) {
+							_ if clear => None,
+							(Some(limit), _) => Some(SkeleBudget { limit, unit: BudgetUnit::Tokens, on_exceed: on_exceed.into() }),
+							(None, Some(limit)) => Some(SkeleBudget { limit, unit: BudgetUnit::Chars, on_exceed: on_exceed.into() }),
+							(None, None) => None,
+						};
+						Some(SkeleAction::Budget(budget))
+					}
+					SkelebuildSubcommand::Sink { mut sinks, headers, clear } => {
+						use ripdoc::skelebuild::SinkSpec;
+
+						if clear {
+							sinks.clear();
+						}
+						for sink in &mut sinks {
+							if let SinkSpec::Http { headers: sink_headers, .. } = sink {
+								sink_headers.clone_from(&headers);
+							}
+						}
+						Some(SkeleAction::Sinks(sinks))
+					}
+					SkelebuildSubcommand::Watch { debounce } => {
+						ripdoc::skelebuild::watch(&rs, std::time::Duration::from_millis(debounce))?;
+						return Ok(());
+					}
+					SkelebuildSubcommand::Ci { manifest, check } => {
+						use ripdoc::skelebuild::CiOutcome;
+
+						return match ripdoc::skelebuild::run_ci(&manifest, check, args.locked, &rs)? {
+							CiOutcome::Written(path) => {
+								println!("Wrote {}", path.display());
+								Ok(())
+							}
+							CiOutcome::UpToDate(path) => {
+								println!("Up to date: {}", path.display());
+								Ok(())
+							}
+							CiOutcome::Stale(path) => Err(format!(
+								"Skeleton output is out of date: {}\nRun `ripdoc skelebuild ci --manifest {}` and commit the result.",
+								path.display(),
+								manifest.display()
+							)
+							.into()),
+						};
+					}
 				}
 			} else {
 				None
 			};
 
-			ripdoc::skelebuild::run_skelebuild(action, output, plain, args.show_state, &rs)?;
+			let render_notes = if args.render_notes {
+				Some(true)
+			} else if args.no_render_notes {
+				Some(false)
+			} else {
+				None
+			};
+			let options = ripdoc::skelebuild::SkeleOptions {
+				output,
+				plain,
+				render_notes,
+				locked: args.locked,
+				show_state: args.show_state,
+			};
+			ripdoc::skelebuild::run_skelebuild(action, options, &rs, out)?;
 			Ok(())
 		}
 	}
@@ -1560,17 +5412,147 @@ enum OutputFormat {
 	/// Print Markdown with stripped documentation markers (default).
 	#[value(alias = "md")]
 	Markdown,
-	/// Print JSON output (only for list command).
+	/// Print structured JSON (item records for print/search, a tree for list).
 	Json,
+	/// Print one JSON record per line (`export`; other commands treat it as `json`).
+	Jsonl,
+	/// Print a Graphviz DOT graph (`traitgraph`; other commands treat it as `markdown`).
+	Dot,
+	/// Print a standalone HTML page with highlighted code and a module tree sidebar (used by `print`).
+	Html,
+	/// Print a Vim `tags` file (`list`; other commands treat it as `markdown`).
+	Ctags,
+	/// Print an Emacs `TAGS` file (`list`; other commands treat it as `markdown`).
+	Etags,
+}
+
+impl OutputFormat {
+	/// Whether the output is JSON in either layout.
+	fn is_json(self) -> bool {
+		matches!(self, Self::Json | Self::Jsonl)
+	}
 }
 
 impl From<OutputFormat> for RenderFormat {
 	fn from(format: OutputFormat) -> Self {
 		match format {
 			OutputFormat::Rust => RenderFormat::Rust,
-			OutputFormat::Markdown => RenderFormat::Markdown,
-			// JSON format doesn't have a RenderFormat equivalent; it's only for list output
-			OutputFormat::Json => RenderFormat::Markdown,
+			OutputFormat::Markdown | OutputFormat::Dot | OutputFormat::Ctags | OutputFormat::Etags => RenderFormat::Markdown,
+			OutputFormat::Json | OutputFormat::Jsonl => RenderFormat::Json,
+			OutputFormat::Html => RenderFormat::Html,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Reductions accepted by `skelebuild budget --on-exceed`.
+enum BudgetExceedArg {
+	/// Fail with a per-entry size report.
+	Error,
+	/// Keep only the first paragraph of each doc comment.
+	TruncateDocs,
+	/// Render targets without implementations or raw source.
+	DropImplementations,
+}
+
+impl From<BudgetExceedArg> for ripdoc::skelebuild::BudgetAction {
+	fn from(action: BudgetExceedArg) -> Self {
+		match action {
+			BudgetExceedArg::Error => Self::Error,
+			BudgetExceedArg::TruncateDocs => Self::TruncateDocs,
+			BudgetExceedArg::DropImplementations => Self::DropImplementations,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Report formats accepted by `skelebuild add-test-failures --from`.
+enum TestReportFormat {
+	/// Output of `cargo test --message-format json` (libtest text or JSON events).
+	CargoJson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Report formats accepted by `skelebuild add-diagnostics --from`.
+enum DiagnosticsFormat {
+	/// Output of `cargo check --message-format json` (also `cargo build` and `cargo clippy`).
+	CargoCheckJson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Ways to mark query hits in search output.
+enum HighlightStyle {
+	/// Bright green bold ANSI escape codes.
+	Ansi,
+	/// Markdown `**bold**`, skipped inside code blocks and code spans.
+	Markdown,
+	/// No highlighting.
+	None,
+}
+
+impl HighlightStyle {
+	/// Wrap a matched fragment in this style's markers.
+	fn mark(self, matched: &str) -> String {
+		match self {
+			Self::Ansi => matched.bright_green().bold().to_string(),
+			Self::Markdown => format!("**{matched}**"),
+			Self::None => matched.to_string(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Rankings accepted by `list --rank`.
+enum ListRank {
+	/// Most-referenced items first.
+	FanIn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Keys accepted by `list --group-by`.
+enum ListGroupBy {
+	/// Group hits by the source file that defines them.
+	File,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Styles accepted by `--derives`.
+enum DerivesArg {
+	/// `#[derive(...)]` attributes in place of the impl blocks (default).
+	Attribute,
+	/// A `/* derives: ... */` comment in place of the impl blocks.
+	Summary,
+	/// No annotation; render the impl blocks themselves.
+	Impls,
+}
+
+impl From<DerivesArg> for DeriveStyle {
+	fn from(style: DerivesArg) -> Self {
+		match style {
+			DerivesArg::Attribute => DeriveStyle::Attribute,
+			DerivesArg::Summary => DeriveStyle::Summary,
+			DerivesArg::Impls => DeriveStyle::Impls,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+/// Language tags accepted for Markdown code fences.
+enum FenceLang {
+	/// Tag fences as `rust` (default).
+	Rust,
+	/// Tag fences as `rs`.
+	Rs,
+	/// Emit untagged fences.
+	None,
+}
+
+impl From<FenceLang> for FenceLanguage {
+	fn from(lang: FenceLang) -> Self {
+		match lang {
+			FenceLang::Rust => FenceLanguage::Rust,
+			FenceLang::Rs => FenceLanguage::Rs,
+			FenceLang::None => FenceLanguage::None,
 		}
 	}
 }
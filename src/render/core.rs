@@ -1,11 +1,16 @@
 use std::collections::HashSet;
+use std::io::Write;
 
 use rust_format::{Config, Formatter, RustFmt};
 use rustdoc_types::{Crate, Id};
 
+use super::docs::{DocMode, DocOptions};
 use super::error::Result;
-use crate::render::markdown;
+use super::impls::DeriveStyle;
+use crate::render::markdown::{self, MarkdownOptions};
 use crate::render::utils::dedup_gap_markers;
+use crate::render::visibility::VisibilityLevel;
+use crate::timings;
 
 /// Configuration for a render pass, specifying which items to include and how to format them.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,6 +19,10 @@ pub enum RenderFormat {
 	Rust,
 	/// Format as Markdown documentation.
 	Markdown,
+	/// Structured JSON document listing the rendered items; see [`super::json::JsonDocument`].
+	Json,
+	/// Standalone HTML page with highlighted skeleton code and a module tree sidebar.
+	Html,
 }
 
 /// Selection of items to be rendered from a crate.
@@ -89,14 +98,42 @@ pub struct Renderer {
 	pub render_private_items: bool,
 	/// Whether to inject source filename labels in the output.
 	pub render_source_labels: bool,
+	/// Whether enum variant fields should be collapsed to `Variant { /* .. */ }` placeholders.
+	pub collapse_variant_fields: bool,
+	/// How impls of commonly derived traits are shown.
+	pub derive_style: DeriveStyle,
+	/// Whether function signatures should have redundant lifetimes elided.
+	pub simplify_lifetimes: bool,
+	/// Whether code examples in doc comments are repeated as labelled sections after their item.
+	pub doc_examples: bool,
+	/// How much of each doc comment is rendered, and how it is shaped.
+	pub doc_options: DocOptions,
+	/// Whether items marked `#[doc(hidden)]` are rendered when the rustdoc JSON contains them.
+	pub render_doc_hidden: bool,
+	/// Whether items gated on `#[cfg(...)]` (such as cargo features) show their cfg attributes.
+	pub cfg_annotations: bool,
+	/// Code-fence options applied when rendering Markdown.
+	pub markdown_options: MarkdownOptions,
+	/// Hide items less visible than this level (only meaningful with private items).
+	pub min_visibility: Option<VisibilityLevel>,
+	/// Render only `pub` items reachable from the crate root, even from a crate documented with
+	/// private items.
+	pub strict_public: bool,
 	/// Filter path relative to the crate root.
 	pub filter: String,
+	/// Path patterns whose items (and everything inside them) are left out.
+	pub exclude: Vec<String>,
+	/// Longest source span, in lines, that full-source items are rendered with; longer ones are
+	/// rendered as skeletons.
+	pub max_impl_lines: Option<usize>,
 	/// Optional selection restricting which items are rendered.
 	pub selection: Option<RenderSelection>,
 	/// Optional root path for resolving relative source files.
 	pub source_root: Option<std::path::PathBuf>,
 	/// Whether to use plain output (skip module nesting).
 	pub plain: bool,
+	/// Whether modules are rendered as one-line item inventories instead of their items.
+	pub module_summary: bool,
 	/// Optional initial source file to suppress redundant headers.
 	pub initial_current_file: Option<std::path::PathBuf>,
 	/// Optional persistent visited set to avoid redundant item rendering across calls.
@@ -122,10 +159,23 @@ impl Renderer {
 			render_auto_impls: false,
 			render_private_items: false,
 			render_source_labels: true,
+			collapse_variant_fields: false,
+			derive_style: DeriveStyle::default(),
+			simplify_lifetimes: false,
+			doc_examples: false,
+			doc_options: DocOptions::default(),
+			render_doc_hidden: false,
+			cfg_annotations: false,
+			markdown_options: MarkdownOptions::default(),
+			min_visibility: None,
+			strict_public: false,
 			filter: String::new(),
+			exclude: Vec::new(),
+			max_impl_lines: None,
 			selection: None,
 			source_root: None,
 			plain: false,
+			module_summary: false,
 			initial_current_file: None,
 			visited: None,
 		}
@@ -137,12 +187,33 @@ impl Renderer {
 		self
 	}
 
+	/// Render each module as `pub mod name; // 3 structs, 12 fns` instead of its items, keeping
+	/// only the module tree. Has no effect on JSON output.
+	pub fn with_module_summary(mut self, summary: bool) -> Self {
+		self.module_summary = summary;
+		self
+	}
+
 	/// Apply a filter to output. The filter is a path BELOW the outermost module.
 	pub fn with_filter(mut self, filter: &str) -> Self {
 		self.filter = filter.to_string();
 		self
 	}
 
+	/// Leave out items whose path matches one of `patterns` (e.g. `tokio::runtime`), along with
+	/// everything inside them. A `*` in a pattern matches within one path segment.
+	pub fn with_exclude(mut self, patterns: Vec<String>) -> Self {
+		self.exclude = patterns;
+		self
+	}
+
+	/// Render items selected for full source as skeletons when their span is longer than
+	/// `lines`, marking elided function bodies with their line count.
+	pub fn with_max_impl_lines(mut self, lines: Option<usize>) -> Self {
+		self.max_impl_lines = lines;
+		self
+	}
+
 	/// Select the output format to render.
 	pub fn with_format(mut self, format: RenderFormat) -> Self {
 		self.format = format;
@@ -167,6 +238,93 @@ impl Renderer {
 		self
 	}
 
+	/// Collapse enum variant fields into `Variant { /* .. */ }` / `Variant(/* .. */)` placeholders.
+	///
+	/// The placeholders keep the output valid Rust. Discriminants are always preserved, and
+	/// variants that were explicitly matched by the selection keep their full field list.
+	pub fn with_collapsed_variants(mut self, collapse: bool) -> Self {
+		self.collapse_variant_fields = collapse;
+		self
+	}
+
+	/// Show impls of commonly derived traits as a `#[derive(...)]` attribute, as a
+	/// `/* derives: ... */` summary comment, or as the impl blocks themselves.
+	pub fn with_derive_style(mut self, style: DeriveStyle) -> Self {
+		self.derive_style = style;
+		self
+	}
+
+	/// Elide lifetimes that Rust's elision rules would infer and collapse `for<'a>` binders on
+	/// `Fn`-family bounds. Signatures that cannot be simplified safely are left exact.
+	pub fn with_simplified_lifetimes(mut self, simplify: bool) -> Self {
+		self.simplify_lifetimes = simplify;
+		self
+	}
+
+	/// Repeat the Rust code blocks of each item's docs as labelled example sections after the
+	/// item's signature.
+	pub fn with_doc_examples(mut self, enabled: bool) -> Self {
+		self.doc_examples = enabled;
+		self
+	}
+
+	/// Render docs in full, cut to their first paragraph or first line, or drop them. Shortened
+	/// docs end with an ellipsis marker.
+	pub fn with_docs(mut self, mode: DocMode) -> Self {
+		self.doc_options.mode = mode;
+		self
+	}
+
+	/// Wrap doc prose longer than `width` columns; code blocks, headings, and tables are kept.
+	pub fn with_doc_wrap(mut self, width: Option<usize>) -> Self {
+		self.doc_options.wrap = width;
+		self
+	}
+
+	/// Drop fenced code blocks from docs while keeping their prose.
+	pub fn with_doc_code_stripped(mut self, strip: bool) -> Self {
+		self.doc_options.strip_code_blocks = strip;
+		self
+	}
+
+	/// Render items marked `#[doc(hidden)]` instead of hiding them like rustdoc's HTML output.
+	pub fn with_doc_hidden_items(mut self, enabled: bool) -> Self {
+		self.render_doc_hidden = enabled;
+		self
+	}
+
+	/// Show the `#[cfg(...)]` attributes of feature- or platform-gated items.
+	pub fn with_cfg_annotations(mut self, enabled: bool) -> Self {
+		self.cfg_annotations = enabled;
+		self
+	}
+
+	/// Replace all doc shaping options at once.
+	pub fn with_doc_options(mut self, options: DocOptions) -> Self {
+		self.doc_options = options;
+		self
+	}
+
+	/// Configure code-fence language and block splitting for Markdown output.
+	pub fn with_markdown_options(mut self, options: MarkdownOptions) -> Self {
+		self.markdown_options = options;
+		self
+	}
+
+	/// Skip items less visible than `level`, e.g. `pub(crate)` to hide module-private helpers.
+	pub fn with_min_visibility(mut self, level: Option<VisibilityLevel>) -> Self {
+		self.min_visibility = level;
+		self
+	}
+
+	/// Render only `pub` items reachable from the crate root through public modules and
+	/// re-exports, dropping `pub` items in private modules that are never exported (what rustdoc
+	/// strips from public docs). Overrides [`Self::with_private_items`].
+	pub fn with_strict_public(mut self, strict: bool) -> Self {
+		self.strict_public = strict;
+		self
+	}
+
 	/// Restrict rendering to the provided selection.
 	pub fn with_selection(mut self, selection: RenderSelection) -> Self {
 		self.selection = Some(selection);
@@ -198,20 +356,104 @@ impl Renderer {
 
 	/// Render a crate into formatted Rust source text, returning both output and final current file.
 	pub fn render_ext(&self, crate_data: &Crate) -> Result<(String, Option<std::path::PathBuf>)> {
+		timings::time("render", || self.render_ext_untimed(crate_data))
+	}
+
+	fn render_ext_untimed(&self, crate_data: &Crate) -> Result<(String, Option<std::path::PathBuf>)> {
 		use super::state::RenderState;
 
+		if self.format == RenderFormat::Json {
+			let document = self.render_document(crate_data);
+			return Ok((serde_json::to_string_pretty(&document)?, None));
+		}
+
 		let mut state = RenderState::new(self, crate_data);
 		let raw_output = state.render()?;
 		let final_file = state.current_file.clone();
 		let output = match self.format {
 			RenderFormat::Rust => self.render_rust(&raw_output)?,
 			RenderFormat::Markdown => self.render_markdown(raw_output)?,
+			RenderFormat::Html => self.render_html_page(crate_data, &self.render_rust(&raw_output)?),
+			RenderFormat::Json => unreachable!("JSON output is rendered from the search index"),
 		};
 		Ok((output, final_file))
 	}
 
+	/// Stream the rendered crate into `out`, one top-level item at a time.
+	///
+	/// Unlike [`Self::render`], the whole skeleton is never held in memory: each item below the
+	/// crate root is formatted and written as soon as it is rendered. The result matches
+	/// [`Self::render`] apart from blank lines between top-level items and, in Markdown, each
+	/// top-level item getting its own code block. JSON documents are serialized straight into
+	/// the writer, and HTML pages, which need the whole skeleton for their anchors, and module
+	/// summaries are rendered first and then written.
+	pub fn render_to<W: Write + ?Sized>(&self, crate_data: &Crate, out: &mut W) -> Result<()> {
+		timings::time("render", || self.render_to_untimed(crate_data, out))
+	}
+
+	fn render_to_untimed<W: Write + ?Sized>(&self, crate_data: &Crate, out: &mut W) -> Result<()> {
+		use super::state::RenderState;
+
+		if self.format == RenderFormat::Json {
+			serde_json::to_writer_pretty(&mut *out, &self.render_document(crate_data))?;
+			return Ok(());
+		}
+		if self.format == RenderFormat::Html || self.module_summary {
+			out.write_all(self.render_ext_untimed(crate_data)?.0.as_bytes())?;
+			out.flush()?;
+			return Ok(());
+		}
+
+		let mut state = RenderState::new(self, crate_data);
+		let head = state.root_module_head();
+		let wrapped = !head.is_empty() && self.format == RenderFormat::Rust;
+		// Items follow the opening line directly, but are set off from the crate's inner docs.
+		let mut first = true;
+		if wrapped {
+			let open = self.render_rust(&format!("{head}}}\n"))?;
+			let open = open.trim_end().strip_suffix('}').unwrap_or(&open).trim_end();
+			writeln!(out, "{open}")?;
+			first = !open.contains('\n');
+		} else if !head.is_empty() {
+			let docs = markdown::render_markdown_with(&format!("{head}}}\n"), &self.markdown_options);
+			if !docs.trim().is_empty() {
+				write!(out, "{}\n\n", docs.trim_end())?;
+			}
+		}
+
+		state.render_root_items(&mut |chunk| {
+			let formatted = self.render_rust(&chunk)?;
+			let text = match self.format {
+				RenderFormat::Markdown => {
+					markdown::render_markdown_fragment(&formatted, &self.markdown_options)
+				}
+				_ if wrapped => indent_lines(&formatted),
+				_ => formatted,
+			};
+			let text = text.trim_end();
+			if text.is_empty() {
+				return Ok(());
+			}
+			if !first {
+				writeln!(out)?;
+			}
+			first = false;
+			match self.format {
+				RenderFormat::Markdown => writeln!(out, "{text}")?,
+				_ => writeln!(out, "{text}")?,
+			}
+			Ok(())
+		})?;
+
+		if wrapped {
+			writeln!(out, "}}")?;
+		}
+		out.flush()?;
+		Ok(())
+	}
+
 	fn render_rust(&self, raw_output: &str) -> Result<String> {
-		match self.formatter.format_str(raw_output) {
+		match timings::time("rustfmt", || self.formatter.format_str(raw_output)) {
 			Ok(formatted) => Ok(self.apply_postprocessors(formatted)),
 			Err(e) => {
 				// Formatting failures are expected when rendering partial snippets.
@@ -226,10 +468,18 @@ impl Renderer {
 
 	fn render_markdown(&self, raw_output: String) -> Result<String> {
 		let formatted = self.render_rust(&raw_output)?;
-		Ok(markdown::render_markdown(&formatted))
+		Ok(markdown::render_markdown_with(&formatted, &self.markdown_options))
 	}
 
 	fn apply_postprocessors(&self, rendered: String) -> String {
 		dedup_gap_markers(&rendered)
 	}
 }
+
+/// Indent every non-empty line by one tab, for items written inside the streamed root module.
+fn indent_lines(text: &str) -> String {
+	text.lines()
+		.map(|line| if line.is_empty() { String::new() } else { format!("\t{line}") })
+		.collect::<Vec<_>>()
+		.join("\n")
+}
@@ -0,0 +1,288 @@
+//! How much of each doc comment is rendered, and how.
+//!
+//! Shortened docs end with an ellipsis marker so readers can tell text was dropped. Cuts are
+//! paragraph-aware: a fenced code block is never split, even when it contains blank lines.
+//! [`DocOptions`] can additionally drop code blocks and wrap long prose lines; code, headings,
+//! and tables are never wrapped.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Marker appended to docs that were cut short.
+pub const DOC_ELLIPSIS: &str = "…";
+
+/// How much of each doc comment to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocMode {
+	/// Render docs in full.
+	#[default]
+	Full,
+	/// Render the first paragraph.
+	FirstParagraph,
+	/// Render the first line of the first paragraph.
+	FirstLine,
+	/// Drop docs entirely.
+	None,
+}
+
+impl fmt::Display for DocMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let label = match self {
+			Self::Full => "full",
+			Self::FirstParagraph => "first-paragraph",
+			Self::FirstLine => "first-line",
+			Self::None => "none",
+		};
+		f.write_str(label)
+	}
+}
+
+impl FromStr for DocMode {
+	type Err = String;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value.trim() {
+			"full" => Ok(Self::Full),
+			"first-paragraph" | "paragraph" => Ok(Self::FirstParagraph),
+			"first-line" | "line" => Ok(Self::FirstLine),
+			"none" => Ok(Self::None),
+			other => Err(format!(
+				"unknown doc mode '{other}' (expected full, first-paragraph, first-line, or none)"
+			)),
+		}
+	}
+}
+
+impl DocMode {
+	/// Cut `docs` down to this mode, or `None` when nothing should be rendered.
+	pub fn apply(self, docs: &str) -> Option<String> {
+		match self {
+			Self::Full => return Some(docs.to_string()),
+			Self::None => return None,
+			Self::FirstParagraph | Self::FirstLine => {}
+		}
+		let docs = docs.trim_start_matches('\n').trim_end();
+		let paragraph = first_paragraph(docs);
+
+		let kept = match self {
+			// A first line that opens a code block would leave it unterminated.
+			Self::FirstLine if !is_fence(paragraph.lines().next().unwrap_or_default()) => {
+				paragraph.lines().next().unwrap_or_default()
+			}
+			_ => paragraph,
+		};
+		if kept.len() == docs.len() {
+			return Some(kept.to_string());
+		}
+		if self == Self::FirstLine && !kept.contains('\n') {
+			return Some(format!("{} {DOC_ELLIPSIS}", kept.trim_end()));
+		}
+		Some(format!("{kept}\n{DOC_ELLIPSIS}"))
+	}
+}
+
+/// How doc comments are shaped before rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DocOptions {
+	/// How much of each doc comment to keep.
+	pub mode: DocMode,
+	/// Wrap prose lines longer than this many columns, not counting the comment prefix.
+	pub wrap: Option<usize>,
+	/// Drop fenced code blocks, keeping the prose around them.
+	pub strip_code_blocks: bool,
+}
+
+impl DocOptions {
+	/// Shape `docs` for rendering, or `None` when nothing should be rendered.
+	///
+	/// Code blocks are stripped before the mode cuts the docs, so a summary never ends up being
+	/// just an example.
+	pub fn apply(&self, docs: &str) -> Option<String> {
+		let stripped;
+		let docs = if self.strip_code_blocks {
+			stripped = strip_code_blocks(docs);
+			if stripped.is_empty() {
+				return None;
+			}
+			stripped.as_str()
+		} else {
+			docs
+		};
+		let docs = self.mode.apply(docs)?;
+		Some(match self.wrap {
+			Some(width) => wrap_prose(&docs, width),
+			None => docs,
+		})
+	}
+}
+
+/// Remove fenced code blocks from `docs`, collapsing the blank lines they leave behind.
+fn strip_code_blocks(docs: &str) -> String {
+	let mut kept: Vec<&str> = Vec::new();
+	let mut in_fence = false;
+	for line in docs.lines() {
+		if is_fence(line) {
+			in_fence = !in_fence;
+			continue;
+		}
+		let repeated_blank =
+			line.trim().is_empty() && kept.last().is_none_or(|last| last.trim().is_empty());
+		if in_fence || repeated_blank {
+			continue;
+		}
+		kept.push(line);
+	}
+	while kept.last().is_some_and(|last| last.trim().is_empty()) {
+		kept.pop();
+	}
+	kept.join("\n")
+}
+
+/// Wrap prose lines of `docs` to `width` columns, leaving code blocks, headings, and tables as
+/// they are. List items keep a hanging indent under their marker.
+fn wrap_prose(docs: &str, width: usize) -> String {
+	let mut lines = Vec::new();
+	let mut in_fence = false;
+	for line in docs.lines() {
+		if is_fence(line) {
+			in_fence = !in_fence;
+			lines.push(line.to_string());
+			continue;
+		}
+		let trimmed = line.trim_start();
+		if in_fence
+			|| line.chars().count() <= width
+			|| trimmed.starts_with('#')
+			|| trimmed.starts_with('|')
+		{
+			lines.push(line.to_string());
+			continue;
+		}
+		let indent = &line[..line.len() - trimmed.len()];
+		let hanging = format!("{indent}{}", " ".repeat(list_marker_len(trimmed)));
+		let mut current = indent.to_string();
+		let mut current_width = indent.chars().count();
+		let mut first_word = true;
+		for word in trimmed.split_whitespace() {
+			let word_width = word.chars().count();
+			if !first_word && current_width + 1 + word_width > width {
+				lines.push(std::mem::replace(&mut current, hanging.clone()));
+				current_width = hanging.chars().count();
+				first_word = true;
+			}
+			if !first_word {
+				current.push(' ');
+				current_width += 1;
+			}
+			current.push_str(word);
+			current_width += word_width;
+			first_word = false;
+		}
+		lines.push(current);
+	}
+	lines.join("\n")
+}
+
+/// Width of a leading list marker such as `- `, `* `, or `1. `, or zero.
+fn list_marker_len(line: &str) -> usize {
+	if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+		return 2;
+	}
+	let digits = line.chars().take_while(char::is_ascii_digit).count();
+	if digits > 0 && line[digits..].starts_with(". ") {
+		digits + 2
+	} else {
+		0
+	}
+}
+
+/// Leading text of `docs` up to the first blank line outside a fenced code block.
+fn first_paragraph(docs: &str) -> &str {
+	let mut in_fence = false;
+	let mut end = 0;
+	for line in docs.split_inclusive('\n') {
+		if !in_fence && line.trim().is_empty() {
+			break;
+		}
+		if is_fence(line) {
+			in_fence = !in_fence;
+		}
+		end += line.len();
+	}
+	docs[..end].trim_end()
+}
+
+fn is_fence(line: &str) -> bool {
+	let line = line.trim_start();
+	line.starts_with("```") || line.starts_with("~~~")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DOCS: &str = "Parses the input.\nReturns the value.\n\nMore details.\n\n```\nlet x = 1;\n\nlet y = 2;\n```";
+
+	#[test]
+	fn full_and_none() {
+		assert_eq!(DocMode::Full.apply(DOCS).as_deref(), Some(DOCS));
+		assert_eq!(DocMode::None.apply(DOCS), None);
+	}
+
+	#[test]
+	fn first_paragraph_marks_truncation() {
+		assert_eq!(
+			DocMode::FirstParagraph.apply(DOCS).as_deref(),
+			Some("Parses the input.\nReturns the value.\n…")
+		);
+		assert_eq!(
+			DocMode::FirstParagraph.apply("Only one paragraph.").as_deref(),
+			Some("Only one paragraph.")
+		);
+	}
+
+	#[test]
+	fn first_line_marks_truncation_inline() {
+		assert_eq!(DocMode::FirstLine.apply(DOCS).as_deref(), Some("Parses the input. …"));
+		assert_eq!(DocMode::FirstLine.apply("Short.").as_deref(), Some("Short."));
+	}
+
+	#[test]
+	fn stripping_code_keeps_prose() {
+		let options = DocOptions {
+			strip_code_blocks: true,
+			..DocOptions::default()
+		};
+		assert_eq!(
+			options.apply(DOCS).as_deref(),
+			Some("Parses the input.\nReturns the value.\n\nMore details.")
+		);
+		assert_eq!(options.apply("```\nlet x = 1;\n```"), None);
+	}
+
+	#[test]
+	fn wrapping_leaves_code_and_keeps_list_indent() {
+		let options = DocOptions {
+			wrap: Some(20),
+			..DocOptions::default()
+		};
+		let docs = "- parses the input into tokens\n```\nlet value = parse(input_string);\n```";
+		assert_eq!(
+			options.apply(docs).as_deref(),
+			Some("- parses the input\n  into tokens\n```\nlet value = parse(input_string);\n```")
+		);
+	}
+
+	#[test]
+	fn code_blocks_are_never_split() {
+		let docs = "```\nlet x = 1;\n\nlet y = 2;\n```\n\nAfter.";
+		assert_eq!(
+			DocMode::FirstParagraph.apply(docs).as_deref(),
+			Some("```\nlet x = 1;\n\nlet y = 2;\n```\n…")
+		);
+		assert_eq!(
+			DocMode::FirstLine.apply(docs).as_deref(),
+			Some("```\nlet x = 1;\n\nlet y = 2;\n```\n…")
+		);
+	}
+}
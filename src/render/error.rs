@@ -9,6 +9,10 @@ pub enum RipdocError {
 	FilterNotMatched(String),
 	/// Formatting failure while pretty-printing the rendered output.
 	Formatter(FormatError),
+	/// Failed to encode the JSON output format.
+	Serialization(serde_json::Error),
+	/// Failed to write streamed output.
+	Io(std::io::Error),
 }
 
 impl fmt::Display for RipdocError {
@@ -18,6 +22,8 @@ impl fmt::Display for RipdocError {
 				write!(f, "filter path '{filter}' did not match any items")
 			}
 			Self::Formatter(err) => write!(f, "{err}"),
+			Self::Serialization(err) => write!(f, "{err}"),
+			Self::Io(err) => write!(f, "{err}"),
 		}
 	}
 }
@@ -30,5 +36,17 @@ impl From<FormatError> for RipdocError {
 	}
 }
 
+impl From<serde_json::Error> for RipdocError {
+	fn from(err: serde_json::Error) -> Self {
+		Self::Serialization(err)
+	}
+}
+
+impl From<std::io::Error> for RipdocError {
+	fn from(err: std::io::Error) -> Self {
+		Self::Io(err)
+	}
+}
+
 /// Result type returned by renderer helpers.
 pub type Result<T> = std::result::Result<T, RipdocError>;
@@ -0,0 +1,93 @@
+//! Usage examples lifted out of doc comments into labelled sections after their item.
+//!
+//! Sections are emitted into the raw Rust output as a `// ripdoc:example: <label>` marker
+//! followed by the example commented out line by line, so Rust output stays valid. Markdown
+//! conversion turns each section into a labelled code block.
+
+use rustdoc_types::Item;
+
+/// Marker opening an example section in the raw output.
+pub const EXAMPLE_MARKER: &str = "// ripdoc:example: ";
+
+/// Extract the Rust code blocks from `docs`, with doctest-hidden (`# `) lines removed.
+pub fn doc_examples(docs: &str) -> Vec<String> {
+	let mut examples = Vec::new();
+	// `Some` while inside a fence; the lines are collected only for Rust fences.
+	let mut fence: Option<Option<Vec<String>>> = None;
+	for line in docs.lines() {
+		let trimmed = line.trim();
+		if let Some(info) = trimmed.strip_prefix("```") {
+			match fence.take() {
+				Some(Some(lines)) => {
+					let code = lines.join("\n");
+					if !code.trim().is_empty() {
+						examples.push(code.trim_matches('\n').to_string());
+					}
+				}
+				Some(None) => {}
+				None => fence = Some(is_rust_fence(info).then(Vec::new)),
+			}
+			continue;
+		}
+		let Some(Some(lines)) = fence.as_mut() else {
+			continue;
+		};
+		if trimmed == "#" || trimmed.starts_with("# ") {
+			continue;
+		}
+		// `##` escapes a literal leading `#` in doctests.
+		lines.push(if trimmed.starts_with("##") {
+			line.replacen("##", "#", 1)
+		} else {
+			line.to_string()
+		});
+	}
+	examples
+}
+
+/// Render the example sections for `item`, labelled with `label`; empty when it has none.
+pub fn render_example_sections(item: &Item, label: &str) -> String {
+	let Some(docs) = &item.docs else {
+		return String::new();
+	};
+	let mut output = String::new();
+	for example in doc_examples(docs) {
+		output.push_str(EXAMPLE_MARKER);
+		output.push_str(label);
+		output.push('\n');
+		for line in example.lines() {
+			if line.trim().is_empty() {
+				output.push_str("//\n");
+			} else {
+				output.push_str("// ");
+				output.push_str(line);
+				output.push('\n');
+			}
+		}
+		output.push('\n');
+	}
+	output
+}
+
+/// Whether a fence info string (the text after the opening backticks) denotes Rust code.
+fn is_rust_fence(info: &str) -> bool {
+	let primary = info.split(',').next().unwrap_or("").trim();
+	matches!(
+		primary,
+		"" | "rust" | "no_run" | "compile_fail" | "should_panic" | "ignore"
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extracts_rust_blocks_without_hidden_lines() {
+		let docs = "Builds a config.\n\n```\n# use demo::Config;\nlet config = Config::new();\n```\n\n```text\nnot code\n```\n\n```rust,no_run\n## attr\nconfig.load();\n```";
+		assert_eq!(
+			doc_examples(docs),
+			vec!["let config = Config::new();".to_string(), "# attr\nconfig.load();".to_string()]
+		);
+	}
+}
@@ -0,0 +1,566 @@
+//! Standalone HTML pages for [`RenderFormat::Html`](super::RenderFormat::Html).
+//!
+//! A page pairs the formatted skeleton, syntax-highlighted, with a sidebar module tree built by
+//! [`build_list_tree`] from the items the JSON format would list. Declarations in the skeleton
+//! get `id` anchors named after their canonical path (`my_crate::io::Reader`), and sidebar
+//! entries link to them. Styles are inline, so the page can be shared as a single file.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use rustdoc_types::Crate;
+
+use super::core::Renderer;
+use crate::core_api::list_tree::{ListTreeNode, build_list_tree};
+use crate::core_api::search::ListItem;
+
+/// Keywords highlighted in skeleton code.
+const KEYWORDS: &[&str] = &[
+	"as", "async", "await", "const", "crate", "dyn", "else", "enum", "extern", "fn", "for", "if",
+	"impl", "in", "let", "macro_rules", "match", "mod", "move", "mut", "pub", "ref", "return",
+	"self", "Self", "static", "struct", "super", "trait", "type", "union", "unsafe", "use", "where",
+];
+
+const STYLE: &str = "\
+body { margin: 0; display: flex; font-family: system-ui, sans-serif; color: #1f2328; }
+nav { width: 18rem; flex-shrink: 0; height: 100vh; position: sticky; top: 0; overflow: auto;
+  padding: 1rem; box-sizing: border-box; border-right: 1px solid #d0d7de; background: #f6f8fa;
+  font-size: 0.85rem; }
+nav ul { list-style: none; margin: 0; padding-left: 1rem; }
+nav > ul { padding-left: 0; }
+nav summary { cursor: pointer; }
+nav a { color: inherit; text-decoration: none; }
+nav a:hover { text-decoration: underline; }
+nav .kind { color: #656d76; font-size: 0.75rem; margin-right: 0.3rem; }
+main { flex: 1; min-width: 0; padding: 1rem 2rem; }
+pre { font-size: 0.85rem; line-height: 1.45; tab-size: 4; }
+:target { background: #fff8c5; }
+.kw { color: #cf222e; } .ty { color: #953800; } .str { color: #0a3069; } .num { color: #0550ae; }
+.lt { color: #8250df; } .mac { color: #6639ba; } .attr { color: #57606a; }
+.com { color: #6e7781; font-style: italic; } .doc { color: #116329; }
+";
+
+impl Renderer {
+	/// Wrap the formatted skeleton `source` of `crate_data` in a standalone HTML page.
+	pub(crate) fn render_html_page(&self, crate_data: &Crate, source: &str) -> String {
+		let document = self.render_document(crate_data);
+		let items: Vec<ListItem> = document
+			.items
+			.iter()
+			.map(|item| ListItem {
+				kind: item.kind,
+				path: item.path.clone(),
+				source: item.source.clone(),
+				fan_in: None,
+				deprecated: item.deprecated,
+				deprecation_note: item.deprecation_note.clone(),
+				cfg: Vec::new(),
+			})
+			.collect();
+		let (code, anchors) = highlight_source(source, &document.crate_name);
+
+		let title = match &document.version {
+			Some(version) => format!("{} {version}", document.crate_name),
+			None => document.crate_name.clone(),
+		};
+		let mut page = String::new();
+		let _ = write!(
+			page,
+			"<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+			 <title>{}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<nav>\n<ul>\n",
+			escape(&title)
+		);
+		render_tree(&build_list_tree(&items), "", 0, &anchors, &mut page);
+		let _ = write!(
+			page,
+			"</ul>\n</nav>\n<main>\n<h1>{}</h1>\n<pre><code>{code}</code></pre>\n</main>\n\
+			 </body>\n</html>\n",
+			escape(&title)
+		);
+		page
+	}
+}
+
+/// Write `nodes` as nested sidebar lists, linking entries whose anchor exists in the code.
+fn render_tree(
+	nodes: &[ListTreeNode],
+	parent: &str,
+	depth: usize,
+	anchors: &HashSet<String>,
+	out: &mut String,
+) {
+	for node in nodes {
+		let path = if parent.is_empty() {
+			node.name.clone()
+		} else {
+			format!("{parent}::{}", node.name)
+		};
+		let name = escape(&node.name);
+		let mut label = format!("<span class=\"kind\">{}</span>", node.kind.label());
+		if anchors.contains(&path) {
+			let _ = write!(label, "<a href=\"#{}\">{name}</a>", escape(&path));
+		} else {
+			label.push_str(&name);
+		}
+		if node.children.is_empty() {
+			let _ = writeln!(out, "<li>{label}</li>");
+			continue;
+		}
+		let open = if depth == 0 { " open" } else { "" };
+		let _ = writeln!(out, "<li><details{open}><summary>{label}</summary>\n<ul>");
+		render_tree(&node.children, &path, depth + 1, anchors, out);
+		out.push_str("</ul></details></li>\n");
+	}
+}
+
+/// Highlight `source` line by line, anchoring declarations; returns the HTML and the anchors.
+fn highlight_source(source: &str, crate_name: &str) -> (String, HashSet<String>) {
+	let mut scopes = ScopeTracker::new(crate_name);
+	let mut anchors = HashSet::new();
+	let mut html = String::new();
+	for line in source.lines() {
+		if let Some(path) = scopes.line(line)
+			&& anchors.insert(path.clone())
+		{
+			let _ = write!(html, "<span id=\"{}\"></span>", escape(&path));
+		}
+		highlight_line(line, &mut html);
+		html.push('\n');
+	}
+	(html, anchors)
+}
+
+/// What the items directly inside a braced scope are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeKind {
+	/// Module items.
+	Module,
+	/// Struct or union fields.
+	Fields,
+	/// Enum variants.
+	Variants,
+	/// Trait or impl items.
+	Items,
+	/// Function bodies and other scopes whose contents are not items.
+	Opaque,
+}
+
+/// A braced scope opened by a declaration.
+#[derive(Debug)]
+struct Scope {
+	/// Path segment the scope adds; `None` if items inside have no path of their own (trait
+	/// impls), and empty for the crate root module.
+	name: Option<String>,
+	kind: ScopeKind,
+	/// Brace depth outside the scope.
+	depth: usize,
+}
+
+/// Follows brace nesting through formatted skeleton code to name each declaration's path.
+#[derive(Debug)]
+struct ScopeTracker {
+	crate_name: String,
+	stack: Vec<Scope>,
+	depth: usize,
+	/// Declaration whose opening brace is still to come, e.g. after a `where` clause.
+	pending: Option<Scope>,
+}
+
+impl ScopeTracker {
+	fn new(crate_name: &str) -> Self {
+		Self {
+			crate_name: crate_name.to_string(),
+			stack: Vec::new(),
+			depth: 0,
+			pending: None,
+		}
+	}
+
+	/// Advance past `line`, returning the path of the item it declares, if any.
+	fn line(&mut self, line: &str) -> Option<String> {
+		let code = line.trim();
+		if code.is_empty() || code.starts_with("//") || code.starts_with('#') {
+			return None;
+		}
+		let kind = self.stack.last().map_or(ScopeKind::Module, |scope| scope.kind);
+		let declared = declaration(code, kind);
+		// Rust output may wrap everything in `pub mod <crate> { .. }`, which adds no segment.
+		let root_wrapper = self.stack.is_empty()
+			&& matches!(&declared, Some((Some(name), Some(ScopeKind::Module))) if *name == self.crate_name);
+		let path = declared
+			.as_ref()
+			.filter(|_| !root_wrapper)
+			.and_then(|(name, _)| self.path_of(name.as_deref()?));
+
+		let (opens, closes) = count_braces(code);
+		if let Some((name, Some(kind))) = declared {
+			let scope = Scope {
+				name: if root_wrapper { Some(String::new()) } else { name },
+				kind,
+				depth: self.depth,
+			};
+			if opens > closes {
+				self.stack.push(scope);
+			} else if opens == 0 && !code.ends_with(';') {
+				self.pending = Some(scope);
+			}
+		} else if opens > closes
+			&& let Some(scope) = self.pending.take()
+		{
+			self.stack.push(scope);
+		}
+		if opens > 0 || code.ends_with(';') {
+			self.pending = None;
+		}
+
+		self.depth = (self.depth + opens).saturating_sub(closes);
+		while self.stack.last().is_some_and(|scope| scope.depth >= self.depth) {
+			self.stack.pop();
+		}
+		path
+	}
+
+	/// Canonical path of `name` declared in the current scope, if items there have paths.
+	fn path_of(&self, name: &str) -> Option<String> {
+		let mut path = self.crate_name.clone();
+		for scope in &self.stack {
+			if scope.kind == ScopeKind::Opaque {
+				return None;
+			}
+			let segment = scope.name.as_deref()?;
+			if !segment.is_empty() {
+				path.push_str("::");
+				path.push_str(segment);
+			}
+		}
+		path.push_str("::");
+		path.push_str(name);
+		Some(path)
+	}
+}
+
+/// Parse the item `code` declares inside a scope of `kind`: its name (if it has a path) and the
+/// kind of scope its braces open (if any).
+fn declaration(code: &str, kind: ScopeKind) -> Option<(Option<String>, Option<ScopeKind>)> {
+	match kind {
+		ScopeKind::Opaque => return None,
+		ScopeKind::Fields => {
+			let rest = strip_visibility(code);
+			let name = ident(rest)?;
+			return rest[name.len()..]
+				.trim_start()
+				.starts_with(':')
+				.then(|| (Some(name.to_string()), None));
+		}
+		ScopeKind::Variants => {
+			let name = ident(code)?;
+			let next = code[name.len()..].trim_start().chars().next();
+			return matches!(next, None | Some(',' | '(' | '{' | '='))
+				.then(|| (Some(name.to_string()), None));
+		}
+		ScopeKind::Module | ScopeKind::Items => {}
+	}
+
+	let mut rest = strip_visibility(code);
+	loop {
+		let before = rest;
+		for qualifier in ["unsafe ", "async ", "default ", "auto "] {
+			rest = rest.strip_prefix(qualifier).unwrap_or(rest);
+		}
+		if let Some(abi) = rest.strip_prefix("extern \"")
+			&& let Some((_, after)) = abi.split_once("\" ")
+		{
+			rest = after;
+		}
+		if rest.starts_with("const fn ") || rest.starts_with("const unsafe ") {
+			rest = &rest["const ".len()..];
+		}
+		if rest == before {
+			break;
+		}
+	}
+
+	if let Some(target) = rest.strip_prefix("impl")
+		&& target.starts_with([' ', '<'])
+	{
+		let target = target.trim_start();
+		let target = if target.starts_with('<') {
+			&target[matching_angle(target)?..]
+		} else {
+			target
+		};
+		// Items of trait impls are addressed through the trait, so they get no anchors.
+		let name = if target.contains(" for ") {
+			None
+		} else {
+			ident(target.trim_start()).map(str::to_string)
+		};
+		return Some((name, Some(ScopeKind::Items)));
+	}
+
+	let (keyword, scope) = [
+		("mod ", Some(ScopeKind::Module)),
+		("struct ", Some(ScopeKind::Fields)),
+		("union ", Some(ScopeKind::Fields)),
+		("enum ", Some(ScopeKind::Variants)),
+		("trait ", Some(ScopeKind::Items)),
+		("fn ", Some(ScopeKind::Opaque)),
+		("type ", None),
+		("const ", None),
+		("static mut ", None),
+		("static ", None),
+		("macro_rules! ", Some(ScopeKind::Opaque)),
+	]
+	.into_iter()
+	.find(|(keyword, _)| rest.starts_with(keyword))?;
+	let name = ident(&rest[keyword.len()..])?;
+	(name != "_").then(|| (Some(name.to_string()), scope))
+}
+
+/// Drop a leading `pub`, `pub(crate)`, or `pub(in path)`.
+fn strip_visibility(code: &str) -> &str {
+	let Some(rest) = code.strip_prefix("pub") else {
+		return code;
+	};
+	if let Some(restricted) = rest.strip_prefix('(')
+		&& let Some((_, after)) = restricted.split_once(')')
+	{
+		return after.trim_start();
+	}
+	rest.strip_prefix(' ').unwrap_or(code)
+}
+
+/// The identifier `text` starts with, including a raw `r#` prefix.
+fn ident(text: &str) -> Option<&str> {
+	let body = text.strip_prefix("r#").unwrap_or(text);
+	let prefix = text.len() - body.len();
+	let end = body
+		.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+		.unwrap_or(body.len());
+	let first = body.chars().next()?;
+	(end > 0 && !first.is_ascii_digit()).then(|| &text[..prefix + end])
+}
+
+/// Byte offset just past the `>` closing the `<` that `text` starts with.
+fn matching_angle(text: &str) -> Option<usize> {
+	let mut depth = 0usize;
+	let mut previous = '\0';
+	for (offset, c) in text.char_indices() {
+		match c {
+			'<' => depth += 1,
+			// `->` in `Fn() -> T` bounds does not close a bracket.
+			'>' if previous != '-' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(offset + 1);
+				}
+			}
+			_ => {}
+		}
+		previous = c;
+	}
+	None
+}
+
+/// Opening and closing braces in `code`, ignoring string and char literals and comments.
+fn count_braces(code: &str) -> (usize, usize) {
+	let (mut opens, mut closes) = (0, 0);
+	let mut chars = code.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' => {
+				while let Some(c) = chars.next() {
+					match c {
+						'\\' => {
+							chars.next();
+						}
+						'"' => break,
+						_ => {}
+					}
+				}
+			}
+			'\'' => {
+				// Skip char literals such as `'{'`; lifetimes have no closing quote.
+				let mut lookahead = chars.clone();
+				if lookahead.next().is_some() && lookahead.next() == Some('\'') {
+					chars = lookahead;
+				}
+			}
+			'/' if chars.peek() == Some(&'/') => break,
+			'{' => opens += 1,
+			'}' => closes += 1,
+			_ => {}
+		}
+	}
+	(opens, closes)
+}
+
+/// Append `line` to `out` as HTML with highlighting spans.
+fn highlight_line(line: &str, out: &mut String) {
+	let trimmed = line.trim_start();
+	let indent = &line[..line.len() - trimmed.len()];
+	let whole = if trimmed.starts_with("///") || trimmed.starts_with("//!") {
+		Some("doc")
+	} else if trimmed.starts_with("//") {
+		Some("com")
+	} else if trimmed.starts_with("#[") || trimmed.starts_with("#![") {
+		Some("attr")
+	} else {
+		None
+	};
+	if let Some(class) = whole {
+		let _ = write!(out, "{indent}<span class=\"{class}\">{}</span>", escape(trimmed));
+		return;
+	}
+
+	let chars: Vec<char> = line.chars().collect();
+	let mut i = 0;
+	while i < chars.len() {
+		let c = chars[i];
+		let start = i;
+		let class = if c == '/' && chars.get(i + 1) == Some(&'/') {
+			i = chars.len();
+			"com"
+		} else if c == '/' && chars.get(i + 1) == Some(&'*') {
+			i += 2;
+			while i < chars.len() && !(chars[i - 1] == '*' && chars[i] == '/') {
+				i += 1;
+			}
+			i = (i + 1).min(chars.len());
+			"com"
+		} else if c == '"' {
+			i += 1;
+			while i < chars.len() && chars[i] != '"' {
+				i += if chars[i] == '\\' { 2 } else { 1 };
+			}
+			i = (i + 1).min(chars.len());
+			"str"
+		} else if c == '\'' && (chars.get(i + 2) == Some(&'\'') || chars.get(i + 1) == Some(&'\\')) {
+			i += 1;
+			while i < chars.len() && chars[i] != '\'' {
+				i += if chars[i] == '\\' { 2 } else { 1 };
+			}
+			i = (i + 1).min(chars.len());
+			"str"
+		} else if c == '\'' && chars.get(i + 1).is_some_and(|c| c.is_alphabetic() || *c == '_') {
+			i += 1;
+			while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+				i += 1;
+			}
+			"lt"
+		} else if c.is_ascii_digit() {
+			while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+				i += 1;
+			}
+			"num"
+		} else if c.is_alphabetic() || c == '_' {
+			while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+				i += 1;
+			}
+			let word: String = chars[start..i].iter().collect();
+			if chars.get(i) == Some(&'!') && word != "macro_rules" {
+				i += 1;
+				"mac"
+			} else if KEYWORDS.contains(&word.as_str()) {
+				"kw"
+			} else if c.is_uppercase() {
+				"ty"
+			} else {
+				""
+			}
+		} else {
+			i += 1;
+			""
+		};
+		let text: String = chars[start..i].iter().collect();
+		if class.is_empty() {
+			out.push_str(&escape(&text));
+		} else {
+			let _ = write!(out, "<span class=\"{class}\">{}</span>", escape(&text));
+		}
+	}
+}
+
+/// Escape text for HTML element content and attribute values.
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn anchors_follow_declaration_nesting() {
+		let source = "\
+pub mod demo {
+	pub mod io {
+		/// A reader.
+		pub struct Reader {
+			pub buffer: Vec<u8>,
+		}
+
+		impl Reader {
+			pub fn new() -> Self {}
+		}
+
+		impl<T: Clone> From<T> for Reader {
+			fn from(value: T) -> Self {}
+		}
+	}
+
+	pub enum Mode {
+		Fast,
+		Slow(u8),
+	}
+
+	pub fn run<F>(f: F)
+	where
+		F: Fn() -> u8,
+	{
+	}
+
+	pub const LIMIT: usize = 3;
+}
+";
+		let (html, anchors) = highlight_source(source, "demo");
+		let mut anchors: Vec<_> = anchors.into_iter().collect();
+		anchors.sort();
+		assert_eq!(
+			anchors,
+			vec![
+				"demo::LIMIT",
+				"demo::Mode",
+				"demo::Mode::Fast",
+				"demo::Mode::Slow",
+				"demo::io",
+				"demo::io::Reader",
+				"demo::io::Reader::buffer",
+				"demo::io::Reader::new",
+				"demo::run",
+			]
+		);
+		assert!(html.contains("<span class=\"doc\">/// A reader.</span>"));
+		assert!(html.contains("<span class=\"ty\">Vec</span>&lt;u8&gt;"));
+	}
+
+	#[test]
+	fn highlights_literals_and_lifetimes() {
+		let mut out = String::new();
+		highlight_line("pub fn f<'a>(s: &'a str) -> char { '{' } // \"note\"", &mut out);
+		assert!(out.contains("<span class=\"lt\">'a</span>"));
+		assert!(out.contains("<span class=\"str\">'{'</span>"));
+		assert!(out.contains("<span class=\"com\">// &quot;note&quot;</span>"));
+		assert_eq!(count_braces("fn f() -> char { '{' } // }"), (1, 1));
+	}
+}
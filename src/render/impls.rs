@@ -1,8 +1,11 @@
 use rustdoc_types::{Impl, Item, ItemEnum, Type, Visibility};
 
+use super::examples::render_example_sections;
+use super::lifetimes::simplify_lifetimes;
 use super::state::{GapController, RenderState};
 use super::syntax::*;
 use super::utils::ppush;
+use crate::core_api::export::is_doc_hidden;
 
 fn extracted_source_looks_like_item(item: &Item, source: &str) -> bool {
 	fn first_code_line(source: &str) -> Option<&str> {
@@ -57,13 +60,36 @@ pub const DERIVE_TRAITS: &[&str] = &[
 	"Deserialize",
 ];
 
+/// How structs and enums show their impls of the traits in [`DERIVE_TRAITS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeriveStyle {
+	/// A `#[derive(...)]` attribute in place of the impl blocks.
+	#[default]
+	Attribute,
+	/// A `/* derives: ... */` comment in place of the impl blocks, so consumers can tell the
+	/// summary apart from attributes written in the source.
+	Summary,
+	/// No annotation; the impl blocks are rendered like any other trait impl.
+	Impls,
+}
+
 /// Determine whether an impl block should be rendered in the output.
-pub fn should_render_impl(impl_: &Impl, render_auto_impls: bool) -> bool {
+pub fn should_render_impl(impl_: &Impl, render_auto_impls: bool, derives: DeriveStyle) -> bool {
 	if impl_.is_synthetic && !render_auto_impls {
 		return false;
 	}
 
-	if DERIVE_TRAITS.contains(&impl_.trait_.as_ref().map_or("", |t| t.path.as_str())) {
+	let trait_path = impl_.trait_.as_ref().map_or("", |t| t.path.as_str());
+	let summarized = match derives {
+		DeriveStyle::Attribute => DERIVE_TRAITS.contains(&trait_path),
+		// Match the annotation exactly, which names traits by their last segment.
+		DeriveStyle::Summary => {
+			!impl_.is_synthetic
+				&& DERIVE_TRAITS.contains(&trait_path.rsplit("::").next().unwrap_or_default())
+		}
+		DeriveStyle::Impls => false,
+	};
+	if summarized {
 		return false;
 	}
 
@@ -87,7 +113,7 @@ pub fn render_impl(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 		return format!("{source}\n\n");
 	}
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 	let impl_ = extract_item!(item, ItemEnum::Impl);
 
 	let selection_active = state.selection().is_some();
@@ -187,13 +213,17 @@ pub fn render_impl_item(
 		return format!("{source}\n\n");
 	}
 
-	match &item.inner {
+	let mut output = match &item.inner {
 		ItemEnum::Function(_) => render_function(state, item, false),
 		ItemEnum::Constant { .. } => render_constant(state, item),
 		ItemEnum::AssocType { .. } => render_associated_type(item),
 		ItemEnum::TypeAlias(_) => render_type_alias(state, item),
 		_ => String::new(),
+	};
+	if state.config.doc_examples && !output.is_empty() {
+		output.push_str(&render_example_sections(item, &ppush(path_prefix, &render_name(item))));
 	}
+	output
 }
 
 /// Render a trait definition.
@@ -209,7 +239,7 @@ pub fn render_trait(state: &mut RenderState, item: &Item) -> String {
 		return format!("{source}\n\n");
 	}
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let trait_ = extract_item!(item, ItemEnum::Trait);
 
@@ -228,7 +258,7 @@ pub fn render_trait(state: &mut RenderState, item: &Item) -> String {
 
 	output.push_str(&format!(
 		"{}{}trait {}{}{}{} {{\n",
-		render_vis(item),
+		state.render_vis(item),
 		unsafe_prefix,
 		render_name(item),
 		generics,
@@ -240,8 +270,12 @@ pub fn render_trait(state: &mut RenderState, item: &Item) -> String {
 
 	for item_id in &trait_.items {
 		if selection.includes_child(state, item_id) {
-			let item = super::utils::must_get(state.crate_data, item_id);
-			let rendered = render_trait_item(state, item, &selection);
+			let trait_item = super::utils::must_get(state.crate_data, item_id);
+			let mut rendered = render_trait_item(state, trait_item, &selection);
+			if state.config.doc_examples && !rendered.is_empty() {
+				let label = format!("{}::{}", render_name(item), render_name(trait_item));
+				rendered.push_str(&render_example_sections(trait_item, &label));
+			}
 			if !rendered.is_empty() {
 				gaps.emit_if_needed(state, &mut output, &rendered);
 				output.push_str(&rendered);
@@ -310,12 +344,16 @@ fn render_trait_item(
 
 /// Determine whether an item should be rendered based on visibility settings.
 fn is_visible(state: &RenderState, item: &Item) -> bool {
-	state.config.render_private_items || matches!(item.visibility, Visibility::Public)
+	let private_allowed = state.config.render_private_items && !state.config.strict_public;
+	(private_allowed || matches!(item.visibility, Visibility::Public))
+		&& state.meets_min_visibility(item)
+		&& state.is_reachable(item)
+		&& (state.config.render_doc_hidden || !is_doc_hidden(item))
 }
 
 /// Render a function or method signature.
-fn render_function(_state: &RenderState, item: &Item, is_trait_method: bool) -> String {
-	let mut output = docs(item);
+fn render_function(state: &RenderState, item: &Item, is_trait_method: bool) -> String {
+	let mut output = state.docs(item);
 	let function = extract_item!(item, ItemEnum::Function);
 
 	// Handle const, async, and unsafe keywords in the correct order
@@ -330,20 +368,27 @@ fn render_function(_state: &RenderState, item: &Item, is_trait_method: bool) ->
 		prefixes.push("unsafe");
 	}
 
-	output.push_str(&format!(
+	let signature = format!(
 		"{} {} fn {}{}({}){}{}",
-		render_vis(item),
+		state.render_vis(item),
 		prefixes.join(" "),
 		render_name(item),
 		render_generics(&function.generics),
 		render_function_args(&function.sig),
 		render_return_type(&function.sig),
 		render_where_clause(&function.generics)
-	));
+	);
+	if state.config.simplify_lifetimes {
+		output.push_str(&simplify_lifetimes(&signature));
+	} else {
+		output.push_str(&signature);
+	}
 
 	// Use semicolon for trait method declarations, empty body for implementations
 	if is_trait_method && !function.has_body {
 		output.push_str(";\n\n");
+	} else if let Some(lines) = state.elided_source_lines(&item.id) {
+		output.push_str(&format!(" {{ /* {lines} lines elided */ }}\n\n"));
 	} else {
 		output.push_str(" {}\n\n");
 	}
@@ -352,13 +397,13 @@ fn render_function(_state: &RenderState, item: &Item, is_trait_method: bool) ->
 }
 
 /// Render a constant definition.
-fn render_constant(_state: &RenderState, item: &Item) -> String {
-	let mut output = docs(item);
+fn render_constant(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
 
 	let (type_, const_) = extract_item!(item, ItemEnum::Constant { type_, const_ });
 	output.push_str(&format!(
 		"{}const {}: {} = {};\n\n",
-		render_vis(item),
+		state.render_vis(item),
 		render_name(item),
 		render_type(type_),
 		const_.expr
@@ -368,13 +413,13 @@ fn render_constant(_state: &RenderState, item: &Item) -> String {
 }
 
 /// Render a type alias with generics, bounds, and visibility.
-fn render_type_alias(_state: &RenderState, item: &Item) -> String {
+fn render_type_alias(state: &RenderState, item: &Item) -> String {
 	let type_alias = extract_item!(item, ItemEnum::TypeAlias);
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	output.push_str(&format!(
 		"{}type {}{}{}",
-		render_vis(item),
+		state.render_vis(item),
 		render_name(item),
 		render_generics(&type_alias.generics),
 		render_where_clause(&type_alias.generics),
@@ -5,7 +5,7 @@ use super::super::state::{GapController, RenderState};
 use super::super::syntax::*;
 use super::super::utils::must_get;
 use super::structs::render_struct_field;
-use super::{SelectionView, collect_inline_traits};
+use super::{SelectionView, derive_annotation};
 
 /// Shared context for rendering enums and their variants consistently.
 pub(crate) struct EnumRenderContext {
@@ -41,6 +41,11 @@ impl EnumRenderContext {
 			|| self.selection().includes_child(state, variant_id)
 	}
 
+	/// Whether a variant's fields should be elided to a `/* .. */` placeholder instead of listed.
+	pub fn collapse_variant_fields(&self, state: &RenderState, variant: &Item) -> bool {
+		state.config.collapse_variant_fields && !state.selection_matches(&variant.id)
+	}
+
 	pub fn include_variant_fields(&self, state: &RenderState, variant: &Item) -> bool {
 		self.selection().expands_self()
 			|| !self.selection().is_active()
@@ -63,13 +68,10 @@ pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 			.ok()
 			.map(|s| format!("{s}\n\n"))
 	} else {
-		let mut output = docs(item);
+		let mut output = state.docs(item);
 
 		// Collect inline traits first while we have immutable access
-		let inline_traits: Vec<String> = collect_inline_traits(state, &enum_.impls)
-			.into_iter()
-			.map(|s| s.to_string())
-			.collect();
+		let derives = derive_annotation(state, &enum_.impls);
 
 		let ctx = EnumRenderContext::new(
 			state,
@@ -78,13 +80,11 @@ pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 			render_where_clause(&enum_.generics),
 		);
 
-		if !inline_traits.is_empty() {
-			output.push_str(&format!("#[derive({})]\n", inline_traits.join(", ")));
-		}
+		output.push_str(&derives);
 
 		output.push_str(&format!(
 			"{}enum {}{}{} {{\n",
-			render_vis(item),
+			state.render_vis(item),
 			render_name(item),
 			ctx.generics(),
 			ctx.where_clause()
@@ -119,7 +119,7 @@ pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 	for impl_id in &enum_.impls {
 		let impl_item = must_get(state.crate_data, impl_id);
 		let impl_ = extract_item!(impl_item, ItemEnum::Impl);
-		if should_render_impl(impl_, state.config.render_auto_impls)
+		if should_render_impl(impl_, state.config.render_auto_impls, state.config.derive_style)
 			&& state.selection_allows_child(&item.id, impl_id)
 		{
 			output.push_str(&render_impl(state, path_prefix, impl_item));
@@ -144,13 +144,22 @@ fn render_enum_variant(
 		return format!("    {source},\n");
 	}
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 	let variant = extract_item!(item, ItemEnum::Variant);
 
 	output.push_str(&format!("    {}", render_name(item)));
 
+	let collapse = ctx.collapse_variant_fields(state, item);
 	match &variant.kind {
 		VariantKind::Plain => {}
+		VariantKind::Tuple(fields) if collapse => {
+			if !fields.is_empty() {
+				output.push_str("(/* .. */)");
+			}
+		}
+		VariantKind::Struct { .. } if collapse => {
+			output.push_str(" { /* .. */ }");
+		}
 		VariantKind::Tuple(fields) => {
 			let fields_str = fields
 				.iter()
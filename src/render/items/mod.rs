@@ -16,10 +16,13 @@ use rustdoc_types::{Id, Item, ItemEnum, Visibility};
 pub use structs::render_struct;
 pub use use_stmt::render_use;
 
-use super::impls::DERIVE_TRAITS;
+use super::examples::render_example_sections;
+use super::impls::{DERIVE_TRAITS, DeriveStyle};
 use super::macros::{render_macro, render_proc_macro};
 use super::state::RenderState;
-use super::utils::must_get;
+use super::syntax::render_name;
+use super::utils::{must_get, ppush, source_label};
+use crate::core_api::export::is_doc_hidden;
 
 pub(crate) fn extracted_source_looks_like_item(item: &Item, source: &str) -> bool {
 	fn first_code_line(source: &str) -> Option<&str> {
@@ -100,8 +103,24 @@ impl SelectionView {
 	}
 }
 
+/// The line announcing derived traits above a struct or enum, per the configured
+/// [`DeriveStyle`]; empty when there is nothing to announce.
+pub(crate) fn derive_annotation(state: &RenderState, impls: &[Id]) -> String {
+	if state.config.derive_style == DeriveStyle::Impls {
+		return String::new();
+	}
+	let traits = collect_inline_traits(state, impls);
+	if traits.is_empty() {
+		return String::new();
+	}
+	match state.config.derive_style {
+		DeriveStyle::Summary => format!("/* derives: {} */\n", traits.join(", ")),
+		_ => format!("#[derive({})]\n", traits.join(", ")),
+	}
+}
+
 /// Collect trait names rendered via `#[derive]` for the provided impl list.
-pub(crate) fn collect_inline_traits<'a>(state: &'a RenderState, impls: &[Id]) -> Vec<&'a str> {
+fn collect_inline_traits<'a>(state: &'a RenderState, impls: &[Id]) -> Vec<&'a str> {
 	let mut inline_traits = Vec::new();
 	for impl_id in impls {
 		let impl_item = must_get(state.crate_data, impl_id);
@@ -120,6 +139,23 @@ pub(crate) fn collect_inline_traits<'a>(state: &'a RenderState, impls: &[Id]) ->
 	inline_traits
 }
 
+/// Whether [`render_item`] has rendering support for the kind of `item`.
+pub fn is_renderable(item: &Item) -> bool {
+	matches!(
+		item.inner,
+		ItemEnum::Module(_)
+			| ItemEnum::Struct(_)
+			| ItemEnum::Enum(_)
+			| ItemEnum::Trait(_)
+			| ItemEnum::Use(_)
+			| ItemEnum::Function(_)
+			| ItemEnum::Constant { .. }
+			| ItemEnum::TypeAlias(_)
+			| ItemEnum::Macro(_)
+			| ItemEnum::ProcMacro(_)
+	)
+}
+
 /// Render an item into Rust source text.
 pub fn render_item(
 	state: &mut RenderState,
@@ -151,18 +187,42 @@ pub fn render_item(
 		return String::new();
 	}
 
-	if state.should_filter(path_prefix, item) {
+	if state.should_filter(path_prefix, item) || state.is_excluded(path_prefix, item) {
 		return String::new();
 	}
 
-	if state.selection_is_full_source(&item.id)
+	let full_source = if state.selection_is_full_source(&item.id)
 		&& let Some(span) = &item.span
 		&& let Ok(source) =
 			crate::render::utils::extract_source(span, state.config.source_root.as_deref())
 		&& extracted_source_looks_like_item(item, &source)
 	{
+		Some(source)
+	} else {
+		None
+	};
+
+	// Claim the file section before rendering so nested items from the same file do not
+	// repeat the label, and items from another file re-label when rendering returns here.
+	// Plain output flattens modules away, so only their items are labelled there; the crate
+	// root is left to its children to keep labels inside its wrapper.
+	let label_file = item.span.as_ref().map(|span| &span.filename).filter(|filename| {
+		state.config.render_source_labels
+			&& !matches!(item.inner, ItemEnum::Use(_))
+			&& !(matches!(item.inner, ItemEnum::Module(_)) && (state.config.plain || item.id == state.crate_data.root))
+			&& state.current_file.as_ref() != Some(*filename)
+	});
+	let previous_file = label_file.map(|filename| state.current_file.replace(filename.clone()));
+
+	if let Some(source) = full_source {
 		state.visited.insert(item.id);
-		return format!("{source}\n\n");
+		return match label_file {
+			Some(filename) => {
+				let label = source_label(filename, state.config.source_root.as_deref());
+				format!("{label}{source}\n\n")
+			}
+			None => format!("{source}\n\n"),
+		};
 	}
 
 	let mut output = match &item.inner {
@@ -179,26 +239,34 @@ pub fn render_item(
 		_ => String::new(),
 	};
 
-	if !output.is_empty() {
-		state.visited.insert(item.id);
+	if output.is_empty() {
+		if let Some(previous) = previous_file {
+			state.current_file = previous;
+		}
+		return output;
 	}
+	state.visited.insert(item.id);
 
-	if !output.is_empty()
-		&& state.config.render_source_labels
-		&& !matches!(item.inner, ItemEnum::Use(_))
-		&& !(matches!(item.inner, ItemEnum::Module(_)) && state.config.plain)
-		&& let Some(span) = &item.span
-		&& state.current_file.as_ref() != Some(&span.filename)
-	{
-		state.current_file = Some(span.filename.clone());
-		let label = format!("// ripdoc:source: {}\n\n", span.filename.display());
-		output = format!("{}{}", label, output);
+	// Module examples would land after the closing brace, far from the module's docs.
+	if state.config.doc_examples && !matches!(item.inner, ItemEnum::Module(_) | ItemEnum::Use(_)) {
+		let label = ppush(path_prefix, &render_name(item));
+		output.push_str(&render_example_sections(item, &label));
 	}
 
-	output
+	match label_file {
+		Some(filename) => {
+			let label = source_label(filename, state.config.source_root.as_deref());
+			format!("{label}{output}")
+		}
+		None => output,
+	}
 }
 
 /// Determine whether an item should be rendered based on visibility settings.
 pub(crate) fn is_visible(state: &RenderState, item: &Item) -> bool {
-	state.config.render_private_items || matches!(item.visibility, Visibility::Public)
+	let private_allowed = state.config.render_private_items && !state.config.strict_public;
+	(private_allowed || matches!(item.visibility, Visibility::Public))
+		&& state.meets_min_visibility(item)
+		&& state.is_reachable(item)
+		&& (state.config.render_doc_hidden || !is_doc_hidden(item))
 }
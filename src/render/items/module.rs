@@ -3,7 +3,7 @@ use rustdoc_types::{Item, ItemEnum};
 use super::super::state::{GapController, RenderState};
 use super::super::syntax::*;
 use super::super::utils::ppush;
-use super::render_item;
+use super::{is_visible, render_item};
 
 /// Render a module and its children.
 pub fn render_module(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
@@ -16,23 +16,12 @@ pub fn render_module(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 	}
 
 	let path_prefix = ppush(path_prefix, &render_name(item));
+	if state.config.module_summary {
+		return render_module_summary(state, &path_prefix, item);
+	}
 
 	let is_plain = state.config.plain;
-	let mut output = if is_plain {
-		String::new()
-	} else {
-		let mut head = format!("{}mod {} {{\n", render_vis(item), render_name(item));
-		// Add module doc comment if present
-		if state.should_module_doc(&path_prefix, item)
-			&& let Some(docs) = &item.docs
-		{
-			for line in docs.lines() {
-				head.push_str(&format!("    //! {line}\n"));
-			}
-			head.push('\n');
-		}
-		head
-	};
+	let mut output = module_head(state, &path_prefix, item);
 
 	let module = extract_item!(item, ItemEnum::Module);
 	let gaps = GapController::new(if is_plain { "" } else { "    " });
@@ -63,3 +52,114 @@ pub fn render_module(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 
 	output
 }
+
+/// A module as its visible submodules, each annotated with a one-line inventory of its items.
+///
+/// Modules without visible submodules collapse to `pub mod name; // 2 structs, 5 fns`.
+fn render_module_summary(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
+	let module = extract_item!(item, ItemEnum::Module);
+	let mut counts = [0usize; INVENTORY.len()];
+	let mut submodules = String::new();
+	for item_id in &module.items {
+		if !state.selection_allows_child(&item.id, item_id) {
+			state.mark_skipped();
+			continue;
+		}
+		let Some(child) = state.crate_data.index.get(item_id) else {
+			state.mark_skipped();
+			continue;
+		};
+		if matches!(child.inner, ItemEnum::Module(_)) {
+			submodules.push_str(&render_item(state, path_prefix, child, false));
+		} else if is_visible(state, child)
+			&& !state.is_excluded(path_prefix, child)
+			&& let Some(slot) = inventory_slot(&child.inner)
+		{
+			counts[slot] += 1;
+		}
+	}
+
+	let inventory = INVENTORY
+		.iter()
+		.zip(counts)
+		.filter(|(_, count)| *count > 0)
+		.map(|((singular, plural), count)| {
+			format!("{count} {}", if count == 1 { singular } else { plural })
+		})
+		.collect::<Vec<_>>()
+		.join(", ");
+	let head =
+		format!("{}{}mod {}", deprecation_attr(item), state.render_vis(item), render_name(item));
+	if submodules.is_empty() {
+		return if inventory.is_empty() {
+			format!("{head};\n")
+		} else {
+			format!("{head}; // {inventory}\n")
+		};
+	}
+	let mut output = format!("{head} {{\n");
+	if !inventory.is_empty() {
+		output.push_str(&format!("    // {inventory}\n\n"));
+	}
+	output.push_str(&submodules);
+	output.push_str("}\n\n");
+	output
+}
+
+/// Inventory categories in the order they are listed, as (singular, plural) labels.
+const INVENTORY: [(&str, &str); 10] = [
+	("struct", "structs"),
+	("enum", "enums"),
+	("union", "unions"),
+	("trait", "traits"),
+	("fn", "fns"),
+	("const", "consts"),
+	("static", "statics"),
+	("type", "types"),
+	("macro", "macros"),
+	("re-export", "re-exports"),
+];
+
+/// Index into [`INVENTORY`] counting an item of this kind, if it is counted at all.
+fn inventory_slot(inner: &ItemEnum) -> Option<usize> {
+	Some(match inner {
+		ItemEnum::Struct(_) => 0,
+		ItemEnum::Enum(_) => 1,
+		ItemEnum::Union(_) => 2,
+		ItemEnum::Trait(_) | ItemEnum::TraitAlias(_) => 3,
+		ItemEnum::Function(_) => 4,
+		ItemEnum::Constant { .. } => 5,
+		ItemEnum::Static(_) => 6,
+		ItemEnum::TypeAlias(_) => 7,
+		ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => 8,
+		ItemEnum::Use(_) => 9,
+		_ => return None,
+	})
+}
+
+/// The opening `mod name {` line and inner docs of a module; empty in plain mode.
+///
+/// `path_prefix` is the module's own path.
+pub(crate) fn module_head(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
+	if state.config.plain {
+		return String::new();
+	}
+	let mut head = deprecation_attr(item);
+	if state.config.cfg_annotations {
+		head.push_str(&cfg_attrs(item));
+	}
+	head.push_str(&format!("{}mod {} {{\n", state.render_vis(item), render_name(item)));
+	// Add module doc comment if present
+	if state.should_module_doc(path_prefix, item)
+		&& let Some(docs) = item
+			.docs
+			.as_deref()
+			.and_then(|docs| state.config.doc_options.apply(docs))
+	{
+		for line in docs.lines() {
+			head.push_str(&format!("    //! {line}\n"));
+		}
+		head.push('\n');
+	}
+	head
+}
@@ -1,5 +1,6 @@
 use rustdoc_types::{Item, ItemEnum};
 
+use super::super::lifetimes::simplify_lifetimes;
 use super::super::state::RenderState;
 use super::super::syntax::*;
 use super::extracted_source_looks_like_item;
@@ -15,7 +16,7 @@ pub fn render_function_item(state: &RenderState, item: &Item, is_trait_method: b
 		return format!("{source}\n\n");
 	}
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 	let function = extract_item!(item, ItemEnum::Function);
 
 	// Handle const, async, and unsafe keywords in the correct order
@@ -30,20 +31,27 @@ pub fn render_function_item(state: &RenderState, item: &Item, is_trait_method: b
 		prefixes.push("unsafe");
 	}
 
-	output.push_str(&format!(
+	let signature = format!(
 		"{} {} fn {}{}({}){}{}",
-		render_vis(item),
+		state.render_vis(item),
 		prefixes.join(" "),
 		render_name(item),
 		render_generics(&function.generics),
 		render_function_args(&function.sig),
 		render_return_type(&function.sig),
 		render_where_clause(&function.generics)
-	));
+	);
+	if state.config.simplify_lifetimes {
+		output.push_str(&simplify_lifetimes(&signature));
+	} else {
+		output.push_str(&signature);
+	}
 
 	// Use semicolon for trait method declarations, empty body for implementations
 	if is_trait_method && !function.has_body {
 		output.push_str(";\n\n");
+	} else if let Some(lines) = state.elided_source_lines(&item.id) {
+		output.push_str(&format!(" {{ /* {lines} lines elided */ }}\n\n"));
 	} else {
 		output.push_str(" {}\n\n");
 	}
@@ -62,12 +70,12 @@ pub fn render_constant_item(state: &RenderState, item: &Item) -> String {
 		return format!("{source}\n\n");
 	}
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let (type_, const_) = extract_item!(item, ItemEnum::Constant { type_, const_ });
 	output.push_str(&format!(
 		"{}const {}: {} = {};\n\n",
-		render_vis(item),
+		state.render_vis(item),
 		render_name(item),
 		render_type(type_),
 		const_.expr
@@ -88,11 +96,11 @@ pub fn render_type_alias_item(state: &RenderState, item: &Item) -> String {
 	}
 
 	let type_alias = extract_item!(item, ItemEnum::TypeAlias);
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	output.push_str(&format!(
 		"{}type {}{}{}",
-		render_vis(item),
+		state.render_vis(item),
 		render_name(item),
 		render_generics(&type_alias.generics),
 		render_where_clause(&type_alias.generics),
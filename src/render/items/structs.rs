@@ -4,7 +4,7 @@ use super::super::impls::{render_impl, should_render_impl};
 use super::super::state::{GapController, RenderState};
 use super::super::syntax::*;
 use super::super::utils::must_get;
-use super::{SelectionView, collect_inline_traits, is_visible};
+use super::{SelectionView, derive_annotation, is_visible};
 
 /// Shared context for rendering structs with consistent generics/selection info.
 pub(crate) struct StructRenderContext<'a> {
@@ -58,7 +58,7 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 		return String::new();
 	}
 
-	let docs = docs(item);
+	let docs = state.docs(item);
 
 	let rendered_struct = if state.selection_is_full_source(&item.id)
 		&& let Some(span) = &item.span
@@ -71,15 +71,12 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 		let where_clause = render_where_clause(&struct_.generics);
 
 		// Collect inline traits first while we have immutable access
-		let inline_traits: Vec<String> = collect_inline_traits(state, &struct_.impls)
-			.into_iter()
-			.map(|s| s.to_string())
-			.collect();
+		let derives = derive_annotation(state, &struct_.impls);
 
 		let ctx = StructRenderContext::new(state, item, generics, where_clause);
 
 		let rendered = match &struct_.kind {
-			StructKind::Unit => Some(render_struct_unit(&ctx)),
+			StructKind::Unit => Some(render_struct_unit(state, &ctx)),
 			StructKind::Tuple(fields) => render_struct_tuple(state, &ctx, fields),
 			StructKind::Plain { fields, .. } => Some(render_struct_plain(state, &ctx, fields)),
 		};
@@ -87,9 +84,7 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 		rendered.map(|r| {
 			let mut output = String::new();
 			output.push_str(&docs);
-			if !inline_traits.is_empty() {
-				output.push_str(&format!("#[derive({})]\n", inline_traits.join(", ")));
-			}
+			output.push_str(&derives);
 			output.push_str(&r);
 			output
 		})
@@ -101,7 +96,7 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 	for impl_id in &struct_.impls {
 		let impl_item = must_get(state.crate_data, impl_id);
 		let impl_ = extract_item!(impl_item, ItemEnum::Impl);
-		if should_render_impl(impl_, state.config.render_auto_impls)
+		if should_render_impl(impl_, state.config.render_auto_impls, state.config.derive_style)
 			&& state.selection_allows_child(&item.id, impl_id)
 		{
 			output.push_str(&render_impl(state, path_prefix, impl_item));
@@ -111,10 +106,10 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 	output
 }
 
-fn render_struct_unit(ctx: &StructRenderContext) -> String {
+fn render_struct_unit(state: &RenderState, ctx: &StructRenderContext) -> String {
 	format!(
 		"{}struct {}{}{};\n\n",
-		render_vis(ctx.item()),
+		state.render_vis(ctx.item()),
 		render_name(ctx.item()),
 		ctx.generics(),
 		ctx.where_clause()
@@ -140,7 +135,7 @@ fn render_struct_tuple(
 				if !is_visible(state, field_item) {
 					Some("_".to_string())
 				} else {
-					Some(format!("{}{}", render_vis(field_item), render_type(ty)))
+					Some(format!("{}{}", state.render_vis(field_item), render_type(ty)))
 				}
 			}
 			None => include_placeholders.then(|| "_".to_string()),
@@ -151,7 +146,7 @@ fn render_struct_tuple(
 	if selection.expands_self() || !fields_str.is_empty() {
 		Some(format!(
 			"{}struct {}{}({}){};\n\n",
-			render_vis(ctx.item()),
+			state.render_vis(ctx.item()),
 			render_name(ctx.item()),
 			ctx.generics(),
 			fields_str,
@@ -169,7 +164,7 @@ fn render_struct_plain(
 ) -> String {
 	let mut output = format!(
 		"{}struct {}{}{} {{\n",
-		render_vis(ctx.item()),
+		state.render_vis(ctx.item()),
 		render_name(ctx.item()),
 		ctx.generics(),
 		ctx.where_clause()
@@ -219,10 +214,10 @@ pub fn render_struct_field(
 
 	let ty = extract_item!(field_item, ItemEnum::StructField);
 	let mut out = String::new();
-	out.push_str(&docs(field_item));
+	out.push_str(&state.docs(field_item));
 	out.push_str(&format!(
 		"{}{}: {},\n",
-		render_vis(field_item),
+		state.render_vis(field_item),
 		render_name(field_item),
 		render_type(ty)
 	));
@@ -1,7 +1,6 @@
 use rustdoc_types::{Id, Item, ItemEnum};
 
 use super::super::state::{GapController, RenderState};
-use super::super::syntax::*;
 use super::super::utils::escape_path;
 use super::{is_visible, render_item};
 
@@ -45,12 +44,12 @@ pub fn render_use(state: &mut RenderState, path_prefix: &str, item: &Item) -> St
 			output
 		}
 		UseResolution::Alias { source, alias } => {
-			let mut output = docs(item);
+			let mut output = state.docs(item);
 			output.push_str(&format!("pub use {source} as {alias};\n"));
 			output
 		}
 		UseResolution::Simple(source) => {
-			let mut output = docs(item);
+			let mut output = state.docs(item);
 			output.push_str(&format!("pub use {source};\n"));
 			output
 		}
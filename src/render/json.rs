@@ -0,0 +1,141 @@
+//! Structured JSON output for [`RenderFormat::Json`](super::RenderFormat::Json).
+//!
+//! Instead of skeleton text, the JSON format emits one record per rendered item with its
+//! canonical path, kind, signature, docs, and source span. Records come from the search index,
+//! so they carry the same signatures and locations that `ripdoc list` reports.
+
+use rustdoc_types::Crate;
+use serde::{Deserialize, Serialize};
+
+use super::core::Renderer;
+use super::utils::path_excluded;
+use super::visibility::{EffectiveVisibility, ModuleMap};
+use crate::core_api::search::{SearchIndex, SearchItemKind, SearchResult, SourceLocation};
+
+/// JSON document describing the items rendered from one crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonDocument {
+	/// Package name, set when the target resolved to a named package.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub package: Option<String>,
+	/// Name of the crate root module.
+	#[serde(rename = "crate")]
+	pub crate_name: String,
+	/// Crate version reported by rustdoc, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+	/// Rendered items in index order.
+	pub items: Vec<JsonItem>,
+}
+
+/// A single item in a [`JsonDocument`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonItem {
+	/// Canonical `::`-separated path, starting with the crate name.
+	pub path: String,
+	/// Item kind.
+	pub kind: SearchItemKind,
+	/// Item name as written in source.
+	pub name: String,
+	/// Rendered signature, if the item has one.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+	/// Documentation comment, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub docs: Option<String>,
+	/// Where the item is declared.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	/// Whether the item is marked `#[deprecated]`.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub deprecated: bool,
+	/// Deprecation note, if one was given.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecation_note: Option<String>,
+	/// Whether the item was matched directly by a search (as opposed to shown for context).
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub matched: bool,
+}
+
+impl Renderer {
+	/// Build the JSON document for `crate_data`, honouring the filter, selection, privacy, and
+	/// minimum-visibility settings of this renderer.
+	pub fn render_document(&self, crate_data: &Crate) -> JsonDocument {
+		let index = SearchIndex::build(
+			crate_data,
+			self.render_private_items,
+			self.source_root.as_deref(),
+		);
+		let modules = self.min_visibility.map(|_| ModuleMap::build(crate_data));
+		let filter: Vec<&str> = self
+			.filter
+			.split("::")
+			.filter(|segment| !segment.is_empty())
+			.collect();
+
+		let items = index
+			.entries()
+			.iter()
+			.filter(|entry| self.in_selection(entry) && in_filter(entry, &filter))
+			.filter(|entry| !path_excluded(&entry.path_string, &self.exclude))
+			.filter(|entry| self.render_doc_hidden || !entry.doc_hidden)
+			.filter(|entry| {
+				let (Some(min), Some(modules)) = (self.min_visibility, &modules) else {
+					return true;
+				};
+				crate_data.index.get(&entry.item_id).is_none_or(|item| {
+					EffectiveVisibility::of(item, modules)
+						.level()
+						.is_none_or(|level| level >= min)
+				})
+			})
+			.map(|entry| JsonItem {
+				path: entry.path_string.clone(),
+				kind: entry.kind,
+				name: entry.raw_name.clone(),
+				signature: entry.signature.clone(),
+				docs: entry.docs.as_deref().and_then(|docs| self.doc_options.apply(docs)),
+				source: entry.source.clone(),
+				deprecated: entry.deprecated,
+				deprecation_note: entry.deprecation_note.clone(),
+				matched: self
+					.selection
+					.as_ref()
+					.is_some_and(|selection| selection.matches().contains(&entry.item_id)),
+			})
+			.collect();
+
+		JsonDocument {
+			package: None,
+			crate_name: crate_data
+				.index
+				.get(&crate_data.root)
+				.and_then(|root| root.name.clone())
+				.unwrap_or_default(),
+			version: crate_data.crate_version.clone(),
+			items,
+		}
+	}
+
+	/// Whether the selection (if any) keeps `entry`, directly or through an expanded ancestor.
+	fn in_selection(&self, entry: &SearchResult) -> bool {
+		let Some(selection) = &self.selection else {
+			return true;
+		};
+		selection.matches().contains(&entry.item_id)
+			|| selection.context().contains(&entry.item_id)
+			|| entry
+				.ancestors
+				.iter()
+				.any(|ancestor| selection.expanded().contains(ancestor))
+	}
+}
+
+/// Whether `entry` lies under the filter path (given relative to the crate root).
+fn in_filter(entry: &SearchResult, filter: &[&str]) -> bool {
+	let below_root = entry.path.iter().skip(1).map(|segment| segment.name.as_str());
+	filter
+		.iter()
+		.zip(below_root.chain(std::iter::repeat("")))
+		.all(|(expected, actual)| *expected == actual)
+}
@@ -0,0 +1,496 @@
+//! Signature post-processing that simplifies lifetimes for readability.
+//!
+//! The pass operates on rendered function signatures. It removes named lifetime parameters that
+//! Rust's elision rules would infer anyway, and drops `for<'a>` binders on `Fn`-family bounds whose
+//! lifetimes can be elided inside the sugar. Anything it cannot prove elidable is left untouched.
+
+/// Trait names that accept parenthesized sugar and follow function elision rules.
+const FN_SUGAR_TRAITS: &[&str] = &["FnOnce", "FnMut", "Fn"];
+
+/// Simplify lifetimes in a rendered function signature.
+pub fn simplify_lifetimes(signature: &str) -> String {
+	let elided = elide_fn_lifetimes(signature);
+	collapse_hrtb(&elided)
+}
+
+fn is_ident_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_'
+}
+
+/// Byte offsets of `lifetime` (e.g. `'a`) occurrences that are not part of a longer identifier.
+fn lifetime_positions(text: &str, lifetime: &str) -> Vec<usize> {
+	text.match_indices(lifetime)
+		.filter(|(idx, _)| {
+			!text[idx + lifetime.len()..]
+				.chars()
+				.next()
+				.is_some_and(is_ident_char)
+		})
+		.map(|(idx, _)| idx)
+		.collect()
+}
+
+fn count_lifetime(text: &str, lifetime: &str) -> usize {
+	lifetime_positions(text, lifetime).len()
+}
+
+/// Replace a named lifetime with its elided form: `&'a T` becomes `&T`, `Foo<'a>` becomes `Foo<'_>`.
+fn replace_lifetime(text: &str, lifetime: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	let mut last = 0;
+	for idx in lifetime_positions(text, lifetime) {
+		out.push_str(&text[last..idx]);
+		let end = idx + lifetime.len();
+		if out.ends_with('&') {
+			last = if text[end..].starts_with(' ') {
+				end + 1
+			} else {
+				end
+			};
+		} else {
+			out.push_str("'_");
+			last = end;
+		}
+	}
+	out.push_str(&text[last..]);
+	out
+}
+
+/// Find the index of the delimiter closing the one at `open_idx`, ignoring `->` arrows.
+fn matching_close(text: &str, open_idx: usize) -> Option<usize> {
+	let mut depth = 0usize;
+	let mut prev = '\0';
+	for (idx, ch) in text[open_idx..].char_indices() {
+		match ch {
+			'<' | '(' | '[' => depth += 1,
+			'>' if prev == '-' => {}
+			'>' | ')' | ']' => {
+				depth = depth.checked_sub(1)?;
+				if depth == 0 {
+					return Some(open_idx + idx);
+				}
+			}
+			_ => {}
+		}
+		prev = ch;
+	}
+	None
+}
+
+/// Split a parameter list on commas that are not nested inside brackets.
+fn split_top_level(text: &str) -> Vec<&str> {
+	let mut parts = Vec::new();
+	let mut depth = 0usize;
+	let mut start = 0;
+	let mut prev = '\0';
+	for (idx, ch) in text.char_indices() {
+		match ch {
+			'<' | '(' | '[' => depth += 1,
+			'>' if prev == '-' => {}
+			'>' | ')' | ']' => depth = depth.saturating_sub(1),
+			',' if depth == 0 => {
+				parts.push(text[start..idx].trim());
+				start = idx + 1;
+			}
+			_ => {}
+		}
+		prev = ch;
+	}
+	let tail = text[start..].trim();
+	if !tail.is_empty() {
+		parts.push(tail);
+	}
+	parts
+}
+
+/// Whether `param` is a bare lifetime parameter that is a candidate for elision.
+fn is_plain_lifetime_param(param: &str) -> bool {
+	param.starts_with('\'') && param != "'static" && param != "'_" && !param.contains(':')
+}
+
+/// Number of lifetime positions (explicit or elided) in a list of inputs.
+fn input_lifetime_slots(inputs: &str) -> usize {
+	let mut slots = 0;
+	let mut chars = inputs.chars().peekable();
+	while let Some(ch) = chars.next() {
+		match ch {
+			'&' => {
+				slots += 1;
+				// `&'a T` is a single slot.
+				if chars.peek() == Some(&'\'') {
+					chars.next();
+					while chars.peek().is_some_and(|c| is_ident_char(*c)) {
+						chars.next();
+					}
+				}
+			}
+			'\'' => {
+				slots += 1;
+				while chars.peek().is_some_and(|c| is_ident_char(*c)) {
+					chars.next();
+				}
+			}
+			_ => {}
+		}
+	}
+	slots
+}
+
+/// Returns the lifetime borrowed by a `&'a self` / `&'a mut self` receiver, if any.
+fn self_receiver_lifetime(inputs: &str) -> Option<&str> {
+	let first = split_top_level(inputs).into_iter().next()?;
+	let rest = first.strip_prefix("&'")?;
+	let (name, tail) = rest.split_once(' ')?;
+	let tail = tail.trim_start();
+	(tail == "self" || tail == "mut self").then(|| &first[1..name.len() + 2])
+}
+
+fn has_self_reference(inputs: &str) -> bool {
+	split_top_level(inputs)
+		.first()
+		.is_some_and(|first| first.starts_with('&') && first.ends_with("self"))
+}
+
+/// Whether the output uses lifetimes that are themselves resolved through elision.
+fn output_has_elided_lifetimes(output: &str) -> bool {
+	output.contains("'_")
+		|| output
+			.match_indices('&')
+			.any(|(idx, _)| !output[idx + 1..].starts_with('\''))
+}
+
+/// Decide whether a lifetime can be dropped given where it appears in a signature.
+fn lifetime_is_elidable(lifetime: &str, inputs: &str, output: &str, allow_self: bool) -> bool {
+	let in_count = count_lifetime(inputs, lifetime);
+	let out_count = count_lifetime(output, lifetime);
+	if in_count > 1 {
+		return false;
+	}
+	if out_count == 0 {
+		return in_count == 1
+			&& (!output_has_elided_lifetimes(output) || (allow_self && has_self_reference(inputs)));
+	}
+	if in_count == 0 {
+		return false;
+	}
+	(allow_self && self_receiver_lifetime(inputs) == Some(lifetime))
+		|| input_lifetime_slots(inputs) == 1
+}
+
+/// End offset of a type that starts at `start`, stopping at a top-level `,` or unmatched closer.
+///
+/// Unlike [`bound_end`], `+` does not end it, so `dyn A + 'a` is covered as a whole.
+fn type_end(text: &str, start: usize) -> usize {
+	let mut depth = 0usize;
+	let mut prev = '\0';
+	for (idx, ch) in text[start..].char_indices() {
+		match ch {
+			'<' | '(' | '[' => depth += 1,
+			'>' if prev == '-' => {}
+			'>' | ')' | ']' => {
+				if depth == 0 {
+					return start + idx;
+				}
+				depth -= 1;
+			}
+			',' | ';' | '{' if depth == 0 => return start + idx,
+			_ => {}
+		}
+		prev = ch;
+	}
+	text.len()
+}
+
+/// Whether `lifetime` appears inside a `dyn`/`impl` trait type, `Fn`-family sugar, or a `fn`
+/// pointer.
+///
+/// Elision means something else there: `impl Fn(&'a str)` names a caller-chosen lifetime, while
+/// `impl Fn(&str)` accepts any lifetime.
+fn appears_in_trait_or_fn_type(text: &str, lifetime: &str) -> bool {
+	let ranges: Vec<(usize, usize)> = ["dyn ", "impl ", "fn(", "FnOnce(", "FnMut(", "Fn("]
+		.iter()
+		.flat_map(|keyword| text.match_indices(*keyword))
+		.filter(|(idx, _)| !text[..*idx].chars().next_back().is_some_and(is_ident_char))
+		.map(|(idx, keyword)| (idx, type_end(text, idx + keyword.len())))
+		.collect();
+	lifetime_positions(text, lifetime)
+		.into_iter()
+		.any(|pos| ranges.iter().any(|(start, end)| (*start..*end).contains(&pos)))
+}
+
+/// Locate the start of the `fn` keyword in a rendered signature.
+fn find_fn_keyword(signature: &str) -> Option<usize> {
+	signature.match_indices("fn ").map(|(idx, _)| idx).find(|&idx| {
+		signature[..idx]
+			.chars()
+			.next_back()
+			.is_none_or(|c| c.is_whitespace())
+	})
+}
+
+/// Remove lifetime parameters from a function signature that elision rules make redundant.
+fn elide_fn_lifetimes(signature: &str) -> String {
+	let Some(fn_idx) = find_fn_keyword(signature) else {
+		return signature.to_string();
+	};
+	let name_start = fn_idx + 3;
+	let name_end = signature[name_start..]
+		.find(|c: char| !(is_ident_char(c) || c == '#'))
+		.map(|offset| name_start + offset)
+		.unwrap_or(signature.len());
+	if !signature[name_end..].starts_with('<') {
+		return signature.to_string();
+	}
+	let Some(generics_close) = matching_close(signature, name_end) else {
+		return signature.to_string();
+	};
+	let args_open = generics_close + 1;
+	if !signature[args_open..].starts_with('(') {
+		return signature.to_string();
+	}
+	let Some(args_close) = matching_close(signature, args_open) else {
+		return signature.to_string();
+	};
+
+	let params = split_top_level(&signature[name_end + 1..generics_close]);
+	let inputs = &signature[args_open + 1..args_close];
+	let rest = &signature[args_close + 1..];
+	let (output, where_clause) = match rest.find(" where ") {
+		Some(idx) => (&rest[..idx], &rest[idx..]),
+		None => (rest, ""),
+	};
+
+	let mut kept = Vec::new();
+	let mut elided = Vec::new();
+	for param in &params {
+		let referenced_elsewhere = count_lifetime(where_clause, param) > 0
+			|| params
+				.iter()
+				.any(|other| other != param && count_lifetime(other, param) > 0);
+		if is_plain_lifetime_param(param)
+			&& !referenced_elsewhere
+			&& !appears_in_trait_or_fn_type(inputs, param)
+			&& !appears_in_trait_or_fn_type(output, param)
+			&& lifetime_is_elidable(param, inputs, output, true)
+		{
+			elided.push(*param);
+		} else {
+			kept.push(*param);
+		}
+	}
+	if elided.is_empty() {
+		return signature.to_string();
+	}
+
+	let mut inputs = inputs.to_string();
+	let mut output = output.to_string();
+	for lifetime in &elided {
+		inputs = replace_lifetime(&inputs, lifetime);
+		output = replace_lifetime(&output, lifetime);
+	}
+	let generics = if kept.is_empty() {
+		String::new()
+	} else {
+		format!("<{}>", kept.join(", "))
+	};
+	format!(
+		"{}{generics}({inputs}){output}{where_clause}",
+		&signature[..name_end]
+	)
+}
+
+/// End offset of a bound that starts at `start`, stopping at a top-level separator.
+fn bound_end(text: &str, start: usize) -> usize {
+	let mut depth = 0usize;
+	let mut prev = '\0';
+	for (idx, ch) in text[start..].char_indices() {
+		match ch {
+			'<' | '(' | '[' => depth += 1,
+			'>' if prev == '-' => {}
+			'>' | ')' | ']' => {
+				if depth == 0 {
+					return start + idx;
+				}
+				depth -= 1;
+			}
+			',' | '+' | ';' | '{' if depth == 0 => return start + idx,
+			_ => {}
+		}
+		prev = ch;
+	}
+	text.len()
+}
+
+/// Locate `Fn(..) -> ..` sugar inside a bound, returning `(inputs, output)` ranges.
+fn fn_sugar_ranges(bound: &str) -> Option<((usize, usize), (usize, usize))> {
+	let (open, _) = FN_SUGAR_TRAITS
+		.iter()
+		.filter_map(|name| {
+			bound.match_indices(&format!("{name}(")).find(|(idx, _)| {
+				!bound[..*idx]
+					.chars()
+					.next_back()
+					.is_some_and(is_ident_char)
+			})
+		})
+		.map(|(idx, matched)| (idx + matched.len() - 1, idx))
+		.min_by_key(|(_, idx)| *idx)?;
+	let close = matching_close(bound, open)?;
+	let after = &bound[close + 1..];
+	let output = match after.trim_start().strip_prefix("->") {
+		Some(_) => (close + 1, bound.len()),
+		None => (close + 1, close + 1),
+	};
+	Some(((open + 1, close), output))
+}
+
+/// Drop `for<'a>` binders whose lifetimes can be elided inside `Fn`-family sugar.
+fn collapse_hrtb(text: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	let mut cursor = 0;
+	while let Some(offset) = text[cursor..].find("for<") {
+		let binder_start = cursor + offset;
+		let at_boundary = !text[..binder_start]
+			.chars()
+			.next_back()
+			.is_some_and(is_ident_char);
+		let Some(binder_close) = matching_close(text, binder_start + 3).filter(|_| at_boundary)
+		else {
+			out.push_str(&text[cursor..binder_start + 4]);
+			cursor = binder_start + 4;
+			continue;
+		};
+		let params = split_top_level(&text[binder_start + 4..binder_close]);
+		let body_start = text[binder_close + 1..]
+			.find(|c: char| !c.is_whitespace())
+			.map(|idx| binder_close + 1 + idx)
+			.unwrap_or(text.len());
+		let body_end = bound_end(text, body_start);
+		let body = &text[body_start..body_end];
+
+		out.push_str(&text[cursor..binder_start]);
+		cursor = body_end;
+
+		let Some(((in_start, in_end), (out_start, out_end))) = fn_sugar_ranges(body) else {
+			out.push_str(&text[binder_start..body_end]);
+			continue;
+		};
+		let inputs = &body[in_start..in_end];
+		let output = &body[out_start..out_end];
+
+		let mut kept = Vec::new();
+		let mut elided = Vec::new();
+		for param in &params {
+			let inside = count_lifetime(inputs, param) + count_lifetime(output, param);
+			let outside = count_lifetime(body, param) - inside;
+			if param.starts_with('\'')
+				&& !param.contains(':')
+				&& outside == 0
+				&& (inside == 0 || lifetime_is_elidable(param, inputs, output, false))
+			{
+				elided.push(*param);
+			} else {
+				kept.push(*param);
+			}
+		}
+
+		let mut body = body.to_string();
+		for lifetime in &elided {
+			body = replace_lifetime(&body, lifetime);
+		}
+		if !kept.is_empty() {
+			out.push_str(&format!("for<{}> ", kept.join(", ")));
+		}
+		out.push_str(&body);
+	}
+	out.push_str(&text[cursor..]);
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn elides_single_input_lifetime() {
+		assert_eq!(
+			simplify_lifetimes("pub fn len<'a>(value: &'a str) -> usize"),
+			"pub fn len(value: &str) -> usize"
+		);
+	}
+
+	#[test]
+	fn elides_lifetime_returned_from_only_input() {
+		assert_eq!(
+			simplify_lifetimes("pub fn first<'a, T>(items: &'a [T]) -> &'a T"),
+			"pub fn first<T>(items: &[T]) -> &T"
+		);
+	}
+
+	#[test]
+	fn elides_self_receiver_lifetime() {
+		assert_eq!(
+			simplify_lifetimes("pub fn name<'a>(&'a self, key: &str) -> Ref<'a, str>"),
+			"pub fn name(&self, key: &str) -> Ref<'_, str>"
+		);
+	}
+
+	#[test]
+	fn keeps_lifetime_tied_to_multiple_inputs() {
+		let sig = "pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str";
+		assert_eq!(simplify_lifetimes(sig), sig);
+	}
+
+	#[test]
+	fn keeps_lifetime_with_ambiguous_output() {
+		let sig = "pub fn pick<'a>(x: &'a str, y: &str) -> &'a str";
+		assert_eq!(simplify_lifetimes(sig), sig);
+	}
+
+	#[test]
+	fn keeps_bounded_and_where_lifetimes() {
+		let sig = "pub fn bounded<'a, 'b: 'a>(x: &'a str, y: &'b str)";
+		assert_eq!(simplify_lifetimes(sig), sig);
+		let sig = "pub fn wrapped<'a, T>(x: &'a T) where T: 'a";
+		assert_eq!(simplify_lifetimes(sig), sig);
+	}
+
+	#[test]
+	fn keeps_lifetimes_inside_fn_and_trait_object_arguments() {
+		for sig in [
+			"pub fn call<'a>(f: impl Fn(&'a str))",
+			"pub fn boxed<'a>(f: Box<dyn Fn(&'a str) -> &'a str>)",
+			"pub fn each<'a>(items: impl Iterator<Item = &'a str>)",
+			"pub fn erased<'a>(value: Box<dyn Any + 'a>)",
+			"pub fn pointer<'a>(f: fn(&'a str))",
+		] {
+			assert_eq!(simplify_lifetimes(sig), sig);
+		}
+	}
+
+	#[test]
+	fn collapses_hrtb_on_fn_bounds() {
+		assert_eq!(
+			simplify_lifetimes("pub fn apply<F>(f: F) where F: for<'a> Fn(&'a str) -> &'a str"),
+			"pub fn apply<F>(f: F) where F: Fn(&str) -> &str"
+		);
+		assert_eq!(
+			simplify_lifetimes("pub fn visit(f: impl for<'a> FnMut(&'a mut Node) + Send)"),
+			"pub fn visit(f: impl FnMut(&mut Node) + Send)"
+		);
+	}
+
+	#[test]
+	fn keeps_hrtb_on_non_fn_bounds() {
+		let sig = "pub fn load<T>(input: &str) -> T where T: for<'de> Deserialize<'de>";
+		assert_eq!(simplify_lifetimes(sig), sig);
+	}
+
+	#[test]
+	fn leaves_static_and_mut_references() {
+		assert_eq!(
+			simplify_lifetimes("pub fn push<'a>(buf: &'a mut Vec<u8>, tag: &'static str)"),
+			"pub fn push(buf: &mut Vec<u8>, tag: &'static str)"
+		);
+	}
+}
@@ -19,7 +19,7 @@ pub fn render_macro(state: &crate::render::state::RenderState, item: &Item) -> S
 	}
 	use super::syntax::is_reserved_word;
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let macro_def = extract_item!(item, ItemEnum::Macro);
 	// Add #[macro_export] for public macros
@@ -83,7 +83,7 @@ pub fn render_proc_macro(state: &crate::render::state::RenderState, item: &Item)
 	{
 		return format!("{source}\n\n");
 	}
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let fn_name = render_name(item);
 
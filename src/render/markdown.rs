@@ -1,13 +1,75 @@
 use std::iter::Peekable;
 
+use super::examples::EXAMPLE_MARKER;
+
+/// Language tag written on Rust code fences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FenceLanguage {
+	/// Tag fences as ```` ```rust ````.
+	#[default]
+	Rust,
+	/// Tag fences as ```` ```rs ````.
+	Rs,
+	/// Emit bare ```` ``` ```` fences without a language tag.
+	None,
+}
+
+impl FenceLanguage {
+	/// The tag appended to an opening fence.
+	pub fn tag(self) -> &'static str {
+		match self {
+			Self::Rust => "rust",
+			Self::Rs => "rs",
+			Self::None => "",
+		}
+	}
+}
+
+/// Options controlling how code blocks are emitted in Markdown output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownOptions {
+	/// Language tag used for Rust code fences.
+	pub fence_language: FenceLanguage,
+	/// Maximum number of lines in a single code block before it is split, if any.
+	pub max_block_lines: Option<usize>,
+	/// Emit source labels as `### Source:` headings rather than `<!-- ripdoc:source: -->`
+	/// comments.
+	pub source_headings: bool,
+}
+
+impl Default for MarkdownOptions {
+	fn default() -> Self {
+		Self {
+			fence_language: FenceLanguage::default(),
+			max_block_lines: None,
+			source_headings: true,
+		}
+	}
+}
+
+/// Marker closing a code block that continues in the next fence.
+const CONTINUED_MARKER: &str = "// ... (continued)";
+/// Marker opening a code block that continues the previous fence.
+const CONTINUATION_MARKER: &str = "// (continued) ...";
+
 /// Render formatted Rust source into Markdown by stripping the outer module and
 /// converting doc comments + code fences into Markdown-friendly output.
 pub fn render_markdown(source: &str) -> String {
+	render_markdown_with(source, &MarkdownOptions::default())
+}
+
+/// Render formatted Rust source into Markdown using custom code-block options.
+pub fn render_markdown_with(source: &str, options: &MarkdownOptions) -> String {
 	let without_outer = strip_outer_module(source);
-	rust_to_markdown(&without_outer)
+	rust_to_markdown(&without_outer, options)
 }
 
-fn rust_to_markdown(source: &str) -> String {
+/// Render a fragment of formatted Rust items (no outer module wrapper) into Markdown.
+pub(crate) fn render_markdown_fragment(source: &str, options: &MarkdownOptions) -> String {
+	rust_to_markdown(source, options)
+}
+
+fn rust_to_markdown(source: &str, options: &MarkdownOptions) -> String {
 	let base_indent = min_leading_indent(source);
 	let mut markdown = String::new();
 	let mut in_code_block = false;
@@ -19,9 +81,40 @@ fn rust_to_markdown(source: &str) -> String {
 		let trimmed = line.trim_start();
 
 		if let Some(filename) = trimmed.strip_prefix("// ripdoc:source: ") {
-			flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code);
+			flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code, options);
 			in_code_block = false;
-			markdown.push_str(&format!("### Source: {}\n\n", filename));
+			if options.source_headings {
+				markdown.push_str(&format!("### Source: {filename}\n\n"));
+			} else {
+				markdown.push_str(&format!("<!-- ripdoc:source: {filename} -->\n\n"));
+			}
+			continue;
+		}
+
+		if let Some(label) = trimmed.strip_prefix(EXAMPLE_MARKER) {
+			flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code, options);
+			in_code_block = false;
+			let mut example = Vec::new();
+			while let Some(next) = lines.peek().map(|next| next.trim_start()) {
+				let Some(code) = next.strip_prefix("//") else {
+					break;
+				};
+				if is_doc_comment(next) || next.starts_with("// ripdoc:") {
+					break;
+				}
+				example.push(code.strip_prefix(' ').unwrap_or(code).to_string());
+				lines.next();
+			}
+			ensure_block_gap(&mut markdown);
+			markdown.push_str(&format!("**Example** (`{label}`):\n\n"));
+			markdown.push_str("```");
+			markdown.push_str(options.fence_language.tag());
+			markdown.push('\n');
+			for line in example {
+				markdown.push_str(&line);
+				markdown.push('\n');
+			}
+			markdown.push_str("```\n\n");
 			continue;
 		}
 
@@ -42,9 +135,9 @@ fn rust_to_markdown(source: &str) -> String {
 					code_buffer.push(format!("{indent}// {text}"));
 				}
 			} else {
-				flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code);
+				flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code, options);
 				in_code_block = false;
-				let doc_contains_text = render_doc_block(&doc_block, &mut markdown);
+				let doc_contains_text = render_doc_block(&doc_block, &mut markdown, options);
 				need_gap_before_code = doc_contains_text;
 			}
 			continue;
@@ -66,7 +159,7 @@ fn rust_to_markdown(source: &str) -> String {
 		code_buffer.push(line.to_string());
 	}
 
-	flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code);
+	flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code, options);
 
 	let normalized = normalize_spacing(&markdown);
 	normalized.trim().to_string()
@@ -241,7 +334,11 @@ fn ensure_block_gap(markdown: &mut String) {
 	}
 }
 
-fn render_doc_block(doc_block: &[(String, String)], markdown: &mut String) -> bool {
+fn render_doc_block(
+	doc_block: &[(String, String)],
+	markdown: &mut String,
+	options: &MarkdownOptions,
+) -> bool {
 	let mut fence_open = false;
 	let mut contains_text = false;
 	let mut paragraph = String::new();
@@ -258,7 +355,11 @@ fn render_doc_block(doc_block: &[(String, String)], markdown: &mut String) -> bo
 					markdown.push_str("```\n\n");
 				} else {
 					markdown.push_str("```");
-					markdown.push_str(mapped);
+					markdown.push_str(if mapped == "rust" {
+						options.fence_language.tag()
+					} else {
+						mapped
+					});
 					markdown.push('\n');
 				}
 			} else {
@@ -316,6 +417,7 @@ fn flush_code_block(
 	markdown: &mut String,
 	code_buffer: &mut Vec<String>,
 	need_gap_before_code: &mut bool,
+	options: &MarkdownOptions,
 ) {
 	if code_buffer.is_empty() || code_buffer.iter().all(|line| line.trim().is_empty()) {
 		code_buffer.clear();
@@ -329,13 +431,69 @@ fn flush_code_block(
 		markdown.push('\n');
 	}
 
-	markdown.push_str("```rust\n");
-	markdown.push_str(&dedent_lines(code_buffer));
-	markdown.push_str("```\n\n");
+	let body = dedent_lines(code_buffer);
+	let lines: Vec<&str> = body.lines().collect();
+	let chunks = split_code_lines(&lines, options.max_block_lines);
+	let last = chunks.len().saturating_sub(1);
+	for (idx, chunk) in chunks.iter().enumerate() {
+		markdown.push_str("```");
+		markdown.push_str(options.fence_language.tag());
+		markdown.push('\n');
+		if idx > 0 {
+			markdown.push_str(CONTINUATION_MARKER);
+			markdown.push('\n');
+		}
+		for line in chunk {
+			markdown.push_str(line);
+			markdown.push('\n');
+		}
+		if idx < last {
+			markdown.push_str(CONTINUED_MARKER);
+			markdown.push('\n');
+		}
+		markdown.push_str("```\n\n");
+	}
 	code_buffer.clear();
 	*need_gap_before_code = false;
 }
 
+/// Split code lines into chunks of at most `max_lines`, preferring to break at blank lines.
+fn split_code_lines<'a>(lines: &[&'a str], max_lines: Option<usize>) -> Vec<Vec<&'a str>> {
+	let Some(max_lines) = max_lines.filter(|max| *max > 0) else {
+		return vec![lines.to_vec()];
+	};
+
+	let mut chunks = Vec::new();
+	let mut start = 0;
+	while lines.len() - start > max_lines {
+		let window_end = start + max_lines;
+		// Break after the last blank line in the second half of the window, if there is one.
+		let split_at = (start + max_lines / 2..window_end)
+			.rev()
+			.find(|&idx| lines[idx].trim().is_empty())
+			.map(|idx| idx + 1)
+			.unwrap_or(window_end);
+		let chunk: Vec<&str> = lines[start..split_at].to_vec();
+		chunks.push(trim_blank_edges(chunk));
+		start = split_at;
+	}
+	chunks.push(trim_blank_edges(lines[start..].to_vec()));
+	chunks.retain(|chunk| !chunk.is_empty());
+	if chunks.is_empty() {
+		chunks.push(Vec::new());
+	}
+	chunks
+}
+
+fn trim_blank_edges(mut chunk: Vec<&str>) -> Vec<&str> {
+	while chunk.last().is_some_and(|line| line.trim().is_empty()) {
+		chunk.pop();
+	}
+	let leading = chunk.iter().take_while(|line| line.trim().is_empty()).count();
+	chunk.drain(..leading);
+	chunk
+}
+
 fn dedent_lines(lines: &[String]) -> String {
 	let min_indent = lines
 		.iter()
@@ -476,7 +634,7 @@ pub struct Foo {
 }
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected.trim());
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected.trim());
 	}
 
 	#[test]
@@ -495,7 +653,7 @@ multiple paragraphs
 pub struct Foo;
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected.trim());
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected.trim());
 	}
 
 	#[test]
@@ -518,7 +676,7 @@ let markdown = "**very** _important".into();
 pub fn set_input(&mut self) {}
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected.trim());
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected.trim());
 	}
 
 	#[test]
@@ -533,7 +691,7 @@ pub fn alpha() {}
 pub fn beta() {}
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected);
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected);
 	}
 
 	#[test]
@@ -554,7 +712,7 @@ pub trait T {
 }
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected);
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected);
 	}
 
 	#[test]
@@ -579,7 +737,7 @@ pub fn bold(&self) -> bool {}
 pub fn dim(&self) -> bool {}
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected);
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected);
 	}
 
 	#[test]
@@ -613,7 +771,7 @@ let value = helper();
 pub fn demo() {}
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected);
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected);
 	}
 
 	#[test]
@@ -637,7 +795,7 @@ fn main() {
 pub fn demo() {}
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected);
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected);
 	}
 
 	#[test]
@@ -662,6 +820,63 @@ Notes follow.
 pub struct Cart;
 ```"#;
 
-		assert_eq!(rust_to_markdown(source), expected.trim());
+		assert_eq!(rust_to_markdown(source, &MarkdownOptions::default()), expected.trim());
+	}
+
+	#[test]
+	fn uses_configured_fence_language() {
+		let source = "/// Demo.\n///\n/// ```\n/// demo();\n/// ```\npub fn demo() {}\n";
+		let options = MarkdownOptions {
+			fence_language: FenceLanguage::Rs,
+			..MarkdownOptions::default()
+		};
+		let rendered = rust_to_markdown(source, &options);
+		assert!(rendered.contains("```rs\ndemo();"));
+		assert!(rendered.contains("```rs\npub fn demo() {}"));
+		assert!(!rendered.contains("```rust"));
+
+		let options = MarkdownOptions {
+			fence_language: FenceLanguage::None,
+			..MarkdownOptions::default()
+		};
+		let rendered = rust_to_markdown("pub struct Cart;\n", &options);
+		assert_eq!(rendered, "```\npub struct Cart;\n```");
+	}
+
+	#[test]
+	fn splits_long_code_blocks_with_continuation_markers() {
+		let source = "pub fn a() {}\npub fn b() {}\n\npub fn c() {}\npub fn d() {}\n";
+		let options = MarkdownOptions {
+			max_block_lines: Some(3),
+			..MarkdownOptions::default()
+		};
+		let expected = r#"```rust
+pub fn a() {}
+pub fn b() {}
+// ... (continued)
+```
+
+```rust
+// (continued) ...
+pub fn c() {}
+pub fn d() {}
+```"#;
+		assert_eq!(rust_to_markdown(source, &options), expected);
+	}
+
+	#[test]
+	fn source_labels_become_headings_or_comments() {
+		let source = "// ripdoc:source: src/cart.rs\n\npub struct Cart;\n";
+		let rendered = rust_to_markdown(source, &MarkdownOptions::default());
+		assert!(rendered.starts_with("### Source: src/cart.rs\n"));
+		assert!(rendered.contains("pub struct Cart;"));
+
+		let options = MarkdownOptions {
+			source_headings: false,
+			..MarkdownOptions::default()
+		};
+		let rendered = rust_to_markdown(source, &options);
+		assert!(rendered.starts_with("<!-- ripdoc:source: src/cart.rs -->\n"));
+		assert!(!rendered.contains("### Source:"));
 	}
 }
@@ -24,12 +24,22 @@ pub mod syntax;
 
 /// Main renderer configuration and public API.
 pub mod core;
+/// How much of each doc comment is rendered.
+pub mod docs;
 /// Domain-specific errors for the renderer.
 pub mod error;
+/// Doc-comment code examples rendered as labelled sections.
+pub mod examples;
+/// Standalone HTML page output.
+pub mod html;
 /// Trait and impl rendering logic.
 pub mod impls;
 /// Item-specific rendering functions.
 pub mod items;
+/// Structured JSON output format.
+pub mod json;
+/// Lifetime elision and HRTB simplification for rendered signatures.
+pub mod lifetimes;
 /// Procedural and declarative macro rendering.
 pub mod macros;
 /// Markdown conversion helpers.
@@ -40,10 +50,21 @@ pub mod signatures;
 pub mod state;
 /// Utility functions for rendering.
 pub mod utils;
+/// Effective visibility annotations and minimum-visibility filtering.
+pub mod visibility;
 
 // Re-export public API
 pub use core::{RenderFormat, RenderSelection, Renderer};
 
+pub use docs::{DocMode, DocOptions};
+
+pub use impls::DeriveStyle;
+
+pub use json::{JsonDocument, JsonItem};
+
+pub use markdown::{FenceLanguage, MarkdownOptions};
+pub use visibility::VisibilityLevel;
+
 pub use syntax::{
 	is_reserved_word, render_function_args, render_generic_bounds, render_generics, render_name,
 	render_path, render_return_type, render_type, render_type_inner, render_vis,
@@ -43,7 +43,11 @@ pub fn function_signature(item: &Item) -> String {
 	signature.push('(');
 	signature.push_str(&render_function_args(&function.sig));
 	signature.push(')');
-	signature.push_str(&render_return_type(&function.sig));
+	let return_type = render_return_type(&function.sig);
+	if !return_type.is_empty() {
+		signature.push(' ');
+		signature.push_str(&return_type);
+	}
 	signature.push_str(&render_where_clause(&function.generics));
 	signature
 }
@@ -261,7 +265,7 @@ pub fn field_signature(item: &Item) -> String {
 	signature
 }
 
-/// Render an enum variant signature (including fields if present).
+/// Render an enum variant signature (including fields and discriminant if present).
 pub fn variant_signature(
 	item: &Item,
 	variant: &Variant,
@@ -286,5 +290,9 @@ pub fn variant_signature(
 			signature.push_str(" }");
 		}
 	}
+	if let Some(discriminant) = &variant.discriminant {
+		signature.push_str(" = ");
+		signature.push_str(&discriminant.expr);
+	}
 	signature
 }
@@ -1,10 +1,14 @@
 use std::collections::HashSet;
 
-use rustdoc_types::{Crate, Id, Item};
+use rustdoc_types::{Crate, Id, Item, ItemEnum};
 
 use super::core::{RenderSelection, Renderer};
 use super::error::{Result, RipdocError};
-use super::utils::{FilterMatch, GAP_MARKER, ends_with_gap, must_get, ppush, starts_with_gap};
+use super::syntax::{cfg_attrs, docs, render_name};
+use super::utils::{
+	FilterMatch, GAP_MARKER, ends_with_gap, must_get, path_excluded, ppush, starts_with_gap,
+};
+use super::visibility::{EffectiveVisibility, ModuleMap, Reachability};
 
 /// Tracks whether a gap marker should be inserted before the next rendered item.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -61,6 +65,10 @@ pub struct RenderState<'a, 'b> {
 	pub visited: VisitedSet,
 	/// Tracks the current source file being rendered to detect transitions.
 	pub current_file: Option<std::path::PathBuf>,
+	/// Enclosing module of each item, built on first use by visibility queries.
+	modules: once_cell::unsync::OnceCell<ModuleMap>,
+	/// Items reachable from the crate root, built on first use in strict-public mode.
+	reachability: once_cell::unsync::OnceCell<Reachability>,
 }
 
 /// Tracks items already rendered to prevent infinite recursion or redundancy across multiple
@@ -105,6 +113,8 @@ impl<'a, 'b> RenderState<'a, 'b> {
 			gap_state: GapState::Clear,
 			visited,
 			current_file: config.initial_current_file.clone(),
+			modules: once_cell::unsync::OnceCell::new(),
+			reachability: once_cell::unsync::OnceCell::new(),
 		}
 	}
 
@@ -127,6 +137,49 @@ impl<'a, 'b> RenderState<'a, 'b> {
 		Ok(output)
 	}
 
+	/// Opening of the crate root module (`pub mod name {` plus inner docs); empty in plain mode.
+	pub fn root_module_head(&mut self) -> String {
+		let root = must_get(self.crate_data, &self.crate_data.root);
+		let path_prefix = ppush("", &render_name(root));
+		super::items::module::module_head(self, &path_prefix, root)
+	}
+
+	/// Render the crate root's children one at a time, passing each non-empty chunk to `emit`.
+	///
+	/// This is [`Self::render`] without the root module wrapper (see [`Self::root_module_head`]),
+	/// so streaming output only holds one top-level item in memory at a time.
+	pub fn render_root_items(&mut self, emit: &mut dyn FnMut(String) -> Result<()>) -> Result<()> {
+		use super::items::render_item;
+
+		let root = must_get(self.crate_data, &self.crate_data.root);
+		let path_prefix = ppush("", &render_name(root));
+		let module = extract_item!(root, ItemEnum::Module);
+		for item_id in &module.items {
+			if !self.selection_allows_child(&root.id, item_id) {
+				self.mark_skipped();
+				continue;
+			}
+			let Some(item) = self.crate_data.index.get(item_id) else {
+				self.mark_skipped();
+				continue;
+			};
+			let rendered = render_item(self, &path_prefix, item, false);
+			if rendered.is_empty() {
+				self.mark_skipped();
+				continue;
+			}
+			let mut chunk = String::new();
+			self.emit_gap_if_needed(&mut chunk, "", &rendered);
+			chunk.push_str(&rendered);
+			emit(chunk)?;
+		}
+
+		if !self.config.filter.is_empty() && !self.filter_matched {
+			return Err(RipdocError::FilterNotMatched(self.config.filter.clone()));
+		}
+		Ok(())
+	}
+
 	/// Return the active render selection, if any.
 	pub fn selection(&self) -> Option<&RenderSelection> {
 		self.config.selection.as_ref()
@@ -148,6 +201,16 @@ impl<'a, 'b> RenderState<'a, 'b> {
 		}
 	}
 
+	/// Doc comment and attribute lines rendered above `item`, including its `#[cfg(...)]` gates
+	/// when cfg annotations are enabled.
+	pub fn docs(&self, item: &Item) -> String {
+		let mut output = docs(item, &self.config.doc_options);
+		if self.config.cfg_annotations {
+			output.push_str(&cfg_attrs(item));
+		}
+		output
+	}
+
 	/// Determine whether a matched container should expand its children in the rendered output.
 	pub fn selection_expands(&self, id: &Id) -> bool {
 		match self.selection() {
@@ -159,11 +222,25 @@ impl<'a, 'b> RenderState<'a, 'b> {
 	/// Check if an item should be rendered with its full source code.
 	pub fn selection_is_full_source(&self, id: &Id) -> bool {
 		match self.selection() {
-			Some(selection) => selection.full_source().contains(id),
+			Some(selection) => {
+				selection.full_source().contains(id) && self.elided_source_lines(id).is_none()
+			}
 			None => false,
 		}
 	}
 
+	/// Line count of an item selected for full source whose span exceeds the configured
+	/// maximum, so its skeleton is rendered instead.
+	pub fn elided_source_lines(&self, id: &Id) -> Option<usize> {
+		let max_lines = self.config.max_impl_lines?;
+		if !self.selection()?.full_source().contains(id) {
+			return None;
+		}
+		let span = self.crate_data.index.get(id)?.span.as_ref()?;
+		let lines = span.end.0.saturating_sub(span.begin.0) + 1;
+		(lines > max_lines).then_some(lines)
+	}
+
 	/// Determine whether a child item should be rendered based on its parent and selection context.
 	pub fn selection_allows_child(&self, parent_id: &Id, child_id: &Id) -> bool {
 		if self.selection().is_none() {
@@ -172,6 +249,42 @@ impl<'a, 'b> RenderState<'a, 'b> {
 		self.selection_expands(parent_id) || self.selection_context_contains(child_id)
 	}
 
+	/// Compute the effective visibility of an item relative to its enclosing module.
+	pub fn effective_visibility(&self, item: &Item) -> EffectiveVisibility {
+		let modules = self.modules.get_or_init(|| ModuleMap::build(self.crate_data));
+		EffectiveVisibility::of(item, modules)
+	}
+
+	/// Render the visibility keyword for an item, including restricted forms like `pub(crate)`.
+	pub fn render_vis(&self, item: &Item) -> String {
+		match &item.visibility {
+			rustdoc_types::Visibility::Public => "pub ".to_string(),
+			rustdoc_types::Visibility::Default => String::new(),
+			_ => self.effective_visibility(item).keyword(),
+		}
+	}
+
+	/// Check whether an item meets the configured minimum visibility.
+	pub fn meets_min_visibility(&self, item: &Item) -> bool {
+		let Some(min) = self.config.min_visibility else {
+			return true;
+		};
+		self.effective_visibility(item)
+			.level()
+			.is_none_or(|level| level >= min)
+	}
+
+	/// Check whether an item may be rendered under strict-public mode: only items reachable from
+	/// the crate root pass. Always true outside strict-public mode.
+	pub fn is_reachable(&self, item: &Item) -> bool {
+		if !self.config.strict_public {
+			return true;
+		}
+		self.reachability
+			.get_or_init(|| Reachability::build(self.crate_data))
+			.allows(&item.id)
+	}
+
 	/// Determine whether an item is filtered out by the configured path filter.
 	pub fn should_filter(&mut self, path_prefix: &str, item: &Item) -> bool {
 		// We never filter the root module - filters operate under the root.
@@ -192,6 +305,16 @@ impl<'a, 'b> RenderState<'a, 'b> {
 		}
 	}
 
+	/// Determine whether an item matches one of the configured exclude patterns.
+	pub fn is_excluded(&self, path_prefix: &str, item: &Item) -> bool {
+		if self.config.exclude.is_empty() || item.id == self.crate_data.root {
+			return false;
+		}
+		item.name
+			.as_ref()
+			.is_some_and(|name| path_excluded(&ppush(path_prefix, name), &self.config.exclude))
+	}
+
 	/// Evaluate how the current filter matches a candidate path.
 	pub fn filter_match(&self, path_prefix: &str, item: &Item) -> FilterMatch {
 		let item_path = if let Some(name) = &item.name {
@@ -1,16 +1,53 @@
 use rustdoc_types::{Item, ItemEnum, Visibility};
 
-/// Format documentation comments as triple-slash lines.
-pub fn docs(item: &Item) -> String {
+use crate::core_api::export::{cfg_predicates, unstable_feature};
+use crate::render::docs::DocOptions;
+
+/// Format documentation comments as triple-slash lines, shaped according to `options`.
+///
+/// Deprecated items also get their `#[deprecated]` attribute after the docs, and unstable
+/// standard library items their `#[unstable]` feature gate.
+pub fn docs(item: &Item, options: &DocOptions) -> String {
 	let mut output = String::new();
-	if let Some(docs) = &item.docs {
+	if let Some(docs) = item.docs.as_deref().and_then(|docs| options.apply(docs)) {
 		for line in docs.lines() {
 			output.push_str(&format!("/// {line}\n"));
 		}
 	}
+	output.push_str(&deprecation_attr(item));
+	if let Some(feature) = unstable_feature(item) {
+		output.push_str(&format!("#[unstable(feature = {feature:?})]\n"));
+	}
 	output
 }
 
+/// Render one `#[cfg(...)]` attribute line per cfg predicate the item is gated on.
+pub fn cfg_attrs(item: &Item) -> String {
+	cfg_predicates(item)
+		.into_iter()
+		.map(|predicate| format!("#[cfg({predicate})]\n"))
+		.collect()
+}
+
+/// Render the `#[deprecated(...)]` attribute line of a deprecated item; empty otherwise.
+pub fn deprecation_attr(item: &Item) -> String {
+	let Some(deprecation) = &item.deprecation else {
+		return String::new();
+	};
+	let mut args = Vec::new();
+	if let Some(since) = &deprecation.since {
+		args.push(format!("since = {since:?}"));
+	}
+	if let Some(note) = &deprecation.note {
+		args.push(format!("note = {note:?}"));
+	}
+	if args.is_empty() {
+		"#[deprecated]\n".to_string()
+	} else {
+		format!("#[deprecated({})]\n", args.join(", "))
+	}
+}
+
 /// Render the visibility modifier for an item if it is public.
 pub fn render_vis(item: &Item) -> String {
 	match &item.visibility {
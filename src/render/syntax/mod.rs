@@ -5,7 +5,7 @@ pub use self::function::{render_function_args, render_return_type};
 pub use self::generics::{
 	render_generic_args, render_generic_param_def, render_generics, render_where_clause,
 };
-pub use self::item::{docs, render_associated_type, render_name, render_vis};
+pub use self::item::{cfg_attrs, deprecation_attr, docs, render_associated_type, render_name, render_vis};
 pub use self::keywords::is_reserved_word;
 pub use self::path::render_path;
 pub use self::types::{render_type, render_type_inner};
@@ -97,6 +97,41 @@ pub fn dedup_gap_markers(output: &str) -> String {
 	deduped
 }
 
+/// Whether `path` lies under one of the exclude `patterns`.
+///
+/// Patterns are `::`-separated paths starting with the crate name, and a `*` matches any run of
+/// characters within a segment. A pattern matching a parent also excludes everything inside it.
+pub fn path_excluded(path: &str, patterns: &[String]) -> bool {
+	let segments: Vec<&str> = path.split("::").collect();
+	patterns.iter().any(|pattern| {
+		let pattern: Vec<&str> = pattern.split("::").collect();
+		pattern.len() <= segments.len()
+			&& pattern
+				.iter()
+				.zip(&segments)
+				.all(|(expected, actual)| segment_matches(expected, actual))
+	})
+}
+
+/// Match one path segment against a pattern segment that may contain `*` wildcards.
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+	let mut parts = pattern.split('*');
+	let Some(mut rest) = segment.strip_prefix(parts.next().unwrap_or_default()) else {
+		return false;
+	};
+	let parts: Vec<&str> = parts.collect();
+	let Some((last, middle)) = parts.split_last() else {
+		return rest.is_empty();
+	};
+	for part in middle {
+		let Some(pos) = rest.find(part) else {
+			return false;
+		};
+		rest = &rest[pos + part.len()..];
+	}
+	rest.ends_with(last)
+}
+
 /// Classification describing how a filter string matches a path.
 #[derive(Debug, PartialEq)]
 pub enum FilterMatch {
@@ -168,8 +203,8 @@ pub fn extract_source(
 	let end_line = std::cmp::min(span.end.0, lines.len());
 
 	let mut extracted = Vec::new();
-	for i in start_line..end_line {
-		let mut line = lines[i].to_string();
+	for line in &lines[start_line..end_line] {
+		let mut line = line.to_string();
 		// Convert inner doc comments to outer ones if they appear in a snippet.
 		// //! -> ///
 		// /*! -> /**
@@ -190,6 +225,39 @@ pub fn extract_source(
 	Ok(sanitize_extracted_snippet(&result))
 }
 
+/// Express a span filename relative to `source_root` for display in source labels.
+///
+/// Absolute paths under the root have the root stripped; workspace-relative paths (as rustdoc
+/// records them for workspace members) drop leading components until they resolve below the
+/// root. Paths outside the root, such as registry dependencies, are returned unchanged.
+pub fn relative_source_path(
+	path: &std::path::Path,
+	source_root: Option<&std::path::Path>,
+) -> std::path::PathBuf {
+	let Some(root) = source_root else {
+		return path.to_path_buf();
+	};
+	if path.is_absolute() {
+		return path.strip_prefix(root).unwrap_or(path).to_path_buf();
+	}
+	if root.join(path).exists() {
+		return path.to_path_buf();
+	}
+	let mut components = path.components();
+	while components.next().is_some() {
+		let candidate = components.as_path();
+		if !candidate.as_os_str().is_empty() && root.join(candidate).exists() {
+			return candidate.to_path_buf();
+		}
+	}
+	path.to_path_buf()
+}
+
+/// The `// ripdoc:source:` label placed before the first item of each source file section.
+pub fn source_label(path: &std::path::Path, source_root: Option<&std::path::Path>) -> String {
+	format!("// ripdoc:source: {}\n\n", relative_source_path(path, source_root).display())
+}
+
 fn sanitize_extracted_snippet(snippet: &str) -> String {
 	// Spans can occasionally slice through attribute-heavy blocks (e.g. derived impls),
 	// producing snippets that start or end with a standalone attribute.
@@ -0,0 +1,292 @@
+//! Effective visibility of items, used to annotate restricted items and filter by visibility.
+//!
+//! Rustdoc reports private items as visible only within their enclosing module, so telling
+//! `pub(super)` apart from a plain private item requires knowing which module holds the item.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+/// Ordered visibility levels, from most to least restrictive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VisibilityLevel {
+	/// Visible only within the enclosing module.
+	Private,
+	/// `pub(super)` or `pub(in path)`.
+	Restricted,
+	/// `pub(crate)`.
+	Crate,
+	/// `pub`.
+	Public,
+}
+
+impl fmt::Display for VisibilityLevel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let label = match self {
+			Self::Private => "private",
+			Self::Restricted => "pub(super)",
+			Self::Crate => "pub(crate)",
+			Self::Public => "pub",
+		};
+		f.write_str(label)
+	}
+}
+
+impl FromStr for VisibilityLevel {
+	type Err = String;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value.trim() {
+			"private" => Ok(Self::Private),
+			"pub(super)" | "pub(in)" | "restricted" => Ok(Self::Restricted),
+			"pub(crate)" | "crate" => Ok(Self::Crate),
+			"pub" | "public" => Ok(Self::Public),
+			other => Err(format!(
+				"unknown visibility '{other}' (expected pub, pub(crate), pub(super), or private)"
+			)),
+		}
+	}
+}
+
+/// The visibility of an item relative to the module that declares it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectiveVisibility {
+	/// Visibility is inherited from the parent (trait items, enum variants, trait impl items).
+	Inherited,
+	/// Private to the enclosing module.
+	Private,
+	/// `pub(super)`.
+	Super,
+	/// `pub(in path)`, with the path rendered from the crate root.
+	InPath(String),
+	/// `pub(crate)`.
+	Crate,
+	/// Declared in the crate root as private or `pub(crate)`, which rustdoc reports alike. Both
+	/// are visible to the whole crate, so this ranks as `pub(crate)` but is left unannotated.
+	CrateRoot,
+	/// `pub`.
+	Public,
+}
+
+impl EffectiveVisibility {
+	/// Compute the effective visibility of `item`, using `modules` to find its enclosing module.
+	pub fn of(item: &Item, modules: &ModuleMap) -> Self {
+		match &item.visibility {
+			Visibility::Public => Self::Public,
+			// Rustdoc reports private items of the crate root as crate-visible; the two are
+			// equivalent there, so keep them unannotated.
+			Visibility::Crate if modules.enclosing(&item.id) == Some(&modules.root) => Self::CrateRoot,
+			Visibility::Crate => Self::Crate,
+			Visibility::Default => Self::Inherited,
+			Visibility::Restricted { parent, path } => {
+				let enclosing = modules.enclosing(&item.id);
+				if enclosing == Some(parent) {
+					Self::Private
+				} else if enclosing.and_then(|module| modules.enclosing(module)) == Some(parent) {
+					Self::Super
+				} else {
+					let path = path.trim_start_matches("::");
+					if path.is_empty() || path == "crate" {
+						Self::Crate
+					} else if path.starts_with("crate::") {
+						Self::InPath(path.to_string())
+					} else {
+						Self::InPath(format!("crate::{path}"))
+					}
+				}
+			}
+		}
+	}
+
+	/// Visibility keyword (with trailing space) to place before the item declaration.
+	pub fn keyword(&self) -> String {
+		match self {
+			Self::Inherited | Self::Private | Self::CrateRoot => String::new(),
+			Self::Super => "pub(super) ".to_string(),
+			Self::InPath(path) => format!("pub(in {path}) "),
+			Self::Crate => "pub(crate) ".to_string(),
+			Self::Public => "pub ".to_string(),
+		}
+	}
+
+	/// Level used for `--min-visibility` filtering; inherited visibility is never filtered.
+	pub fn level(&self) -> Option<VisibilityLevel> {
+		match self {
+			Self::Inherited => None,
+			Self::Private => Some(VisibilityLevel::Private),
+			Self::Super | Self::InPath(_) => Some(VisibilityLevel::Restricted),
+			Self::Crate | Self::CrateRoot => Some(VisibilityLevel::Crate),
+			Self::Public => Some(VisibilityLevel::Public),
+		}
+	}
+}
+
+/// Maps items to the module that declares them.
+#[derive(Debug)]
+pub struct ModuleMap {
+	root: Id,
+	parents: HashMap<Id, Id>,
+}
+
+impl ModuleMap {
+	/// Walk every module in the crate, recording the enclosing module of its items, of their
+	/// fields and variants, and of items declared inside impl and trait blocks.
+	pub fn build(crate_data: &Crate) -> Self {
+		let mut parents = HashMap::new();
+		for (module_id, module_item) in &crate_data.index {
+			let ItemEnum::Module(module) = &module_item.inner else {
+				continue;
+			};
+			for child_id in &module.items {
+				parents.insert(*child_id, *module_id);
+				let Some(child) = crate_data.index.get(child_id) else {
+					continue;
+				};
+				for nested in nested_item_ids(crate_data, child) {
+					parents.entry(nested).or_insert(*module_id);
+				}
+			}
+		}
+		Self {
+			root: crate_data.root,
+			parents,
+		}
+	}
+
+	/// Return the module enclosing `id`, if known.
+	pub fn enclosing(&self, id: &Id) -> Option<&Id> {
+		self.parents.get(id)
+	}
+}
+
+/// Which module-level items are reachable from the crate root through `pub` modules and `pub use`
+/// re-exports, i.e. the crate's actual external API.
+#[derive(Debug)]
+pub struct Reachability {
+	module_children: HashSet<Id>,
+	reachable: HashSet<Id>,
+}
+
+impl Reachability {
+	/// Walk the crate from its root, following public modules and public re-exports (including
+	/// glob re-exports of modules).
+	pub fn build(crate_data: &Crate) -> Self {
+		let mut module_children = HashSet::new();
+		for item in crate_data.index.values() {
+			if let ItemEnum::Module(module) = &item.inner {
+				module_children.extend(module.items.iter().copied());
+			}
+		}
+
+		let is_module = |id: &Id| {
+			matches!(crate_data.index.get(id).map(|item| &item.inner), Some(ItemEnum::Module(_)))
+		};
+		let mut reachable = HashSet::from([crate_data.root]);
+		let mut expanded = HashSet::new();
+		let mut queue = vec![crate_data.root];
+		while let Some(module_id) = queue.pop() {
+			if !expanded.insert(module_id) {
+				continue;
+			}
+			let Some(ItemEnum::Module(module)) = crate_data.index.get(&module_id).map(|item| &item.inner)
+			else {
+				continue;
+			};
+			for child_id in &module.items {
+				let Some(child) = crate_data.index.get(child_id) else {
+					continue;
+				};
+				if !matches!(child.visibility, Visibility::Public) {
+					continue;
+				}
+				reachable.insert(*child_id);
+				match &child.inner {
+					ItemEnum::Module(_) => queue.push(*child_id),
+					ItemEnum::Use(use_) => {
+						let Some(target) = use_.id else {
+							continue;
+						};
+						// A glob re-export exposes the module's public items, not the module.
+						if !use_.is_glob {
+							reachable.insert(target);
+						}
+						if is_module(&target) {
+							queue.push(target);
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+
+		Self {
+			module_children,
+			reachable,
+		}
+	}
+
+	/// Whether `id` is part of the external API. Items that are not declared directly in a module
+	/// (fields, variants, associated items) are judged by their parent and always pass.
+	pub fn allows(&self, id: &Id) -> bool {
+		!self.module_children.contains(id) || self.reachable.contains(id)
+	}
+}
+
+/// Collect ids declared inside an item that share its enclosing module.
+fn nested_item_ids(crate_data: &Crate, item: &Item) -> Vec<Id> {
+	let mut ids = Vec::new();
+	let push_impls = |impls: &[Id], ids: &mut Vec<Id>| {
+		for impl_id in impls {
+			if let Some(impl_item) = crate_data.index.get(impl_id)
+				&& let ItemEnum::Impl(impl_) = &impl_item.inner
+			{
+				ids.extend(impl_.items.iter().copied());
+			}
+		}
+	};
+	match &item.inner {
+		ItemEnum::Struct(struct_) => {
+			match &struct_.kind {
+				rustdoc_types::StructKind::Plain { fields, .. } => ids.extend(fields.iter().copied()),
+				rustdoc_types::StructKind::Tuple(fields) => ids.extend(fields.iter().flatten().copied()),
+				rustdoc_types::StructKind::Unit => {}
+			}
+			push_impls(&struct_.impls, &mut ids);
+		}
+		ItemEnum::Union(union_) => {
+			ids.extend(union_.fields.iter().copied());
+			push_impls(&union_.impls, &mut ids);
+		}
+		ItemEnum::Enum(enum_) => {
+			ids.extend(enum_.variants.iter().copied());
+			push_impls(&enum_.impls, &mut ids);
+		}
+		ItemEnum::Trait(trait_) => ids.extend(trait_.items.iter().copied()),
+		ItemEnum::Impl(impl_) => ids.extend(impl_.items.iter().copied()),
+		_ => {}
+	}
+	ids
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_min_visibility_values() {
+		assert_eq!("pub(crate)".parse(), Ok(VisibilityLevel::Crate));
+		assert_eq!("pub(super)".parse(), Ok(VisibilityLevel::Restricted));
+		assert_eq!("private".parse(), Ok(VisibilityLevel::Private));
+		assert!("pub(self)".parse::<VisibilityLevel>().is_err());
+		assert!(VisibilityLevel::Crate > VisibilityLevel::Restricted);
+	}
+
+	#[test]
+	fn restricted_paths_render_from_crate_root() {
+		let vis = EffectiveVisibility::InPath("crate::a::b".to_string());
+		assert_eq!(vis.keyword(), "pub(in crate::a::b) ");
+		assert_eq!(vis.level(), Some(VisibilityLevel::Restricted));
+	}
+}
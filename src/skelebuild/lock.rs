@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::state::{SkeleEntry, SkeleState};
+use crate::cargo_utils::{get_toolchain_version, resolve_target};
+use crate::core_api::error::RipdocError;
+use crate::core_api::{Result, Ripdoc};
+
+/// Default lockfile name, written next to the skelebuild output or manifest.
+pub const DEFAULT_LOCKFILE: &str = "ripdoc.lock";
+
+/// The exact crate inputs a skelebuild document was built from.
+///
+/// Written by explicit rebuilds; with `--locked`, a rebuild whose inputs differ from the recorded
+/// ones is refused instead, so a shared document is only regenerated from the same crates.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkeleLock {
+	/// One record per resolved package of every target entry, in entry order.
+	pub targets: Vec<LockedTarget>,
+}
+
+/// Inputs of one target entry as resolved for a rebuild.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedTarget {
+	/// Target spec as stored in the state or manifest.
+	pub target: String,
+	/// Package the target resolved to.
+	pub package: String,
+	/// Exact package version, unless inherited from a workspace.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+	/// Whether default features were disabled.
+	#[serde(default)]
+	pub no_default_features: bool,
+	/// Whether all features were enabled.
+	#[serde(default)]
+	pub all_features: bool,
+	/// Explicitly enabled features, sorted.
+	#[serde(default)]
+	pub features: Vec<String>,
+	/// Toolchain rustdoc JSON was built with.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub toolchain: Option<String>,
+	/// Cache key the crate was read under.
+	///
+	/// Informational only: the key includes the absolute manifest path, so it differs between
+	/// machines and is not compared by `--locked`.
+	pub cache_key: String,
+}
+
+impl LockedTarget {
+	/// The fields `--locked` compares, as `package version [features] toolchain`.
+	fn inputs(&self) -> String {
+		let mut inputs = self.package.clone();
+		if let Some(version) = &self.version {
+			inputs.push_str(&format!(" {version}"));
+		}
+		let mut flags = Vec::new();
+		if self.no_default_features {
+			flags.push("no-default-features".to_string());
+		}
+		if self.all_features {
+			flags.push("all-features".to_string());
+		}
+		if !self.features.is_empty() {
+			flags.push(format!("features={}", self.features.join(",")));
+		}
+		if !flags.is_empty() {
+			inputs.push_str(&format!(" [{}]", flags.join(" ")));
+		}
+		if let Some(toolchain) = &self.toolchain {
+			inputs.push_str(&format!(" ({toolchain})"));
+		}
+		inputs
+	}
+
+	fn same_inputs(&self, other: &Self) -> bool {
+		self.target == other.target && self.inputs() == other.inputs()
+	}
+}
+
+impl SkeleLock {
+	/// Resolve the target entries of `state` and record their inputs.
+	///
+	/// Targets that fail to resolve or whose build cannot be keyed are left out; the rebuild
+	/// reports them.
+	pub fn for_state(state: &SkeleState, ripdoc: &Ripdoc) -> Self {
+		let toolchain = get_toolchain_version();
+		let mut targets = Vec::new();
+		for entry in &state.entries {
+			let SkeleEntry::Target(target) = entry else {
+				continue;
+			};
+			let Ok(resolved) = resolve_target(&target.path, &ripdoc.resolve_context()) else {
+				continue;
+			};
+			for rt in resolved {
+				let Ok(key) = rt.cache_key(
+					target.no_default_features,
+					target.all_features,
+					target.features.clone(),
+					target.private,
+					ripdoc.cache_config(),
+				) else {
+					continue;
+				};
+				let metadata = rt.package_metadata().ok().flatten();
+				let (package, version) = match metadata {
+					Some(metadata) => (metadata.name, metadata.version),
+					None => (key.package_info.clone(), None),
+				};
+				targets.push(LockedTarget {
+					target: target.path.clone(),
+					package,
+					version,
+					no_default_features: key.no_default_features,
+					all_features: key.all_features,
+					features: key.features.clone(),
+					toolchain: toolchain.clone(),
+					cache_key: key.hash(),
+				});
+			}
+		}
+		Self { targets }
+	}
+
+	/// Read a lockfile, or `None` if it does not exist.
+	pub fn load(path: &Path) -> Result<Option<Self>> {
+		let content = match fs::read_to_string(path) {
+			Ok(content) => content,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(err.into()),
+		};
+		let lock = serde_json::from_str(&content).map_err(|err| {
+			RipdocError::InvalidTarget(format!(
+				"Failed to parse lockfile '{}': {err}",
+				path.display()
+			))
+		})?;
+		Ok(Some(lock))
+	}
+
+	/// Write the lockfile, leaving it untouched when nothing changed.
+	pub fn save(&self, path: &Path) -> Result<()> {
+		if Self::load(path).ok().flatten().as_ref() == Some(self) {
+			return Ok(());
+		}
+		if let Some(parent) = path.parent()
+			&& !parent.as_os_str().is_empty()
+		{
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(path, format!("{}\n", serde_json::to_string_pretty(self)?))?;
+		Ok(())
+	}
+
+	/// Describe how `current` differs from the recorded inputs, one line per change; empty when
+	/// they match.
+	pub fn drift(&self, current: &Self) -> Vec<String> {
+		let mut changes = Vec::new();
+		for locked in &self.targets {
+			if !current.targets.iter().any(|target| target.same_inputs(locked)) {
+				match current.targets.iter().find(|target| target.target == locked.target) {
+					Some(target) => changes.push(format!(
+						"{}: locked {}, now {}",
+						locked.target,
+						locked.inputs(),
+						target.inputs()
+					)),
+					None => changes.push(format!("{}: locked but no longer built", locked.target)),
+				}
+			}
+		}
+		for target in &current.targets {
+			if !self.targets.iter().any(|locked| locked.target == target.target) {
+				changes.push(format!("{}: not in the lockfile", target.target));
+			}
+		}
+		changes
+	}
+
+	/// Refuse to continue unless the lockfile at `path` records the same inputs as `self`.
+	pub fn verify(&self, path: &Path) -> Result<()> {
+		let Some(locked) = Self::load(path)? else {
+			return Err(RipdocError::LockMismatch(format!(
+				"--locked was given but no lockfile exists at {}",
+				path.display()
+			)));
+		};
+		let changes = locked.drift(self);
+		if changes.is_empty() {
+			return Ok(());
+		}
+		let mut message = format!(
+			"Skeleton inputs drifted from {} (refusing to rebuild with --locked):",
+			path.display()
+		);
+		for change in changes {
+			message.push_str(&format!("\n  {change}"));
+		}
+		Err(RipdocError::LockMismatch(message))
+	}
+}
+
+/// Lockfile location for a document written to `output`: `ripdoc.lock` in the same directory.
+pub fn lock_path_for(output: &Path) -> PathBuf {
+	output
+		.parent()
+		.map_or_else(|| PathBuf::from(DEFAULT_LOCKFILE), |dir| dir.join(DEFAULT_LOCKFILE))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn locked(target: &str, version: &str) -> LockedTarget {
+		LockedTarget {
+			target: target.to_string(),
+			package: "serde".to_string(),
+			version: Some(version.to_string()),
+			no_default_features: false,
+			all_features: false,
+			features: vec!["derive".to_string()],
+			toolchain: Some("rustc 1.90.0-nightly".to_string()),
+			cache_key: "abc".to_string(),
+		}
+	}
+
+	#[test]
+	fn drift_ignores_cache_keys_but_reports_version_changes() {
+		let lock = SkeleLock {
+			targets: vec![locked("serde::Serialize", "1.0.200")],
+		};
+		let mut moved = locked("serde::Serialize", "1.0.200");
+		moved.cache_key = "def".to_string();
+		assert!(lock.drift(&SkeleLock { targets: vec![moved] }).is_empty());
+
+		let bumped = SkeleLock {
+			targets: vec![locked("serde::Serialize", "1.0.210")],
+		};
+		assert_eq!(
+			lock.drift(&bumped),
+			vec![
+				"serde::Serialize: locked serde 1.0.200 [features=derive] (rustc 1.90.0-nightly), \
+				 now serde 1.0.210 [features=derive] (rustc 1.90.0-nightly)"
+					.to_string()
+			]
+		);
+
+		let added = SkeleLock {
+			targets: vec![locked("serde::Serialize", "1.0.200"), locked("serde::de", "1.0.200")],
+		};
+		assert_eq!(lock.drift(&added), vec!["serde::de: not in the lockfile".to_string()]);
+	}
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::lock::{DEFAULT_LOCKFILE, SkeleLock};
+use super::state::{SkeleBudget, SkeleEntry, SkeleState};
+use crate::cargo_utils::target::{Entrypoint, Target};
+use crate::core_api::error::RipdocError;
+use crate::core_api::{Result, Ripdoc};
+
+/// Default manifest filename used by `skelebuild ci`.
+pub const DEFAULT_MANIFEST: &str = "ripdoc.toml";
+
+/// A committed skelebuild definition, independent of the per-user state file.
+///
+/// Paths in the manifest (the output file, path-based targets, and raw source files) are
+/// resolved relative to the directory containing the manifest.
+#[derive(Deserialize, Debug)]
+struct SkeleManifest {
+	/// Output file the document is written to.
+	output: PathBuf,
+	/// Whether to use plain output (skip module nesting). Defaults to true.
+	#[serde(default = "default_plain")]
+	plain: bool,
+	/// Entries rendered into the document, in order.
+	#[serde(default)]
+	entries: Vec<SkeleEntry>,
+	/// Size budget the document must fit.
+	#[serde(default)]
+	budget: Option<SkeleBudget>,
+	/// Render entry notes as HTML comments above their entries.
+	#[serde(default)]
+	render_notes: bool,
+}
+
+fn default_plain() -> bool {
+	true
+}
+
+/// Result of a `skelebuild ci` run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CiOutcome {
+	/// The output file was (re)written.
+	Written(PathBuf),
+	/// The committed output file matches the rebuilt document.
+	UpToDate(PathBuf),
+	/// The committed output file is missing or differs from the rebuilt document.
+	Stale(PathBuf),
+}
+
+/// Load a skelebuild manifest into a state, resolving relative paths against the manifest dir.
+pub fn load_manifest(path: &Path) -> Result<SkeleState> {
+	let content = fs::read_to_string(path).map_err(|err| {
+		RipdocError::InvalidTarget(format!(
+			"Failed to read skelebuild manifest '{}': {err}",
+			path.display()
+		))
+	})?;
+	let manifest: SkeleManifest = toml::from_str(&content).map_err(|err| {
+		RipdocError::InvalidTarget(format!(
+			"Failed to parse skelebuild manifest '{}': {err}",
+			path.display()
+		))
+	})?;
+
+	let base = path
+		.parent()
+		.filter(|parent| !parent.as_os_str().is_empty())
+		.map(Path::to_path_buf)
+		.unwrap_or_else(|| PathBuf::from("."));
+
+	let entries = manifest
+		.entries
+		.into_iter()
+		.map(|entry| match entry {
+			SkeleEntry::Target(mut target) => {
+				target.path = resolve_target_spec(&target.path, &base);
+				SkeleEntry::Target(target)
+			}
+			SkeleEntry::RawSource(mut raw) => {
+				if raw.file.is_relative() {
+					raw.file = base.join(&raw.file);
+				}
+				SkeleEntry::RawSource(raw)
+			}
+			other => other,
+		})
+		.collect();
+
+	Ok(SkeleState {
+		output_path: Some(base.join(manifest.output)),
+		entries,
+		plain: manifest.plain,
+		budget: manifest.budget,
+		render_notes: manifest.render_notes,
+		sinks: Vec::new(),
+	})
+}
+
+/// Resolve a path-based target spec relative to `base`, leaving named targets untouched.
+fn resolve_target_spec(spec: &str, base: &Path) -> String {
+	let Ok(parsed) = Target::parse(spec) else {
+		return spec.to_string();
+	};
+	match parsed.entrypoint {
+		Entrypoint::Path(path) if path.is_relative() => {
+			let mut resolved = base.join(path).to_string_lossy().to_string();
+			if !parsed.path.is_empty() {
+				resolved.push_str("::");
+				resolved.push_str(&parsed.path.join("::"));
+			}
+			resolved
+		}
+		_ => spec.to_string(),
+	}
+}
+
+/// Rebuild the document described by `manifest`.
+///
+/// With `check`, nothing is written and the rebuilt document is compared against the existing
+/// output file instead. Otherwise the crate inputs are recorded in a `ripdoc.lock` next to the
+/// manifest; with `locked`, the build is refused if they differ from that file, which is then
+/// left untouched.
+pub fn run_ci(manifest: &Path, check: bool, locked: bool, ripdoc: &Ripdoc) -> Result<CiOutcome> {
+	let state = load_manifest(manifest)?;
+	let output_path = state
+		.output_path
+		.clone()
+		.expect("manifest always sets an output path");
+	let lock_path = manifest.with_file_name(DEFAULT_LOCKFILE);
+	let lock = SkeleLock::for_state(&state, ripdoc);
+	if locked {
+		lock.verify(&lock_path)?;
+	}
+	let rendered = state.build_budgeted_output(ripdoc)?;
+
+	if !check {
+		if let Some(parent) = output_path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(&output_path, rendered)?;
+		if !locked {
+			lock.save(&lock_path)?;
+		}
+		return Ok(CiOutcome::Written(output_path));
+	}
+
+	match fs::read_to_string(&output_path) {
+		Ok(committed) if committed == rendered => Ok(CiOutcome::UpToDate(output_path)),
+		Ok(_) => Ok(CiOutcome::Stale(output_path)),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(CiOutcome::Stale(output_path)),
+		Err(err) => Err(err.into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn load_manifest_resolves_paths_relative_to_manifest() {
+		let temp = tempfile::TempDir::new().unwrap();
+		let manifest_path = temp.path().join(DEFAULT_MANIFEST);
+		fs::write(
+			&manifest_path,
+			r###"
+output = "docs/context.md"
+
+[[entries]]
+type = "target"
+path = "./crates/core::crate::Engine"
+implementation = false
+
+[[entries]]
+type = "injection"
+content = "## Notes"
+
+[[entries]]
+type = "raw_source"
+file = "src/lib.rs"
+start_line = 1
+end_line = 5
+"###,
+		)
+		.unwrap();
+
+		let state = load_manifest(&manifest_path).unwrap();
+		let base = temp.path();
+		assert_eq!(state.output_path, Some(base.join("docs/context.md")));
+		assert!(state.plain);
+		assert_eq!(state.entries.len(), 3);
+
+		let SkeleEntry::Target(target) = &state.entries[0] else {
+			panic!("expected target entry");
+		};
+		assert_eq!(
+			target.path,
+			format!("{}::crate::Engine", base.join("./crates/core").display())
+		);
+		assert!(!target.implementation);
+		assert!(target.private);
+
+		let SkeleEntry::RawSource(raw) = &state.entries[2] else {
+			panic!("expected raw source entry");
+		};
+		assert_eq!(raw.file, base.join("src/lib.rs"));
+	}
+
+	#[test]
+	fn resolve_target_spec_keeps_named_targets() {
+		let base = Path::new("/repo");
+		assert_eq!(resolve_target_spec("serde::Deserialize", base), "serde::Deserialize");
+	}
+}
@@ -1,45 +1,99 @@
+/// Lockfiles pinning the crate inputs of a skelebuild document.
+pub mod lock;
+/// Committed skelebuild manifests and up-to-date checks for CI.
+pub mod manifest;
 mod rebuild;
+/// Parsers for cargo test and cargo check output.
+pub mod reports;
 /// Target resolution and validation logic.
 pub mod resolver;
+/// Destinations rebuilt output is delivered to.
+pub mod sink;
 /// Persistent state and data structures for skelebuild.
 pub mod state;
+/// Rebuilding the output when watched sources change.
+mod watch;
 
+use std::io::Write;
 use std::path::PathBuf;
 
+pub use lock::{DEFAULT_LOCKFILE, LockedTarget, SkeleLock, lock_path_for};
+pub use manifest::{CiOutcome, DEFAULT_MANIFEST, load_manifest, run_ci};
 pub use resolver::unescape_inject_content;
+pub use sink::{ClipboardSink, FileSink, HttpSink, SinkSpec, SkeleSink, StdoutSink};
+pub use watch::watch;
 use resolver::{
-	find_entry_match, find_target_match, normalize_target_spec_for_storage,
-	validate_add_target_or_error,
+	find_entry_match, normalize_target_spec_for_storage, validate_add_target_or_error,
+	validate_target_with_deps,
+};
+pub use state::{
+	BudgetAction, BudgetUnit, PreviewSegment, PreviewSegmentKind, SkeleAction, SkeleBudget,
+	SkeleEntry, SkeleInjection, SkelePreview, SkeleRawSource, SkeleState, SkeleTarget,
 };
-pub use state::{SkeleAction, SkeleEntry, SkeleInjection, SkeleRawSource, SkeleState, SkeleTarget};
 
 use crate::core_api::error::RipdocError;
 use crate::core_api::{Result, Ripdoc};
 
+/// Feature configuration a crate is documented with: (no default features, all features,
+/// sorted features).
+pub(crate) type FeatureKey = (bool, bool, Vec<String>);
+
 pub(crate) enum SkeleGroup {
 	Targets {
 		pkg_root: PathBuf,
+		features: FeatureKey,
 		targets: Vec<SkeleTarget>,
 	},
 	Injection(String),
 	RawSource(SkeleRawSource),
 }
 
-/// Executes the skelebuild subcommand.
+/// Output settings of a skelebuild invocation, applied before its action runs.
+#[derive(Debug, Clone, Default)]
+pub struct SkeleOptions {
+	/// Output file to write the skeleton to from now on.
+	pub output: Option<PathBuf>,
+	/// Switch plain output (no module nesting) on or off.
+	pub plain: Option<bool>,
+	/// Switch rendering entry notes as HTML comments on or off.
+	pub render_notes: Option<bool>,
+	/// Refuse rebuilds whose crate inputs differ from `ripdoc.lock`.
+	pub locked: bool,
+	/// Print the full state after the action.
+	pub show_state: bool,
+}
+
+/// Executes the skelebuild subcommand, writing its report to `out`.
+///
+/// Explicit rebuilds (`skelebuild rebuild`) record their crate inputs in a `ripdoc.lock` next to
+/// the output; with [`SkeleOptions::locked`], any rebuild whose inputs differ from that file is refused.
+///
+/// Relative paths are resolved against the working directory of `ripdoc`. With its memory cache
+/// enabled, as in `ripdoc daemon`, the state stays in memory between calls (see
+/// [`SkeleState::load_warm`]).
 pub fn run_skelebuild(
 	action: Option<SkeleAction>,
-	output: Option<PathBuf>,
-	plain: Option<bool>,
-	show_state: bool,
+	options: SkeleOptions,
 	ripdoc: &Ripdoc,
+	out: &mut dyn Write,
 ) -> Result<()> {
-	let mut state = SkeleState::load();
+	let SkeleOptions {
+		output,
+		plain,
+		render_notes,
+		locked,
+		show_state,
+	} = options;
+	let warm = ripdoc.cache_config().memory;
+	let save = |state: &SkeleState| if warm { state.save_warm(ripdoc) } else { state.save(ripdoc) };
+	let mut state = if warm { SkeleState::load_warm(ripdoc) } else { SkeleState::load(ripdoc) };
 	let prev_output_path = state.output_path.clone();
 	let prev_plain = state.plain;
+	let prev_render_notes = state.render_notes;
 
 	if let Some(ref out) = output {
 		let out = if out.is_relative() {
-			std::path::absolute(out).map_err(|err| {
+			std::path::absolute(ripdoc.in_working_dir(out)).map_err(|err| {
 				RipdocError::InvalidTarget(format!(
 					"Failed to resolve output path '{}': {err}",
 					out.display()
@@ -53,10 +107,17 @@ pub fn run_skelebuild(
 	if let Some(plain_value) = plain {
 		state.plain = plain_value;
 	}
+	if let Some(render_notes) = render_notes {
+		state.render_notes = render_notes;
+	}
 
-	let config_changed = state.output_path != prev_output_path || state.plain != prev_plain;
+	let config_changed = state.output_path != prev_output_path
+		|| state.plain != prev_plain
+		|| state.render_notes != prev_render_notes;
 	let show_state_on_exit =
 		show_state || matches!(action.as_ref(), Some(SkeleAction::Status { .. }));
+	// Sinks and the lockfile follow explicit rebuilds only, not the rebuild after every edit.
+	let explicit_rebuild = matches!(action, Some(SkeleAction::Rebuild));
 	let mut action_summary: Option<String> = None;
 
 	let mut should_rebuild = false;
@@ -68,18 +129,36 @@ pub fn run_skelebuild(
 			validate,
 			private,
 			strict,
+			features,
+			all_features,
+			no_default_features,
+			with_deps,
 		}) => {
-			let normalized_target = normalize_target_spec_for_storage(&target);
-			let validated = if validate {
-				Some(validate_add_target_or_error(
-					&normalized_target,
+			let normalized_target = normalize_target_spec_for_storage(&target, ripdoc);
+			let new_target = SkeleTarget {
+				path: normalized_target.clone(),
+				implementation,
+				raw_source,
+				private,
+				features,
+				all_features,
+				no_default_features,
+				note: None,
+			};
+			let validated = if validate || with_deps.is_some() {
+				Some(validate_target_with_deps(
+					&new_target,
 					ripdoc,
-					private,
 					strict,
+					with_deps.unwrap_or(0),
 				)?)
 			} else {
 				None
 			};
+			let dependencies = validated
+				.as_ref()
+				.map(|info| info.dependencies.clone())
+				.unwrap_or_default();
 
 			let already_present = state.entries.iter().any(|e| match e {
 				SkeleEntry::Target(t) => t.path == normalized_target,
@@ -87,17 +166,14 @@ pub fn run_skelebuild(
 			});
 
 			should_rebuild = config_changed;
+			let deps_template = new_target.clone();
 			if already_present {
 				action_summary = Some(format!(
 					"No change (target already exists): {normalized_target}"
 				));
 			} else {
-				state.entries.push(SkeleEntry::Target(SkeleTarget {
-					path: normalized_target.clone(),
-					implementation,
-					raw_source,
-					private,
-				}));
+				let feature_flags = new_target.feature_flags();
+				state.entries.push(SkeleEntry::Target(new_target));
 				let index = state.entries.len() - 1;
 				let source = validated
 					.as_ref()
@@ -120,6 +196,7 @@ pub fn run_skelebuild(
 				if !private {
 					flags.push("public-only");
 				}
+				flags.extend(feature_flags.iter().map(String::as_str));
 				let flags_str = if flags.is_empty() {
 					String::new()
 				} else {
@@ -129,6 +206,18 @@ pub fn run_skelebuild(
 					"Added #{index}: {normalized_target} ({source}:{span_lines}){flags_str}"
 				));
 			}
+
+			let added_deps =
+				push_dependency_targets(&mut state.entries, &deps_template, dependencies);
+			if !added_deps.is_empty() {
+				should_rebuild = true;
+				action_summary = Some(format!(
+					"{}\nAdded {} referenced types: {}",
+					action_summary.unwrap_or_default(),
+					added_deps.len(),
+					added_deps.join(", ")
+				));
+			}
 		}
 		Some(SkeleAction::AddMany {
 			targets,
@@ -137,15 +226,31 @@ pub fn run_skelebuild(
 			validate,
 			private,
 			strict,
+			features,
+			all_features,
+			no_default_features,
+			with_deps,
 		}) => {
 			let mut added: Vec<String> = Vec::new();
 			let mut added_indices: Vec<usize> = Vec::new();
 			let mut already: Vec<String> = Vec::new();
+			let mut dependencies: Vec<(SkeleTarget, Vec<String>)> = Vec::new();
 			for target in targets {
-				let normalized_target = normalize_target_spec_for_storage(&target);
-				if validate {
-					let _ =
-						validate_add_target_or_error(&normalized_target, ripdoc, private, strict)?;
+				let normalized_target = normalize_target_spec_for_storage(&target, ripdoc);
+				let new_target = SkeleTarget {
+					path: normalized_target.clone(),
+					implementation,
+					raw_source,
+					private,
+					features: features.clone(),
+					all_features,
+					no_default_features,
+					note: None,
+				};
+				if validate || with_deps.is_some() {
+					let depth = with_deps.unwrap_or(0);
+					let info = validate_target_with_deps(&new_target, ripdoc, strict, depth)?;
+					dependencies.push((new_target.clone(), info.dependencies));
 				}
 				let is_present = state.entries.iter().any(|e| match e {
 					SkeleEntry::Target(t) => t.path == normalized_target,
@@ -155,13 +260,8 @@ pub fn run_skelebuild(
 					already.push(normalized_target);
 					continue;
 				}
-				added.push(normalized_target.clone());
-				state.entries.push(SkeleEntry::Target(SkeleTarget {
-					path: normalized_target,
-					implementation,
-					raw_source,
-					private,
-				}));
+				added.push(normalized_target);
+				state.entries.push(SkeleEntry::Target(new_target));
 				added_indices.push(state.entries.len() - 1);
 			}
 
@@ -196,6 +296,16 @@ pub fn run_skelebuild(
 				if !private {
 					flags.push("public-only");
 				}
+				if no_default_features {
+					flags.push("no-default-features");
+				}
+				if all_features {
+					flags.push("all-features");
+				}
+				let features_flag = format!("features: {}", features.join(","));
+				if !features.is_empty() {
+					flags.push(&features_flag);
+				}
 				let flags_str = if flags.is_empty() {
 					String::new()
 				} else {
@@ -207,9 +317,23 @@ pub fn run_skelebuild(
 					indices
 				));
 			}
+
+			let mut added_deps = Vec::new();
+			for (template, deps) in dependencies {
+				added_deps.extend(push_dependency_targets(&mut state.entries, &template, deps));
+			}
+			if !added_deps.is_empty() {
+				should_rebuild = true;
+				action_summary = Some(format!(
+					"{}\nAdded {} referenced types: {}",
+					action_summary.unwrap_or_default(),
+					added_deps.len(),
+					added_deps.join(", ")
+				));
+			}
 		}
 		Some(SkeleAction::AddRaw { spec }) => {
-			let raw = parse_raw_source_spec(&spec)?;
+			let raw = parse_raw_source_spec(&spec, ripdoc)?;
 			let already_present = state.entries.iter().any(|e| match e {
 				SkeleEntry::RawSource(existing) => existing == &raw,
 				_ => false,
@@ -249,7 +373,7 @@ pub fn run_skelebuild(
 			let mut added_indices: Vec<usize> = Vec::new();
 
 			for spec in specs {
-				let raw = parse_raw_source_spec(&spec)?;
+				let raw = parse_raw_source_spec(&spec, ripdoc)?;
 				let exists = state.entries.iter().any(|e| match e {
 					SkeleEntry::RawSource(existing) => existing == &raw,
 					_ => false,
@@ -289,11 +413,20 @@ pub fn run_skelebuild(
 				));
 			}
 		}
-		Some(SkeleAction::AddChangedResolved { targets, raw_specs }) => {
+		Some(SkeleAction::AddChangedResolved {
+			targets,
+			raw_specs,
+			with_notes,
+			preamble,
+		}) => {
+			let preamble_added = preamble.is_some();
+			if let Some(content) = preamble {
+				state.entries.push(SkeleEntry::Injection(SkeleInjection { content }));
+			}
 			let mut added_targets: Vec<String> = Vec::new();
 			let mut already_targets: Vec<String> = Vec::new();
 			for target in targets {
-				let normalized_target = normalize_target_spec_for_storage(&target);
+				let normalized_target = normalize_target_spec_for_storage(&target, ripdoc);
 				let is_present = state.entries.iter().any(|e| match e {
 					SkeleEntry::Target(t) => t.path == normalized_target,
 					_ => false,
@@ -308,28 +441,27 @@ pub fn run_skelebuild(
 					implementation: true,
 					raw_source: false,
 					private: true,
+					features: Vec::new(),
+					all_features: false,
+					no_default_features: false,
+					note: None,
 				}));
 			}
 
-			let mut added_raw: Vec<SkeleRawSource> = Vec::new();
-			let mut already_raw: Vec<SkeleRawSource> = Vec::new();
-			for spec in raw_specs {
-				let raw = parse_raw_source_spec(&spec)?;
-				let exists = state.entries.iter().any(|e| match e {
-					SkeleEntry::RawSource(existing) => existing == &raw,
-					_ => false,
-				});
-				if exists {
-					already_raw.push(raw);
-					continue;
-				}
-				added_raw.push(raw.clone());
-				state.entries.push(SkeleEntry::RawSource(raw));
-			}
+			let (added_raw, already_raw) =
+				push_changed_raw_sources(&mut state.entries, raw_specs, with_notes, ripdoc)?;
 
-			should_rebuild = config_changed || !added_targets.is_empty() || !added_raw.is_empty();
+			should_rebuild = config_changed
+				|| preamble_added
+				|| !added_targets.is_empty()
+				|| !added_raw.is_empty();
+			let notes_summary = if with_notes {
+				format!(", {} note slots", added_raw.len())
+			} else {
+				String::new()
+			};
 			action_summary = Some(format!(
-				"Added changed-context: {} targets ({} already), {} raw snippets ({} already)",
+				"Added changed-context: {} targets ({} already), {} raw snippets ({} already){notes_summary}",
 				added_targets.len(),
 				already_targets.len(),
 				added_raw.len(),
@@ -362,11 +494,11 @@ pub fn run_skelebuild(
 				state.entries.insert(index, injection);
 				format!("Injected commentary at index {index}.")
 			} else if let Some(spec) = before_target {
-				let index = find_entry_match(&state.entries, &spec)?;
+				let index = find_entry_match(&state.entries, &spec, ripdoc)?;
 				state.entries.insert(index, injection);
 				format!("Injected commentary before entry #{index}.")
 			} else if let Some(spec) = after_target {
-				let index = find_entry_match(&state.entries, &spec)?;
+				let index = find_entry_match(&state.entries, &spec, ripdoc)?;
 				let insert_at = index + 1;
 				state.entries.insert(insert_at, injection);
 				format!("Injected commentary after entry #{index}.")
@@ -428,45 +560,154 @@ pub fn run_skelebuild(
 			spec,
 			implementation,
 			raw_source,
+			file,
+			start_line,
+			end_line,
+			to_raw,
+			to_target,
 		}) => {
-			let index = find_target_match(&state.entries, &spec)?;
-			let entry = state.entries.get_mut(index).ok_or_else(|| {
+			let index = find_update_match(&state.entries, &spec, ripdoc)?;
+			let entry = state.entries.get(index).cloned().ok_or_else(|| {
 				RipdocError::InvalidTarget(format!("Invalid entry index {index}"))
 			})?;
-			let SkeleEntry::Target(target) = entry else {
-				return Err(RipdocError::InvalidTarget(format!(
-					"Entry #{index} matched '{spec}' but is not a target",
-				)));
-			};
+			let edits_range = file.is_some() || start_line.is_some() || end_line.is_some();
 
-			let prev_impl = target.implementation;
-			let prev_raw_source = target.raw_source;
-			if let Some(value) = implementation {
-				target.implementation = value;
-			}
-			if let Some(value) = raw_source {
-				target.raw_source = value;
-			}
+			let (updated, changes) = match entry {
+				SkeleEntry::Target(_) if to_target.is_some() => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} matched '{spec}' and is already a target",
+					)));
+				}
+				SkeleEntry::Target(target) if to_raw => {
+					let info = validate_add_target_or_error(&target, ripdoc, true)?;
+					let Some((file_path, start, end)) = info.span_range else {
+						return Err(RipdocError::InvalidTarget(format!(
+							"No source span recorded for '{}'; use `--file` with `--start/--end` after adding it via `add-raw`",
+							target.path
+						)));
+					};
+					let raw = SkeleRawSource {
+						canonical_key: compute_canonical_key(&file_path),
+						file: file_path,
+						start_line: Some(start),
+						end_line: Some(end),
+						note: target.note.clone(),
+					};
+					let raw = update_raw_range(raw, file.as_deref(), start_line, end_line, ripdoc)?;
+					(SkeleEntry::RawSource(raw), vec!["target -> raw".to_string()])
+				}
+				SkeleEntry::Target(_) if edits_range => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} is a target; `--file/--start/--end` apply to raw sources (add `--to-raw` to convert it)",
+					)));
+				}
+				SkeleEntry::Target(mut target) => {
+					let prev_impl = target.implementation;
+					let prev_raw_source = target.raw_source;
+					if let Some(value) = implementation {
+						target.implementation = value;
+					}
+					if let Some(value) = raw_source {
+						target.raw_source = value;
+					}
+					let mut changes = Vec::new();
+					if target.implementation != prev_impl {
+						changes.push(if target.implementation { "+impl" } else { "-impl" }.to_string());
+					}
+					if target.raw_source != prev_raw_source {
+						changes.push(if target.raw_source { "+raw" } else { "-raw" }.to_string());
+					}
+					(SkeleEntry::Target(target), changes)
+				}
+				SkeleEntry::RawSource(_) if to_raw => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} matched '{spec}' and is already a raw source",
+					)));
+				}
+				SkeleEntry::RawSource(_) if to_target.is_some() && edits_range => {
+					return Err(RipdocError::InvalidTarget(
+						"`--to-target` cannot be combined with `--file/--start/--end`".to_string(),
+					));
+				}
+				SkeleEntry::RawSource(raw) if to_target.is_some() => {
+					let target_spec = to_target.unwrap_or_default();
+					let target = SkeleTarget {
+						path: normalize_target_spec_for_storage(&target_spec, ripdoc),
+						implementation: implementation.unwrap_or(true),
+						raw_source: raw_source.unwrap_or(false),
+						private: true,
+						features: Vec::new(),
+						all_features: false,
+						no_default_features: false,
+						note: raw.note,
+					};
+					validate_add_target_or_error(&target, ripdoc, false)?;
+					(SkeleEntry::Target(target), vec!["raw -> target".to_string()])
+				}
+				SkeleEntry::RawSource(_) if implementation.is_some() || raw_source.is_some() => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} is a raw source; `--implementation/--raw-source` apply to targets (add `--to-target <PATH>` to convert it)",
+					)));
+				}
+				SkeleEntry::RawSource(raw) => {
+					let updated = update_raw_range(raw.clone(), file.as_deref(), start_line, end_line, ripdoc)?;
+					let mut changes = Vec::new();
+					if updated.file != raw.file {
+						changes.push("file".to_string());
+					}
+					if (updated.start_line, updated.end_line) != (raw.start_line, raw.end_line) {
+						changes.push(match (updated.start_line, updated.end_line) {
+							(Some(start), Some(end)) => format!("lines {start}-{end}"),
+							_ => "whole file".to_string(),
+						});
+					}
+					(SkeleEntry::RawSource(updated), changes)
+				}
+				SkeleEntry::Injection(_) => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} matched '{spec}' but is an injection",
+					)));
+				}
+			};
 
-			let changed =
-				target.implementation != prev_impl || target.raw_source != prev_raw_source;
+			let label = match &updated {
+				SkeleEntry::Target(target) => target.path.clone(),
+				SkeleEntry::RawSource(raw) => raw_source_summary(raw),
+				SkeleEntry::Injection(injection) => injection.content.clone(),
+			};
+			let changed = !changes.is_empty();
+			state.entries[index] = updated;
 			should_rebuild = config_changed || changed;
 			action_summary = Some(if changed {
-				let mut changes = Vec::new();
-				if target.implementation != prev_impl {
-					changes.push(if target.implementation {
-						"+impl"
-					} else {
-						"-impl"
-					});
+				format!("Updated #{index}: {label} [{}]", changes.join(", "))
+			} else {
+				format!("No change: #{index} {label}")
+			});
+		}
+		Some(SkeleAction::Annotate { spec, note }) => {
+			let index = find_update_match(&state.entries, &spec, ripdoc)?;
+			let note = note
+				.map(|note| note.trim().to_string())
+				.filter(|note| !note.is_empty());
+			let (slot, label) = match &mut state.entries[index] {
+				SkeleEntry::Target(target) => (&mut target.note, target.path.clone()),
+				SkeleEntry::RawSource(raw) => {
+					let label = raw_source_summary(raw);
+					(&mut raw.note, label)
 				}
-				if target.raw_source != prev_raw_source {
-					changes.push(if target.raw_source { "+raw" } else { "-raw" });
+				SkeleEntry::Injection(_) => {
+					return Err(RipdocError::InvalidTarget(format!(
+						"Entry #{index} matched '{spec}' but is an injection; injections cannot carry notes",
+					)));
 				}
-				format!("Updated #{index}: {} [{}]", target.path, changes.join(", "))
-			} else {
-				format!("No change: #{index} {}", target.path)
+			};
+			let changed = *slot != note;
+			action_summary = Some(match &note {
+				Some(note) => format!("Annotated #{index}: {label} ({note})"),
+				None => format!("Cleared note on #{index}: {label}"),
 			});
+			*slot = note;
+			should_rebuild = config_changed || (changed && state.render_notes);
 		}
 		Some(SkeleAction::Remove(target_str)) => {
 			let before_len = state.entries.len();
@@ -486,26 +727,71 @@ pub fn run_skelebuild(
 			});
 		}
 		Some(SkeleAction::Reset) => {
-			// Preserve output path and plain setting from previous state unless overridden.
+			// Preserve output path, plain setting, note rendering, budget, and sinks from previous
+			// state unless overridden.
 			let prev_output = state.output_path.clone();
 			let prev_plain = state.plain;
+			let prev_budget = state.budget;
+			let prev_render_notes = state.render_notes;
+			let prev_sinks = std::mem::take(&mut state.sinks);
 			state = SkeleState::default();
 			state.output_path = output.clone().or(prev_output);
 			state.plain = plain.unwrap_or(prev_plain);
+			state.budget = prev_budget;
+			state.render_notes = prev_render_notes;
+			state.sinks = prev_sinks;
 			should_rebuild = true;
-			action_summary =
-				Some("State reset (entries cleared, output/plain preserved).".to_string());
+			action_summary = Some(
+				"State reset (entries cleared, output/plain/budget/sinks preserved).".to_string(),
+			);
 		}
-		Some(SkeleAction::Preview) => {
-			let rendered = state.build_output(ripdoc)?;
-			print!("{rendered}");
-			state.save()?;
+		Some(SkeleAction::Preview { json }) => {
+			if json {
+				let preview = state.build_budgeted_preview(ripdoc)?;
+				writeln!(out, "{}", serde_json::to_string_pretty(&preview)?)?;
+			} else {
+				write!(out, "{}", state.build_budgeted_output(ripdoc)?)?;
+			}
+			save(&state)?;
 			return Ok(());
 		}
 		Some(SkeleAction::Rebuild) => {
 			should_rebuild = true;
 			action_summary = Some("Rebuilt output.".to_string());
 		}
+		Some(SkeleAction::Budget(budget)) => {
+			should_rebuild = config_changed || state.budget != budget;
+			action_summary = Some(match &budget {
+				Some(budget) => format!(
+					"Budget set: {} {} (on exceed: {})",
+					budget.limit,
+					budget.unit.label(),
+					budget.on_exceed.label()
+				),
+				None => "Budget cleared.".to_string(),
+			});
+			state.budget = budget;
+		}
+		Some(SkeleAction::Sinks(sinks)) => {
+			let mut resolved = Vec::with_capacity(sinks.len());
+			for sink in sinks {
+				resolved.push(match sink {
+					SinkSpec::File { path } if path.is_relative() => SinkSpec::File {
+						path: std::path::absolute(ripdoc.in_working_dir(&path))?,
+					},
+					other => other,
+				});
+			}
+			// Sinks only see the next explicit rebuild, so changing them does not rebuild by itself.
+			should_rebuild = config_changed;
+			action_summary = Some(if resolved.is_empty() {
+				"Sinks cleared; output goes to the output file only.".to_string()
+			} else {
+				let labels: Vec<String> = resolved.iter().map(ToString::to_string).collect();
+				format!("Sinks set: {}", labels.join(", "))
+			});
+			state.sinks = resolved;
+		}
 		Some(SkeleAction::Status { keys }) => {
 			// Status is read-only, but if config changed we should rebuild.
 			if config_changed && !state.entries.is_empty() {
@@ -540,7 +826,7 @@ pub fn run_skelebuild(
 						continue;
 					}
 
-					println!("{}  {}  {}", idx, entry_type, key);
+					writeln!(out, "{}  {}  {}", idx, entry_type, key)?;
 				}
 				return Ok(());
 			}
@@ -553,15 +839,44 @@ pub fn run_skelebuild(
 		}
 	}
 
+	// Save first, so a failed rebuild never loses the edit that triggered it.
+	save(&state)?;
 	if should_rebuild {
-		state.rebuild(ripdoc)?;
+		let lock_path = lock_path_for(
+			&state
+				.output_path
+				.clone()
+				.unwrap_or_else(|| ripdoc.in_working_dir("skeleton.md")),
+		);
+		// Recording the lock resolves every target again, which may query the registry, so
+		// it is only computed to be verified or saved.
+		let lock = if locked || explicit_rebuild {
+			Some(SkeleLock::for_state(&state, ripdoc))
+		} else {
+			None
+		};
+		if locked && let Some(lock) = &lock {
+			lock.verify(&lock_path)?;
+		}
+		let rebuilt = if explicit_rebuild {
+			state.rebuild(ripdoc)
+		} else {
+			state.rebuild_output(ripdoc)
+		};
+		match rebuilt {
+			// The output file was written; only extra destinations failed.
+			Err(RipdocError::Sink(failures)) => eprintln!("Warning: {failures}"),
+			other => other?,
+		}
+		if !locked && let Some(lock) = &lock {
+			lock.save(&lock_path)?;
+		}
 	}
-	state.save()?;
 
 	let output_path = state
 		.output_path
 		.clone()
-		.unwrap_or_else(|| PathBuf::from("skeleton.md"));
+		.unwrap_or_else(|| ripdoc.in_working_dir("skeleton.md"));
 
 	// Count lines in output file for summary
 	let output_lines = std::fs::read_to_string(&output_path)
@@ -570,17 +885,34 @@ pub fn run_skelebuild(
 
 	let show_full_state = show_state_on_exit;
 	if show_full_state {
-		println!("Skeleton state:");
-		println!(
+		writeln!(out, "Skeleton state:")?;
+		writeln!(
+			out,
 			"  State file: {}",
-			state::SkeleState::state_file().display()
-		);
-		println!(
+			state::SkeleState::state_file(ripdoc).display()
+		)?;
+		writeln!(
+			out,
 			"  Output: {} ({} lines)",
 			output_path.display(),
 			output_lines
-		);
-		println!("  Entries: {}", state.entries.len());
+		)?;
+		if let Some(budget) = &state.budget {
+			writeln!(
+				out,
+				"  Budget: {} {} (on exceed: {})",
+				budget.limit,
+				budget.unit.label(),
+				budget.on_exceed.label()
+			)?;
+		}
+		if state.render_notes {
+			writeln!(out, "  Notes: rendered as HTML comments")?;
+		}
+		for sink in &state.sinks {
+			writeln!(out, "  Sink: {sink}")?;
+		}
+		writeln!(out, "  Entries: {}", state.entries.len())?;
 		for (idx, e) in state.entries.iter().enumerate() {
 			match e {
 				SkeleEntry::Target(t) => {
@@ -595,12 +927,14 @@ pub fn run_skelebuild(
 					if !t.private {
 						flags.push("public");
 					}
+					let feature_flags = t.feature_flags();
+					flags.extend(feature_flags.iter().map(String::as_str));
 					let flags_str = if flags.is_empty() {
 						String::new()
 					} else {
 						format!(" [{}]", flags.join(", "))
 					};
-					println!("    {idx}: {}{flags_str}", t.path)
+					writeln!(out, "    {idx}: {}{flags_str}", t.path)?;
 				}
 				SkeleEntry::Injection(i) => {
 					let trimmed = i.content.trim();
@@ -610,27 +944,37 @@ pub fn run_skelebuild(
 					} else {
 						compact
 					};
-					println!("    {idx}: [inject] \"{summary}\"");
+					writeln!(out, "    {idx}: [inject] \"{summary}\"")?;
 				}
 				SkeleEntry::RawSource(raw) => {
-					println!("    {idx}: [raw] {}", raw_source_summary(raw));
+					writeln!(out, "    {idx}: [raw] {}", raw_source_summary(raw))?;
 				}
 			}
+			if let Some(note) = e.note() {
+				writeln!(out, "        note: {note}")?;
+			}
 		}
-	} else if let Some(summary) = action_summary {
-		println!(
-			"{summary} (output: {}, entries: {}, lines: {})",
-			output_path.display(),
-			state.entries.len(),
-			output_lines
-		);
 	} else {
-		println!(
-			"Output: {} (entries: {}, lines: {})",
-			output_path.display(),
-			state.entries.len(),
-			output_lines
-		);
+		let summary = match action_summary {
+			Some(summary) => format!(
+				"{summary} (output: {}, entries: {}, lines: {})",
+				output_path.display(),
+				state.entries.len(),
+				output_lines
+			),
+			None => format!(
+				"Output: {} (entries: {}, lines: {})",
+				output_path.display(),
+				state.entries.len(),
+				output_lines
+			),
+		};
+		// Keep stdout to the document itself when it was just printed there.
+		if should_rebuild && explicit_rebuild && state.sinks.contains(&SinkSpec::Stdout) {
+			eprintln!("{summary}");
+		} else {
+			writeln!(out, "{summary}")?;
+		}
 	}
 
 	Ok(())
@@ -651,7 +995,139 @@ fn raw_source_summary(raw: &SkeleRawSource) -> String {
 	}
 }
 
-fn parse_raw_source_spec(spec: &str) -> Result<SkeleRawSource> {
+/// Empty reviewer-notes heading labelled with the snippet's file and line range.
+fn reviewer_notes_content(raw: &SkeleRawSource) -> String {
+	let file = raw
+		.canonical_key
+		.clone()
+		.unwrap_or_else(|| raw.file.display().to_string());
+	match (raw.start_line, raw.end_line) {
+		(Some(start), Some(end)) => format!("## Reviewer notes: `{file}` (lines {start}-{end})\n"),
+		_ => format!("## Reviewer notes: `{file}`\n"),
+	}
+}
+
+/// Append a raw source for each `file:start:end` spec not already present, each followed by an
+/// empty reviewer-notes injection when `with_notes` is set. Returns the added and the already
+/// present raw sources.
+fn push_changed_raw_sources(
+	entries: &mut Vec<SkeleEntry>,
+	raw_specs: Vec<String>,
+	with_notes: bool,
+	ripdoc: &Ripdoc,
+) -> Result<(Vec<SkeleRawSource>, Vec<SkeleRawSource>)> {
+	let mut added = Vec::new();
+	let mut already = Vec::new();
+	for spec in raw_specs {
+		let raw = parse_raw_source_spec(&spec, ripdoc)?;
+		let exists = entries.iter().any(|e| match e {
+			SkeleEntry::RawSource(existing) => existing == &raw,
+			_ => false,
+		});
+		if exists {
+			already.push(raw);
+			continue;
+		}
+		added.push(raw.clone());
+		let note = with_notes.then(|| reviewer_notes_content(&raw));
+		entries.push(SkeleEntry::RawSource(raw));
+		if let Some(content) = note {
+			entries.push(SkeleEntry::Injection(SkeleInjection { content }));
+		}
+	}
+	Ok((added, already))
+}
+
+/// Append a target for each dependency spec not already present, copying the flags of the
+/// target that referenced it. Returns the specs that were added.
+fn push_dependency_targets(
+	entries: &mut Vec<SkeleEntry>,
+	template: &SkeleTarget,
+	dependencies: Vec<String>,
+) -> Vec<String> {
+	let mut added = Vec::new();
+	for path in dependencies {
+		let is_present = entries.iter().any(|entry| match entry {
+			SkeleEntry::Target(target) => target.path == path,
+			_ => false,
+		});
+		if is_present {
+			continue;
+		}
+		entries.push(SkeleEntry::Target(SkeleTarget {
+			path: path.clone(),
+			note: None,
+			..template.clone()
+		}));
+		added.push(path);
+	}
+	added
+}
+
+/// Find the entry `update` should modify: a raw source by its exact `file:start:end` summary
+/// first (so several snippets of one file stay addressable), then anything `find_entry_match`
+/// accepts.
+fn find_update_match(entries: &[SkeleEntry], spec: &str, ripdoc: &Ripdoc) -> Result<usize> {
+	let spec = spec.trim();
+	let by_summary: Vec<usize> = entries
+		.iter()
+		.enumerate()
+		.filter_map(|(idx, entry)| match entry {
+			SkeleEntry::RawSource(raw) if raw_source_summary(raw) == spec => Some(idx),
+			_ => None,
+		})
+		.collect();
+	if let [index] = by_summary[..] {
+		return Ok(index);
+	}
+	find_entry_match(entries, spec, ripdoc)
+}
+
+/// Apply `--file/--start/--end` to a raw source. A missing bound keeps the current one, falling
+/// back to the first or last line of the file when the entry previously covered the whole file.
+fn update_raw_range(
+	mut raw: SkeleRawSource,
+	file: Option<&str>,
+	start_line: Option<usize>,
+	end_line: Option<usize>,
+	ripdoc: &Ripdoc,
+) -> Result<SkeleRawSource> {
+	if let Some(file) = file {
+		let path = normalize_file_path(file, ripdoc)?;
+		if !path.exists() {
+			return Err(RipdocError::InvalidTarget(format!(
+				"Raw source file not found: {}",
+				path.display()
+			)));
+		}
+		raw.canonical_key = compute_canonical_key(&path);
+		raw.file = path;
+	}
+	if start_line.is_none() && end_line.is_none() {
+		return Ok(raw);
+	}
+
+	let start = start_line.or(raw.start_line).unwrap_or(1);
+	let end = match end_line.or(raw.end_line) {
+		Some(end) => end,
+		None => std::fs::read_to_string(&raw.file)?.lines().count(),
+	};
+	if start == 0 || end == 0 {
+		return Err(RipdocError::InvalidTarget(
+			"Raw source line numbers are 1-based (must be >= 1)".to_string(),
+		));
+	}
+	if start > end {
+		return Err(RipdocError::InvalidTarget(format!(
+			"Raw source line range is invalid: start ({start}) > end ({end})",
+		)));
+	}
+	raw.start_line = Some(start);
+	raw.end_line = Some(end);
+	Ok(raw)
+}
+
+fn parse_raw_source_spec(spec: &str, ripdoc: &Ripdoc) -> Result<SkeleRawSource> {
 	let trimmed = spec.trim();
 	if trimmed.is_empty() {
 		return Err(RipdocError::InvalidTarget(
@@ -663,13 +1139,14 @@ fn parse_raw_source_spec(spec: &str) -> Result<SkeleRawSource> {
 		None => (trimmed, None, None),
 		Some((maybe_path, last)) => {
 			let Ok(last_num) = last.parse::<usize>() else {
-				let file = normalize_file_path(trimmed)?;
+				let file = normalize_file_path(trimmed, ripdoc)?;
 				let canonical_key = compute_canonical_key(&file);
 				return Ok(SkeleRawSource {
 					file,
 					canonical_key,
 					start_line: None,
 					end_line: None,
+					note: None,
 				});
 			};
 			match maybe_path.rsplit_once(':') {
@@ -682,7 +1159,7 @@ fn parse_raw_source_spec(spec: &str) -> Result<SkeleRawSource> {
 		}
 	};
 
-	let file = normalize_file_path(path_part)?;
+	let file = normalize_file_path(path_part, ripdoc)?;
 	if !file.exists() {
 		return Err(RipdocError::InvalidTarget(format!(
 			"Raw source file not found: {}",
@@ -710,13 +1187,14 @@ fn parse_raw_source_spec(spec: &str) -> Result<SkeleRawSource> {
 		canonical_key,
 		start_line,
 		end_line,
+		note: None,
 	})
 }
 
-fn normalize_file_path(path_str: &str) -> Result<PathBuf> {
+fn normalize_file_path(path_str: &str, ripdoc: &Ripdoc) -> Result<PathBuf> {
 	let path = PathBuf::from(path_str);
 	let abs = if path.is_relative() {
-		std::path::absolute(&path).map_err(|err| {
+		std::path::absolute(ripdoc.in_working_dir(&path)).map_err(|err| {
 			RipdocError::InvalidTarget(format!(
 				"Failed to resolve raw source path '{}': {err}",
 				path.display()
@@ -731,9 +1209,10 @@ fn normalize_file_path(path_str: &str) -> Result<PathBuf> {
 /// Compute a canonical repo-root-relative path key for a file.
 /// Returns None if not in a git repo or if the file is outside the repo.
 fn compute_canonical_key(abs_path: &std::path::Path) -> Option<String> {
-	// Try to find git root
+	// Try to find the root of the git repository holding the file
 	let git_root = std::process::Command::new("git")
 		.args(["rev-parse", "--show-toplevel"])
+		.current_dir(abs_path.parent()?)
 		.output()
 		.ok()?;
 
@@ -754,3 +1233,57 @@ fn compute_canonical_key(abs_path: &std::path::Path) -> Option<String> {
 	let rel_str = rel.to_str()?;
 	Some(rel_str.replace('\\', "/"))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn update_raw_range_keeps_missing_bounds() {
+		let ripdoc = Ripdoc::new();
+		let dir = tempfile::TempDir::new().unwrap();
+		let file = dir.path().join("lib.rs");
+		std::fs::write(&file, "one\ntwo\nthree\nfour\n").unwrap();
+		let whole = SkeleRawSource {
+			file: file.clone(),
+			canonical_key: None,
+			start_line: None,
+			end_line: None,
+			note: None,
+		};
+
+		let tail = update_raw_range(whole.clone(), None, Some(2), None, &ripdoc).unwrap();
+		assert_eq!((tail.start_line, tail.end_line), (Some(2), Some(4)));
+
+		let narrowed = update_raw_range(tail, None, None, Some(3), &ripdoc).unwrap();
+		assert_eq!((narrowed.start_line, narrowed.end_line), (Some(2), Some(3)));
+
+		assert!(update_raw_range(whole.clone(), None, Some(4), Some(3), &ripdoc).is_err());
+		assert_eq!(update_raw_range(whole.clone(), None, None, None, &ripdoc).unwrap(), whole);
+	}
+
+	#[test]
+	fn changed_raw_sources_are_each_followed_by_reviewer_notes() {
+		let ripdoc = Ripdoc::new();
+		let dir = tempfile::TempDir::new().unwrap();
+		let file = dir.path().join("lib.rs");
+		std::fs::write(&file, "one\ntwo\nthree\nfour\n").unwrap();
+		let specs = vec![format!("{}:1:2", file.display()), format!("{}:3:4", file.display())];
+
+		let mut entries = Vec::new();
+		let (added, already) = push_changed_raw_sources(&mut entries, specs.clone(), true, &ripdoc).unwrap();
+		assert_eq!((added.len(), already.len()), (2, 0));
+		assert_eq!(entries.len(), 4);
+		for (pair, lines) in entries.chunks(2).zip(["lines 1-2", "lines 3-4"]) {
+			let [SkeleEntry::RawSource(_), SkeleEntry::Injection(note)] = pair else {
+				panic!("expected a raw source followed by a note, got {pair:?}");
+			};
+			assert!(note.content.starts_with("## Reviewer notes: "), "{}", note.content);
+			assert!(note.content.contains(lines), "{}", note.content);
+		}
+
+		let (added, already) = push_changed_raw_sources(&mut entries, specs, true, &ripdoc).unwrap();
+		assert_eq!((added.len(), already.len()), (0, 2));
+		assert_eq!(entries.len(), 4);
+	}
+}
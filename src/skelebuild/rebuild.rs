@@ -1,14 +1,22 @@
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use super::SkeleGroup;
+use super::{FeatureKey, SkeleGroup};
 use super::resolver::{resolve_best_path_match, resolve_impl_target};
-use super::state::{SkeleEntry, SkeleRawSource, SkeleState};
+use super::sink::{FileSink, SinkSpec, SkeleSink};
+use super::state::{
+	BudgetAction, PreviewSegment, PreviewSegmentKind, SkeleBudget, SkeleEntry, SkeleRawSource,
+	SkelePreview, SkeleState,
+};
 use crate::cargo_utils::resolve_target;
+use crate::core_api::error::RipdocError;
 use crate::core_api::search::{SearchIndex, SearchItemKind, SearchResult, build_render_selection};
 use crate::core_api::{Result, Ripdoc};
 use crate::render::Renderer;
+use crate::render::utils::source_label;
 
 pub fn ensure_markdown_block_sep(out: &mut String) {
 	if out.is_empty() {
@@ -24,6 +32,51 @@ pub fn ensure_markdown_block_sep(out: &mut String) {
 	}
 }
 
+/// Code-fence language tag for a raw source file, based on its name and extension.
+///
+/// Unknown file types are tagged `text` so they are not highlighted as Rust.
+fn fence_language(path: &Path) -> &'static str {
+	let file_name = path
+		.file_name()
+		.and_then(|name| name.to_str())
+		.unwrap_or_default();
+	match file_name {
+		"Dockerfile" | "Containerfile" => return "dockerfile",
+		"Makefile" | "GNUmakefile" | "makefile" => return "make",
+		"justfile" | "Justfile" => return "just",
+		"Cargo.lock" => return "toml",
+		_ => {}
+	}
+	let extension = path
+		.extension()
+		.and_then(|ext| ext.to_str())
+		.map(str::to_ascii_lowercase)
+		.unwrap_or_default();
+	match extension.as_str() {
+		"rs" => "rust",
+		"toml" => "toml",
+		"sql" => "sql",
+		"proto" => "protobuf",
+		"json" => "json",
+		"yaml" | "yml" => "yaml",
+		"md" | "markdown" => "markdown",
+		"sh" | "bash" | "zsh" => "bash",
+		"py" => "python",
+		"js" | "mjs" | "cjs" => "javascript",
+		"ts" => "typescript",
+		"c" | "h" => "c",
+		"cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+		"go" => "go",
+		"html" | "htm" => "html",
+		"css" => "css",
+		"xml" => "xml",
+		"graphql" | "gql" => "graphql",
+		"nix" => "nix",
+		"wgsl" => "wgsl",
+		_ => "text",
+	}
+}
+
 fn render_raw_source(out: &mut String, raw: &SkeleRawSource) -> Result<()> {
 	let content = fs::read_to_string(&raw.file)?;
 	let lines: Vec<&str> = content.lines().collect();
@@ -33,10 +86,11 @@ fn render_raw_source(out: &mut String, raw: &SkeleRawSource) -> Result<()> {
 		_ => (1usize, lines.len().max(1)),
 	};
 
+	let language = fence_language(&raw.file);
 	let total_lines = lines.len();
 	if total_lines == 0 {
 		out.push_str(&format!(
-			"### Raw source: {}\n\n```rust\n```\n",
+			"### Raw source: {}\n\n```{language}\n```\n",
 			raw.file.display()
 		));
 		return Ok(());
@@ -64,7 +118,7 @@ fn render_raw_source(out: &mut String, raw: &SkeleRawSource) -> Result<()> {
 		"### Raw source: {}:{start_line}:{end_line}\n\n",
 		raw.file.display()
 	));
-	out.push_str("```rust\n");
+	out.push_str(&format!("```{language}\n"));
 	for (idx, line) in lines[(start_line - 1)..end_line].iter().enumerate() {
 		out.push_str(line);
 		if idx + 1 != end_line - (start_line - 1) {
@@ -75,19 +129,199 @@ fn render_raw_source(out: &mut String, raw: &SkeleRawSource) -> Result<()> {
 	Ok(())
 }
 
+/// Reductions applied to a build when the output is over budget.
+#[derive(Debug, Clone, Copy, Default)]
+struct BuildOptions {
+	/// Keep only the first paragraph of each doc comment.
+	truncate_docs: bool,
+	/// Render targets as if `--implementation` and `--raw-source` were off.
+	drop_implementations: bool,
+}
+
+impl BuildOptions {
+	fn for_action(action: BudgetAction) -> Option<Self> {
+		match action {
+			BudgetAction::Error => None,
+			BudgetAction::TruncateDocs => Some(Self {
+				truncate_docs: true,
+				..Self::default()
+			}),
+			BudgetAction::DropImplementations => Some(Self {
+				drop_implementations: true,
+				..Self::default()
+			}),
+		}
+	}
+}
+
+/// Cut every doc comment in the crate down to its first paragraph.
+fn truncate_crate_docs(crate_data: &mut rustdoc_types::Crate) {
+	for item in crate_data.index.values_mut() {
+		let Some(docs) = item.docs.as_mut() else {
+			continue;
+		};
+		let summary_len = docs
+			.lines()
+			.take_while(|line| !line.trim().is_empty())
+			.map(|line| line.len() + 1)
+			.sum::<usize>()
+			.min(docs.len());
+		if summary_len < docs.len() {
+			docs.truncate(summary_len);
+			let trimmed = docs.trim_end().len();
+			docs.truncate(trimmed);
+		}
+	}
+}
+
+/// An entry note as an HTML comment, invisible in rendered Markdown.
+fn note_comment(note: &str) -> String {
+	format!("<!-- note: {} -->", note.trim().replace("-->", "-- >"))
+}
+
+/// Short label for an entry in the budget report.
+fn entry_label(entry: &SkeleEntry) -> String {
+	match entry {
+		SkeleEntry::Target(target) => target.path.clone(),
+		SkeleEntry::RawSource(raw) => format!("[raw] {}", super::raw_source_summary(raw)),
+		SkeleEntry::Injection(injection) => {
+			let first = injection.content.trim().lines().next().unwrap_or_default();
+			if first.chars().count() > 50 {
+				format!("[inject] {}...", first.chars().take(47).collect::<String>())
+			} else {
+				format!("[inject] {first}")
+			}
+		}
+	}
+}
+
 impl SkeleState {
 	/// Build the final markdown output without writing it.
 	pub fn build_output(&self, ripdoc: &Ripdoc) -> Result<String> {
-		// Pre-load all crates to avoid redundant work.
-		let mut crates_data: HashMap<PathBuf, rustdoc_types::Crate> = HashMap::new();
+		self.build_output_with(ripdoc, BuildOptions::default())
+	}
+
+	/// Build the output and enforce the configured budget, if any.
+	///
+	/// Output over budget is rebuilt with the budget's reduction applied; if it still does not
+	/// fit (or the budget action is `error`), a [`RipdocError::BudgetExceeded`] carrying a
+	/// per-entry size report is returned.
+	pub fn build_budgeted_output(&self, ripdoc: &Ripdoc) -> Result<String> {
+		self.build_budgeted_preview(ripdoc).map(|preview| preview.output)
+	}
+
+	/// Like [`Self::build_budgeted_output`], but also reports which byte range of the output
+	/// each entry produced.
+	pub fn build_budgeted_preview(&self, ripdoc: &Ripdoc) -> Result<SkelePreview> {
+		let preview = self.build_preview_with(ripdoc, BuildOptions::default())?;
+		let Some(budget) = self.budget else {
+			return Ok(preview);
+		};
+		if budget.fits(&preview.output) {
+			return Ok(preview);
+		}
+
+		let (output, options) = match BuildOptions::for_action(budget.on_exceed) {
+			Some(options) => {
+				let reduced = self.build_preview_with(ripdoc, options)?;
+				if budget.fits(&reduced.output) {
+					eprintln!(
+						"Note: output exceeded the budget of {} {}; applied {}.",
+						budget.limit,
+						budget.unit.label(),
+						budget.on_exceed.label()
+					);
+					return Ok(reduced);
+				}
+				(reduced.output, options)
+			}
+			None => (preview.output, BuildOptions::default()),
+		};
+		Err(RipdocError::BudgetExceeded(
+			self.budget_report(ripdoc, &budget, &output, options),
+		))
+	}
+
+	/// Describe how far `output` is over budget and which entries contribute most.
+	fn budget_report(
+		&self,
+		ripdoc: &Ripdoc,
+		budget: &SkeleBudget,
+		output: &str,
+		options: BuildOptions,
+	) -> String {
+		let unit = budget.unit.label();
+		let mut sizes: Vec<(usize, String, usize)> = self
+			.entries
+			.iter()
+			.enumerate()
+			.map(|(idx, entry)| {
+				let single = SkeleState {
+					output_path: None,
+					entries: vec![entry.clone()],
+					plain: self.plain,
+					budget: None,
+					render_notes: self.render_notes,
+					sinks: Vec::new(),
+				};
+				let size = single
+					.build_output_with(ripdoc, options)
+					.map(|rendered| budget.measure(&rendered))
+					.unwrap_or(0);
+				(idx, entry_label(entry), size)
+			})
+			.collect();
+		sizes.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+
+		let mut report = format!(
+			"Skeleton output is {} {unit}, over the budget of {} {unit}",
+			budget.measure(output),
+			budget.limit
+		);
+		if budget.on_exceed != BudgetAction::Error {
+			report.push_str(&format!(" even with {}", budget.on_exceed.label()));
+		}
+		report.push_str(".\nApproximate size per entry (largest first):\n");
+		let width = sizes.iter().map(|(_, _, size)| size.to_string().len()).max().unwrap_or(0);
+		for (idx, label, size) in &sizes {
+			report.push_str(&format!("  {size:>width$} {unit}  #{idx} {label}\n"));
+		}
+		report.push_str(
+			"Shrink the skeleton with `skelebuild remove` or `skelebuild update --no-implementation`, \
+			 or adjust the limit with `skelebuild budget`.",
+		);
+		report
+	}
+
+	fn build_output_with(&self, ripdoc: &Ripdoc, options: BuildOptions) -> Result<String> {
+		self.build_preview_with(ripdoc, options).map(|preview| preview.output)
+	}
+
+	fn build_preview_with(&self, ripdoc: &Ripdoc, options: BuildOptions) -> Result<SkelePreview> {
+		// Pre-load all crates to avoid redundant work. A crate is loaded once per feature set.
+		let mut crates_data: HashMap<(PathBuf, FeatureKey), Arc<rustdoc_types::Crate>> = HashMap::new();
 
 		// Group sequential targets of the same crate to avoid redundant headers and choppy output.
+		// `group_sources` records, per group, what kind of section it renders and which entries
+		// it covers.
 		let mut grouped_entries: Vec<SkeleGroup> = Vec::new();
+		let mut group_sources: Vec<(PreviewSegmentKind, Vec<usize>)> = Vec::new();
 		let mut had_errors = false;
-		for entry in &self.entries {
+		for (entry_idx, entry) in self.entries.iter().enumerate() {
+			if self.render_notes
+				&& let Some(note) = entry.note()
+			{
+				grouped_entries.push(SkeleGroup::Injection(note_comment(note)));
+				group_sources.push((PreviewSegmentKind::Note, vec![entry_idx]));
+			}
 			match entry {
 				SkeleEntry::Target(t) => {
-					let resolved = match resolve_target(&t.path, ripdoc.offline()) {
+					let mut t = t.clone();
+					if options.drop_implementations {
+						t.implementation = false;
+						t.raw_source = false;
+					}
+					let resolved = match resolve_target(&t.path, &ripdoc.resolve_context()) {
 						Ok(r) => r,
 						Err(err) => {
 							had_errors = true;
@@ -95,19 +329,24 @@ impl SkeleState {
 							continue;
 						}
 					};
+					let features = t.feature_key();
 					for rt in resolved {
 						let pkg_root = rt.package_root().to_path_buf();
-						if !crates_data.contains_key(&pkg_root) {
+						let crate_key = (pkg_root.clone(), features.clone());
+						if let Entry::Vacant(slot) = crates_data.entry(crate_key) {
 							match rt.read_crate(
-								false,
-								false,
-								vec![],
+								t.no_default_features,
+								t.all_features,
+								t.features.clone(),
 								true,
-								ripdoc.silent(),
+								ripdoc.verbosity(),
 								ripdoc.cache_config(),
 							) {
-								Ok(data) => {
-									crates_data.insert(pkg_root.clone(), data);
+								Ok(mut data) => {
+									if options.truncate_docs {
+										truncate_crate_docs(Arc::make_mut(&mut data));
+									}
+									slot.insert(data);
 								}
 								Err(err) => {
 									had_errors = true;
@@ -122,46 +361,64 @@ impl SkeleState {
 
 						if let Some(SkeleGroup::Targets {
 							pkg_root: last_root,
+							features: last_features,
 							targets,
 						}) = grouped_entries.last_mut()
 							&& *last_root == pkg_root
+							&& *last_features == features
 						{
 							targets.push(t.clone());
+							if let Some((_, entries)) = group_sources.last_mut()
+								&& entries.last() != Some(&entry_idx)
+							{
+								entries.push(entry_idx);
+							}
 							continue;
 						}
 						grouped_entries.push(SkeleGroup::Targets {
-							pkg_root: pkg_root.clone(),
+							pkg_root,
+							features: features.clone(),
 							targets: vec![t.clone()],
 						});
+						group_sources.push((PreviewSegmentKind::Targets, vec![entry_idx]));
 					}
 				}
 				SkeleEntry::Injection(i) => {
 					grouped_entries.push(SkeleGroup::Injection(i.content.clone()));
+					group_sources.push((PreviewSegmentKind::Injection, vec![entry_idx]));
 				}
 				SkeleEntry::RawSource(raw) => {
 					grouped_entries.push(SkeleGroup::RawSource(raw.clone()));
+					group_sources.push((PreviewSegmentKind::RawSource, vec![entry_idx]));
 				}
 			}
 		}
 
 		let mut final_output = String::new();
+		let mut segments = Vec::new();
 		let mut last_file: Option<PathBuf> = None;
 
-		for group in grouped_entries {
+		for (group, (kind, entries)) in grouped_entries.into_iter().zip(group_sources) {
+			ensure_markdown_block_sep(&mut final_output);
+			let start = final_output.len();
 			match group {
 				SkeleGroup::Injection(content) => {
-					ensure_markdown_block_sep(&mut final_output);
 					final_output.push_str(&content);
 					ensure_markdown_block_sep(&mut final_output);
+					// Anything between target groups starts a new file section.
+					last_file = None;
 				}
 				SkeleGroup::RawSource(raw) => {
-					ensure_markdown_block_sep(&mut final_output);
 					render_raw_source(&mut final_output, &raw)?;
 					ensure_markdown_block_sep(&mut final_output);
+					last_file = None;
 				}
-				SkeleGroup::Targets { pkg_root, targets } => {
-					ensure_markdown_block_sep(&mut final_output);
-					let crate_data = crates_data.get(&pkg_root).unwrap();
+				SkeleGroup::Targets {
+					pkg_root,
+					features,
+					targets,
+				} => {
+					let crate_data = crates_data.get(&(pkg_root.clone(), features)).unwrap();
 					let mut full_source = HashSet::new();
 					let mut raw_files = HashSet::new();
 					let mut selection_results: Vec<SearchResult> = Vec::new();
@@ -247,7 +504,6 @@ impl SkeleState {
 								if let Some((ty_match, impl_id)) = resolve_impl_target(
 									&index,
 									crate_data,
-									crate_name.as_deref(),
 									&pkg_root,
 									&base_query,
 									is_local,
@@ -336,11 +592,9 @@ impl SkeleState {
 						match fs::read_to_string(&abs_path) {
 							Ok(content) => {
 								wrote_raw_files = true;
-								final_output.push_str(&format!(
-									"// ripdoc:source: {}\n\n{}\n\n",
-									file_path.display(),
-									content
-								));
+								final_output.push_str(&source_label(&file_path, Some(&pkg_root)));
+								final_output.push_str(&format!("{content}\n\n"));
+								last_file = None;
 							}
 							Err(err) => {
 								had_errors = true;
@@ -368,6 +622,15 @@ impl SkeleState {
 						.with_selection(selection)
 						.with_source_root(pkg_root.clone())
 						.with_plain(self.plain)
+						.with_collapsed_variants(ripdoc.collapse_variants())
+						.with_derive_style(ripdoc.derive_style())
+						.with_simplified_lifetimes(ripdoc.simplify_lifetimes())
+						.with_markdown_options(ripdoc.markdown_options())
+						.with_doc_options(ripdoc.doc_options())
+						.with_min_visibility(ripdoc.min_visibility())
+						.with_strict_public(ripdoc.strict_public())
+						.with_cfg_annotations(ripdoc.cfg_annotations())
+						.with_doc_hidden_items(ripdoc.doc_hidden())
 						.with_current_file(last_file.clone());
 
 					let (rendered, final_file) = renderer.render_ext(crate_data)?;
@@ -375,21 +638,62 @@ impl SkeleState {
 					final_output.push_str(&rendered);
 				}
 			}
+			segments.push(PreviewSegment {
+				entries,
+				kind,
+				start,
+				end: final_output.len(),
+				estimated_tokens: final_output[start..].chars().count().div_ceil(4),
+			});
 		}
 
 		if had_errors {
 			eprintln!("Completed with errors; output may be incomplete.");
 		}
-		Ok(final_output)
+		Ok(SkelePreview {
+			output: final_output,
+			segments,
+		})
 	}
 
-	/// Rebuilds the skeleton file from scratch using all stored entries.
+	/// Rebuilds the skeleton file from scratch using all stored entries, then delivers it to the
+	/// configured sinks.
+	///
+	/// The output file is written first; sink failures are returned afterwards as
+	/// [`RipdocError::Sink`], so callers can report them without treating the rebuild as failed.
 	pub fn rebuild(&self, ripdoc: &Ripdoc) -> Result<()> {
-		let output_path = self
-			.output_path
+		let sinks: Vec<Box<dyn SkeleSink>> = self.sinks.iter().map(SinkSpec::open).collect();
+		let output = self.rebuilt_output(ripdoc)?;
+		FileSink(self.output_file(ripdoc)).write(&output)?;
+		deliver(&output, &sinks)
+	}
+
+	/// Rebuilds the skeleton file from scratch without delivering it to the configured sinks.
+	///
+	/// Used for the rebuild that follows every edit, so sinks such as URLs only receive explicit
+	/// rebuilds.
+	pub fn rebuild_output(&self, ripdoc: &Ripdoc) -> Result<()> {
+		let output = self.rebuilt_output(ripdoc)?;
+		FileSink(self.output_file(ripdoc)).write(&output)
+	}
+
+	/// Rebuilds the output from scratch and writes it to each of `sinks` in order.
+	///
+	/// Every sink is attempted; failures are reported together once all have run.
+	pub fn rebuild_to(&self, ripdoc: &Ripdoc, sinks: &[Box<dyn SkeleSink>]) -> Result<()> {
+		let output = self.rebuilt_output(ripdoc)?;
+		deliver(&output, sinks)
+	}
+
+	fn output_file(&self, ripdoc: &Ripdoc) -> PathBuf {
+		self.output_path
 			.clone()
-			.unwrap_or_else(|| PathBuf::from("skeleton.md"));
-		let output = self.build_output(ripdoc)?;
+			.unwrap_or_else(|| ripdoc.in_working_dir("skeleton.md"))
+	}
+
+	/// The rebuilt document, with a warning when target entries produced almost nothing.
+	fn rebuilt_output(&self, ripdoc: &Ripdoc) -> Result<String> {
+		let output = self.build_budgeted_output(ripdoc)?;
 
 		// Warn if entries exist but output is empty or nearly empty
 		let target_count = self
@@ -412,8 +716,22 @@ impl SkeleState {
 				"  - Incorrect module paths (use `ripdoc list --search <name> --private` to discover exact paths)"
 			);
 		}
+		Ok(output)
+	}
+}
 
-		fs::write(&output_path, output)?;
+/// Write `output` to every sink, collecting failures into one [`RipdocError::Sink`].
+fn deliver(output: &str, sinks: &[Box<dyn SkeleSink>]) -> Result<()> {
+	let failures: Vec<String> = sinks
+		.iter()
+		.filter_map(|sink| {
+			let err = sink.write(output).err()?;
+			Some(format!("Failed to write output to {}: {err}", sink.label()))
+		})
+		.collect();
+	if failures.is_empty() {
 		Ok(())
+	} else {
+		Err(RipdocError::Sink(failures.join("\n")))
 	}
 }
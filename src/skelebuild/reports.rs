@@ -0,0 +1,501 @@
+//! Parsers for cargo output used to seed skelebuild context.
+//!
+//! `cargo test --message-format json` interleaves cargo's JSON messages with the plain-text test
+//! harness output (or libtest JSON events with `-Z unstable-options --format json`). Both forms are
+//! accepted: failing test names and panic locations are collected from whichever appears.
+//!
+//! `cargo check --message-format json` emits one `compiler-message` per diagnostic; its primary
+//! spans and the spans of its notes are collected.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+/// `thread 'name' panicked at src/lib.rs:10:5:` (and the pre-1.73 `at 'msg', src/lib.rs:10:5`).
+static PANIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+	Regex::new(r"^thread '([^']*)' panicked at (?:'(.*)', )?(.+?):([0-9]+):([0-9]+):?$")
+		.expect("valid panic pattern")
+});
+
+/// `test tests::name ... FAILED`
+static FAILED_REGEX: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r"^test (.+?) \.\.\. FAILED$").expect("valid failed-test pattern"));
+
+/// `---- tests::name stdout ----`
+static SECTION_REGEX: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r"^---- (.+?) stdout ----$").expect("valid stdout section pattern"));
+
+/// Doctest names: `src/lib.rs - module::Item (line 12)`.
+static DOCTEST_REGEX: Lazy<Regex> = Lazy::new(|| {
+	Regex::new(r"^(.+?) - (.+?) \(line ([0-9]+)\)$").expect("valid doctest name pattern")
+});
+
+/// A line range in a source file, as reported by cargo (paths may be relative).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceRange {
+	/// File path as written in the report.
+	pub file: PathBuf,
+	/// First line (1-based).
+	pub start_line: usize,
+	/// Last line (1-based, inclusive).
+	pub end_line: usize,
+}
+
+impl SourceRange {
+	/// A single-line range.
+	pub fn line(file: impl Into<PathBuf>, line: usize) -> Self {
+		Self {
+			file: file.into(),
+			start_line: line,
+			end_line: line,
+		}
+	}
+}
+
+/// A failing test with the locations its output points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFailure {
+	/// Test name as printed by the harness (e.g. `tests::parses_input`).
+	pub name: String,
+	/// First line of the panic message, when one was printed.
+	pub message: Option<String>,
+	/// Panic locations (and the documented line for doctests).
+	pub locations: Vec<SourceRange>,
+}
+
+/// Failing tests and the packages they were built from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestReport {
+	/// Failing tests in the order they were reported.
+	pub failures: Vec<TestFailure>,
+	/// Directories of packages whose test targets were built, from cargo's artifact messages.
+	pub package_roots: Vec<PathBuf>,
+}
+
+/// Parse the output of `cargo test --message-format json`.
+pub fn parse_test_report(text: &str) -> TestReport {
+	let mut failures: BTreeMap<String, TestFailure> = BTreeMap::new();
+	let mut order: Vec<String> = Vec::new();
+	let mut package_roots: Vec<PathBuf> = Vec::new();
+	let mut section: Option<String> = None;
+	let mut pending_message: Option<String> = None;
+	for line in text.lines() {
+		let trimmed = line.trim_end();
+		if trimmed.starts_with('{')
+			&& let Ok(message) = serde_json::from_str::<Value>(trimmed)
+		{
+			match message.get("reason").and_then(Value::as_str) {
+				Some("compiler-artifact") => {
+					let is_test = message
+						.pointer("/profile/test")
+						.and_then(Value::as_bool)
+						.unwrap_or(false);
+					if is_test
+						&& let Some(dir) = message
+							.get("manifest_path")
+							.and_then(Value::as_str)
+							.and_then(|manifest| Path::new(manifest).parent())
+						&& !package_roots.iter().any(|root| root == dir)
+					{
+						package_roots.push(dir.to_path_buf());
+					}
+				}
+				_ if message.get("type").and_then(Value::as_str) == Some("test")
+					&& message.get("event").and_then(Value::as_str) == Some("failed") =>
+				{
+					let Some(name) = message.get("name").and_then(Value::as_str) else {
+						continue;
+					};
+					let output = message.get("stdout").and_then(Value::as_str).unwrap_or("");
+					let panics = parse_panics(output);
+					let failure = record(&mut failures, &mut order, name);
+					for (_, location, panic_message) in panics {
+						push_location(failure, location, panic_message);
+					}
+				}
+				_ => {}
+			}
+			continue;
+		}
+
+		if let Some(captures) = FAILED_REGEX.captures(trimmed) {
+			record(&mut failures, &mut order, &captures[1]);
+			continue;
+		}
+		if let Some(captures) = SECTION_REGEX.captures(trimmed) {
+			section = Some(captures[1].to_string());
+			continue;
+		}
+		if let Some(panic) = parse_panic_line(trimmed) {
+			let (thread, location, message) = panic;
+			let owner = section.clone().unwrap_or(thread);
+			pending_message = message.is_none().then_some(owner.clone());
+			push_location(record(&mut failures, &mut order, &owner), location, message);
+			continue;
+		}
+		// New-style panics print the message on the line after the location.
+		if let Some(owner) = pending_message.take()
+			&& !trimmed.is_empty()
+			&& let Some(failure) = failures.get_mut(&owner)
+			&& failure.message.is_none()
+		{
+			failure.message = Some(trimmed.trim().to_string());
+		}
+	}
+
+	TestReport {
+		failures: order
+			.into_iter()
+			.filter_map(|name| failures.remove(&name))
+			.collect(),
+		package_roots,
+	}
+}
+
+/// The failure entry for `name`, created (in report order) on first sight.
+fn record<'a>(
+	failures: &'a mut BTreeMap<String, TestFailure>,
+	order: &mut Vec<String>,
+	name: &str,
+) -> &'a mut TestFailure {
+	if !failures.contains_key(name) {
+		order.push(name.to_string());
+	}
+	failures
+		.entry(name.to_string())
+		.or_insert_with(|| TestFailure {
+			name: name.to_string(),
+			message: None,
+			locations: doctest_location(name).into_iter().collect(),
+		})
+}
+
+/// Panics in a block of captured test output, with the message line following each location.
+fn parse_panics(output: &str) -> Vec<(String, SourceRange, Option<String>)> {
+	let mut panics = Vec::new();
+	let mut lines = output.lines().peekable();
+	while let Some(line) = lines.next() {
+		let Some((thread, location, message)) = parse_panic_line(line.trim_end()) else {
+			continue;
+		};
+		let message = message.or_else(|| {
+			lines
+				.peek()
+				.map(|next| next.trim())
+				.filter(|next| !next.is_empty())
+				.map(str::to_string)
+		});
+		panics.push((thread, location, message));
+	}
+	panics
+}
+
+fn parse_panic_line(line: &str) -> Option<(String, SourceRange, Option<String>)> {
+	let captures = PANIC_REGEX.captures(line)?;
+	let line_number = captures[4].parse().ok()?;
+	Some((
+		captures[1].to_string(),
+		SourceRange::line(&captures[3], line_number),
+		captures.get(2).map(|message| message.as_str().to_string()),
+	))
+}
+
+fn push_location(failure: &mut TestFailure, location: SourceRange, message: Option<String>) {
+	if failure.message.is_none() {
+		failure.message = message;
+	}
+	if !failure.locations.contains(&location) {
+		failure.locations.push(location);
+	}
+}
+
+/// The documented line of a doctest, from a name like `src/lib.rs - Item (line 12)`.
+fn doctest_location(name: &str) -> Option<SourceRange> {
+	let captures = DOCTEST_REGEX.captures(name)?;
+	Some(SourceRange::line(&captures[1], captures[3].parse().ok()?))
+}
+
+/// A compiler error or warning with the source ranges it points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerDiagnostic {
+	/// Severity (`error`, `warning`, ...).
+	pub level: String,
+	/// Diagnostic code such as `E0308`, when present.
+	pub code: Option<String>,
+	/// Main message.
+	pub message: String,
+	/// Primary spans first, then the spans of related notes and help messages.
+	pub spans: Vec<SourceRange>,
+	/// Directory of the package the diagnostic was emitted for.
+	pub package_root: Option<PathBuf>,
+}
+
+/// Parse the output of `cargo check --message-format json` (or `cargo build`/`clippy`).
+///
+/// Diagnostics without source spans (such as "aborting due to previous error") are dropped, as are
+/// duplicates emitted once per target.
+pub fn parse_diagnostics(text: &str) -> Vec<CompilerDiagnostic> {
+	let mut diagnostics: Vec<CompilerDiagnostic> = Vec::new();
+	for line in text.lines() {
+		let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+			continue;
+		};
+		if value.get("reason").and_then(Value::as_str) != Some("compiler-message") {
+			continue;
+		}
+		let Some(message) = value.get("message") else {
+			continue;
+		};
+
+		let mut primary = Vec::new();
+		let mut related = Vec::new();
+		collect_spans(message, &mut primary, &mut related);
+		let mut spans = Vec::new();
+		for span in primary.into_iter().chain(related) {
+			if !spans.contains(&span) {
+				spans.push(span);
+			}
+		}
+		if spans.is_empty() {
+			continue;
+		}
+
+		let diagnostic = CompilerDiagnostic {
+			level: message
+				.get("level")
+				.and_then(Value::as_str)
+				.unwrap_or("error")
+				.to_string(),
+			code: message
+				.pointer("/code/code")
+				.and_then(Value::as_str)
+				.map(str::to_string),
+			message: message
+				.get("message")
+				.and_then(Value::as_str)
+				.unwrap_or_default()
+				.to_string(),
+			spans,
+			package_root: value
+				.get("manifest_path")
+				.and_then(Value::as_str)
+				.and_then(|manifest| Path::new(manifest).parent())
+				.map(Path::to_path_buf),
+		};
+		if !diagnostics.contains(&diagnostic) {
+			diagnostics.push(diagnostic);
+		}
+	}
+	diagnostics
+}
+
+/// Spans of a diagnostic and (recursively) its children, split into primary and related ones.
+///
+/// Spans inside the standard library or macro definitions outside the workspace are skipped.
+fn collect_spans(message: &Value, primary: &mut Vec<SourceRange>, related: &mut Vec<SourceRange>) {
+	for span in message
+		.get("spans")
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+	{
+		let file = span.get("file_name").and_then(Value::as_str).unwrap_or("");
+		let start = span.get("line_start").and_then(Value::as_u64);
+		let end = span.get("line_end").and_then(Value::as_u64);
+		let (Some(start), Some(end)) = (start, end) else {
+			continue;
+		};
+		if file.is_empty() || file.starts_with('<') || file.starts_with("/rustc/") {
+			continue;
+		}
+		let range = SourceRange {
+			file: PathBuf::from(file),
+			start_line: start as usize,
+			end_line: end.max(start) as usize,
+		};
+		if span.get("is_primary").and_then(Value::as_bool).unwrap_or(false) {
+			primary.push(range);
+		} else {
+			related.push(range);
+		}
+	}
+	for child in message
+		.get("children")
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+	{
+		// Notes and help are context even when their own spans are marked primary.
+		let mut child_spans = Vec::new();
+		collect_spans(child, &mut child_spans, related);
+		related.extend(child_spans);
+	}
+}
+
+/// Resolve a path from cargo output against the given roots and their ancestors.
+///
+/// Cargo reports paths relative to the workspace root, which may be any ancestor of a package
+/// directory. Returns `None` when the file cannot be found.
+pub fn resolve_report_path(path: &Path, roots: &[PathBuf]) -> Option<PathBuf> {
+	if path.is_absolute() {
+		return path.is_file().then(|| path.to_path_buf());
+	}
+	roots
+		.iter()
+		.flat_map(|root| root.ancestors())
+		.map(|dir| dir.join(path))
+		.find(|candidate| candidate.is_file())
+		.map(|found| found.canonicalize().unwrap_or(found))
+}
+
+/// Find the definition of a test function by name below `roots`.
+///
+/// The last path segment is the function name; files whose path mentions the other segments (a
+/// `tests` module, an integration test file) are preferred. The returned range spans the function
+/// up to its closing brace at the same indentation.
+pub fn find_test_definition(name: &str, roots: &[PathBuf]) -> Option<SourceRange> {
+	let segments: Vec<&str> = name.split("::").collect();
+	let function = *segments.last()?;
+	let pattern = Regex::new(&format!(
+		r"^([ \t]*)(?:pub(?:\([^)]*\))?[ \t]+)?(?:async[ \t]+)?fn[ \t]+{}[ \t]*[(<]",
+		regex::escape(function)
+	))
+	.ok()?;
+
+	let mut files = Vec::new();
+	for root in roots {
+		collect_rust_files(root, &mut files);
+	}
+	files.sort();
+	files.dedup();
+
+	let mut best: Option<(usize, SourceRange)> = None;
+	for file in files {
+		let Ok(content) = fs::read_to_string(&file) else {
+			continue;
+		};
+		let lines: Vec<&str> = content.lines().collect();
+		for (idx, line) in lines.iter().enumerate() {
+			let Some(captures) = pattern.captures(line) else {
+				continue;
+			};
+			let indent = &captures[1];
+			let end = lines[idx + 1..]
+				.iter()
+				.position(|candidate| {
+					candidate.starts_with(indent) && candidate[indent.len()..].starts_with('}')
+				})
+				.map_or(idx + 1, |offset| idx + 2 + offset);
+			let path = file.to_string_lossy();
+			let score = segments[..segments.len() - 1]
+				.iter()
+				.filter(|segment| path.contains(**segment) || content.contains(&format!("mod {segment}")))
+				.count();
+			if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+				best = Some((
+					score,
+					SourceRange {
+						file: file.clone(),
+						start_line: idx + 1,
+						end_line: end,
+					},
+				));
+			}
+		}
+	}
+	best.map(|(_, range)| range)
+}
+
+/// Rust files below `dir`, skipping build output and hidden directories.
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return;
+	};
+	for entry in entries.flatten() {
+		let path = entry.path();
+		let name = entry.file_name();
+		let name = name.to_string_lossy();
+		if path.is_dir() {
+			if name != "target" && !name.starts_with('.') {
+				collect_rust_files(&path, files);
+			}
+		} else if name.ends_with(".rs") {
+			files.push(path);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_plain_and_json_test_output() {
+		let text = r#"{"reason":"compiler-artifact","manifest_path":"/work/demo/Cargo.toml","profile":{"test":true}}
+running 3 tests
+test tests::adds ... ok
+test tests::parses ... FAILED
+test tests::legacy ... FAILED
+
+failures:
+
+---- tests::parses stdout ----
+thread 'tests::parses' panicked at src/parser.rs:42:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+
+---- tests::legacy stdout ----
+thread 'tests::legacy' panicked at 'boom', tests/legacy.rs:7:5
+{ "type": "test", "event": "failed", "name": "src/lib.rs - Widget::new (line 30)", "stdout": "thread 'main' panicked at src/lib.rs:35:1:\nexplicit panic\n" }
+"#;
+		let report = parse_test_report(text);
+		assert_eq!(report.package_roots, vec![PathBuf::from("/work/demo")]);
+		let names: Vec<&str> = report.failures.iter().map(|failure| failure.name.as_str()).collect();
+		assert_eq!(names, ["tests::parses", "tests::legacy", "src/lib.rs - Widget::new (line 30)"]);
+
+		let parses = &report.failures[0];
+		assert_eq!(parses.message.as_deref(), Some("assertion `left == right` failed"));
+		assert_eq!(parses.locations, [SourceRange::line("src/parser.rs", 42)]);
+
+		let legacy = &report.failures[1];
+		assert_eq!(legacy.message.as_deref(), Some("boom"));
+		assert_eq!(legacy.locations, [SourceRange::line("tests/legacy.rs", 7)]);
+
+		let doctest = &report.failures[2];
+		assert_eq!(doctest.message.as_deref(), Some("explicit panic"));
+		assert_eq!(
+			doctest.locations,
+			[SourceRange::line("src/lib.rs", 30), SourceRange::line("src/lib.rs", 35)]
+		);
+	}
+
+	#[test]
+	fn parses_compiler_messages_with_related_spans() {
+		let message = r#"{"reason":"compiler-message","manifest_path":"/work/demo/Cargo.toml","message":{"level":"error","code":{"code":"E0308"},"message":"mismatched types","spans":[{"file_name":"src/lib.rs","line_start":12,"line_end":12,"is_primary":true},{"file_name":"src/lib.rs","line_start":10,"line_end":10,"is_primary":false}],"children":[{"level":"note","message":"function defined here","spans":[{"file_name":"src/util.rs","line_start":3,"line_end":5,"is_primary":true}],"children":[]}]}}"#;
+		let aborting = r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","spans":[],"children":[]}}"#;
+		let text = format!("{message}\n{aborting}\n{message}\n{{\"reason\":\"build-finished\",\"success\":false}}\n");
+
+		let diagnostics = parse_diagnostics(&text);
+		assert_eq!(diagnostics.len(), 1);
+		let diagnostic = &diagnostics[0];
+		assert_eq!(diagnostic.code.as_deref(), Some("E0308"));
+		assert_eq!(diagnostic.package_root.as_deref(), Some(Path::new("/work/demo")));
+		assert_eq!(
+			diagnostic.spans,
+			[
+				SourceRange::line("src/lib.rs", 12),
+				SourceRange::line("src/lib.rs", 10),
+				SourceRange {
+					file: PathBuf::from("src/util.rs"),
+					start_line: 3,
+					end_line: 5,
+				},
+			]
+		);
+	}
+}
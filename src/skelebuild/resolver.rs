@@ -1,8 +1,12 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use super::state::SkeleEntry;
+use rustdoc_types::{Crate, Id, Item, ItemEnum, ItemKind, StructKind, VariantKind};
+
+use super::state::{SkeleEntry, SkeleTarget};
 use crate::cargo_utils::resolve_target;
 use crate::core_api::error::RipdocError;
+use crate::core_api::externs::collect_item_refs;
 use crate::core_api::search::{
 	SearchDomain, SearchIndex, SearchItemKind, SearchOptions, SearchResult,
 };
@@ -17,13 +21,17 @@ pub struct ValidatedTargetInfo {
 	pub source_location: Option<String>,
 	/// Best-effort span line count when available.
 	pub span_line_count: Option<usize>,
+	/// Absolute file and 1-based inclusive line range of the matched item, when known.
+	pub span_range: Option<(PathBuf, usize, usize)>,
+	/// Target specs for local types the matched item references (empty unless requested).
+	pub dependencies: Vec<String>,
 }
 
 /// Normalize a target specification for persistent storage.
 ///
-/// If the target is a relative path, it is converted to an absolute path to ensure
-/// the state remains valid even if ripdoc is executed from a different directory later.
-pub fn normalize_target_spec_for_storage(target: &str) -> String {
+/// If the target is a relative path, it is resolved against the working directory of `ripdoc`
+/// so the state remains valid even if ripdoc is executed from a different directory later.
+pub fn normalize_target_spec_for_storage(target: &str, ripdoc: &Ripdoc) -> String {
 	let parsed = crate::cargo_utils::target::Target::parse(target);
 	let Ok(parsed) = parsed else {
 		return target.to_string();
@@ -31,7 +39,7 @@ pub fn normalize_target_spec_for_storage(target: &str) -> String {
 	match parsed.entrypoint {
 		crate::cargo_utils::target::Entrypoint::Path(path) => {
 			let abs = if path.is_relative() {
-				match std::path::absolute(&path) {
+				match std::path::absolute(ripdoc.in_working_dir(&path)) {
 					Ok(abs) => abs,
 					Err(_) => return target.to_string(),
 				}
@@ -135,7 +143,6 @@ pub fn resolve_best_path_match(
 pub fn resolve_impl_target(
 	index: &SearchIndex,
 	crate_data: &rustdoc_types::Crate,
-	crate_name: Option<&str>,
 	pkg_root: &Path,
 	base_query: &str,
 	is_local: impl Fn(&SearchResult) -> bool,
@@ -147,6 +154,10 @@ pub fn resolve_impl_target(
 		return None;
 	}
 
+	let crate_name = crate_data
+		.index
+		.get(&crate_data.root)
+		.and_then(|root| root.name.as_deref());
 	let ty_match = resolve_best_path_match(
 		index,
 		crate_name,
@@ -183,9 +194,7 @@ pub fn resolve_impl_target(
 	});
 	let trait_match = trait_results.first()?.clone();
 
-	let Some(ty_item) = crate_data.index.get(&ty_match.item_id) else {
-		return None;
-	};
+	let ty_item = crate_data.index.get(&ty_match.item_id)?;
 	let impl_ids: Vec<rustdoc_types::Id> = match &ty_item.inner {
 		rustdoc_types::ItemEnum::Struct(struct_) => struct_.impls.clone(),
 		rustdoc_types::ItemEnum::Enum(enum_) => enum_.impls.clone(),
@@ -209,19 +218,33 @@ pub fn resolve_impl_target(
 	None
 }
 
-/// Validate that a target specification can be resolved against its crate.
+/// Validate that a target can be resolved against its crate, built with the target's features.
 pub fn validate_add_target_or_error(
-	target_spec: &str,
+	target: &SkeleTarget,
+	ripdoc: &Ripdoc,
+	strict: bool,
+) -> Result<ValidatedTargetInfo> {
+	validate_target_with_deps(target, ripdoc, strict, 0)
+}
+
+/// Like [`validate_add_target_or_error`], also collecting the local types the matched item
+/// references, following references up to `dep_depth` hops.
+pub fn validate_target_with_deps(
+	target: &SkeleTarget,
 	ripdoc: &Ripdoc,
-	include_private: bool,
 	strict: bool,
+	dep_depth: usize,
 ) -> Result<ValidatedTargetInfo> {
+	let target_spec = target.path.as_str();
+	let include_private = target.private;
 	let parsed = crate::cargo_utils::target::Target::parse(target_spec)?;
 	if parsed.path.is_empty() {
 		return Ok(ValidatedTargetInfo {
 			matched_path: target_spec.to_string(),
 			source_location: None,
 			span_line_count: None,
+			span_range: None,
+			dependencies: Vec::new(),
 		});
 	}
 
@@ -232,18 +255,18 @@ pub fn validate_add_target_or_error(
 		crate::cargo_utils::target::Entrypoint::Path(_) => parsed.path.join("::"),
 	};
 
-	let resolved = resolve_target(target_spec, ripdoc.offline())
+	let resolved = resolve_target(target_spec, &ripdoc.resolve_context())
 		.map_err(|err| RipdocError::InvalidTarget(format!("{err}")))?;
 	let rt = resolved
 		.first()
 		.ok_or_else(|| RipdocError::InvalidTarget("No resolved targets".to_string()))?;
 	let pkg_root = rt.package_root().to_path_buf();
 	let crate_data = rt.read_crate(
-		false,
-		false,
-		vec![],
+		target.no_default_features,
+		target.all_features,
+		target.features.clone(),
 		true,
-		ripdoc.silent(),
+		ripdoc.verbosity(),
 		ripdoc.cache_config(),
 	)?;
 	let index = SearchIndex::build(&crate_data, true, Some(&pkg_root));
@@ -292,7 +315,6 @@ pub fn validate_add_target_or_error(
 	} else if let Some((_ty_match, impl_id)) = resolve_impl_target(
 		&index,
 		&crate_data,
-		crate_name.as_deref(),
 		&pkg_root,
 		&base_query,
 		is_local,
@@ -305,43 +327,42 @@ pub fn validate_add_target_or_error(
 
 	// If no match and query starts with something other than crate name,
 	// try replacing first segment with "crate" (unless --strict is set)
-	if matched_id.is_none() && !strict {
-		if let Some((first, rest)) = base_query.split_once("::") {
-			if let Some(ref actual_crate) = crate_name {
-				if first != actual_crate && first != "crate" {
-					let crate_query = format!("crate::{}", rest);
-
-					if let Some(best) = resolve_best_path_match(
-						&index,
-						crate_name.as_deref(),
-						&pkg_root,
-						&crate_query,
-						is_local,
-						include_private,
-						silent,
-					) {
-						matched_path = Some(best.path_string);
-						matched_id = Some(best.item_id);
-						if !silent {
-							eprintln!("Interpreted `{}` as `{}`", base_query, crate_query);
-						}
-					} else if let Some((_ty_match, impl_id)) = resolve_impl_target(
-						&index,
-						&crate_data,
-						crate_name.as_deref(),
-						&pkg_root,
-						&crate_query,
-						is_local,
-						include_private,
-						silent,
-					) {
-						matched_path = Some(crate_query.clone());
-						matched_id = Some(impl_id);
-						if !silent {
-							eprintln!("Interpreted `{}` as `{}`", base_query, crate_query);
-						}
-					}
-				}
+	if matched_id.is_none()
+		&& !strict
+		&& let Some((first, rest)) = base_query.split_once("::")
+		&& let Some(ref actual_crate) = crate_name
+		&& first != actual_crate
+		&& first != "crate"
+	{
+		let crate_query = format!("crate::{}", rest);
+
+		if let Some(best) = resolve_best_path_match(
+			&index,
+			crate_name.as_deref(),
+			&pkg_root,
+			&crate_query,
+			is_local,
+			include_private,
+			silent,
+		) {
+			matched_path = Some(best.path_string);
+			matched_id = Some(best.item_id);
+			if !silent {
+				eprintln!("Interpreted `{}` as `{}`", base_query, crate_query);
+			}
+		} else if let Some((_ty_match, impl_id)) = resolve_impl_target(
+			&index,
+			&crate_data,
+			&pkg_root,
+			&crate_query,
+			is_local,
+			include_private,
+			silent,
+		) {
+			matched_path = Some(crate_query.clone());
+			matched_id = Some(impl_id);
+			if !silent {
+				eprintln!("Interpreted `{}` as `{}`", base_query, crate_query);
 			}
 		}
 	}
@@ -392,37 +413,117 @@ pub fn validate_add_target_or_error(
 	};
 	let matched_path = matched_path.unwrap_or_else(|| base_query.clone());
 
-	let (source_location, span_line_count) = crate_data
+	let (source_location, span_line_count, span_range) = crate_data
 		.index
 		.get(&matched_id)
 		.and_then(|item| item.span.as_ref())
 		.map(|span| {
-			let mut display_path = span.filename.clone();
-			if display_path.is_relative() {
-				display_path = resolve_span_path(span);
+			let mut abs_path = span.filename.clone();
+			if abs_path.is_relative() {
+				abs_path = resolve_span_path(span);
 			}
-			let display_path = display_path
+			let display_path = abs_path
 				.strip_prefix(&pkg_root)
 				.map(|p| p.display().to_string())
-				.unwrap_or_else(|_| display_path.display().to_string());
+				.unwrap_or_else(|_| abs_path.display().to_string());
 			let begin_line = span.begin.0;
 			let end_line = span.end.0;
-			let line_count = if begin_line > 0 && end_line >= begin_line {
-				Some(end_line - begin_line + 1)
+			let (line_count, range) = if begin_line > 0 && end_line >= begin_line {
+				(Some(end_line - begin_line + 1), Some((abs_path, begin_line, end_line)))
 			} else {
-				None
+				(None, None)
 			};
-			(Some(format!("{display_path}:{begin_line}")), line_count)
+			(Some(format!("{display_path}:{begin_line}")), line_count, range)
 		})
-		.unwrap_or((None, None));
+		.unwrap_or((None, None, None));
+
+	let entrypoint = target_spec.split("::").next().unwrap_or(target_spec);
+	let dependencies = collect_type_dependencies(&crate_data, &matched_id, dep_depth)
+		.into_iter()
+		.filter_map(|path| match &parsed.entrypoint {
+			crate::cargo_utils::target::Entrypoint::Name { .. } => path
+				.split_once("::")
+				.map(|(_, rest)| format!("{entrypoint}::{rest}")),
+			crate::cargo_utils::target::Entrypoint::Path(_) => Some(format!("{entrypoint}::{path}")),
+		})
+		.collect();
 
 	Ok(ValidatedTargetInfo {
 		matched_path,
 		source_location,
 		span_line_count,
+		span_range,
+		dependencies,
 	})
 }
 
+/// Canonical paths of local types (structs, enums, unions, traits, and type aliases) that `root`
+/// references, breadth-first up to `depth` hops. Fields, variants, and trait items are followed
+/// as part of their parent.
+fn collect_type_dependencies(crate_data: &Crate, root: &Id, depth: usize) -> Vec<String> {
+	let mut seen: HashSet<Id> = HashSet::from([*root]);
+	let mut frontier = vec![*root];
+	let mut found = Vec::new();
+	for _ in 0..depth {
+		let mut next = Vec::new();
+		for id in &frontier {
+			let Some(item) = crate_data.index.get(id) else {
+				continue;
+			};
+			let mut refs = Vec::new();
+			collect_dependency_refs(crate_data, item, &mut refs);
+			for dep in refs {
+				if !seen.insert(dep) || !crate_data.index.contains_key(&dep) {
+					continue;
+				}
+				let Some(summary) = crate_data.paths.get(&dep) else {
+					continue;
+				};
+				let is_type = matches!(
+					summary.kind,
+					ItemKind::Struct
+						| ItemKind::Enum
+						| ItemKind::Union
+						| ItemKind::Trait
+						| ItemKind::TypeAlias
+				);
+				if summary.crate_id != 0 || !is_type {
+					continue;
+				}
+				found.push(summary.path.join("::"));
+				next.push(dep);
+			}
+		}
+		frontier = next;
+	}
+	found
+}
+
+fn collect_dependency_refs(crate_data: &Crate, item: &Item, refs: &mut Vec<Id>) {
+	collect_item_refs(crate_data, item, refs);
+	let members: Vec<Id> = match &item.inner {
+		ItemEnum::Struct(struct_) => match &struct_.kind {
+			StructKind::Plain { fields, .. } => fields.clone(),
+			StructKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+			StructKind::Unit => Vec::new(),
+		},
+		ItemEnum::Union(union_) => union_.fields.clone(),
+		ItemEnum::Enum(enum_) => enum_.variants.clone(),
+		ItemEnum::Variant(variant) => match &variant.kind {
+			VariantKind::Struct { fields, .. } => fields.clone(),
+			VariantKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+			VariantKind::Plain => Vec::new(),
+		},
+		ItemEnum::Trait(trait_) => trait_.items.clone(),
+		_ => Vec::new(),
+	};
+	for member in members {
+		if let Some(member) = crate_data.index.get(&member) {
+			collect_dependency_refs(crate_data, member, refs);
+		}
+	}
+}
+
 /// Unescape backslash sequences in injection content (e.g., `\n` to newline).
 pub fn unescape_inject_content(input: &str) -> String {
 	let mut out = String::with_capacity(input.len());
@@ -493,15 +594,16 @@ pub fn find_target_match(entries: &[SkeleEntry], spec: &str) -> Result<usize> {
 
 /// Locate any entry (target or raw source) that matches the provided spec.
 /// This is used for --after-target/--before-target which should work with any stable entry key.
-pub fn find_entry_match(entries: &[SkeleEntry], spec: &str) -> Result<usize> {
+///
+/// Relative file specs are resolved against the working directory of `ripdoc`.
+pub fn find_entry_match(entries: &[SkeleEntry], spec: &str, ripdoc: &Ripdoc) -> Result<usize> {
 	use super::state::SkeleEntry;
 
 	let mut matches: Vec<usize> = Vec::new();
 	let spec = spec.trim();
 
 	// Normalize the spec for matching
-	let normalized_spec = std::path::PathBuf::from(spec);
-	let canonical_spec = normalized_spec.canonicalize().ok();
+	let canonical_spec = ripdoc.in_working_dir(spec).canonicalize().ok();
 	let spec_str = spec.replace('\\', "/");
 
 	for (idx, entry) in entries.iter().enumerate() {
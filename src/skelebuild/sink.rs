@@ -0,0 +1,271 @@
+//! Destinations a rebuilt skelebuild document is delivered to.
+//!
+//! The output file is always written; sinks configured with `ripdoc skelebuild sink` receive the
+//! same text afterwards, so a document can go straight to stdout, the clipboard, or a paste
+//! service or doc system accepting HTTP POSTs. Sinks only see explicit rebuilds
+//! (`ripdoc skelebuild rebuild` and `ripdoc skelebuild watch`), not the rebuild after every edit,
+//! so adding entries one by one does not post each intermediate document. Library users can pass
+//! their own [`SkeleSink`] implementations to
+//! [`SkeleState::rebuild_to`](super::SkeleState::rebuild_to).
+
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core_api::Result;
+use crate::core_api::error::RipdocError;
+
+/// `$NAME` or `${NAME}` references in HTTP header values.
+static ENV_REF_REGEX: Lazy<Regex> = Lazy::new(|| {
+	Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))")
+		.expect("valid environment reference pattern")
+});
+
+/// Clipboard programs tried in order: macOS, Wayland, X11, Windows.
+const CLIPBOARD_PROGRAMS: &[(&str, &[&str])] = &[
+	("pbcopy", &[]),
+	("wl-copy", &[]),
+	("xclip", &["-selection", "clipboard"]),
+	("xsel", &["--clipboard", "--input"]),
+	("clip.exe", &[]),
+];
+
+/// A destination for rebuilt output.
+pub trait SkeleSink {
+	/// Short label for messages, such as `stdout` or the target URL.
+	fn label(&self) -> String;
+
+	/// Deliver the rebuilt document.
+	fn write(&self, output: &str) -> Result<()>;
+}
+
+/// A sink as configured on the command line and persisted in the skelebuild state.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SinkSpec {
+	/// Write a copy to another file.
+	File {
+		/// Destination path.
+		path: PathBuf,
+	},
+	/// Print the document to stdout.
+	Stdout,
+	/// POST the document to a URL.
+	Http {
+		/// Endpoint receiving the document as the request body.
+		url: String,
+		/// Extra `Name: value` headers; `$VAR` in a value is read from the environment when
+		/// sending, so tokens are not stored in the state file.
+		#[serde(default, skip_serializing_if = "Vec::is_empty")]
+		headers: Vec<String>,
+	},
+	/// Copy the document to the system clipboard.
+	Clipboard,
+}
+
+impl SinkSpec {
+	/// Build the sink this spec describes.
+	pub fn open(&self) -> Box<dyn SkeleSink> {
+		match self {
+			Self::File { path } => Box::new(FileSink(path.clone())),
+			Self::Stdout => Box::new(StdoutSink),
+			Self::Http { url, headers } => Box::new(HttpSink {
+				url: url.clone(),
+				headers: headers.clone(),
+			}),
+			Self::Clipboard => Box::new(ClipboardSink),
+		}
+	}
+}
+
+impl FromStr for SinkSpec {
+	type Err = String;
+
+	/// Parse `stdout` (or `-`), `clipboard`, `file:PATH`, or an `http://`/`https://` URL.
+	fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+		match spec {
+			"stdout" | "-" => Ok(Self::Stdout),
+			"clipboard" => Ok(Self::Clipboard),
+			_ if spec.starts_with("http://") || spec.starts_with("https://") => Ok(Self::Http {
+				url: spec.to_string(),
+				headers: Vec::new(),
+			}),
+			_ => match spec.strip_prefix("file:") {
+				Some(path) if !path.is_empty() => Ok(Self::File {
+					path: PathBuf::from(path),
+				}),
+				_ => Err(format!(
+					"unknown sink '{spec}'; expected stdout, clipboard, file:PATH, or an http(s) URL"
+				)),
+			},
+		}
+	}
+}
+
+impl fmt::Display for SinkSpec {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::File { path } => write!(f, "file:{}", path.display()),
+			Self::Stdout => write!(f, "stdout"),
+			Self::Http { url, .. } => write!(f, "{url}"),
+			Self::Clipboard => write!(f, "clipboard"),
+		}
+	}
+}
+
+/// Writes the document to a file, creating parent directories.
+pub struct FileSink(pub PathBuf);
+
+impl SkeleSink for FileSink {
+	fn label(&self) -> String {
+		self.0.display().to_string()
+	}
+
+	fn write(&self, output: &str) -> Result<()> {
+		if let Some(parent) = self.0.parent()
+			&& !parent.as_os_str().is_empty()
+		{
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(&self.0, output)?;
+		Ok(())
+	}
+}
+
+/// Prints the document to stdout.
+pub struct StdoutSink;
+
+impl SkeleSink for StdoutSink {
+	fn label(&self) -> String {
+		"stdout".to_string()
+	}
+
+	fn write(&self, output: &str) -> Result<()> {
+		let mut stdout = std::io::stdout().lock();
+		stdout.write_all(output.as_bytes())?;
+		stdout.flush()?;
+		Ok(())
+	}
+}
+
+/// POSTs the document as `text/markdown` to a URL.
+pub struct HttpSink {
+	/// Endpoint receiving the document.
+	pub url: String,
+	/// Extra `Name: value` headers, with `$VAR` references expanded on send.
+	pub headers: Vec<String>,
+}
+
+impl SkeleSink for HttpSink {
+	fn label(&self) -> String {
+		self.url.clone()
+	}
+
+	fn write(&self, output: &str) -> Result<()> {
+		let mut request = ureq::post(&self.url).content_type("text/markdown; charset=utf-8");
+		for header in &self.headers {
+			let (name, value) = header.split_once(':').ok_or_else(|| {
+				RipdocError::Sink(format!("header '{header}' is not of the form 'Name: value'"))
+			})?;
+			request = request.header(name.trim(), expand_env(value.trim())?);
+		}
+		let response = request
+			.send(output)
+			.map_err(|err| RipdocError::Sink(format!("POST to {} failed: {err}", self.url)))?;
+		eprintln!("Posted output to {} ({})", self.url, response.status());
+		Ok(())
+	}
+}
+
+/// Copies the document with the first available clipboard program.
+pub struct ClipboardSink;
+
+impl SkeleSink for ClipboardSink {
+	fn label(&self) -> String {
+		"clipboard".to_string()
+	}
+
+	fn write(&self, output: &str) -> Result<()> {
+		for (program, args) in CLIPBOARD_PROGRAMS {
+			let Ok(mut child) = Command::new(program)
+				.args(*args)
+				.stdin(Stdio::piped())
+				.stdout(Stdio::null())
+				.spawn()
+			else {
+				continue;
+			};
+			if let Some(mut stdin) = child.stdin.take() {
+				stdin.write_all(output.as_bytes())?;
+			}
+			let status = child.wait()?;
+			if !status.success() {
+				return Err(RipdocError::Sink(format!("{program} exited with {status}")));
+			}
+			return Ok(());
+		}
+		let programs: Vec<&str> = CLIPBOARD_PROGRAMS.iter().map(|(program, _)| *program).collect();
+		Err(RipdocError::Sink(format!(
+			"no clipboard program found; install one of: {}",
+			programs.join(", ")
+		)))
+	}
+}
+
+/// Replace `$NAME`/`${NAME}` references with environment variables.
+fn expand_env(value: &str) -> Result<String> {
+	let mut missing = None;
+	let expanded = ENV_REF_REGEX.replace_all(value, |caps: &regex::Captures<'_>| {
+		let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+		std::env::var(name).unwrap_or_else(|_| {
+			missing.get_or_insert_with(|| name.to_string());
+			String::new()
+		})
+	});
+	match missing {
+		Some(name) => Err(RipdocError::Sink(format!("environment variable ${name} is not set"))),
+		None => Ok(expanded.into_owned()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_sink_specs() {
+		assert_eq!("stdout".parse(), Ok(SinkSpec::Stdout));
+		assert_eq!("-".parse(), Ok(SinkSpec::Stdout));
+		assert_eq!("clipboard".parse(), Ok(SinkSpec::Clipboard));
+		assert_eq!(
+			"file:out/copy.md".parse(),
+			Ok(SinkSpec::File {
+				path: PathBuf::from("out/copy.md")
+			})
+		);
+		assert_eq!(
+			"https://paste.example/api".parse(),
+			Ok(SinkSpec::Http {
+				url: "https://paste.example/api".to_string(),
+				headers: Vec::new()
+			})
+		);
+		assert!("ftp://example".parse::<SinkSpec>().is_err());
+		assert!("file:".parse::<SinkSpec>().is_err());
+	}
+
+	#[test]
+	fn file_sink_creates_parent_directories() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("nested").join("copy.md");
+		SinkSpec::File { path: path.clone() }.open().write("# Doc\n").unwrap();
+		assert_eq!(fs::read_to_string(path).unwrap(), "# Doc\n");
+	}
+}
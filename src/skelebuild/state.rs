@@ -1,12 +1,18 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::sync::Mutex;
 
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use crate::core_api::Result;
+use super::sink::SinkSpec;
+use crate::core_api::{Result, Ripdoc};
 
 /// State of an ongoing skeleton build.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SkeleState {
 	/// Path to the output file where skeletonized code is written.
 	pub output_path: Option<PathBuf>,
@@ -15,6 +21,27 @@ pub struct SkeleState {
 	/// Whether to use plain output (skip module nesting). Defaults to true.
 	#[serde(default = "default_plain")]
 	pub plain: bool,
+	/// Size budget enforced on rebuild, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub budget: Option<SkeleBudget>,
+	/// Whether entry notes are rendered as HTML comments above their entries.
+	#[serde(default, skip_serializing_if = "is_false")]
+	pub render_notes: bool,
+	/// Extra destinations the rebuilt output is delivered to after the output file.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub sinks: Vec<SinkSpec>,
+}
+
+/// States kept in memory by long-lived processes (see [`SkeleState::load_warm`]), keyed by the
+/// state file they were read from or written to.
+static WARM_STATES: Lazy<Mutex<HashMap<PathBuf, WarmState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A state held in [`WARM_STATES`].
+struct WarmState {
+	/// Hash of the state file's contents when it was last read or written.
+	content_hash: u64,
+	/// The state itself.
+	state: SkeleState,
 }
 
 fn default_plain() -> bool {
@@ -27,6 +54,81 @@ impl Default for SkeleState {
 			output_path: None,
 			entries: Vec::new(),
 			plain: true,
+			budget: None,
+			render_notes: false,
+			sinks: Vec::new(),
+		}
+	}
+}
+
+/// Size budget for the rebuilt skeleton.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkeleBudget {
+	/// Maximum size, in `unit`s.
+	pub limit: usize,
+	/// Unit the limit is expressed in.
+	pub unit: BudgetUnit,
+	/// What to do when the output exceeds the limit.
+	#[serde(default)]
+	pub on_exceed: BudgetAction,
+}
+
+/// Unit of a [`SkeleBudget`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetUnit {
+	/// Characters.
+	Chars,
+	/// Approximate tokens (one per four characters).
+	Tokens,
+}
+
+/// How a rebuild responds to output over budget.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+	/// Fail with a per-entry size report.
+	#[default]
+	Error,
+	/// Keep only the first paragraph of each doc comment, then fail if still over.
+	TruncateDocs,
+	/// Render targets without implementations or raw source, then fail if still over.
+	DropImplementations,
+}
+
+impl SkeleBudget {
+	/// Size of `text` in this budget's unit.
+	pub fn measure(&self, text: &str) -> usize {
+		let chars = text.chars().count();
+		match self.unit {
+			BudgetUnit::Chars => chars,
+			BudgetUnit::Tokens => chars.div_ceil(4),
+		}
+	}
+
+	/// Whether `text` fits within the limit.
+	pub fn fits(&self, text: &str) -> bool {
+		self.measure(text) <= self.limit
+	}
+}
+
+impl BudgetUnit {
+	/// Human-readable unit name.
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::Chars => "chars",
+			Self::Tokens => "tokens",
+		}
+	}
+}
+
+impl BudgetAction {
+	/// Flag-style name, as accepted on the command line.
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::Error => "error",
+			Self::TruncateDocs => "truncate-docs",
+			Self::DropImplementations => "drop-implementations",
 		}
 	}
 }
@@ -43,6 +145,17 @@ pub enum SkeleEntry {
 	RawSource(SkeleRawSource),
 }
 
+impl SkeleEntry {
+	/// The note attached to the entry, if any. Injections carry no notes.
+	pub fn note(&self) -> Option<&str> {
+		match self {
+			Self::Target(target) => target.note.as_deref(),
+			Self::RawSource(raw) => raw.note.as_deref(),
+			Self::Injection(_) => None,
+		}
+	}
+}
+
 /// A target in the skeleton build.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SkeleTarget {
@@ -56,12 +169,54 @@ pub struct SkeleTarget {
 	/// Whether to search private items when resolving this target. Defaults to true.
 	#[serde(default = "default_private")]
 	pub private: bool,
+	/// Features to enable when documenting the target's crate.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub features: Vec<String>,
+	/// Whether to enable all features of the target's crate.
+	#[serde(default, skip_serializing_if = "is_false")]
+	pub all_features: bool,
+	/// Whether to disable the default features of the target's crate.
+	#[serde(default, skip_serializing_if = "is_false")]
+	pub no_default_features: bool,
+	/// Why the entry is part of the skeleton.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub note: Option<String>,
 }
 
 fn default_private() -> bool {
 	true
 }
 
+fn is_false(value: &bool) -> bool {
+	!value
+}
+
+impl SkeleTarget {
+	/// Feature configuration of the target as a comparable key: (no default features, all
+	/// features, sorted features).
+	pub fn feature_key(&self) -> (bool, bool, Vec<String>) {
+		let mut features = self.features.clone();
+		features.sort();
+		features.dedup();
+		(self.no_default_features, self.all_features, features)
+	}
+
+	/// Compact description of non-default feature settings, e.g. `features: a,b`.
+	pub fn feature_flags(&self) -> Vec<String> {
+		let mut flags = Vec::new();
+		if self.no_default_features {
+			flags.push("no-default-features".to_string());
+		}
+		if self.all_features {
+			flags.push("all-features".to_string());
+		}
+		if !self.features.is_empty() {
+			flags.push(format!("features: {}", self.features.join(",")));
+		}
+		flags
+	}
+}
+
 /// A manual text injection.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SkeleInjection {
@@ -84,6 +239,48 @@ pub struct SkeleRawSource {
 	/// 1-based inclusive end line, if set.
 	#[serde(default)]
 	pub end_line: Option<usize>,
+	/// Why the entry is part of the skeleton.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub note: Option<String>,
+}
+
+/// Rendered skelebuild output, split into the sections produced by each entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SkelePreview {
+	/// The full rendered output.
+	pub output: String,
+	/// Sections of `output` in order.
+	pub segments: Vec<PreviewSegment>,
+}
+
+/// A section of the rendered output and the entries it came from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PreviewSegment {
+	/// Indices of the entries rendered here, as listed by `skelebuild status`. Consecutive
+	/// targets from one crate render as a single merged skeleton, so they share a section.
+	pub entries: Vec<usize>,
+	/// What produced the section.
+	pub kind: PreviewSegmentKind,
+	/// Byte offset of the section in the output.
+	pub start: usize,
+	/// Byte offset just past the end of the section.
+	pub end: usize,
+	/// Approximate tokens in the section (one per four characters).
+	pub estimated_tokens: usize,
+}
+
+/// Source of a [`PreviewSegment`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewSegmentKind {
+	/// Rendered rustdoc targets.
+	Targets,
+	/// A raw source snippet.
+	RawSource,
+	/// A manual injection.
+	Injection,
+	/// An entry note rendered as an HTML comment.
+	Note,
 }
 
 /// Action to perform on the skelebuild state.
@@ -103,6 +300,14 @@ pub enum SkeleAction {
 		/// Strict mode: disable heuristics during validation.
 		#[allow(dead_code)]
 		strict: bool,
+		/// Features to enable when documenting the target's crate.
+		features: Vec<String>,
+		/// Whether to enable all features.
+		all_features: bool,
+		/// Whether to disable default features.
+		no_default_features: bool,
+		/// Also add local types referenced by the target, following references this many hops.
+		with_deps: Option<usize>,
 	},
 	/// Add multiple targets in one operation.
 	AddMany {
@@ -119,6 +324,14 @@ pub enum SkeleAction {
 		/// Strict mode: disable heuristics during validation.
 		#[allow(dead_code)]
 		strict: bool,
+		/// Features to enable when documenting the target's crate.
+		features: Vec<String>,
+		/// Whether to enable all features.
+		all_features: bool,
+		/// Whether to disable default features.
+		no_default_features: bool,
+		/// Also add local types referenced by the target, following references this many hops.
+		with_deps: Option<usize>,
 	},
 	/// Add a raw source snippet from disk.
 	AddRaw {
@@ -136,6 +349,10 @@ pub enum SkeleAction {
 		targets: Vec<String>,
 		/// Raw source specs to add.
 		raw_specs: Vec<String>,
+		/// Insert an empty reviewer-notes injection after each added hunk snippet.
+		with_notes: bool,
+		/// Commentary injected before the added entries (e.g. failing test messages).
+		preamble: Option<String>,
 	},
 	/// Inject manual commentary.
 	Inject {
@@ -152,14 +369,31 @@ pub enum SkeleAction {
 		/// Optional numeric index (0-based) to insert at.
 		at: Option<usize>,
 	},
-	/// Update an existing target entry.
+	/// Update an existing target or raw-source entry in place.
 	Update {
-		/// Target spec to update (matches like `--after-target`).
+		/// Entry spec to update (matches like `--after-target`, or a raw-source `file:start:end`).
 		spec: String,
 		/// New implementation flag, if provided.
 		implementation: Option<bool>,
 		/// New raw_source flag, if provided.
 		raw_source: Option<bool>,
+		/// New file for a raw-source entry, if provided.
+		file: Option<String>,
+		/// New 1-based start line for a raw-source entry, if provided.
+		start_line: Option<usize>,
+		/// New 1-based end line for a raw-source entry, if provided.
+		end_line: Option<usize>,
+		/// Convert a target entry into a raw-source entry covering the item's span.
+		to_raw: bool,
+		/// Convert a raw-source entry into a target entry for this target spec.
+		to_target: Option<String>,
+	},
+	/// Attach a note to a target or raw-source entry, or clear it.
+	Annotate {
+		/// Entry spec (matches like `update`).
+		spec: String,
+		/// The note; `None` clears the existing one.
+		note: Option<String>,
 	},
 	/// Remove an entry.
 	Remove(String),
@@ -171,44 +405,95 @@ pub enum SkeleAction {
 		keys: bool,
 	},
 	/// Preview the output to stdout.
-	Preview,
+	Preview {
+		/// Print a [`SkelePreview`] as JSON instead of the rendered text.
+		json: bool,
+	},
 	/// Rebuild output using current entries.
 	Rebuild,
+	/// Set or clear the size budget enforced on rebuild.
+	Budget(Option<SkeleBudget>),
+	/// Replace the extra output sinks; an empty list leaves only the output file.
+	Sinks(Vec<SinkSpec>),
 }
 
 impl SkeleState {
-	/// Returns the path to the state file in the XDG state directory.
-	pub fn state_file() -> PathBuf {
-		let mut path = dirs::state_dir().unwrap_or_else(|| {
-			let mut p = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-			p.push(".local");
-			p.push("state");
-			p
-		});
-		path.push("ripdoc");
-		path.push("skelebuild.json");
-		path
+	/// Returns the path to the state file in the state directory of `ripdoc`
+	/// (see [`Ripdoc::with_state_dir`]).
+	pub fn state_file(ripdoc: &Ripdoc) -> PathBuf {
+		ripdoc.state_dir().join("skelebuild.json")
 	}
 
 	/// Loads the skelebuild state from the state file.
-	pub fn load() -> Self {
-		let path = Self::state_file();
-		if path.exists() {
-			let content = fs::read_to_string(path).unwrap_or_default();
-			serde_json::from_str(&content).unwrap_or_default()
-		} else {
-			Self::default()
-		}
+	pub fn load(ripdoc: &Ripdoc) -> Self {
+		let content = fs::read_to_string(Self::state_file(ripdoc)).unwrap_or_default();
+		serde_json::from_str(&content).unwrap_or_default()
 	}
 
 	/// Saves the skelebuild state to the state file.
-	pub fn save(&self) -> Result<()> {
-		let path = Self::state_file();
+	pub fn save(&self, ripdoc: &Ripdoc) -> Result<()> {
+		self.write(ripdoc)?;
+		Ok(())
+	}
+
+	/// Like [`Self::load`], but reuses the state kept in memory by an earlier
+	/// [`Self::load_warm`] or [`Self::save_warm`] in this process, unless the contents of the state
+	/// file changed since, e.g. through a `skelebuild` run outside the daemon.
+	pub fn load_warm(ripdoc: &Ripdoc) -> Self {
+		let path = Self::state_file(ripdoc);
+		let content = fs::read_to_string(&path).unwrap_or_default();
+		let content_hash = hash_content(&content);
+		let Ok(mut warm) = WARM_STATES.lock() else {
+			return serde_json::from_str(&content).unwrap_or_default();
+		};
+		if let Some(held) = warm.get(&path)
+			&& held.content_hash == content_hash
+		{
+			return held.state.clone();
+		}
+		let state: Self = serde_json::from_str(&content).unwrap_or_default();
+		warm.insert(
+			path,
+			WarmState {
+				content_hash,
+				state: state.clone(),
+			},
+		);
+		state
+	}
+
+	/// Like [`Self::save`], and keeps the saved state in memory for [`Self::load_warm`].
+	///
+	/// The state file is still written, so runs that do not go through this process see the
+	/// same session.
+	pub fn save_warm(&self, ripdoc: &Ripdoc) -> Result<()> {
+		let content = self.write(ripdoc)?;
+		if let Ok(mut warm) = WARM_STATES.lock() {
+			warm.insert(
+				Self::state_file(ripdoc),
+				WarmState {
+					content_hash: hash_content(&content),
+					state: self.clone(),
+				},
+			);
+		}
+		Ok(())
+	}
+
+	/// Writes the state file, returning what was written.
+	fn write(&self, ripdoc: &Ripdoc) -> Result<String> {
+		let path = Self::state_file(ripdoc);
 		if let Some(parent) = path.parent() {
 			fs::create_dir_all(parent)?;
 		}
 		let content = serde_json::to_string_pretty(self)?;
-		fs::write(path, content)?;
-		Ok(())
+		fs::write(path, &content)?;
+		Ok(content)
 	}
 }
+
+fn hash_content(content: &str) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	content.hash(&mut hasher);
+	hasher.finish()
+}
@@ -0,0 +1,182 @@
+//! Rebuilding the skeleton whenever sources behind the stored entries change.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::state::{SkeleEntry, SkeleState};
+use crate::cargo_utils::{is_rustdoc_input, resolve_target};
+use crate::core_api::error::RipdocError;
+use crate::core_api::{Result, Ripdoc};
+
+/// Sources the current state depends on.
+#[derive(Default)]
+struct WatchSet {
+	/// Package roots of target entries.
+	roots: BTreeSet<PathBuf>,
+	/// Files backing raw-source entries.
+	raw_files: HashSet<PathBuf>,
+}
+
+impl WatchSet {
+	fn from_state(state: &SkeleState, ripdoc: &Ripdoc) -> Self {
+		let mut set = Self::default();
+		for entry in &state.entries {
+			match entry {
+				SkeleEntry::Target(target) => {
+					let Ok(resolved) = resolve_target(&target.path, &ripdoc.resolve_context()) else {
+						continue;
+					};
+					for rt in resolved {
+						set.roots.insert(rt.package_root().to_path_buf());
+					}
+				}
+				SkeleEntry::RawSource(raw) => {
+					set.raw_files.insert(raw.file.clone());
+				}
+				SkeleEntry::Injection(_) => {}
+			}
+		}
+		set
+	}
+
+	/// Register watches for roots and raw-source directories not watched yet.
+	fn watch_new(&self, watcher: &mut RecommendedWatcher, watched: &mut HashSet<PathBuf>) {
+		let roots = self
+			.roots
+			.iter()
+			.map(|root| (root.clone(), RecursiveMode::Recursive));
+		// Watch the parent directory so editors that replace files on save are still seen.
+		let raw_dirs = self
+			.raw_files
+			.iter()
+			.filter_map(|file| file.parent())
+			.map(|dir| (dir.to_path_buf(), RecursiveMode::NonRecursive));
+		for (path, mode) in roots.chain(raw_dirs) {
+			if !path.exists() || watched.contains(&path) {
+				continue;
+			}
+			match watcher.watch(&path, mode) {
+				Ok(()) => {
+					watched.insert(path);
+				}
+				Err(err) => eprintln!("Warning: cannot watch {}: {err}", path.display()),
+			}
+		}
+	}
+
+	/// Whether a change to `path` can affect the rebuilt output.
+	fn is_relevant(&self, path: &Path, output: &Path) -> bool {
+		if path == output {
+			return false;
+		}
+		if self.raw_files.contains(path) {
+			return true;
+		}
+		self.root_of(path).is_some_and(|root| is_crate_source(root, path))
+	}
+
+	fn root_of(&self, path: &Path) -> Option<&PathBuf> {
+		self.roots
+			.iter()
+			.filter(|root| path.starts_with(root))
+			.max_by_key(|root| root.as_os_str().len())
+	}
+}
+
+/// Rust sources and manifests of a package, excluding its build directory.
+fn is_crate_source(root: &Path, path: &Path) -> bool {
+	if path.strip_prefix(root).is_ok_and(|rel| rel.starts_with("target")) {
+		return false;
+	}
+	is_rustdoc_input(path)
+}
+
+fn output_path(state: &SkeleState) -> PathBuf {
+	let output = state
+		.output_path
+		.clone()
+		.unwrap_or_else(|| PathBuf::from("skeleton.md"));
+	std::path::absolute(&output).unwrap_or(output)
+}
+
+fn rebuild_and_report(state: &SkeleState, ripdoc: &Ripdoc, changed: usize) {
+	let output = output_path(state);
+	match state.rebuild(ripdoc) {
+		Ok(()) if changed == 0 => eprintln!("Rebuilt {}", output.display()),
+		Ok(()) => eprintln!("Rebuilt {} ({changed} changed files)", output.display()),
+		Err(RipdocError::Sink(failures)) => eprintln!("Warning: {failures}"),
+		Err(err) => eprintln!("Rebuild failed: {err}"),
+	}
+}
+
+/// Watch the package roots and raw-source files referenced by the stored state, rebuilding the
+/// output after every change.
+///
+/// Changes arriving within `debounce` of each other trigger a single rebuild. The state is
+/// reloaded before each rebuild, so entries added from another shell are picked up (and their
+/// sources watched). Rebuild errors are reported and watching continues; the function only
+/// returns when the watcher cannot be created or stops delivering events.
+pub fn watch(ripdoc: &Ripdoc, debounce: Duration) -> Result<()> {
+	let (tx, rx) = mpsc::channel();
+	let mut watcher = notify::recommended_watcher(tx)
+		.map_err(|err| RipdocError::Io(std::io::Error::other(err)))?;
+	let mut watched = HashSet::new();
+
+	let mut state = SkeleState::load(ripdoc);
+	let mut set = WatchSet::from_state(&state, ripdoc);
+	set.watch_new(&mut watcher, &mut watched);
+	rebuild_and_report(&state, ripdoc, 0);
+	eprintln!(
+		"Watching {} package roots and {} raw sources (Ctrl-C to stop)",
+		set.roots.len(),
+		set.raw_files.len()
+	);
+
+	while let Ok(first) = rx.recv() {
+		let mut changed: HashSet<PathBuf> = HashSet::new();
+		let mut collect = |event: notify::Result<notify::Event>| {
+			if let Ok(event) = event
+				&& !matches!(event.kind, EventKind::Access(_))
+			{
+				changed.extend(event.paths);
+			}
+		};
+		collect(first);
+		while let Ok(event) = rx.recv_timeout(debounce) {
+			collect(event);
+		}
+
+		let output = output_path(&state);
+		changed.retain(|path| set.is_relevant(path, &output));
+		if changed.is_empty() {
+			continue;
+		}
+
+		// Builds of local crates are keyed on a fingerprint of their sources, so the rebuild
+		// misses the cache for edited packages without invalidating anything here.
+		state = SkeleState::load(ripdoc);
+		set = WatchSet::from_state(&state, ripdoc);
+		set.watch_new(&mut watcher, &mut watched);
+		rebuild_and_report(&state, ripdoc, changed.len());
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn crate_sources_skip_build_output() {
+		let root = Path::new("/work/demo");
+		assert!(is_crate_source(root, Path::new("/work/demo/src/lib.rs")));
+		assert!(is_crate_source(root, Path::new("/work/demo/Cargo.toml")));
+		assert!(!is_crate_source(root, Path::new("/work/demo/target/doc/demo.json")));
+		assert!(!is_crate_source(root, Path::new("/work/demo/target/debug/build/out.rs")));
+		assert!(!is_crate_source(root, Path::new("/work/demo/README.md")));
+	}
+}
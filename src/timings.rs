@@ -0,0 +1,163 @@
+//! Per-phase durations collected for `--timings`.
+//!
+//! Recording is off by default. A [`Timings`] collects the phases timed while it is in
+//! [`scope`](Timings::scope) (target resolution, crate fetches, rustdoc builds, cache loads, index
+//! builds, selection, rendering, rustfmt), each adding its duration to a running total. Phases
+//! that run once per package add up across packages, including packages processed in parallel,
+//! since worker threads inherit the recorder of the thread that started them. Recorders are
+//! per invocation, so separate requests in one process never see each other's phases.
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+thread_local! {
+	/// Recorder of the phases timed on this thread, installed by [`Timings::scope`].
+	static CURRENT: RefCell<Option<Timings>> = const { RefCell::new(None) };
+}
+
+/// Total time spent in one phase.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseTiming {
+	/// Phase name, e.g. `rustdoc build`.
+	pub phase: String,
+	/// How many times the phase ran.
+	pub calls: usize,
+	/// Total duration in milliseconds.
+	pub millis: f64,
+}
+
+/// Phase totals of one invocation, in first-seen order. Clones share the totals.
+#[derive(Debug, Clone, Default)]
+pub struct Timings(Arc<Mutex<Vec<PhaseTiming>>>);
+
+impl Timings {
+	/// An empty recorder.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Run `f` with the phases timed on this thread, and on worker threads it starts, recorded
+	/// here.
+	pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+		/// Reinstalls the previous recorder even if `f` panics.
+		struct Restore(Option<Timings>);
+
+		impl Drop for Restore {
+			fn drop(&mut self) {
+				let previous = self.0.take();
+				CURRENT.with(|current| *current.borrow_mut() = previous);
+			}
+		}
+
+		let _restore = Restore(CURRENT.with(|current| current.replace(Some(self.clone()))));
+		f()
+	}
+
+	/// Add `elapsed` to the total of `phase`.
+	pub fn record(&self, phase: &str, elapsed: Duration) {
+		let mut timings = self.0.lock().unwrap_or_else(|err| err.into_inner());
+		let millis = elapsed.as_secs_f64() * 1000.0;
+		match timings.iter_mut().find(|timing| timing.phase == phase) {
+			Some(timing) => {
+				timing.calls += 1;
+				timing.millis += millis;
+			}
+			None => timings.push(PhaseTiming {
+				phase: phase.to_string(),
+				calls: 1,
+				millis,
+			}),
+		}
+	}
+
+	/// The phase totals recorded so far, leaving the recorder empty.
+	pub fn take(&self) -> Vec<PhaseTiming> {
+		std::mem::take(&mut *self.0.lock().unwrap_or_else(|err| err.into_inner()))
+	}
+}
+
+/// The recorder in scope on this thread, if any.
+pub fn current() -> Option<Timings> {
+	CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Whether phase durations are being recorded on this thread.
+pub fn is_enabled() -> bool {
+	CURRENT.with(|current| current.borrow().is_some())
+}
+
+/// Add `elapsed` to the total of `phase`; a no-op unless a recorder is in scope.
+pub fn record(phase: &str, elapsed: Duration) {
+	if let Some(timings) = current() {
+		timings.record(phase, elapsed);
+	}
+}
+
+/// Run `f`, recording its duration under `phase` when a recorder is in scope.
+pub fn time<T>(phase: &str, f: impl FnOnce() -> T) -> T {
+	let Some(timings) = current() else {
+		return f();
+	};
+	let start = Instant::now();
+	let value = f();
+	timings.record(phase, start.elapsed());
+	value
+}
+
+/// Render phase totals as an aligned text table.
+pub fn render_report(timings: &[PhaseTiming]) -> String {
+	let width = timings.iter().map(|timing| timing.phase.len()).max().unwrap_or(0);
+	let mut report = String::from("ripdoc timings:\n");
+	for timing in timings {
+		report.push_str(&format!(
+			"  {:<width$}  {:>10.1} ms  ({}x)\n",
+			timing.phase, timing.millis, timing.calls
+		));
+	}
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recorders_only_see_their_own_scope() {
+		let outer = Timings::new();
+		let inner = Timings::new();
+		outer.scope(|| {
+			time("resolve", || {});
+			inner.scope(|| record("render", Duration::from_millis(2)));
+			record("render", Duration::from_millis(1));
+		});
+		record("unscoped", Duration::from_millis(1));
+
+		let phases = |timings: &Timings| -> Vec<(String, usize)> {
+			timings.take().into_iter().map(|timing| (timing.phase, timing.calls)).collect()
+		};
+		assert_eq!(phases(&outer), [("resolve".to_string(), 1), ("render".to_string(), 1)]);
+		assert_eq!(phases(&inner), [("render".to_string(), 1)]);
+		assert!(!is_enabled());
+	}
+
+	#[test]
+	fn report_aligns_phases() {
+		let report = render_report(&[
+			PhaseTiming {
+				phase: "rustdoc build".to_string(),
+				calls: 1,
+				millis: 1520.0,
+			},
+			PhaseTiming {
+				phase: "render".to_string(),
+				calls: 3,
+				millis: 12.3,
+			},
+		]);
+		assert_eq!(
+			report,
+			"ripdoc timings:\n  rustdoc build      1520.0 ms  (1x)\n  render               12.3 ms  (3x)\n"
+		);
+	}
+}
@@ -0,0 +1,337 @@
+//! Browser state and key handling, kept free of terminal I/O so it can be tested directly.
+
+use std::collections::HashSet;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::core_api::ListTreeNode;
+use crate::core_api::search::{ListItem, SearchItemKind};
+use crate::build_list_tree;
+
+/// Pane receiving key presses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Focus {
+	/// The module tree, or the search results while a query is active.
+	Items,
+	/// The search bar.
+	Search,
+	/// The preview pane.
+	Preview,
+}
+
+/// One line of the items pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Row {
+	/// Canonical `::` path of the item.
+	pub path: String,
+	/// Text shown for the row: the item name in the tree, the whole path for search results.
+	pub label: String,
+	pub kind: SearchItemKind,
+	/// Nesting depth in the tree; 0 for search results.
+	pub depth: usize,
+	/// Whether the row has children, and so can be expanded.
+	pub expandable: bool,
+	pub expanded: bool,
+}
+
+/// Effects a key press asks the browser to perform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Action {
+	/// Nothing beyond the state change.
+	None,
+	/// Run the search bar's query (empty to go back to the tree).
+	Search(String),
+	/// Add an item to the skelebuild session.
+	Add { path: String, implementation: bool },
+	/// Leave the browser.
+	Quit,
+}
+
+/// State of the browser between frames.
+pub(super) struct App {
+	tree: Vec<ListTreeNode>,
+	expanded: HashSet<String>,
+	/// Search hits, while a query is active.
+	results: Option<Vec<ListItem>>,
+	pub rows: Vec<Row>,
+	pub selected: usize,
+	pub query: String,
+	pub focus: Focus,
+	pub preview_scroll: u16,
+	/// Message shown in the status line, such as the outcome of an add.
+	pub status: Option<String>,
+}
+
+impl App {
+	/// Browse `items`, with the crate roots expanded.
+	pub fn new(items: &[ListItem]) -> Self {
+		let tree = build_list_tree(items);
+		let expanded = tree.iter().map(|root| root.name.clone()).collect();
+		let mut app = Self {
+			tree,
+			expanded,
+			results: None,
+			rows: Vec::new(),
+			selected: 0,
+			query: String::new(),
+			focus: Focus::Items,
+			preview_scroll: 0,
+			status: None,
+		};
+		app.refresh_rows();
+		app
+	}
+
+	/// Show `results` in place of the tree, or the tree again for `None`.
+	pub fn set_results(&mut self, results: Option<Vec<ListItem>>) {
+		self.results = results;
+		self.selected = 0;
+		self.refresh_rows();
+	}
+
+	/// Path of the selected row.
+	pub fn selected_path(&self) -> Option<&str> {
+		self.rows.get(self.selected).map(|row| row.path.as_str())
+	}
+
+	/// Update the state for `key`, returning what the browser should do about it.
+	pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+		if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+			return Action::Quit;
+		}
+		match self.focus {
+			Focus::Search => self.search_key(key),
+			Focus::Items => self.items_key(key),
+			Focus::Preview => self.preview_key(key),
+		}
+	}
+
+	fn search_key(&mut self, key: KeyEvent) -> Action {
+		match key.code {
+			KeyCode::Esc => {
+				self.query.clear();
+				self.focus = Focus::Items;
+				Action::Search(String::new())
+			}
+			KeyCode::Enter | KeyCode::Down | KeyCode::Tab => {
+				self.focus = Focus::Items;
+				Action::None
+			}
+			KeyCode::Backspace => {
+				self.query.pop();
+				Action::Search(self.query.clone())
+			}
+			KeyCode::Char(ch) => {
+				self.query.push(ch);
+				Action::Search(self.query.clone())
+			}
+			_ => Action::None,
+		}
+	}
+
+	fn items_key(&mut self, key: KeyEvent) -> Action {
+		match key.code {
+			KeyCode::Char('q') => return Action::Quit,
+			KeyCode::Char('/') => self.focus = Focus::Search,
+			KeyCode::Tab => self.focus = Focus::Preview,
+			KeyCode::Esc if self.results.is_some() => {
+				self.query.clear();
+				return Action::Search(String::new());
+			}
+			KeyCode::Down | KeyCode::Char('j') => self.select(self.selected.saturating_add(1)),
+			KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+			KeyCode::PageDown => self.select(self.selected.saturating_add(10)),
+			KeyCode::PageUp => self.select(self.selected.saturating_sub(10)),
+			KeyCode::Home | KeyCode::Char('g') => self.select(0),
+			KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+			KeyCode::Right | KeyCode::Char('l') => self.set_expanded(true),
+			KeyCode::Left | KeyCode::Char('h') => self.collapse_or_parent(),
+			KeyCode::Enter | KeyCode::Char(' ') => {
+				let expanded = self.rows.get(self.selected).is_some_and(|row| row.expanded);
+				self.set_expanded(!expanded);
+			}
+			KeyCode::Char(add @ ('a' | 'A')) => {
+				if let Some(path) = self.selected_path() {
+					return Action::Add {
+						path: path.to_string(),
+						implementation: add == 'A',
+					};
+				}
+			}
+			_ => {}
+		}
+		Action::None
+	}
+
+	fn preview_key(&mut self, key: KeyEvent) -> Action {
+		match key.code {
+			KeyCode::Char('q') => return Action::Quit,
+			KeyCode::Char('/') => self.focus = Focus::Search,
+			KeyCode::Tab | KeyCode::Esc => self.focus = Focus::Items,
+			KeyCode::Down | KeyCode::Char('j') => self.preview_scroll = self.preview_scroll.saturating_add(1),
+			KeyCode::Up | KeyCode::Char('k') => self.preview_scroll = self.preview_scroll.saturating_sub(1),
+			KeyCode::PageDown => self.preview_scroll = self.preview_scroll.saturating_add(20),
+			KeyCode::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(20),
+			KeyCode::Home | KeyCode::Char('g') => self.preview_scroll = 0,
+			_ => {}
+		}
+		Action::None
+	}
+
+	fn select(&mut self, index: usize) {
+		let index = index.min(self.rows.len().saturating_sub(1));
+		if index != self.selected {
+			self.selected = index;
+			self.preview_scroll = 0;
+		}
+	}
+
+	fn set_expanded(&mut self, expand: bool) {
+		let Some(row) = self.rows.get(self.selected) else {
+			return;
+		};
+		if !row.expandable {
+			return;
+		}
+		if expand {
+			self.expanded.insert(row.path.clone());
+		} else {
+			self.expanded.remove(&row.path);
+		}
+		self.refresh_rows();
+	}
+
+	/// Collapse the selected row, or move to its parent if it is already collapsed.
+	fn collapse_or_parent(&mut self) {
+		let Some(row) = self.rows.get(self.selected) else {
+			return;
+		};
+		if row.expanded {
+			self.set_expanded(false);
+			return;
+		}
+		let depth = row.depth;
+		if let Some(parent) = self.rows[..self.selected].iter().rposition(|row| row.depth < depth) {
+			self.select(parent);
+		}
+	}
+
+	fn refresh_rows(&mut self) {
+		let selected = self.selected_path().map(str::to_string);
+		self.rows = match &self.results {
+			Some(results) => results
+				.iter()
+				.map(|item| Row {
+					path: item.path.clone(),
+					label: item.path.clone(),
+					kind: item.kind,
+					depth: 0,
+					expandable: false,
+					expanded: false,
+				})
+				.collect(),
+			None => {
+				let mut rows = Vec::new();
+				for root in &self.tree {
+					flatten(root, "", 0, &self.expanded, &mut rows);
+				}
+				rows
+			}
+		};
+		// Keep the selection on the same item when rows appear or disappear around it.
+		if let Some(selected) = selected
+			&& let Some(index) = self.rows.iter().position(|row| row.path == selected)
+		{
+			self.selected = index;
+		}
+		self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+	}
+}
+
+fn flatten(node: &ListTreeNode, parent: &str, depth: usize, expanded: &HashSet<String>, rows: &mut Vec<Row>) {
+	let path = if parent.is_empty() {
+		node.name.clone()
+	} else {
+		format!("{parent}::{}", node.name)
+	};
+	let is_expanded = !node.children.is_empty() && expanded.contains(&path);
+	rows.push(Row {
+		path: path.clone(),
+		label: node.name.clone(),
+		kind: node.kind,
+		depth,
+		expandable: !node.children.is_empty(),
+		expanded: is_expanded,
+	});
+	if is_expanded {
+		for child in &node.children {
+			flatten(child, &path, depth + 1, expanded, rows);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ratatui::crossterm::event::KeyEvent;
+
+	use super::*;
+
+	fn item(kind: SearchItemKind, path: &str) -> ListItem {
+		ListItem {
+			kind,
+			path: path.to_string(),
+			source: None,
+			fan_in: None,
+			deprecated: false,
+			deprecation_note: None,
+			cfg: Vec::new(),
+		}
+	}
+
+	fn press(app: &mut App, code: KeyCode) -> Action {
+		app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+	}
+
+	fn paths(app: &App) -> Vec<&str> {
+		app.rows.iter().map(|row| row.path.as_str()).collect()
+	}
+
+	#[test]
+	fn tree_rows_expand_and_collapse() {
+		let mut app = App::new(&[
+			item(SearchItemKind::Crate, "demo"),
+			item(SearchItemKind::Module, "demo::io"),
+			item(SearchItemKind::Struct, "demo::io::Reader"),
+			item(SearchItemKind::Function, "demo::open"),
+		]);
+		assert_eq!(paths(&app), vec!["demo", "demo::io", "demo::open"]);
+
+		press(&mut app, KeyCode::Down);
+		press(&mut app, KeyCode::Right);
+		assert_eq!(paths(&app), vec!["demo", "demo::io", "demo::io::Reader", "demo::open"]);
+
+		press(&mut app, KeyCode::Down);
+		press(&mut app, KeyCode::Left);
+		assert_eq!(app.selected_path(), Some("demo::io"));
+		press(&mut app, KeyCode::Left);
+		assert_eq!(paths(&app), vec!["demo", "demo::io", "demo::open"]);
+	}
+
+	#[test]
+	fn search_keys_drive_queries_and_adds() {
+		let mut app = App::new(&[item(SearchItemKind::Crate, "demo"), item(SearchItemKind::Function, "demo::open")]);
+		press(&mut app, KeyCode::Char('/'));
+		assert_eq!(press(&mut app, KeyCode::Char('o')), Action::Search("o".to_string()));
+		app.set_results(Some(vec![item(SearchItemKind::Function, "demo::open")]));
+		press(&mut app, KeyCode::Enter);
+		assert_eq!(
+			press(&mut app, KeyCode::Char('A')),
+			Action::Add {
+				path: "demo::open".to_string(),
+				implementation: true,
+			}
+		);
+		assert_eq!(press(&mut app, KeyCode::Esc), Action::Search(String::new()));
+		assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+	}
+}
@@ -0,0 +1,208 @@
+//! Interactive terminal browser (`ripdoc tui`).
+//!
+//! [`Browser`] shows a target's module tree next to a preview of the selected item's skeleton.
+//! Typing in the search bar replaces the tree with fuzzy matches from the crate's search index,
+//! and `a` adds the selected item to the current skelebuild session (`A` with its
+//! implementation), so a skeleton can be assembled while exploring.
+
+/// Browser state and key handling.
+mod app;
+/// Drawing the browser's panes.
+mod view;
+
+use std::collections::HashMap;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use self::app::{Action, App};
+use crate::core_api::Result;
+use crate::skelebuild::resolver::normalize_target_spec_for_storage;
+use crate::skelebuild::{SkeleEntry, SkeleState, SkeleTarget};
+use crate::{FeatureSelection, RenderFormat, Ripdoc, SearchDomain, SearchOptions};
+
+/// Largest number of search hits listed at once.
+const MAX_RESULTS: usize = 500;
+
+/// A terminal browser over one target.
+pub struct Browser {
+	ripdoc: Ripdoc,
+	target: String,
+	no_default_features: bool,
+	all_features: bool,
+	features: Vec<String>,
+	private: bool,
+	/// Rendered previews by item path.
+	previews: HashMap<String, String>,
+}
+
+impl Browser {
+	/// Browse `target`, rendering previews as Rust skeletons with `ripdoc`.
+	///
+	/// Parsed crates are kept in memory and build output is silenced, since it would draw over
+	/// the interface.
+	pub fn new(ripdoc: Ripdoc, target: impl Into<String>) -> Self {
+		Self {
+			ripdoc: ripdoc
+				.with_memory_cache(true)
+				.with_silent(true)
+				.with_render_format(RenderFormat::Rust),
+			target: target.into(),
+			no_default_features: false,
+			all_features: false,
+			features: Vec::new(),
+			private: false,
+			previews: HashMap::new(),
+		}
+	}
+
+	/// Feature selection used when building the browsed crates, and recorded on added items.
+	pub fn with_features(
+		mut self,
+		no_default_features: bool,
+		all_features: bool,
+		features: Vec<String>,
+	) -> Self {
+		self.no_default_features = no_default_features;
+		self.all_features = all_features;
+		self.features = features;
+		self
+	}
+
+	/// Include private items in the tree, search, and previews.
+	pub fn with_private(mut self, private: bool) -> Self {
+		self.private = private;
+		self
+	}
+
+	/// Take over the terminal until the user quits.
+	///
+	/// The crate is built before the terminal is switched over, so build failures are reported
+	/// as plain errors.
+	pub fn run(&mut self) -> Result<()> {
+		let items = self.ripdoc.list(
+			&self.target,
+			self.no_default_features,
+			self.all_features,
+			self.features.clone(),
+			self.private,
+			None,
+		)?;
+		let mut app = App::new(&items);
+		let mut terminal = ratatui::init();
+		let result = self.event_loop(&mut terminal, &mut app);
+		ratatui::restore();
+		result
+	}
+
+	fn event_loop(&mut self, terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+		loop {
+			let preview = match app.selected_path().map(str::to_string) {
+				Some(path) => self.preview(&path).to_string(),
+				None => String::new(),
+			};
+			terminal.draw(|frame| view::draw(frame, app, &self.target, &preview))?;
+
+			let Event::Key(key) = event::read()? else {
+				continue;
+			};
+			if key.kind != KeyEventKind::Press {
+				continue;
+			}
+			app.status = None;
+			match app.handle_key(key) {
+				Action::None => {}
+				Action::Quit => return Ok(()),
+				Action::Search(query) => {
+					let results = self.search(&query);
+					match results {
+						Ok(results) => app.set_results(results),
+						Err(err) => app.status = Some(format!("Search failed: {err}")),
+					}
+				}
+				Action::Add {
+					path,
+					implementation,
+				} => {
+					app.status = Some(match self.add_to_skelebuild(&path, implementation) {
+						Ok(message) => message,
+						Err(err) => format!("Could not add {path}: {err}"),
+					});
+				}
+			}
+		}
+	}
+
+	/// Fuzzy matches for `query` over names and paths, or `None` for an empty query.
+	fn search(&self, query: &str) -> Result<Option<Vec<crate::core_api::search::ListItem>>> {
+		if query.trim().is_empty() {
+			return Ok(None);
+		}
+		let mut options = SearchOptions::new(query);
+		options.domains = SearchDomain::NAMES | SearchDomain::PATHS;
+		options.fuzzy = true;
+		options.include_private = self.private;
+		options.expand_containers = false;
+		options.max_results = Some(MAX_RESULTS);
+		let items = self.ripdoc.list(
+			&self.target,
+			self.no_default_features,
+			self.all_features,
+			self.features.clone(),
+			self.private,
+			Some(&options),
+		)?;
+		Ok(Some(items))
+	}
+
+	/// The skeleton of the item at `path`, rendered on first use.
+	fn preview(&mut self, path: &str) -> &str {
+		if !self.previews.contains_key(path) {
+			let mut options = SearchOptions::new(path);
+			options.domains = SearchDomain::PATHS;
+			options.include_private = self.private;
+			let features = FeatureSelection {
+				no_default_features: self.no_default_features,
+				all_features: self.all_features,
+				features: self.features.clone(),
+			};
+			let rendered = match self.ripdoc.search(&self.target, features, &options, false, false) {
+				Ok(response) if response.rendered.trim().is_empty() => {
+					format!("No skeleton for {path}.")
+				}
+				Ok(response) => response.rendered,
+				Err(err) => format!("Could not render {path}: {err}"),
+			};
+			self.previews.insert(path.to_string(), rendered);
+		}
+		&self.previews[path]
+	}
+
+	/// Append the item at `path` to the skelebuild state, as `skelebuild add` would.
+	fn add_to_skelebuild(&self, path: &str, implementation: bool) -> Result<String> {
+		let spec = normalize_target_spec_for_storage(&format!("{}::{path}", self.target), &self.ripdoc);
+		let mut state = SkeleState::load(&self.ripdoc);
+		let present = state
+			.entries
+			.iter()
+			.any(|entry| matches!(entry, SkeleEntry::Target(target) if target.path == spec));
+		if present {
+			return Ok(format!("Already in the skeleton: {path}"));
+		}
+		state.entries.push(SkeleEntry::Target(SkeleTarget {
+			path: spec,
+			implementation,
+			raw_source: false,
+			private: true,
+			features: self.features.clone(),
+			all_features: self.all_features,
+			no_default_features: self.no_default_features,
+			note: None,
+		}));
+		state.save(&self.ripdoc)?;
+		Ok(format!(
+			"Added {path} as entry {}; run `ripdoc skelebuild rebuild` to update the output",
+			state.entries.len()
+		))
+	}
+}
@@ -0,0 +1,82 @@
+//! Drawing the browser's panes.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+
+use super::app::{App, Focus};
+
+/// Key help shown in the status line when there is no message.
+const HELP: &str = "/ search  ↑↓ move  ←→ fold  tab preview  a add  A add with body  q quit";
+
+/// Draw the whole browser: search bar, items and preview side by side, and a status line.
+pub(super) fn draw(frame: &mut Frame<'_>, app: &App, title: &str, preview: &str) {
+	let [search, body, status] =
+		Layout::vertical([Constraint::Length(3), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+	let [items, preview_area] =
+		Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(body);
+
+	draw_search(frame, app, search);
+	draw_items(frame, app, title, items);
+	let paragraph = Paragraph::new(preview)
+		.block(pane("Preview", app.focus == Focus::Preview))
+		.scroll((app.preview_scroll, 0));
+	frame.render_widget(paragraph, preview_area);
+	let message = app.status.as_deref().unwrap_or(HELP);
+	frame.render_widget(Paragraph::new(message).style(Style::default().fg(Color::DarkGray)), status);
+}
+
+fn draw_search(frame: &mut Frame<'_>, app: &App, area: Rect) {
+	let focused = app.focus == Focus::Search;
+	let text = if app.query.is_empty() && !focused {
+		Line::from(Span::styled("press / to search", Style::default().fg(Color::DarkGray)))
+	} else {
+		Line::from(app.query.as_str())
+	};
+	frame.render_widget(Paragraph::new(text).block(pane("Search", focused)), area);
+	if focused {
+		let column = area.x + 1 + app.query.chars().count() as u16;
+		frame.set_cursor_position((column.min(area.right().saturating_sub(2)), area.y + 1));
+	}
+}
+
+fn draw_items(frame: &mut Frame<'_>, app: &App, title: &str, area: Rect) {
+	let rows: Vec<ListItem<'_>> = app
+		.rows
+		.iter()
+		.map(|row| {
+			let marker = match (row.expandable, row.expanded) {
+				(false, _) => "  ",
+				(true, false) => "▸ ",
+				(true, true) => "▾ ",
+			};
+			ListItem::new(Line::from(vec![
+				Span::raw("  ".repeat(row.depth)),
+				Span::raw(marker),
+				Span::styled(format!("{:<12} ", row.kind.label()), Style::default().fg(Color::Cyan)),
+				Span::raw(row.label.as_str()),
+			]))
+		})
+		.collect();
+	let title = if app.query.is_empty() {
+		title.to_string()
+	} else {
+		format!("{title}: {} matches", app.rows.len())
+	};
+	let list = List::new(rows)
+		.block(pane(&title, app.focus == Focus::Items))
+		.highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+	let mut state = ListState::default().with_selected((!app.rows.is_empty()).then_some(app.selected));
+	frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+	let style = if focused {
+		Style::default().fg(Color::Yellow)
+	} else {
+		Style::default()
+	};
+	Block::default().borders(Borders::ALL).border_style(style).title(title)
+}
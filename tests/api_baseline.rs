@@ -0,0 +1,51 @@
+//! Integration tests for checked-in public API baselines.
+
+mod utils;
+
+use ripdoc::core_api::{ApiBaseline, crate_baseline};
+use utils::inspect_crate;
+
+fn baseline(source: &str) -> ApiBaseline {
+	ApiBaseline {
+		crates: vec![crate_baseline(&inspect_crate(source, false, false))],
+	}
+}
+
+#[test]
+fn check_fails_when_public_items_appear() {
+	let allowed = baseline(
+		r#"
+        /// Parses input.
+        pub fn parse(input: &str) -> u32 { 0 }
+        pub mod io {
+            pub struct Reader;
+        }
+        fn hidden() {}
+    "#,
+	);
+	let reparsed = ApiBaseline::parse(&allowed.render()).unwrap();
+	assert_eq!(reparsed, allowed);
+
+	let reformatted = baseline(
+		r#"
+        pub mod io { pub struct Reader; }
+        pub fn parse(input: &str) -> u32 { input.len() as u32 }
+        fn other_hidden(_: u8) {}
+    "#,
+	);
+	assert!(!reparsed.check(&reformatted).is_violation());
+
+	let grown = baseline(
+		r#"
+        pub fn parse(input: &str, strict: bool) -> u32 { 0 }
+        pub mod io {
+            pub struct Reader;
+            pub struct Writer;
+        }
+    "#,
+	);
+	let report = reparsed.check(&grown);
+	assert!(report.is_violation());
+	assert_eq!(report.added, vec!["struct dummy_crate::io::Writer".to_string()]);
+	assert_eq!(report.changed, vec!["function dummy_crate::parse".to_string()]);
+}
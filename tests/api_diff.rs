@@ -0,0 +1,88 @@
+//! Integration tests for structured API diffs.
+
+mod utils;
+
+use ripdoc::core_api::{ApiChangeKind, ApiDiff, SemverImpact, diff_crates};
+use utils::inspect_crate;
+
+#[test]
+fn diff_classifies_added_removed_and_changed_items() {
+	let old = inspect_crate(
+		r#"
+        pub fn parse(input: &str) -> u32 {
+            input.len() as u32
+        }
+
+        pub fn legacy() {}
+
+        pub enum Mode {
+            Fast,
+        }
+
+        #[non_exhaustive]
+        pub enum Level {
+            Low,
+        }
+
+        pub trait Sink {
+            fn write(&mut self, data: &[u8]);
+        }
+    "#,
+		false,
+		false,
+	);
+	let new = inspect_crate(
+		r#"
+        pub fn parse(input: &str, strict: bool) -> u32 {
+            if strict { 0 } else { input.len() as u32 }
+        }
+
+        pub fn fresh() {}
+
+        pub enum Mode {
+            Fast,
+            Slow,
+        }
+
+        #[non_exhaustive]
+        pub enum Level {
+            Low,
+            High,
+        }
+
+        pub trait Sink {
+            fn write(&mut self, data: &[u8]);
+            fn flush(&mut self) {}
+            fn close(&mut self);
+        }
+    "#,
+		false,
+		false,
+	);
+
+	let changes = diff_crates(&old, &new);
+	let find = |path: &str| {
+		changes
+			.iter()
+			.find(|change| change.path == path)
+			.unwrap_or_else(|| panic!("no change for {path}: {changes:#?}"))
+	};
+
+	assert_eq!(find("legacy").change, ApiChangeKind::Removed);
+	assert_eq!(find("legacy").impact, SemverImpact::Major);
+
+	let parse = find("parse");
+	assert_eq!(parse.change, ApiChangeKind::Changed);
+	assert!(parse.new_signature.as_deref().is_some_and(|sig| sig.contains("strict")));
+
+	assert_eq!(find("fresh").impact, SemverImpact::Minor);
+	assert_eq!(find("Mode::Slow").impact, SemverImpact::Major);
+	assert_eq!(find("Level::High").impact, SemverImpact::Minor);
+	assert_eq!(find("Sink::flush").impact, SemverImpact::Minor);
+	assert_eq!(find("Sink::close").impact, SemverImpact::Major);
+	assert!(changes.iter().all(|change| change.path != "Mode::Fast"));
+
+	let diff = ApiDiff::new("old", &old, "new", &new);
+	assert_eq!(diff.impact, SemverImpact::Major);
+	assert!(ApiDiff::new("a", &old, "b", &old).changes.is_empty());
+}
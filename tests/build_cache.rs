@@ -0,0 +1,33 @@
+//! Integration tests for the cache keys of local builds.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use std::path::PathBuf;
+
+use ripdoc::Ripdoc;
+use ripdoc::cargo_utils::CargoPath;
+use utils::create_test_crate;
+
+#[test]
+fn builds_into_a_configured_target_dir_keep_the_cache_key() {
+	let (temp_dir, target) = create_test_crate("pub fn kept() {}\n", false);
+	std::fs::create_dir(temp_dir.path().join(".cargo")).unwrap();
+	std::fs::write(temp_dir.path().join(".cargo/config.toml"), "[build]\ntarget-dir = \"tgt\"\n").unwrap();
+	// The cache lives inside the package as well, so both outputs must be left out.
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_cache_dir(temp_dir.path().join("ripdoc-cache"));
+	let key = || {
+		CargoPath::Path(PathBuf::from(&target))
+			.cache_key(false, false, Vec::new(), false, ripdoc.cache_config())
+			.unwrap()
+			.hash()
+	};
+
+	let before = key();
+	ripdoc.inspect(&target, false, false, Vec::new(), false).unwrap();
+	assert!(temp_dir.path().join("tgt/doc/dummy_crate.json").exists());
+	assert_eq!(key(), before);
+}
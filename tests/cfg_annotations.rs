@@ -0,0 +1,77 @@
+//! Integration tests for surfacing the cfg gates of feature-specific items.
+
+use std::fs;
+
+use ripdoc::{FeatureSelection, RenderFormat, Ripdoc};
+use tempfile::TempDir;
+
+fn gated_crate() -> Result<TempDir, Box<dyn std::error::Error>> {
+	let fixture = TempDir::new()?;
+	fs::create_dir_all(fixture.path().join("src"))?;
+	fs::write(
+		fixture.path().join("Cargo.toml"),
+		r#"
+[package]
+name = "gated"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+tls = []
+"#,
+	)?;
+	fs::write(
+		fixture.path().join("src/lib.rs"),
+		r#"
+pub struct Always;
+
+/// Opens a TLS connection.
+#[cfg(feature = "tls")]
+pub fn connect_tls() {}
+"#,
+	)?;
+	Ok(fixture)
+}
+
+#[test]
+fn cfg_gates_are_rendered_when_enabled() -> Result<(), Box<dyn std::error::Error>> {
+	let fixture = gated_crate()?;
+	let target = fixture.path().display().to_string();
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_render_format(RenderFormat::Rust)
+		.with_source_labels(false);
+	let features = FeatureSelection {
+		features: vec!["tls".to_string()],
+		..FeatureSelection::default()
+	};
+
+	let plain = ripdoc.render(&target, features.clone(), false, false, false)?;
+	assert!(!plain.rendered.contains("#[cfg("));
+
+	let annotated = ripdoc
+		.clone()
+		.with_cfg_annotations(true)
+		.render(&target, features, false, false, false)?;
+	assert!(
+		annotated
+			.rendered
+			.contains("/// Opens a TLS connection.\n\t#[cfg(feature = \"tls\")]\n\tpub fn connect_tls()")
+	);
+	Ok(())
+}
+
+#[test]
+fn list_items_carry_their_cfg() -> Result<(), Box<dyn std::error::Error>> {
+	let fixture = gated_crate()?;
+	let target = fixture.path().display().to_string();
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let items = ripdoc.list(&target, false, false, vec!["tls".to_string()], false, None)?;
+	let gated = items.iter().find(|item| item.path == "gated::connect_tls").unwrap();
+	assert_eq!(gated.cfg, vec!["feature = \"tls\"".to_string()]);
+	let always = items.iter().find(|item| item.path == "gated::Always").unwrap();
+	assert!(always.cfg.is_empty());
+	Ok(())
+}
@@ -0,0 +1,67 @@
+//! Integration tests for size-bounded chunk export.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::{RenderFormat, Ripdoc};
+use utils::create_test_crate;
+
+#[test]
+fn chunks_break_at_item_boundaries() {
+	let source = r#"
+        /// Configuration for the parser, documented at some length so that it takes up room.
+        pub struct Config {
+            /// Maximum nesting depth accepted by the parser.
+            pub depth: usize,
+            /// Whether trailing commas are allowed.
+            pub trailing_commas: bool,
+        }
+
+        /// Parse a document with the given configuration and return its length.
+        pub fn parse(input: &str, config: &Config) -> usize {
+            input.len()
+        }
+
+        pub mod io {
+            /// Read everything from the given path.
+            pub fn read(path: &str) -> String {
+                String::new()
+            }
+        }
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_render_format(RenderFormat::Rust);
+
+	let whole = ripdoc
+		.export_chunks(&target, false, false, Vec::new(), false, 100_000)
+		.unwrap();
+	assert_eq!(whole.len(), 1);
+	assert!(whole[0].items.iter().any(|item| item.ends_with("io::read")));
+
+	let chunks = ripdoc
+		.export_chunks(&target, false, false, Vec::new(), false, 150)
+		.unwrap();
+	assert!(chunks.len() >= 3, "expected one chunk per item, got {chunks:#?}");
+	let holding = |needle: &str| {
+		chunks
+			.iter()
+			.filter(|chunk| chunk.contents.contains(needle))
+			.count()
+	};
+	assert_eq!(holding("pub struct Config"), 1);
+	assert_eq!(holding("pub fn parse"), 1);
+	assert_eq!(holding("pub fn read"), 1);
+	let config = chunks
+		.iter()
+		.find(|chunk| chunk.contents.contains("pub struct Config"))
+		.unwrap();
+	assert!(config.contents.contains("pub trailing_commas: bool"));
+	assert!(chunks.iter().any(|chunk| chunk.module == "dummy_crate::io"));
+
+	let header = chunks[0].header(1, chunks.len(), RenderFormat::Rust);
+	assert!(header.starts_with(&format!("// ripdoc chunk 1/{}", chunks.len())));
+}
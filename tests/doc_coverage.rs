@@ -0,0 +1,54 @@
+//! Integration tests for the doc-coverage report.
+
+mod utils;
+
+use ripdoc::core_api::doc_coverage;
+use utils::inspect_crate;
+
+#[test]
+fn doc_coverage_groups_undocumented_items_by_module() {
+	let crate_data = inspect_crate(
+		r#"
+//! Crate docs.
+
+/// Documented.
+pub struct Config(pub u32);
+
+impl Clone for Config {
+    fn clone(&self) -> Self { Config(self.0) }
+}
+
+pub fn undocumented() {}
+
+/// Network types.
+pub mod net {
+    pub struct Addr {
+        /// The port.
+        pub port: u16,
+        pub host: String,
+    }
+
+    #[doc = ""]
+    pub fn blank() {}
+}
+"#,
+		false,
+		false,
+	);
+
+	let report = doc_coverage(&crate_data);
+	let modules: Vec<_> = report.modules.iter().map(|module| (module.path.as_str(), module.documented, module.items)).collect();
+	// The tuple field and the `Clone` impl's method are not expected to carry docs.
+	assert_eq!(modules, vec![("dummy_crate", 2, 3), ("dummy_crate::net", 2, 5)]);
+	assert_eq!((report.documented, report.items), (4, 8));
+
+	let missing: Vec<_> = report.modules[1].undocumented.iter().map(|item| (item.path.as_str(), item.blank)).collect();
+	assert_eq!(
+		missing,
+		vec![
+			("dummy_crate::net::Addr", false),
+			("dummy_crate::net::Addr::host", false),
+			("dummy_crate::net::blank", true),
+		]
+	);
+}
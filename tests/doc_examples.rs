@@ -0,0 +1,75 @@
+//! Integration tests for rendering doc-comment examples as separate sections.
+
+mod utils;
+
+use ripdoc::RenderFormat;
+use ripdoc::core_api::Renderer;
+use utils::inspect_crate;
+
+const SOURCE: &str = r#"
+/// Loads settings.
+///
+/// ```
+/// # use dummy_crate::load;
+/// let value = load();
+/// assert_eq!(value, 1);
+/// ```
+pub fn load() -> u32 {
+    1
+}
+
+pub struct Config;
+
+impl Config {
+    /// Builds a config.
+    ///
+    /// ```text
+    /// not an example
+    /// ```
+    ///
+    /// ```rust
+    /// let config = dummy_crate::Config::new();
+    /// ```
+    pub fn new() -> Self {
+        Config
+    }
+}
+"#;
+
+#[test]
+fn examples_follow_their_items_in_markdown() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let rendered = Renderer::default()
+		.with_source_labels(false)
+		.with_doc_examples(true)
+		.render(&crate_data)
+		.unwrap();
+
+	let load_example = rendered.find("**Example** (`dummy_crate::load`):").expect("load example");
+	assert!(rendered.find("pub fn load() -> u32").unwrap() < load_example);
+	assert!(rendered[load_example..].contains("let value = load();\nassert_eq!(value, 1);"));
+	assert!(!rendered[load_example..].contains("# use dummy_crate::load;"));
+	assert!(rendered.contains("**Example** (`dummy_crate::Config::new`):"));
+	assert_eq!(rendered.matches("**Example**").count(), 2);
+}
+
+#[test]
+fn examples_are_commented_out_in_rust_output() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_labels(false)
+		.with_doc_examples(true)
+		.render(&crate_data)
+		.unwrap();
+
+	assert!(rendered.contains("// ripdoc:example: dummy_crate::load"));
+	assert!(rendered.contains("// let value = load();"));
+
+	let without = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_labels(false)
+		.render(&crate_data)
+		.unwrap();
+	assert!(!without.contains("ripdoc:example"));
+}
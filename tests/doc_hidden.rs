@@ -0,0 +1,68 @@
+//! Integration tests for omitting `#[doc(hidden)]` items unless `--doc-hidden` is given.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use std::fs;
+
+use ripdoc::{FeatureSelection, Ripdoc, SearchOptions};
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+/// A crate whose docs.rs build keeps hidden items in the rustdoc JSON, as crates built with
+/// `--document-hidden-items` do.
+fn crate_with_hidden_items() -> (TempDir, String) {
+	let (temp_dir, target) = create_test_crate(
+		r#"
+/// Shown in the docs.
+pub fn visible_helper() {}
+
+/// Public for macros only.
+#[doc(hidden)]
+pub fn hidden_helper() {}
+"#,
+		false,
+	);
+	let manifest = temp_dir.path().join("Cargo.toml");
+	let mut contents = fs::read_to_string(&manifest).unwrap();
+	contents.push_str("\n[package.metadata.docs.rs]\nrustdoc-args = [\"--document-hidden-items\"]\n");
+	fs::write(&manifest, contents).unwrap();
+	(temp_dir, target)
+}
+
+fn ripdoc() -> Ripdoc {
+	Ripdoc::new().with_offline(true).with_silent(true).with_docs_rs_config(true)
+}
+
+#[test]
+fn render_omits_hidden_items_by_default() {
+	let (_temp_dir, target) = crate_with_hidden_items();
+
+	let rendered = ripdoc().render(&target, FeatureSelection::default(), false, false, false).unwrap().rendered;
+	assert!(rendered.contains("visible_helper"), "{rendered}");
+	assert!(!rendered.contains("hidden_helper"), "{rendered}");
+
+	let rendered =
+		ripdoc().with_doc_hidden(true).render(&target, FeatureSelection::default(), false, false, false).unwrap().rendered;
+	assert!(rendered.contains("visible_helper"), "{rendered}");
+	assert!(rendered.contains("hidden_helper"), "{rendered}");
+}
+
+#[test]
+fn search_omits_hidden_items_by_default() {
+	let (_temp_dir, target) = crate_with_hidden_items();
+	let mut options = SearchOptions::new("helper");
+
+	let searched = ripdoc().search(&target, FeatureSelection::default(), &options, false, false).unwrap();
+	let paths: Vec<_> = searched.results.iter().map(|result| result.path_string.as_str()).collect();
+	assert!(paths.contains(&"dummy_crate::visible_helper"), "{paths:?}");
+	assert!(!paths.contains(&"dummy_crate::hidden_helper"), "{paths:?}");
+	assert!(!searched.rendered.contains("hidden_helper"), "{}", searched.rendered);
+
+	options.include_doc_hidden = true;
+	let searched =
+		ripdoc().with_doc_hidden(true).search(&target, FeatureSelection::default(), &options, false, false).unwrap();
+	let paths: Vec<_> = searched.results.iter().map(|result| result.path_string.as_str()).collect();
+	assert!(paths.contains(&"dummy_crate::hidden_helper"), "{paths:?}");
+	assert!(searched.rendered.contains("hidden_helper"), "{}", searched.rendered);
+}
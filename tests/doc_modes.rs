@@ -0,0 +1,93 @@
+//! Integration tests for shortening, wrapping, or dropping doc comments.
+
+mod utils;
+
+use ripdoc::RenderFormat;
+use ripdoc::core_api::{DocMode, Renderer};
+use utils::inspect_crate;
+
+const SOURCE: &str = r#"
+/// Loads settings from disk.
+/// Falls back to defaults.
+///
+/// Reads `config.toml` from the working directory.
+pub fn load() -> u32 {
+    1
+}
+
+/// Saves settings.
+pub fn save() {}
+
+/// Writes the settings file back to disk, replacing whatever was there before.
+///
+/// ```
+/// demo::write();
+/// ```
+pub fn write() {}
+"#;
+
+fn render(mode: DocMode) -> String {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_labels(false)
+		.with_docs(mode)
+		.render(&crate_data)
+		.unwrap()
+}
+
+#[test]
+fn full_docs_are_the_default() {
+	let rendered = render(DocMode::Full);
+	assert!(rendered.contains("/// Reads `config.toml` from the working directory."));
+	assert!(!rendered.contains('…'));
+}
+
+#[test]
+fn first_paragraph_keeps_the_summary() {
+	let rendered = render(DocMode::FirstParagraph);
+	assert!(rendered.contains("/// Loads settings from disk.\n\t/// Falls back to defaults.\n\t/// …\n"));
+	assert!(!rendered.contains("config.toml"));
+	assert!(rendered.contains("/// Saves settings.\n\tpub fn save()"));
+}
+
+#[test]
+fn first_line_marks_the_cut_inline() {
+	let rendered = render(DocMode::FirstLine);
+	assert!(rendered.contains("/// Loads settings from disk. …\n\tpub fn load()"));
+	assert!(!rendered.contains("Falls back"));
+}
+
+#[test]
+fn none_drops_docs() {
+	let rendered = render(DocMode::None);
+	assert!(!rendered.contains("///"));
+	assert!(rendered.contains("pub fn load() -> u32"));
+}
+
+#[test]
+fn stripped_code_blocks_keep_prose() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_labels(false)
+		.with_doc_code_stripped(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(!rendered.contains("demo::write();"));
+	assert!(rendered.contains("replacing whatever was there before.\n\tpub fn write()"));
+}
+
+#[test]
+fn wrapped_docs_fit_the_width() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_labels(false)
+		.with_doc_wrap(Some(40))
+		.render(&crate_data)
+		.unwrap();
+	assert!(rendered.contains(
+		"/// Writes the settings file back to disk,\n\t/// replacing whatever was there before.\n"
+	));
+}
@@ -0,0 +1,62 @@
+//! Integration tests for the dyn-compatibility report.
+
+mod utils;
+
+use ripdoc::core_api::{GenericMethod, dyn_compat};
+use utils::inspect_crate;
+
+#[test]
+fn dyn_compat_reports_status_generic_methods_and_usage() {
+	let crate_data = inspect_crate(
+		r#"
+pub trait Handler {
+    fn handle(&self, request: &str);
+}
+
+pub trait Codec {
+    fn encode<W: std::io::Write>(&self, writer: W);
+    fn boxed(self) -> Box<Self> where Self: Sized { Box::new(self) }
+    fn map<F: Fn()>(&self, f: F) where Self: Sized;
+}
+
+pub fn register(handler: Box<dyn Handler>) {}
+
+pub fn serve(handler: impl Handler) {}
+
+pub fn serve_all<H>(handlers: &[H]) where H: Handler {}
+
+pub fn encode_with<C: Codec>(codec: &C) {}
+"#,
+		false,
+		false,
+	);
+
+	let report = dyn_compat(&crate_data, false);
+	let [codec, handler] = report.traits.as_slice() else {
+		panic!("expected two traits, got {:?}", report.traits);
+	};
+
+	assert_eq!(codec.path, "dummy_crate::Codec");
+	assert!(!codec.dyn_compatible);
+	assert_eq!(
+		codec.generic_methods,
+		vec![
+			GenericMethod {
+				name: "encode".to_string(),
+				requires_sized: false,
+			},
+			GenericMethod {
+				name: "map".to_string(),
+				requires_sized: true,
+			},
+		]
+	);
+	assert!(codec.dyn_users.is_empty());
+	assert_eq!(codec.impl_users, vec!["dummy_crate::encode_with"]);
+
+	assert_eq!(handler.path, "dummy_crate::Handler");
+	assert!(handler.dyn_compatible);
+	assert!(handler.generic_methods.is_empty());
+	assert_eq!(handler.dyn_users, vec!["dummy_crate::register"]);
+	assert_eq!(handler.impl_users, vec!["dummy_crate::serve", "dummy_crate::serve_all"]);
+}
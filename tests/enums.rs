@@ -114,6 +114,38 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			collapsed_variants: {
+				renderer: Renderer::default().with_format(RenderFormat::Rust).with_collapsed_variants(true),
+				input: r#"
+                    pub enum CollapsedEnum {
+                        Unit,
+                        Tuple(i32, String),
+                        Struct {
+                            field1: String,
+                            field2: bool,
+                        },
+                    }
+
+                    pub enum Discriminants {
+                        A = 1,
+                        B = 2,
+                    }
+                "#,
+				output: r#"
+                    pub enum CollapsedEnum {
+                    	Unit,
+                    	Tuple(/* .. */),
+                    	Struct { /* .. */ },
+                    }
+
+                    pub enum Discriminants {
+                    	A = 1,
+                    	B = 2,
+                    }
+                "#
+			}
+		}
 	}
 
 
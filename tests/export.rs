@@ -0,0 +1,49 @@
+//! Integration tests for item-level exports.
+
+mod utils;
+
+use ripdoc::core_api::{SearchItemKind, export_items};
+use utils::inspect_crate;
+
+#[test]
+fn export_items_carries_docs_signatures_and_visibility() {
+	let crate_data = inspect_crate(
+		r#"
+        /// Parse the input.
+        ///
+        /// Returns the length.
+        pub fn parse(input: &str) -> usize {
+            input.len()
+        }
+
+        pub mod io {
+            pub(crate) struct Buffer;
+
+            pub enum Mode {
+                Fast,
+            }
+        }
+
+        use std::fmt::Debug;
+    "#,
+		true,
+		false,
+	);
+	let items = export_items(&crate_data, true, None);
+
+	let parse = items.iter().find(|item| item.path.ends_with("::parse")).expect("parse exported");
+	assert_eq!(parse.kind, SearchItemKind::Function);
+	assert_eq!(parse.visibility, "pub");
+	assert_eq!(parse.docs.as_deref(), Some("Parse the input.\n\nReturns the length."));
+	assert!(parse.signature.as_deref().is_some_and(|sig| sig.contains("fn parse(input: &str) -> usize")));
+	let span = parse.span.as_ref().expect("span recorded");
+	assert!(span.end_line > span.start_line);
+
+	let buffer = items.iter().find(|item| item.path.ends_with("io::Buffer")).expect("Buffer exported");
+	assert_eq!(buffer.visibility, "pub(crate)");
+
+	let variant = items.iter().find(|item| item.path.ends_with("Mode::Fast")).expect("variant exported");
+	assert_eq!(variant.visibility, "inherited");
+
+	assert!(items.iter().all(|item| item.kind != SearchItemKind::Use));
+}
@@ -0,0 +1,41 @@
+//! Integration tests for external crate cross-references.
+
+mod utils;
+
+use ripdoc::core_api::extern_usage;
+use utils::inspect_crate;
+
+#[test]
+fn reports_items_exposing_toolchain_crates_only_on_request() {
+	let source = r#"
+        pub struct Registry {
+            pub names: Vec<String>,
+        }
+
+        pub fn lookup(registry: &Registry) -> Option<String> {
+            None
+        }
+
+        fn private_helper() -> std::collections::HashMap<u8, u8> {
+            Default::default()
+        }
+    "#;
+
+	let crate_data = inspect_crate(source, false, false);
+	assert!(extern_usage(&crate_data, false).is_empty());
+
+	let usages = extern_usage(&crate_data, true);
+	let leaks: Vec<_> = usages.iter().flat_map(|usage| usage.leaks.iter()).collect();
+	assert!(
+		leaks
+			.iter()
+			.any(|leak| leak.item.ends_with("::lookup") && leak.external.ends_with("String")),
+		"expected lookup to expose String, got {leaks:?}"
+	);
+	assert!(
+		leaks
+			.iter()
+			.any(|leak| leak.item.ends_with("::Registry::names") && leak.external.ends_with("Vec"))
+	);
+	assert!(!leaks.iter().any(|leak| leak.item.contains("private_helper")));
+}
@@ -0,0 +1,81 @@
+//! Integration tests for public API fingerprints.
+
+mod utils;
+
+use ripdoc::core_api::fingerprint;
+use utils::inspect_crate;
+
+#[test]
+fn fingerprint_ignores_order_docs_and_bodies() {
+	let first = inspect_crate(
+		r#"
+        /// Parses input.
+        pub fn parse(input: &str) -> u32 {
+            input.len() as u32
+        }
+
+        pub mod io {
+            pub struct Reader;
+            pub fn open() -> Reader { Reader }
+        }
+
+        fn hidden() {}
+    "#,
+		false,
+		false,
+	);
+	let second = inspect_crate(
+		r#"
+        pub mod io {
+            pub fn open() -> Reader {
+                let reader = Reader;
+                reader
+            }
+            pub struct Reader;
+        }
+
+        pub fn parse(input: &str) -> u32 { 0 }
+
+        fn other_hidden(_: u8) {}
+    "#,
+		false,
+		false,
+	);
+
+	let first = fingerprint(&first);
+	let second = fingerprint(&second);
+	assert_eq!(first.hash, second.hash);
+	assert_eq!(first.modules, second.modules);
+	assert!(first.modules.iter().any(|module| module.module == "io" && module.items == 2));
+}
+
+#[test]
+fn fingerprint_changes_with_signatures() {
+	let old = fingerprint(&inspect_crate(
+		r#"
+        pub fn parse(input: &str) -> u32 { 0 }
+        pub mod io {
+            pub struct Reader;
+        }
+    "#,
+		false,
+		false,
+	));
+	let new = fingerprint(&inspect_crate(
+		r#"
+        pub fn parse(input: &str, strict: bool) -> u32 { 0 }
+        pub mod io {
+            pub struct Reader;
+        }
+    "#,
+		false,
+		false,
+	));
+
+	assert_ne!(old.hash, new.hash);
+	let module_hash = |fp: &ripdoc::core_api::ApiFingerprint, name: &str| {
+		fp.modules.iter().find(|module| module.module == name).map(|module| module.hash.clone())
+	};
+	assert_eq!(module_hash(&old, "io"), module_hash(&new, "io"));
+	assert_ne!(module_hash(&old, ""), module_hash(&new, ""));
+}
@@ -23,6 +23,15 @@ gen_tests! {
                     for<'a> F: Fn(&'a str) -> bool,
                 {
                 }
+            "#
+		}
+		idemp {
+			deprecated_attributes: r#"
+                /// Opens the connection.
+                #[deprecated(since = "1.2.0", note = "use `connect` instead")]
+                pub fn open() {}
+                #[deprecated]
+                pub fn close() {}
             "#
 		}
 		idemp {
@@ -96,6 +105,33 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			simplified_lifetimes: {
+				renderer: Renderer::default().with_format(RenderFormat::Rust).with_simplified_lifetimes(true),
+				input: r#"
+                    pub fn first<'a, T>(items: &'a [T]) -> &'a T {
+                        &items[0]
+                    }
+                    pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+                        x
+                    }
+                    pub fn apply<F>(f: F)
+                    where
+                        F: for<'a> Fn(&'a str) -> &'a str,
+                    {
+                    }
+                "#,
+				output: r#"
+                    pub fn first<T>(items: &[T]) -> &T {}
+                    pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {}
+                    pub fn apply<F>(f: F)
+                    where
+                    	F: Fn(&str) -> &str,
+                    {
+                    }
+                "#
+			}
+		}
 	}
 
 }
@@ -60,7 +60,7 @@ fn use_glob_emits_single_gap_marker() {
 		.with_format(RenderFormat::Rust)
 		.with_selection(selection);
 
-	let rendered = renderer.render(&crate_data).unwrap();
+	let rendered = renderer.render(crate_data).unwrap();
 	let gap_count = rendered.matches(GAP_MARKER).count();
 	assert_eq!(
 		gap_count, 1,
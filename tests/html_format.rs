@@ -0,0 +1,36 @@
+//! Integration tests for the standalone HTML render format.
+
+mod utils;
+
+use ripdoc::RenderFormat;
+use ripdoc::core_api::Renderer;
+use utils::inspect_crate;
+
+#[test]
+fn html_page_links_sidebar_to_item_anchors() {
+	let source = r#"
+        pub mod io {
+            /// Reads <bytes>.
+            pub struct Reader {
+                pub buffer: Vec<u8>,
+            }
+        }
+
+        pub fn open(path: &str) -> io::Reader {
+            unimplemented!()
+        }
+    "#;
+	let crate_data = inspect_crate(source, false, false);
+	let page = Renderer::default()
+		.with_format(RenderFormat::Html)
+		.render(&crate_data)
+		.unwrap();
+
+	assert!(page.starts_with("<!DOCTYPE html>"));
+	assert!(page.trim_end().ends_with("</html>"));
+	assert!(page.contains("<span id=\"dummy_crate::io::Reader\"></span>"));
+	assert!(page.contains("<a href=\"#dummy_crate::io::Reader\">Reader</a>"));
+	assert!(page.contains("<a href=\"#dummy_crate::open\">open</a>"));
+	assert!(page.contains("Reads &lt;bytes&gt;."));
+	assert!(page.contains("<span class=\"kw\">fn</span>"));
+}
@@ -0,0 +1,38 @@
+//! Integration tests for whole-crate implementation mode.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::{FeatureSelection, Ripdoc};
+use utils::create_test_crate;
+
+#[test]
+fn max_impl_lines_elides_long_bodies() {
+	let source = r#"
+pub fn short() -> u32 {
+    1 + 1
+}
+
+pub fn long() -> u32 {
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    a + b + c
+}
+
+pub struct Counter(u32);
+
+impl Counter {
+    pub fn bump(&mut self) { self.0 += 1; }
+}
+"#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true).with_max_impl_lines(Some(3));
+	let rendered = ripdoc.render(&target, FeatureSelection::default(), false, true, false).unwrap().rendered;
+
+	assert!(rendered.contains("1 + 1"), "short body kept:\n{rendered}");
+	assert!(rendered.contains("self.0 += 1"), "short method kept:\n{rendered}");
+	assert!(!rendered.contains("let a = 1"), "long body elided:\n{rendered}");
+	assert!(rendered.contains("/* 6 lines elided */"), "elision marker:\n{rendered}");
+}
@@ -1,7 +1,8 @@
 //! Integration tests for impl block rendering.
+#![allow(clippy::tests_outside_test_module)]
 mod utils;
 use ripdoc::RenderFormat;
-use ripdoc::core_api::Renderer;
+use ripdoc::core_api::{DeriveStyle, Renderer};
 use utils::*;
 
 gen_tests! {
@@ -259,5 +260,56 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			derive_summary: {
+				renderer: Renderer::default().with_format(RenderFormat::Rust).with_derive_style(DeriveStyle::Summary),
+				input: r#"
+                    #[derive(Clone, Debug)]
+                    pub struct Point {
+                        pub x: i32,
+                    }
+                "#,
+				output: r#"
+                    /* derives: Clone, Debug */
+                    pub struct Point {
+                        pub x: i32,
+                    }
+                "#
+			}
+		}
 	}
 }
+
+#[test]
+fn derived_impls_follow_the_derive_style() {
+	let crate_data = inspect_crate(
+		r#"
+            #[derive(Clone, Debug)]
+            pub struct Point {
+                pub x: i32,
+            }
+        "#,
+		false,
+		false,
+	);
+	let render = |style| {
+		Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.with_derive_style(style)
+			.render(&crate_data)
+			.unwrap()
+	};
+
+	// Expanded, the derived traits appear as impl blocks and nothing marks them as derived.
+	let expanded = render(DeriveStyle::Impls);
+	assert!(expanded.contains("impl Clone for Point"), "{expanded}");
+	assert!(expanded.contains("impl Debug for Point"), "{expanded}");
+	assert!(!expanded.contains("#[derive("), "{expanded}");
+	assert!(!expanded.contains("/* derives"), "{expanded}");
+
+	// Summarized, the impl blocks are hidden behind a single comment.
+	let summary = render(DeriveStyle::Summary);
+	assert!(summary.contains("/* derives: Clone, Debug */"), "{summary}");
+	assert!(!summary.contains("impl Clone for Point"), "{summary}");
+	assert!(!summary.contains("impl Debug for Point"), "{summary}");
+}
@@ -0,0 +1,57 @@
+//! Integration tests for reverse impl lookups.
+
+mod utils;
+
+use ripdoc::core_api::{ImplSubject, find_impls};
+use utils::inspect_crate;
+
+const SOURCE: &str = r#"
+pub trait Service {
+    fn call(&self);
+}
+
+pub struct Stack;
+
+impl Service for Stack {
+    fn call(&self) {}
+}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T> Service for Wrapper<T> {
+    fn call(&self) {}
+}
+
+impl std::fmt::Display for Stack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("stack")
+    }
+}
+"#;
+
+#[test]
+fn trait_query_lists_local_implementors() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let report = find_impls(&crate_data, "Service", false).unwrap();
+	assert_eq!(report.subject, ImplSubject::Trait);
+	let types: Vec<&str> = report.impls.iter().map(|found| found.type_path.as_str()).collect();
+	assert_eq!(types, ["dummy_crate::Stack", "dummy_crate::Wrapper"]);
+
+	let display = find_impls(&crate_data, "fmt::Display", false).unwrap();
+	assert_eq!(display.impls.len(), 1);
+	assert_eq!(display.impls[0].type_path, "dummy_crate::Stack");
+}
+
+#[test]
+fn type_query_lists_traits_and_auto_impls_on_request() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let report = find_impls(&crate_data, "Stack", false).unwrap();
+	assert_eq!(report.subject, ImplSubject::Type);
+	assert!(report.impls.iter().all(|found| !found.synthetic && !found.blanket));
+	assert!(report.impls.iter().any(|found| found.trait_path == "dummy_crate::Service"));
+	assert!(report.impls.iter().any(|found| found.trait_path.ends_with("::Display")));
+
+	let with_auto = find_impls(&crate_data, "Stack", true).unwrap();
+	assert!(with_auto.impls.iter().any(|found| found.synthetic && found.trait_path.ends_with("::Send")));
+	assert!(find_impls(&crate_data, "Missing", true).is_none());
+}
@@ -0,0 +1,65 @@
+//! Integration tests for the structured JSON render format.
+
+mod utils;
+
+use ripdoc::RenderFormat;
+use ripdoc::core_api::{JsonDocument, Renderer};
+use utils::inspect_crate;
+
+fn render_json(renderer: Renderer, source: &str) -> JsonDocument {
+	let crate_data = inspect_crate(source, false, false);
+	let rendered = renderer
+		.with_format(RenderFormat::Json)
+		.render(&crate_data)
+		.unwrap();
+	serde_json::from_str(&rendered).expect("JSON render output should parse")
+}
+
+#[test]
+fn json_lists_items_with_signatures_and_docs() {
+	let source = r#"
+        /// A configurable widget.
+        pub struct Widget;
+
+        impl Widget {
+            /// Build a widget with the given size.
+            pub fn new(size: u32) -> Self {
+                Widget
+            }
+        }
+
+        fn hidden() {}
+    "#;
+
+	let document = render_json(Renderer::default(), source);
+	let widget = document
+		.items
+		.iter()
+		.find(|item| item.path.ends_with("::Widget"))
+		.expect("Widget record");
+	assert_eq!(widget.docs.as_deref(), Some("A configurable widget."));
+
+	let new = document
+		.items
+		.iter()
+		.find(|item| item.path.ends_with("::Widget::new"))
+		.expect("Widget::new record");
+	assert!(new.signature.as_deref().unwrap().contains("fn new(size: u32) -> Self"));
+	assert!(!new.matched);
+	assert!(!document.items.iter().any(|item| item.name == "hidden"));
+}
+
+#[test]
+fn json_respects_filter() {
+	let source = r#"
+        pub mod keep {
+            pub fn inside() {}
+        }
+
+        pub fn outside() {}
+    "#;
+
+	let document = render_json(Renderer::default().with_filter("keep"), source);
+	assert!(document.items.iter().any(|item| item.name == "inside"));
+	assert!(!document.items.iter().any(|item| item.name == "outside"));
+}
@@ -0,0 +1,67 @@
+//! Integration tests for the structural export lints.
+
+mod utils;
+
+use ripdoc::core_api::lint_exports;
+use ripdoc::{ExportLint, ExportLintKind};
+use utils::inspect_crate;
+
+fn kinds_for<'a>(lints: &'a [ExportLint], path_suffix: &str) -> Vec<&'a ExportLintKind> {
+	lints
+		.iter()
+		.filter(|lint| lint.path.ends_with(path_suffix))
+		.map(|lint| &lint.kind)
+		.collect()
+}
+
+#[test]
+fn flags_empty_modules_and_unreachable_items() {
+	let source = r#"
+        mod hidden {
+            pub struct Exposed;
+            pub struct Forgotten;
+        }
+
+        mod internal {
+            mod deeper {}
+        }
+
+        pub use hidden::Exposed;
+
+        pub mod api {
+            pub fn call() {}
+        }
+    "#;
+
+	let crate_data = inspect_crate(source, true, false);
+	let lints = lint_exports(&crate_data, None);
+
+	assert_eq!(kinds_for(&lints, "::deeper"), vec![&ExportLintKind::EmptyModule]);
+	assert_eq!(
+		kinds_for(&lints, "::hidden::Forgotten"),
+		vec![&ExportLintKind::UnreachablePublic]
+	);
+	assert!(kinds_for(&lints, "::hidden::Exposed").is_empty());
+	assert!(kinds_for(&lints, "::api::call").is_empty());
+}
+
+#[test]
+fn flags_glob_reexports_of_modules_without_public_items() {
+	let source = r#"
+        mod nothing_public {
+            fn helper() {}
+        }
+
+        pub use nothing_public::*;
+    "#;
+
+	let crate_data = inspect_crate(source, true, false);
+	let lints = lint_exports(&crate_data, None);
+
+	assert!(
+		lints
+			.iter()
+			.any(|lint| lint.kind == ExportLintKind::EmptyGlobReexport),
+		"expected an empty glob re-export finding, got {lints:?}"
+	);
+}
@@ -4,6 +4,7 @@
 mod utils;
 
 use pretty_assertions::assert_eq;
+use ripdoc::core_api::DeprecationFilter;
 use ripdoc::{Ripdoc, SearchDomain, SearchItemKind, SearchOptions};
 use utils::create_test_crate;
 
@@ -156,3 +157,128 @@ fn list_reports_source_paths() {
 	assert!(root_fn_source.path.ends_with("src/lib.rs"));
 	assert!(root_fn_source.line.is_some());
 }
+
+#[test]
+fn list_ranks_by_fan_in() {
+	let source = r#"
+        pub struct Config;
+        pub struct Client {
+            pub config: Config,
+        }
+        pub struct Unused;
+
+        pub fn connect(config: &Config) -> Client {
+            Client { config: Config }
+        }
+        pub fn reload(config: Config, other: Config) -> Config {
+            config
+        }
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_fan_in_ranking(true);
+
+	let items = ripdoc
+		.list(&target, false, false, Vec::new(), false, None)
+		.unwrap();
+	assert_eq!(items[0].path, "dummy_crate::Config");
+	// The field and both functions mention `Config`; `reload` counts once.
+	assert_eq!(items[0].fan_in, Some(3));
+	let unused = items.iter().find(|item| item.path == "dummy_crate::Unused").unwrap();
+	assert_eq!(unused.fan_in, Some(0));
+}
+
+#[test]
+fn list_filters_deprecated_items() {
+	let source = r#"
+        #[deprecated(since = "0.2.0", note = "use `connect_with`")]
+        pub fn connect() {}
+        pub fn connect_with() {}
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let items = ripdoc
+		.clone()
+		.with_list_deprecation(DeprecationFilter::Only)
+		.list(&target, false, false, Vec::new(), false, None)
+		.unwrap();
+	assert_eq!(items.len(), 1);
+	assert_eq!(items[0].path, "dummy_crate::connect");
+	assert!(items[0].deprecated);
+	assert_eq!(items[0].deprecation_note.as_deref(), Some("use `connect_with`"));
+
+	let mut options = SearchOptions::new("connect");
+	options.domains = SearchDomain::NAMES;
+	options.deprecation = DeprecationFilter::Exclude;
+	let items = ripdoc
+		.list(&target, false, false, Vec::new(), false, Some(&options))
+		.unwrap();
+	let paths: Vec<_> = items.into_iter().map(|item| item.path).collect();
+	assert_eq!(paths, vec!["dummy_crate::connect_with".to_string()]);
+}
+
+#[test]
+fn list_filters_by_kind() {
+	let source = r#"
+        pub trait Codec {
+            fn encode(&self);
+        }
+        pub struct Json;
+        pub fn encode_json() {}
+        #[macro_export]
+        macro_rules! codec { () => {}; }
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	// Without a query, the kinds alone select items.
+	let mut options = SearchOptions::new("");
+	options.kinds = Some([SearchItemKind::Trait, SearchItemKind::Macro].into_iter().collect());
+	let items = ripdoc
+		.list(&target, false, false, Vec::new(), false, Some(&options))
+		.unwrap();
+	let paths: Vec<_> = items.into_iter().map(|item| item.path).collect();
+	assert_eq!(paths, vec!["dummy_crate::Codec", "dummy_crate::codec"]);
+
+	let mut options = SearchOptions::new("encode");
+	options.domains = SearchDomain::NAMES;
+	options.kinds = Some([SearchItemKind::Function].into_iter().collect());
+	let items = ripdoc
+		.list(&target, false, false, Vec::new(), false, Some(&options))
+		.unwrap();
+	let paths: Vec<_> = items.into_iter().map(|item| item.path).collect();
+	assert_eq!(paths, vec!["dummy_crate::encode_json"]);
+}
+
+#[test]
+fn list_skips_excluded_paths() {
+	let source = r#"
+        pub mod runtime {
+            pub struct Runtime;
+        }
+        pub mod net {
+            pub struct Socket;
+        }
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_exclude(vec!["dummy_crate::runtime".to_string()]);
+
+	let items = ripdoc.list(&target, false, false, Vec::new(), false, None).unwrap();
+	let paths: Vec<_> = items.into_iter().map(|item| item.path).collect();
+	assert!(paths.contains(&"dummy_crate::net::Socket".to_string()));
+	assert!(
+		!paths
+			.iter()
+			.any(|path| path.starts_with("dummy_crate::runtime"))
+	);
+}
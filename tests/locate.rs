@@ -0,0 +1,30 @@
+//! Integration tests for resolving items to source locations.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::Ripdoc;
+use utils::create_test_crate;
+
+#[test]
+fn locate_reports_file_line_and_column() {
+	let source = "pub mod io {\n    pub struct Reader;\n}\n\npub fn read() {}\n";
+
+	let (temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let locations = ripdoc
+		.locate(&target, Some("Reader"), false, false, Vec::new(), false)
+		.unwrap();
+	assert_eq!(locations.len(), 1);
+	let reader = &locations[0];
+	assert_eq!(reader.item, "dummy_crate::io::Reader");
+	assert_eq!(reader.file, temp_dir.path().join("src/lib.rs"));
+	assert_eq!((reader.line, reader.column), (2, 5));
+	assert!(reader.quickfix_line().ends_with("src/lib.rs:2:5: struct dummy_crate::io::Reader"));
+
+	let missing = ripdoc
+		.locate(&target, Some("Writer"), false, false, Vec::new(), false)
+		.unwrap();
+	assert!(missing.is_empty());
+}
@@ -0,0 +1,56 @@
+//! Integration tests for single-item manual pages.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::Ripdoc;
+use ripdoc::core_api::DocOptions;
+use utils::create_test_crate;
+
+#[test]
+fn man_page_collects_signature_members_impls_and_related_items() {
+	let source = r#"
+pub mod io {
+    /// Configuration for readers.
+    pub struct Options;
+
+    /// Buffers bytes read from a source before handing them out in chunks of the requested size.
+    pub struct Reader<T> where T: Clone {
+        pub source: T,
+    }
+
+    impl<T: Clone> Reader<T> {
+        pub fn with_options(source: T, options: Options) -> Self {
+            Reader { source }
+        }
+    }
+
+    impl<T: Clone> Clone for Reader<T> {
+        fn clone(&self) -> Self {
+            Reader { source: self.source.clone() }
+        }
+    }
+}
+"#;
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let pages = ripdoc
+		.man(&target, Some("io::Reader"), false, false, Vec::new(), false)
+		.unwrap();
+	assert_eq!(pages.len(), 1);
+	let page = &pages[0];
+	assert_eq!(page.path, "dummy_crate::io::Reader");
+	assert_eq!(page.signature.as_deref(), Some("pub struct Reader<T>"));
+	assert_eq!(page.where_clauses, vec!["T: Clone".to_string()]);
+	assert!(page.members.iter().any(|member| member.contains("fn with_options")));
+	assert!(page.members.iter().any(|member| member.contains("source: T")));
+	assert!(page.impls.iter().any(|line| line.starts_with("impl core::clone::Clone for")));
+	assert!(page.see_also.contains(&"dummy_crate::io".to_string()));
+
+	let text = page.render(40, &DocOptions::default());
+	assert!(text.starts_with("NAME\n    dummy_crate::io::Reader - struct\n"));
+	assert!(text.contains("\nWHERE\n    T: Clone,\n"));
+	assert!(text.contains("\nDESCRIPTION\n    Buffers bytes read from a source\n    before"));
+	assert!(text.contains("\nSEE ALSO\n"));
+}
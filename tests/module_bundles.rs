@@ -0,0 +1,54 @@
+//! Integration tests for per-module Markdown bundles.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use std::fs;
+
+use ripdoc::{RenderFormat, Ripdoc};
+use utils::create_test_crate;
+
+#[test]
+fn bundles_pair_module_docs_with_included_files() {
+	let source = r#"
+        #![doc = include_str!("../GUIDE.md")]
+
+        /// Parse a document.
+        pub fn parse(input: &str) -> usize {
+            input.len()
+        }
+
+        /// Input and output helpers.
+        pub mod io {
+            /// Read everything from the given path.
+            pub fn read(path: &str) -> String {
+                String::new()
+            }
+        }
+    "#;
+
+	let (temp_dir, target) = create_test_crate(source, false);
+	fs::write(temp_dir.path().join("GUIDE.md"), "# Guide\n\nStart with `parse`.\n").unwrap();
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_render_format(RenderFormat::Markdown);
+
+	let bundles = ripdoc
+		.export_bundles(&target, false, false, Vec::new(), false)
+		.unwrap();
+	let modules: Vec<&str> = bundles.iter().map(|bundle| bundle.module.as_str()).collect();
+	assert_eq!(modules, vec!["dummy_crate", "dummy_crate::io"]);
+
+	let root = &bundles[0];
+	assert_eq!(root.included.len(), 1);
+	assert_eq!(root.included[0].path, "../GUIDE.md");
+	assert!(root.to_markdown().contains("Start with `parse`."));
+	assert!(root.skeleton.contains("pub fn parse"));
+	assert!(!root.skeleton.contains("pub fn read"));
+
+	let io = &bundles[1];
+	assert!(io.docs.as_deref().unwrap_or("").contains("Input and output helpers."));
+	assert!(io.skeleton.contains("pub fn read"));
+	assert_eq!(io.file_path(), std::path::Path::new("dummy_crate/io.md"));
+}
@@ -1,5 +1,7 @@
 //! Integration tests covering module rendering scenarios.
 mod utils;
+use ripdoc::RenderFormat;
+use ripdoc::core_api::{Renderer, VisibilityLevel};
 use utils::*;
 
 gen_tests! {
@@ -330,5 +332,193 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			restricted_visibility: {
+				renderer: Renderer::default().with_format(RenderFormat::Rust).with_private_items(true),
+				input: r#"
+                    pub mod outer {
+                        pub(crate) fn crate_fn() {}
+                        fn private_fn() {}
+                        pub mod inner {
+                            pub(super) fn parent_fn() {}
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub mod outer {
+                        pub mod inner {
+                            pub(super) fn parent_fn() {}
+                        }
+                        pub(crate) fn crate_fn() {}
+                        fn private_fn() {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			min_visibility_crate: {
+				renderer: Renderer::default()
+					.with_format(RenderFormat::Rust)
+					.with_private_items(true)
+					.with_min_visibility(Some(VisibilityLevel::Crate)),
+				input: r#"
+                    pub mod outer {
+                        pub(crate) fn crate_fn() {}
+                        fn private_fn() {}
+                        pub mod inner {
+                            pub(super) fn parent_fn() {}
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub mod outer {
+                        pub mod inner {}
+                        pub(crate) fn crate_fn() {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			min_visibility_crate_keeps_crate_root_items: {
+				renderer: Renderer::default()
+					.with_format(RenderFormat::Rust)
+					.with_private_items(true)
+					.with_min_visibility(Some(VisibilityLevel::Crate)),
+				input: r#"
+                    pub(crate) fn root_crate_fn() {}
+                    pub mod m {
+                        pub(crate) fn inner_crate_fn() {}
+                        fn inner_private_fn() {}
+                    }
+                "#,
+				output: r#"
+                    pub mod m {
+                        pub(crate) fn inner_crate_fn() {}
+                    }
+                    fn root_crate_fn() {}
+                "#
+			}
+		}
+		rt_custom {
+			strict_public_drops_unexported_items: {
+				renderer: Renderer::default()
+					.with_format(RenderFormat::Rust)
+					.with_private_items(true)
+					.with_strict_public(true),
+				input: r#"
+                    mod hidden {
+                        pub struct Hidden;
+                        pub struct Exported;
+                    }
+                    pub use hidden::Exported;
+                    pub(crate) fn helper() {}
+                    pub fn api() {}
+                "#,
+				output: r#"
+                    pub struct Exported;
+                    pub fn api() {}
+                "#
+			}
+		}
+		rt_custom {
+			summary_lists_module_inventories: {
+				renderer: Renderer::default()
+					.with_format(RenderFormat::Rust)
+					.with_module_summary(true),
+				input: r#"
+                    pub mod net {
+                        pub struct Addr;
+                        pub struct Socket;
+                        pub trait Connect {}
+                        pub mod tcp {
+                            pub struct Stream;
+                            pub fn connect() {}
+                            pub fn listen() {}
+                        }
+                        mod internal {
+                            pub struct Hidden;
+                        }
+                    }
+                    pub mod consts {
+                        pub const PORT: u16 = 80;
+                    }
+                    pub mod empty {}
+                    pub fn run() {}
+                "#,
+				output: r#"
+                    // 1 fn
+
+                    pub mod net {
+                        // 2 structs, 1 trait
+
+                        pub mod tcp; // 1 struct, 2 fns
+                    }
+
+                    pub mod consts; // 1 const
+                    pub mod empty;
+                "#
+			}
+		}
+		rt_custom {
+			exclude_prunes_matching_paths: {
+				renderer: Renderer::default()
+					.with_format(RenderFormat::Rust)
+					.with_exclude(vec![
+						"dummy_crate::runtime".to_string(),
+						"dummy_crate::net::*_impl".to_string(),
+					]),
+				input: r#"
+                    pub mod runtime {
+                        pub struct Runtime;
+                        pub fn block_on() {}
+                    }
+                    pub mod net {
+                        pub struct Socket;
+                        pub fn connect_impl() {}
+                    }
+                    pub fn spawn() {}
+                "#,
+				output: r#"
+                    pub mod net {
+                        pub struct Socket;
+                    }
+
+                    pub fn spawn() {}
+                "#
+			}
+		}
 	}
 }
+
+#[test]
+fn source_labels_mark_each_file_section_relative_to_the_package() {
+	let (temp_dir, target) = create_test_crate(
+		"pub mod inline {\n    pub struct Inline;\n}\npub mod child;\npub struct Last;\n",
+		false,
+	);
+	std::fs::write(temp_dir.path().join("src/child.rs"), "pub struct Nested;\n").unwrap();
+	let crate_data = ripdoc::Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.inspect(&target, false, false, Vec::new(), false)
+		.unwrap()
+		.remove(0);
+
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_source_root(temp_dir.path().to_path_buf())
+		.render(&crate_data)
+		.unwrap();
+	let labels: Vec<&str> = rendered
+		.lines()
+		.filter_map(|line| line.trim().strip_prefix("// ripdoc:source: "))
+		.collect();
+
+	// rustdoc lists submodules before other items, so `Last` follows `child`. lib.rs is labelled
+	// again once rendering returns from the child module's file.
+	assert_eq!(labels, ["src/lib.rs", "src/child.rs", "src/lib.rs"]);
+	// Modules carry the label of the file they are declared in, ahead of their `mod` line.
+	let position = |needle: &str| rendered.find(needle).unwrap_or_else(|| panic!("{needle} missing from:\n{rendered}"));
+	assert!(position("// ripdoc:source: src/lib.rs") < position("pub mod inline {"), "{rendered}");
+	assert!(position("// ripdoc:source: src/child.rs") < position("pub mod child {"), "{rendered}");
+}
@@ -0,0 +1,36 @@
+//! Integration tests for the renderer coverage report.
+
+mod utils;
+
+use ripdoc::core_api::{MissingReason, Renderer, render_coverage};
+use utils::inspect_crate;
+
+#[test]
+fn coverage_reports_unsupported_and_filtered_items() {
+	let crate_data = inspect_crate(
+		r#"
+pub struct Config;
+
+pub static LIMIT: u32 = 3;
+
+pub mod inner {
+    pub fn helper() {}
+}
+"#,
+		false,
+		false,
+	);
+
+	let report = render_coverage(&crate_data, Renderer::default()).unwrap();
+	let missing: Vec<_> = report.missing.iter().map(|item| (item.path.as_str(), item.reason)).collect();
+	assert_eq!(missing, vec![("dummy_crate::LIMIT", MissingReason::UnsupportedKind)]);
+
+	let filtered = render_coverage(&crate_data, Renderer::default().with_filter("inner")).unwrap();
+	assert!(
+		filtered
+			.missing
+			.iter()
+			.any(|item| item.path == "dummy_crate::Config" && item.reason == MissingReason::Filtered)
+	);
+	assert!(!filtered.missing.iter().any(|item| item.path == "dummy_crate::inner::helper"));
+}
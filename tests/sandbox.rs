@@ -0,0 +1,39 @@
+//! Integration tests for `ripdoc try` sandbox projects.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use std::fs;
+
+use ripdoc::{FeatureSelection, Ripdoc};
+use ripdoc::core_api::Sandbox;
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+#[test]
+fn sandbox_depends_on_local_crate_by_path() {
+	let (temp_dir, target) = create_test_crate("pub fn hello() {}\n", false);
+	let manifest = temp_dir.path().join("Cargo.toml");
+	let mut contents = fs::read_to_string(&manifest).unwrap();
+	contents.push_str("\n[features]\nfast = []\nslow = []\n");
+	fs::write(&manifest, contents).unwrap();
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let location = ripdoc.locations(&target).unwrap().remove(0);
+	let parent = TempDir::new().unwrap();
+	let features = FeatureSelection { no_default_features: true, all_features: true, features: Vec::new() };
+	let sandbox = Sandbox::create(parent.path(), &location, &features, "pub fn hello();\n", "skeleton.rs").unwrap();
+
+	assert!(sandbox.dir.starts_with(parent.path()));
+	assert_eq!(sandbox.package, "dummy_crate");
+	assert_eq!(sandbox.version, None);
+	assert_eq!(fs::read_to_string(&sandbox.skeleton).unwrap(), "pub fn hello();\n");
+	assert!(fs::read_to_string(&sandbox.main).unwrap().contains("use dummy_crate as _;"));
+
+	let cargo_toml = fs::read_to_string(&sandbox.manifest).unwrap();
+	assert!(cargo_toml.contains("name = \"try-dummy-crate\""));
+	let dependency = cargo_toml.lines().find(|line| line.starts_with("dummy_crate = ")).unwrap();
+	assert!(dependency.contains(&format!("path = \"{}\"", location.root.display())));
+	assert!(dependency.contains("default-features = false"));
+	assert!(dependency.contains("features = [\"fast\", \"slow\"]"));
+}
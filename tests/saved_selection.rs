@@ -0,0 +1,52 @@
+//! Integration tests for saving and re-applying render selections.
+
+mod utils;
+
+use ripdoc::core_api::{SearchItemKind, SelectedItem};
+use ripdoc::{FeatureSelection, Ripdoc, SavedSelection, SearchOptions};
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+#[test]
+fn saved_selection_rerenders_the_searched_items() -> Result<(), Box<dyn std::error::Error>> {
+	let (_crate_dir, target) = create_test_crate(
+		r#"
+pub struct Config {
+    pub verbose: bool,
+}
+
+pub fn load_config() -> Config {
+    Config { verbose: false }
+}
+
+pub fn unrelated() {}
+"#,
+		false,
+	);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let options = SearchOptions::new("config");
+	let searched = ripdoc.search(&target, FeatureSelection::default(), &options, false, false)?;
+	assert!(!searched.results.is_empty());
+
+	let out = TempDir::new()?;
+	let path = out.path().join("selection.json");
+	SavedSelection::from_results(&searched.results, options.expand_containers).save(&path)?;
+	let mut selection = SavedSelection::load(&path)?;
+
+	let replayed =
+		ripdoc.render_saved_selection(&target, FeatureSelection::default(), false, &selection, false, false)?;
+	assert_eq!(replayed.rendered, searched.rendered);
+	assert!(!replayed.rendered.contains("unrelated"));
+	assert!(selection.missing(&replayed.results).is_empty());
+
+	selection.items.push(SelectedItem {
+		path: "dummy_crate::removed".to_string(),
+		kind: SearchItemKind::Function,
+	});
+	let missing = selection.missing(&replayed.results);
+	assert_eq!(missing.len(), 1);
+	assert_eq!(missing[0].path, "dummy_crate::removed");
+
+	Ok(())
+}
@@ -0,0 +1,39 @@
+//! Integration tests for searching every cached crate.
+
+mod utils;
+
+use ripdoc::{Ripdoc, SearchOptions};
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+#[test]
+fn search_cache_finds_items_in_every_cached_crate() {
+	let cache = TempDir::new().unwrap();
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_cache_dir(cache.path().to_path_buf());
+
+	let (_crate_dir, target) = create_test_crate(
+		r#"
+pub struct ConfigLoader;
+
+pub fn load_config() {}
+"#,
+		false,
+	);
+	// Building the crate once is what puts it in the cache.
+	ripdoc.inspect(&target, false, false, Vec::new(), false).unwrap();
+
+	let output = ripdoc.search_cache(&SearchOptions::new("ConfigLoader")).unwrap();
+	assert!(output.errors.is_empty());
+	let [package] = output.packages.as_slice() else {
+		panic!("expected one matching crate, got {:?}", output.packages);
+	};
+	assert_eq!(package.name, "dummy_crate");
+	assert_eq!(package.version.as_deref(), Some("0.1.0"));
+	assert!(package.items.iter().any(|item| item.path == "dummy_crate::ConfigLoader"));
+
+	let none = ripdoc.search_cache(&SearchOptions::new("NoSuchItem")).unwrap();
+	assert!(none.packages.is_empty());
+}
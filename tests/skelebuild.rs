@@ -1,7 +1,7 @@
 //! Integration tests for `skelebuild` workflows.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use ripdoc::Ripdoc;
 use ripdoc::core_api::search::{SearchDomain, SearchIndex, SearchItemKind, SearchOptions};
@@ -83,7 +83,7 @@ fn main() {}
 	temp_dir
 }
 
-fn find_inherent_save_path(crate_dir: &PathBuf) -> String {
+fn find_inherent_save_path(crate_dir: &Path) -> String {
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 	let crates = ripdoc
 		.inspect(
@@ -120,14 +120,12 @@ fn find_inherent_save_path(crate_dir: &PathBuf) -> String {
 		}
 	}
 
-	let inherent = inherent.unwrap_or_else(|| {
+	inherent.unwrap_or_else(|| {
 		panic!(
 			"failed to find inherent save path; candidates: {:?}",
 			candidates
 		)
-	});
-
-	inherent
+	})
 }
 
 #[test]
@@ -141,42 +139,56 @@ fn skelebuild_realistic_session_produces_detailed_markdown()
 
 	let inherent_save = find_inherent_save_path(&crate_dir);
 
-	let mut state = SkeleState::default();
-	state.output_path = Some(out_path.clone());
-	state.plain = true;
-	state.entries = vec![
-		SkeleEntry::Injection(SkeleInjection {
-			content: "## Intro\nThis is injected commentary.".to_string(),
-		}),
-		// Users often guess the crate prefix from the package name (tome_term), but for bin crates
-		// rustdoc uses the bin name (tome). skelebuild should still resolve this.
-		SkeleEntry::Target(SkeleTarget {
-			path: format!(
-				"{}::tome_term::terminal_panel::TerminalState",
-				crate_dir.display()
-			),
-			implementation: true,
-			raw_source: false,
-			private: false,
-		}),
-		SkeleEntry::Injection(SkeleInjection {
-			// Stored injections are literal; CLI `inject` now unescapes `\\n` by default.
-			content: "### Notes\n- first\n- second".to_string(),
-		}),
-		SkeleEntry::Target(SkeleTarget {
-			path: format!("{}::{inherent_save}", crate_dir.display()),
-			implementation: true,
-			raw_source: false,
-			private: false,
-		}),
-		// Target an entire impl block via `Type::Trait`.
-		SkeleEntry::Target(SkeleTarget {
-			path: format!("{}::editor::Editor::EditorOps", crate_dir.display()),
-			implementation: false,
-			raw_source: false,
-			private: false,
-		}),
-	];
+	let state = SkeleState {
+		output_path: Some(out_path.clone()),
+		plain: true,
+		entries: vec![
+			SkeleEntry::Injection(SkeleInjection {
+				content: "## Intro\nThis is injected commentary.".to_string(),
+			}),
+			// Users often guess the crate prefix from the package name (tome_term), but for bin crates
+			// rustdoc uses the bin name (tome). skelebuild should still resolve this.
+			SkeleEntry::Target(SkeleTarget {
+				path: format!(
+					"{}::tome_term::terminal_panel::TerminalState",
+					crate_dir.display()
+				),
+				implementation: true,
+				raw_source: false,
+				private: false,
+				features: Vec::new(),
+				all_features: false,
+				no_default_features: false,
+				note: None,
+			}),
+			SkeleEntry::Injection(SkeleInjection {
+				// Stored injections are literal; CLI `inject` now unescapes `\\n` by default.
+				content: "### Notes\n- first\n- second".to_string(),
+			}),
+			SkeleEntry::Target(SkeleTarget {
+				path: format!("{}::{inherent_save}", crate_dir.display()),
+				implementation: true,
+				raw_source: false,
+				private: false,
+				features: Vec::new(),
+				all_features: false,
+				no_default_features: false,
+				note: None,
+			}),
+			// Target an entire impl block via `Type::Trait`.
+			SkeleEntry::Target(SkeleTarget {
+				path: format!("{}::editor::Editor::EditorOps", crate_dir.display()),
+				implementation: false,
+				raw_source: false,
+				private: false,
+				features: Vec::new(),
+				all_features: false,
+				no_default_features: false,
+				note: None,
+			}),
+		],
+		..SkeleState::default()
+	};
 
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 	state.rebuild(&ripdoc)?;
@@ -223,6 +235,7 @@ fn skelebuild_canonical_path_matching() -> Result<(), Box<dyn std::error::Error>
 		canonical_key: Some("test.rs".to_string()),
 		start_line: None,
 		end_line: None,
+		note: None,
 	};
 
 	let entries = vec![
@@ -231,20 +244,24 @@ fn skelebuild_canonical_path_matching() -> Result<(), Box<dyn std::error::Error>
 			implementation: true,
 			raw_source: false,
 			private: true,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
 		}),
 		SkeleEntry::RawSource(raw_source),
 	];
 
 	// Test 1: Match by canonical key
-	let idx = find_entry_match(&entries, "test.rs")?;
+	let idx = find_entry_match(&entries, "test.rs", &Ripdoc::new())?;
 	assert_eq!(idx, 1, "Should match raw source by canonical key");
 
 	// Test 2: Match target by path
-	let idx = find_entry_match(&entries, "crate::module::Type")?;
+	let idx = find_entry_match(&entries, "crate::module::Type", &Ripdoc::new())?;
 	assert_eq!(idx, 0, "Should match target by path");
 
 	// Test 3: Match by absolute path
-	let idx = find_entry_match(&entries, test_file.to_str().unwrap())?;
+	let idx = find_entry_match(&entries, test_file.to_str().unwrap(), &Ripdoc::new())?;
 	assert_eq!(idx, 1, "Should match raw source by absolute path");
 
 	Ok(())
@@ -272,12 +289,13 @@ fn skelebuild_canonical_key_normalization() -> Result<(), Box<dyn std::error::Er
 		canonical_key: Some("crates/foo/src/lib.rs".to_string()),
 		start_line: None,
 		end_line: None,
+		note: None,
 	};
 
 	let entries = vec![SkeleEntry::RawSource(raw_source)];
 
 	// Test matching with canonical key
-	let idx = find_entry_match(&entries, "crates/foo/src/lib.rs")?;
+	let idx = find_entry_match(&entries, "crates/foo/src/lib.rs", &Ripdoc::new())?;
 	assert_eq!(idx, 0, "Should match by exact canonical key");
 
 	Ok(())
@@ -293,17 +311,22 @@ fn skelebuild_find_entry_match_error_shows_available_keys() {
 			implementation: true,
 			raw_source: false,
 			private: true,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
 		}),
 		SkeleEntry::RawSource(SkeleRawSource {
 			file: PathBuf::from("/tmp/test.rs"),
 			canonical_key: Some("src/test.rs".to_string()),
 			start_line: None,
 			end_line: None,
+			note: None,
 		}),
 	];
 
 	// Try to match a non-existent entry
-	let result = find_entry_match(&entries, "nonexistent::path");
+	let result = find_entry_match(&entries, "nonexistent::path", &Ripdoc::new());
 	assert!(result.is_err());
 
 	let error_msg = result.unwrap_err().to_string();
@@ -335,16 +358,20 @@ fn skelebuild_find_entry_match_partial_target_path() -> Result<(), Box<dyn std::
 		implementation: true,
 		raw_source: false,
 		private: true,
+		features: Vec::new(),
+		all_features: false,
+		no_default_features: false,
+		note: None,
 	})];
 
 	// Should match by just the item path suffix
-	let idx = find_entry_match(&entries, "Type")?;
+	let idx = find_entry_match(&entries, "Type", &Ripdoc::new())?;
 	assert_eq!(idx, 0, "Should match target by last segment");
 
-	let idx = find_entry_match(&entries, "submodule::Type")?;
+	let idx = find_entry_match(&entries, "submodule::Type", &Ripdoc::new())?;
 	assert_eq!(idx, 0, "Should match target by path suffix");
 
-	let idx = find_entry_match(&entries, "module::submodule::Type")?;
+	let idx = find_entry_match(&entries, "module::submodule::Type", &Ripdoc::new())?;
 	assert_eq!(idx, 0, "Should match target by longer path suffix");
 
 	Ok(())
@@ -367,36 +394,45 @@ fn skelebuild_injection_placement_with_mixed_entries() -> Result<(), Box<dyn std
 			implementation: true,
 			raw_source: false,
 			private: true,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
 		}),
 		SkeleEntry::RawSource(SkeleRawSource {
 			file: PathBuf::from("/tmp/raw.rs"),
 			canonical_key: Some("src/raw.rs".to_string()),
 			start_line: Some(1),
 			end_line: Some(10),
+			note: None,
 		}),
 		SkeleEntry::Target(SkeleTarget {
 			path: "crate::second::Item".to_string(),
 			implementation: true,
 			raw_source: false,
 			private: true,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
 		}),
 	];
 
 	// Injections should NOT be matchable (they don't have stable keys)
-	let result = find_entry_match(&entries, "## Header");
+	let result = find_entry_match(&entries, "## Header", &Ripdoc::new());
 	assert!(
 		result.is_err(),
 		"Injections should not be matchable by content"
 	);
 
 	// But targets and raw sources should be
-	let idx = find_entry_match(&entries, "first::Item")?;
+	let idx = find_entry_match(&entries, "first::Item", &Ripdoc::new())?;
 	assert_eq!(idx, 1);
 
-	let idx = find_entry_match(&entries, "src/raw.rs")?;
+	let idx = find_entry_match(&entries, "src/raw.rs", &Ripdoc::new())?;
 	assert_eq!(idx, 2);
 
-	let idx = find_entry_match(&entries, "second::Item")?;
+	let idx = find_entry_match(&entries, "second::Item", &Ripdoc::new())?;
 	assert_eq!(idx, 3);
 
 	Ok(())
@@ -408,12 +444,16 @@ fn skelebuild_injection_placement_with_mixed_entries() -> Result<(), Box<dyn std
 
 #[test]
 fn skelebuild_status_keys_format() {
-	let entries = vec![
+	let entries = [
 		SkeleEntry::Target(SkeleTarget {
 			path: "crate::module::Type".to_string(),
 			implementation: true,
 			raw_source: false,
 			private: true,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
 		}),
 		SkeleEntry::Injection(SkeleInjection {
 			content: "## Notes".to_string(),
@@ -423,6 +463,7 @@ fn skelebuild_status_keys_format() {
 			canonical_key: Some("src/lib.rs".to_string()),
 			start_line: None,
 			end_line: None,
+			note: None,
 		}),
 	];
 
@@ -465,6 +506,7 @@ fn skelebuild_raw_source_with_line_range() {
 		canonical_key: Some("src/lib.rs".to_string()),
 		start_line: Some(10),
 		end_line: Some(20),
+		note: None,
 	};
 
 	assert_eq!(raw.canonical_key.as_deref(), Some("src/lib.rs"));
@@ -480,6 +522,7 @@ fn skelebuild_raw_source_without_canonical_key() {
 		canonical_key: None,
 		start_line: None,
 		end_line: None,
+		note: None,
 	};
 
 	// Should fallback to file path
@@ -503,6 +546,10 @@ fn skelebuild_action_add_with_strict_flag() {
 		validate: true,
 		private: true,
 		strict: true,
+		features: Vec::new(),
+		all_features: false,
+		no_default_features: false,
+		with_deps: None,
 	};
 
 	match action {
@@ -522,6 +569,10 @@ fn skelebuild_action_add_many_with_strict_flag() {
 		validate: true,
 		private: true,
 		strict: false,
+		features: Vec::new(),
+		all_features: false,
+		no_default_features: false,
+		with_deps: None,
 	};
 
 	match action {
@@ -625,3 +676,323 @@ fn skelebuild_unescape_inject_content() {
 	// Test unknown escape sequence (kept as-is)
 	assert_eq!(unescape_inject_content("\\x unknown"), "\\x unknown");
 }
+
+// ============================================================================
+// Tests for entry notes
+// ============================================================================
+
+#[test]
+fn skelebuild_renders_notes_only_when_enabled() -> Result<(), Box<dyn std::error::Error>> {
+	let temp_dir = TempDir::new()?;
+	let file = temp_dir.path().join("lib.rs");
+	fs::write(&file, "pub fn answer() -> u32 {\n\t42\n}\n")?;
+
+	let mut state = SkeleState {
+		entries: vec![SkeleEntry::RawSource(SkeleRawSource {
+			file: file.clone(),
+			canonical_key: Some("lib.rs".to_string()),
+			start_line: Some(1),
+			end_line: Some(3),
+			note: Some("the only entry point --> keep".to_string()),
+		})],
+		..SkeleState::default()
+	};
+	assert_eq!(state.entries[0].note(), Some("the only entry point --> keep"));
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let hidden = state.build_output(&ripdoc)?;
+	assert!(!hidden.contains("<!-- note"), "{hidden}");
+
+	state.render_notes = true;
+	let shown = state.build_output(&ripdoc)?;
+	let note = shown.find("<!-- note: the only entry point -- > keep -->").expect("note rendered");
+	let source = shown.find(&format!("### Raw source: {}:1:3", file.display())).expect("raw source rendered");
+	assert!(note < source, "note precedes its entry:\n{shown}");
+	Ok(())
+}
+
+#[test]
+fn skelebuild_preview_segments_output_by_entry() -> Result<(), Box<dyn std::error::Error>> {
+	use ripdoc::skelebuild::PreviewSegmentKind;
+
+	let temp_dir = TempDir::new()?;
+	let file = temp_dir.path().join("lib.rs");
+	fs::write(&file, "pub fn answer() -> u32 {\n\t42\n}\n")?;
+
+	let state = SkeleState {
+		render_notes: true,
+		entries: vec![
+			SkeleEntry::Injection(SkeleInjection {
+				content: "## Intro".to_string(),
+			}),
+			SkeleEntry::RawSource(SkeleRawSource {
+				file: file.clone(),
+				canonical_key: Some("lib.rs".to_string()),
+				start_line: Some(1),
+				end_line: Some(3),
+				note: Some("entry point".to_string()),
+			}),
+		],
+		..SkeleState::default()
+	};
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let preview = state.build_budgeted_preview(&ripdoc)?;
+	assert_eq!(preview.output, state.build_output(&ripdoc)?);
+
+	let sections: Vec<_> = preview
+		.segments
+		.iter()
+		.map(|segment| (segment.kind, segment.entries.clone(), &preview.output[segment.start..segment.end]))
+		.collect();
+	assert_eq!(sections.len(), 3, "{sections:?}");
+	assert_eq!(sections[0].0, PreviewSegmentKind::Injection);
+	assert_eq!(sections[0].1, vec![0]);
+	assert!(sections[0].2.starts_with("## Intro"));
+	assert_eq!((sections[1].0, sections[1].1.clone()), (PreviewSegmentKind::Note, vec![1]));
+	assert!(sections[1].2.starts_with("<!-- note: entry point -->"));
+	assert_eq!((sections[2].0, sections[2].1.clone()), (PreviewSegmentKind::RawSource, vec![1]));
+	assert!(sections[2].2.contains(&format!("### Raw source: {}:1:3", file.display())));
+	assert!(preview.segments.iter().all(|segment| segment.estimated_tokens > 0));
+	Ok(())
+}
+
+// ============================================================================
+// Tests for the size budget
+// ============================================================================
+
+#[test]
+fn skelebuild_budget_reports_entry_sizes_when_exceeded() {
+	use ripdoc::skelebuild::{BudgetAction, BudgetUnit, SkeleBudget};
+
+	let mut state = SkeleState {
+		entries: vec![
+			SkeleEntry::Injection(SkeleInjection {
+				content: "short".to_string(),
+			}),
+			SkeleEntry::Injection(SkeleInjection {
+				content: "x".repeat(400),
+			}),
+		],
+		budget: Some(SkeleBudget {
+			limit: 50,
+			unit: BudgetUnit::Tokens,
+			on_exceed: BudgetAction::TruncateDocs,
+		}),
+		..SkeleState::default()
+	};
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let err = state.build_budgeted_output(&ripdoc).unwrap_err().to_string();
+	assert!(err.contains("over the budget of 50 tokens even with truncate-docs"));
+	let big = err.find("#1 [inject] xxx").expect("large entry listed");
+	let small = err.find("#0 [inject] short").expect("small entry listed");
+	assert!(big < small, "entries are sorted largest first:\n{err}");
+
+	state.budget = Some(SkeleBudget {
+		limit: 500,
+		unit: BudgetUnit::Chars,
+		on_exceed: BudgetAction::Error,
+	});
+	assert!(state.build_budgeted_output(&ripdoc).is_ok());
+}
+
+#[test]
+fn skelebuild_budget_drops_implementations_to_fit() -> Result<(), Box<dyn std::error::Error>> {
+	use ripdoc::skelebuild::{BudgetAction, BudgetUnit, SkeleBudget};
+
+	let fixture = write_bin_crate_fixture();
+	let crate_dir = fixture.path().to_path_buf();
+
+	let mut state = SkeleState {
+		entries: vec![SkeleEntry::Target(SkeleTarget {
+			path: format!(
+				"{}::tome_term::terminal_panel::TerminalState",
+				crate_dir.display()
+			),
+			implementation: true,
+			raw_source: false,
+			private: false,
+			features: Vec::new(),
+			all_features: false,
+			no_default_features: false,
+			note: None,
+		})],
+		..SkeleState::default()
+	};
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let full = state.build_output(&ripdoc)?;
+	assert!(full.contains("tick_body_marker"));
+
+	state.budget = Some(SkeleBudget {
+		limit: full.chars().count() - 1,
+		unit: BudgetUnit::Chars,
+		on_exceed: BudgetAction::DropImplementations,
+	});
+	let reduced = state.build_budgeted_output(&ripdoc)?;
+	assert!(reduced.contains("pub struct TerminalState"));
+	assert!(!reduced.contains("tick_body_marker"));
+
+	Ok(())
+}
+
+// ============================================================================
+// Tests for per-entry features
+// ============================================================================
+
+#[test]
+fn skelebuild_target_features_enable_gated_items() -> Result<(), Box<dyn std::error::Error>> {
+	let fixture = TempDir::new()?;
+	fs::create_dir_all(fixture.path().join("src"))?;
+	fs::write(
+		fixture.path().join("Cargo.toml"),
+		r#"
+[package]
+name = "gated"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+extra = []
+"#,
+	)?;
+	fs::write(
+		fixture.path().join("src/lib.rs"),
+		r#"
+pub struct Always;
+
+#[cfg(feature = "extra")]
+pub struct Extra;
+"#,
+	)?;
+
+	let target = |item: &str, features: Vec<String>| {
+		SkeleEntry::Target(SkeleTarget {
+			path: format!("{}::gated::{item}", fixture.path().display()),
+			implementation: false,
+			raw_source: false,
+			private: false,
+			features,
+			all_features: false,
+			no_default_features: false,
+			note: None,
+		})
+	};
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let mut state = SkeleState {
+		entries: vec![target("Always", Vec::new()), target("Extra", Vec::new())],
+		..SkeleState::default()
+	};
+	let output = state.build_output(&ripdoc)?;
+	assert!(output.contains("pub struct Always"));
+	assert!(!output.contains("pub struct Extra"));
+
+	state.entries = vec![
+		target("Always", Vec::new()),
+		target("Extra", vec!["extra".to_string()]),
+	];
+	let output = state.build_output(&ripdoc)?;
+	assert!(output.contains("pub struct Always"));
+	assert!(output.contains("pub struct Extra"));
+
+	Ok(())
+}
+
+#[test]
+fn skelebuild_raw_source_fences_use_file_language() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = TempDir::new()?;
+	let toml = dir.path().join("Cargo.toml");
+	let sql = dir.path().join("schema.sql");
+	let rust = dir.path().join("lib.rs");
+	fs::write(&toml, "[package]\nname = \"demo\"\n")?;
+	fs::write(&sql, "CREATE TABLE users (id INTEGER);\n")?;
+	fs::write(&rust, "pub fn demo() {}\n")?;
+
+	let raw = |file: &PathBuf| {
+		SkeleEntry::RawSource(SkeleRawSource {
+			file: file.clone(),
+			canonical_key: None,
+			start_line: None,
+			end_line: None,
+			note: None,
+		})
+	};
+	let state = SkeleState {
+		entries: vec![raw(&toml), raw(&sql), raw(&rust)],
+		..SkeleState::default()
+	};
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let output = state.build_output(&ripdoc)?;
+	assert!(output.contains("```toml\n[package]"));
+	assert!(output.contains("```sql\nCREATE TABLE"));
+	assert!(output.contains("```rust\npub fn demo"));
+
+	Ok(())
+}
+
+// ============================================================================
+// Tests for type dependency closure
+// ============================================================================
+
+#[test]
+fn skelebuild_with_deps_follows_referenced_local_types() -> Result<(), Box<dyn std::error::Error>> {
+	use ripdoc::skelebuild::resolver::validate_target_with_deps;
+
+	let fixture = TempDir::new()?;
+	fs::create_dir_all(fixture.path().join("src"))?;
+	fs::write(
+		fixture.path().join("Cargo.toml"),
+		"[package]\nname = \"deps\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+	)?;
+	fs::write(
+		fixture.path().join("src/lib.rs"),
+		r#"
+pub struct Config {
+	pub level: LogLevel,
+	pub name: String,
+}
+
+pub enum LogLevel {
+	Custom(Color),
+	Off,
+}
+
+pub struct Color(pub u8);
+
+pub struct Unrelated;
+"#,
+	)?;
+
+	let target = SkeleTarget {
+		path: format!("{}::deps::Config", fixture.path().display()),
+		implementation: false,
+		raw_source: false,
+		private: false,
+		features: Vec::new(),
+		all_features: false,
+		no_default_features: false,
+		note: None,
+	};
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let prefix = fixture.path().display().to_string();
+
+	let direct = validate_target_with_deps(&target, &ripdoc, false, 1)?;
+	assert_eq!(direct.dependencies, vec![format!("{prefix}::deps::LogLevel")]);
+
+	let closure = validate_target_with_deps(&target, &ripdoc, false, 2)?;
+	assert_eq!(
+		closure.dependencies,
+		vec![
+			format!("{prefix}::deps::LogLevel"),
+			format!("{prefix}::deps::Color"),
+		]
+	);
+
+	let none = validate_target_with_deps(&target, &ripdoc, false, 0)?;
+	assert!(none.dependencies.is_empty());
+
+	Ok(())
+}
@@ -0,0 +1,55 @@
+//! Integration tests for splitting rendered output per top-level module.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use std::path::{Path, PathBuf};
+
+use ripdoc::Ripdoc;
+use ripdoc::core_api::render_split_index;
+use utils::create_test_crate;
+
+#[test]
+fn split_writes_root_items_and_one_file_per_module() {
+	let source = r#"
+        pub mod shapes {
+            pub struct Circle;
+            pub mod nested {
+                pub fn deep() {}
+            }
+        }
+
+        pub mod colors {
+            pub enum Color { Red }
+        }
+
+        pub fn top_level() {}
+    "#;
+
+	let (_temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let files = ripdoc
+		.render_split(&target, false, false, Vec::new(), false)
+		.unwrap();
+
+	let paths: Vec<&PathBuf> = files.iter().map(|file| &file.path).collect();
+	assert!(paths.contains(&&PathBuf::from("lib.md")));
+	assert!(paths.contains(&&PathBuf::from("shapes.md")));
+	assert!(paths.contains(&&PathBuf::from("colors.md")));
+	assert!(!paths.contains(&&PathBuf::from("nested.md")));
+
+	let file = |name: &str| {
+		files
+			.iter()
+			.find(|file| file.path == Path::new(name))
+			.unwrap()
+	};
+	assert!(file("lib.md").contents.contains("top_level"));
+	assert!(!file("lib.md").contents.contains("Circle"));
+	assert!(file("shapes.md").contents.contains("Circle"));
+	assert!(file("shapes.md").contents.contains("deep"));
+	assert!(!file("shapes.md").contents.contains("Color"));
+
+	let index = render_split_index(&files);
+	assert!(index.contains("[`dummy_crate::shapes`](shapes.md)"));
+}
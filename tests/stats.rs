@@ -0,0 +1,115 @@
+//! Integration tests for API-surface stats.
+
+mod utils;
+
+use std::fs;
+
+use ripdoc::Ripdoc;
+use ripdoc::core_api::SearchItemKind;
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+#[test]
+fn stats_attribute_gated_items_to_features() -> Result<(), Box<dyn std::error::Error>> {
+	let fixture = TempDir::new()?;
+	fs::create_dir_all(fixture.path().join("src"))?;
+	fs::write(
+		fixture.path().join("Cargo.toml"),
+		r#"
+[package]
+name = "gated"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+tls = []
+json = []
+unused = []
+"#,
+	)?;
+	fs::write(
+		fixture.path().join("src/lib.rs"),
+		r#"
+pub struct Always;
+
+#[cfg(feature = "tls")]
+pub mod tls {
+    pub struct Connector;
+    pub fn connect() {}
+}
+
+#[cfg(all(feature = "tls", feature = "json"))]
+pub fn tls_json() {}
+"#,
+	)?;
+
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let target = fixture.path().display().to_string();
+
+	let plain = ripdoc.stats(&target, false, false, Vec::new(), false)?;
+	assert_eq!(plain.len(), 1);
+	assert!(plain[0].by_feature.is_none());
+	assert!(
+		plain[0]
+			.kinds
+			.iter()
+			.any(|kind| kind.kind == SearchItemKind::Struct && kind.items == 1)
+	);
+
+	let stats = ripdoc.stats(&target, false, false, Vec::new(), true)?;
+	let breakdown = stats[0].by_feature.as_ref().expect("feature breakdown");
+	let feature = |name: &str| {
+		breakdown
+			.features
+			.iter()
+			.find(|feature| feature.feature == name)
+			.map(|feature| (feature.items, feature.exclusive))
+	};
+	// `tls` module, `Connector`, `connect`, and `tls_json`; only the last also needs `json`.
+	assert_eq!(feature("tls"), Some((4, 3)));
+	assert_eq!(feature("json"), Some((1, 0)));
+	assert_eq!(feature("unused"), Some((0, 0)));
+	assert_eq!(breakdown.features[0].feature, "tls");
+
+	Ok(())
+}
+
+#[test]
+fn stats_measure_visibility_generics_docs_and_modules() -> Result<(), Box<dyn std::error::Error>> {
+	let (_crate_dir, target) = create_test_crate(
+		r#"
+/// A documented wrapper.
+pub struct Wrapper<T>(pub T);
+
+pub fn borrow<'a>(value: &'a str) -> &'a str { value }
+
+pub fn boxed(value: impl Into<String>) -> String { value.into() }
+
+fn helper() {}
+
+pub mod net {
+    pub struct Addr;
+    pub struct Socket;
+    pub fn connect() {}
+    struct Pool;
+}
+"#,
+		false,
+	);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+	let stats = ripdoc.stats(&target, false, false, Vec::new(), false)?;
+	let stats = &stats[0];
+
+	// `helper` and `net::Pool`.
+	assert_eq!(stats.private_items, 2);
+	// `Wrapper<T>` and `boxed`; a lifetime parameter alone does not count.
+	assert_eq!(stats.generic_items, 2);
+	assert_eq!(stats.documented_items, 1);
+	assert!(stats.doc_coverage() < 0.5);
+	assert!(stats.public_ratio() > 0.5 && stats.public_ratio() < 1.0);
+	let net = stats.largest_modules.iter().find(|module| module.path == "dummy_crate::net");
+	assert_eq!(net.map(|module| module.items), Some(3));
+	assert!(stats.estimated_tokens > 0);
+
+	Ok(())
+}
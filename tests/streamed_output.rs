@@ -0,0 +1,39 @@
+//! Integration tests comparing streamed output with the buffered render.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::{FeatureSelection, RenderFormat, Ripdoc};
+use utils::create_test_crate;
+
+fn render_both(source: &str) -> (String, String) {
+	let (temp_dir, target) = create_test_crate(source, false);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_render_format(RenderFormat::Rust)
+		.with_cache_dir(temp_dir.path().join("cache"));
+	let rendered = ripdoc
+		.render(&target, FeatureSelection::default(), false, false, false)
+		.unwrap()
+		.rendered;
+	let mut streamed = Vec::new();
+	let failures = ripdoc
+		.render_to_writer(&target, FeatureSelection::default(), false, false, false, &mut streamed)
+		.unwrap();
+	assert!(failures.is_empty());
+	(rendered, String::from_utf8(streamed).unwrap())
+}
+
+#[test]
+fn streamed_rust_output_matches_the_buffered_render() {
+	let (rendered, streamed) = render_both("//! Crate docs.\n\n/// A function.\npub fn f() {}\n\npub fn g() -> u32 {\n    1\n}\n");
+	assert_eq!(streamed, rendered);
+}
+
+#[test]
+fn streamed_rust_output_opens_the_crate_module_on_its_own_line() {
+	let (rendered, streamed) = render_both("pub mod m {}\n");
+	assert!(streamed.starts_with("pub mod dummy_crate {\n"), "{streamed}");
+	assert_eq!(streamed, rendered);
+}
@@ -0,0 +1,48 @@
+//! Integration tests for the crate summary card.
+
+mod utils;
+
+use ripdoc::core_api::summarize;
+use utils::inspect_crate;
+
+#[test]
+fn summary_counts_modules_and_ranks_key_items_by_fan_in() {
+	let source = r#"
+        pub mod shapes {
+            pub struct Point;
+            pub struct Circle {
+                pub center: Point,
+            }
+            pub fn origin() -> Point {
+                Point
+            }
+        }
+
+        pub mod draw {
+            use crate::shapes::Point;
+
+            pub trait Canvas {
+                fn plot(&mut self, at: Point);
+            }
+        }
+
+        pub struct Unused;
+    "#;
+
+	let crate_data = inspect_crate(source, false, false);
+	let summary = summarize(&crate_data, None, 8);
+
+	let shapes = summary
+		.modules
+		.iter()
+		.find(|module| module.path.ends_with("::shapes"))
+		.expect("shapes module");
+	assert!(shapes.items >= 4);
+	assert!(summary.modules.iter().any(|module| module.path.ends_with("::draw")));
+
+	let top = summary.key_items.first().expect("key items");
+	assert!(top.path.ends_with("::Point"), "unexpected top item: {}", top.path);
+	assert!(top.fan_in >= 3);
+	assert!(!summary.key_items.iter().any(|item| item.path.ends_with("::Unused")));
+	assert!(summary.msrv.is_none());
+}
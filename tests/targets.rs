@@ -2,7 +2,7 @@
 
 use std::fs;
 
-use ripdoc::Ripdoc;
+use ripdoc::{FeatureSelection, Ripdoc};
 use tempfile::tempdir;
 
 #[cfg(test)]
@@ -34,10 +34,209 @@ mod tests {
 
 		let target = format!("{}::DummyStruct", foo_path.display());
 		let ripdoc = Ripdoc::new().with_silent(true);
-		let output = ripdoc.render(&target, false, false, Vec::new(), false, false, false)?;
+		let output = ripdoc
+			.render(&target, FeatureSelection::default(), false, false, false)?
+			.rendered;
 
 		assert!(output.contains("pub struct DummyStruct;"));
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_render_workspace_skips_failing_member() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::write(
+			root.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"good\", \"broken\"]\nresolver = \"2\"\n",
+		)?;
+		for (name, source) in [("good", "pub struct Fine;"), ("broken", "pub fn broken( {")] {
+			fs::create_dir_all(root.join(name).join("src"))?;
+			fs::write(
+				root.join(name).join("Cargo.toml"),
+				format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+			)?;
+			fs::write(root.join(name).join("src/lib.rs"), source)?;
+		}
+
+		let ripdoc = Ripdoc::new().with_silent(true).with_offline(true).with_workspace(true);
+		let output = ripdoc.render(&root.join("good").display().to_string(), FeatureSelection::default(), false, false, false)?;
+
+		assert!(output.rendered.contains("pub struct Fine;"));
+		assert_eq!(output.errors.len(), 1);
+		assert_eq!(output.errors[0].package, "broken");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_render_with_path_dependencies() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		let packages = [
+			("app", "pub struct App;", "helper = { path = \"../helper\" }\nleaf = { path = \"../leaf\" }\n"),
+			("helper", "pub struct Helper;", "leaf = { path = \"../leaf\" }\n"),
+			("leaf", "pub struct Leaf;", ""),
+		];
+		for (name, source, dependencies) in packages {
+			fs::create_dir_all(root.join(name).join("src"))?;
+			fs::write(
+				root.join(name).join("Cargo.toml"),
+				format!(
+					"[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{dependencies}"
+				),
+			)?;
+			fs::write(root.join(name).join("src/lib.rs"), source)?;
+		}
+		let app = root.join("app").display().to_string();
+
+		let direct = Ripdoc::new().with_silent(true).with_offline(true).with_dependencies(1);
+		let output = direct.render(&app, FeatureSelection::default(), false, false, false)?.rendered;
+		assert!(output.contains("pub struct App;"));
+		assert!(output.contains("pub struct Helper;"));
+		// `leaf` is shared by `app` and `helper` but rendered once.
+		assert_eq!(output.matches("pub struct Leaf;").count(), 1);
+
+		let none = Ripdoc::new().with_silent(true).with_offline(true);
+		let output = none.render(&app, FeatureSelection::default(), false, false, false)?.rendered;
+		assert!(!output.contains("pub struct Helper;"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_render_to_writer_matches_render() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let src_dir = temp_dir.path().join("src");
+		fs::create_dir_all(&src_dir)?;
+		fs::write(
+			src_dir.join("lib.rs"),
+			"//! Crate docs.\n\n/// A thing.\npub struct Thing;\n\npub mod nested {\n    pub fn helper() {}\n}\n\npub fn run() {}\n",
+		)?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"streamed\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)?;
+		let target = temp_dir.path().display().to_string();
+
+		for format in [ripdoc::RenderFormat::Markdown, ripdoc::RenderFormat::Rust] {
+			let ripdoc = Ripdoc::new().with_silent(true).with_offline(true).with_render_format(format);
+			let rendered = ripdoc.render(&target, FeatureSelection::default(), false, false, false)?.rendered;
+			let mut streamed = Vec::new();
+			let errors = ripdoc.render_to_writer(
+				&target,
+				FeatureSelection::default(),
+				false,
+				false,
+				false,
+				&mut streamed,
+			)?;
+			assert!(errors.is_empty());
+
+			// Streaming only differs in the blank lines and Markdown fences between top-level items.
+			let non_blank = |text: &str| -> Vec<String> {
+				text.lines()
+					.filter(|line| !line.trim().is_empty() && !line.starts_with("```"))
+					.map(str::to_string)
+					.collect()
+			};
+			assert_eq!(non_blank(&String::from_utf8(streamed)?), non_blank(&rendered));
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_streamed_print_reuses_rendered_cache() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let src_dir = temp_dir.path().join("src");
+		fs::create_dir_all(&src_dir)?;
+		fs::write(src_dir.join("lib.rs"), "/// A thing.\npub struct Thing;\n\npub fn run() {}\n")?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"reprinted\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)?;
+		let target = temp_dir.path().display().to_string();
+		let cache_dir = tempdir()?;
+		let ripdoc = Ripdoc::new().with_silent(true).with_offline(true).with_cache_dir(cache_dir.path().to_path_buf());
+		let print = |ripdoc: &Ripdoc| -> Result<String, Box<dyn std::error::Error>> {
+			let mut out = Vec::new();
+			let errors = ripdoc.render_to_writer(&target, FeatureSelection::default(), false, false, false, &mut out)?;
+			assert!(errors.is_empty());
+			Ok(String::from_utf8(out)?)
+		};
+		let first = print(&ripdoc)?;
+		assert!(first.contains("pub struct Thing"));
+
+		// With the cached build unreadable and no time to run rustdoc, only the rendered cache can
+		// serve the second print.
+		for entry in ripdoc::cargo_utils::cache_entries(ripdoc.cache_config())? {
+			if entry.kind == ripdoc::cargo_utils::CacheEntryKind::Rustdoc {
+				fs::write(&entry.path, b"not a crate")?;
+			}
+		}
+		let second = print(&ripdoc.clone().with_max_build_time(Some(std::time::Duration::ZERO)))?;
+		assert_eq!(second, first);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_render_prebuilt_rustdoc_json() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let src_dir = temp_dir.path().join("src");
+		fs::create_dir_all(&src_dir)?;
+		fs::write(src_dir.join("lib.rs"), "/// A thing.\npub struct Thing;\n\npub mod nested {\n    pub fn helper() {}\n}\n")?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"prebuilt\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)?;
+		let target = temp_dir.path().display().to_string();
+		let ripdoc = Ripdoc::new().with_silent(true).with_offline(true).with_source_labels(false);
+		let built = ripdoc.render(&target, FeatureSelection::default(), false, false, false)?.rendered;
+
+		let crate_data = ripdoc.inspect(&target, false, false, Vec::new(), false)?.remove(0);
+		let json_dir = tempdir()?;
+		let json_path = json_dir.path().join("prebuilt.json");
+		fs::write(&json_path, serde_json::to_string(&crate_data)?)?;
+
+		let json_target = json_path.display().to_string();
+		let read = ripdoc.render(&json_target, FeatureSelection::default(), false, false, false)?.rendered;
+		assert_eq!(read, built);
+
+		let filtered = ripdoc
+			.render(&format!("{json_target}::nested"), FeatureSelection::default(), false, false, false)?
+			.rendered;
+		assert!(filtered.contains("pub fn helper()"));
+		assert!(!filtered.contains("pub struct Thing"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_locations_report_package_paths() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let src_dir = temp_dir.path().join("src");
+		fs::create_dir_all(&src_dir)?;
+		fs::write(src_dir.join("lib.rs"), "pub mod nested {\n    pub fn helper() {}\n}\n")?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"located\"\nversion = \"0.3.1\"\nedition = \"2021\"\n",
+		)?;
+		let target = format!("{}::nested", temp_dir.path().display());
+		let ripdoc = Ripdoc::new().with_silent(true).with_offline(true);
+
+		let locations = ripdoc.locations(&target)?;
+		assert_eq!(locations.len(), 1);
+		let location = &locations[0];
+		assert_eq!(location.package.as_deref(), Some("located"));
+		assert_eq!(location.version.as_deref(), Some("0.3.1"));
+		assert_eq!(location.module, "nested");
+		assert!(location.manifest.as_ref().is_some_and(|manifest| manifest.ends_with("Cargo.toml")));
+		assert!(location.entry.as_ref().is_some_and(|entry| entry.ends_with("src/lib.rs")));
+		assert!(location.registry_archive.is_none());
+
+		Ok(())
+	}
 }
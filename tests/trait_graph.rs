@@ -0,0 +1,51 @@
+//! Integration tests for trait hierarchy graphs.
+
+mod utils;
+
+use ripdoc::core_api::trait_graph;
+use utils::inspect_crate;
+
+#[test]
+fn trait_graph_links_supertraits_and_implementors() {
+	let crate_data = inspect_crate(
+		r#"
+pub trait Service {
+    fn call(&self);
+}
+
+pub trait Layer: Service + Clone {}
+
+#[derive(Clone)]
+pub struct Stack;
+
+impl Service for Stack {
+    fn call(&self) {}
+}
+
+impl Layer for Stack {}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T: Clone> Service for Wrapper<T> {
+    fn call(&self) {}
+}
+"#,
+		false,
+		false,
+	);
+	let graph = trait_graph(&crate_data, false);
+
+	let layer = graph.traits.iter().find(|node| node.path == "dummy_crate::Layer").unwrap();
+	assert!(layer.local);
+	assert_eq!(layer.supertraits[0], "dummy_crate::Service");
+	assert!(layer.supertraits[1].ends_with("::Clone"));
+	assert_eq!(layer.implementors, ["dummy_crate::Stack"]);
+
+	let service = graph.traits.iter().find(|node| node.path == "dummy_crate::Service").unwrap();
+	assert_eq!(service.implementors, ["dummy_crate::Stack", "dummy_crate::Wrapper"]);
+
+	// External supertraits become leaf nodes so every edge has both ends.
+	let clone = graph.traits.iter().find(|node| node.path.ends_with("::Clone")).unwrap();
+	assert!(!clone.local);
+	assert!(clone.implementors.is_empty());
+}
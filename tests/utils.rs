@@ -115,7 +115,7 @@ pub fn render(renderer: &Renderer, source: &str, expected_output: &str, is_proc_
 
 	let normalized_expected = normalize_whitespace(expected_output);
 
-	// Only use rustfmt for Rust format, not Markdown
+	// Only use rustfmt for Rust format
 	match renderer.format {
 		ripdoc::RenderFormat::Rust => {
 			let config = Config::new_str().option("hard_tabs", "true");
@@ -125,7 +125,7 @@ pub fn render(renderer: &Renderer, source: &str, expected_output: &str, is_proc_
 				formatter.format_str(normalized_expected).unwrap(),
 			);
 		}
-		ripdoc::RenderFormat::Markdown => {
+		ripdoc::RenderFormat::Markdown | ripdoc::RenderFormat::Json | ripdoc::RenderFormat::Html => {
 			assert_eq!(normalized_rendered, normalized_expected);
 		}
 	}
@@ -0,0 +1,115 @@
+//! Integration tests for walking a crate's item tree with visitors.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::Ripdoc;
+use ripdoc::core_api::{ItemVisitor, VisitContext, VisitFlow, VisitOptions, walk_crate};
+use rustdoc_types::Item;
+use utils::{create_test_crate, inspect_crate};
+
+const SOURCE: &str = r#"
+pub mod net {
+    pub struct Client {
+        pub timeout: u64,
+        retries: u32,
+    }
+
+    impl Client {
+        pub fn connect() {}
+    }
+}
+
+pub enum Mode {
+    Fast,
+    Slow(u8),
+}
+
+fn helper() {}
+"#;
+
+/// Collects the path of every visited named item.
+#[derive(Default)]
+struct Paths(Vec<String>);
+
+impl<'a> ItemVisitor<'a> for Paths {
+	fn visit_item(&mut self, item: &'a Item, context: &VisitContext<'_>) -> VisitFlow {
+		if let Some(name) = &item.name {
+			self.0.push(context.path_of(name));
+		}
+		VisitFlow::Continue
+	}
+}
+
+#[test]
+fn walks_items_in_module_order() {
+	let crate_data = inspect_crate(SOURCE, true, false);
+	let mut paths = Paths::default();
+	assert!(walk_crate(&crate_data, &VisitOptions::default(), &mut paths));
+	assert_eq!(
+		paths.0,
+		vec![
+			"dummy_crate",
+			"dummy_crate::net",
+			"dummy_crate::net::Client",
+			"dummy_crate::net::Client::timeout",
+			"dummy_crate::net::Client::{impl}::connect",
+			"dummy_crate::Mode",
+			"dummy_crate::Mode::Fast",
+			"dummy_crate::Mode::Slow",
+			"dummy_crate::Mode::Slow::0",
+		]
+	);
+
+	let mut private = Paths::default();
+	let options = VisitOptions {
+		include_private: true,
+		..VisitOptions::default()
+	};
+	walk_crate(&crate_data, &options, &mut private);
+	assert!(private.0.contains(&"dummy_crate::net::Client::retries".to_string()));
+	assert!(private.0.contains(&"dummy_crate::helper".to_string()));
+}
+
+#[test]
+fn closures_can_skip_children_and_stop() {
+	let crate_data = inspect_crate(SOURCE, false, false);
+	let mut names = Vec::new();
+	let completed = walk_crate(
+		&crate_data,
+		&VisitOptions::default(),
+		&mut |item: &Item, _: &VisitContext<'_>| {
+			names.push(item.name.clone().unwrap_or_default());
+			match item.name.as_deref() {
+				Some("net") => VisitFlow::SkipChildren,
+				Some("Mode") => VisitFlow::Stop,
+				_ => VisitFlow::Continue,
+			}
+		},
+	);
+	assert!(!completed);
+	assert_eq!(names, vec!["dummy_crate", "net", "Mode"]);
+}
+
+#[test]
+fn ripdoc_visits_resolved_targets() {
+	let (_temp_dir, target) = create_test_crate(SOURCE, false);
+	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+	let mut count = 0;
+	ripdoc
+		.visit(
+			&target,
+			false,
+			false,
+			Vec::new(),
+			&VisitOptions::default(),
+			&mut |_: &Item, _: &VisitContext<'_>| {
+				count += 1;
+				VisitFlow::Continue
+			},
+		)
+		.unwrap();
+	// Every named item above plus the unnamed `impl Client` block.
+	assert_eq!(count, 10);
+}
@@ -0,0 +1,70 @@
+//! Integration tests for search indexes kept warm by long-lived processes.
+//!
+//! Warm indexes live in a process-wide map, so this file holds a single test that owns it.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use ripdoc::core_api::search::SearchIndex;
+use ripdoc::{Ripdoc, SearchOptions};
+use tempfile::TempDir;
+use utils::create_test_crate;
+
+#[test]
+fn memory_cache_keeps_one_index_per_crate_and_options() {
+	SearchIndex::clear_warm();
+	let cache = TempDir::new().unwrap();
+	let (crate_dir, target) = create_test_crate(
+		r#"
+pub struct ConfigLoader;
+
+pub fn load_config() {}
+
+fn private_config() {}
+"#,
+		false,
+	);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_silent(true)
+		.with_cache_dir(cache.path().to_path_buf())
+		.with_memory_cache(true);
+	let options = SearchOptions::new("config");
+	let list = |ripdoc: &Ripdoc, private: bool| {
+		let items = ripdoc.list(&target, false, false, Vec::new(), private, Some(&options)).unwrap();
+		items.into_iter().map(|item| item.path).collect::<Vec<_>>()
+	};
+
+	let first = list(&ripdoc, false);
+	assert!(first.iter().any(|path| path == "dummy_crate::load_config"), "{first:?}");
+	assert_eq!(SearchIndex::warm_len(), 1);
+
+	// The same crate and options reuse the warm index.
+	assert_eq!(list(&ripdoc, false), first);
+	assert_eq!(SearchIndex::warm_len(), 1);
+
+	// Private items index differently, so they get an index of their own.
+	let private = list(&ripdoc, true);
+	assert!(private.iter().any(|path| path == "dummy_crate::private_config"), "{private:?}");
+	assert_eq!(SearchIndex::warm_len(), 2);
+
+	// An edit supersedes the build, whose crate and index are dropped with it.
+	let lib = crate_dir.path().join("src/lib.rs");
+	let source = std::fs::read_to_string(&lib).unwrap();
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	std::fs::write(&lib, format!("{source}\npub fn reload_config() {{}}\n")).unwrap();
+	let edited = list(&ripdoc, false);
+	assert!(edited.iter().any(|path| path == "dummy_crate::reload_config"), "{edited:?}");
+	assert_eq!(SearchIndex::warm_len(), 2);
+	assert_eq!(ripdoc::cargo_utils::memory_cache_len(), 2);
+
+	// A refresh rebuilds the crate, so its warm index is dropped rather than reused.
+	assert_eq!(list(&ripdoc.clone().with_force_refresh(true), false), edited);
+	assert_eq!(SearchIndex::warm_len(), 1);
+
+	// Without the memory cache nothing is kept.
+	SearchIndex::clear_warm();
+	let cold = Ripdoc::new().with_offline(true).with_silent(true).with_cache_dir(cache.path().to_path_buf());
+	assert_eq!(list(&cold, false), edited);
+	assert_eq!(SearchIndex::warm_len(), 0);
+}